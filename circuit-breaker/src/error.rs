@@ -0,0 +1,14 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CircuitBreakerError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("The {message} message is disabled by the circuit breaker of the chain")]
+    MessageDisabled { message: String },
+
+    #[error("{message} isn't an execute message of the contract")]
+    InvalidMessageName { message: String },
+}
@@ -0,0 +1,124 @@
+use cosmwasm_schema::schema_for;
+use cosmwasm_schema::schemars::schema::Schema;
+use cosmwasm_schema::schemars::JsonSchema;
+use cosmwasm_std::{ensure, Response, StdError, StdResult, Storage};
+use cw_storage_plus::Item;
+use serde::Serialize;
+use serde_json::Value;
+
+pub use crate::error::CircuitBreakerError;
+
+mod error;
+
+/// The execute messages disabled by the circuit breaker module of the chain, named as they're
+/// serialized. Removed once all of them are enabled again.
+pub const DISABLED_MESSAGES: Item<Vec<String>> = Item::new("disabled_messages");
+
+/// Disables the given messages, named as they're serialized, e.g. `provide_liquidity`. `M` is the
+/// execute message of the contract, which the names are validated against.
+pub fn disable_messages<M: JsonSchema>(
+    storage: &mut dyn Storage,
+    messages: Vec<String>,
+) -> Result<Response, CircuitBreakerError> {
+    validate_message_names::<M>(&messages)?;
+
+    let mut disabled_messages = get_disabled_messages(storage)?;
+    for message in &messages {
+        if !disabled_messages.contains(message) {
+            disabled_messages.push(message.clone());
+        }
+    }
+    DISABLED_MESSAGES.save(storage, &disabled_messages)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "disable_messages".to_string()),
+        ("messages", messages.join(",")),
+    ]))
+}
+
+/// Enables the given messages again.
+pub fn enable_messages(
+    storage: &mut dyn Storage,
+    messages: Vec<String>,
+) -> Result<Response, CircuitBreakerError> {
+    let mut disabled_messages = get_disabled_messages(storage)?;
+    disabled_messages.retain(|message| !messages.contains(message));
+
+    if disabled_messages.is_empty() {
+        DISABLED_MESSAGES.remove(storage);
+    } else {
+        DISABLED_MESSAGES.save(storage, &disabled_messages)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "enable_messages".to_string()),
+        ("messages", messages.join(",")),
+    ]))
+}
+
+/// Ensures the given message wasn't disabled by the circuit breaker module of the chain.
+pub fn assert_message_enabled<M: Serialize>(
+    storage: &dyn Storage,
+    msg: &M,
+) -> Result<(), CircuitBreakerError> {
+    let Some(disabled_messages) = DISABLED_MESSAGES.may_load(storage)? else {
+        return Ok(());
+    };
+
+    let message = get_message_name(msg)?;
+    ensure!(
+        !disabled_messages.contains(&message),
+        CircuitBreakerError::MessageDisabled { message }
+    );
+
+    Ok(())
+}
+
+/// Gets the execute messages disabled by the circuit breaker module of the chain.
+pub fn get_disabled_messages(storage: &dyn Storage) -> StdResult<Vec<String>> {
+    Ok(DISABLED_MESSAGES.may_load(storage)?.unwrap_or_default())
+}
+
+/// Gets the name of the given message as it's serialized, i.e. the only key of the object a
+/// variant with fields serializes to, or the string a unit variant serializes to.
+fn get_message_name<M: Serialize>(msg: &M) -> StdResult<String> {
+    let name = match serde_json::to_value(msg)
+        .map_err(|err| StdError::serialize_err(std::any::type_name::<M>(), err))?
+    {
+        Value::Object(msg) => msg.into_iter().next().map(|(name, _)| name),
+        Value::String(name) => Some(name),
+        _ => None,
+    };
+
+    name.ok_or_else(|| {
+        StdError::serialize_err(std::any::type_name::<M>(), "message is not an enum variant")
+    })
+}
+
+/// Ensures the given names are execute messages of the contract as they're serialized, so a typo
+/// doesn't go through while leaving the intended message enabled.
+fn validate_message_names<M: JsonSchema>(messages: &[String]) -> Result<(), CircuitBreakerError> {
+    let message_names: Vec<String> = schema_for!(M)
+        .schema
+        .subschemas
+        .and_then(|subschemas| subschemas.one_of)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|variant| match variant {
+            Schema::Object(variant) => variant.object,
+            Schema::Bool(_) => None,
+        })
+        .flat_map(|variant| variant.required)
+        .collect();
+
+    for message in messages {
+        ensure!(
+            message_names.contains(message),
+            CircuitBreakerError::InvalidMessageName {
+                message: message.clone()
+            }
+        );
+    }
+
+    Ok(())
+}
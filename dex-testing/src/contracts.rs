@@ -0,0 +1,51 @@
+use cosmwasm_std::Empty;
+use cw_multi_test::{Contract, ContractWrapper};
+
+/// Creates the pool manager contract
+pub fn pool_manager_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        pool_manager::contract::execute,
+        pool_manager::contract::instantiate,
+        pool_manager::contract::query,
+    )
+    .with_reply(pool_manager::contract::reply)
+    .with_sudo(pool_manager::contract::sudo);
+
+    Box::new(contract)
+}
+
+/// Creates the farm manager contract
+pub fn farm_manager_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        farm_manager::contract::execute,
+        farm_manager::contract::instantiate,
+        farm_manager::contract::query,
+    )
+    .with_migrate(farm_manager::contract::migrate);
+
+    Box::new(contract)
+}
+
+/// Creates the epoch manager contract
+pub fn epoch_manager_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        epoch_manager::contract::execute,
+        epoch_manager::contract::instantiate,
+        epoch_manager::contract::query,
+    )
+    .with_migrate(epoch_manager::contract::migrate);
+
+    Box::new(contract)
+}
+
+/// Creates the fee collector contract
+pub fn fee_collector_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        fee_collector::contract::execute,
+        fee_collector::contract::instantiate,
+        fee_collector::contract::query,
+    )
+    .with_migrate(fee_collector::contract::migrate);
+
+    Box::new(contract)
+}
@@ -0,0 +1,497 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{
+    coin, Addr, Coin, Decimal, Empty, StdError, StdResult, Timestamp, Uint128, Uint64,
+};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, DistributionKeeper, Executor, FailingModule,
+    GovFailingModule, IbcFailingModule, MockApiBech32, StakeKeeper, WasmKeeper,
+};
+
+use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+use mantra_dex_std::constants::MONTH_IN_SECONDS;
+use mantra_dex_std::epoch_manager::{EpochConfig, EpochResponse};
+use mantra_dex_std::farm_manager::{
+    FarmAction, FarmParams, PositionsBy, PositionsResponse, RewardsResponse,
+};
+use mantra_dex_std::fee::PoolFee;
+use mantra_dex_std::pool_manager::{
+    PoolFeeCaps, PoolInfoResponse, PoolType, PoolsResponse, SimulationResponse,
+};
+
+use crate::contracts::{
+    epoch_manager_contract, farm_manager_contract, fee_collector_contract, pool_manager_contract,
+};
+
+pub type DexApp = App<
+    BankKeeper,
+    MockApiBech32,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    StargateMock,
+>;
+
+/// The denom the token factory charges its fee in.
+pub const TOKEN_FACTORY_FEE_DENOM: &str = "uom";
+/// The fee the token factory charges to create a denom.
+pub const TOKEN_FACTORY_FEE_AMOUNT: &str = "8888";
+/// The fee charged by the pool manager to create a pool.
+pub const POOL_CREATION_FEE: u128 = 1_000;
+/// The denom the pool creation fee is charged in.
+pub const POOL_CREATION_FEE_DENOM: &str = "uusd";
+/// 25 April 2024 15:00:00 UTC, the genesis of the epochs.
+pub const GENESIS_TIME: u64 = 1_714_057_200;
+/// The duration of an epoch, in seconds.
+pub const EPOCH_DURATION: u64 = 86_400;
+
+/// The contracts of the DEX deployed together in cw-multi-test.
+pub struct DexEnv {
+    pub app: DexApp,
+    pub senders: [Addr; 4],
+    pub pool_manager_addr: Addr,
+    pub farm_manager_addr: Addr,
+    pub epoch_manager_addr: Addr,
+    pub fee_collector_addr: Addr,
+}
+
+impl DexEnv {
+    /// Deploys the contracts, funding each of the senders with the given balance. The first sender
+    /// owns the contracts.
+    pub fn new(initial_balance: Vec<Coin>) -> Self {
+        let senders = [
+            Addr::unchecked("mantra15n2dapfyf7mzz70y0srycnduw5skp0s9u9g74e"),
+            Addr::unchecked("mantra13cxr0w5tvczvte29r5n0mauejmrg83m4zxj4l2"),
+            Addr::unchecked("mantra150qvkpleat9spklzs3mtwdxszjpeyjcssce49d"),
+            Addr::unchecked("mantra15dzl255vgd8t4y2jdjkeyrjqjygv446nr58ltm"),
+        ];
+
+        let balances = senders
+            .iter()
+            .map(|sender| (sender.clone(), initial_balance.clone()))
+            .collect::<Vec<_>>();
+
+        let mut app = AppBuilder::new()
+            .with_api(MockApiBech32::new("mantra"))
+            .with_wasm(WasmKeeper::default())
+            .with_bank(BankKeeper::new())
+            .with_stargate(StargateMock::new(
+                TOKEN_FACTORY_FEE_DENOM.to_string(),
+                TOKEN_FACTORY_FEE_AMOUNT.to_string(),
+            ))
+            .build(|router, _api, storage| {
+                balances.into_iter().for_each(|(account, amount)| {
+                    router.bank.init_balance(storage, &account, amount).unwrap()
+                });
+            });
+
+        let mut block_info = app.block_info();
+        block_info.time = Timestamp::from_seconds(GENESIS_TIME);
+        app.set_block(block_info);
+
+        let owner = senders[0].clone();
+
+        let epoch_manager_id = app.store_code(epoch_manager_contract());
+        let epoch_manager_addr = app
+            .instantiate_contract(
+                epoch_manager_id,
+                owner.clone(),
+                &mantra_dex_std::epoch_manager::InstantiateMsg {
+                    owner: owner.to_string(),
+                    epoch_config: EpochConfig {
+                        duration: Uint64::new(EPOCH_DURATION),
+                        genesis_epoch: Uint64::new(GENESIS_TIME),
+                    },
+                },
+                &[],
+                "Epoch Manager",
+                Some(owner.to_string()),
+            )
+            .unwrap();
+
+        let fee_collector_id = app.store_code(fee_collector_contract());
+        let fee_collector_addr = app
+            .instantiate_contract(
+                fee_collector_id,
+                owner.clone(),
+                &mantra_dex_std::fee_collector::InstantiateMsg {},
+                &[],
+                "Fee Collector",
+                Some(owner.to_string()),
+            )
+            .unwrap();
+
+        let farm_manager_id = app.store_code(farm_manager_contract());
+        let farm_manager_addr = app
+            .instantiate_contract(
+                farm_manager_id,
+                owner.clone(),
+                &mantra_dex_std::farm_manager::InstantiateMsg {
+                    owner: owner.to_string(),
+                    epoch_manager_addr: epoch_manager_addr.to_string(),
+                    fee_collector_addr: fee_collector_addr.to_string(),
+                    pool_manager_addr: "".to_string(),
+                    create_farm_fee: coin(0, "uom"),
+                    max_concurrent_farms: 5,
+                    max_farm_epoch_buffer: 14,
+                    min_unlocking_duration: EPOCH_DURATION,
+                    max_unlocking_duration: 31_536_000,
+                    farm_expiration_time: MONTH_IN_SECONDS,
+                    emergency_unlock_penalty: Decimal::percent(10),
+                },
+                &[],
+                "Farm Manager",
+                Some(owner.to_string()),
+            )
+            .unwrap();
+
+        let pool_manager_id = app.store_code(pool_manager_contract());
+        let pool_manager_addr = app
+            .instantiate_contract(
+                pool_manager_id,
+                owner.clone(),
+                &mantra_dex_std::pool_manager::InstantiateMsg {
+                    fee_collector_addr: fee_collector_addr.to_string(),
+                    farm_manager_addr: farm_manager_addr.to_string(),
+                    pool_creation_fees: vec![coin(POOL_CREATION_FEE, POOL_CREATION_FEE_DENOM)],
+                    pool_fee_caps: PoolFeeCaps {
+                        max_swap_fee: Decimal::percent(20),
+                        max_protocol_fee: Decimal::percent(20),
+                        max_burn_fee: Decimal::percent(20),
+                        max_extra_fees: Decimal::percent(20),
+                        max_total_fee: Decimal::percent(50),
+                    },
+                },
+                &[],
+                "Pool Manager",
+                Some(owner.to_string()),
+            )
+            .unwrap();
+
+        app.execute_contract(
+            owner,
+            farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                epoch_manager_addr: None,
+                pool_manager_addr: Some(pool_manager_addr.to_string()),
+                create_farm_fee: None,
+                max_concurrent_farms: None,
+                max_farm_epoch_buffer: None,
+                min_unlocking_duration: None,
+                max_unlocking_duration: None,
+                farm_expiration_time: None,
+                emergency_unlock_penalty: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        Self {
+            app,
+            senders,
+            pool_manager_addr,
+            farm_manager_addr,
+            epoch_manager_addr,
+            fee_collector_addr,
+        }
+    }
+
+    /// The owner of the contracts.
+    pub fn owner(&self) -> Addr {
+        self.senders[0].clone()
+    }
+
+    /// Moves the block time forward by the given amount of seconds.
+    pub fn advance_time(&mut self, seconds: u64) {
+        let mut block_info = self.app.block_info();
+        block_info.time = block_info.time.plus_seconds(seconds);
+        block_info.height += seconds / 6;
+        self.app.set_block(block_info);
+    }
+
+    /// Creates a pool with the given identifier on behalf of the owner, paying the fees.
+    pub fn create_pool(
+        &mut self,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            self.owner(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::CreatePool {
+                asset_denoms,
+                asset_decimals,
+                pool_fees,
+                pool_type,
+                pool_identifier: Some(pool_identifier.to_string()),
+                canonical_identifier: false,
+                metadata: None,
+            },
+            &[
+                coin(
+                    TOKEN_FACTORY_FEE_AMOUNT.parse().unwrap(),
+                    TOKEN_FACTORY_FEE_DENOM,
+                ),
+                coin(POOL_CREATION_FEE, POOL_CREATION_FEE_DENOM),
+            ],
+        )
+    }
+
+    /// Moves the block time forward by the given amount of epochs.
+    pub fn advance_epochs(&mut self, epochs: u64) {
+        self.advance_time(epochs * EPOCH_DURATION);
+    }
+
+    /// Provides the given assets as liquidity to a pool, locking the LP tokens in a farm manager
+    /// position for the given duration.
+    pub fn provide_locked_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: &str,
+        assets: Vec<Coin>,
+        unlocking_duration: u64,
+    ) -> AnyResult<AppResponse> {
+        let mut assets = assets;
+        assets.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::ProvideLiquidity {
+                pool_identifier: pool_identifier.to_string(),
+                slippage_tolerance: None,
+                max_spread: None,
+                receiver: None,
+                unlocking_duration: Some(unlocking_duration),
+                lock_position_identifier: None,
+            },
+            &assets,
+        )
+    }
+
+    /// Creates a farm distributing `farm_asset` to the LP denom of the given pool between the
+    /// given epochs, which default to the next epoch and the farm manager default duration.
+    pub fn fill_farm(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: &str,
+        farm_asset: Coin,
+        start_epoch: Option<u64>,
+        preliminary_end_epoch: Option<u64>,
+    ) -> AnyResult<AppResponse> {
+        let lp_denom = self.pool(pool_identifier)?.pool_info.lp_denom;
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::ManageFarm {
+                action: FarmAction::Fill {
+                    params: FarmParams {
+                        lp_denom,
+                        start_epoch,
+                        preliminary_end_epoch,
+                        curve: None,
+                        farm_asset: farm_asset.clone(),
+                        farm_identifier: None,
+                    },
+                },
+            },
+            &[farm_asset],
+        )
+    }
+
+    /// Claims the farm rewards of the sender.
+    pub fn claim(&mut self, sender: &Addr) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::Claim {},
+            &[],
+        )
+    }
+
+    /// Provides the given assets as liquidity to a pool, unlocked.
+    pub fn provide_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: &str,
+        assets: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        let mut assets = assets;
+        assets.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::ProvideLiquidity {
+                pool_identifier: pool_identifier.to_string(),
+                slippage_tolerance: None,
+                max_spread: None,
+                receiver: None,
+                unlocking_duration: None,
+                lock_position_identifier: None,
+            },
+            &assets,
+        )
+    }
+
+    /// Swaps the given asset for `ask_asset_denom` in a pool.
+    pub fn swap(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: &str,
+        offer_asset: Coin,
+        ask_asset_denom: &str,
+        max_spread: Option<Decimal>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::Swap {
+                ask_asset_denom: ask_asset_denom.to_string(),
+                belief_price: None,
+                max_spread,
+                receiver: None,
+                pool_identifier: pool_identifier.to_string(),
+                fee_payer: None,
+                referral: None,
+                min_receive: None,
+            },
+            &[offer_asset],
+        )
+    }
+
+    /// Withdraws liquidity from a pool by returning the given amount of its LP tokens.
+    pub fn withdraw_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: &str,
+        lp_amount: Uint128,
+    ) -> AnyResult<AppResponse> {
+        let lp_denom = self.pool(pool_identifier)?.pool_info.lp_denom;
+
+        self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::WithdrawLiquidity {
+                pool_identifier: pool_identifier.to_string(),
+                remote_receiver: None,
+            },
+            &[coin(lp_amount.u128(), lp_denom)],
+        )
+    }
+
+    /// Gets the current epoch.
+    pub fn current_epoch(&self) -> StdResult<u64> {
+        let response: EpochResponse = self.app.wrap().query_wasm_smart(
+            &self.epoch_manager_addr,
+            &mantra_dex_std::epoch_manager::QueryMsg::CurrentEpoch {},
+        )?;
+
+        Ok(response.epoch.id)
+    }
+
+    /// Gets the farm manager positions of the given address.
+    pub fn positions(&self, address: &Addr) -> StdResult<PositionsResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.farm_manager_addr,
+            &mantra_dex_std::farm_manager::QueryMsg::Positions {
+                filter_by: Some(PositionsBy::Receiver(address.to_string())),
+                open_state: Some(true),
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    /// Gets the farm rewards the given address can claim.
+    pub fn rewards(&self, address: &Addr) -> StdResult<RewardsResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.farm_manager_addr,
+            &mantra_dex_std::farm_manager::QueryMsg::Rewards {
+                address: address.to_string(),
+            },
+        )
+    }
+
+    /// Simulates a swap of the given asset for `ask_asset_denom` in a pool.
+    pub fn simulate(
+        &self,
+        pool_identifier: &str,
+        offer_asset: Coin,
+        ask_asset_denom: &str,
+    ) -> StdResult<SimulationResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Simulation {
+                offer_asset,
+                ask_asset_denom: ask_asset_denom.to_string(),
+                pool_identifier: pool_identifier.to_string(),
+                trader: None,
+            },
+        )
+    }
+
+    /// Gets the given pool along with its LP supply.
+    pub fn pool(&self, pool_identifier: &str) -> StdResult<PoolInfoResponse> {
+        let pools: PoolsResponse = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Pools {
+                pool_identifier: Some(pool_identifier.to_string()),
+                start_after: None,
+                limit: None,
+                order: None,
+                deprecated: None,
+            },
+        )?;
+
+        pools
+            .pools
+            .into_iter()
+            .next()
+            .ok_or_else(|| StdError::not_found(pool_identifier))
+    }
+
+    /// Gets all the pools along with their LP supply.
+    pub fn pools(&self) -> StdResult<Vec<PoolInfoResponse>> {
+        let mut pools = vec![];
+        let mut start_after = None;
+
+        loop {
+            let response: PoolsResponse = self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::Pools {
+                    pool_identifier: None,
+                    start_after: start_after.clone(),
+                    limit: Some(30),
+                    order: None,
+                    deprecated: None,
+                },
+            )?;
+
+            pools.extend(response.pools);
+            if response.next_key.is_none() {
+                return Ok(pools);
+            }
+            start_after = response.next_key;
+        }
+    }
+
+    /// Gets the balance of the given address.
+    pub fn balance(&self, address: &Addr, denom: &str) -> StdResult<Uint128> {
+        Ok(self.app.wrap().query_balance(address, denom)?.amount)
+    }
+
+    /// Gets the total supply of the given denom.
+    pub fn supply(&self, denom: &str) -> StdResult<Uint128> {
+        Ok(self.app.wrap().query_supply(denom)?.amount)
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coin, Decimal, Uint128};
+
+use mantra_dex_std::fee::{Fee, PoolFee};
+use mantra_dex_std::pool_manager::{PoolType, SimulationResponse};
+
+use crate::env::DexEnv;
+
+/// The environment variable rewriting the golden files with the current outputs instead of
+/// comparing against them, once a change to the outputs is intended.
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// The pool types of the matrix, labelled.
+const POOL_TYPES: [(&str, PoolType); 6] = [
+    ("constant_product", PoolType::ConstantProduct),
+    ("stableswap_amp_1", PoolType::StableSwap { amp: 1 }),
+    ("stableswap_amp_10", PoolType::StableSwap { amp: 10 }),
+    ("stableswap_amp_100", PoolType::StableSwap { amp: 100 }),
+    ("stableswap_amp_1000", PoolType::StableSwap { amp: 1_000 }),
+    (
+        "stableswap_amp_1000000",
+        PoolType::StableSwap { amp: 1_000_000 },
+    ),
+];
+/// The decimals of the assets of the matrix.
+const DECIMALS: [[u8; 2]; 4] = [[6, 6], [6, 18], [18, 6], [8, 8]];
+/// The offers of the matrix, in basis points of the reserve of the offered asset. Zero stands for
+/// the smallest unit of the asset.
+const OFFERS_BPS: [u128; 6] = [0, 1, 100, 1_000, 5_000, 20_000];
+const DENOMS: [&str; 2] = ["uom", "uusd"];
+/// Each asset is seeded with this amount of whole tokens.
+const SEED_TOKENS: u128 = 1_000_000;
+/// The deposit made on top of the seed, in whole tokens, imbalanced on purpose.
+const DEPOSIT_TOKENS: [u128; 2] = [10_000, 3_700];
+
+/// Computes the swap and LP mint outputs of the pool math for a matrix of pool types, asset
+/// decimals and fees, keyed by `{pool type}/{decimals}/{fees}/{output}`. The outputs go through
+/// the contract, so every code path a release ships is covered.
+pub fn snapshot_pool_math() -> AnyResult<BTreeMap<String, String>> {
+    let mut env = DexEnv::new(
+        DENOMS
+            .iter()
+            .map(|denom| coin(u128::MAX / 8, *denom))
+            .collect(),
+    );
+    let owner = env.owner();
+    let depositor = env.senders[1].clone();
+
+    let mut outputs = BTreeMap::new();
+    let mut pool_count = 0;
+
+    for (pool_type_label, pool_type) in POOL_TYPES {
+        for decimals in DECIMALS {
+            for (fees_label, pool_fees) in pool_fees() {
+                let case = format!(
+                    "{pool_type_label}/{}_{}/{fees_label}",
+                    decimals[0], decimals[1]
+                );
+                let pool_identifier = format!("golden{pool_count}");
+                pool_count += 1;
+
+                env.create_pool(
+                    DENOMS.iter().map(|denom| denom.to_string()).collect(),
+                    decimals.to_vec(),
+                    pool_fees,
+                    pool_type.clone(),
+                    &pool_identifier,
+                )?;
+                let pool_identifier = format!("o.{pool_identifier}");
+                let lp_denom = env.pool(&pool_identifier)?.pool_info.lp_denom;
+
+                // seed the pool with the same value of each asset
+                let lp_before = env.balance(&owner, &lp_denom)?;
+                env.provide_liquidity(
+                    &owner,
+                    &pool_identifier,
+                    vec![
+                        coin(to_units(SEED_TOKENS, decimals[0]), DENOMS[0]),
+                        coin(to_units(SEED_TOKENS, decimals[1]), DENOMS[1]),
+                    ],
+                )?;
+                outputs.insert(
+                    format!("{case}/seed_lp"),
+                    (env.balance(&owner, &lp_denom)? - lp_before).to_string(),
+                );
+
+                let lp_before = env.balance(&depositor, &lp_denom)?;
+                let deposit = env.provide_liquidity(
+                    &depositor,
+                    &pool_identifier,
+                    vec![
+                        coin(to_units(DEPOSIT_TOKENS[0], decimals[0]), DENOMS[0]),
+                        coin(to_units(DEPOSIT_TOKENS[1], decimals[1]), DENOMS[1]),
+                    ],
+                );
+                outputs.insert(
+                    format!("{case}/deposit_lp"),
+                    match deposit {
+                        Ok(_) => (env.balance(&depositor, &lp_denom)? - lp_before).to_string(),
+                        Err(err) => format!("error={}", err.root_cause()),
+                    },
+                );
+
+                let pool_info = env.pool(&pool_identifier)?.pool_info;
+                for (offer_index, ask_index) in [(0, 1), (1, 0)] {
+                    let reserve = pool_info.assets[offer_index].amount;
+                    for offer_bps in OFFERS_BPS {
+                        let offer_amount = if offer_bps == 0 {
+                            Uint128::one()
+                        } else {
+                            reserve.multiply_ratio(offer_bps, 10_000u128)
+                        };
+
+                        let simulation = env.simulate(
+                            &pool_identifier,
+                            coin(offer_amount.u128(), DENOMS[offer_index]),
+                            DENOMS[ask_index],
+                        );
+                        outputs.insert(
+                            format!(
+                                "{case}/swap_{}_for_{}/{offer_bps}bps",
+                                DENOMS[offer_index], DENOMS[ask_index]
+                            ),
+                            match simulation {
+                                Ok(simulation) => format_simulation(&simulation),
+                                Err(err) => format!("error={err}"),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(outputs)
+}
+
+/// Loads a golden file, made of `{key} = {value}` lines.
+pub fn load_golden(path: impl AsRef<Path>) -> AnyResult<BTreeMap<String, String>> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_once(" = ")
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| anyhow::anyhow!("malformed golden line: {line}"))
+        })
+        .collect()
+}
+
+/// Writes a golden file, one sorted `{key} = {value}` line per output so changes diff nicely.
+pub fn write_golden(path: impl AsRef<Path>, outputs: &BTreeMap<String, String>) -> AnyResult<()> {
+    let contents: String = outputs
+        .iter()
+        .map(|(key, value)| format!("{key} = {value}\n"))
+        .collect();
+
+    if let Some(parent) = path.as_ref().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    Ok(std::fs::write(path, contents)?)
+}
+
+/// Lists the outputs drifting from the golden ones, as well as the ones missing from either side.
+/// Returns an empty list if they match.
+pub fn diff_golden(
+    golden: &BTreeMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut differences = vec![];
+
+    for (key, expected) in golden {
+        match actual.get(key) {
+            Some(value) if value != expected => {
+                differences.push(format!("{key}: expected {expected}, got {value}"))
+            }
+            Some(_) => {}
+            None => differences.push(format!("{key}: missing")),
+        }
+    }
+
+    for key in actual.keys() {
+        if !golden.contains_key(key) {
+            differences.push(format!("{key}: not in the golden file"));
+        }
+    }
+
+    differences
+}
+
+/// Compares the outputs against the golden file, panicking on any drift. The golden file is
+/// written instead if it doesn't exist yet, or if [UPDATE_GOLDEN_ENV] is set.
+#[track_caller]
+pub fn assert_golden(path: impl AsRef<Path>, actual: &BTreeMap<String, String>) {
+    let path = path.as_ref();
+
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() || !path.exists() {
+        write_golden(path, actual).unwrap();
+        eprintln!("recorded {} outputs in {}", actual.len(), path.display());
+        return;
+    }
+
+    let differences = diff_golden(&load_golden(path).unwrap(), actual);
+    assert!(
+        differences.is_empty(),
+        "{} outputs drifted from {}, rerun with {UPDATE_GOLDEN_ENV}=1 if the change is intended:\n{}",
+        differences.len(),
+        path.display(),
+        differences.join("\n")
+    );
+}
+
+fn format_simulation(simulation: &SimulationResponse) -> String {
+    format!(
+        "return={}, spread={}, swap_fee={}, protocol_fee={}, burn_fee={}, extra_fees={}",
+        simulation.return_amount,
+        simulation.spread_amount,
+        simulation.swap_fee_amount,
+        simulation.protocol_fee_amount,
+        simulation.burn_fee_amount,
+        simulation.extra_fees_amount
+    )
+}
+
+fn to_units(tokens: u128, decimals: u8) -> u128 {
+    tokens * 10u128.pow(decimals.into())
+}
+
+/// The fee combinations of the matrix, labelled.
+fn pool_fees() -> [(&'static str, PoolFee); 3] {
+    let fees = |protocol_fee, swap_fee, burn_fee, extra_fees: Vec<Decimal>| PoolFee {
+        protocol_fee: Fee {
+            share: protocol_fee,
+        },
+        swap_fee: Fee { share: swap_fee },
+        burn_fee: Fee { share: burn_fee },
+        extra_fees: extra_fees.into_iter().map(|share| Fee { share }).collect(),
+    };
+
+    [
+        (
+            "no_fees",
+            fees(Decimal::zero(), Decimal::zero(), Decimal::zero(), vec![]),
+        ),
+        (
+            "standard_fees",
+            fees(
+                Decimal::permille(1),
+                Decimal::permille(2),
+                Decimal::zero(),
+                vec![],
+            ),
+        ),
+        (
+            "all_fees",
+            fees(
+                Decimal::percent(1),
+                Decimal::percent(2),
+                Decimal::permille(5),
+                vec![Decimal::permille(3), Decimal::permille(7)],
+            ),
+        ),
+    ]
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{StdResult, Uint128, Uint512};
+
+use mantra_dex_std::pool_manager::{PoolInfoResponse, PoolType};
+
+use crate::env::DexEnv;
+
+/// Tracks the state the conservation invariants of the pools are checked against between steps.
+pub struct Invariants {
+    /// What the pool manager holds on top of the pool reserves, per denom, e.g. fees or
+    /// escrowed funds. It must not change when only the pools are operated.
+    excess_balances: BTreeMap<String, Uint128>,
+    /// The constant product and the LP supply of the constant product pools.
+    constant_products: BTreeMap<String, (Uint512, Uint128)>,
+}
+
+impl Invariants {
+    /// Snapshots the current state of the pools.
+    pub fn new(env: &DexEnv) -> StdResult<Self> {
+        let mut invariants = Self {
+            excess_balances: BTreeMap::new(),
+            constant_products: BTreeMap::new(),
+        };
+        invariants.resync(env)?;
+
+        Ok(invariants)
+    }
+
+    /// Snapshots the current state of the pools again, e.g. after fees were paid to the pool
+    /// manager on pool creation.
+    pub fn resync(&mut self, env: &DexEnv) -> StdResult<()> {
+        let pools = env.pools()?;
+
+        self.excess_balances = get_excess_balances(env, &pools)?;
+        self.constant_products = get_constant_products(&pools);
+
+        Ok(())
+    }
+
+    /// Checks the invariants against the last snapshot, then snapshots the current state. Panics
+    /// if any of them doesn't hold.
+    #[track_caller]
+    pub fn check(&mut self, env: &DexEnv) -> StdResult<()> {
+        let pools = env.pools()?;
+
+        // the reserves match the balances held by the pool manager
+        let excess_balances = get_excess_balances(env, &pools)?;
+        assert_eq!(
+            excess_balances, self.excess_balances,
+            "the balances of the pool manager diverged from the pool reserves"
+        );
+
+        // the LP supply of the pools matches the supply of their LP denom
+        for pool in &pools {
+            let lp_supply = env.supply(&pool.pool_info.lp_denom)?;
+            assert_eq!(
+                pool.total_share.amount, lp_supply,
+                "the LP supply of {} diverged from the supply of its LP denom",
+                pool.pool_info.pool_identifier
+            );
+        }
+
+        // no value is created, i.e. the reserves backing each LP share never decrease
+        let constant_products = get_constant_products(&pools);
+        for (pool_identifier, (k, lp_supply)) in &constant_products {
+            let Some((previous_k, previous_lp_supply)) =
+                self.constant_products.get(pool_identifier)
+            else {
+                continue;
+            };
+            if previous_lp_supply.is_zero() || lp_supply.is_zero() {
+                continue;
+            }
+
+            let previous_lp_supply = Uint512::from(previous_lp_supply.u128());
+            let lp_supply = Uint512::from(lp_supply.u128());
+            assert!(
+                *k * previous_lp_supply * previous_lp_supply >= *previous_k * lp_supply * lp_supply,
+                "the value of the LP shares of {pool_identifier} decreased"
+            );
+        }
+
+        self.excess_balances = excess_balances;
+        self.constant_products = constant_products;
+
+        Ok(())
+    }
+}
+
+/// Gets what the pool manager holds on top of the reserves of the pools, for each denom of the
+/// pools. Panics if it holds less than the reserves.
+#[track_caller]
+fn get_excess_balances(
+    env: &DexEnv,
+    pools: &[PoolInfoResponse],
+) -> StdResult<BTreeMap<String, Uint128>> {
+    let mut reserves: BTreeMap<String, Uint128> = BTreeMap::new();
+    for pool in pools {
+        for asset in &pool.pool_info.assets {
+            *reserves.entry(asset.denom.clone()).or_default() += asset.amount;
+        }
+    }
+
+    reserves
+        .into_iter()
+        .map(|(denom, reserve)| {
+            let balance = env.balance(&env.pool_manager_addr, &denom)?;
+            let excess = balance.checked_sub(reserve).unwrap_or_else(|_| {
+                panic!("the pool manager holds {balance}{denom}, less than the {reserve}{denom} in reserves")
+            });
+
+            Ok((denom, excess))
+        })
+        .collect()
+}
+
+/// Gets the constant product and the LP supply of the constant product pools.
+fn get_constant_products(pools: &[PoolInfoResponse]) -> BTreeMap<String, (Uint512, Uint128)> {
+    pools
+        .iter()
+        .filter(|pool| pool.pool_info.pool_type == PoolType::ConstantProduct)
+        .map(|pool| {
+            let k = pool
+                .pool_info
+                .assets
+                .iter()
+                .fold(Uint512::one(), |k, asset| {
+                    k * Uint512::from(asset.amount.u128())
+                });
+
+            (
+                pool.pool_info.pool_identifier.clone(),
+                (k, pool.total_share.amount),
+            )
+        })
+        .collect()
+}
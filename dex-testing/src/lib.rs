@@ -0,0 +1,6 @@
+pub mod contracts;
+pub mod env;
+pub mod golden;
+pub mod invariants;
+pub mod replay;
+pub mod scenarios;
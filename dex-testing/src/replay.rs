@@ -0,0 +1,277 @@
+use std::path::Path;
+
+use anyhow::Result as AnyResult;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_string, Addr, Coin, Timestamp};
+use cw_multi_test::{AppResponse, BankSudo, Executor, SudoMsg};
+
+use mantra_dex_std::pool_manager::PoolInfoResponse;
+
+use crate::env::DexEnv;
+
+/// A trace of historical executes, as exported from an indexer.
+#[cw_serde]
+pub struct Trace {
+    /// The addresses of the contracts on chain, replaced by the ones of the replay environment
+    /// wherever they appear in the trace.
+    pub contracts: TraceContracts,
+    /// The address owning the contracts on chain. Its executes are sent by the owner of the replay
+    /// environment.
+    pub owner: String,
+    /// The pool creation fee configured on chain, if it differs from the one of the replay
+    /// environment.
+    pub pool_creation_fee: Option<Coin>,
+    /// The executes, in the order they were included in blocks.
+    pub steps: Vec<TraceStep>,
+}
+
+/// The addresses of the contracts of the DEX on chain.
+#[cw_serde]
+pub struct TraceContracts {
+    pub pool_manager: String,
+    pub farm_manager: Option<String>,
+    pub epoch_manager: Option<String>,
+    pub fee_collector: Option<String>,
+}
+
+/// An execute of a trace.
+#[cw_serde]
+pub struct TraceStep {
+    /// The height of the block the execute was included in.
+    pub height: u64,
+    /// The time of the block the execute was included in, in seconds.
+    pub time: u64,
+    pub sender: String,
+    pub msg: TraceMsg,
+    pub funds: Vec<Coin>,
+}
+
+/// The message of an execute of a trace, keyed by the contract it was sent to.
+#[cw_serde]
+pub enum TraceMsg {
+    PoolManager(mantra_dex_std::pool_manager::ExecuteMsg),
+    FarmManager(mantra_dex_std::farm_manager::ExecuteMsg),
+    EpochManager(mantra_dex_std::epoch_manager::ExecuteMsg),
+    FeeCollector(mantra_dex_std::fee_collector::ExecuteMsg),
+}
+
+/// The state resulting from replaying a trace, compared across replays to make sure changes to
+/// the contracts preserve their behavior.
+#[cw_serde]
+pub struct ReplayState {
+    /// The outcome of each step of the trace.
+    pub outcomes: Vec<StepOutcome>,
+    /// The pools once the trace is replayed.
+    pub pools: Vec<PoolInfoResponse>,
+}
+
+/// The outcome of a step of a trace.
+#[cw_serde]
+pub enum StepOutcome {
+    /// The execute succeeded, emitting the given attributes in its wasm events.
+    Success { attributes: Vec<(String, String)> },
+    /// The execute failed with the given error.
+    Failure { error: String },
+}
+
+/// Loads a trace from the given JSON file.
+pub fn load_trace(path: impl AsRef<Path>) -> AnyResult<Trace> {
+    Ok(from_json(std::fs::read(path)?)?)
+}
+
+/// Replays the given trace against a fresh deployment of the contracts. The senders of the trace
+/// are funded with the native tokens they send, as the trace only records their executes. LP
+/// tokens are never funded, so they must come from the replayed deposits.
+pub fn replay(trace: &Trace) -> AnyResult<ReplayState> {
+    let mut env = DexEnv::new(vec![]);
+
+    if let Some(pool_creation_fee) = &trace.pool_creation_fee {
+        env.app.execute_contract(
+            env.owner(),
+            env.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: Some(vec![pool_creation_fee.clone()]),
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        )?;
+    }
+
+    let outcomes = trace
+        .steps
+        .iter()
+        .map(|step| {
+            let step = remap_addresses(trace, &env, step)?;
+
+            Ok(match execute_step(&mut env, step) {
+                Ok(response) => StepOutcome::Success {
+                    attributes: get_wasm_attributes(&response),
+                },
+                Err(err) => StepOutcome::Failure {
+                    error: err.root_cause().to_string(),
+                },
+            })
+        })
+        .collect::<AnyResult<Vec<_>>>()?;
+
+    Ok(ReplayState {
+        outcomes,
+        pools: env.pools()?,
+    })
+}
+
+/// Lists the differences between the expected state of a replay and the actual one. Returns an
+/// empty list if the states match.
+pub fn diff(expected: &ReplayState, actual: &ReplayState) -> Vec<String> {
+    let mut differences = vec![];
+
+    if expected.outcomes.len() != actual.outcomes.len() {
+        differences.push(format!(
+            "expected {} steps, replayed {}",
+            expected.outcomes.len(),
+            actual.outcomes.len()
+        ));
+    }
+
+    for (index, (expected, actual)) in expected
+        .outcomes
+        .iter()
+        .zip(actual.outcomes.iter())
+        .enumerate()
+    {
+        if expected != actual {
+            differences.push(format!(
+                "step {index}: expected {expected:?}, got {actual:?}"
+            ));
+        }
+    }
+
+    for expected_pool in &expected.pools {
+        let pool_identifier = &expected_pool.pool_info.pool_identifier;
+        match actual
+            .pools
+            .iter()
+            .find(|pool| &pool.pool_info.pool_identifier == pool_identifier)
+        {
+            Some(actual_pool) if actual_pool != expected_pool => differences.push(format!(
+                "pool {pool_identifier}: expected {expected_pool:?}, got {actual_pool:?}"
+            )),
+            Some(_) => {}
+            None => differences.push(format!("pool {pool_identifier} is missing")),
+        }
+    }
+
+    for actual_pool in &actual.pools {
+        let pool_identifier = &actual_pool.pool_info.pool_identifier;
+        if !expected
+            .pools
+            .iter()
+            .any(|pool| &pool.pool_info.pool_identifier == pool_identifier)
+        {
+            differences.push(format!("pool {pool_identifier} is unexpected"));
+        }
+    }
+
+    differences
+}
+
+/// Replaces the addresses of the contracts and the owner on chain with the ones of the replay
+/// environment, wherever they appear in the step, e.g. in LP denoms or receivers.
+fn remap_addresses(trace: &Trace, env: &DexEnv, step: &TraceStep) -> AnyResult<TraceStep> {
+    let replacements = [
+        (Some(&trace.contracts.pool_manager), &env.pool_manager_addr),
+        (
+            trace.contracts.farm_manager.as_ref(),
+            &env.farm_manager_addr,
+        ),
+        (
+            trace.contracts.epoch_manager.as_ref(),
+            &env.epoch_manager_addr,
+        ),
+        (
+            trace.contracts.fee_collector.as_ref(),
+            &env.fee_collector_addr,
+        ),
+        (Some(&trace.owner), &env.senders[0]),
+    ];
+
+    let mut json = to_json_string(step)?;
+    for (address, local_address) in replacements {
+        if let Some(address) = address {
+            json = json.replace(address.as_str(), local_address.as_str());
+        }
+    }
+
+    Ok(from_json(json)?)
+}
+
+fn execute_step(env: &mut DexEnv, step: TraceStep) -> AnyResult<AppResponse> {
+    let mut block_info = env.app.block_info();
+    block_info.height = block_info.height.max(step.height);
+    block_info.time = block_info.time.max(Timestamp::from_seconds(step.time));
+    env.app.set_block(block_info);
+
+    let sender = Addr::unchecked(step.sender);
+
+    // fund the sender with the native tokens it's short of
+    for coin in &step.funds {
+        if coin.denom.starts_with("factory/") {
+            continue;
+        }
+
+        let balance = env.balance(&sender, &coin.denom)?;
+        if balance < coin.amount {
+            env.app.sudo(SudoMsg::Bank(BankSudo::Mint {
+                to_address: sender.to_string(),
+                amount: vec![Coin {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount - balance,
+                }],
+            }))?;
+        }
+    }
+
+    match step.msg {
+        TraceMsg::PoolManager(msg) => {
+            env.app
+                .execute_contract(sender, env.pool_manager_addr.clone(), &msg, &step.funds)
+        }
+        TraceMsg::FarmManager(msg) => {
+            env.app
+                .execute_contract(sender, env.farm_manager_addr.clone(), &msg, &step.funds)
+        }
+        TraceMsg::EpochManager(msg) => {
+            env.app
+                .execute_contract(sender, env.epoch_manager_addr.clone(), &msg, &step.funds)
+        }
+        TraceMsg::FeeCollector(msg) => {
+            env.app
+                .execute_contract(sender, env.fee_collector_addr.clone(), &msg, &step.funds)
+        }
+    }
+}
+
+/// Gets the attributes of the wasm events of the response, in the order they were emitted.
+fn get_wasm_attributes(response: &AppResponse) -> Vec<(String, String)> {
+    response
+        .events
+        .iter()
+        .filter(|event| event.ty.starts_with("wasm"))
+        .flat_map(|event| {
+            event
+                .attributes
+                .iter()
+                .map(|attribute| (attribute.key.clone(), attribute.value.clone()))
+        })
+        .collect()
+}
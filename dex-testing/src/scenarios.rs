@@ -0,0 +1,215 @@
+use cosmwasm_std::{coin, Addr, Coin, Decimal};
+
+use mantra_dex_std::fee::{Fee, PoolFee};
+use mantra_dex_std::pool_manager::PoolType;
+
+use crate::env::{DexEnv, EPOCH_DURATION};
+
+/// The denom every pool of a scenario trades against.
+pub const BASE_DENOM: &str = "uom";
+/// The denoms the pools of a scenario pair with [BASE_DENOM], in order.
+pub const QUOTE_DENOMS: [&str; 5] = ["uusd", "uusdc", "uusdt", "uatom", "uosmo"];
+/// The denom farms of a scenario distribute.
+pub const REWARD_DENOM: &str = "ureward";
+
+const INITIAL_BALANCE: u128 = 1_000_000_000_000_000;
+const SEED_LIQUIDITY: u128 = 1_000_000_000;
+const PROVIDED_LIQUIDITY: u128 = 100_000_000;
+
+/// An ecosystem of pools, farms and users built by a [ScenarioBuilder].
+pub struct Scenario {
+    pub env: DexEnv,
+    /// The identifiers of the pools, in order of creation.
+    pub pools: Vec<String>,
+    /// The users providing liquidity to every pool, locked in the farm manager.
+    pub liquidity_providers: Vec<Addr>,
+    /// The user swapping in every pool during the epochs of activity.
+    pub trader: Addr,
+}
+
+/// Builds scenarios wiring the pool manager, the farm manager and the epoch manager together,
+/// e.g. an incentivized 3-pool ecosystem after 10 epochs of activity:
+///
+/// ```ignore
+/// let scenario = ScenarioBuilder::new()
+///     .pools(3)
+///     .incentivized(coin(1_000_000, REWARD_DENOM), 20)
+///     .epochs_of_activity(10)
+///     .build();
+/// ```
+pub struct ScenarioBuilder {
+    pools: usize,
+    pool_type: PoolType,
+    farm: Option<(Coin, u64)>,
+    epochs_of_activity: u64,
+    swaps_per_epoch: u64,
+}
+
+impl Default for ScenarioBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScenarioBuilder {
+    /// A single constant product pool, without farms nor activity.
+    pub fn new() -> Self {
+        Self {
+            pools: 1,
+            pool_type: PoolType::ConstantProduct,
+            farm: None,
+            epochs_of_activity: 0,
+            swaps_per_epoch: 4,
+        }
+    }
+
+    /// Sets the number of pools, up to the number of [QUOTE_DENOMS].
+    pub fn pools(mut self, pools: usize) -> Self {
+        assert!(
+            (1..=QUOTE_DENOMS.len()).contains(&pools),
+            "scenarios have between 1 and {} pools",
+            QUOTE_DENOMS.len()
+        );
+        self.pools = pools;
+        self
+    }
+
+    /// Sets the type of the pools.
+    pub fn pool_type(mut self, pool_type: PoolType) -> Self {
+        self.pool_type = pool_type;
+        self
+    }
+
+    /// Incentivizes every pool with a farm distributing `reward` over the given amount of epochs,
+    /// starting from the next epoch.
+    pub fn incentivized(mut self, reward: Coin, epochs: u64) -> Self {
+        self.farm = Some((reward, epochs));
+        self
+    }
+
+    /// Sets the amount of epochs the trader swaps in every pool for.
+    pub fn epochs_of_activity(mut self, epochs: u64) -> Self {
+        self.epochs_of_activity = epochs;
+        self
+    }
+
+    /// Sets the amount of swaps the trader makes in every pool each epoch.
+    pub fn swaps_per_epoch(mut self, swaps: u64) -> Self {
+        self.swaps_per_epoch = swaps;
+        self
+    }
+
+    /// Deploys the contracts and plays the scenario. Panics if any step fails, as scenarios are
+    /// expected to be valid.
+    pub fn build(self) -> Scenario {
+        let mut balances = vec![coin(INITIAL_BALANCE, BASE_DENOM)];
+        balances.extend(
+            QUOTE_DENOMS
+                .iter()
+                .map(|denom| coin(INITIAL_BALANCE, *denom)),
+        );
+        balances.push(coin(INITIAL_BALANCE, REWARD_DENOM));
+
+        let mut env = DexEnv::new(balances);
+        let owner = env.owner();
+        let liquidity_providers = vec![env.senders[1].clone(), env.senders[2].clone()];
+        let trader = env.senders[3].clone();
+
+        let mut pools = vec![];
+        for quote_denom in QUOTE_DENOMS.iter().take(self.pools) {
+            let identifier = format!("{BASE_DENOM}_{quote_denom}");
+            env.create_pool(
+                vec![BASE_DENOM.to_string(), quote_denom.to_string()],
+                vec![6u8, 6u8],
+                pool_fees(),
+                self.pool_type.clone(),
+                &identifier,
+            )
+            .unwrap();
+
+            let pool_identifier = format!("o.{identifier}");
+            env.provide_liquidity(
+                &owner,
+                &pool_identifier,
+                vec![
+                    coin(SEED_LIQUIDITY, BASE_DENOM),
+                    coin(SEED_LIQUIDITY, *quote_denom),
+                ],
+            )
+            .unwrap();
+
+            for liquidity_provider in &liquidity_providers {
+                env.provide_locked_liquidity(
+                    liquidity_provider,
+                    &pool_identifier,
+                    vec![
+                        coin(PROVIDED_LIQUIDITY, BASE_DENOM),
+                        coin(PROVIDED_LIQUIDITY, *quote_denom),
+                    ],
+                    EPOCH_DURATION,
+                )
+                .unwrap();
+            }
+
+            if let Some((reward, epochs)) = &self.farm {
+                let start_epoch = env.current_epoch().unwrap() + 1;
+                env.fill_farm(
+                    &owner,
+                    &pool_identifier,
+                    reward.clone(),
+                    Some(start_epoch),
+                    Some(start_epoch + epochs),
+                )
+                .unwrap();
+            }
+
+            pools.push(pool_identifier);
+        }
+
+        for _ in 0..self.epochs_of_activity {
+            env.advance_epochs(1);
+
+            for (pool_identifier, quote_denom) in pools.iter().zip(QUOTE_DENOMS) {
+                // alternate the direction of the swaps so the pools stay balanced
+                for swap in 0..self.swaps_per_epoch {
+                    let (offer_denom, ask_denom) = if swap % 2 == 0 {
+                        (BASE_DENOM, quote_denom)
+                    } else {
+                        (quote_denom, BASE_DENOM)
+                    };
+
+                    env.swap(
+                        &trader,
+                        pool_identifier,
+                        coin(1_000_000, offer_denom),
+                        ask_denom,
+                        None,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        Scenario {
+            env,
+            pools,
+            liquidity_providers,
+            trader,
+        }
+    }
+}
+
+fn pool_fees() -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::permille(1),
+        },
+        swap_fee: Fee {
+            share: Decimal::permille(2),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        extra_fees: vec![],
+    }
+}
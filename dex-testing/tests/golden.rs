@@ -0,0 +1,13 @@
+use dex_testing::golden::{assert_golden, snapshot_pool_math};
+
+/// Fails on any drift of the swap and LP mint outputs of the pool math, e.g. a rounding change.
+/// Intended changes are recorded with `UPDATE_GOLDEN=1 cargo test -p dex-testing --test golden`.
+#[test]
+fn pool_math_outputs_match_golden_file() {
+    let outputs = snapshot_pool_math().unwrap();
+
+    assert_golden(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/pool_math.golden"),
+        &outputs,
+    );
+}
@@ -0,0 +1,241 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use dex_testing::env::DexEnv;
+use dex_testing::invariants::Invariants;
+use mantra_dex_std::fee::{Fee, PoolFee};
+use mantra_dex_std::pool_manager::PoolType;
+
+const DENOMS: [&str; 2] = ["uom", "uusd"];
+const INITIAL_BALANCE: u128 = 1_000_000_000_000_000;
+const MAX_AMOUNT: u128 = 10_000_000_000;
+
+#[derive(Debug, Clone)]
+enum Action {
+    CreatePool {
+        stable: bool,
+    },
+    ProvideLiquidity {
+        sender: usize,
+        pool: Index,
+        amounts: [u128; 2],
+    },
+    Swap {
+        sender: usize,
+        pool: Index,
+        offer_index: usize,
+        amount: u128,
+    },
+    WithdrawLiquidity {
+        sender: usize,
+        pool: Index,
+        share: Decimal,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        1 => any::<bool>().prop_map(|stable| Action::CreatePool { stable }),
+        3 => (0..4usize, any::<Index>(), 1..MAX_AMOUNT, 1..MAX_AMOUNT).prop_map(
+            |(sender, pool, amount_0, amount_1)| Action::ProvideLiquidity {
+                sender,
+                pool,
+                amounts: [amount_0, amount_1],
+            }
+        ),
+        5 => (0..4usize, any::<Index>(), 0..2usize, 1..MAX_AMOUNT).prop_map(
+            |(sender, pool, offer_index, amount)| Action::Swap {
+                sender,
+                pool,
+                offer_index,
+                amount,
+            }
+        ),
+        2 => (0..4usize, any::<Index>(), 1..=100u64).prop_map(|(sender, pool, percent)| {
+            Action::WithdrawLiquidity {
+                sender,
+                pool,
+                share: Decimal::percent(percent),
+            }
+        }),
+    ]
+}
+
+fn pool_fees() -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::permille(1),
+        },
+        swap_fee: Fee {
+            share: Decimal::permille(2),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        extra_fees: vec![],
+    }
+}
+
+fn create_pool(env: &mut DexEnv, stable: bool, count: usize) {
+    let (pool_type, identifier) = if stable {
+        (PoolType::StableSwap { amp: 100 }, format!("stable_{count}"))
+    } else {
+        (PoolType::ConstantProduct, format!("cp_{count}"))
+    };
+
+    env.create_pool(
+        DENOMS.iter().map(|denom| denom.to_string()).collect(),
+        vec![6u8, 6u8],
+        pool_fees(),
+        pool_type,
+        &identifier,
+    )
+    .unwrap();
+}
+
+fn pick_pool(env: &DexEnv, pool: &Index) -> String {
+    let pools = env.pools().unwrap();
+
+    pool.get(&pools).pool_info.pool_identifier.clone()
+}
+
+/// Applies the action, ignoring the ones the contracts reject. Rejected actions must leave the
+/// state untouched, which the invariants check as well.
+fn apply(env: &mut DexEnv, invariants: &mut Invariants, action: &Action, pool_count: &mut usize) {
+    match action {
+        Action::CreatePool { stable } => {
+            create_pool(env, *stable, *pool_count);
+            *pool_count += 1;
+
+            // the fees paid to create the pool are not part of any reserve
+            invariants.resync(env).unwrap();
+            return;
+        }
+        Action::ProvideLiquidity {
+            sender,
+            pool,
+            amounts,
+        } => {
+            let pool_identifier = pick_pool(env, pool);
+            let sender = env.senders[*sender].clone();
+            let _ = env.provide_liquidity(
+                &sender,
+                &pool_identifier,
+                vec![coin(amounts[0], DENOMS[0]), coin(amounts[1], DENOMS[1])],
+            );
+        }
+        Action::Swap {
+            sender,
+            pool,
+            offer_index,
+            amount,
+        } => {
+            let pool_identifier = pick_pool(env, pool);
+            let sender = env.senders[*sender].clone();
+            let _ = env.swap(
+                &sender,
+                &pool_identifier,
+                coin(*amount, DENOMS[*offer_index]),
+                DENOMS[1 - *offer_index],
+                Some(Decimal::percent(50)),
+            );
+        }
+        Action::WithdrawLiquidity {
+            sender,
+            pool,
+            share,
+        } => {
+            let pool_identifier = pick_pool(env, pool);
+            let sender = env.senders[*sender].clone();
+            let lp_denom = env.pool(&pool_identifier).unwrap().pool_info.lp_denom;
+            let lp_amount = env.balance(&sender, &lp_denom).unwrap().mul_floor(*share);
+
+            if !lp_amount.is_zero() {
+                let _ = env.withdraw_liquidity(&sender, &pool_identifier, lp_amount);
+            }
+        }
+    }
+
+    invariants.check(env).unwrap();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn pools_conserve_value(actions in prop::collection::vec(action(), 1..40)) {
+        let mut env = DexEnv::new(
+            DENOMS
+                .iter()
+                .map(|denom| coin(INITIAL_BALANCE, *denom))
+                .collect(),
+        );
+
+        // seed a pool of each type so every action has a pool to operate on
+        create_pool(&mut env, false, 0);
+        create_pool(&mut env, true, 1);
+        let mut pool_count = 2;
+
+        let owner = env.owner();
+        for pool in env.pools().unwrap() {
+            env.provide_liquidity(
+                &owner,
+                &pool.pool_info.pool_identifier,
+                vec![coin(1_000_000_000, DENOMS[0]), coin(1_000_000_000, DENOMS[1])],
+            )
+            .unwrap();
+        }
+
+        let mut invariants = Invariants::new(&env).unwrap();
+        for action in &actions {
+            apply(&mut env, &mut invariants, action, &mut pool_count);
+        }
+    }
+
+    #[test]
+    fn round_trip_swaps_create_no_value(
+        amount in 1..MAX_AMOUNT,
+        stable in any::<bool>(),
+    ) {
+        let mut env = DexEnv::new(
+            DENOMS
+                .iter()
+                .map(|denom| coin(INITIAL_BALANCE, *denom))
+                .collect(),
+        );
+        create_pool(&mut env, stable, 0);
+
+        let owner = env.owner();
+        let pool_identifier = env.pools().unwrap()[0].pool_info.pool_identifier.clone();
+        env.provide_liquidity(
+            &owner,
+            &pool_identifier,
+            vec![coin(100_000_000_000, DENOMS[0]), coin(100_000_000_000, DENOMS[1])],
+        )
+        .unwrap();
+
+        let trader = env.senders[1].clone();
+        let balance_before = env.balance(&trader, DENOMS[0]).unwrap();
+
+        let _ = env.swap(
+            &trader,
+            &pool_identifier,
+            coin(amount, DENOMS[0]),
+            DENOMS[1],
+            Some(Decimal::percent(50)),
+        );
+        let received = env.balance(&trader, DENOMS[1]).unwrap() - Uint128::new(INITIAL_BALANCE);
+        if !received.is_zero() {
+            let _ = env.swap(
+                &trader,
+                &pool_identifier,
+                coin(received.u128(), DENOMS[1]),
+                DENOMS[0],
+                Some(Decimal::percent(50)),
+            );
+        }
+
+        prop_assert!(env.balance(&trader, DENOMS[0]).unwrap() <= balance_before);
+    }
+}
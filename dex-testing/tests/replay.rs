@@ -0,0 +1,54 @@
+use cosmwasm_std::Uint128;
+
+use dex_testing::replay::{diff, load_trace, replay, StepOutcome};
+
+const TRACE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/traces/om_usd.json");
+
+#[test]
+fn replays_trace() {
+    let trace = load_trace(TRACE).unwrap();
+    let state = replay(&trace).unwrap();
+
+    assert_eq!(state.outcomes.len(), 5);
+    for (index, outcome) in state.outcomes.iter().enumerate() {
+        // the fourth step swaps in a pool that doesn't exist
+        match outcome {
+            StepOutcome::Failure { .. } => assert_eq!(index, 3),
+            StepOutcome::Success { attributes } => {
+                assert!(attributes.iter().any(|(key, _)| key == "action"))
+            }
+        }
+    }
+
+    assert_eq!(state.pools.len(), 1);
+    assert_eq!(state.pools[0].pool_info.pool_identifier, "o.om_usd");
+    // half of the LP shares of the depositor were withdrawn
+    assert_eq!(state.pools[0].total_share.amount, Uint128::new(500_000));
+}
+
+#[test]
+fn replays_are_deterministic() {
+    let trace = load_trace(TRACE).unwrap();
+
+    let expected = replay(&trace).unwrap();
+    let actual = replay(&trace).unwrap();
+
+    assert!(diff(&expected, &actual).is_empty());
+}
+
+#[test]
+fn diff_reports_drift() {
+    let trace = load_trace(TRACE).unwrap();
+
+    let expected = replay(&trace).unwrap();
+    let mut actual = expected.clone();
+    actual.pools[0].pool_info.assets[0].amount += Uint128::one();
+    actual.outcomes[2] = StepOutcome::Failure {
+        error: "drift".to_string(),
+    };
+
+    let differences = diff(&expected, &actual);
+    assert_eq!(differences.len(), 2);
+    assert!(differences[0].starts_with("step 2"));
+    assert!(differences[1].starts_with("pool o.om_usd"));
+}
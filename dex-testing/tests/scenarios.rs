@@ -0,0 +1,74 @@
+use cosmwasm_std::coin;
+
+use dex_testing::invariants::Invariants;
+use dex_testing::scenarios::{ScenarioBuilder, REWARD_DENOM};
+use mantra_dex_std::farm_manager::RewardsResponse;
+
+#[test]
+fn incentivized_ecosystem_distributes_rewards() {
+    let mut scenario = ScenarioBuilder::new()
+        .pools(3)
+        .incentivized(coin(1_000_000_000, REWARD_DENOM), 20)
+        .epochs_of_activity(10)
+        .build();
+
+    assert_eq!(scenario.pools.len(), 3);
+    assert_eq!(scenario.env.current_epoch().unwrap(), 10);
+
+    // the swaps of the trader moved the pools, which still hold together
+    Invariants::new(&scenario.env).unwrap();
+    for pool_identifier in &scenario.pools {
+        let pool = scenario.env.pool(pool_identifier).unwrap();
+        assert_ne!(
+            pool.pool_info.assets[0].amount,
+            pool.pool_info.assets[1].amount
+        );
+    }
+
+    for liquidity_provider in scenario.liquidity_providers.clone() {
+        // a position per pool
+        let positions = scenario.env.positions(&liquidity_provider).unwrap();
+        assert_eq!(positions.positions.len(), 3);
+
+        let RewardsResponse::RewardsResponse { total_rewards, .. } =
+            scenario.env.rewards(&liquidity_provider).unwrap()
+        else {
+            panic!("should return RewardsResponse::RewardsResponse");
+        };
+        assert_eq!(total_rewards.len(), 1);
+        assert_eq!(total_rewards[0].denom, REWARD_DENOM);
+
+        let balance_before = scenario
+            .env
+            .balance(&liquidity_provider, REWARD_DENOM)
+            .unwrap();
+        scenario.env.claim(&liquidity_provider).unwrap();
+        let balance_after = scenario
+            .env
+            .balance(&liquidity_provider, REWARD_DENOM)
+            .unwrap();
+
+        assert_eq!(balance_after - balance_before, total_rewards[0].amount);
+    }
+}
+
+#[test]
+fn scenarios_without_farms_have_no_rewards() {
+    let scenario = ScenarioBuilder::new().epochs_of_activity(2).build();
+
+    let RewardsResponse::RewardsResponse { total_rewards, .. } = scenario
+        .env
+        .rewards(&scenario.liquidity_providers[0])
+        .unwrap()
+    else {
+        panic!("should return RewardsResponse::RewardsResponse");
+    };
+    assert!(total_rewards.is_empty());
+
+    // the liquidity is still locked
+    let positions = scenario
+        .env
+        .positions(&scenario.liquidity_providers[0])
+        .unwrap();
+    assert_eq!(positions.positions.len(), 1);
+}
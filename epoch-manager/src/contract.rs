@@ -2,7 +2,10 @@ use cosmwasm_std::{ensure, entry_point, to_json_binary};
 use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response};
 use cw2::set_contract_version;
 
-use mantra_dex_std::epoch_manager::{Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
+use mantra_dex_std::epoch_manager::{
+    Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+};
 use mantra_utils::validate_contract;
 
 use crate::error::ContractError;
@@ -54,7 +57,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    circuit_breaker::assert_message_enabled(deps.storage, &msg)?;
+
+    let response = match msg {
         ExecuteMsg::UpdateConfig { epoch_config } => {
             cw_utils::nonpayable(&info)?;
             commands::update_config(deps, &info, epoch_config)
@@ -63,7 +68,9 @@ pub fn execute(
             cw_utils::nonpayable(&info)?;
             mantra_utils::ownership::update_ownership(deps, env, info, action).map_err(Into::into)
         }
-    }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
 }
 
 #[entry_point]
@@ -73,9 +80,26 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::CurrentEpoch {} => Ok(to_json_binary(&queries::query_current_epoch(deps, env)?)?),
         QueryMsg::Epoch { id } => Ok(to_json_binary(&queries::query_epoch(deps, id)?)?),
         QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
+        QueryMsg::DisabledMessages {} => {
+            Ok(to_json_binary(&queries::query_disabled_messages(deps)?)?)
+        }
     }
 }
 
+#[entry_point]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let response = match msg {
+        SudoMsg::DisableMessages { messages } => {
+            circuit_breaker::disable_messages::<ExecuteMsg>(deps.storage, messages)
+        }
+        SudoMsg::EnableMessages { messages } => {
+            circuit_breaker::enable_messages(deps.storage, messages)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
+}
+
 #[entry_point]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
@@ -1,3 +1,4 @@
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::StdError;
 use cw_migrate_error_derive::cw_migrate_invalid_version_error;
 use cw_ownable::OwnershipError;
@@ -27,6 +28,9 @@ pub enum ContractError {
 
     #[error("Invalid epoch duration, must be at least {min}.")]
     InvalidEpochDuration { min: u64 },
+
+    #[error("{0}")]
+    CircuitBreakerError(#[from] CircuitBreakerError),
 }
 
 impl From<semver::Error> for ContractError {
@@ -1,7 +1,9 @@
 use cosmwasm_std::{ensure, Deps, Env, StdError, Timestamp, Uint64};
 
 use crate::ContractError;
-use mantra_dex_std::epoch_manager::{ConfigResponse, Epoch, EpochResponse};
+use mantra_dex_std::epoch_manager::{
+    ConfigResponse, DisabledMessagesResponse, Epoch, EpochResponse,
+};
 
 use crate::state::CONFIG;
 
@@ -52,3 +54,13 @@ pub(crate) fn query_epoch(deps: Deps, id: u64) -> Result<EpochResponse, Contract
 
     Ok(epoch.to_epoch_response())
 }
+
+/// Gets the execute messages disabled by the circuit breaker module of the chain. Returns a
+/// [DisabledMessagesResponse].
+pub(crate) fn query_disabled_messages(
+    deps: Deps,
+) -> Result<DisabledMessagesResponse, ContractError> {
+    Ok(DisabledMessagesResponse {
+        messages: circuit_breaker::get_disabled_messages(deps.storage)?,
+    })
+}
@@ -0,0 +1,74 @@
+use circuit_breaker::CircuitBreakerError;
+use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+use cosmwasm_std::{from_json, Uint64};
+use cw_multi_test::IntoBech32;
+
+use epoch_manager::contract::{execute, query, sudo};
+use epoch_manager::ContractError;
+use mantra_dex_std::epoch_manager::{
+    DisabledMessagesResponse, EpochConfig, ExecuteMsg, QueryMsg, SudoMsg,
+};
+
+use crate::common::mock_instantiation;
+
+mod common;
+
+#[test]
+fn chain_disables_and_enables_messages() {
+    let mut deps = mock_dependencies();
+
+    let owner = "owner".into_bech32();
+
+    let info = message_info(&owner, &[]);
+    let current_time = mock_env().block.time;
+    mock_instantiation(deps.as_mut(), &mock_env(), info.clone()).unwrap();
+
+    let msg = SudoMsg::DisableMessages {
+        messages: vec!["update_configs".to_string()],
+    };
+    let err = sudo(deps.as_mut(), mock_env(), msg).unwrap_err();
+    match err {
+        ContractError::CircuitBreakerError(CircuitBreakerError::InvalidMessageName { message }) => {
+            assert_eq!(message, "update_configs")
+        }
+        _ => panic!("should return CircuitBreakerError::InvalidMessageName"),
+    }
+
+    let msg = SudoMsg::DisableMessages {
+        messages: vec!["update_config".to_string()],
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::DisabledMessages {}).unwrap();
+    let disabled_messages_res: DisabledMessagesResponse = from_json(query_res).unwrap();
+    assert_eq!(
+        disabled_messages_res.messages,
+        vec!["update_config".to_string()]
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        epoch_config: Some(EpochConfig {
+            duration: Uint64::new(172800),
+            genesis_epoch: Uint64::new(current_time.seconds()),
+        }),
+    };
+
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+    match err {
+        ContractError::CircuitBreakerError(CircuitBreakerError::MessageDisabled { message }) => {
+            assert_eq!(message, "update_config")
+        }
+        _ => panic!("should return CircuitBreakerError::MessageDisabled"),
+    }
+
+    let sudo_msg = SudoMsg::EnableMessages {
+        messages: vec!["update_config".to_string()],
+    };
+    sudo(deps.as_mut(), mock_env(), sudo_msg).unwrap();
+
+    let query_res = query(deps.as_ref(), mock_env(), QueryMsg::DisabledMessages {}).unwrap();
+    let disabled_messages_res: DisabledMessagesResponse = from_json(query_res).unwrap();
+    assert!(disabled_messages_res.messages.is_empty());
+
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
@@ -3,8 +3,9 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 
+use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
 use mantra_dex_std::farm_manager::{
-    Config, ExecuteMsg, FarmAction, InstantiateMsg, MigrateMsg, PositionAction, QueryMsg,
+    Config, ExecuteMsg, FarmAction, InstantiateMsg, MigrateMsg, PositionAction, QueryMsg, SudoMsg,
 };
 use mantra_utils::validate_contract;
 
@@ -95,7 +96,9 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    circuit_breaker::assert_message_enabled(deps.storage, &msg)?;
+
+    let response = match msg {
         ExecuteMsg::ManageFarm { action } => match action {
             FarmAction::Fill { params } => manager::commands::fill_farm(deps, env, info, params),
             FarmAction::Close { farm_identifier } => {
@@ -162,7 +165,21 @@ pub fn execute(
                 emergency_unlock_penalty,
             )
         }
-    }
+        ExecuteMsg::UpdateSuccessor { successor } => {
+            manager::commands::update_successor(deps, info, successor)
+        }
+        ExecuteMsg::UpdatePredecessor { predecessor } => {
+            manager::commands::update_predecessor(deps, info, predecessor)
+        }
+        ExecuteMsg::MigratePositions { receiver, limit } => {
+            position::commands::migrate_positions(deps, env, info, receiver, limit)
+        }
+        ExecuteMsg::ReceivePositions { positions } => {
+            position::commands::receive_positions(deps, env, info, positions)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
 }
 
 #[entry_point]
@@ -202,9 +219,27 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         } => Ok(to_json_binary(&queries::query_lp_weight(
             deps, address, denom, epoch_id,
         )?)?),
+        QueryMsg::Succession {} => Ok(to_json_binary(&queries::query_succession(deps)?)?),
+        QueryMsg::DisabledMessages {} => {
+            Ok(to_json_binary(&queries::query_disabled_messages(deps)?)?)
+        }
     }
 }
 
+#[entry_point]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let response = match msg {
+        SudoMsg::DisableMessages { messages } => {
+            circuit_breaker::disable_messages::<ExecuteMsg>(deps.storage, messages)
+        }
+        SudoMsg::EnableMessages { messages } => {
+            circuit_breaker::enable_messages(deps.storage, messages)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
+}
+
 #[entry_point]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
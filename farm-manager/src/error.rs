@@ -1,3 +1,4 @@
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyFractionError, ConversionOverflowError,
     DivideByZeroError, OverflowError, StdError, Uint128,
@@ -165,6 +166,15 @@ pub enum ContractError {
 
     #[error("Invalid identifier provided: {identifier}.")]
     InvalidIdentifier { identifier: String },
+
+    #[error("No successor was set to migrate the positions to")]
+    NoSuccessor,
+
+    #[error("{receiver} has no positions to migrate")]
+    NoPositionsToMigrate { receiver: String },
+
+    #[error("{0}")]
+    CircuitBreakerError(#[from] CircuitBreakerError),
 }
 
 impl From<semver::Error> for ContractError {
@@ -20,10 +20,34 @@ use crate::ContractError;
 pub(crate) fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
     cw_utils::nonpayable(&info)?;
 
+    let total_rewards = claim_rewards(deps, &env, &info.sender)?;
+
+    let mut messages = vec![];
+
+    // don't send any bank message if there's nothing to send
+    if !total_rewards.is_empty() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: total_rewards,
+        }));
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![("action", "claim".to_string())]))
+}
+
+/// Claims the pending rewards of the given receiver, updating the farms and the receiver's state,
+/// and returns them so they can be sent. Fails if the receiver has no open positions.
+pub(crate) fn claim_rewards(
+    deps: DepsMut,
+    env: &Env,
+    receiver: &Addr,
+) -> Result<Vec<Coin>, ContractError> {
     // check if the user has any open LP positions
     let open_positions = get_positions_by_receiver(
         deps.storage,
-        info.sender.as_str(),
+        receiver.as_str(),
         Some(true),
         None,
         Some(MAX_ITEMS_LIMIT),
@@ -44,9 +68,9 @@ pub(crate) fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
         // calculate the rewards for the lp denom
         let rewards_response = calculate_rewards(
             deps.as_ref(),
-            &env,
+            env,
             lp_denom,
-            &info.sender,
+            receiver,
             current_epoch.id,
             true,
         )?;
@@ -83,7 +107,7 @@ pub(crate) fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
                 // sync the address lp weight history for the user
                 sync_address_lp_weight_history(
                     deps.storage,
-                    &info.sender,
+                    receiver,
                     lp_denom,
                     &current_epoch.id,
                     true,
@@ -94,21 +118,9 @@ pub(crate) fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respon
     }
 
     // update the last claimed epoch for the user
-    LAST_CLAIMED_EPOCH.save(deps.storage, &info.sender, &current_epoch.id)?;
-
-    let mut messages = vec![];
+    LAST_CLAIMED_EPOCH.save(deps.storage, receiver, &current_epoch.id)?;
 
-    // don't send any bank message if there's nothing to send
-    if !total_rewards.is_empty() {
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: aggregate_coins(total_rewards)?,
-        }));
-    }
-
-    Ok(Response::default()
-        .add_messages(messages)
-        .add_attributes(vec![("action", "claim".to_string())]))
+    Ok(aggregate_coins(total_rewards)?)
 }
 
 /// Calculates the rewards for a position
@@ -12,7 +12,10 @@ use crate::helpers::{
     validate_emergency_unlock_penalty, validate_farm_epochs, validate_farm_expiration_time,
     validate_identifier, validate_lp_denom, validate_unlocking_duration,
 };
-use crate::state::{get_farm_by_identifier, get_farms_by_lp_denom, CONFIG, FARMS, FARM_COUNTER};
+use crate::state::{
+    get_farm_by_identifier, get_farms_by_lp_denom, CONFIG, FARMS, FARM_COUNTER, PREDECESSOR,
+    SUCCESSOR,
+};
 use crate::ContractError;
 
 pub(crate) fn fill_farm(
@@ -390,3 +393,59 @@ pub(crate) fn update_config(
         ),
     ]))
 }
+
+/// Sets the farm manager superseding this one, to which positions can then be migrated with
+/// `MigratePositions`. Clears it if `None`, halting the migration.
+pub(crate) fn update_successor(
+    deps: DepsMut,
+    info: MessageInfo,
+    successor: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let successor = match successor {
+        Some(successor) => {
+            let successor = deps.api.addr_validate(&successor)?;
+            SUCCESSOR.save(deps.storage, &successor)?;
+            successor.into_string()
+        }
+        None => {
+            SUCCESSOR.remove(deps.storage);
+            "none".to_string()
+        }
+    };
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_successor".to_string()),
+        ("successor", successor),
+    ]))
+}
+
+/// Sets the farm manager this one supersedes, the only one allowed to migrate positions into this
+/// contract. Clears it if `None`.
+pub(crate) fn update_predecessor(
+    deps: DepsMut,
+    info: MessageInfo,
+    predecessor: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let predecessor = match predecessor {
+        Some(predecessor) => {
+            let predecessor = deps.api.addr_validate(&predecessor)?;
+            PREDECESSOR.save(deps.storage, &predecessor)?;
+            predecessor.into_string()
+        }
+        None => {
+            PREDECESSOR.remove(deps.storage);
+            "none".to_string()
+        }
+    };
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_predecessor".to_string()),
+        ("predecessor", predecessor),
+    ]))
+}
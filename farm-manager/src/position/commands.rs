@@ -1,11 +1,13 @@
 use cosmwasm_std::{
-    coin, ensure, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
-    Uint128,
+    coin, ensure, wasm_execute, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    Response, Uint128,
 };
 use std::collections::HashSet;
 
-use mantra_dex_std::farm_manager::Position;
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::farm_manager::{ExecuteMsg, Position};
 
+use crate::farm::commands::claim_rewards;
 use crate::helpers::{validate_identifier, validate_lp_denom};
 use crate::position::helpers::{
     calculate_weight, create_penalty_share_msg, get_latest_address_weight, reconcile_user_state,
@@ -16,8 +18,8 @@ use crate::position::helpers::{
     validate_positions_limit, validate_unlocking_duration_for_position,
 };
 use crate::state::{
-    get_farms_by_lp_denom, get_position, CONFIG, LP_WEIGHT_HISTORY, MAX_ITEMS_LIMIT, POSITIONS,
-    POSITION_ID_COUNTER,
+    get_farms_by_lp_denom, get_position, get_positions_by_receiver, CONFIG, LP_WEIGHT_HISTORY,
+    MAX_ITEMS_LIMIT, POSITIONS, POSITION_ID_COUNTER, PREDECESSOR, SUCCESSOR,
 };
 use crate::ContractError;
 
@@ -290,6 +292,8 @@ fn close_position_in_full(position: &mut Position, expires_at: u64) -> Uint128 {
 
 /// Withdraws the given position. If the position has not expired, i.e. the unlocking period has not
 /// passed, the position can be withdrawn with a penalty fee using the`emergency_unlock` param.
+/// The pool manager can withdraw expired positions on behalf of their receiver, in which case the
+/// LP tokens are sent to the pool manager so it can withdraw the liquidity for the receiver.
 pub(crate) fn withdraw_position(
     mut deps: DepsMut,
     env: Env,
@@ -305,12 +309,18 @@ pub(crate) fn withdraw_position(
         },
     )?;
 
+    let current_time = env.block.time.seconds();
+
+    // the pool manager can only withdraw positions that expired, on behalf of their receiver
+    let pool_manager_addr = CONFIG.load(deps.storage)?.pool_manager_addr;
     ensure!(
-        position.receiver == info.sender,
+        position.receiver == info.sender
+            || (info.sender == pool_manager_addr
+                && !emergency_unlock.unwrap_or(false)
+                && position.is_expired(current_time)),
         ContractError::Unauthorized
     );
 
-    let current_time = env.block.time.seconds();
     let mut messages: Vec<CosmosMsg> = vec![];
 
     // check if the emergency unlock is requested, will pull the whole position out whether it's
@@ -425,7 +435,7 @@ pub(crate) fn withdraw_position(
         // withdraw the remaining LP tokens
         messages.push(
             BankMsg::Send {
-                to_address: position.receiver.to_string(),
+                to_address: info.sender.to_string(),
                 amount: vec![position.lp_asset.clone()],
             }
             .into(),
@@ -443,12 +453,210 @@ pub(crate) fn withdraw_position(
     Ok(Response::default()
         .add_attributes(vec![
             ("action", "withdraw_position".to_string()),
-            ("receiver", info.sender.to_string()),
+            ("receiver", position.receiver.to_string()),
             ("identifier", identifier),
         ])
         .add_messages(messages))
 }
 
+/// Migrates up to `limit` positions of the given receiver, open or closed, to the successor of this
+/// contract, along with their LP tokens. Anyone can do this once a successor is set, so positions
+/// aren't left behind when the farm manager is replaced. The pending rewards of the receiver are
+/// claimed on its behalf first, as its weights are cleared once its open positions leave.
+pub(crate) fn migrate_positions(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: String,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let successor = SUCCESSOR
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoSuccessor)?;
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    let positions = get_positions_by_receiver(
+        deps.storage,
+        receiver.as_str(),
+        None,
+        None,
+        Some(limit.unwrap_or(MAX_ITEMS_LIMIT)),
+    )?;
+    ensure!(
+        !positions.is_empty(),
+        ContractError::NoPositionsToMigrate {
+            receiver: receiver.to_string(),
+        }
+    );
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    if positions.iter().any(|position| position.open) {
+        let rewards = claim_rewards(deps.branch(), &env, &receiver)?;
+
+        // don't send any bank message if there's nothing to send
+        if !rewards.is_empty() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: receiver.to_string(),
+                    amount: rewards,
+                }
+                .into(),
+            );
+        }
+    }
+
+    for position in &positions {
+        POSITIONS.remove(deps.storage, &position.identifier)?;
+
+        // closed positions have no weight left
+        if position.open {
+            update_weights(
+                deps.branch(),
+                &env,
+                &receiver,
+                &position.lp_asset,
+                position.unlocking_duration,
+                false,
+            )?;
+        }
+    }
+
+    for position in positions.iter().filter(|position| position.open) {
+        reconcile_user_state(deps.branch(), &receiver, position)?;
+    }
+
+    let lp_assets = aggregate_coins(
+        positions
+            .iter()
+            .map(|position| position.lp_asset.clone())
+            .collect(),
+    )?;
+
+    messages.push(
+        wasm_execute(
+            successor.clone(),
+            &ExecuteMsg::ReceivePositions {
+                positions: positions.clone(),
+            },
+            lp_assets,
+        )?
+        .into(),
+    );
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "migrate_positions".to_string()),
+            ("receiver", receiver.into_string()),
+            ("successor", successor.into_string()),
+            (
+                "identifiers",
+                positions
+                    .iter()
+                    .map(|position| position.identifier.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
+
+/// Receives the positions migrated by the predecessor of this contract, which must send their LP
+/// tokens along. The positions are kept as they were, their unlocking durations included, so they
+/// carry on in this contract. A position keeps its identifier unless it's taken, in which case it
+/// gets a new one.
+pub(crate) fn receive_positions(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    positions: Vec<Position>,
+) -> Result<Response, ContractError> {
+    let predecessor = PREDECESSOR.may_load(deps.storage)?;
+    ensure!(
+        predecessor.as_ref() == Some(&info.sender),
+        ContractError::Unauthorized
+    );
+
+    // the funds sent must be exactly the LP tokens of the positions
+    let lp_assets = aggregate_coins(
+        positions
+            .iter()
+            .map(|position| position.lp_asset.clone())
+            .collect(),
+    )?;
+    ensure!(
+        lp_assets.len() == info.funds.len()
+            && lp_assets
+                .iter()
+                .all(|lp_asset| info.funds.contains(lp_asset)),
+        ContractError::AssetMismatch
+    );
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut attributes = vec![
+        ("action", "receive_positions".to_string()),
+        ("predecessor", info.sender.to_string()),
+    ];
+
+    for position in positions {
+        validate_lp_denom(&position.lp_asset.denom, config.pool_manager_addr.as_str())?;
+
+        let position_id_counter = POSITION_ID_COUNTER
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+
+        let identifier = if get_position(deps.storage, Some(position.identifier.clone()))?.is_none()
+        {
+            // keep the counter ahead of the automatic identifiers kept, so it doesn't collide
+            // with them later on
+            if let Some(position_id) = position
+                .identifier
+                .strip_prefix(AUTO_POSITION_ID_PREFIX)
+                .and_then(|position_id| position_id.parse::<u64>().ok())
+            {
+                POSITION_ID_COUNTER.save(deps.storage, &position_id_counter.max(position_id))?;
+            }
+
+            position.identifier.clone()
+        } else {
+            let position_id_counter = position_id_counter + 1u64;
+            POSITION_ID_COUNTER.save(deps.storage, &position_id_counter)?;
+
+            let identifier = format!("{AUTO_POSITION_ID_PREFIX}{position_id_counter}");
+            attributes.push((
+                "renamed_position",
+                format!("{}:{identifier}", position.identifier),
+            ));
+
+            identifier
+        };
+
+        let position = Position {
+            identifier: identifier.clone(),
+            ..position
+        };
+
+        POSITIONS.save(deps.storage, &identifier, &position)?;
+
+        if position.open {
+            update_weights(
+                deps.branch(),
+                &env,
+                &position.receiver,
+                &position.lp_asset,
+                position.unlocking_duration,
+                true,
+            )?;
+        }
+
+        attributes.push(("position", position.to_string()));
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
 /// Updates the weights when managing a position. Computes what the weight is gonna be in the next epoch.
 fn update_weights(
     deps: DepsMut,
@@ -2,8 +2,8 @@ use cosmwasm_std::{Coin, Deps, Env};
 
 use mantra_dex_std::coin::aggregate_coins;
 use mantra_dex_std::farm_manager::{
-    Config, EpochId, FarmsBy, FarmsResponse, LpWeightResponse, PositionsBy, PositionsResponse,
-    RewardsResponse,
+    Config, DisabledMessagesResponse, EpochId, FarmsBy, FarmsResponse, LpWeightResponse,
+    PositionsBy, PositionsResponse, RewardsResponse, SuccessionResponse,
 };
 
 use crate::farm::commands::calculate_rewards;
@@ -11,7 +11,7 @@ use crate::helpers::get_unique_lp_asset_denoms_from_positions;
 use crate::state::{
     get_farm_by_identifier, get_farms, get_farms_by_farm_asset, get_farms_by_lp_denom,
     get_position, get_positions, get_positions_by_receiver, CONFIG, LP_WEIGHT_HISTORY,
-    MAX_ITEMS_LIMIT,
+    MAX_ITEMS_LIMIT, PREDECESSOR, SUCCESSOR,
 };
 use crate::ContractError;
 
@@ -150,3 +150,21 @@ pub(crate) fn query_lp_weight(
         epoch_id,
     })
 }
+
+/// Queries the farm managers this one supersedes and is superseded by, if any.
+pub(crate) fn query_succession(deps: Deps) -> Result<SuccessionResponse, ContractError> {
+    Ok(SuccessionResponse {
+        predecessor: PREDECESSOR.may_load(deps.storage)?,
+        successor: SUCCESSOR.may_load(deps.storage)?,
+    })
+}
+
+/// Gets the execute messages disabled by the circuit breaker module of the chain. Returns a
+/// [DisabledMessagesResponse].
+pub(crate) fn query_disabled_messages(
+    deps: Deps,
+) -> Result<DisabledMessagesResponse, ContractError> {
+    Ok(DisabledMessagesResponse {
+        messages: circuit_breaker::get_disabled_messages(deps.storage)?,
+    })
+}
@@ -80,6 +80,12 @@ impl<'a> IndexList<Farm> for FarmIndexes<'a> {
     }
 }
 
+/// The farm manager superseding this one, to which positions can be migrated.
+pub const SUCCESSOR: Item<Addr> = Item::new("successor");
+
+/// The farm manager this one supersedes, from which positions can be migrated.
+pub const PREDECESSOR: Item<Addr> = Item::new("predecessor");
+
 // settings for pagination
 // MAX_ITEMS_LIMIT in the case of positions, is the maximum number of positions that a user can have
 // open or closed at a given time, i.e. there can be at most MAX_ITEMS_LIMIT open positions and
@@ -13,8 +13,9 @@ use crate::common::MOCK_CONTRACT_ADDR_1;
 use mantra_common_testing::multi_test::stargate_mock::StargateMock;
 use mantra_dex_std::epoch_manager::{EpochConfig, EpochResponse};
 use mantra_dex_std::farm_manager::{
-    Config, FarmAction, FarmsBy, FarmsResponse, InstantiateMsg, LpWeightResponse, PositionAction,
-    PositionsResponse, RewardsResponse,
+    Config, DisabledMessagesResponse, FarmAction, FarmsBy, FarmsResponse, InstantiateMsg,
+    LpWeightResponse, PositionAction, PositionsResponse, RewardsResponse, SuccessionResponse,
+    SudoMsg,
 };
 
 type OsmosisTokenFactoryApp = App<
@@ -247,6 +248,31 @@ impl TestingSuite {
         self
     }
 
+    /// Instantiates another farm manager with the default config and returns its address, the
+    /// current one staying in use.
+    #[track_caller]
+    pub(crate) fn instantiate_another_farm_manager(&mut self) -> Addr {
+        let farm_manager_addr = self.farm_manager_addr.clone();
+
+        self.instantiate(
+            self.fee_collector_addr.to_string(),
+            self.epoch_manager_addr.to_string(),
+            self.pool_manager_addr.to_string(),
+            Coin {
+                denom: "uom".to_string(),
+                amount: Uint128::new(1_000u128),
+            },
+            2,
+            14,
+            86_400,
+            31_556_926u64,
+            MONTH_IN_SECONDS,
+            Decimal::percent(10),
+        );
+
+        std::mem::replace(&mut self.farm_manager_addr, farm_manager_addr)
+    }
+
     #[track_caller]
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn instantiate_err(
@@ -396,6 +422,70 @@ impl TestingSuite {
         self
     }
 
+    #[track_caller]
+    pub(crate) fn update_successor(
+        &mut self,
+        sender: &Addr,
+        successor: Option<&Addr>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::farm_manager::ExecuteMsg::UpdateSuccessor {
+            successor: successor.map(|addr| addr.to_string()),
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_predecessor(
+        &mut self,
+        sender: &Addr,
+        predecessor: Option<&Addr>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::farm_manager::ExecuteMsg::UpdatePredecessor {
+            predecessor: predecessor.map(|addr| addr.to_string()),
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn migrate_positions(
+        &mut self,
+        sender: &Addr,
+        receiver: &Addr,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::farm_manager::ExecuteMsg::MigratePositions {
+            receiver: receiver.to_string(),
+            limit: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
     #[track_caller]
     pub(crate) fn claim(
         &mut self,
@@ -414,6 +504,17 @@ impl TestingSuite {
 
         self
     }
+
+    #[track_caller]
+    pub(crate) fn sudo(
+        &mut self,
+        msg: SudoMsg,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.wasm_sudo(self.farm_manager_addr.clone(), &msg));
+
+        self
+    }
 }
 
 /// queries
@@ -530,6 +631,37 @@ impl TestingSuite {
         self
     }
 
+    #[track_caller]
+    pub(crate) fn query_succession(
+        &mut self,
+        result: impl Fn(StdResult<SuccessionResponse>),
+    ) -> &mut Self {
+        let succession_response: StdResult<SuccessionResponse> = self.app.wrap().query_wasm_smart(
+            &self.farm_manager_addr,
+            &mantra_dex_std::farm_manager::QueryMsg::Succession {},
+        );
+
+        result(succession_response);
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn query_disabled_messages(
+        &mut self,
+        result: impl Fn(StdResult<DisabledMessagesResponse>),
+    ) -> &mut Self {
+        let disabled_messages_response: StdResult<DisabledMessagesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.farm_manager_addr,
+                &mantra_dex_std::farm_manager::QueryMsg::DisabledMessages {},
+            );
+
+        result(disabled_messages_response);
+
+        self
+    }
+
     #[track_caller]
     pub(crate) fn query_balance(
         &mut self,
@@ -8,7 +8,8 @@ pub fn farm_manager_contract() -> Box<dyn Contract<Empty>> {
         farm_manager::contract::instantiate,
         farm_manager::contract::query,
     )
-    .with_migrate(farm_manager::contract::migrate);
+    .with_migrate(farm_manager::contract::migrate)
+    .with_sudo(farm_manager::contract::sudo);
 
     Box::new(contract)
 }
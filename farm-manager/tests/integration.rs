@@ -2,6 +2,7 @@ extern crate core;
 
 use std::cell::RefCell;
 
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::{coin, Addr, Coin, Decimal, StdResult, Timestamp, Uint128};
 use cw_utils::PaymentError;
 use farm_manager::state::MAX_ITEMS_LIMIT;
@@ -9,7 +10,7 @@ use farm_manager::ContractError;
 use mantra_dex_std::constants::{LP_SYMBOL, MONTH_IN_SECONDS};
 use mantra_dex_std::farm_manager::{
     Config, Curve, Farm, FarmAction, FarmParams, FarmsBy, LpWeightResponse, Position,
-    PositionAction, PositionsBy, PositionsResponse, RewardsResponse,
+    PositionAction, PositionsBy, PositionsResponse, RewardsResponse, SudoMsg,
 };
 
 use crate::common::suite::TestingSuite;
@@ -8587,3 +8588,255 @@ fn farm_owners_get_penalty_fees() {
             assert_eq!(balance, Uint128::new(1_000_000_000u128 + 25u128));
         });
 }
+
+#[test]
+fn migrates_positions_to_successor() {
+    let lp_denom = format!("factory/{MOCK_CONTRACT_ADDR_1}/{LP_SYMBOL}").to_string();
+    let mut suite = TestingSuite::default_with_balances(vec![
+        coin(1_000_000_000u128, "uom"),
+        coin(1_000_000_000u128, lp_denom.clone()),
+    ]);
+    let creator = suite.creator();
+    let alice = suite.senders[1].clone();
+    let bob = suite.senders[2].clone();
+
+    suite.instantiate_default();
+
+    suite
+        .manage_position(
+            &alice,
+            PositionAction::Create {
+                identifier: Some("alice".to_string()),
+                unlocking_duration: 86_400,
+                receiver: None,
+            },
+            vec![coin(5_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .manage_position(
+            &alice,
+            PositionAction::Create {
+                identifier: None,
+                unlocking_duration: 172_800,
+                receiver: None,
+            },
+            vec![coin(1_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .manage_position(
+            &alice,
+            PositionAction::Close {
+                identifier: "p-1".to_string(),
+                lp_asset: None,
+            },
+            vec![],
+            |result| {
+                result.unwrap();
+            },
+        )
+        // positions can't be migrated without a successor
+        .migrate_positions(&bob, &alice, |result| {
+            let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+            match err {
+                ContractError::NoSuccessor => {}
+                _ => panic!("Wrong error type, should return ContractError::NoSuccessor"),
+            }
+        });
+
+    let predecessor = suite.farm_manager_addr.clone();
+    let successor = suite.instantiate_another_farm_manager();
+
+    // bob takes the first automatic identifier of the successor
+    suite.farm_manager_addr = successor.clone();
+    suite
+        .manage_position(
+            &bob,
+            PositionAction::Create {
+                identifier: None,
+                unlocking_duration: 86_400,
+                receiver: None,
+            },
+            vec![coin(2_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .update_predecessor(&alice, Some(&predecessor), |result| {
+            let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+            match err {
+                ContractError::OwnershipError { .. } => {}
+                _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+            }
+        })
+        .update_predecessor(&creator, Some(&predecessor), |result| {
+            result.unwrap();
+        });
+
+    suite.farm_manager_addr = predecessor.clone();
+    suite
+        .update_successor(&creator, Some(&successor), |result| {
+            result.unwrap();
+        })
+        .query_succession(|result| {
+            let succession = result.unwrap();
+            assert_eq!(succession.predecessor, None);
+            assert_eq!(succession.successor, Some(successor.clone()));
+        })
+        // anyone can migrate the positions of alice
+        .migrate_positions(&bob, &alice, |result| {
+            result.unwrap();
+        })
+        .query_positions(
+            Some(PositionsBy::Receiver(alice.to_string())),
+            None,
+            None,
+            None,
+            |result| {
+                assert!(result.unwrap().positions.is_empty());
+            },
+        )
+        .query_balance(lp_denom.clone(), &predecessor, |balance| {
+            assert_eq!(balance, Uint128::zero());
+        })
+        .migrate_positions(&bob, &alice, |result| {
+            let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+            match err {
+                ContractError::NoPositionsToMigrate { .. } => {}
+                _ => panic!("Wrong error type, should return ContractError::NoPositionsToMigrate"),
+            }
+        });
+
+    // the positions carry on in the successor, the one whose identifier was taken getting a new one
+    suite.farm_manager_addr = successor.clone();
+    suite
+        .query_positions(
+            Some(PositionsBy::Receiver(alice.to_string())),
+            None,
+            None,
+            None,
+            |result| {
+                let positions = result.unwrap().positions;
+                assert_eq!(positions.len(), 2);
+                assert_eq!(positions[0].identifier, "p-2");
+                assert_eq!(positions[0].lp_asset, coin(1_000, lp_denom.clone()));
+                assert_eq!(positions[0].unlocking_duration, 172_800);
+                assert!(!positions[0].open);
+                assert!(positions[0].expiring_at.is_some());
+                assert_eq!(positions[1].identifier, "u-alice");
+                assert_eq!(positions[1].lp_asset, coin(5_000, lp_denom.clone()));
+                assert!(positions[1].open);
+            },
+        )
+        .query_balance(lp_denom.clone(), &successor, |balance| {
+            assert_eq!(balance, Uint128::new(8_000));
+        })
+        .query_succession(|result| {
+            assert_eq!(result.unwrap().predecessor, Some(predecessor.clone()));
+        })
+        // the migrated positions can be managed as usual
+        .manage_position(
+            &alice,
+            PositionAction::Expand {
+                identifier: "u-alice".to_string(),
+            },
+            vec![coin(1_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        );
+}
+
+#[test]
+fn chain_disables_and_enables_messages() {
+    let lp_denom = format!("factory/{MOCK_CONTRACT_ADDR_1}/{LP_SYMBOL}").to_string();
+    let mut suite = TestingSuite::default_with_balances(vec![
+        coin(1_000_000_000u128, "uom"),
+        coin(1_000_000_000u128, lp_denom.clone()),
+    ]);
+    let alice = suite.senders[1].clone();
+
+    suite.instantiate_default();
+
+    suite
+        .sudo(
+            SudoMsg::DisableMessages {
+                messages: vec!["manage_positions".to_string()],
+            },
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::CircuitBreakerError(
+                        CircuitBreakerError::InvalidMessageName { message },
+                    ) => {
+                        assert_eq!(message, "manage_positions");
+                    }
+                    _ => panic!(
+                        "Wrong error type, should return CircuitBreakerError::InvalidMessageName"
+                    ),
+                }
+            },
+        )
+        .sudo(
+            SudoMsg::DisableMessages {
+                messages: vec!["manage_position".to_string()],
+            },
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_disabled_messages(|result| {
+            assert_eq!(
+                result.unwrap().messages,
+                vec!["manage_position".to_string()]
+            );
+        })
+        .manage_position(
+            &alice,
+            PositionAction::Create {
+                identifier: None,
+                unlocking_duration: 86_400,
+                receiver: None,
+            },
+            vec![coin(5_000, lp_denom.clone())],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::CircuitBreakerError(CircuitBreakerError::MessageDisabled {
+                        message,
+                    }) => {
+                        assert_eq!(message, "manage_position");
+                    }
+                    _ => panic!(
+                        "Wrong error type, should return CircuitBreakerError::MessageDisabled"
+                    ),
+                }
+            },
+        )
+        .sudo(
+            SudoMsg::EnableMessages {
+                messages: vec!["manage_position".to_string()],
+            },
+            |result| {
+                result.unwrap();
+            },
+        )
+        .query_disabled_messages(|result| {
+            assert!(result.unwrap().messages.is_empty());
+        })
+        .manage_position(
+            &alice,
+            PositionAction::Create {
+                identifier: None,
+                unlocking_duration: 86_400,
+                receiver: None,
+            },
+            vec![coin(5_000, lp_denom.clone())],
+            |result| {
+                result.unwrap();
+            },
+        );
+}
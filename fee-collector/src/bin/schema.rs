@@ -1,10 +1,11 @@
 use cosmwasm_schema::write_api;
-use mantra_dex_std::fee_collector::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use mantra_dex_std::fee_collector::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
     }
 }
@@ -1,7 +1,10 @@
 use cosmwasm_std::{entry_point, to_json_binary};
 use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 use cw2::set_contract_version;
-use mantra_dex_std::fee_collector::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
+use mantra_dex_std::fee_collector::{
+    DisabledMessagesResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+};
 use mantra_utils::validate_contract;
 
 use crate::error::ContractError;
@@ -32,21 +35,43 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    circuit_breaker::assert_message_enabled(deps.storage, &msg)?;
+
+    let response = match msg {
         ExecuteMsg::UpdateOwnership(action) => {
             cw_utils::nonpayable(&info)?;
-            mantra_utils::ownership::update_ownership(deps, env, info, action).map_err(Into::into)
+            mantra_utils::ownership::update_ownership(deps, env, info, action)
+                .map_err(ContractError::from)
         }
-    }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
 }
 
 #[entry_point]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
+        QueryMsg::DisabledMessages {} => Ok(to_json_binary(&DisabledMessagesResponse {
+            messages: circuit_breaker::get_disabled_messages(deps.storage)?,
+        })?),
     }
 }
 
+#[entry_point]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let response = match msg {
+        SudoMsg::DisableMessages { messages } => {
+            circuit_breaker::disable_messages::<ExecuteMsg>(deps.storage, messages)
+        }
+        SudoMsg::EnableMessages { messages } => {
+            circuit_breaker::enable_messages(deps.storage, messages)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
+}
+
 #[entry_point]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
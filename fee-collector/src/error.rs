@@ -1,3 +1,4 @@
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::StdError;
 use cw_migrate_error_derive::cw_migrate_invalid_version_error;
 use cw_ownable::OwnershipError;
@@ -21,6 +22,9 @@ pub enum ContractError {
     // Handle errors specific to payments from cw-util
     #[error("{0}")]
     PaymentError(#[from] PaymentError),
+
+    #[error("{0}")]
+    CircuitBreakerError(#[from] CircuitBreakerError),
 }
 
 impl From<semver::Error> for ContractError {
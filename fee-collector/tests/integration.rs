@@ -1,7 +1,9 @@
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::{Empty, StdResult};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor, IntoBech32};
+use fee_collector::ContractError;
 use mantra_dex_std::fee_collector::ExecuteMsg::UpdateOwnership;
-use mantra_dex_std::fee_collector::{InstantiateMsg, QueryMsg};
+use mantra_dex_std::fee_collector::{DisabledMessagesResponse, InstantiateMsg, QueryMsg, SudoMsg};
 
 pub fn fee_collector_contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
@@ -9,7 +11,8 @@ pub fn fee_collector_contract() -> Box<dyn Contract<Empty>> {
         fee_collector::contract::instantiate,
         fee_collector::contract::query,
     )
-    .with_migrate(fee_collector::contract::migrate);
+    .with_migrate(fee_collector::contract::migrate)
+    .with_sudo(fee_collector::contract::sudo);
 
     Box::new(contract)
 }
@@ -70,3 +73,89 @@ fn change_contract_ownership() {
 
     assert_eq!(ownership_response.unwrap().owner, Some(alice.to_string()));
 }
+
+#[test]
+fn chain_disables_and_enables_messages() {
+    let mut app = App::default();
+    let code_id = app.store_code(fee_collector_contract());
+    let msg = InstantiateMsg {};
+
+    let admin = "admin".into_bech32();
+    let alice = "alice".into_bech32();
+
+    let fee_collector = app
+        .instantiate_contract(
+            code_id,
+            admin.clone(),
+            &msg,
+            &[],
+            "Fee Collector",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+
+    // names that aren't execute messages are rejected
+    let err = app
+        .wasm_sudo(
+            fee_collector.clone(),
+            &SudoMsg::DisableMessages {
+                messages: vec!["update_owner".to_string()],
+            },
+        )
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap();
+
+    match err {
+        ContractError::CircuitBreakerError(CircuitBreakerError::InvalidMessageName { message }) => {
+            assert_eq!(message, "update_owner")
+        }
+        _ => panic!("Wrong error type, should return CircuitBreakerError::InvalidMessageName"),
+    }
+
+    app.wasm_sudo(
+        fee_collector.clone(),
+        &SudoMsg::DisableMessages {
+            messages: vec!["update_ownership".to_string()],
+        },
+    )
+    .unwrap();
+
+    let disabled_messages_response: StdResult<DisabledMessagesResponse> = app
+        .wrap()
+        .query_wasm_smart(&fee_collector, &QueryMsg::DisabledMessages {});
+
+    assert_eq!(
+        disabled_messages_response.unwrap().messages,
+        vec!["update_ownership".to_string()]
+    );
+
+    let msg = UpdateOwnership(cw_ownable::Action::TransferOwnership {
+        new_owner: alice.to_string(),
+        expiry: None,
+    });
+
+    let err = app
+        .execute_contract(admin.clone(), fee_collector.clone(), &msg, &[])
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap();
+
+    match err {
+        ContractError::CircuitBreakerError(CircuitBreakerError::MessageDisabled { message }) => {
+            assert_eq!(message, "update_ownership")
+        }
+        _ => panic!("Wrong error type, should return CircuitBreakerError::MessageDisabled"),
+    }
+
+    app.wasm_sudo(
+        fee_collector.clone(),
+        &SudoMsg::EnableMessages {
+            messages: vec!["update_ownership".to_string()],
+        },
+    )
+    .unwrap();
+
+    app.execute_contract(admin.clone(), fee_collector.clone(), &msg, &[])
+        .unwrap();
+}
@@ -0,0 +1,214 @@
+use cosmwasm_std::{
+    coin, ensure, entry_point, to_json_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::MinterResponse;
+use cw20_base::allowances::{
+    execute_decrease_allowance, execute_increase_allowance, execute_send_from,
+    execute_transfer_from, query_allowance,
+};
+use cw20_base::contract::{
+    execute_burn, execute_mint, execute_send, execute_transfer, query_balance, query_token_info,
+};
+use cw20_base::enumerable::{query_all_accounts, query_all_allowances};
+use mantra_dex_std::common::{validate_addr_or_default, EVENT_SCHEMA_VERSION};
+use mantra_dex_std::lp_wrapper::{Config, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use mantra_utils::validate_contract;
+
+use crate::error::ContractError;
+use crate::state::CONFIG;
+
+const CONTRACT_NAME: &str = "mantra:lp-wrapper";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[entry_point]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    // the wrapper is the only minter of its token, minting only against locked LP tokens
+    cw20_base::contract::instantiate(
+        deps.branch(),
+        env.clone(),
+        info,
+        cw20_base::msg::InstantiateMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: env.contract.address.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        },
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            lp_denom: msg.lp_denom.clone(),
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "instantiate".to_string()),
+        ("lp_denom", msg.lp_denom),
+    ]))
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let response = match msg {
+        ExecuteMsg::Wrap { receiver } => wrap(deps, env, info, receiver),
+        ExecuteMsg::Unwrap { amount, receiver } => unwrap(deps, env, info, amount, receiver),
+        ExecuteMsg::Transfer { recipient, amount } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_transfer(deps, env, info, recipient, amount)?)
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_send(deps, env, info, contract, amount, msg)?)
+        }
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_increase_allowance(
+                deps, env, info, spender, amount, expires,
+            )?)
+        }
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_decrease_allowance(
+                deps, env, info, spender, amount, expires,
+            )?)
+        }
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_transfer_from(
+                deps, env, info, owner, recipient, amount,
+            )?)
+        }
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => {
+            cw_utils::nonpayable(&info)?;
+            Ok(execute_send_from(
+                deps, env, info, owner, contract, amount, msg,
+            )?)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
+}
+
+/// Locks the LP tokens sent and mints the same amount of the CW20 token to the receiver.
+fn wrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = cw_utils::must_pay(&info, &config.lp_denom)?;
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    let minter = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    execute_mint(deps, env, minter, receiver.to_string(), amount)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "wrap".to_string()),
+        ("sender", info.sender.into_string()),
+        ("receiver", receiver.into_string()),
+        ("amount", amount.to_string()),
+    ]))
+}
+
+/// Burns `amount` of the CW20 token of the sender and releases the same amount of LP tokens to
+/// the receiver.
+fn unwrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    ensure!(!amount.is_zero(), ContractError::InvalidUnwrapAmount);
+
+    let config = CONFIG.load(deps.storage)?;
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    execute_burn(deps, env, info.clone(), amount)?;
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![coin(amount.u128(), config.lp_denom)],
+        })
+        .add_attributes(vec![
+            ("action", "unwrap".to_string()),
+            ("sender", info.sender.into_string()),
+            ("receiver", receiver.into_string()),
+            ("amount", amount.to_string()),
+        ]))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_json_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_json_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+    }
+}
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use cw_migrate_error_derive::cw_migrate_invalid_version_error;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[cw_migrate_invalid_version_error]
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    // Handle errors specific to payments from cw-util
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    // Handle errors from the cw20 token implementation
+    #[error("{0}")]
+    Cw20(#[from] cw20_base::ContractError),
+
+    // Handle Upgrade/Migrate related semver errors
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("Invalid unwrap amount")]
+    InvalidUnwrapAmount,
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -0,0 +1,6 @@
+use cw_storage_plus::Item;
+
+use mantra_dex_std::lp_wrapper::Config;
+
+/// The configuration of the wrapper, i.e. the LP denom it wraps.
+pub const CONFIG: Item<Config> = Item::new("config");
@@ -0,0 +1,180 @@
+use cosmwasm_std::{coin, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, TokenInfoResponse};
+use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor, IntoBech32};
+use mantra_dex_std::lp_wrapper::{Config, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+const LP_DENOM: &str = "factory/mantra1pool/o.whale.uluna.LP";
+
+pub fn lp_wrapper_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        lp_wrapper::contract::execute,
+        lp_wrapper::contract::instantiate,
+        lp_wrapper::contract::query,
+    )
+    .with_migrate(lp_wrapper::contract::migrate);
+
+    Box::new(contract)
+}
+
+fn setup(alice: &Addr) -> (App, Addr) {
+    let mut app = AppBuilder::new().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                alice,
+                vec![coin(1_000_000u128, LP_DENOM), coin(1_000_000u128, "uom")],
+            )
+            .unwrap();
+    });
+
+    let admin = "admin".into_bech32();
+    let code_id = app.store_code(lp_wrapper_contract());
+    let lp_wrapper = app
+        .instantiate_contract(
+            code_id,
+            admin.clone(),
+            &InstantiateMsg {
+                lp_denom: LP_DENOM.to_string(),
+                name: "Wrapped whale-uluna LP".to_string(),
+                symbol: "wLP".to_string(),
+                decimals: 6,
+            },
+            &[],
+            "LP Wrapper",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+
+    (app, lp_wrapper)
+}
+
+fn cw20_balance(app: &App, lp_wrapper: &Addr, address: &Addr) -> Uint128 {
+    let response: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            lp_wrapper,
+            &QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    response.balance
+}
+
+#[test]
+fn wrap_and_unwrap_lp_tokens() {
+    let alice = "alice".into_bech32();
+    let bob = "bob".into_bech32();
+    let (mut app, lp_wrapper) = setup(&alice);
+
+    let config: Config = app
+        .wrap()
+        .query_wasm_smart(&lp_wrapper, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.lp_denom, LP_DENOM);
+
+    // only the LP denom can be wrapped
+    app.execute_contract(
+        alice.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Wrap { receiver: None },
+        &[coin(1_000u128, "uom")],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        alice.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Wrap { receiver: None },
+        &[coin(400_000u128, LP_DENOM)],
+    )
+    .unwrap();
+
+    assert_eq!(
+        cw20_balance(&app, &lp_wrapper, &alice),
+        Uint128::new(400_000)
+    );
+    assert_eq!(
+        app.wrap()
+            .query_balance(&lp_wrapper, LP_DENOM)
+            .unwrap()
+            .amount,
+        Uint128::new(400_000)
+    );
+
+    let token_info: TokenInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&lp_wrapper, &QueryMsg::TokenInfo {})
+        .unwrap();
+    assert_eq!(token_info.total_supply, Uint128::new(400_000));
+    assert_eq!(token_info.decimals, 6);
+
+    // the wrapped token is a regular CW20
+    app.execute_contract(
+        alice.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Transfer {
+            recipient: bob.to_string(),
+            amount: Uint128::new(100_000),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(cw20_balance(&app, &lp_wrapper, &bob), Uint128::new(100_000));
+
+    // can't unwrap more than the balance
+    app.execute_contract(
+        bob.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Unwrap {
+            amount: Uint128::new(100_001),
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        bob.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Unwrap {
+            amount: Uint128::zero(),
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+
+    app.execute_contract(
+        bob.clone(),
+        lp_wrapper.clone(),
+        &ExecuteMsg::Unwrap {
+            amount: Uint128::new(100_000),
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(cw20_balance(&app, &lp_wrapper, &bob), Uint128::zero());
+    assert_eq!(
+        app.wrap().query_balance(&bob, LP_DENOM).unwrap().amount,
+        Uint128::new(100_000)
+    );
+
+    let token_info: TokenInfoResponse = app
+        .wrap()
+        .query_wasm_smart(&lp_wrapper, &QueryMsg::TokenInfo {})
+        .unwrap();
+    assert_eq!(token_info.total_supply, Uint128::new(300_000));
+    assert_eq!(
+        app.wrap()
+            .query_balance(&lp_wrapper, LP_DENOM)
+            .unwrap()
+            .amount,
+        Uint128::new(300_000)
+    );
+}
@@ -0,0 +1,10 @@
+use cosmwasm_schema::write_api;
+use mantra_dex_std::oracle_adapter::{ExecuteMsg, InstantiateMsg, QueryMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+    }
+}
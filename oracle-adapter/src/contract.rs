@@ -0,0 +1,176 @@
+use cosmwasm_std::{
+    ensure, entry_point, to_json_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult,
+};
+use cw2::set_contract_version;
+use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
+use mantra_dex_std::oracle::PriceResponse;
+use mantra_dex_std::oracle_adapter::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, PriceFeed, PriceFeedInfo, PriceFeedsResponse,
+    PriceSource, QueryMsg,
+};
+use mantra_utils::validate_contract;
+
+use crate::error::ContractError;
+use crate::sources::query_feed_price;
+use crate::state::PRICE_FEEDS;
+
+const CONTRACT_NAME: &str = "mantra:oracle-adapter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "instantiate".to_string()),
+        ("owner", info.sender.into_string()),
+    ]))
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let response = match msg {
+        ExecuteMsg::UpdatePriceFeed {
+            base_denom,
+            quote_denom,
+            price_feed,
+        } => update_price_feed(deps, info, base_denom, quote_denom, price_feed),
+        ExecuteMsg::UpdateOwnership(action) => {
+            mantra_utils::ownership::update_ownership(deps, env, info, action).map_err(Into::into)
+        }
+    }?;
+
+    Ok(response.add_attribute("schema_version", EVENT_SCHEMA_VERSION))
+}
+
+/// Sets or removes the price feed of the given pair. Only the owner can do this.
+fn update_price_feed(
+    deps: DepsMut,
+    info: MessageInfo,
+    base_denom: String,
+    quote_denom: String,
+    price_feed: Option<PriceFeed>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![
+        ("action", "update_price_feed".to_string()),
+        ("base_denom", base_denom.clone()),
+        ("quote_denom", quote_denom.clone()),
+    ];
+
+    match price_feed {
+        Some(price_feed) => {
+            let valid_source = match &price_feed.source {
+                PriceSource::Slinky { currency_pair } => currency_pair
+                    .split_once('/')
+                    .map_or(false, |(base, quote)| !base.is_empty() && !quote.is_empty()),
+                PriceSource::Pyth {
+                    contract,
+                    price_feed_id,
+                } => {
+                    deps.api.addr_validate(contract).is_ok()
+                        && pyth_sdk_cw::PriceIdentifier::from_hex(price_feed_id).is_ok()
+                }
+            };
+            ensure!(
+                valid_source
+                    && base_denom != quote_denom
+                    && price_feed.max_staleness > 0
+                    && price_feed.max_confidence < Decimal::one(),
+                ContractError::InvalidPriceFeed
+            );
+
+            attributes.push(("max_staleness", price_feed.max_staleness.to_string()));
+            attributes.push(("max_confidence", price_feed.max_confidence.to_string()));
+            PRICE_FEEDS.save(deps.storage, (&base_denom, &quote_denom), &price_feed)?;
+        }
+        None => {
+            attributes.push(("removed", "true".to_string()));
+            PRICE_FEEDS.remove(deps.storage, (&base_denom, &quote_denom));
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Price {
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&query_price(
+            deps,
+            env,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::PriceFeeds {} => Ok(to_json_binary(&query_price_feeds(deps)?)?),
+        QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
+    }
+}
+
+/// Gets the rate of the quote asset per unit of the base asset, following the oracle interface
+/// of the pool manager. If only the inverse pair has a feed, its rate is inverted.
+fn query_price(
+    deps: Deps,
+    env: Env,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<PriceResponse, ContractError> {
+    if let Some(price_feed) = PRICE_FEEDS.may_load(deps.storage, (&base_denom, &quote_denom))? {
+        return query_feed_price(deps, &env, &price_feed);
+    }
+
+    let price_feed = PRICE_FEEDS
+        .may_load(deps.storage, (&quote_denom, &base_denom))?
+        .ok_or(ContractError::PriceFeedNotFound {
+            base_denom,
+            quote_denom,
+        })?;
+    let price = query_feed_price(deps, &env, &price_feed)?;
+
+    Ok(PriceResponse {
+        rate: price.rate.inv().ok_or(ContractError::InvalidPrice)?,
+        last_updated: price.last_updated,
+    })
+}
+
+/// Gets all the price feeds of the adapter.
+fn query_price_feeds(deps: Deps) -> StdResult<PriceFeedsResponse> {
+    let price_feeds = PRICE_FEEDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((base_denom, quote_denom), price_feed) = item?;
+            Ok(PriceFeedInfo {
+                base_denom,
+                quote_denom,
+                price_feed,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PriceFeedsResponse { price_feeds })
+}
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::default())
+}
@@ -0,0 +1,51 @@
+use cosmwasm_std::{Decimal, OverflowError, StdError};
+use cw_migrate_error_derive::cw_migrate_invalid_version_error;
+use cw_ownable::OwnershipError;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[cw_migrate_invalid_version_error]
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    // Handle errors specific to payments from cw-util
+    #[error("{0}")]
+    PaymentError(#[from] PaymentError),
+
+    // Handle ownership errors from cw-ownable
+    #[error("{0}")]
+    OwnershipError(#[from] OwnershipError),
+
+    // Handle Upgrade/Migrate related semver errors
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("Invalid price feed, it needs a valid source, a non-zero staleness window and a confidence ratio lower than one")]
+    InvalidPriceFeed,
+
+    #[error("There's no price feed for {base_denom}/{quote_denom}")]
+    PriceFeedNotFound {
+        base_denom: String,
+        quote_denom: String,
+    },
+
+    #[error("The price is stale, it was last updated at {last_updated}")]
+    StalePrice { last_updated: u64 },
+
+    #[error("The confidence interval of the price is too wide, it is {confidence} of the price")]
+    PriceConfidenceTooWide { confidence: Decimal },
+
+    #[error("The price reported by the source is not positive")]
+    InvalidPrice,
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -0,0 +1,6 @@
+pub mod contract;
+mod error;
+pub mod sources;
+pub mod state;
+
+pub use crate::error::ContractError;
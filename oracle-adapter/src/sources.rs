@@ -0,0 +1,92 @@
+use cosmwasm_std::{ensure, Decimal, Deps, Env, Uint128};
+use pyth_sdk_cw::{query_price_feed, PriceIdentifier};
+
+use mantra_dex_std::oracle::PriceResponse;
+use mantra_dex_std::oracle_adapter::{PriceFeed, PriceSource};
+use mantra_dex_std::slinky::query_price;
+
+use crate::ContractError;
+
+/// Reads the price of the given feed from its source, as the rate of the quote asset per unit of
+/// the base asset. Fails if the price is older than the staleness window of the feed, or if its
+/// confidence interval is wider than the feed allows.
+pub fn query_feed_price(
+    deps: Deps,
+    env: &Env,
+    price_feed: &PriceFeed,
+) -> Result<PriceResponse, ContractError> {
+    let price = match &price_feed.source {
+        PriceSource::Slinky { currency_pair } => query_slinky_price(deps, currency_pair)?,
+        PriceSource::Pyth {
+            contract,
+            price_feed_id,
+        } => query_pyth_price(deps, contract, price_feed_id, price_feed.max_confidence)?,
+    };
+
+    ensure!(
+        env.block.time.seconds().saturating_sub(price.last_updated) <= price_feed.max_staleness,
+        ContractError::StalePrice {
+            last_updated: price.last_updated,
+        }
+    );
+
+    Ok(price)
+}
+
+/// Reads the price of the given currency pair, e.g. `ATOM/USD`, from the Slinky oracle module of
+/// the chain. Slinky prices are aggregated from the votes of the validators, so they carry no
+/// confidence interval.
+fn query_slinky_price(deps: Deps, currency_pair: &str) -> Result<PriceResponse, ContractError> {
+    let price = query_price(&deps.querier, currency_pair.to_string())?;
+    ensure!(!price.price.is_zero(), ContractError::InvalidPrice);
+
+    Ok(PriceResponse {
+        rate: Decimal::from_atomics(price.price, price.decimals as u32)
+            .map_err(|_| ContractError::InvalidPrice)?,
+        last_updated: price.block_timestamp,
+    })
+}
+
+/// Reads the price of the given feed from the Pyth contract. The price is rejected if its
+/// confidence interval, relative to the price, exceeds `max_confidence`.
+fn query_pyth_price(
+    deps: Deps,
+    contract: &str,
+    price_feed_id: &str,
+    max_confidence: Decimal,
+) -> Result<PriceResponse, ContractError> {
+    let price_feed_id =
+        PriceIdentifier::from_hex(price_feed_id).map_err(|_| ContractError::InvalidPriceFeed)?;
+    let price = query_price_feed(
+        &deps.querier,
+        deps.api.addr_validate(contract)?,
+        price_feed_id,
+    )?
+    .price_feed
+    .get_price_unchecked();
+
+    ensure!(price.price > 0, ContractError::InvalidPrice);
+    let atomics = Uint128::from(price.price as u128);
+
+    let confidence = Decimal::from_ratio(price.conf, atomics);
+    ensure!(
+        confidence <= max_confidence,
+        ContractError::PriceConfidenceTooWide { confidence }
+    );
+
+    // Pyth prices are integers scaled by 10^expo, with a negative expo for fractional prices
+    let rate = if price.expo <= 0 {
+        Decimal::from_atomics(atomics, price.expo.unsigned_abs())
+    } else {
+        Decimal::from_atomics(
+            atomics.checked_mul(Uint128::from(10u128).checked_pow(price.expo as u32)?)?,
+            0,
+        )
+    }
+    .map_err(|_| ContractError::InvalidPrice)?;
+
+    Ok(PriceResponse {
+        rate,
+        last_updated: price.publish_time.max(0) as u64,
+    })
+}
@@ -0,0 +1,6 @@
+use cw_storage_plus::Map;
+
+use mantra_dex_std::oracle_adapter::PriceFeed;
+
+/// The price feeds of the adapter, keyed by the base and quote denoms they price.
+pub const PRICE_FEEDS: Map<(&str, &str), PriceFeed> = Map::new("price_feeds");
@@ -0,0 +1,231 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, IntoBech32};
+use cw_storage_plus::Item;
+use mantra_dex_std::oracle::PriceResponse;
+use mantra_dex_std::oracle_adapter::{
+    ExecuteMsg, InstantiateMsg, PriceFeed, PriceFeedsResponse, PriceSource, QueryMsg,
+};
+use oracle_adapter::ContractError;
+use pyth_sdk_cw::{Price, PriceFeedResponse, PriceIdentifier};
+
+const PRICE_FEED_ID: &str = "63f341689d98a12ef60a5cff1d7f85c70a9e17bf1575f0e7c0b2512d48b1c8b3";
+
+const PYTH_PRICE: Item<Price> = Item::new("price");
+
+/// A Pyth contract reporting the price set by its last execution for any feed.
+pub fn mock_pyth_contract() -> Box<dyn Contract<Empty>> {
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(deps: DepsMut, _: Env, _: MessageInfo, price: Price) -> StdResult<Response> {
+        PYTH_PRICE.save(deps.storage, &price)?;
+        Ok(Response::default())
+    }
+
+    fn query(deps: Deps, _: Env, msg: pyth_sdk_cw::QueryMsg) -> StdResult<Binary> {
+        match msg {
+            pyth_sdk_cw::QueryMsg::PriceFeed { id } => {
+                let price = PYTH_PRICE.load(deps.storage)?;
+                to_json_binary(&PriceFeedResponse {
+                    price_feed: pyth_sdk_cw::PriceFeed::new(id, price, price),
+                })
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn oracle_adapter_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        oracle_adapter::contract::execute,
+        oracle_adapter::contract::instantiate,
+        oracle_adapter::contract::query,
+    )
+    .with_migrate(oracle_adapter::contract::migrate);
+
+    Box::new(contract)
+}
+
+fn setup(admin: &Addr) -> (App, Addr, Addr) {
+    let mut app = App::default();
+
+    let pyth_code_id = app.store_code(mock_pyth_contract());
+    let pyth = app
+        .instantiate_contract(pyth_code_id, admin.clone(), &Empty {}, &[], "Pyth", None)
+        .unwrap();
+
+    let code_id = app.store_code(oracle_adapter_contract());
+    let oracle_adapter = app
+        .instantiate_contract(
+            code_id,
+            admin.clone(),
+            &InstantiateMsg {},
+            &[],
+            "Oracle Adapter",
+            Some(admin.to_string()),
+        )
+        .unwrap();
+
+    (app, pyth, oracle_adapter)
+}
+
+fn set_pyth_price(app: &mut App, admin: &Addr, pyth: &Addr, price: i64, conf: u64, age: u64) {
+    let publish_time = app.block_info().time.seconds() - age;
+    app.execute_contract(
+        admin.clone(),
+        pyth.clone(),
+        &Price {
+            price,
+            conf,
+            expo: -8,
+            publish_time: publish_time as i64,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn query_price(
+    app: &App,
+    oracle_adapter: &Addr,
+    base_denom: &str,
+    quote_denom: &str,
+) -> StdResult<PriceResponse> {
+    app.wrap().query_wasm_smart(
+        oracle_adapter,
+        &QueryMsg::Price {
+            base_denom: base_denom.to_string(),
+            quote_denom: quote_denom.to_string(),
+        },
+    )
+}
+
+#[test]
+fn serves_pyth_prices_within_bounds() {
+    let admin = "admin".into_bech32();
+    let alice = "alice".into_bech32();
+    let (mut app, pyth, oracle_adapter) = setup(&admin);
+
+    let price_feed = PriceFeed {
+        source: PriceSource::Pyth {
+            contract: pyth.to_string(),
+            price_feed_id: PRICE_FEED_ID.to_string(),
+        },
+        max_staleness: 60,
+        max_confidence: Decimal::percent(1),
+    };
+
+    // only the owner can set price feeds
+    let err = app
+        .execute_contract(
+            alice.clone(),
+            oracle_adapter.clone(),
+            &ExecuteMsg::UpdatePriceFeed {
+                base_denom: "ustatom".to_string(),
+                quote_denom: "uatom".to_string(),
+                price_feed: Some(price_feed.clone()),
+            },
+            &[],
+        )
+        .unwrap_err();
+    match err.downcast::<ContractError>().unwrap() {
+        ContractError::OwnershipError { .. } => {}
+        _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+    }
+
+    // the staleness window can't be zero
+    let err = app
+        .execute_contract(
+            admin.clone(),
+            oracle_adapter.clone(),
+            &ExecuteMsg::UpdatePriceFeed {
+                base_denom: "ustatom".to_string(),
+                quote_denom: "uatom".to_string(),
+                price_feed: Some(PriceFeed {
+                    max_staleness: 0,
+                    ..price_feed.clone()
+                }),
+            },
+            &[],
+        )
+        .unwrap_err();
+    match err.downcast::<ContractError>().unwrap() {
+        ContractError::InvalidPriceFeed => {}
+        _ => panic!("Wrong error type, should return ContractError::InvalidPriceFeed"),
+    }
+
+    app.execute_contract(
+        admin.clone(),
+        oracle_adapter.clone(),
+        &ExecuteMsg::UpdatePriceFeed {
+            base_denom: "ustatom".to_string(),
+            quote_denom: "uatom".to_string(),
+            price_feed: Some(price_feed.clone()),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let price_feeds: PriceFeedsResponse = app
+        .wrap()
+        .query_wasm_smart(&oracle_adapter, &QueryMsg::PriceFeeds {})
+        .unwrap();
+    assert_eq!(price_feeds.price_feeds.len(), 1);
+    assert_eq!(price_feeds.price_feeds[0].price_feed, price_feed);
+
+    // 1.25 with a confidence interval of 0.5%
+    set_pyth_price(&mut app, &admin, &pyth, 125_000_000, 625_000, 10);
+
+    let now = app.block_info().time.seconds();
+    let price = query_price(&app, &oracle_adapter, "ustatom", "uatom").unwrap();
+    assert_eq!(price.rate, Decimal::from_ratio(5u128, 4u128));
+    assert_eq!(price.last_updated, now - 10);
+
+    // the inverse pair is served from the same feed
+    let price = query_price(&app, &oracle_adapter, "uatom", "ustatom").unwrap();
+    assert_eq!(price.rate, Decimal::from_ratio(4u128, 5u128));
+
+    assert!(query_price(&app, &oracle_adapter, "ustatom", "uom")
+        .unwrap_err()
+        .to_string()
+        .contains("There's no price feed for ustatom/uom"));
+
+    // prices older than the staleness window are rejected
+    set_pyth_price(&mut app, &admin, &pyth, 125_000_000, 625_000, 61);
+    assert!(query_price(&app, &oracle_adapter, "ustatom", "uatom")
+        .unwrap_err()
+        .to_string()
+        .contains("The price is stale"));
+
+    // and so are prices with a confidence interval too wide
+    set_pyth_price(&mut app, &admin, &pyth, 125_000_000, 2_500_000, 10);
+    assert!(query_price(&app, &oracle_adapter, "ustatom", "uatom")
+        .unwrap_err()
+        .to_string()
+        .contains("The confidence interval of the price is too wide"));
+
+    // removing the feed
+    app.execute_contract(
+        admin.clone(),
+        oracle_adapter.clone(),
+        &ExecuteMsg::UpdatePriceFeed {
+            base_denom: "ustatom".to_string(),
+            quote_denom: "uatom".to_string(),
+            price_feed: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let price_feeds: PriceFeedsResponse = app
+        .wrap()
+        .query_wasm_smart(&oracle_adapter, &QueryMsg::PriceFeeds {})
+        .unwrap();
+    assert!(price_feeds.price_feeds.is_empty());
+}
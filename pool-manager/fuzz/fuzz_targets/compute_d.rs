@@ -0,0 +1,40 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cosmwasm_std::coin;
+use libfuzzer_sys::fuzz_target;
+
+use pool_manager::helpers::compute_d;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    amp: u32,
+    deposits: Vec<u64>,
+    extra_deposit: u64,
+}
+
+fuzz_target!(|input: Input| {
+    let amp = u64::from(input.amp.clamp(1, 1_000_000));
+    if input.deposits.len() < 2 || input.deposits.len() > 5 {
+        return;
+    }
+
+    let deposits = input
+        .deposits
+        .iter()
+        .enumerate()
+        .map(|(i, amount)| coin(u128::from(*amount), format!("denom{i}")))
+        .collect::<Vec<_>>();
+
+    let Some(d) = compute_d(&amp, &deposits) else {
+        return;
+    };
+
+    // depositing more never decreases the invariant
+    let mut larger_deposits = deposits.clone();
+    larger_deposits[0].amount += cosmwasm_std::Uint128::from(input.extra_deposit);
+
+    if let Some(larger_d) = compute_d(&amp, &larger_deposits) {
+        assert!(larger_d >= d);
+    }
+});
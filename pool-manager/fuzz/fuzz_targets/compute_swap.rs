@@ -0,0 +1,100 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use cosmwasm_std::{coin, Decimal, Uint128, Uint256};
+use libfuzzer_sys::fuzz_target;
+
+use mantra_dex_std::fee::{Fee, PoolFee};
+use mantra_dex_std::pool_manager::{PoolInfo, PoolType};
+use pool_manager::helpers::{compute_swap, get_asset_scaling_factors};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    stable: bool,
+    amp: u32,
+    reserves: [u64; 2],
+    decimals: [u8; 2],
+    offer_index: bool,
+    offer_amount: u64,
+    swap_fee_permille: u8,
+    protocol_fee_permille: u8,
+}
+
+fuzz_target!(|input: Input| {
+    let decimals = input.decimals.map(|decimals| decimals % 19);
+    let Ok(asset_scaling_factors) = get_asset_scaling_factors(&decimals) else {
+        return;
+    };
+
+    let pool_type = if input.stable {
+        PoolType::StableSwap {
+            amp: u64::from(input.amp.clamp(1, 1_000_000)),
+        }
+    } else {
+        PoolType::ConstantProduct
+    };
+
+    let pool_fees = PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::permille(u64::from(input.protocol_fee_permille % 200)),
+        },
+        swap_fee: Fee {
+            share: Decimal::permille(u64::from(input.swap_fee_permille % 200)),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        extra_fees: vec![],
+    };
+
+    let pool_info = PoolInfo {
+        pool_identifier: "o.fuzz".to_string(),
+        admin: None,
+        asset_denoms: vec!["uom".to_string(), "uusd".to_string()],
+        pool_type,
+        lp_denom: "factory/pool_manager/o.fuzz.LP".to_string(),
+        asset_decimals: decimals.to_vec(),
+        asset_scaling_factors,
+        pool_fees: pool_fees.clone(),
+        assets: vec![
+            coin(u128::from(input.reserves[0]), "uom"),
+            coin(u128::from(input.reserves[1]), "uusd"),
+        ],
+    };
+
+    let (offer_index, ask_index) = if input.offer_index { (1, 0) } else { (0, 1) };
+    let offer_amount = Uint128::from(input.offer_amount);
+
+    // errors are fine, panics are not
+    let Ok(swap) = compute_swap(
+        &pool_info,
+        offer_index,
+        ask_index,
+        offer_amount,
+        pool_fees,
+        None,
+        None,
+    ) else {
+        return;
+    };
+
+    let ask_pool = pool_info.assets[ask_index].amount;
+    let gross_return_amount = swap.return_amount
+        + swap.swap_fee_amount
+        + swap.protocol_fee_amount
+        + swap.burn_fee_amount
+        + swap.extra_fees_amount;
+
+    // the swap can't pay out more than the pool holds
+    assert!(gross_return_amount <= ask_pool);
+
+    // the constant product never decreases
+    if pool_info.pool_type == PoolType::ConstantProduct {
+        let offer_pool = Uint256::from(pool_info.assets[offer_index].amount);
+        let k = offer_pool * Uint256::from(ask_pool);
+        let new_k = (offer_pool + Uint256::from(offer_amount))
+            * Uint256::from(ask_pool - gross_return_amount);
+
+        assert!(new_k >= k);
+    }
+});
@@ -0,0 +1,181 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, Uint128,
+};
+
+use mantra_dex_std::coin::{aggregate_coins, burn_coin_msg};
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::pool_manager::SwapSpec;
+
+use crate::permissioned::commands::assert_pool_access;
+use crate::state::{get_pool_by_identifier, CONFIG};
+use crate::swap::perform_swap::perform_swap;
+use crate::ContractError;
+
+/// The maximum number of swaps a batch can hold.
+pub const MAX_BATCH_SWAPS: usize = 10;
+
+/// Performs the given independent swaps atomically, in order, each against its own pool. The
+/// funds sent must cover the offer assets of all the swaps, and what's left of them is refunded
+/// to the sender. The outputs of the swaps are sent to the receiver in a single transfer, while
+/// each swap is reported in its own attribute.
+pub fn batch_swap(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    swaps: Vec<SwapSpec>,
+    max_spread: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the swap feature is enabled
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+    ensure!(
+        !swaps.is_empty() && swaps.len() <= MAX_BATCH_SWAPS,
+        ContractError::InvalidBatchSwaps {
+            max: MAX_BATCH_SWAPS
+        }
+    );
+
+    // what's left of the funds sent once each swap took its offer is refunded
+    let mut remaining_funds = aggregate_coins(info.funds.clone())?;
+
+    let mut returned = vec![];
+    let mut fee_messages: Vec<CosmosMsg> = vec![];
+    let mut price_hook_msgs = vec![];
+    let mut recall_messages = vec![];
+    let mut swap_attributes = vec![];
+
+    for swap in swaps {
+        ensure!(
+            swap.offer_asset.denom != swap.ask_asset_denom,
+            ContractError::SameAsset
+        );
+
+        let funds = remaining_funds
+            .iter_mut()
+            .find(|funds| funds.denom == swap.offer_asset.denom)
+            .filter(|funds| funds.amount >= swap.offer_asset.amount)
+            .ok_or(ContractError::MissingNativeSwapFunds {
+                denom: swap.offer_asset.denom.clone(),
+            })?;
+        funds.amount = funds.amount.checked_sub(swap.offer_asset.amount)?;
+
+        // verify that the assets of the swap match the ones from the pool
+        let pool = get_pool_by_identifier(&deps.as_ref(), &swap.pool_identifier)?;
+        ensure!(
+            [&swap.offer_asset.denom, &swap.ask_asset_denom]
+                .iter()
+                .all(|denom| pool.asset_denoms.iter().any(|d| d == *denom)),
+            ContractError::AssetMismatch
+        );
+        assert_pool_access(deps.storage, &env, &swap.pool_identifier, &info.sender)?;
+
+        let swap_result = perform_swap(
+            deps.branch(),
+            &env,
+            &info.sender,
+            swap.offer_asset.clone(),
+            swap.ask_asset_denom.clone(),
+            swap.pool_identifier.clone(),
+            None,
+            swap.max_spread.or(max_spread),
+            None,
+        )?;
+
+        if let Some(minimum_receive) = swap.minimum_receive {
+            ensure!(
+                swap_result.return_asset.amount >= minimum_receive,
+                ContractError::MinimumReceiveAssertion {
+                    minimum_receive,
+                    swap_amount: swap_result.return_asset.amount,
+                }
+            );
+        }
+
+        swap_attributes.push((
+            "swap",
+            format!(
+                "pool={}, in={}, out={}, burn_fee={}, protocol_fee={}, swap_fee={}",
+                swap.pool_identifier,
+                swap.offer_asset,
+                swap_result.return_asset,
+                swap_result.burn_fee_asset,
+                swap_result.protocol_fee_asset,
+                swap_result.swap_fee_asset
+            ),
+        ));
+
+        returned.push(swap_result.return_asset);
+        price_hook_msgs.extend(swap_result.price_hook_msgs);
+        recall_messages.extend(swap_result.recall_msgs);
+
+        if !swap_result.burn_fee_asset.amount.is_zero() {
+            fee_messages.push(burn_coin_msg(swap_result.burn_fee_asset));
+        }
+        if !swap_result.protocol_fee_asset.amount.is_zero() {
+            fee_messages.push(
+                BankMsg::Send {
+                    to_address: config.fee_collector_addr.to_string(),
+                    amount: vec![swap_result.protocol_fee_asset],
+                }
+                .into(),
+            );
+        }
+    }
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    let returned = aggregate_coins(returned)?
+        .into_iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .collect::<Vec<_>>();
+    let refunds = remaining_funds
+        .into_iter()
+        .filter(|funds| !funds.amount.is_zero())
+        .collect::<Vec<_>>();
+
+    let mut messages = vec![];
+    if !returned.is_empty() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: returned.clone(),
+        }));
+    }
+    if !refunds.is_empty() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refunds.clone(),
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(recall_messages)
+        .add_messages(messages)
+        .add_messages(fee_messages)
+        .add_submessages(price_hook_msgs)
+        .add_attributes(vec![
+            ("action", "batch_swap".to_string()),
+            ("sender", info.sender.into_string()),
+            ("receiver", receiver.into_string()),
+            (
+                "returned",
+                returned
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            (
+                "refunded",
+                refunds
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ])
+        .add_attributes(swap_attributes))
+}
@@ -0,0 +1,555 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Coin, CosmosMsg, Decimal256, DepsMut, Env, Int128, MessageInfo,
+    Response, Storage, Uint128,
+};
+
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::pool_manager::{
+    ConcentratedPoolState, ConcentratedPosition, PoolInfo, PoolType,
+};
+
+use crate::concentrated::math::{
+    add_liquidity_delta, get_amounts_for_liquidity, get_liquidity_for_amounts,
+    get_sqrt_price_at_tick, get_tick_at_sqrt_price, to_decimal, MAX_TICK, MIN_TICK,
+};
+use crate::permissioned::commands::assert_pool_access;
+use crate::state::{
+    get_concentrated_position_by_id, get_pool_by_identifier, TickInfo, CONCENTRATED_POOLS,
+    CONCENTRATED_POSITIONS, CONCENTRATED_POSITION_COUNTER, CONFIG, DEPRECATED_POOLS, POOLS, TICKS,
+};
+use crate::twap::accumulate_prices;
+use crate::yield_strategy::commands::recall_uncovered_reserves;
+use crate::ContractError;
+
+/// Provides liquidity to a concentrated liquidity pool within the price range between
+/// `lower_tick` and `upper_tick`, opening a new position for the sender. The funds sent are the
+/// most the position can take, the liquidity they provide at the current price is deposited and
+/// the rest is refunded.
+///
+/// The first provision to the pool sets its price to `initial_price`, the amount of its second
+/// asset a unit of its first asset is worth.
+#[allow(clippy::too_many_arguments)]
+pub fn provide_concentrated_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    lower_tick: i64,
+    upper_tick: i64,
+    initial_price: Option<Decimal256>,
+    min_liquidity: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the deposit feature is enabled
+    ensure!(
+        config.feature_toggle.deposits_enabled,
+        ContractError::OperationDisabled("provide_concentrated_liquidity".to_string())
+    );
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    validate_tick_range(&pool, lower_tick, upper_tick)?;
+
+    assert_pool_access(deps.storage, &env, &pool_identifier, &info.sender)?;
+
+    let deposits = aggregate_coins(info.funds.clone())?;
+    ensure!(!deposits.is_empty(), ContractError::EmptyAssets);
+    ensure!(
+        deposits
+            .iter()
+            .all(|deposit| pool.asset_denoms.contains(&deposit.denom)),
+        ContractError::AssetMismatch
+    );
+    let amounts = get_pool_amounts(&pool, &deposits);
+
+    let mut state = match (
+        CONCENTRATED_POOLS.may_load(deps.storage, &pool_identifier)?,
+        initial_price,
+    ) {
+        (None, Some(initial_price)) if !initial_price.is_zero() => {
+            let sqrt_price = initial_price.sqrt();
+            ConcentratedPoolState {
+                sqrt_price,
+                tick: get_tick_at_sqrt_price(sqrt_price)?,
+                liquidity: Uint128::zero(),
+                fee_growth_global: vec![Decimal256::zero(); pool.assets.len()],
+            }
+        }
+        (Some(state), None) => state,
+        _ => return Err(ContractError::InvalidInitialPrice { pool_identifier }),
+    };
+
+    let sqrt_price_lower = get_sqrt_price_at_tick(lower_tick)?;
+    let sqrt_price_upper = get_sqrt_price_at_tick(upper_tick)?;
+
+    let liquidity = get_liquidity_for_amounts(
+        state.sqrt_price,
+        sqrt_price_lower,
+        sqrt_price_upper,
+        amounts,
+    )?;
+    let min_liquidity = min_liquidity.unwrap_or(Uint128::one()).max(Uint128::one());
+    ensure!(
+        liquidity >= min_liquidity,
+        ContractError::MinLiquidityAssertion {
+            min_liquidity,
+            liquidity,
+        }
+    );
+
+    // the deposit is rounded up in favor of the pool
+    let deposited = get_amounts_for_liquidity(
+        state.sqrt_price,
+        sqrt_price_lower,
+        sqrt_price_upper,
+        liquidity,
+        true,
+    )?;
+
+    update_position_ticks(
+        deps.storage,
+        &pool_identifier,
+        &mut state,
+        lower_tick,
+        upper_tick,
+        Int128::try_from(liquidity)?,
+    )?;
+
+    let position_id = CONCENTRATED_POSITION_COUNTER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1u64;
+    CONCENTRATED_POSITION_COUNTER.save(deps.storage, &position_id)?;
+
+    CONCENTRATED_POSITIONS.save(
+        deps.storage,
+        position_id,
+        &ConcentratedPosition {
+            id: position_id,
+            owner: info.sender.clone(),
+            pool_identifier: pool_identifier.clone(),
+            lower_tick,
+            upper_tick,
+            liquidity,
+            fee_growth_inside_last: get_fee_growth_inside(
+                deps.storage,
+                &pool_identifier,
+                &state,
+                lower_tick,
+                upper_tick,
+            )?,
+            fees_owed: vec![Uint128::zero(); pool.assets.len()],
+        },
+    )?;
+
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+
+    let mut refund = vec![];
+    for (index, pool_asset) in pool.assets.iter_mut().enumerate() {
+        pool_asset.amount = pool_asset.amount.checked_add(deposited[index])?;
+
+        let excess = amounts[index].checked_sub(deposited[index])?;
+        if !excess.is_zero() {
+            refund.push(coin(excess.u128(), &pool_asset.denom));
+        }
+    }
+
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+    CONCENTRATED_POOLS.save(deps.storage, &pool_identifier, &state)?;
+
+    // the bank module expects the coins sorted by denom
+    refund.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !refund.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: refund.clone(),
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "provide_concentrated_liquidity".to_string()),
+            ("sender", info.sender.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("position_id", position_id.to_string()),
+            ("lower_tick", lower_tick.to_string()),
+            ("upper_tick", upper_tick.to_string()),
+            ("liquidity", liquidity.to_string()),
+            ("deposited", join_amounts(&pool, &deposited)),
+            (
+                "refunded",
+                refund
+                    .iter()
+                    .map(|coin| coin.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
+
+/// Withdraws `liquidity` from a concentrated liquidity position of the sender, or all of it if not
+/// given, together with the fees the position earned. Positions withdrawn in full are closed.
+/// Deprecated pools can be exited even when withdrawals are disabled, so their LPs are never
+/// locked in.
+pub fn withdraw_concentrated_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    position_id: u64,
+    liquidity: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut position = get_concentrated_position_by_id(deps.storage, position_id)?;
+    ensure!(position.owner == info.sender, ContractError::Unauthorized);
+
+    let config = CONFIG.load(deps.storage)?;
+    // check if the withdraw feature is enabled, deprecated pools can always be exited
+    ensure!(
+        config.feature_toggle.withdrawals_enabled
+            || DEPRECATED_POOLS.has(deps.storage, &position.pool_identifier),
+        ContractError::OperationDisabled("withdraw_concentrated_liquidity".to_string())
+    );
+
+    let liquidity = liquidity.unwrap_or(position.liquidity);
+    ensure!(
+        !liquidity.is_zero() && liquidity <= position.liquidity,
+        ContractError::InvalidConcentratedLiquidity {
+            liquidity,
+            max: position.liquidity,
+        }
+    );
+
+    let pool_identifier = position.pool_identifier.clone();
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut state = CONCENTRATED_POOLS.load(deps.storage, &pool_identifier)?;
+
+    update_position_fees(deps.storage, &state, &mut position)?;
+
+    update_position_ticks(
+        deps.storage,
+        &pool_identifier,
+        &mut state,
+        position.lower_tick,
+        position.upper_tick,
+        -Int128::try_from(liquidity)?,
+    )?;
+
+    // the withdrawal is rounded down in favor of the pool
+    let withdrawn = get_amounts_for_liquidity(
+        state.sqrt_price,
+        get_sqrt_price_at_tick(position.lower_tick)?,
+        get_sqrt_price_at_tick(position.upper_tick)?,
+        liquidity,
+        false,
+    )?;
+    let fees = std::mem::replace(
+        &mut position.fees_owed,
+        vec![Uint128::zero(); pool.assets.len()],
+    );
+
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+
+    let mut payout = vec![];
+    for (index, pool_asset) in pool.assets.iter_mut().enumerate() {
+        let amount = withdrawn[index].checked_add(fees[index])?;
+        pool_asset.amount = pool_asset.amount.checked_sub(amount)?;
+
+        if !amount.is_zero() {
+            payout.push(coin(amount.u128(), &pool_asset.denom));
+        }
+    }
+
+    position.liquidity = position.liquidity.checked_sub(liquidity)?;
+    if position.liquidity.is_zero() {
+        CONCENTRATED_POSITIONS.remove(deps.storage, position_id)?;
+    } else {
+        CONCENTRATED_POSITIONS.save(deps.storage, position_id, &position)?;
+    }
+
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+    CONCENTRATED_POOLS.save(deps.storage, &pool_identifier, &state)?;
+
+    // recall the deployed reserves the withdrawal pays out before sending them
+    let recall_msgs = recall_uncovered_reserves(deps.storage, &pool)?;
+
+    // the bank module expects the coins sorted by denom
+    payout.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !payout.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: payout,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::default()
+        .add_messages(recall_msgs)
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "withdraw_concentrated_liquidity".to_string()),
+            ("sender", info.sender.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("position_id", position_id.to_string()),
+            ("liquidity", liquidity.to_string()),
+            ("withdrawn", join_amounts(&pool, &withdrawn)),
+            ("fees", join_amounts(&pool, &fees)),
+        ]))
+}
+
+/// Collects the fees a concentrated liquidity position of the sender earned, leaving its liquidity
+/// in the pool.
+pub fn collect_concentrated_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    position_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut position = get_concentrated_position_by_id(deps.storage, position_id)?;
+    ensure!(position.owner == info.sender, ContractError::Unauthorized);
+
+    let pool_identifier = position.pool_identifier.clone();
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let state = CONCENTRATED_POOLS.load(deps.storage, &pool_identifier)?;
+
+    update_position_fees(deps.storage, &state, &mut position)?;
+
+    let fees = std::mem::replace(
+        &mut position.fees_owed,
+        vec![Uint128::zero(); pool.assets.len()],
+    );
+
+    let mut collected = vec![];
+    for (index, pool_asset) in pool.assets.iter_mut().enumerate() {
+        if fees[index].is_zero() {
+            continue;
+        }
+
+        pool_asset.amount = pool_asset.amount.checked_sub(fees[index])?;
+        collected.push(coin(fees[index].u128(), &pool_asset.denom));
+    }
+
+    ensure!(
+        !collected.is_empty(),
+        ContractError::NothingToCollect { position_id }
+    );
+
+    CONCENTRATED_POSITIONS.save(deps.storage, position_id, &position)?;
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+
+    // the bank module expects the coins sorted by denom
+    collected.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+    Ok(Response::default()
+        .add_messages(recall_uncovered_reserves(deps.storage, &pool)?)
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: collected.clone(),
+        })
+        .add_attributes(vec![
+            ("action", "collect_concentrated_fees".to_string()),
+            ("sender", info.sender.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("position_id", position_id.to_string()),
+            (
+                "collected",
+                collected
+                    .iter()
+                    .map(|coin| coin.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
+
+/// Gets the swap fees accrued per unit of liquidity within the range between `lower_tick` and
+/// `upper_tick` of the given pool, for each of its assets. Only the fees accrued while the price
+/// was in range count, i.e. the global fee growth less the growth below and above the range.
+pub(crate) fn get_fee_growth_inside(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    state: &ConcentratedPoolState,
+    lower_tick: i64,
+    upper_tick: i64,
+) -> Result<Vec<Decimal256>, ContractError> {
+    let lower = TICKS.load(storage, (pool_identifier, lower_tick))?;
+    let upper = TICKS.load(storage, (pool_identifier, upper_tick))?;
+
+    Ok(state
+        .fee_growth_global
+        .iter()
+        .enumerate()
+        .map(|(index, global)| {
+            let below = if state.tick >= lower_tick {
+                lower.fee_growth_outside[index]
+            } else {
+                global.saturating_sub(lower.fee_growth_outside[index])
+            };
+            let above = if state.tick < upper_tick {
+                upper.fee_growth_outside[index]
+            } else {
+                global.saturating_sub(upper.fee_growth_outside[index])
+            };
+
+            global.saturating_sub(below).saturating_sub(above)
+        })
+        .collect())
+}
+
+/// Gets the fees the given position earned and hasn't collected yet, for each asset of its pool.
+pub(crate) fn get_position_fees(
+    storage: &dyn Storage,
+    state: &ConcentratedPoolState,
+    position: &ConcentratedPosition,
+) -> Result<Vec<Uint128>, ContractError> {
+    let fee_growth_inside = get_fee_growth_inside(
+        storage,
+        &position.pool_identifier,
+        state,
+        position.lower_tick,
+        position.upper_tick,
+    )?;
+
+    fee_growth_inside
+        .iter()
+        .zip(position.fee_growth_inside_last.iter())
+        .zip(position.fees_owed.iter())
+        .map(|((inside, inside_last), owed)| {
+            // the fees are rounded down in favor of the pool
+            let earned: Uint128 = to_decimal(position.liquidity)
+                .checked_mul(inside.saturating_sub(*inside_last))?
+                .to_uint_floor()
+                .try_into()?;
+
+            Ok(owed.checked_add(earned)?)
+        })
+        .collect()
+}
+
+/// Adds the fees the given position earned since it was last updated to the fees it's owed.
+fn update_position_fees(
+    storage: &dyn Storage,
+    state: &ConcentratedPoolState,
+    position: &mut ConcentratedPosition,
+) -> Result<(), ContractError> {
+    position.fees_owed = get_position_fees(storage, state, position)?;
+    position.fee_growth_inside_last = get_fee_growth_inside(
+        storage,
+        &position.pool_identifier,
+        state,
+        position.lower_tick,
+        position.upper_tick,
+    )?;
+
+    Ok(())
+}
+
+/// Adds `liquidity_delta` to the ticks a position starts and ends at, and to the liquidity of the
+/// pool if the position is in range. Ticks are initialized when liquidity first starts or ends at
+/// them, and removed once none does.
+fn update_position_ticks(
+    storage: &mut dyn Storage,
+    pool_identifier: &str,
+    state: &mut ConcentratedPoolState,
+    lower_tick: i64,
+    upper_tick: i64,
+    liquidity_delta: Int128,
+) -> Result<(), ContractError> {
+    for (tick, net_delta) in [
+        (lower_tick, liquidity_delta),
+        (upper_tick, -liquidity_delta),
+    ] {
+        // the fee growth below the current tick is assumed to have been accrued outside the range
+        let mut tick_info = TICKS
+            .may_load(storage, (pool_identifier, tick))?
+            .unwrap_or_else(|| TickInfo {
+                liquidity_gross: Uint128::zero(),
+                liquidity_net: Int128::zero(),
+                fee_growth_outside: if tick <= state.tick {
+                    state.fee_growth_global.clone()
+                } else {
+                    vec![Decimal256::zero(); state.fee_growth_global.len()]
+                },
+            });
+
+        tick_info.liquidity_gross =
+            add_liquidity_delta(tick_info.liquidity_gross, liquidity_delta)?;
+        tick_info.liquidity_net = tick_info.liquidity_net.checked_add(net_delta)?;
+
+        if tick_info.liquidity_gross.is_zero() {
+            TICKS.remove(storage, (pool_identifier, tick));
+        } else {
+            TICKS.save(storage, (pool_identifier, tick), &tick_info)?;
+        }
+    }
+
+    if (lower_tick..upper_tick).contains(&state.tick) {
+        state.liquidity = add_liquidity_delta(state.liquidity, liquidity_delta)?;
+    }
+
+    Ok(())
+}
+
+/// Validates the range of a position in the given concentrated liquidity pool.
+fn validate_tick_range(
+    pool_info: &PoolInfo,
+    lower_tick: i64,
+    upper_tick: i64,
+) -> Result<(), ContractError> {
+    let PoolType::ConcentratedLiquidity { tick_spacing } = pool_info.pool_type else {
+        return Err(ContractError::NotConcentratedLiquidityPool {
+            pool_identifier: pool_info.pool_identifier.clone(),
+        });
+    };
+
+    let spacing = tick_spacing as i64;
+    ensure!(
+        lower_tick < upper_tick
+            && lower_tick >= MIN_TICK
+            && upper_tick <= MAX_TICK
+            && lower_tick % spacing == 0
+            && upper_tick % spacing == 0,
+        ContractError::InvalidTickRange {
+            lower_tick,
+            upper_tick,
+            tick_spacing,
+        }
+    );
+
+    Ok(())
+}
+
+/// Gets the amount of each asset of the given pool among the given coins, indexed like the pool
+/// assets.
+fn get_pool_amounts(pool_info: &PoolInfo, coins: &[Coin]) -> [Uint128; 2] {
+    let mut amounts = [Uint128::zero(); 2];
+    for (amount, denom) in amounts.iter_mut().zip(pool_info.asset_denoms.iter()) {
+        *amount = coins
+            .iter()
+            .find(|coin| &coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default();
+    }
+
+    amounts
+}
+
+/// Formats the given amounts, indexed like the assets of the given pool, as coins.
+fn join_amounts(pool_info: &PoolInfo, amounts: &[Uint128]) -> String {
+    amounts
+        .iter()
+        .zip(pool_info.asset_denoms.iter())
+        .map(|(amount, denom)| coin(amount.u128(), denom).to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
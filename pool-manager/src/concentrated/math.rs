@@ -0,0 +1,200 @@
+//! The math of the concentrated liquidity pools.
+//!
+//! The price of a concentrated liquidity pool is the amount of its second asset a unit of its first
+//! asset is worth. Prices are discretized into ticks, the price at tick `i` being `1.0001^i`, and
+//! the math works on their square roots. Liquidity provided within a range of ticks is worth:
+//!
+//! - `L * (sqrt(p_upper) - sqrt(p)) / (sqrt(p) * sqrt(p_upper))` of the first asset, and
+//! - `L * (sqrt(p) - sqrt(p_lower))` of the second asset,
+//!
+//! where `sqrt(p)` is the square root of the current price clamped to the range. Amounts are only
+//! converted into units of an asset once the math is done, rounding up what the pool is paid and
+//! rounding down what it pays out.
+
+use cosmwasm_std::{ensure, Decimal256, Int128, Uint128, Uint256};
+
+use crate::ContractError;
+
+/// The lowest tick the range of a position can start at.
+pub const MIN_TICK: i64 = -400_000i64;
+/// The highest tick the range of a position can end at.
+pub const MAX_TICK: i64 = 400_000i64;
+
+/// The square root of the price ratio between two adjacent ticks, i.e. `sqrt(1.0001)`.
+const SQRT_TICK_RATIO: Decimal256 = Decimal256::raw(1_000_049_998_750_062_496u128);
+
+/// Gets the square root of the price at the given tick.
+pub fn get_sqrt_price_at_tick(tick: i64) -> Result<Decimal256, ContractError> {
+    ensure!(
+        (MIN_TICK..=MAX_TICK).contains(&tick),
+        ContractError::TickOutOfBounds { tick }
+    );
+
+    let sqrt_price = SQRT_TICK_RATIO.checked_pow(tick.unsigned_abs() as u32)?;
+
+    if tick < 0 {
+        Ok(Decimal256::one().checked_div(sqrt_price)?)
+    } else {
+        Ok(sqrt_price)
+    }
+}
+
+/// Gets the greatest tick whose square root price isn't above the given one.
+pub fn get_tick_at_sqrt_price(sqrt_price: Decimal256) -> Result<i64, ContractError> {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if get_sqrt_price_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(low)
+}
+
+/// Gets the amount of the first asset moving the square root price between `sqrt_price_a` and
+/// `sqrt_price_b` takes with the given liquidity.
+pub fn get_amount0_delta(
+    sqrt_price_a: Decimal256,
+    sqrt_price_b: Decimal256,
+    liquidity: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    let (lower, upper) = sort(sqrt_price_a, sqrt_price_b);
+
+    // divide twice rather than by the product of the prices, which loses the precision of low prices
+    Ok(liquidity
+        .checked_mul(upper - lower)?
+        .checked_div(upper)?
+        .checked_div(lower)?)
+}
+
+/// Gets the amount of the second asset moving the square root price between `sqrt_price_a` and
+/// `sqrt_price_b` takes with the given liquidity.
+pub fn get_amount1_delta(
+    sqrt_price_a: Decimal256,
+    sqrt_price_b: Decimal256,
+    liquidity: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    let (lower, upper) = sort(sqrt_price_a, sqrt_price_b);
+
+    Ok(liquidity.checked_mul(upper - lower)?)
+}
+
+/// Gets the square root price after `amount` of the first asset is swapped into the given
+/// liquidity, moving the price down. Rounded up, so the price moves less in favor of the pool.
+pub fn get_next_sqrt_price_from_amount0(
+    sqrt_price: Decimal256,
+    liquidity: Decimal256,
+    amount: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    let numerator = liquidity.checked_mul(sqrt_price)?;
+    let denominator = liquidity.checked_add(amount.checked_mul(sqrt_price)?)?;
+
+    let next_sqrt_price = numerator.checked_div(denominator)?;
+    if next_sqrt_price.checked_mul(denominator)? < numerator {
+        Ok(next_sqrt_price.checked_add(Decimal256::raw(1u128))?)
+    } else {
+        Ok(next_sqrt_price)
+    }
+}
+
+/// Gets the square root price after `amount` of the second asset is swapped into the given
+/// liquidity, moving the price up. Rounded down, so the price moves less in favor of the pool.
+pub fn get_next_sqrt_price_from_amount1(
+    sqrt_price: Decimal256,
+    liquidity: Decimal256,
+    amount: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    Ok(sqrt_price.checked_add(amount.checked_div(liquidity)?)?)
+}
+
+/// Gets the amounts of both assets `liquidity` is worth within the range between the given square
+/// root prices, at the current square root price of the pool. Rounded up if `round_up`, i.e. for
+/// deposits, and down otherwise.
+pub fn get_amounts_for_liquidity(
+    sqrt_price: Decimal256,
+    sqrt_price_lower: Decimal256,
+    sqrt_price_upper: Decimal256,
+    liquidity: Uint128,
+    round_up: bool,
+) -> Result<[Uint128; 2], ContractError> {
+    let sqrt_price = sqrt_price.clamp(sqrt_price_lower, sqrt_price_upper);
+    let liquidity = to_decimal(liquidity);
+
+    let amounts = [
+        get_amount0_delta(sqrt_price, sqrt_price_upper, liquidity)?,
+        get_amount1_delta(sqrt_price_lower, sqrt_price, liquidity)?,
+    ];
+
+    let mut units = [Uint128::zero(); 2];
+    for (unit, amount) in units.iter_mut().zip(amounts) {
+        *unit = if round_up {
+            amount.to_uint_ceil()
+        } else {
+            amount.to_uint_floor()
+        }
+        .try_into()?;
+    }
+
+    Ok(units)
+}
+
+/// Gets the most liquidity the given amounts of both assets provide within the range between the
+/// given square root prices, at the current square root price of the pool. Rounded down.
+pub fn get_liquidity_for_amounts(
+    sqrt_price: Decimal256,
+    sqrt_price_lower: Decimal256,
+    sqrt_price_upper: Decimal256,
+    amounts: [Uint128; 2],
+) -> Result<Uint128, ContractError> {
+    let sqrt_price = sqrt_price.clamp(sqrt_price_lower, sqrt_price_upper);
+
+    // the range is only made of the first asset below the price, and of the second one above it
+    let liquidity0 = (sqrt_price < sqrt_price_upper)
+        .then(|| -> Result<Decimal256, ContractError> {
+            Ok(to_decimal(amounts[0])
+                .checked_mul(sqrt_price)?
+                .checked_mul(sqrt_price_upper)?
+                .checked_div(sqrt_price_upper - sqrt_price)?)
+        })
+        .transpose()?;
+    let liquidity1 = (sqrt_price > sqrt_price_lower)
+        .then(|| -> Result<Decimal256, ContractError> {
+            Ok(to_decimal(amounts[1]).checked_div(sqrt_price - sqrt_price_lower)?)
+        })
+        .transpose()?;
+
+    let liquidity = match (liquidity0, liquidity1) {
+        (Some(liquidity0), Some(liquidity1)) => liquidity0.min(liquidity1),
+        (Some(liquidity), None) | (None, Some(liquidity)) => liquidity,
+        (None, None) => Decimal256::zero(),
+    };
+
+    Ok(liquidity.to_uint_floor().try_into()?)
+}
+
+/// Adds a signed liquidity delta, e.g. the net liquidity of a crossed tick, to the given liquidity.
+pub fn add_liquidity_delta(liquidity: Uint128, delta: Int128) -> Result<Uint128, ContractError> {
+    if delta.is_negative() {
+        Ok(liquidity.checked_sub(delta.unsigned_abs())?)
+    } else {
+        Ok(liquidity.checked_add(delta.unsigned_abs())?)
+    }
+}
+
+/// Converts a number of units into a decimal.
+pub fn to_decimal(amount: impl Into<Uint256>) -> Decimal256 {
+    Decimal256::from_ratio(amount.into(), Uint256::one())
+}
+
+/// Sorts the given square root prices in ascending order.
+fn sort(a: Decimal256, b: Decimal256) -> (Decimal256, Decimal256) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod commands;
+pub(crate) mod math;
+pub(crate) mod swap;
@@ -0,0 +1,219 @@
+use cosmwasm_std::{Decimal256, Order, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::Bound;
+
+use mantra_dex_std::pool_manager::{ConcentratedPoolState, PoolFee, PoolInfo};
+
+use crate::concentrated::math::{
+    add_liquidity_delta, get_amount0_delta, get_amount1_delta, get_next_sqrt_price_from_amount0,
+    get_next_sqrt_price_from_amount1, get_sqrt_price_at_tick, get_tick_at_sqrt_price, to_decimal,
+};
+use crate::helpers::{compute_fees, get_swap_computation, is_concentrated, SwapComputation};
+use crate::state::{TickInfo, CONCENTRATED_POOLS, TICKS};
+use crate::ContractError;
+
+/// A swap computed over the ticks of a concentrated liquidity pool, to be applied once performed.
+#[derive(Debug)]
+pub struct ConcentratedSwap {
+    /// The amounts of the swap, as computed for the other pool types.
+    pub swap_computation: SwapComputation,
+    /// The state of the pool after the swap.
+    pub state: ConcentratedPoolState,
+    /// The ticks crossed by the swap, with their fee growth flipped.
+    pub crossed_ticks: Vec<(i64, TickInfo)>,
+}
+
+/// Gets the state of the given concentrated liquidity pool. Returns `None` for pools of any other
+/// type, and fails if no liquidity was ever provided to the pool.
+pub(crate) fn load_concentrated_pool(
+    storage: &dyn Storage,
+    pool_info: &PoolInfo,
+) -> Result<Option<ConcentratedPoolState>, ContractError> {
+    if !is_concentrated(&pool_info.pool_type) {
+        return Ok(None);
+    }
+
+    let state = CONCENTRATED_POOLS
+        .may_load(storage, &pool_info.pool_identifier)?
+        .ok_or_else(|| ContractError::InsufficientConcentratedLiquidity {
+            pool_identifier: pool_info.pool_identifier.clone(),
+        })?;
+
+    Ok(Some(state))
+}
+
+/// Computes a swap of `offer_amount` of the asset at `offer_index` for the asset at `ask_index` of
+/// the given concentrated liquidity pool, from the given state of the pool.
+///
+/// The swap moves the price across the initialized ticks one range at a time, against the
+/// liquidity in range. The swap fee of each range is accrued to the liquidity in it, while the
+/// other fees are taken out of the total return as for the other pool types. The spread is the loss
+/// from the conversion at the price the pool was at.
+pub(crate) fn compute_concentrated_swap(
+    storage: &dyn Storage,
+    pool_info: &PoolInfo,
+    state: &ConcentratedPoolState,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    pool_fees: PoolFee,
+) -> Result<ConcentratedSwap, ContractError> {
+    // offering the first asset moves the price down, offering the second one moves it up
+    let zero_for_one = offer_index == 0;
+    let swap_fee_share = pool_fees.swap_fee.to_decimal_256();
+    let price = state.sqrt_price.checked_mul(state.sqrt_price)?;
+
+    let mut state = state.clone();
+    let mut crossed_ticks = vec![];
+    let mut remaining = to_decimal(offer_amount);
+    let mut return_amount = Decimal256::zero();
+    let mut swap_fee_amount = Decimal256::zero();
+
+    while !remaining.is_zero() {
+        let (next_tick, mut tick_info) = get_next_initialized_tick(
+            storage,
+            &pool_info.pool_identifier,
+            state.tick,
+            zero_for_one,
+        )?
+        .ok_or_else(|| ContractError::InsufficientConcentratedLiquidity {
+            pool_identifier: pool_info.pool_identifier.clone(),
+        })?;
+        let target_sqrt_price = get_sqrt_price_at_tick(next_tick)?;
+        let liquidity = to_decimal(state.liquidity);
+
+        // swap within the current range, up to the next initialized tick
+        let (amount_in, amount_out, next_sqrt_price) = if state.liquidity.is_zero() {
+            (Decimal256::zero(), Decimal256::zero(), target_sqrt_price)
+        } else if zero_for_one {
+            let max_in = get_amount0_delta(target_sqrt_price, state.sqrt_price, liquidity)?;
+            let next_sqrt_price = if remaining >= max_in {
+                target_sqrt_price
+            } else {
+                get_next_sqrt_price_from_amount0(state.sqrt_price, liquidity, remaining)?
+            };
+            (
+                max_in.min(remaining),
+                get_amount1_delta(next_sqrt_price, state.sqrt_price, liquidity)?,
+                next_sqrt_price,
+            )
+        } else {
+            let max_in = get_amount1_delta(state.sqrt_price, target_sqrt_price, liquidity)?;
+            let next_sqrt_price = if remaining >= max_in {
+                target_sqrt_price
+            } else {
+                get_next_sqrt_price_from_amount1(state.sqrt_price, liquidity, remaining)?
+            };
+            (
+                max_in.min(remaining),
+                get_amount0_delta(state.sqrt_price, next_sqrt_price, liquidity)?,
+                next_sqrt_price,
+            )
+        };
+
+        remaining = remaining.checked_sub(amount_in)?;
+        return_amount = return_amount.checked_add(amount_out)?;
+
+        // the swap fee of the range is accrued to the liquidity in it
+        if !state.liquidity.is_zero() {
+            let step_swap_fee = amount_out.checked_mul(swap_fee_share)?;
+            swap_fee_amount = swap_fee_amount.checked_add(step_swap_fee)?;
+            state.fee_growth_global[ask_index] = state.fee_growth_global[ask_index]
+                .checked_add(step_swap_fee.checked_div(liquidity)?)?;
+        }
+
+        state.sqrt_price = next_sqrt_price;
+
+        if next_sqrt_price == target_sqrt_price {
+            // cross the tick, flipping the side its fee growth is accounted on
+            for (outside, global) in tick_info
+                .fee_growth_outside
+                .iter_mut()
+                .zip(state.fee_growth_global.iter())
+            {
+                *outside = global.saturating_sub(*outside);
+            }
+
+            if zero_for_one {
+                state.liquidity = add_liquidity_delta(state.liquidity, -tick_info.liquidity_net)?;
+                state.tick = next_tick - 1;
+            } else {
+                state.liquidity = add_liquidity_delta(state.liquidity, tick_info.liquidity_net)?;
+                state.tick = next_tick;
+            }
+
+            crossed_ticks.push((next_tick, tick_info));
+        } else {
+            state.tick = get_tick_at_sqrt_price(next_sqrt_price)?;
+        }
+    }
+
+    let return_amount: Uint256 = return_amount.to_uint_floor();
+
+    // the spread is the loss from the conversion at the price before the swap
+    let expected_return = if zero_for_one {
+        to_decimal(offer_amount).checked_mul(price)?
+    } else {
+        to_decimal(offer_amount).checked_div(price)?
+    };
+    let spread_amount = expected_return
+        .to_uint_floor()
+        .saturating_sub(return_amount);
+
+    // the swap fee is the one accrued to the liquidity, rounded up so it's always covered
+    let mut fees_computation = compute_fees(pool_fees, return_amount)?;
+    fees_computation.swap_fee_amount = swap_fee_amount.to_uint_ceil();
+
+    Ok(ConcentratedSwap {
+        swap_computation: get_swap_computation(return_amount, spread_amount, fees_computation)?,
+        state,
+        crossed_ticks,
+    })
+}
+
+/// Saves the state a computed swap left the given concentrated liquidity pool in.
+pub(crate) fn apply_concentrated_swap(
+    storage: &mut dyn Storage,
+    pool_identifier: &str,
+    concentrated_swap: &ConcentratedSwap,
+) -> Result<(), ContractError> {
+    CONCENTRATED_POOLS.save(storage, pool_identifier, &concentrated_swap.state)?;
+
+    for (tick, tick_info) in concentrated_swap.crossed_ticks.iter() {
+        TICKS.save(storage, (pool_identifier, *tick), tick_info)?;
+    }
+
+    Ok(())
+}
+
+/// Gets the next initialized tick the price reaches moving from the given tick, i.e. the closest
+/// one at or below it when moving down, or above it when moving up.
+fn get_next_initialized_tick(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    tick: i64,
+    down: bool,
+) -> StdResult<Option<(i64, TickInfo)>> {
+    let ticks = TICKS.prefix(pool_identifier);
+
+    if down {
+        ticks
+            .range(
+                storage,
+                None,
+                Some(Bound::inclusive(tick)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()
+    } else {
+        ticks
+            .range(
+                storage,
+                Some(Bound::exclusive(tick)),
+                None,
+                Order::Ascending,
+            )
+            .next()
+            .transpose()
+    }
+}
@@ -1,26 +1,60 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    coin, entry_point, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdResult, Storage,
+};
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    from_json, wasm_execute, BankMsg, Coin, IbcBasicResponse, IbcSourceCallbackMsg, Reply,
+    StdError, SubMsgResult,
 };
-use cosmwasm_std::{wasm_execute, Reply, StdError};
 use cw2::set_contract_version;
+use cw_utils::parse_reply_instantiate_data;
 
 use mantra_dex_std::pool_manager::{
-    ExecuteMsg, FeatureToggle, InstantiateMsg, MigrateMsg, QueryMsg,
+    ExecuteMsg, FeatureToggle, InstantiateMsg, MigrateMsg, PoolFeeCaps, QueryMsg, SudoMsg,
+    UnlockingDurationBounds,
 };
 use mantra_utils::validate_contract;
+use serde::Deserialize;
 
 use crate::error::ContractError;
-use crate::helpers::validate_asset_balance;
+use crate::events::{with_schema_version, with_schema_version_ibc};
+use crate::failure_injection;
+use crate::gas;
+use crate::helpers::{
+    get_asset_scaling_factors, validate_asset_balance, validate_pool_creation_fees,
+    validate_pool_fee_caps,
+};
+use crate::manager::commands::{
+    DEFAULT_MAX_ASSETS_PER_POOL, DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL, DEFAULT_POOL_FEE_CAPS,
+    DEFAULT_POOL_FEE_UPDATE_DELAY,
+};
 use crate::state::{
-    Config, SingleSideLiquidityProvisionBuffer, CONFIG, POOL_COUNTER,
-    SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER,
+    get_pool_by_identifier, Config, RebalanceBuffer, SingleSideLiquidityProvisionBuffer,
+    UnlockAndWithdrawBuffer, CONFIG, LIQUIDITY_PROVISION_DEPOSITOR, LP_SUPPLY, LP_WRAPPERS,
+    LP_WRAPPER_BUFFER, POOLS, POOL_COUNTER, PROTOCOL_LIQUIDITY, REBALANCE_BUFFER,
+    SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER, UNLOCK_AND_WITHDRAW_BUFFER,
+};
+use crate::swap::commands::DEFAULT_MAX_REFERRAL_FEE_BPS;
+use crate::swap::perform_swap::{DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE};
+use crate::{
+    batch, concentrated, dca, depeg, dust, fee_discount, flash_swap, ibc_denom, ibc_hooks,
+    ibc_transfer, liquidity, lp_wrapper, manager, migration, permissioned, price_breaker,
+    price_hooks, queries, rebalance, rfq, router, skim, split, stop_loss, streaming, swap,
+    swap_adapter, yield_strategy, zap,
 };
-use crate::{liquidity, manager, queries, router, swap};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "mantra:pool-manager";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const SINGLE_SIDE_LIQUIDITY_PROVISION_REPLY_ID: u64 = 1;
+pub const PRICE_HOOK_REPLY_ID: u64 = 2;
+pub const REBALANCE_REPLY_ID: u64 = 3;
+pub const LP_WRAPPER_REPLY_ID: u64 = 4;
+pub const UNLOCK_AND_WITHDRAW_REPLY_ID: u64 = 5;
+pub const FLASH_SWAP_REPLY_ID: u64 = 6;
+pub const ADAPTER_SWAP_REPLY_ID: u64 = 7;
 
 #[entry_point]
 pub fn instantiate(
@@ -30,15 +64,27 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    validate_pool_fee_caps(&msg.pool_fee_caps)?;
+    validate_pool_creation_fees(&msg.pool_creation_fees)?;
+
     let config: Config = Config {
         fee_collector_addr: deps.api.addr_validate(&msg.fee_collector_addr)?,
         farm_manager_addr: deps.api.addr_validate(&msg.farm_manager_addr)?,
-        pool_creation_fee: msg.pool_creation_fee.clone(),
+        pool_creation_fees: msg.pool_creation_fees.clone(),
         feature_toggle: FeatureToggle {
             withdrawals_enabled: true,
             deposits_enabled: true,
             swaps_enabled: true,
         },
+        pool_fee_caps: msg.pool_fee_caps.clone(),
+        default_max_spread: Decimal::from_str(DEFAULT_SLIPPAGE)?,
+        max_spread_cap: Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?,
+        unlocking_duration_bounds: None,
+        max_assets_per_pool: DEFAULT_MAX_ASSETS_PER_POOL,
+        max_assets_per_stableswap_pool: DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL,
+        permissionless_pool_creation: true,
+        pool_fee_update_delay: DEFAULT_POOL_FEE_UPDATE_DELAY,
+        max_referral_fee_bps: DEFAULT_MAX_REFERRAL_FEE_BPS,
     };
     CONFIG.save(deps.storage, &config)?;
     // initialize pool counter
@@ -50,43 +96,164 @@ pub fn instantiate(
         ("owner", info.sender.to_string()),
         ("fee_collector_addr", msg.fee_collector_addr),
         ("farm_manager_addr", msg.farm_manager_addr),
-        ("pool_creation_fee", msg.pool_creation_fee.to_string()),
+        (
+            "pool_creation_fees",
+            msg.pool_creation_fees
+                .iter()
+                .map(|fee| fee.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        (
+            "max_total_pool_fee",
+            msg.pool_fee_caps.max_total_fee.to_string(),
+        ),
     ]))
 }
 
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
-    match msg.id {
+    failure_injection::load_injected_failures(deps.storage)?;
+    failure_injection::assert_reply_not_failed(msg.id)?;
+
+    let response = match msg.id {
         SINGLE_SIDE_LIQUIDITY_PROVISION_REPLY_ID => {
             let SingleSideLiquidityProvisionBuffer {
                 receiver,
+                depositor,
+                deposit,
                 expected_offer_asset_balance_in_contract,
                 expected_ask_asset_balance_in_contract,
                 offer_asset_half,
                 expected_ask_asset,
                 liquidity_provision_data,
             } = SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER.load(deps.storage)?;
+            SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER.remove(deps.storage);
 
-            validate_asset_balance(&deps, &env, &expected_offer_asset_balance_in_contract)?;
-            validate_asset_balance(&deps, &env, &expected_ask_asset_balance_in_contract)?;
+            match msg.result {
+                SubMsgResult::Ok(_) => {
+                    validate_asset_balance(&deps, &env, &expected_offer_asset_balance_in_contract)?;
+                    validate_asset_balance(&deps, &env, &expected_ask_asset_balance_in_contract)?;
 
-            SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER.remove(deps.storage);
+                    LIQUIDITY_PROVISION_DEPOSITOR.save(deps.storage, &depositor)?;
+                    Ok(Response::default().add_message(wasm_execute(
+                        env.contract.address.into_string(),
+                        &ExecuteMsg::ProvideLiquidity {
+                            slippage_tolerance: liquidity_provision_data.slippage_tolerance,
+                            max_spread: liquidity_provision_data.max_spread,
+                            receiver: Some(receiver),
+                            pool_identifier: liquidity_provision_data.pool_identifier,
+                            unlocking_duration: liquidity_provision_data.unlocking_duration,
+                            lock_position_identifier: liquidity_provision_data
+                                .lock_position_identifier,
+                            min_lp_to_receive: liquidity_provision_data.min_lp_to_receive,
+                            deposit_route: None,
+                        },
+                        vec![offer_asset_half, expected_ask_asset],
+                    )?))
+                }
+                // the swap was reverted, so the whole deposit is still held by the contract
+                SubMsgResult::Err(error) => Ok(Response::default()
+                    .add_message(BankMsg::Send {
+                        to_address: depositor.to_string(),
+                        amount: vec![deposit.clone()],
+                    })
+                    .add_attributes(vec![
+                        (
+                            "action",
+                            "single_side_liquidity_provision_failed".to_string(),
+                        ),
+                        ("pool_identifier", liquidity_provision_data.pool_identifier),
+                        ("depositor", depositor.into_string()),
+                        ("refund", deposit.to_string()),
+                        ("error", error),
+                    ])),
+            }
+        }
+        // price hooks only reply on error, which is swallowed so the swap goes through
+        PRICE_HOOK_REPLY_ID => Ok(Response::default().add_attributes(vec![
+            ("action", "price_hook_failed".to_string()),
+            ("error", msg.result.unwrap_err()),
+        ])),
+        REBALANCE_REPLY_ID => {
+            let RebalanceBuffer {
+                pool_identifier,
+                lp_balance_before,
+            } = REBALANCE_BUFFER.load(deps.storage)?;
+            REBALANCE_BUFFER.remove(deps.storage);
+
+            // credit the LP shares minted by the liquidity provision to the protocol
+            let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+            let minted_share = deps
+                .querier
+                .query_balance(&env.contract.address, &pool.lp_denom)?
+                .amount
+                .checked_sub(lp_balance_before)?;
+
+            PROTOCOL_LIQUIDITY.update(
+                deps.storage,
+                &pool_identifier,
+                |balance| -> Result<_, ContractError> {
+                    Ok(balance.unwrap_or_default().checked_add(minted_share)?)
+                },
+            )?;
+
+            Ok(Response::default().add_attributes(vec![
+                ("action", "rebalance_stable_pools_reply".to_string()),
+                ("pool_identifier", pool_identifier),
+                ("minted_share", minted_share.to_string()),
+            ]))
+        }
+        LP_WRAPPER_REPLY_ID => {
+            let pool_identifier = LP_WRAPPER_BUFFER.load(deps.storage)?;
+            LP_WRAPPER_BUFFER.remove(deps.storage);
+
+            let lp_wrapper = deps
+                .api
+                .addr_validate(&parse_reply_instantiate_data(msg)?.contract_address)?;
+            LP_WRAPPERS.save(deps.storage, &pool_identifier, &lp_wrapper)?;
+
+            Ok(Response::default().add_attributes(vec![
+                ("action", "create_lp_wrapper_reply".to_string()),
+                ("pool_identifier", pool_identifier),
+                ("lp_wrapper", lp_wrapper.into_string()),
+            ]))
+        }
+        UNLOCK_AND_WITHDRAW_REPLY_ID => {
+            let UnlockAndWithdrawBuffer {
+                receiver,
+                pool_identifier,
+                lp_balance_before,
+            } = UNLOCK_AND_WITHDRAW_BUFFER.load(deps.storage)?;
+            UNLOCK_AND_WITHDRAW_BUFFER.remove(deps.storage);
+
+            // withdraw the liquidity of the LP tokens the farm manager sent back on behalf of the
+            // position owner
+            let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+            let lp_amount = deps
+                .querier
+                .query_balance(&env.contract.address, &pool.lp_denom)?
+                .amount
+                .checked_sub(lp_balance_before)?;
 
-            Ok(Response::default().add_message(wasm_execute(
-                env.contract.address.into_string(),
-                &ExecuteMsg::ProvideLiquidity {
-                    slippage_tolerance: liquidity_provision_data.slippage_tolerance,
-                    max_spread: liquidity_provision_data.max_spread,
-                    receiver: Some(receiver),
-                    pool_identifier: liquidity_provision_data.pool_identifier,
-                    unlocking_duration: liquidity_provision_data.unlocking_duration,
-                    lock_position_identifier: liquidity_provision_data.lock_position_identifier,
+            liquidity::commands::withdraw_liquidity(
+                deps,
+                env,
+                MessageInfo {
+                    sender: receiver,
+                    funds: vec![coin(lp_amount.u128(), pool.lp_denom)],
                 },
-                vec![offer_asset_half, expected_ask_asset],
-            )?))
+                pool_identifier,
+                None,
+                None,
+            )
         }
+        FLASH_SWAP_REPLY_ID => flash_swap::commands::settle_flash_swap(deps, env),
+        ADAPTER_SWAP_REPLY_ID => swap_adapter::commands::settle_adapter_swap(deps, env),
         _ => Err(StdError::generic_err("reply id not found").into()),
-    }
+    }?;
+
+    Ok(with_schema_version(response))
 }
 
 #[entry_point]
@@ -96,13 +263,22 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    // the circuit breaker module of the chain can disable messages during incidents
+    circuit_breaker::assert_message_enabled(deps.storage, &msg)?;
+    flash_swap::commands::assert_no_flash_swap_in_progress(deps.storage)?;
+    failure_injection::load_injected_failures(deps.storage)?;
+    gas::reset_checkpoints();
+    let contract_addr = env.contract.address.clone();
+
+    let response = match msg {
         ExecuteMsg::CreatePool {
             asset_denoms,
             asset_decimals,
             pool_fees,
             pool_type,
             pool_identifier,
+            canonical_identifier,
+            metadata,
         } => manager::commands::create_pool(
             deps,
             env,
@@ -112,7 +288,38 @@ pub fn execute(
             pool_fees,
             pool_type,
             pool_identifier,
+            canonical_identifier,
+            metadata,
+        ),
+        ExecuteMsg::CreatePoolAndProvideLiquidity {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            canonical_identifier,
+            metadata,
+            liquidity,
+            receiver,
+            min_lp_to_receive,
+        } => manager::create_pool_and_provide_liquidity(
+            deps,
+            env,
+            info,
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            canonical_identifier,
+            metadata,
+            liquidity,
+            receiver,
+            min_lp_to_receive,
         ),
+        ExecuteMsg::CreatePools { pools } => {
+            manager::commands::create_pools(deps, env, info, pools)
+        }
         ExecuteMsg::ProvideLiquidity {
             max_spread,
             slippage_tolerance,
@@ -120,6 +327,8 @@ pub fn execute(
             pool_identifier,
             unlocking_duration,
             lock_position_identifier,
+            min_lp_to_receive,
+            deposit_route,
         } => liquidity::commands::provide_liquidity(
             deps,
             env,
@@ -130,6 +339,8 @@ pub fn execute(
             pool_identifier,
             unlocking_duration,
             lock_position_identifier,
+            min_lp_to_receive,
+            deposit_route,
         ),
         ExecuteMsg::Swap {
             ask_asset_denom,
@@ -137,8 +348,12 @@ pub fn execute(
             max_spread,
             receiver,
             pool_identifier,
+            fee_payer,
+            referral,
+            min_receive,
         } => swap::commands::swap(
             deps,
+            env,
             info.clone(),
             info.sender,
             ask_asset_denom,
@@ -146,10 +361,40 @@ pub fn execute(
             max_spread,
             receiver,
             pool_identifier,
+            fee_payer,
+            referral,
+            min_receive,
         ),
-        ExecuteMsg::WithdrawLiquidity { pool_identifier } => {
-            liquidity::commands::withdraw_liquidity(deps, env, info, pool_identifier)
+        ExecuteMsg::SwapExactOut {
+            ask_asset,
+            max_spread,
+            receiver,
+            pool_identifier,
+        } => swap::commands::swap_exact_out(
+            deps,
+            env,
+            info,
+            ask_asset,
+            max_spread,
+            receiver,
+            pool_identifier,
+        ),
+        ExecuteMsg::WithdrawLiquidity {
+            pool_identifier,
+            remote_receiver,
+            min_amounts_out,
+        } => liquidity::commands::withdraw_liquidity(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            remote_receiver,
+            min_amounts_out,
+        ),
+        ExecuteMsg::Donate { pool_identifier } => {
+            liquidity::commands::donate(deps, env, info, pool_identifier)
         }
+        ExecuteMsg::Skim { denom } => skim::commands::skim(deps, env, info, denom),
         ExecuteMsg::UpdateOwnership(action) => {
             mantra_utils::ownership::update_ownership(deps, env, info, action).map_err(Into::into)
         }
@@ -158,32 +403,633 @@ pub fn execute(
             minimum_receive,
             receiver,
             max_spread,
+            ask_amount,
+            referral,
+            hop_limits,
         } => router::commands::execute_swap_operations(
             deps,
+            env,
             info,
             operations,
             minimum_receive,
             receiver,
             max_spread,
+            ask_amount,
+            referral,
+            hop_limits,
         ),
         ExecuteMsg::UpdateConfig {
             fee_collector_addr,
             farm_manager_addr,
-            pool_creation_fee,
+            pool_creation_fees,
             feature_toggle,
+            pool_fee_caps,
+            default_max_spread,
+            max_spread_cap,
+            max_assets_per_pool,
+            max_assets_per_stableswap_pool,
+            permissionless_pool_creation,
+            pool_fee_update_delay,
+            max_referral_fee_bps,
         } => manager::update_config(
             deps,
             info,
             fee_collector_addr,
             farm_manager_addr,
-            pool_creation_fee,
+            pool_creation_fees,
             feature_toggle,
+            pool_fee_caps,
+            default_max_spread,
+            max_spread_cap,
+            max_assets_per_pool,
+            max_assets_per_stableswap_pool,
+            permissionless_pool_creation,
+            pool_fee_update_delay,
+            max_referral_fee_bps,
         ),
-    }
+        ExecuteMsg::PlaceStopLossOrder {
+            operations,
+            trigger_price,
+            minimum_receive,
+            max_spread,
+            bounty,
+            twap_window,
+        } => stop_loss::commands::place_stop_loss_order(
+            deps,
+            info,
+            operations,
+            trigger_price,
+            minimum_receive,
+            max_spread,
+            bounty,
+            twap_window,
+        ),
+        ExecuteMsg::CancelStopLossOrder { order_id } => {
+            stop_loss::commands::cancel_stop_loss_order(deps, info, order_id)
+        }
+        ExecuteMsg::ExecuteStopLossOrder { order_id } => {
+            stop_loss::commands::execute_stop_loss_order(deps, env, info, order_id)
+        }
+        ExecuteMsg::SubscribePriceThreshold {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            threshold,
+            callback,
+        } => price_hooks::commands::subscribe_price_threshold(
+            deps,
+            info,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            threshold,
+            callback,
+        ),
+        ExecuteMsg::UnsubscribePriceThreshold { subscription_id } => {
+            price_hooks::commands::unsubscribe_price_threshold(deps, info, subscription_id)
+        }
+        ExecuteMsg::CreateDcaSchedule {
+            operations,
+            amount_per_swap,
+            min_receive_per_swap,
+            interval,
+            max_spread,
+        } => dca::commands::create_dca_schedule(
+            deps,
+            env,
+            info,
+            operations,
+            amount_per_swap,
+            min_receive_per_swap,
+            interval,
+            max_spread,
+        ),
+        ExecuteMsg::ExecuteDcaSchedule { schedule_id } => {
+            dca::commands::execute_dca_schedule(deps, env, info, schedule_id)
+        }
+        ExecuteMsg::WithdrawDcaBudget {
+            schedule_id,
+            amount,
+        } => dca::commands::withdraw_dca_budget(deps, info, schedule_id, amount),
+        ExecuteMsg::CreateLiquidityStream {
+            pool_identifier,
+            tranches,
+            min_lp_per_tranche,
+            interval,
+            slippage_tolerance,
+        } => streaming::commands::create_liquidity_stream(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            tranches,
+            min_lp_per_tranche,
+            interval,
+            slippage_tolerance,
+        ),
+        ExecuteMsg::ExecuteLiquidityStream { stream_id } => {
+            streaming::commands::execute_liquidity_stream(deps, env, info, stream_id)
+        }
+        ExecuteMsg::CancelLiquidityStream { stream_id } => {
+            streaming::commands::cancel_liquidity_stream(deps, info, stream_id)
+        }
+        ExecuteMsg::UpdateIbcWithdrawalChannels { channels } => {
+            ibc_transfer::commands::update_ibc_withdrawal_channels(deps, info, channels)
+        }
+        ExecuteMsg::BatchSwap {
+            swaps,
+            max_spread,
+            receiver,
+        } => batch::commands::batch_swap(deps, env, info, swaps, max_spread, receiver),
+        ExecuteMsg::SplitSwap {
+            ask_asset_denom,
+            pool_identifiers,
+            weights,
+            minimum_receive,
+            max_spread,
+            receiver,
+        } => split::commands::split_swap(
+            deps,
+            env,
+            info,
+            ask_asset_denom,
+            pool_identifiers,
+            weights,
+            minimum_receive,
+            max_spread,
+            receiver,
+        ),
+        ExecuteMsg::RegisterRfqMaker { pubkey } => {
+            rfq::commands::register_rfq_maker(deps, info, pubkey)
+        }
+        ExecuteMsg::DepositRfqInventory {} => rfq::commands::deposit_rfq_inventory(deps, info),
+        ExecuteMsg::WithdrawRfqInventory { assets } => {
+            rfq::commands::withdraw_rfq_inventory(deps, info, assets)
+        }
+        ExecuteMsg::FillRfqQuote {
+            quote,
+            signature,
+            fallback_pool_identifier,
+            max_spread,
+        } => rfq::commands::fill_rfq_quote(
+            deps,
+            env,
+            info,
+            quote,
+            signature,
+            fallback_pool_identifier,
+            max_spread,
+        ),
+        ExecuteMsg::AddSwapRoutes { swap_routes } => {
+            router::commands::add_swap_routes(deps, info, swap_routes)
+        }
+        ExecuteMsg::UpdateSwapAdapters { add, remove } => {
+            swap_adapter::commands::update_swap_adapters(deps, info, add, remove)
+        }
+        ExecuteMsg::SwapThroughAdapter {
+            adapter_addr,
+            offer_asset,
+            ask_denom,
+            min_return,
+            msg,
+            receiver,
+        } => swap_adapter::commands::swap_through_adapter(
+            deps,
+            env,
+            info,
+            adapter_addr,
+            offer_asset,
+            ask_denom,
+            min_return,
+            msg,
+            receiver,
+        ),
+        ExecuteMsg::SwapViaRegistry {
+            ask_asset_denom,
+            minimum_receive,
+            receiver,
+            max_spread,
+        } => router::commands::swap_via_registry(
+            deps,
+            env,
+            info,
+            ask_asset_denom,
+            minimum_receive,
+            receiver,
+            max_spread,
+        ),
+        ExecuteMsg::RemoveSwapRoutes { swap_routes } => {
+            router::commands::remove_swap_routes(deps, info, swap_routes)
+        }
+        ExecuteMsg::UpdateRebalanceConfig {
+            keepers,
+            max_move_share,
+            min_deviation_gap,
+        } => rebalance::commands::update_rebalance_config(
+            deps,
+            info,
+            keepers,
+            max_move_share,
+            min_deviation_gap,
+        ),
+        ExecuteMsg::DepositProtocolLiquidity { pool_identifier } => {
+            rebalance::commands::deposit_protocol_liquidity(deps, info, pool_identifier)
+        }
+        ExecuteMsg::WithdrawProtocolLiquidity {
+            pool_identifier,
+            amount,
+            receiver,
+        } => rebalance::commands::withdraw_protocol_liquidity(
+            deps,
+            info,
+            pool_identifier,
+            amount,
+            receiver,
+        ),
+        ExecuteMsg::RebalanceStablePools {
+            from_pool_identifier,
+            to_pool_identifier,
+            lp_amount,
+        } => rebalance::commands::rebalance_stable_pools(
+            deps,
+            env,
+            info,
+            from_pool_identifier,
+            to_pool_identifier,
+            lp_amount,
+        ),
+        ExecuteMsg::CreateLpWrapper {
+            pool_identifier,
+            code_id,
+            name,
+            symbol,
+        } => lp_wrapper::commands::create_lp_wrapper(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            code_id,
+            name,
+            symbol,
+        ),
+        ExecuteMsg::CreateMigrationCampaign {
+            from_pool_identifier,
+            to_pool_identifier,
+            deadline,
+        } => migration::commands::create_migration_campaign(
+            deps,
+            env,
+            info,
+            from_pool_identifier,
+            to_pool_identifier,
+            deadline,
+        ),
+        ExecuteMsg::CancelMigrationCampaign {
+            from_pool_identifier,
+        } => migration::commands::cancel_migration_campaign(deps, info, from_pool_identifier),
+        ExecuteMsg::MoveLiquidity {
+            from_pool_identifier,
+            to_pool_identifier,
+            slippage_tolerance,
+        } => migration::commands::move_liquidity(
+            deps,
+            env,
+            info,
+            from_pool_identifier,
+            to_pool_identifier,
+            slippage_tolerance,
+        ),
+        ExecuteMsg::ClaimMigrationRewards {
+            from_pool_identifier,
+        } => migration::commands::claim_migration_rewards(deps, env, info, from_pool_identifier),
+        ExecuteMsg::UnlockAndWithdrawLiquidity {
+            pool_identifier,
+            position_identifier,
+        } => liquidity::commands::unlock_and_withdraw_liquidity(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            position_identifier,
+        ),
+        ExecuteMsg::UpdateDepegProtection {
+            pool_identifier,
+            config,
+        } => depeg::commands::update_depeg_protection(deps, info, pool_identifier, config),
+        ExecuteMsg::UpdatePriceBreaker {
+            pool_identifier,
+            config,
+        } => price_breaker::commands::update_price_breaker(deps, info, pool_identifier, config),
+        ExecuteMsg::TripPriceBreaker { pool_identifier } => {
+            price_breaker::commands::trip_price_breaker(deps, env, info, pool_identifier)
+        }
+        ExecuteMsg::ResetPriceBreaker { pool_identifier } => {
+            price_breaker::commands::reset_price_breaker(deps, info, pool_identifier)
+        }
+        ExecuteMsg::UpdateFeeDiscounts { config } => {
+            fee_discount::commands::update_fee_discounts(deps, info, config)
+        }
+        ExecuteMsg::UpdateDustTracking { enabled } => {
+            dust::commands::update_dust_tracking(deps, info, enabled)
+        }
+        ExecuteMsg::SweepDust { pool_identifier } => {
+            dust::commands::sweep_dust(deps, env, info, pool_identifier)
+        }
+        ExecuteMsg::UpdateImbalanceFee {
+            pool_identifier,
+            imbalance_fee,
+        } => manager::update_imbalance_fee(deps, info, pool_identifier, imbalance_fee),
+        ExecuteMsg::UpdateReserveFloors {
+            pool_identifier,
+            floors,
+        } => manager::update_reserve_floors(deps, info, pool_identifier, floors),
+        ExecuteMsg::UpdateRateProviders {
+            pool_identifier,
+            rate_providers,
+        } => manager::update_rate_providers(deps, info, pool_identifier, rate_providers),
+        ExecuteMsg::UpdateMinLpLock {
+            pool_identifier,
+            min_unlocking_duration,
+        } => manager::update_min_lp_lock(deps, info, pool_identifier, min_unlocking_duration),
+        ExecuteMsg::UpdateUnlockingDurationBounds {
+            pool_identifier,
+            bounds,
+        } => manager::update_unlocking_duration_bounds(deps, info, pool_identifier, bounds),
+        ExecuteMsg::UpdatePoolMetadata {
+            pool_identifier,
+            metadata,
+        } => manager::update_pool_metadata(deps, info, pool_identifier, metadata),
+        ExecuteMsg::DeprecatePool { pool_identifier } => {
+            manager::deprecate_pool(deps, env, info, pool_identifier)
+        }
+        ExecuteMsg::UpdatePoolFees {
+            pool_identifier,
+            new_fees,
+        } => manager::update_pool_fees(deps, env, info, pool_identifier, new_fees),
+        ExecuteMsg::ApplyPoolFees { pool_identifier } => {
+            manager::apply_pool_fees(deps, env, info, pool_identifier)
+        }
+        ExecuteMsg::SyncPool {
+            pool_identifier,
+            max_deviation,
+        } => manager::sync_pool(deps, env, info, pool_identifier, max_deviation),
+        ExecuteMsg::AddYieldStrategy { strategy, denom } => {
+            yield_strategy::commands::add_yield_strategy(deps, info, strategy, denom)
+        }
+        ExecuteMsg::RemoveYieldStrategy { strategy } => {
+            yield_strategy::commands::remove_yield_strategy(deps, info, strategy)
+        }
+        ExecuteMsg::UpdatePoolYieldStrategy {
+            pool_identifier,
+            denom,
+            strategy,
+            max_deployed_share,
+        } => yield_strategy::commands::update_pool_yield_strategy(
+            deps,
+            info,
+            pool_identifier,
+            denom,
+            strategy,
+            max_deployed_share,
+        ),
+        ExecuteMsg::DeployIdleReserves {
+            pool_identifier,
+            denom,
+            amount,
+        } => yield_strategy::commands::deploy_idle_reserves(
+            deps,
+            info,
+            pool_identifier,
+            denom,
+            amount,
+        ),
+        ExecuteMsg::RecallReserves {
+            pool_identifier,
+            denom,
+            amount,
+        } => yield_strategy::commands::recall_reserves(deps, info, pool_identifier, denom, amount),
+        ExecuteMsg::HarvestYield {
+            pool_identifier,
+            denom,
+        } => yield_strategy::commands::harvest_yield(deps, env, info, pool_identifier, denom),
+        ExecuteMsg::HookSwap {
+            ask_asset_denom,
+            belief_price,
+            max_spread,
+            pool_identifier,
+            receiver,
+            forward,
+        } => ibc_hooks::commands::hook_swap(
+            deps,
+            env,
+            info,
+            ask_asset_denom,
+            belief_price,
+            max_spread,
+            pool_identifier,
+            receiver,
+            forward,
+        ),
+        ExecuteMsg::HookSwapOperations {
+            operations,
+            minimum_receive,
+            max_spread,
+            receiver,
+            forward,
+        } => ibc_hooks::commands::hook_swap_operations(
+            deps,
+            env,
+            info,
+            operations,
+            minimum_receive,
+            max_spread,
+            receiver,
+            forward,
+        ),
+        ExecuteMsg::SwapAndLock {
+            operations,
+            minimum_receive,
+            max_spread,
+            pool_identifier,
+            slippage_tolerance,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+        } => zap::commands::swap_and_lock(
+            deps,
+            env,
+            info,
+            operations,
+            minimum_receive,
+            max_spread,
+            pool_identifier,
+            slippage_tolerance,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+        ),
+        ExecuteMsg::Zap {
+            route,
+            pool_identifier,
+            max_spread,
+            slippage_tolerance,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+        } => zap::commands::zap(
+            deps,
+            env,
+            info,
+            route,
+            pool_identifier,
+            max_spread,
+            slippage_tolerance,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+        ),
+        ExecuteMsg::WithdrawLiquidityAndSwap {
+            pool_identifier,
+            target_denom,
+            route,
+            min_out,
+        } => zap::commands::withdraw_liquidity_and_swap(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            target_denom,
+            route,
+            min_out,
+        ),
+        ExecuteMsg::UpdateIbcSourceChannels { channels } => {
+            ibc_denom::commands::update_ibc_source_channels(deps, info, channels)
+        }
+        ExecuteMsg::UpdatePermissionedPool {
+            pool_identifier,
+            admin,
+        } => permissioned::commands::update_permissioned_pool(deps, info, pool_identifier, admin),
+        ExecuteMsg::UpdatePoolAllowlist {
+            pool_identifier,
+            add,
+            remove,
+        } => {
+            permissioned::commands::update_pool_allowlist(deps, info, pool_identifier, add, remove)
+        }
+        ExecuteMsg::UpdatePoolCreatorAllowlist { add, remove } => {
+            manager::commands::update_pool_creator_allowlist(deps, info, add, remove)
+        }
+        ExecuteMsg::ProposePoolAdmin {
+            pool_identifier,
+            new_admin,
+        } => manager::propose_pool_admin(deps, info, pool_identifier, new_admin),
+        ExecuteMsg::AcceptPoolAdmin { pool_identifier } => {
+            manager::accept_pool_admin(deps, info, pool_identifier)
+        }
+        ExecuteMsg::ClonePool {
+            source_pool,
+            identifier,
+            overrides,
+        } => manager::clone_pool(deps, env, info, source_pool, identifier, overrides),
+        ExecuteMsg::BootstrapPool {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            seed_assets,
+            unlocking_duration,
+            receiver,
+        } => manager::bootstrap_pool(
+            deps,
+            env,
+            info,
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            seed_assets,
+            unlocking_duration,
+            receiver,
+        ),
+        ExecuteMsg::ProvideConcentratedLiquidity {
+            pool_identifier,
+            lower_tick,
+            upper_tick,
+            initial_price,
+            min_liquidity,
+        } => concentrated::commands::provide_concentrated_liquidity(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            lower_tick,
+            upper_tick,
+            initial_price,
+            min_liquidity,
+        ),
+        ExecuteMsg::WithdrawConcentratedLiquidity {
+            position_id,
+            liquidity,
+        } => concentrated::commands::withdraw_concentrated_liquidity(
+            deps,
+            env,
+            info,
+            position_id,
+            liquidity,
+        ),
+        ExecuteMsg::CollectConcentratedFees { position_id } => {
+            concentrated::commands::collect_concentrated_fees(deps, info, position_id)
+        }
+        ExecuteMsg::FlashSwap {
+            pool_identifier,
+            ask_asset,
+            offer_asset_denom,
+            max_spread,
+            receiver,
+            callback,
+        } => flash_swap::commands::flash_swap(
+            deps,
+            env,
+            info,
+            pool_identifier,
+            ask_asset,
+            offer_asset_denom,
+            max_spread,
+            receiver,
+            callback,
+        ),
+    }?;
+
+    let response = failure_injection::with_injected_bank_send_failure(response, &contract_addr);
+
+    Ok(with_schema_version(gas::with_checkpoints(response)))
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    let response = match msg {
+        SudoMsg::DisableMessages { messages } => Ok(
+            circuit_breaker::disable_messages::<ExecuteMsg>(deps.storage, messages)?,
+        ),
+        SudoMsg::EnableMessages { messages } => {
+            Ok(circuit_breaker::enable_messages(deps.storage, messages)?)
+        }
+        SudoMsg::UpdateFailureInjection { failures } => {
+            failure_injection::update_failure_injection(deps.storage, failures)
+        }
+    }?;
+
+    Ok(with_schema_version(response))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    failure_injection::load_injected_failures(deps.storage)?;
+
     match msg {
         QueryMsg::Config {} => Ok(to_json_binary(&queries::query_config(deps)?)?),
         QueryMsg::AssetDecimals {
@@ -198,11 +1044,14 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             offer_asset,
             ask_asset_denom,
             pool_identifier,
+            trader,
         } => Ok(to_json_binary(&queries::query_simulation(
             deps,
+            &env,
             offer_asset,
             ask_asset_denom,
             pool_identifier,
+            trader,
         )?)?),
         QueryMsg::ReverseSimulation {
             ask_asset,
@@ -210,41 +1059,418 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             pool_identifier,
         } => Ok(to_json_binary(&queries::query_reverse_simulation(
             deps,
+            &env,
             ask_asset,
             offer_asset_denom,
             pool_identifier,
         )?)?),
+        QueryMsg::BatchSimulation { simulations } => Ok(to_json_binary(
+            &queries::query_batch_simulation(deps, &env, simulations)?,
+        )?),
+        QueryMsg::SplitSimulation {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifiers,
+            weights,
+        } => Ok(to_json_binary(&queries::query_split_simulation(
+            deps,
+            &env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifiers,
+            weights,
+        )?)?),
+        QueryMsg::SimulationWithReserves {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            reserves,
+        } => Ok(to_json_binary(&queries::query_simulation_with_reserves(
+            deps,
+            &env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            reserves,
+        )?)?),
+        QueryMsg::ReverseSimulationWithReserves {
+            ask_asset,
+            offer_asset_denom,
+            pool_identifier,
+            reserves,
+        } => Ok(to_json_binary(
+            &queries::query_reverse_simulation_with_reserves(
+                deps,
+                &env,
+                ask_asset,
+                offer_asset_denom,
+                pool_identifier,
+                reserves,
+            )?,
+        )?),
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
         } => Ok(to_json_binary(&queries::simulate_swap_operations(
             deps,
+            &env,
             offer_amount,
             operations,
         )?)?),
+        QueryMsg::FindBestRoute {
+            offer_denom,
+            ask_denom,
+            amount,
+            max_hops,
+        } => Ok(to_json_binary(&queries::find_best_route(
+            deps,
+            &env,
+            offer_denom,
+            ask_denom,
+            amount,
+            max_hops,
+        )?)?),
         QueryMsg::ReverseSimulateSwapOperations {
             ask_amount,
             operations,
         } => Ok(to_json_binary(&queries::reverse_simulate_swap_operations(
-            deps, ask_amount, operations,
+            deps, &env, ask_amount, operations,
         )?)?),
         QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
         QueryMsg::Pools {
             pool_identifier,
             start_after,
             limit,
+            order,
+            deprecated,
         } => Ok(to_json_binary(&queries::get_pools(
             deps,
             pool_identifier,
             start_after,
             limit,
+            order,
+            deprecated,
+        )?)?),
+        QueryMsg::StopLossOrders {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::get_stop_loss_orders(
+            deps,
+            owner,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::Twap {
+            pool_identifier,
+            window_secs,
+        } => Ok(to_json_binary(&queries::query_twap(
+            deps,
+            env,
+            pool_identifier,
+            window_secs,
+        )?)?),
+        QueryMsg::RouteTwap {
+            operations,
+            window_secs,
+        } => Ok(to_json_binary(&queries::query_route_twap(
+            deps,
+            env,
+            operations,
+            window_secs,
+        )?)?),
+        QueryMsg::PriceSubscriptions {
+            pool_identifier,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::get_price_subscriptions(
+            deps,
+            pool_identifier,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::DcaSchedules {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::get_dca_schedules(
+            deps,
+            owner,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::LiquidityStreams {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::get_liquidity_streams(
+            deps,
+            owner,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::IbcWithdrawalChannels {} => Ok(to_json_binary(
+            &queries::query_ibc_withdrawal_channels(deps)?,
+        )?),
+        QueryMsg::LpValuation {
+            pool_identifier,
+            address,
+            lp_amount,
+            target_denom,
+            twap_window_secs,
+            swap_routes,
+        } => Ok(to_json_binary(&queries::query_lp_valuation(
+            deps,
+            env,
+            pool_identifier,
+            address,
+            lp_amount,
+            target_denom,
+            twap_window_secs,
+            swap_routes,
+        )?)?),
+        QueryMsg::ProtocolLiquidity {} => {
+            Ok(to_json_binary(&queries::query_protocol_liquidity(deps)?)?)
+        }
+        QueryMsg::RfqMaker { maker } => {
+            Ok(to_json_binary(&queries::query_rfq_maker(deps, maker)?)?)
+        }
+        QueryMsg::RfqQuoteDigest { quote } => Ok(to_json_binary(
+            &queries::query_rfq_quote_digest(env, quote)?,
+        )?),
+        QueryMsg::RoundingDust { pool_identifier } => Ok(to_json_binary(
+            &queries::query_rounding_dust(deps, pool_identifier)?,
+        )?),
+        QueryMsg::StableSwapAmp { pool_identifier } => Ok(to_json_binary(
+            &queries::query_stableswap_amp(deps, pool_identifier)?,
+        )?),
+        QueryMsg::DepegProtection { pool_identifier } => Ok(to_json_binary(
+            &queries::query_depeg_protection(deps, &env, pool_identifier)?,
+        )?),
+        QueryMsg::PriceBreaker { pool_identifier } => Ok(to_json_binary(
+            &queries::query_price_breaker(deps, &env, pool_identifier)?,
+        )?),
+        QueryMsg::FeeDiscounts { trader } => Ok(to_json_binary(&queries::query_fee_discounts(
+            deps, trader,
+        )?)?),
+        QueryMsg::ImbalanceFee { pool_identifier } => Ok(to_json_binary(
+            &queries::query_imbalance_fee(deps, pool_identifier)?,
+        )?),
+        QueryMsg::UnlockingDurationBounds { pool_identifier } => Ok(to_json_binary(
+            &queries::query_unlocking_duration_bounds(deps, pool_identifier)?,
+        )?),
+        QueryMsg::MinLpLock { pool_identifier } => Ok(to_json_binary(
+            &queries::query_min_lp_lock(deps, pool_identifier)?,
+        )?),
+        QueryMsg::YieldStrategies {} => {
+            Ok(to_json_binary(&queries::query_yield_strategies(deps)?)?)
+        }
+        QueryMsg::YieldDeployments { pool_identifier } => Ok(to_json_binary(
+            &queries::query_yield_deployments(deps, pool_identifier)?,
+        )?),
+        QueryMsg::ReserveFloors { pool_identifier } => Ok(to_json_binary(
+            &queries::query_reserve_floors(deps, pool_identifier)?,
+        )?),
+        QueryMsg::RateProviders { pool_identifier } => Ok(to_json_binary(
+            &queries::query_rate_providers(deps, pool_identifier)?,
+        )?),
+        QueryMsg::PendingPoolFees { pool_identifier } => Ok(to_json_binary(
+            &queries::query_pending_pool_fees(deps, pool_identifier)?,
+        )?),
+        QueryMsg::DenomTraces { pool_identifier } => Ok(to_json_binary(
+            &queries::query_denom_traces(deps, pool_identifier)?,
+        )?),
+        QueryMsg::IbcSourceChannels {} => {
+            Ok(to_json_binary(&queries::query_ibc_source_channels(deps)?)?)
+        }
+        QueryMsg::ContractRegistry {} => Ok(to_json_binary(&queries::query_contract_registry(
+            deps, env,
+        )?)?),
+        QueryMsg::DisabledMessages {} => {
+            Ok(to_json_binary(&queries::query_disabled_messages(deps)?)?)
+        }
+        QueryMsg::PoolAdmin { pool_identifier } => Ok(to_json_binary(&queries::query_pool_admin(
+            deps,
+            pool_identifier,
+        )?)?),
+        QueryMsg::PoolAllowlist {
+            pool_identifier,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::query_pool_allowlist(
+            deps,
+            pool_identifier,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::SwapRoute {
+            offer_asset_denom,
+            ask_asset_denom,
+        } => Ok(to_json_binary(&queries::query_swap_route(
+            deps,
+            offer_asset_denom,
+            ask_asset_denom,
+        )?)?),
+        QueryMsg::SwapRoutes { start_after, limit } => Ok(to_json_binary(
+            &queries::query_swap_routes(deps, start_after, limit)?,
+        )?),
+        QueryMsg::SwapAdapters { start_after, limit } => Ok(to_json_binary(
+            &queries::query_swap_adapters(deps, start_after, limit)?,
+        )?),
+        QueryMsg::PoolCreatorAllowlist { start_after, limit } => Ok(to_json_binary(
+            &queries::query_pool_creator_allowlist(deps, start_after, limit)?,
+        )?),
+        QueryMsg::StableSwapDebug {
+            pool_identifier,
+            offer_asset,
+            ask_asset_denom,
+        } => Ok(to_json_binary(&queries::query_stableswap_debug(
+            deps,
+            pool_identifier,
+            offer_asset,
+            ask_asset_denom,
+        )?)?),
+        QueryMsg::LpWrapper { pool_identifier } => Ok(to_json_binary(&queries::query_lp_wrapper(
+            deps,
+            pool_identifier,
+        )?)?),
+        QueryMsg::MigrationCampaign {
+            from_pool_identifier,
+            address,
+        } => Ok(to_json_binary(&queries::query_migration_campaign(
+            deps,
+            from_pool_identifier,
+            address,
+        )?)?),
+        QueryMsg::ConcentratedPool { pool_identifier } => Ok(to_json_binary(
+            &queries::query_concentrated_pool(deps, pool_identifier)?,
+        )?),
+        QueryMsg::ConcentratedPositions {
+            owner,
+            pool_identifier,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&queries::query_concentrated_positions(
+            deps,
+            owner,
+            pool_identifier,
+            start_after,
+            limit,
         )?)?),
     }
 }
 
+#[entry_point]
+pub fn ibc_source_callback(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcSourceCallbackMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(with_schema_version_ibc(
+        ibc_transfer::commands::ibc_source_callback(msg)?,
+    ))
+}
+
 #[entry_point]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     validate_contract!(deps, CONTRACT_NAME, CONTRACT_VERSION);
+
+    // map the config stored by earlier versions of the contract, which may lack the fields added
+    // since, into the current config
+    migrate_config(deps.storage)?;
+
+    // backfill the scaling factors of the pools created before they were stored, and the LP
+    // supply of the pools created before it was cached, so reads no longer query the bank module
+    let pools = POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (pool_identifier, mut pool) in pools {
+        if pool.asset_scaling_factors.is_empty() {
+            pool.asset_scaling_factors = get_asset_scaling_factors(&pool.asset_decimals)?;
+            POOLS.save(deps.storage, &pool_identifier, &pool)?;
+        }
+
+        if !LP_SUPPLY.has(deps.storage, &pool_identifier) {
+            let lp_supply = deps.querier.query_supply(&pool.lp_denom)?.amount;
+            LP_SUPPLY.save(deps.storage, &pool_identifier, &lp_supply)?;
+        }
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::default())
 }
+
+/// The config as stored by earlier versions of the contract. The fields added after the first
+/// release are optional, as older configs don't hold them.
+#[derive(Deserialize)]
+struct LegacyConfig {
+    fee_collector_addr: Addr,
+    farm_manager_addr: Addr,
+    pool_creation_fee: Option<Coin>,
+    pool_creation_fees: Option<Vec<Coin>>,
+    feature_toggle: FeatureToggle,
+    pool_fee_caps: Option<PoolFeeCaps>,
+    default_max_spread: Option<Decimal>,
+    max_spread_cap: Option<Decimal>,
+    unlocking_duration_bounds: Option<UnlockingDurationBounds>,
+    max_assets_per_pool: Option<u32>,
+    max_assets_per_stableswap_pool: Option<u32>,
+    permissionless_pool_creation: Option<bool>,
+    pool_fee_update_delay: Option<u64>,
+    max_referral_fee_bps: Option<u16>,
+}
+
+/// Loads the stored config through [LegacyConfig] and saves it back as the current [Config],
+/// backfilling the fields missing from it.
+fn migrate_config(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let raw_config = storage
+        .get(CONFIG.as_slice())
+        .ok_or_else(|| StdError::not_found("config"))?;
+    let legacy: LegacyConfig = from_json(raw_config)?;
+
+    // configs stored before multiple denoms were accepted hold a single pool creation fee
+    let pool_creation_fees = match legacy.pool_creation_fees {
+        Some(pool_creation_fees) => pool_creation_fees,
+        None => legacy.pool_creation_fee.into_iter().collect(),
+    };
+
+    let (default_max_spread, max_spread_cap) =
+        match (legacy.default_max_spread, legacy.max_spread_cap) {
+            (Some(default_max_spread), Some(max_spread_cap)) => {
+                (default_max_spread, max_spread_cap)
+            }
+            _ => (
+                Decimal::from_str(DEFAULT_SLIPPAGE)?,
+                Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?,
+            ),
+        };
+
+    let config = Config {
+        fee_collector_addr: legacy.fee_collector_addr,
+        farm_manager_addr: legacy.farm_manager_addr,
+        pool_creation_fees,
+        feature_toggle: legacy.feature_toggle,
+        pool_fee_caps: legacy.pool_fee_caps.unwrap_or(DEFAULT_POOL_FEE_CAPS),
+        default_max_spread,
+        max_spread_cap,
+        // unlocking durations stay unbounded on configs stored before the bounds were configurable
+        unlocking_duration_bounds: legacy.unlocking_duration_bounds,
+        max_assets_per_pool: legacy
+            .max_assets_per_pool
+            .unwrap_or(DEFAULT_MAX_ASSETS_PER_POOL),
+        max_assets_per_stableswap_pool: legacy
+            .max_assets_per_stableswap_pool
+            .unwrap_or(DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL),
+        // pools could be created by anyone before the allowlist existed
+        permissionless_pool_creation: legacy.permissionless_pool_creation.unwrap_or(true),
+        pool_fee_update_delay: legacy
+            .pool_fee_update_delay
+            .unwrap_or(DEFAULT_POOL_FEE_UPDATE_DELAY),
+        max_referral_fee_bps: legacy
+            .max_referral_fee_bps
+            .unwrap_or(DEFAULT_MAX_REFERRAL_FEE_BPS),
+    };
+    CONFIG.save(storage, &config)?;
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
+
+use mantra_dex_std::pool_manager::{DcaSchedule, ExecuteMsg, SwapOperation};
+
+use crate::router::commands::assert_operations;
+use crate::state::{get_dca_schedule_by_id, DCA_SCHEDULES, DCA_SCHEDULE_COUNTER};
+use crate::ContractError;
+
+/// Creates a DCA schedule. The funds sent with the message are the budget of the schedule, which
+/// must be the input of the first swap operation. The first swap can be executed right away.
+///
+/// Each swap must return at least `min_receive_per_swap`, scaled down for a last swap smaller than
+/// `amount_per_swap`, as keepers choose when swaps run and could sandwich them otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn create_dca_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    amount_per_swap: Uint128,
+    min_receive_per_swap: Uint128,
+    interval: u64,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    assert_operations(operations.clone())?;
+
+    ensure!(
+        !amount_per_swap.is_zero() && !min_receive_per_swap.is_zero() && interval > 0,
+        ContractError::InvalidDcaSchedule
+    );
+
+    let offer_asset_denom = operations
+        .first()
+        .ok_or(ContractError::NoSwapOperationsProvided)?
+        .get_input_asset_info()
+        .clone();
+
+    let budget = coin(
+        cw_utils::must_pay(&info, &offer_asset_denom)?.u128(),
+        offer_asset_denom,
+    );
+
+    let schedule_id = DCA_SCHEDULE_COUNTER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1u64;
+    DCA_SCHEDULE_COUNTER.save(deps.storage, &schedule_id)?;
+
+    DCA_SCHEDULES.save(
+        deps.storage,
+        schedule_id,
+        &DcaSchedule {
+            id: schedule_id,
+            owner: info.sender.clone(),
+            budget: budget.clone(),
+            amount_per_swap,
+            min_receive_per_swap,
+            interval,
+            operations,
+            max_spread,
+            next_execution: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "create_dca_schedule".to_string()),
+        ("schedule_id", schedule_id.to_string()),
+        ("owner", info.sender.into_string()),
+        ("budget", budget.to_string()),
+        ("amount_per_swap", amount_per_swap.to_string()),
+        ("min_receive_per_swap", min_receive_per_swap.to_string()),
+        ("interval", interval.to_string()),
+    ]))
+}
+
+/// Executes the next swap of a DCA schedule. Can be called by anyone, i.e. keepers, once the
+/// interval since the last swap has elapsed.
+///
+/// The swap is performed by the contract itself via [ExecuteMsg::ExecuteSwapOperations], sending
+/// the proceeds to the owner of the schedule. The last swap uses whatever is left of the budget,
+/// after which the schedule is removed.
+pub fn execute_dca_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    schedule_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut schedule = get_dca_schedule_by_id(deps.storage, schedule_id)?;
+
+    ensure!(
+        env.block.time.seconds() >= schedule.next_execution,
+        ContractError::DcaScheduleNotDue {
+            next_execution: schedule.next_execution,
+        }
+    );
+
+    let swap_amount = schedule.amount_per_swap.min(schedule.budget.amount);
+    let minimum_receive = schedule
+        .min_receive_per_swap
+        .multiply_ratio(swap_amount, schedule.amount_per_swap);
+    schedule.budget.amount = schedule.budget.amount.checked_sub(swap_amount)?;
+    schedule.next_execution = env.block.time.seconds().saturating_add(schedule.interval);
+
+    if schedule.budget.amount.is_zero() {
+        DCA_SCHEDULES.remove(deps.storage, schedule_id)?;
+    } else {
+        DCA_SCHEDULES.save(deps.storage, schedule_id, &schedule)?;
+    }
+
+    let swap_msg = wasm_execute(
+        env.contract.address.into_string(),
+        &ExecuteMsg::ExecuteSwapOperations {
+            operations: schedule.operations,
+            minimum_receive: Some(minimum_receive),
+            receiver: Some(schedule.owner.to_string()),
+            max_spread: schedule.max_spread,
+            ask_amount: None,
+            referral: None,
+            hop_limits: None,
+        },
+        vec![coin(swap_amount.u128(), schedule.budget.denom.clone())],
+    )?;
+
+    Ok(Response::default()
+        .add_message(swap_msg)
+        .add_attributes(vec![
+            ("action", "execute_dca_schedule".to_string()),
+            ("schedule_id", schedule_id.to_string()),
+            ("owner", schedule.owner.into_string()),
+            ("keeper", info.sender.into_string()),
+            (
+                "swap_amount",
+                coin(swap_amount.u128(), schedule.budget.denom.clone()).to_string(),
+            ),
+            ("minimum_receive", minimum_receive.to_string()),
+            ("remaining_budget", schedule.budget.to_string()),
+        ]))
+}
+
+/// Withdraws part of the remaining budget of a DCA schedule. If no amount is specified, or the
+/// whole budget is withdrawn, the schedule is cancelled.
+pub fn withdraw_dca_budget(
+    deps: DepsMut,
+    info: MessageInfo,
+    schedule_id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut schedule = get_dca_schedule_by_id(deps.storage, schedule_id)?;
+
+    ensure!(schedule.owner == info.sender, ContractError::Unauthorized);
+
+    let withdraw_amount = amount.unwrap_or(schedule.budget.amount);
+
+    ensure!(
+        !withdraw_amount.is_zero() && withdraw_amount <= schedule.budget.amount,
+        ContractError::InvalidDcaWithdrawalAmount
+    );
+
+    schedule.budget.amount = schedule.budget.amount.checked_sub(withdraw_amount)?;
+
+    let action = if schedule.budget.amount.is_zero() {
+        DCA_SCHEDULES.remove(deps.storage, schedule_id)?;
+        "cancel_dca_schedule"
+    } else {
+        DCA_SCHEDULES.save(deps.storage, schedule_id, &schedule)?;
+        "withdraw_dca_budget"
+    };
+
+    let refund: CosmosMsg = BankMsg::Send {
+        to_address: schedule.owner.to_string(),
+        amount: vec![coin(withdraw_amount.u128(), schedule.budget.denom.clone())],
+    }
+    .into();
+
+    Ok(Response::default().add_message(refund).add_attributes(vec![
+        ("action", action.to_string()),
+        ("schedule_id", schedule_id.to_string()),
+        ("owner", schedule.owner.into_string()),
+        ("withdrawn_amount", withdraw_amount.to_string()),
+        ("remaining_budget", schedule.budget.to_string()),
+    ]))
+}
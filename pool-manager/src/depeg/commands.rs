@@ -0,0 +1,51 @@
+use cosmwasm_std::{ensure, DepsMut, MessageInfo, Response};
+
+use mantra_dex_std::pool_manager::DepegProtectionConfig;
+
+use crate::helpers::is_stableswap;
+use crate::state::{get_pool_by_identifier, DEPEG_DEVIATING_SINCE, DEPEG_PROTECTION};
+use crate::ContractError;
+
+/// Sets or removes the depeg protection of the given stableswap pool. Only the owner can do this.
+pub fn update_depeg_protection(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    config: Option<DepegProtectionConfig>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        is_stableswap(&pool.pool_type),
+        ContractError::NotStableSwapPool { pool_identifier }
+    );
+
+    // the deviation is tracked from scratch under the new settings
+    DEPEG_DEVIATING_SINCE.remove(deps.storage, &pool_identifier);
+
+    let mut attributes = vec![
+        ("action", "update_depeg_protection".to_string()),
+        ("pool_identifier", pool_identifier.clone()),
+    ];
+
+    match config {
+        Some(config) => {
+            ensure!(
+                !config.band.is_zero(),
+                ContractError::InvalidDepegProtectionConfig
+            );
+
+            attributes.push(("band", config.band.to_string()));
+            attributes.push(("grace_period", config.grace_period.to_string()));
+            DEPEG_PROTECTION.save(deps.storage, &pool_identifier, &config)?;
+        }
+        None => {
+            attributes.push(("removed", "true".to_string()));
+            DEPEG_PROTECTION.remove(deps.storage, &pool_identifier);
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
@@ -0,0 +1,69 @@
+use cosmwasm_std::{Storage, Uint256};
+
+use mantra_dex_std::pool_manager::PoolInfo;
+
+use crate::helpers::{get_peg_deviation, get_scaled_reserves};
+use crate::state::{DEPEG_DEVIATING_SINCE, DEPEG_PROTECTION};
+use crate::ContractError;
+
+/// Returns the denom of the asset depegging in the given pool, if the protection mode of the pool
+/// is active. The protection kicks in once the peg deviation of the pool has stayed beyond the
+/// configured band for longer than the grace period. The depegging asset is the most abundant one,
+/// as it's the asset being sold into the pool.
+pub(crate) fn get_depegging_denom(
+    storage: &dyn Storage,
+    pool_info: &PoolInfo,
+    now: u64,
+) -> Result<Option<String>, ContractError> {
+    let Some(config) = DEPEG_PROTECTION.may_load(storage, &pool_info.pool_identifier)? else {
+        return Ok(None);
+    };
+    let Some(deviating_since) =
+        DEPEG_DEVIATING_SINCE.may_load(storage, &pool_info.pool_identifier)?
+    else {
+        return Ok(None);
+    };
+
+    if now < deviating_since.saturating_add(config.grace_period)
+        || get_peg_deviation(pool_info)? <= config.band
+    {
+        return Ok(None);
+    }
+
+    get_most_abundant_denom(pool_info)
+}
+
+/// Tracks since when the peg deviation of the given pool has been beyond the configured band,
+/// after its reserves changed.
+pub(crate) fn track_peg_deviation(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    now: u64,
+) -> Result<(), ContractError> {
+    let Some(config) = DEPEG_PROTECTION.may_load(storage, &pool_info.pool_identifier)? else {
+        return Ok(());
+    };
+
+    if get_peg_deviation(pool_info)? > config.band {
+        if !DEPEG_DEVIATING_SINCE.has(storage, &pool_info.pool_identifier) {
+            DEPEG_DEVIATING_SINCE.save(storage, &pool_info.pool_identifier, &now)?;
+        }
+    } else {
+        DEPEG_DEVIATING_SINCE.remove(storage, &pool_info.pool_identifier);
+    }
+
+    Ok(())
+}
+
+/// Gets the denom of the asset with the largest reserve in the pool, normalized by decimals.
+fn get_most_abundant_denom(pool_info: &PoolInfo) -> Result<Option<String>, ContractError> {
+    let mut most_abundant: Option<(Uint256, &str)> = None;
+    for (asset, normalized_reserve) in pool_info.assets.iter().zip(get_scaled_reserves(pool_info)?)
+    {
+        if most_abundant.map_or(true, |(reserve, _)| normalized_reserve > reserve) {
+            most_abundant = Some((normalized_reserve, &asset.denom));
+        }
+    }
+
+    Ok(most_abundant.map(|(_, denom)| denom.to_string()))
+}
@@ -0,0 +1,91 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Decimal256, DepsMut, Env, MessageInfo, Response, Uint128, Uint256,
+};
+
+use crate::dust::tracking::get_rounding_dust;
+use crate::state::{get_pool_by_identifier, CONFIG, DUST_TRACKING, POOLS, ROUNDING_DUST};
+use crate::twap::accumulate_prices;
+use crate::yield_strategy::commands::recall_uncovered_reserves;
+use crate::ContractError;
+
+/// Enables or disables the tracking of the rounding remainders of the pool math. The dust
+/// accumulated so far is kept when disabled. Only the owner can do this.
+pub fn update_dust_tracking(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    DUST_TRACKING.save(deps.storage, &enabled)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_dust_tracking".to_string()),
+        ("enabled", enabled.to_string()),
+    ]))
+}
+
+/// Sweeps the whole units of rounding dust the given pool accrued on top of the dust it leaked,
+/// for each of its assets, from its reserves to the fee collector. The swept units are deducted
+/// from the accrued dust, so the LPs of the pool are left with less than a unit of rounding bias.
+/// Only the owner can do this.
+pub fn sweep_dust(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut rounding_dust = get_rounding_dust(deps.storage, &pool)?;
+
+    let mut swept = vec![];
+    for (index, pool_asset) in pool.assets.iter_mut().enumerate() {
+        let net_dust = rounding_dust.accrued[index].saturating_sub(rounding_dust.leaked[index]);
+        let amount = net_dust
+            .to_uint_floor()
+            .min(Uint256::from(pool_asset.amount));
+        if amount.is_zero() {
+            continue;
+        }
+
+        rounding_dust.accrued[index] = rounding_dust.accrued[index]
+            .checked_sub(Decimal256::from_ratio(amount, Uint256::one()))?;
+        let amount: Uint128 = amount.try_into()?;
+        pool_asset.amount = pool_asset.amount.checked_sub(amount)?;
+        swept.push(coin(amount.u128(), &pool_asset.denom));
+    }
+
+    ensure!(
+        !swept.is_empty(),
+        ContractError::NothingToSweep { pool_identifier }
+    );
+
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+    ROUNDING_DUST.save(deps.storage, &pool_identifier, &rounding_dust)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(Response::default()
+        .add_messages(recall_uncovered_reserves(deps.storage, &pool)?)
+        .add_message(BankMsg::Send {
+            to_address: config.fee_collector_addr.to_string(),
+            amount: swept.clone(),
+        })
+        .add_attributes(vec![
+            ("action", "sweep_dust".to_string()),
+            ("pool_identifier", pool_identifier),
+            (
+                "swept",
+                swept
+                    .iter()
+                    .map(|coin| coin.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
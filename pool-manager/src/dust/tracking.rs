@@ -0,0 +1,197 @@
+use cosmwasm_std::{Coin, Decimal256, Storage, Uint128, Uint256};
+
+use mantra_dex_std::pool_manager::{ImbalanceFee, PoolInfo, PoolType};
+
+use crate::helpers::{apply_imbalance_fee, is_stableswap, SwapComputation};
+use crate::state::{RoundingDust, DUST_TRACKING, ROUNDING_DUST};
+use crate::ContractError;
+
+/// Whether the rounding remainders of the pool math are being tracked.
+pub(crate) fn is_dust_tracking_enabled(storage: &dyn Storage) -> Result<bool, ContractError> {
+    Ok(DUST_TRACKING.may_load(storage)?.unwrap_or_default())
+}
+
+/// Returns the rounding dust of the given pool, with one entry per pool asset.
+pub(crate) fn get_rounding_dust(
+    storage: &dyn Storage,
+    pool_info: &PoolInfo,
+) -> Result<RoundingDust, ContractError> {
+    let assets = pool_info.assets.len();
+
+    Ok(ROUNDING_DUST
+        .may_load(storage, &pool_info.pool_identifier)?
+        .unwrap_or(RoundingDust {
+            accrued: vec![Decimal256::zero(); assets],
+            leaked: vec![Decimal256::zero(); assets],
+            lp_accrued: Decimal256::zero(),
+        }))
+}
+
+/// Tracks the rounding of a swap of `offer_amount` of the asset at `offer_index` in the given
+/// pool, before the swap is applied to its reserves. The return of constant product pools is
+/// rounded down, the remainder staying in the pool, while the swap fee is rounded down in favor
+/// of the trader. The rounding of the stableswap solver is not tracked, as it's bounded by the
+/// convergence of the solver rather than by a single division.
+pub(crate) fn track_swap_dust(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    imbalance_fee: Option<&ImbalanceFee>,
+    swap_computation: &SwapComputation,
+) -> Result<(), ContractError> {
+    if !is_dust_tracking_enabled(storage)? {
+        return Ok(());
+    }
+
+    let return_amount_before_fees = swap_computation
+        .return_amount
+        .checked_add(swap_computation.swap_fee_amount)?
+        .checked_add(swap_computation.protocol_fee_amount)?
+        .checked_add(swap_computation.burn_fee_amount)?
+        .checked_add(swap_computation.extra_fees_amount)?;
+
+    let accrued = match pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let offer_pool = Uint256::from(pool_info.assets[offer_index].amount);
+            let ask_pool = Uint256::from(pool_info.assets[ask_index].amount);
+            let offer_amount = Uint256::from(offer_amount);
+
+            get_remainder(
+                Decimal256::checked_from_ratio(
+                    ask_pool.checked_mul(offer_amount)?,
+                    offer_pool.checked_add(offer_amount)?,
+                )?,
+                return_amount_before_fees,
+            )
+        }
+        _ => Decimal256::zero(),
+    };
+
+    // the swap fee of stableswap pools is adjusted by their imbalance fee, as in the swap itself
+    let pool_fees = match imbalance_fee {
+        Some(imbalance_fee) if is_stableswap(&pool_info.pool_type) => apply_imbalance_fee(
+            pool_info,
+            imbalance_fee,
+            offer_index,
+            ask_index,
+            offer_amount,
+            return_amount_before_fees,
+            pool_info.pool_fees.clone(),
+        )?,
+        _ => pool_info.pool_fees.clone(),
+    };
+    let leaked = get_remainder(
+        Decimal256::from_ratio(return_amount_before_fees, Uint256::one())
+            .checked_mul(pool_fees.swap_fee.share.into())?,
+        swap_computation.swap_fee_amount,
+    );
+
+    record_dust(storage, pool_info, ask_index, accrued, leaked)
+}
+
+/// Tracks the rounding of a withdrawal of `lp_amount` out of the `total_share` of the given pool,
+/// before the withdrawal is applied to its reserves. The withdrawn assets are rounded down, the
+/// remainders staying in the pool.
+pub(crate) fn track_withdrawal_dust(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    lp_amount: Uint128,
+    total_share: Uint128,
+    withdrawn_assets: &[Coin],
+) -> Result<(), ContractError> {
+    if !is_dust_tracking_enabled(storage)? {
+        return Ok(());
+    }
+
+    for (index, pool_asset) in pool_info.assets.iter().enumerate() {
+        let withdrawn = withdrawn_assets
+            .iter()
+            .find(|asset| asset.denom == pool_asset.denom)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        let accrued = get_remainder(
+            Decimal256::checked_from_ratio(
+                Uint256::from(pool_asset.amount).checked_mul(lp_amount.into())?,
+                total_share,
+            )?,
+            withdrawn,
+        );
+
+        record_dust(storage, pool_info, index, accrued, Decimal256::zero())?;
+    }
+
+    Ok(())
+}
+
+/// Tracks the rounding of a deposit into a constant product pool, once the pool was seeded. The
+/// LP shares minted are rounded down, the remainder accruing to the existing LPs.
+pub(crate) fn track_deposit_dust(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    deposits: &[Coin],
+    pool_assets: &[Coin],
+    total_share: Uint128,
+    share: Uint128,
+) -> Result<(), ContractError> {
+    if !is_dust_tracking_enabled(storage)? {
+        return Ok(());
+    }
+
+    let mut exact_share: Option<Decimal256> = None;
+    for deposit in deposits {
+        let Some(pool_asset) = pool_assets
+            .iter()
+            .find(|pool_asset| pool_asset.denom == deposit.denom)
+        else {
+            continue;
+        };
+
+        let asset_share = Decimal256::checked_from_ratio(
+            Uint256::from(deposit.amount).checked_mul(total_share.into())?,
+            pool_asset.amount,
+        )?;
+        exact_share = Some(match exact_share {
+            Some(exact_share) => exact_share.min(asset_share),
+            None => asset_share,
+        });
+    }
+
+    let Some(exact_share) = exact_share else {
+        return Ok(());
+    };
+
+    let mut rounding_dust = get_rounding_dust(storage, pool_info)?;
+    rounding_dust.lp_accrued = rounding_dust
+        .lp_accrued
+        .checked_add(get_remainder(exact_share, share))?;
+    ROUNDING_DUST.save(storage, &pool_info.pool_identifier, &rounding_dust)?;
+
+    Ok(())
+}
+
+/// Adds the given remainders of the asset at `index` to the rounding dust of the pool.
+fn record_dust(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    index: usize,
+    accrued: Decimal256,
+    leaked: Decimal256,
+) -> Result<(), ContractError> {
+    if accrued.is_zero() && leaked.is_zero() {
+        return Ok(());
+    }
+
+    let mut rounding_dust = get_rounding_dust(storage, pool_info)?;
+    rounding_dust.accrued[index] = rounding_dust.accrued[index].checked_add(accrued)?;
+    rounding_dust.leaked[index] = rounding_dust.leaked[index].checked_add(leaked)?;
+    ROUNDING_DUST.save(storage, &pool_info.pool_identifier, &rounding_dust)?;
+
+    Ok(())
+}
+
+/// Gets the fraction of a unit the exact amount was rounded away by, be it up or down.
+fn get_remainder(exact: Decimal256, rounded: impl Into<Uint256>) -> Decimal256 {
+    exact.abs_diff(Decimal256::from_ratio(rounded.into(), Uint256::one()))
+}
@@ -1,8 +1,8 @@
-use crate::manager::commands::MAX_ASSETS_PER_POOL;
+use circuit_breaker::CircuitBreakerError;
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
-    ConversionOverflowError, DivideByZeroError, Instantiate2AddressError, OverflowError, StdError,
-    Uint128,
+    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError, Coin,
+    ConversionOverflowError, Decimal, Decimal256, DivideByZeroError, Instantiate2AddressError,
+    OverflowError, StdError, Uint128,
 };
 use cw_migrate_error_derive::cw_migrate_invalid_version_error;
 use cw_ownable::OwnershipError;
@@ -46,14 +46,31 @@ pub enum ContractError {
         swap_amount: Uint128,
     },
 
+    #[error("Assertion failed; minimum LP to receive: {min_lp_to_receive}, LP minted: {share}")]
+    MinLpToReceiveAssertion {
+        min_lp_to_receive: Uint128,
+        share: Uint128,
+    },
+
+    #[error(
+        "Assertion failed; minimum amount out: {min_amount_out}, amount withdrawn: {amount_out}"
+    )]
+    MinAmountOutAssertion {
+        min_amount_out: Coin,
+        amount_out: Uint128,
+    },
+
     #[error("The asset \"{asset_infos}\" with the identifier \"{identifier}\" already has a pool")]
     PoolExists {
         asset_infos: String, //String representation of the asset infos
         identifier: String,
     },
 
-    #[error("More assets provided than it is supported. The max is currently {MAX_ASSETS_PER_POOL}, you provided {assets_provided}")]
-    TooManyAssets { assets_provided: usize },
+    #[error("More assets provided than it is supported. The max is currently {max_assets}, you provided {assets_provided}")]
+    TooManyAssets {
+        assets_provided: usize,
+        max_assets: usize,
+    },
 
     #[error("{asset} is invalid")]
     InvalidAsset { asset: String },
@@ -100,8 +117,16 @@ pub enum ContractError {
     #[error("Error computing the stableswap invariant")]
     StableInvariantError,
 
-    #[error("Failed to converge when performing newtons method")]
-    ConvergeError,
+    #[error("The pool {pool_identifier} is not a stableswap pool")]
+    NotStableSwapPool { pool_identifier: String },
+
+    #[error("Failed to converge when performing newtons method on the stableswap {stage} after {iterations} iterations, last delta {last_delta}, inputs hash {inputs_hash}")]
+    ConvergeError {
+        stage: String,
+        iterations: u64,
+        last_delta: String,
+        inputs_hash: String,
+    },
 
     #[error("An conversion overflow occurred when attempting to swap an asset")]
     SwapOverflowError,
@@ -142,6 +167,9 @@ pub enum ContractError {
     #[error("Pool creation fee was not included")]
     PoolCreationFeeMissing,
 
+    #[error("At least one pool creation fee must be accepted, and each denom only once")]
+    InvalidPoolCreationFees,
+
     #[error("Additional funds were sent with pool creation, expected pool creation and token factory fees only")]
     ExtraFundsSent,
 
@@ -162,6 +190,479 @@ pub enum ContractError {
 
     #[error("The token factory lp denom creation fee was not paid.")]
     TokenFactoryFeeNotPaid,
+
+    #[error("The {fee} of {share} exceeds the cap of {cap}")]
+    PoolFeeCapExceeded {
+        fee: String,
+        share: Decimal,
+        cap: Decimal,
+    },
+
+    #[error(
+        "Invalid pool fee caps. The total fee cap must be below 100% and no fee cap can exceed it"
+    )]
+    InvalidPoolFeeCaps,
+
+    #[error("The total fees of the pool must be below 100%")]
+    InvalidTotalPoolFees,
+
+    #[error("Stop-loss order does not exist")]
+    UnExistingStopLossOrder,
+
+    #[error("The trigger price of a stop-loss order must be greater than zero")]
+    InvalidTriggerPrice,
+
+    #[error(
+        "Invalid funds for the stop-loss order, only the offer asset and the bounty must be sent"
+    )]
+    InvalidStopLossOrderFunds,
+
+    #[error("Stop-loss order not triggered, trigger price: {trigger_price}, current price: {current_price}")]
+    StopLossNotTriggered {
+        trigger_price: Decimal,
+        current_price: Decimal,
+    },
+
+    #[error("DCA schedule does not exist")]
+    UnExistingDcaSchedule,
+
+    #[error(
+        "Invalid DCA schedule, the amount per swap and the interval must be greater than zero"
+    )]
+    InvalidDcaSchedule,
+
+    #[error("The DCA schedule can't be executed until {next_execution}")]
+    DcaScheduleNotDue { next_execution: u64 },
+
+    #[error("Invalid amount to withdraw from the DCA schedule")]
+    InvalidDcaWithdrawalAmount,
+
+    #[error("Invalid reserve overrides, each denom must belong to the pool and appear only once")]
+    InvalidReserveOverrides,
+
+    #[error("Too many simulations requested, the max is {max}")]
+    TooManySimulations { max: u32 },
+
+    #[error("Price subscription does not exist")]
+    UnExistingPriceSubscription,
+
+    #[error("Only contracts can subscribe to price thresholds")]
+    PriceSubscriberNotAContract,
+
+    #[error("The price threshold must be greater than zero")]
+    InvalidPriceThreshold,
+
+    #[error("The pool already has the maximum number of price subscriptions, which is {max}")]
+    TooManyPriceSubscriptions { max: usize },
+
+    #[error(
+        "The fee payer amount {amount} must be lower than the swap return amount {return_amount}"
+    )]
+    InvalidFeePayerAmount {
+        amount: Uint128,
+        return_amount: Uint128,
+    },
+
+    #[error(
+        "The offer amount needed {offer_amount} exceeds the max offer amount {max_offer_amount}"
+    )]
+    MaxOfferAmountExceeded {
+        offer_amount: Uint128,
+        max_offer_amount: Uint128,
+    },
+
+    #[error("Invalid TWAP window, it must be greater than zero and at most {max} seconds")]
+    InvalidTwapWindow { max: u64 },
+
+    #[error("There is not enough price history to compute the TWAP over the given window")]
+    InsufficientTwapHistory,
+
+    #[error(
+        "Invalid pools to split the trade across, provide between one and {max} distinct pools"
+    )]
+    InvalidSplitPools { max: usize },
+
+    #[error("Invalid batch of swaps, provide between one and {max} swaps")]
+    InvalidBatchSwaps { max: usize },
+
+    #[error("The RFQ maker public key must be a 33 bytes compressed secp256k1 key")]
+    InvalidRfqMakerPubkey,
+
+    #[error("The RFQ maker {maker} is not registered")]
+    RfqMakerNotRegistered { maker: String },
+
+    #[error("Invalid RFQ quote signature")]
+    InvalidRfqSignature,
+
+    #[error("The nonce {nonce} was already used by the RFQ maker")]
+    RfqNonceUsed { nonce: u64 },
+
+    #[error("The RFQ quote expired and no pool was provided to fall back to")]
+    RfqQuoteExpired,
+
+    #[error("The funds sent don't match the offer asset of the RFQ quote, expected {expected}")]
+    InvalidRfqPayment { expected: Coin },
+
+    #[error("Not enough {denom} in the RFQ maker inventory")]
+    InsufficientRfqInventory { denom: String },
+
+    #[error(
+        "Invalid rebalance config, the max move share must be greater than zero and at most one"
+    )]
+    InvalidRebalanceConfig,
+
+    #[error(
+        "Rebalancing is only supported between different stable pools holding the same assets"
+    )]
+    InvalidRebalancePools,
+
+    #[error("The amount of LP shares to rebalance must be greater than zero and at most {max}")]
+    InvalidRebalanceAmount { max: Uint128 },
+
+    #[error("The peg deviation of the destination pool doesn't exceed the one of the source pool by {min_deviation_gap}")]
+    RebalanceNotNeeded { min_deviation_gap: Decimal },
+
+    #[error("Not enough protocol-owned liquidity in the pool {pool_identifier}")]
+    InsufficientProtocolLiquidity { pool_identifier: String },
+
+    #[error("Invalid swap route from {offer_asset} to {ask_asset}")]
+    InvalidSwapRoute {
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("No swap route from {offer_asset} to {ask_asset}")]
+    NoSwapRouteForAssets {
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error(
+        "Either an address or an LP amount must be provided to value an LP position, but not both"
+    )]
+    InvalidLpValuationRequest,
+
+    #[error("The pool {pool_identifier} already has an LP wrapper")]
+    LpWrapperAlreadyExists { pool_identifier: String },
+
+    #[error("Liquidity can only be moved between different pools holding the same assets")]
+    InvalidMigrationPools,
+
+    #[error("The pool {pool_identifier} already has a migration campaign")]
+    MigrationCampaignAlreadyExists { pool_identifier: String },
+
+    #[error("The pool {pool_identifier} has no migration campaign")]
+    MigrationCampaignNotFound { pool_identifier: String },
+
+    #[error("The deadline of a migration campaign must be in the future")]
+    InvalidMigrationDeadline,
+
+    #[error("The migration campaign is in progress")]
+    MigrationCampaignInProgress,
+
+    #[error("There are no migration rewards to claim")]
+    NoMigrationRewards,
+
+    #[error("Liquidity stream does not exist")]
+    UnExistingLiquidityStream,
+
+    #[error(
+        "Invalid liquidity stream, the number of tranches and the interval must be greater than zero"
+    )]
+    InvalidLiquidityStream,
+
+    #[error("The liquidity stream can't be executed until {next_execution}")]
+    LiquidityStreamNotDue { next_execution: u64 },
+
+    #[error("Invalid IBC channel {channel_id}")]
+    InvalidIbcChannel { channel_id: String },
+
+    #[error("There's no IBC channel configured to withdraw {denom} to a remote address")]
+    NoIbcChannelForAsset { denom: String },
+
+    #[error("The farm position {identifier} doesn't exist or doesn't belong to the sender")]
+    FarmPositionNotFound { identifier: String },
+
+    #[error("The farm position {identifier} is still locked, it must be closed and its unlocking period must have passed")]
+    FarmPositionNotMatured { identifier: String },
+
+    #[error("The farm position {identifier} doesn't hold LP tokens of pool {pool_identifier}")]
+    FarmPositionPoolMismatch {
+        identifier: String,
+        pool_identifier: String,
+    },
+
+    #[error("Composable stableswap pools need a non-zero weight for each of their assets")]
+    InvalidPoolWeights,
+
+    #[error("Invalid depeg protection config, the band must be greater than zero")]
+    InvalidDepegProtectionConfig,
+
+    #[error(
+        "The depeg protection of the pool is active, swaps selling {denom} into it are paused"
+    )]
+    DepegProtectionActive { denom: String },
+
+    #[error("Pegged pools need a valid oracle and a max deviation lower than one")]
+    InvalidPeggedPoolConfig,
+
+    #[error("The oracle rate of the pegged pool is stale, it was last updated at {last_updated}")]
+    StaleOracleRate { last_updated: u64 },
+
+    #[error("The oracle rate {rate} of the pegged pool is out of its band")]
+    PeggedRateOutOfBand { rate: Decimal },
+
+    #[error("The oracle rate of the pegged pool is unavailable")]
+    PeggedRateUnavailable,
+
+    #[error("Not enough {denom} in the pegged pool to fill the trade")]
+    InsufficientPeggedPoolLiquidity { denom: String },
+
+    #[error("The swap would drain the pool below its reserve floor of {floor}")]
+    ReserveFloorBreached { floor: Coin },
+
+    #[error("Can't donate to a pool without liquidity")]
+    DonationToEmptyPool,
+
+    #[error("{denom} is an LP token, which can't be skimmed")]
+    CannotSkimLpDenom { denom: String },
+
+    #[error("There's no untracked balance of {denom} to skim")]
+    NothingToSkim { denom: String },
+
+    #[error("Syncing {tracked} to {attributable} exceeds the max deviation of {max_deviation}")]
+    SyncDeviationExceeded {
+        tracked: Coin,
+        attributable: Coin,
+        max_deviation: Decimal,
+    },
+
+    #[error("The seed assets must cover every pool asset and be part of the funds sent")]
+    InvalidBootstrapSeed,
+
+    #[error("The max spread cap must be between 0 and 100%, and the default max spread within it")]
+    InvalidSpreadConfig,
+
+    #[error("The trace of {denom} couldn't be resolved or doesn't match the denom")]
+    InvalidIbcDenomTrace { denom: String },
+
+    #[error("{denom} came through {channel_id}, which isn't an allowed source channel")]
+    IbcSourceChannelNotAllowed { denom: String, channel_id: String },
+
+    #[error("{0}")]
+    CircuitBreakerError(#[from] CircuitBreakerError),
+
+    #[error("The pool {pool_identifier} is not permissioned")]
+    PoolNotPermissioned { pool_identifier: String },
+
+    #[error("{address} is not on the allowlist of the permissioned pool {pool_identifier}")]
+    NotAllowlisted {
+        address: String,
+        pool_identifier: String,
+    },
+
+    #[error("{address} is not allowed to create pools while pool creation isn't permissionless")]
+    PoolCreatorNotAllowlisted { address: String },
+
+    #[error("The {field} of the pool metadata can't be empty or longer than {max_length}")]
+    InvalidPoolMetadata { field: String, max_length: usize },
+
+    #[error("The pool {pool_identifier} is deprecated, it only accepts withdrawals")]
+    PoolDeprecated { pool_identifier: String },
+
+    #[error("There are no fees scheduled for the pool {pool_identifier}")]
+    NoPendingPoolFees { pool_identifier: String },
+
+    #[error("The fees scheduled for the pool can't be applied until {effective_at}")]
+    PoolFeesNotEffective { effective_at: u64 },
+
+    #[error("Failure injection is only available in builds with the failure-injection feature")]
+    FailureInjectionUnavailable,
+
+    #[error("Injected failure: {failure}")]
+    InjectedFailure { failure: String },
+
+    #[error("The pool {pool_identifier} has no whole unit of rounding dust to sweep")]
+    NothingToSweep { pool_identifier: String },
+
+    #[error("At least one pool must be given to create")]
+    NoPoolsToCreate,
+
+    #[error(
+        "The funds sent, {funds}, can't be split evenly between the creation fees of {pools} pools"
+    )]
+    UnevenPoolCreationFees { pools: usize, funds: String },
+
+    #[error("There is no pending admin transfer for the pool {pool_identifier}")]
+    NoPendingPoolAdmin { pool_identifier: String },
+
+    #[error("Liquidity provided to the pool {pool_identifier} must be locked for at least {min_unlocking_duration} seconds")]
+    MinLpLockNotMet {
+        pool_identifier: String,
+        min_unlocking_duration: u64,
+    },
+
+    #[error("The minimum unlocking duration {min} can't exceed the maximum {max}")]
+    InvalidUnlockingDurationBounds { min: u64, max: u64 },
+
+    #[error("The unlocking duration {unlocking_duration} is out of the bounds of the pool {pool_identifier}, which accepts between {min} and {max} seconds")]
+    UnlockingDurationOutOfBounds {
+        pool_identifier: String,
+        unlocking_duration: u64,
+        min: u64,
+        max: u64,
+    },
+
+    #[error("The maximum number of assets per pool must be between {min} and {ceiling}, got {max_assets_per_pool}")]
+    InvalidMaxAssetsPerPool {
+        max_assets_per_pool: u32,
+        min: usize,
+        ceiling: u32,
+    },
+
+    #[error("The farm manager {farm_manager_addr} isn't set as the successor of the current one")]
+    FarmManagerNotSuccessor { farm_manager_addr: String },
+
+    #[error("{strategy} is not a whitelisted yield strategy")]
+    UnknownYieldStrategy { strategy: String },
+
+    #[error("The yield strategy {strategy} doesn't accept {denom}")]
+    InvalidYieldStrategy { strategy: String, denom: String },
+
+    #[error("The yield strategy {strategy} is still used by a pool")]
+    YieldStrategyInUse { strategy: String },
+
+    #[error("The max deployed share must be lower than one, got {max_deployed_share}")]
+    InvalidMaxDeployedShare { max_deployed_share: Decimal },
+
+    #[error("The reserves of {denom} in the pool {pool_identifier} have no yield strategy")]
+    NoYieldStrategy {
+        pool_identifier: String,
+        denom: String,
+    },
+
+    #[error("At most {max_deployed} of the reserves can be deployed")]
+    MaxDeployedReservesExceeded { max_deployed: Coin },
+
+    #[error("Only {deployed} of the reserves are deployed")]
+    InsufficientDeployedReserves { deployed: Coin },
+
+    #[error(
+        "The reserves of {denom} the pool {pool_identifier} deployed have no yield to harvest"
+    )]
+    NothingToHarvest {
+        pool_identifier: String,
+        denom: String,
+    },
+
+    #[error("The tick spacing of a concentrated liquidity pool must be greater than zero")]
+    InvalidTickSpacing,
+
+    #[error("The pool {pool_identifier} is not a concentrated liquidity pool")]
+    NotConcentratedLiquidityPool { pool_identifier: String },
+
+    #[error("Concentrated liquidity pools don't support {operation}")]
+    UnsupportedForConcentratedLiquidity { operation: String },
+
+    #[error("The tick {tick} is out of the bounds of the concentrated liquidity pools")]
+    TickOutOfBounds { tick: i64 },
+
+    #[error("Invalid tick range [{lower_tick}, {upper_tick}), the lower tick must be below the upper one and both must be multiples of {tick_spacing}")]
+    InvalidTickRange {
+        lower_tick: i64,
+        upper_tick: i64,
+        tick_spacing: u64,
+    },
+
+    #[error("The initial price of the concentrated liquidity pool {pool_identifier} must be given by the first provision, and only by it")]
+    InvalidInitialPrice { pool_identifier: String },
+
+    #[error("The concentrated liquidity pool {pool_identifier} doesn't have enough liquidity for the swap")]
+    InsufficientConcentratedLiquidity { pool_identifier: String },
+
+    #[error("Concentrated liquidity position does not exist")]
+    UnExistingConcentratedPosition,
+
+    #[error("The liquidity must be greater than zero and at most {max}, got {liquidity}")]
+    InvalidConcentratedLiquidity { liquidity: Uint128, max: Uint128 },
+
+    #[error("The liquidity provided, {liquidity}, is below the minimum of {min_liquidity}")]
+    MinLiquidityAssertion {
+        min_liquidity: Uint128,
+        liquidity: Uint128,
+    },
+
+    #[error("The concentrated liquidity position {position_id} has no fees to collect")]
+    NothingToCollect { position_id: u64 },
+
+    #[error("The rate provider {rate_provider} reported an invalid rate")]
+    InvalidRate { rate_provider: String },
+
+    #[error("The flash swap was not repaid, {repaid_amount} was sent back instead of at least {repay_amount}")]
+    FlashSwapNotRepaid {
+        repay_amount: Uint128,
+        repaid_amount: Uint128,
+    },
+
+    #[error("The pool manager can't be reentered while a flash swap is in progress")]
+    FlashSwapInProgress,
+
+    #[error("The referral fee of {fee_bps} bps is above the maximum of {max_fee_bps} bps")]
+    ReferralFeeTooHigh { fee_bps: u16, max_fee_bps: u16 },
+
+    #[error("The maximum referral fee must be at most {ceiling} bps, got {max_referral_fee_bps}")]
+    InvalidMaxReferralFee {
+        max_referral_fee_bps: u16,
+        ceiling: u16,
+    },
+
+    #[error("Invalid price breaker config, the maximum deviation must be greater than zero and oracles can only be used by two-asset pools")]
+    InvalidPriceBreakerConfig,
+
+    #[error("The pool {pool_identifier} has no price breaker")]
+    NoPriceBreaker { pool_identifier: String },
+
+    #[error("The price breaker of the pool {pool_identifier} is tripped, swaps are halted")]
+    PriceBreakerTripped { pool_identifier: String },
+
+    #[error("The price breaker of the pool {pool_identifier} is not tripped")]
+    PriceBreakerNotTripped { pool_identifier: String },
+
+    #[error("The price deviates by {deviation} from the reference price, above the maximum of {max_deviation}")]
+    PriceDeviationExceeded {
+        deviation: Decimal256,
+        max_deviation: Decimal,
+    },
+
+    #[error("The price deviates by {deviation} from the reference price, within the maximum of {max_deviation}")]
+    PriceDeviationWithinBounds {
+        deviation: Decimal256,
+        max_deviation: Decimal,
+    },
+
+    #[error("Invalid fee discount tiers, there must be between 1 and {max_tiers} tiers with nonzero minimum stakes and discounts of at most 100%, both strictly ascending")]
+    InvalidFeeDiscountTiers { max_tiers: usize },
+
+    #[error("Invalid split weights, provide one weight per pool, adding up to more than zero")]
+    InvalidSplitWeights,
+
+    #[error("Invalid maximum number of hops, it must be between 1 and {max}")]
+    InvalidMaxHops { max: u32 },
+
+    #[error("No route found from {offer_denom} to {ask_denom}")]
+    NoRouteFound {
+        offer_denom: String,
+        ask_denom: String,
+    },
+
+    #[error("Invalid hop limits, provide one for each of the {operations} swap operations")]
+    InvalidHopLimits { operations: usize },
+
+    #[error("The swap adapter {adapter} isn't whitelisted")]
+    SwapAdapterNotWhitelisted { adapter: String },
+
+    #[error("{operation} isn't supported for routes through swap adapters")]
+    UnsupportedForSwapAdapter { operation: String },
 }
 
 impl From<semver::Error> for ContractError {
@@ -0,0 +1,38 @@
+use cosmwasm_std::{Attribute, IbcBasicResponse, Response};
+
+use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
+
+/// The attributes renamed by the current schema version, as pairs of their current and previous
+/// keys. They're emitted under both keys for one schema version, so indexers parsing the previous
+/// schema keep working until they're updated. Entries are dropped on the next version bump.
+const RENAMED_ATTRIBUTES: &[(&str, &str)] = &[];
+
+/// Tags the event of the given response with the schema version of its attributes.
+pub(crate) fn with_schema_version(response: Response) -> Response {
+    let attributes = get_schema_attributes(&response.attributes);
+    response.add_attributes(attributes)
+}
+
+/// Tags the event of the given IBC response with the schema version of its attributes.
+pub(crate) fn with_schema_version_ibc(response: IbcBasicResponse) -> IbcBasicResponse {
+    let attributes = get_schema_attributes(&response.attributes);
+    response.add_attributes(attributes)
+}
+
+/// Gets the attributes to append to the given ones: the attributes renamed by the current schema
+/// under their previous key, followed by the schema version.
+fn get_schema_attributes(attributes: &[Attribute]) -> Vec<Attribute> {
+    attributes
+        .iter()
+        .filter_map(|attribute| {
+            RENAMED_ATTRIBUTES
+                .iter()
+                .find(|(key, _)| *key == attribute.key)
+                .map(|(_, previous_key)| Attribute::new(*previous_key, attribute.value.clone()))
+        })
+        .chain(std::iter::once(Attribute::new(
+            "schema_version",
+            EVENT_SCHEMA_VERSION,
+        )))
+        .collect()
+}
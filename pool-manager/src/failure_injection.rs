@@ -0,0 +1,165 @@
+//! Failure injection for chaos testing, compiled in with the `failure-injection` feature for
+//! integration tests and testnet drills only.
+//!
+//! The chain injects failures with the `UpdateFailureInjection` sudo message, so error and
+//! rollback paths which are otherwise nearly impossible to trigger can be exercised against the
+//! real contract:
+//!
+//! - `stableswap_convergence` makes the stableswap solvers never converge, failing with
+//!   [ContractError::ConvergeError] once they run out of iterations.
+//! - `bank_sends` makes every execute sending funds fail in the bank module, by adding a send of a
+//!   denom the contract doesn't hold. Replies are left untouched, so the rollbacks they perform
+//!   on failed submessages can be observed.
+//! - `replies` makes the replies with the given ids fail. Replies can't be delayed within a
+//!   transaction, so failing them is how a reply that never completes is simulated.
+//!
+//! Without the feature, the sudo message is rejected and the injection points compile to nothing.
+
+use cosmwasm_std::{Response, Storage};
+
+#[cfg(feature = "failure-injection")]
+use cosmwasm_std::{coin, Addr, BankMsg, CosmosMsg};
+
+use mantra_dex_std::pool_manager::FailureInjection;
+
+#[cfg(feature = "failure-injection")]
+use crate::state::FAILURE_INJECTION;
+use crate::ContractError;
+
+/// The denom of the send injected into executes sending funds, which the contract never holds.
+#[cfg(feature = "failure-injection")]
+const INJECTED_FAILURE_DENOM: &str = "injected/failure";
+
+#[cfg(feature = "failure-injection")]
+thread_local! {
+    /// The failures injected into the current execution, loaded from the storage on entry.
+    static INJECTED_FAILURES: std::cell::RefCell<Option<FailureInjection>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Sets the failures to inject into the contract, replacing the previous ones.
+#[cfg(feature = "failure-injection")]
+pub(crate) fn update_failure_injection(
+    storage: &mut dyn Storage,
+    failures: FailureInjection,
+) -> Result<Response, ContractError> {
+    FAILURE_INJECTION.save(storage, &failures)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_failure_injection".to_string()),
+        (
+            "stableswap_convergence",
+            failures.stableswap_convergence.to_string(),
+        ),
+        ("bank_sends", failures.bank_sends.to_string()),
+        (
+            "replies",
+            failures
+                .replies
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
+
+#[cfg(not(feature = "failure-injection"))]
+pub(crate) fn update_failure_injection(
+    _storage: &mut dyn Storage,
+    _failures: FailureInjection,
+) -> Result<Response, ContractError> {
+    Err(ContractError::FailureInjectionUnavailable)
+}
+
+/// Loads the failures to inject into the current execution. Must be called by every entry point
+/// reaching an injection point.
+#[cfg(feature = "failure-injection")]
+pub(crate) fn load_injected_failures(storage: &dyn Storage) -> Result<(), ContractError> {
+    let failures = FAILURE_INJECTION.may_load(storage)?;
+    INJECTED_FAILURES.with(|injected| *injected.borrow_mut() = failures);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "failure-injection"))]
+#[inline(always)]
+pub(crate) fn load_injected_failures(_storage: &dyn Storage) -> Result<(), ContractError> {
+    Ok(())
+}
+
+/// Whether the stableswap solvers must not converge.
+#[cfg(feature = "failure-injection")]
+pub(crate) fn is_convergence_failure_injected() -> bool {
+    INJECTED_FAILURES.with(|injected| {
+        injected
+            .borrow()
+            .as_ref()
+            .is_some_and(|failures| failures.stableswap_convergence)
+    })
+}
+
+#[cfg(not(feature = "failure-injection"))]
+#[inline(always)]
+pub(crate) fn is_convergence_failure_injected() -> bool {
+    false
+}
+
+/// Makes the given execute response fail in the bank module if it sends funds and bank send
+/// failures are injected.
+#[cfg(feature = "failure-injection")]
+pub(crate) fn with_injected_bank_send_failure(response: Response, contract: &Addr) -> Response {
+    let injected = INJECTED_FAILURES.with(|injected| {
+        injected
+            .borrow()
+            .as_ref()
+            .is_some_and(|failures| failures.bank_sends)
+    });
+    let sends_funds = response
+        .messages
+        .iter()
+        .any(|sub_msg| matches!(sub_msg.msg, CosmosMsg::Bank(BankMsg::Send { .. })));
+
+    if !(injected && sends_funds) {
+        return response;
+    }
+
+    response.add_message(BankMsg::Send {
+        to_address: contract.to_string(),
+        amount: vec![coin(1, INJECTED_FAILURE_DENOM)],
+    })
+}
+
+#[cfg(not(feature = "failure-injection"))]
+#[inline(always)]
+pub(crate) fn with_injected_bank_send_failure(
+    response: Response,
+    _contract: &cosmwasm_std::Addr,
+) -> Response {
+    response
+}
+
+/// Fails the reply with the given id if its failure is injected.
+#[cfg(feature = "failure-injection")]
+pub(crate) fn assert_reply_not_failed(reply_id: u64) -> Result<(), ContractError> {
+    let injected = INJECTED_FAILURES.with(|injected| {
+        injected
+            .borrow()
+            .as_ref()
+            .is_some_and(|failures| failures.replies.contains(&reply_id))
+    });
+
+    if injected {
+        return Err(ContractError::InjectedFailure {
+            failure: format!("reply {reply_id}"),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "failure-injection"))]
+#[inline(always)]
+pub(crate) fn assert_reply_not_failed(_reply_id: u64) -> Result<(), ContractError> {
+    Ok(())
+}
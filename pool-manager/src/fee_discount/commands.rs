@@ -0,0 +1,65 @@
+use cosmwasm_std::{ensure, Decimal, DepsMut, MessageInfo, Response};
+
+use mantra_dex_std::pool_manager::FeeDiscountConfig;
+
+use crate::state::FEE_DISCOUNTS;
+use crate::ContractError;
+
+/// The most tiers the fee discounts can have.
+pub const MAX_FEE_DISCOUNT_TIERS: usize = 10;
+
+/// Sets or removes the tiers of the swap fee discounts, along with the staking contract the stake
+/// of the traders is queried from. Only the owner can do this.
+pub fn update_fee_discounts(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<FeeDiscountConfig>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![("action", "update_fee_discounts".to_string())];
+
+    match config {
+        Some(config) => {
+            deps.api.addr_validate(&config.staking_contract)?;
+
+            // the tiers must be sorted by ascending minimum stake, each discounting more than the
+            // previous one
+            ensure!(
+                !config.tiers.is_empty()
+                    && config.tiers.len() <= MAX_FEE_DISCOUNT_TIERS
+                    && config.tiers.windows(2).all(|tiers| {
+                        tiers[0].min_stake < tiers[1].min_stake
+                            && tiers[0].discount < tiers[1].discount
+                    })
+                    && config.tiers.iter().all(|tier| {
+                        !tier.min_stake.is_zero()
+                            && !tier.discount.is_zero()
+                            && tier.discount <= Decimal::one()
+                    }),
+                ContractError::InvalidFeeDiscountTiers {
+                    max_tiers: MAX_FEE_DISCOUNT_TIERS,
+                }
+            );
+
+            attributes.push(("staking_contract", config.staking_contract.clone()));
+            attributes.push((
+                "tiers",
+                config
+                    .tiers
+                    .iter()
+                    .map(|tier| format!("{}:{}", tier.min_stake, tier.discount))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ));
+            FEE_DISCOUNTS.save(deps.storage, &config)?;
+        }
+        None => {
+            attributes.push(("removed", "true".to_string()));
+            FEE_DISCOUNTS.remove(deps.storage);
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
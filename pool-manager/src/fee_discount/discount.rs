@@ -0,0 +1,65 @@
+use cosmwasm_std::{Addr, Decimal, Deps};
+
+use mantra_dex_std::pool_manager::{Fee, PoolFee};
+use mantra_dex_std::staking::{QueryMsg, StakeResponse};
+
+use crate::state::FEE_DISCOUNTS;
+use crate::ContractError;
+
+/// Gets the discount on the swap fees the given trader is entitled to, i.e. the one of the highest
+/// tier its stake in the staking contract reaches. Returns zero if no tiers are set.
+///
+/// A staking contract failing to report the stake of the trader is treated as no stake, so it can't
+/// halt the swaps of the pools.
+pub(crate) fn get_fee_discount(deps: Deps, trader: &Addr) -> Result<Decimal, ContractError> {
+    let Some(fee_discounts) = FEE_DISCOUNTS.may_load(deps.storage)? else {
+        return Ok(Decimal::zero());
+    };
+
+    let Ok(StakeResponse { amount }) = deps.querier.query_wasm_smart(
+        &fee_discounts.staking_contract,
+        &QueryMsg::Stake {
+            address: trader.to_string(),
+        },
+    ) else {
+        return Ok(Decimal::zero());
+    };
+
+    // the tiers are sorted by ascending minimum stake
+    Ok(fee_discounts
+        .tiers
+        .iter()
+        .rev()
+        .find(|tier| amount >= tier.min_stake)
+        .map(|tier| tier.discount)
+        .unwrap_or_default())
+}
+
+/// Applies the given discount to each of the fees of a pool, so the trader is charged less of
+/// every one of them.
+pub(crate) fn apply_fee_discount(
+    pool_fees: &PoolFee,
+    discount: Decimal,
+) -> Result<PoolFee, ContractError> {
+    if discount.is_zero() {
+        return Ok(pool_fees.clone());
+    }
+
+    let remaining = Decimal::one().checked_sub(discount)?;
+    let discounted = |fee: &Fee| -> Result<Fee, ContractError> {
+        Ok(Fee {
+            share: fee.share.checked_mul(remaining)?,
+        })
+    };
+
+    Ok(PoolFee {
+        protocol_fee: discounted(&pool_fees.protocol_fee)?,
+        swap_fee: discounted(&pool_fees.swap_fee)?,
+        burn_fee: discounted(&pool_fees.burn_fee)?,
+        extra_fees: pool_fees
+            .extra_fees
+            .iter()
+            .map(discounted)
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
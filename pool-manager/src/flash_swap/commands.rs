@@ -0,0 +1,235 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Binary, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    Storage, SubMsg, Uint128, WasmMsg,
+};
+
+use mantra_dex_std::coin::burn_coin_msg;
+use mantra_dex_std::common::validate_addr_or_default;
+
+use crate::contract::FLASH_SWAP_REPLY_ID;
+use crate::permissioned::commands::assert_pool_access;
+use crate::queries::query_reverse_simulation;
+use crate::state::{get_pool_by_identifier, FlashSwapBuffer, CONFIG, FLASH_SWAP_BUFFER};
+use crate::swap::perform_swap::perform_swap;
+use crate::ContractError;
+
+/// Sends `ask_asset` out of the given pool to the receiver before anything is paid for it, then
+/// executes `callback` on the receiver. By the time the callback returns, the receiver must have
+/// sent the pool manager at least the amount of `offer_asset_denom` a swap for `ask_asset` takes,
+/// as reverse simulated. The repayment is swapped in the reply, which reverts the whole flash swap
+/// unless the swap returns at least `ask_asset`, so the invariant of the pool holds. Anything
+/// returned on top of `ask_asset`, i.e. from overpaying, goes to the receiver.
+#[allow(clippy::too_many_arguments)]
+pub fn flash_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    ask_asset: Coin,
+    offer_asset_denom: String,
+    max_spread: Option<Decimal>,
+    receiver: Option<String>,
+    callback: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+    ensure!(
+        offer_asset_denom != ask_asset.denom,
+        ContractError::SameAsset
+    );
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        [&ask_asset.denom, &offer_asset_denom]
+            .iter()
+            .all(|denom| pool.assets.iter().any(|asset| &asset.denom == *denom)),
+        ContractError::AssetMismatch
+    );
+    assert_pool_access(deps.storage, &env, &pool_identifier, &info.sender)?;
+
+    // the repayment is rounded up by one unit so the swap doesn't fall short due to rounding
+    let repay_amount = query_reverse_simulation(
+        deps.as_ref(),
+        &env,
+        ask_asset.clone(),
+        offer_asset_denom.clone(),
+        pool_identifier.clone(),
+    )?
+    .offer_amount
+    .checked_add(Uint128::one())?;
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+    let offer_balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &offer_asset_denom)?
+        .amount;
+
+    FLASH_SWAP_BUFFER.save(
+        deps.storage,
+        &FlashSwapBuffer {
+            sender: info.sender.clone(),
+            receiver: receiver.clone(),
+            pool_identifier: pool_identifier.clone(),
+            ask_asset: ask_asset.clone(),
+            offer_asset_denom: offer_asset_denom.clone(),
+            repay_amount,
+            offer_balance_before,
+            max_spread,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![ask_asset.clone()],
+        })
+        .add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: receiver.to_string(),
+                msg: callback,
+                funds: vec![],
+            },
+            FLASH_SWAP_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "flash_swap".to_string()),
+            ("sender", info.sender.into_string()),
+            ("receiver", receiver.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("ask_asset", ask_asset.to_string()),
+            ("offer_denom", offer_asset_denom),
+            ("repay_amount", repay_amount.to_string()),
+        ]))
+}
+
+/// Settles the flash swap once the callback of the receiver is done, swapping what it repaid for
+/// the ask asset it was sent.
+pub(crate) fn settle_flash_swap(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let FlashSwapBuffer {
+        sender,
+        receiver,
+        pool_identifier,
+        ask_asset,
+        offer_asset_denom,
+        repay_amount,
+        offer_balance_before,
+        max_spread,
+    } = FLASH_SWAP_BUFFER.load(deps.storage)?;
+    FLASH_SWAP_BUFFER.remove(deps.storage);
+
+    let repaid_amount = deps
+        .querier
+        .query_balance(&env.contract.address, &offer_asset_denom)?
+        .amount
+        .saturating_sub(offer_balance_before);
+    ensure!(
+        repaid_amount >= repay_amount,
+        ContractError::FlashSwapNotRepaid {
+            repay_amount,
+            repaid_amount,
+        }
+    );
+
+    let swap_result = perform_swap(
+        deps.branch(),
+        &env,
+        &sender,
+        coin(repaid_amount.u128(), &offer_asset_denom),
+        ask_asset.denom.clone(),
+        pool_identifier.clone(),
+        None,
+        max_spread,
+        None,
+    )?;
+
+    // the ask asset is already out, so the swap must return at least as much for the invariant of
+    // the pool to hold
+    ensure!(
+        swap_result.return_asset.amount >= ask_asset.amount,
+        ContractError::MinimumReceiveAssertion {
+            minimum_receive: ask_asset.amount,
+            swap_amount: swap_result.return_asset.amount,
+        }
+    );
+
+    // recall the deployed reserves the swap pays out first
+    let mut messages: Vec<CosmosMsg> = swap_result.recall_msgs;
+
+    let surplus_amount = swap_result
+        .return_asset
+        .amount
+        .checked_sub(ask_asset.amount)?;
+    if !surplus_amount.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: receiver.to_string(),
+                amount: vec![coin(surplus_amount.u128(), &ask_asset.denom)],
+            }
+            .into(),
+        );
+    }
+
+    if !swap_result.burn_fee_asset.amount.is_zero() {
+        messages.push(burn_coin_msg(swap_result.burn_fee_asset.clone()));
+    }
+
+    if !swap_result.protocol_fee_asset.amount.is_zero() {
+        let config = CONFIG.load(deps.storage)?;
+        messages.push(
+            BankMsg::Send {
+                to_address: config.fee_collector_addr.to_string(),
+                amount: vec![swap_result.protocol_fee_asset.clone()],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_submessages(swap_result.price_hook_msgs)
+        .add_attributes(vec![
+            ("action", "settle_flash_swap".to_string()),
+            ("sender", sender.into_string()),
+            ("receiver", receiver.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("offer_denom", offer_asset_denom),
+            ("ask_denom", ask_asset.denom),
+            ("offer_amount", repaid_amount.to_string()),
+            ("return_amount", swap_result.return_asset.amount.to_string()),
+            ("surplus_amount", surplus_amount.to_string()),
+            ("spread_amount", swap_result.spread_amount.to_string()),
+            (
+                "swap_fee_amount",
+                swap_result.swap_fee_asset.amount.to_string(),
+            ),
+            (
+                "protocol_fee_amount",
+                swap_result.protocol_fee_asset.amount.to_string(),
+            ),
+            (
+                "burn_fee_amount",
+                swap_result.burn_fee_asset.amount.to_string(),
+            ),
+            (
+                "extra_fees_amount",
+                swap_result.extra_fees_asset.amount.to_string(),
+            ),
+        ]))
+}
+
+/// Fails while a flash swap is waiting on the callback of its receiver, so the pool manager can't
+/// be reentered until the flash swap is settled. Otherwise, funds sent to the pool manager by the
+/// callback, e.g. to swap or deposit, would be mistaken for the repayment.
+pub(crate) fn assert_no_flash_swap_in_progress(storage: &dyn Storage) -> Result<(), ContractError> {
+    ensure!(
+        !FLASH_SWAP_BUFFER.exists(storage),
+        ContractError::FlashSwapInProgress
+    );
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Gas checkpoints for the expensive paths of the contract, compiled in with the
+//! `gas-instrumentation` feature for testnet builds only.
+//!
+//! Contracts can't read the gas meter of the chain, so checkpoints count the units of work which
+//! dominate the gas spent by each section instead, e.g. the Newton-Raphson iterations of the
+//! stableswap solver or the hops of a route. They're emitted as `gas.<section>` attributes, holding
+//! the calls to the section and the units of work they took, so they can be compared across
+//! transactions and against their total gas. Without the feature, checkpoints compile to nothing.
+
+use cosmwasm_std::Response;
+
+#[cfg(feature = "gas-instrumentation")]
+thread_local! {
+    /// The checkpoints recorded by the current execution, as the section along with its calls and
+    /// units of work, in the order sections were first reached.
+    static CHECKPOINTS: std::cell::RefCell<Vec<(&'static str, u64, u64)>> =
+        const { std::cell::RefCell::new(vec![]) };
+}
+
+/// Records that the given section was reached, taking the given units of work.
+#[cfg(feature = "gas-instrumentation")]
+pub(crate) fn checkpoint(section: &'static str, units: u64) {
+    CHECKPOINTS.with(|checkpoints| {
+        let mut checkpoints = checkpoints.borrow_mut();
+        match checkpoints.iter_mut().find(|(key, _, _)| *key == section) {
+            Some((_, calls, total_units)) => {
+                *calls += 1;
+                *total_units += units;
+            }
+            None => checkpoints.push((section, 1, units)),
+        }
+    });
+}
+
+#[cfg(not(feature = "gas-instrumentation"))]
+#[inline(always)]
+pub(crate) fn checkpoint(_section: &'static str, _units: u64) {}
+
+/// Clears the checkpoints left over by a previous execution of the same instance.
+#[cfg(feature = "gas-instrumentation")]
+pub(crate) fn reset_checkpoints() {
+    CHECKPOINTS.with(|checkpoints| checkpoints.borrow_mut().clear());
+}
+
+#[cfg(not(feature = "gas-instrumentation"))]
+#[inline(always)]
+pub(crate) fn reset_checkpoints() {}
+
+/// Adds the checkpoints recorded by the current execution to the given response.
+#[cfg(feature = "gas-instrumentation")]
+pub(crate) fn with_checkpoints(response: Response) -> Response {
+    let checkpoints =
+        CHECKPOINTS.with(|checkpoints| std::mem::take(&mut *checkpoints.borrow_mut()));
+
+    response.add_attributes(checkpoints.into_iter().map(|(section, calls, units)| {
+        (
+            format!("gas.{section}"),
+            format!("calls={calls}, units={units}"),
+        )
+    }))
+}
+
+#[cfg(not(feature = "gas-instrumentation"))]
+#[inline(always)]
+pub(crate) fn with_checkpoints(response: Response) -> Response {
+    response
+}
+
+#[cfg(all(test, feature = "gas-instrumentation"))]
+mod tests {
+    use cosmwasm_std::{Attribute, Response};
+
+    use super::{checkpoint, reset_checkpoints, with_checkpoints};
+
+    #[test]
+    fn aggregates_checkpoints_per_section() {
+        checkpoint("stale", 1);
+        reset_checkpoints();
+
+        checkpoint("stableswap_d", 5);
+        checkpoint("swap", 1);
+        checkpoint("stableswap_d", 3);
+
+        let response = with_checkpoints(Response::new());
+        assert_eq!(
+            response.attributes,
+            vec![
+                Attribute::new("gas.stableswap_d", "calls=2, units=8"),
+                Attribute::new("gas.swap", "calls=1, units=1"),
+            ]
+        );
+
+        // the checkpoints are drained into the response
+        assert!(with_checkpoints(Response::new()).attributes.is_empty());
+    }
+}
@@ -2,16 +2,29 @@ use std::ops::Mul;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, DepsMut, Env, MessageInfo, StdError,
-    StdResult, Uint128, Uint256, Uint512,
+    coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, DepsMut, Env, HexBinary, MessageInfo,
+    StdError, StdResult, Storage, Uint128, Uint256, Uint512,
 };
 use mantra_dex_std::coin::{aggregate_coins, FACTORY_MAX_SUBDENOM_SIZE};
 use mantra_dex_std::constants::LP_SYMBOL;
 use mantra_dex_std::fee::PoolFee;
-use mantra_dex_std::pool_manager::{PoolInfo, PoolType, SimulationResponse};
+use mantra_dex_std::pool_manager::{
+    ImbalanceFee, PoolFeeCaps, PoolInfo, PoolType, SimulationResponse, SwapOperation,
+    UnlockingDurationBounds,
+};
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
+use crate::failure_injection;
+use crate::gas;
+use crate::manager::commands::CANONICAL_POOL_ID_PREFIX;
 use crate::math::Decimal256Helper;
+use crate::normalization::{
+    denormalize_amount, from_solver_amount, get_normalized_precision, normalize_amount,
+    normalize_reserves, Rounding,
+};
+use crate::pegged::convert_at_pegged_rate;
+use crate::state::{get_pool_by_identifier, CONFIG, UNLOCKING_DURATION_BOUNDS};
 
 /// The amount of iterations to perform when calculating the Newton-Raphson approximation.
 const NEWTON_ITERATIONS: u64 = 32;
@@ -27,16 +40,136 @@ pub struct SwapResult {
     pub amount_swapped: Uint128,
 }
 
+/// Computes the multipliers that scale the amounts of each asset up to the largest decimals among
+/// them, i.e. `10^(max_decimals - asset_decimals)`. They are stored in the [PoolInfo] when the
+/// pool is created, so the swap and liquidity math don't recompute them.
+pub fn get_asset_scaling_factors(asset_decimals: &[u8]) -> Result<Vec<Uint128>, ContractError> {
+    let max_decimals = asset_decimals.iter().max().copied().unwrap_or(0);
+
+    asset_decimals
+        .iter()
+        .map(|decimals| {
+            10u128
+                .checked_pow(u32::from(max_decimals - decimals))
+                .map(Uint128::new)
+                .ok_or(ContractError::DecimalOverflow)
+        })
+        .collect()
+}
+
+/// Whether the given pool type trades along the stableswap curve, i.e. it's either a stableswap or
+/// a composable stableswap pool.
+pub fn is_stableswap(pool_type: &PoolType) -> bool {
+    matches!(
+        pool_type,
+        PoolType::StableSwap { .. } | PoolType::ComposableStableSwap { .. }
+    )
+}
+
+/// Whether the given pool type is a concentrated liquidity pool, whose liquidity is provided within
+/// price ranges rather than along the whole curve.
+pub fn is_concentrated(pool_type: &PoolType) -> bool {
+    matches!(pool_type, PoolType::ConcentratedLiquidity { .. })
+}
+
+/// Gets the weight of each asset of the given pool, i.e. the rate it's pegged at relative to the
+/// unit of the pool. Only composable stableswap pools have weights other than one.
+pub(crate) fn get_asset_weights(pool_info: &PoolInfo) -> Vec<Decimal> {
+    match &pool_info.pool_type {
+        PoolType::ComposableStableSwap { weights, .. } => weights.clone(),
+        _ => vec![Decimal::one(); pool_info.assets.len()],
+    }
+}
+
+/// Scales the given assets of the pool up to the largest decimals of its assets, using the
+/// scaling factors of the pool, and weighs them by the asset weights.
+pub(crate) fn scale_assets(
+    pool_info: &PoolInfo,
+    assets: &[Coin],
+) -> Result<Vec<Coin>, ContractError> {
+    let weights = get_asset_weights(pool_info);
+
+    assets
+        .iter()
+        .map(|asset| {
+            let index = pool_info
+                .asset_denoms
+                .iter()
+                .position(|denom| denom == &asset.denom)
+                .ok_or(ContractError::AssetMismatch)?;
+
+            Ok(coin(
+                asset
+                    .amount
+                    .checked_mul(pool_info.asset_scaling_factors[index])?
+                    .checked_mul_floor(weights[index])?
+                    .u128(),
+                &asset.denom,
+            ))
+        })
+        .collect()
+}
+
+/// Gets the reserves of the given pool scaled up to the largest decimals of its assets and
+/// weighed by the asset weights.
+pub(crate) fn get_scaled_reserves(pool_info: &PoolInfo) -> Result<Vec<Uint256>, ContractError> {
+    pool_info
+        .assets
+        .iter()
+        .zip(pool_info.asset_scaling_factors.iter())
+        .zip(get_asset_weights(pool_info))
+        .map(|((asset, scaling_factor), weight)| {
+            Ok(Uint256::from(asset.amount)
+                .checked_mul(Uint256::from(*scaling_factor))?
+                .checked_mul_floor(weight)?)
+        })
+        .collect()
+}
+
+/// The intermediate values of the Newton-Raphson iterations of the stableswap math, recorded when
+/// replaying a computation to debug it.
+#[derive(Default)]
+pub(crate) struct StableSwapTrace {
+    pub d_iterations: Vec<Decimal256>,
+    pub y_iterations: Vec<Uint256>,
+}
+
+/// Hashes the inputs of a stableswap computation, so a convergence failure reported on chain can
+/// be matched with its replay in the debug query.
+pub(crate) fn hash_stableswap_inputs(
+    pools: &[Decimal256],
+    amp: &u64,
+    precision: u8,
+    swap: Option<(usize, usize, Decimal256, &StableSwapDirection)>,
+) -> String {
+    let inputs = format!("{pools:?}|{amp}|{precision}|{swap:?}");
+
+    HexBinary::from(Sha256::digest(inputs.as_bytes()).as_slice()).to_hex()
+}
+
+/// Calculates the stableswap invariant `D` of the given normalized pool balances.
 fn calculate_stableswap_d(
-    n_coins: Uint256,
-    offer_pool: Decimal256,
-    ask_pool: Decimal256,
+    pools: &[Decimal256],
+    amp: &u64,
+    precision: u8,
+) -> Result<Decimal256, ContractError> {
+    calculate_stableswap_d_with_trace(pools, amp, precision, None)
+}
+
+/// Calculates the stableswap invariant `D` as [calculate_stableswap_d] does, recording every
+/// iteration in the given trace.
+fn calculate_stableswap_d_with_trace(
+    pools: &[Decimal256],
     amp: &u64,
     precision: u8,
+    mut trace: Option<&mut StableSwapTrace>,
 ) -> Result<Decimal256, ContractError> {
+    let n_coins = Uint256::from(pools.len() as u128);
     let n_coins_decimal = Decimal256::from_ratio(n_coins, Uint256::one());
 
-    let sum_pools = offer_pool.checked_add(ask_pool)?;
+    let sum_pools = pools
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, pool| acc.checked_add(*pool))?;
     if sum_pools.is_zero() {
         // there was nothing to swap, return `0`.
         return Ok(Decimal256::zero());
@@ -45,93 +178,156 @@ fn calculate_stableswap_d(
     // ann = amp * n_coins
     let ann = Decimal256::from_ratio(Uint256::from_u128((*amp).into()).checked_mul(n_coins)?, 1u8);
 
+    // these terms don't change between iterations
+    let mul_pools = pools
+        .iter()
+        .map(|pool| pool.checked_mul(n_coins_decimal))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ann_sum_pools = ann.checked_mul(sum_pools)?;
+    let ann_minus_one = ann.checked_sub(Decimal256::one())?;
+    let n_coins_plus_one = n_coins_decimal.checked_add(Decimal256::one())?;
+    let tolerance = Decimal256::decimal_with_precision(1u8, precision)?;
+
     // perform Newton-Raphson method
     let mut current_d = sum_pools;
-    for _ in 0..NEWTON_ITERATIONS {
+    let mut last_delta = Decimal256::zero();
+    for iteration in 1..=NEWTON_ITERATIONS {
         // multiply each pool by the number of coins
         // and multiply together
-        let new_d = [offer_pool, ask_pool]
-            .into_iter()
-            .try_fold::<_, _, Result<_, ContractError>>(current_d, |acc, pool| {
-                let mul_pools = pool.checked_mul(n_coins_decimal)?;
-                acc.checked_multiply_ratio(current_d, mul_pools)
+        let new_d = mul_pools
+            .iter()
+            .try_fold::<_, _, Result<_, ContractError>>(current_d, |acc, mul_pool| {
+                acc.checked_multiply_ratio(current_d, *mul_pool)
             })?;
 
         let old_d = current_d;
         // current_d = ((ann * sum_pools + new_d * n_coins) * current_d) / ((ann - 1) * current_d + (n_coins + 1) * new_d)
-        current_d = (ann
-            .checked_mul(sum_pools)?
+        current_d = (ann_sum_pools
             .checked_add(new_d.checked_mul(n_coins_decimal)?)?
             .checked_mul(current_d)?)
         .checked_div(
-            (ann.checked_sub(Decimal256::one())?
+            ann_minus_one
                 .checked_mul(current_d)?
-                .checked_add(
-                    n_coins_decimal
-                        .checked_add(Decimal256::one())?
-                        .checked_mul(new_d)?,
-                ))?,
+                .checked_add(n_coins_plus_one.checked_mul(new_d)?)?,
         )?;
 
-        if current_d >= old_d {
-            if current_d.checked_sub(old_d)? <= Decimal256::decimal_with_precision(1u8, precision)?
-            {
-                // success
-                return Ok(current_d);
-            }
-        } else if old_d.checked_sub(current_d)?
-            <= Decimal256::decimal_with_precision(1u8, precision)?
-        {
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.d_iterations.push(current_d);
+        }
+
+        last_delta = current_d.abs_diff(old_d);
+        if last_delta <= tolerance && !failure_injection::is_convergence_failure_injected() {
             // success
+            gas::checkpoint("stableswap_d", iteration);
             return Ok(current_d);
         }
     }
 
     // completed iterations
     // but we never approximated correctly
-    Err(ContractError::ConvergeError)
+    Err(ContractError::ConvergeError {
+        stage: "d".to_string(),
+        iterations: NEWTON_ITERATIONS,
+        last_delta: last_delta.to_string(),
+        inputs_hash: hash_stableswap_inputs(pools, amp, precision, None),
+    })
 }
 
 /// Determines the direction of `offer_pool` -> `ask_pool`.
 ///
-/// In a `ReverseSimulate`, we subtract the `offer_pool` from `offer_amount` to get the pool sum.
+/// In a `ReverseSimulate`, we subtract the `offer_amount` from the `ask_pool` and solve for the
+/// new `offer_pool`.
 ///
-/// In a `Simulate`, we add the two.
+/// In a `Simulate`, we add the `offer_amount` to the `offer_pool` and solve for the new
+/// `ask_pool`.
+#[derive(Debug)]
 pub enum StableSwapDirection {
     Simulate,
     ReverseSimulate,
 }
 
-/// Calculates the new pool amount given the current pools and swap size.
+/// Calculates the new pool amount given the normalized balances of all the pools and the swap
+/// size, with the given precision.
 pub fn calculate_stableswap_y(
-    n_coins: Uint256,
-    offer_pool: Decimal256,
-    ask_pool: Decimal256,
+    pools: &[Decimal256],
+    offer_index: usize,
+    ask_index: usize,
     offer_amount: Decimal256,
     amp: &u64,
-    ask_precision: u8,
+    precision: u8,
     direction: StableSwapDirection,
 ) -> Result<Uint128, ContractError> {
+    calculate_stableswap_y_with_trace(
+        pools,
+        offer_index,
+        ask_index,
+        offer_amount,
+        amp,
+        precision,
+        direction,
+        None,
+    )
+}
+
+/// Calculates the new pool amount as [calculate_stableswap_y] does, recording every iteration,
+/// including the ones of the invariant, in the given trace.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn calculate_stableswap_y_with_trace(
+    pools: &[Decimal256],
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Decimal256,
+    amp: &u64,
+    precision: u8,
+    direction: StableSwapDirection,
+    mut trace: Option<&mut StableSwapTrace>,
+) -> Result<Uint128, ContractError> {
+    let n_coins = Uint256::from(pools.len() as u128);
     let ann = Uint256::from_u128((*amp).into()).checked_mul(n_coins)?;
 
-    let d = calculate_stableswap_d(n_coins, offer_pool, ask_pool, amp, ask_precision)?
-        .to_uint256_with_precision(u32::from(ask_precision))?;
+    let d = calculate_stableswap_d_with_trace(pools, amp, precision, trace.as_deref_mut())?
+        .to_uint256_with_precision(u32::from(precision))?;
+
+    // the pool changed by the swap, and the pool to solve for
+    let (known_index, unknown_index, known_pool) = match direction {
+        StableSwapDirection::Simulate => (
+            offer_index,
+            ask_index,
+            pools[offer_index].checked_add(offer_amount)?,
+        ),
+        StableSwapDirection::ReverseSimulate => (
+            ask_index,
+            offer_index,
+            pools[ask_index].checked_sub(offer_amount)?,
+        ),
+    };
 
-    let pool_sum = match direction {
-        StableSwapDirection::Simulate => offer_pool.checked_add(offer_amount)?,
-        StableSwapDirection::ReverseSimulate => ask_pool.checked_sub(offer_amount)?,
-    }
-    .to_uint256_with_precision(u32::from(ask_precision))?;
+    // sum and product terms over all the pools but the one to solve for
+    let mut pool_sum = Uint256::zero();
+    let mut c = d;
+    for (index, pool) in pools.iter().enumerate() {
+        if index == unknown_index {
+            continue;
+        }
 
-    let c = d
-        .checked_multiply_ratio(d, pool_sum.checked_mul(n_coins)?)?
-        .checked_multiply_ratio(d, ann.checked_mul(n_coins)?)?;
+        let pool = if index == known_index {
+            known_pool
+        } else {
+            *pool
+        };
+        let pool = pool.to_uint256_with_precision(u32::from(precision))?;
+
+        pool_sum = pool_sum.checked_add(pool)?;
+        c = c.checked_multiply_ratio(d, pool.checked_mul(n_coins)?)?;
+    }
+    let c = c.checked_multiply_ratio(d, ann.checked_mul(n_coins)?)?;
 
     let b = pool_sum.checked_add(d.checked_div(ann)?)?;
 
     // attempt to converge solution using Newton-Raphson method
     let mut y = d;
-    for _ in 0..NEWTON_ITERATIONS {
+    let mut last_delta = Uint256::zero();
+    for iteration in 1..=NEWTON_ITERATIONS {
         let previous_y = y;
         // y = (y^2 + c) / (2y + b - d)
         y = y
@@ -139,36 +335,50 @@ pub fn calculate_stableswap_y(
             .checked_add(c)?
             .checked_div(y.checked_add(y)?.checked_add(b)?.checked_sub(d)?)?;
 
-        if y >= previous_y {
-            if y.checked_sub(previous_y)? <= Uint256::one() {
-                return y.try_into().map_err(|_| ContractError::SwapOverflowError);
-            }
-        } else if y < previous_y && previous_y.checked_sub(y)? <= Uint256::one() {
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.y_iterations.push(y);
+        }
+
+        last_delta = y.abs_diff(previous_y);
+        if last_delta <= Uint256::one() && !failure_injection::is_convergence_failure_injected() {
+            gas::checkpoint("stableswap_y", iteration);
             return y.try_into().map_err(|_| ContractError::SwapOverflowError);
         }
     }
 
-    Err(ContractError::ConvergeError)
+    Err(ContractError::ConvergeError {
+        stage: "y".to_string(),
+        iterations: NEWTON_ITERATIONS,
+        last_delta: last_delta.to_string(),
+        inputs_hash: hash_stableswap_inputs(
+            pools,
+            amp,
+            precision,
+            Some((offer_index, ask_index, offer_amount, &direction)),
+        ),
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
-/// computes a swap
-#[allow(clippy::too_many_arguments)]
+/// computes a swap of `offer_amount` of the asset at `offer_index` for the asset at `ask_index`
+/// of the given pool. The imbalance fee, if any, only applies to stableswap pools, while the
+/// pegged rate is the oracle rate pegged pools trade at. Concentrated liquidity pools are swapped
+/// over their ticks instead, see [crate::concentrated::swap::compute_concentrated_swap].
 pub fn compute_swap(
-    n_coins: Uint256,
-    offer_pool: Uint128,
-    ask_pool: Uint128,
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
     offer_amount: Uint128,
     pool_fees: PoolFee,
-    swap_type: &PoolType,
-    offer_precision: u8,
-    ask_precision: u8,
+    imbalance_fee: Option<&ImbalanceFee>,
+    pegged_rate: Option<Decimal>,
 ) -> Result<SwapComputation, ContractError> {
-    let offer_pool: Uint256 = offer_pool.into();
-    let ask_pool: Uint256 = ask_pool.into();
+    let offer_pool: Uint256 = pool_info.assets[offer_index].amount.into();
+    let ask_pool: Uint256 = pool_info.assets[ask_index].amount.into();
+    let raw_offer_amount = offer_amount;
     let offer_amount: Uint256 = offer_amount.into();
 
-    match swap_type {
+    match &pool_info.pool_type {
         PoolType::ConstantProduct => {
             // offer => ask
             // ask_amount = (ask_pool * offer_amount / (offer_pool + offer_amount)) - swap_fee - protocol_fee - burn_fee
@@ -192,30 +402,51 @@ pub fn compute_swap(
                 fees_computation,
             )?)
         }
-        PoolType::StableSwap { amp } => {
-            let offer_pool = Decimal256::decimal_with_precision(offer_pool, offer_precision)?;
-            let ask_pool = Decimal256::decimal_with_precision(ask_pool, ask_precision)?;
-            let offer_amount = Decimal256::decimal_with_precision(offer_amount, offer_precision)?;
+        PoolType::StableSwap { amp } | PoolType::ComposableStableSwap { amp, .. } => {
+            // the stableswap math works on the amounts normalized to the precision of the pool
+            let pools = normalize_reserves(pool_info)?;
+            let offer_amount = normalize_amount(pool_info, offer_index, offer_amount)?;
 
             let new_pool = calculate_stableswap_y(
-                n_coins,
-                offer_pool,
-                ask_pool,
+                &pools,
+                offer_index,
+                ask_index,
                 offer_amount,
                 amp,
-                ask_precision,
+                get_normalized_precision(pool_info),
                 StableSwapDirection::Simulate,
             )?;
 
-            let return_amount = ask_pool
-                .to_uint256_with_precision(u32::from(ask_precision))?
-                .checked_sub(Uint256::from_uint128(new_pool))?;
+            // convert the new ask pool back into the ask asset, rounding it up so the return is
+            // rounded down in favor of the pool
+            let new_pool = denormalize_amount(
+                pool_info,
+                ask_index,
+                from_solver_amount(pool_info, new_pool)?,
+                Rounding::Up,
+            )?;
+            let return_amount = ask_pool.checked_sub(new_pool)?;
 
-            // the spread is the loss from 1:1 conversion
+            // the spread is the loss from the conversion at the pegged rate, 1:1 unless weighted
             // thus is it the offer_amount - return_amount
-            let spread_amount = offer_amount
-                .to_uint256_with_precision(u32::from(ask_precision))?
-                .saturating_sub(return_amount);
+            let spread_amount =
+                denormalize_amount(pool_info, ask_index, offer_amount, Rounding::Down)?
+                    .saturating_sub(return_amount);
+
+            let pool_fees = match imbalance_fee {
+                Some(imbalance_fee) => apply_imbalance_fee(
+                    pool_info,
+                    imbalance_fee,
+                    offer_index,
+                    ask_index,
+                    raw_offer_amount,
+                    return_amount
+                        .try_into()
+                        .map_err(|_| ContractError::SwapOverflowError)?,
+                    pool_fees,
+                )?,
+                None => pool_fees,
+            };
 
             let fees_computation = compute_fees(pool_fees, return_amount)?;
 
@@ -225,11 +456,89 @@ pub fn compute_swap(
                 fees_computation,
             )?)
         }
+        PoolType::Pegged { .. } => {
+            let rate = pegged_rate.ok_or(ContractError::PeggedRateUnavailable)?;
+
+            // pegged pools trade at the oracle rate, so there's no spread
+            let return_amount = convert_at_pegged_rate(
+                pool_info,
+                offer_index,
+                ask_index,
+                raw_offer_amount,
+                rate,
+                false,
+            )?;
+            ensure!(
+                return_amount < pool_info.assets[ask_index].amount,
+                ContractError::InsufficientPeggedPoolLiquidity {
+                    denom: pool_info.asset_denoms[ask_index].clone(),
+                }
+            );
+
+            let return_amount: Uint256 = return_amount.into();
+            let fees_computation = compute_fees(pool_fees, return_amount)?;
+
+            Ok(get_swap_computation(
+                return_amount,
+                Uint256::zero(),
+                fees_computation,
+            )?)
+        }
+        PoolType::ConcentratedLiquidity { .. } => {
+            Err(ContractError::UnsupportedForConcentratedLiquidity {
+                operation: "swaps against the reserves".to_string(),
+            })
+        }
     }
 }
 
+/// Adjusts the swap fee of a stableswap trade by the imbalance fee of the pool. Trades leaving the
+/// pool more imbalanced pay a surcharge growing with the post-trade imbalance, while trades
+/// restoring the balance get a discount proportional to the imbalance they remove. The imbalance
+/// is measured as the peg deviation of the pool, with `return_amount` taken before fees.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_imbalance_fee(
+    pool_info: &PoolInfo,
+    imbalance_fee: &ImbalanceFee,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    return_amount: Uint128,
+    mut pool_fees: PoolFee,
+) -> Result<PoolFee, ContractError> {
+    let mut pool_after_trade = pool_info.clone();
+    pool_after_trade.assets[offer_index].amount = pool_after_trade.assets[offer_index]
+        .amount
+        .checked_add(offer_amount)?;
+    pool_after_trade.assets[ask_index].amount = pool_after_trade.assets[ask_index]
+        .amount
+        .checked_sub(return_amount)?;
+
+    let imbalance_before = get_peg_deviation(pool_info)?;
+    let imbalance_after = get_peg_deviation(&pool_after_trade)?;
+
+    if imbalance_after > imbalance_before {
+        let surcharge = imbalance_fee
+            .surcharge_factor
+            .checked_mul(imbalance_after)?
+            .min(imbalance_fee.max_surcharge);
+        pool_fees.swap_fee.share = pool_fees.swap_fee.share.checked_add(surcharge)?;
+    } else {
+        let discount = imbalance_fee
+            .surcharge_factor
+            .checked_mul(imbalance_before - imbalance_after)?
+            .min(imbalance_fee.max_discount);
+        pool_fees.swap_fee.share = pool_fees.swap_fee.share.saturating_sub(discount);
+    }
+
+    Ok(pool_fees)
+}
+
 /// Computes the pool fees for a given (return) amount
-fn compute_fees(pool_fees: PoolFee, amount: Uint256) -> Result<FeesComputation, ContractError> {
+pub(crate) fn compute_fees(
+    pool_fees: PoolFee,
+    amount: Uint256,
+) -> Result<FeesComputation, ContractError> {
     let swap_fee_amount: Uint256 = pool_fees.swap_fee.compute(amount)?;
     let protocol_fee_amount: Uint256 = pool_fees.protocol_fee.compute(amount)?;
     let burn_fee_amount: Uint256 = pool_fees.burn_fee.compute(amount)?;
@@ -255,7 +564,7 @@ fn compute_fees(pool_fees: PoolFee, amount: Uint256) -> Result<FeesComputation,
 }
 
 /// Builds the swap computation struct, subtracting the fees from the return amount.
-fn get_swap_computation(
+pub(crate) fn get_swap_computation(
     return_amount: Uint256,
     spread_amount: Uint256,
     fees_computation: FeesComputation,
@@ -322,6 +631,7 @@ impl SwapComputation {
             protocol_fee_amount: self.protocol_fee_amount,
             burn_fee_amount: self.burn_fee_amount,
             extra_fees_amount: self.extra_fees_amount,
+            fee_discount: Decimal::zero(),
         }
     }
 }
@@ -428,7 +738,9 @@ pub fn assert_slippage_tolerance(
 
         // Ensure each prices are not dropped as much as slippage tolerance rate
         match pool_type {
-            PoolType::StableSwap { .. } => {
+            PoolType::StableSwap { .. }
+            | PoolType::ComposableStableSwap { .. }
+            | PoolType::Pegged { .. } => {
                 let pools_total: Uint256 = pools
                     .into_iter()
                     .fold(Uint256::zero(), |acc, x| acc.checked_add(x).unwrap());
@@ -462,6 +774,11 @@ pub fn assert_slippage_tolerance(
                     return Err(ContractError::MaxSlippageAssertion);
                 }
             }
+            PoolType::ConcentratedLiquidity { .. } => {
+                return Err(ContractError::UnsupportedForConcentratedLiquidity {
+                    operation: "fungible liquidity".to_string(),
+                });
+            }
         }
     }
 
@@ -520,6 +837,112 @@ pub fn validate_pool_identifier(identifier: &str) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Derives the canonical identifier of a pool from its sorted asset denoms, the label of its type
+/// and its swap fee, i.e. its fee tier, so it can be computed offline. It's the hex encoded first
+/// 16 bytes of the sha256 digest of `{denoms joined by ","}|{pool type label}|{swap fee}`, prefixed
+/// with [CANONICAL_POOL_ID_PREFIX].
+pub fn get_canonical_pool_identifier(
+    asset_denoms: &[String],
+    pool_type: &PoolType,
+    pool_fees: &PoolFee,
+) -> String {
+    let mut asset_denoms = asset_denoms.to_vec();
+    asset_denoms.sort();
+
+    let preimage = format!(
+        "{}|{}|{}",
+        asset_denoms.join(","),
+        pool_type.get_label(),
+        pool_fees.swap_fee.share
+    );
+    let digest = Sha256::digest(preimage.as_bytes());
+
+    format!(
+        "{CANONICAL_POOL_ID_PREFIX}{}",
+        HexBinary::from(&digest[..16]).to_hex()
+    )
+}
+
+/// Validates the fees of a pool against the caps defined in the config. Each fee component must
+/// not exceed its own cap, and the sum of swap + protocol + burn + extra fees must not exceed the
+/// total fee cap. Used both when creating pools and when updating their fees.
+pub fn validate_pool_fees(
+    pool_fees: &PoolFee,
+    pool_fee_caps: &PoolFeeCaps,
+) -> Result<(), ContractError> {
+    pool_fees.is_valid()?;
+
+    let extra_fees = pool_fees
+        .extra_fees
+        .iter()
+        .try_fold(Decimal::zero(), |acc, fee| acc.checked_add(fee.share))?;
+
+    for (fee, share, cap) in [
+        (
+            "swap_fee",
+            pool_fees.swap_fee.share,
+            pool_fee_caps.max_swap_fee,
+        ),
+        (
+            "protocol_fee",
+            pool_fees.protocol_fee.share,
+            pool_fee_caps.max_protocol_fee,
+        ),
+        (
+            "burn_fee",
+            pool_fees.burn_fee.share,
+            pool_fee_caps.max_burn_fee,
+        ),
+        ("extra_fees", extra_fees, pool_fee_caps.max_extra_fees),
+    ] {
+        ensure!(
+            share <= cap,
+            ContractError::PoolFeeCapExceeded {
+                fee: fee.to_string(),
+                share,
+                cap,
+            }
+        );
+    }
+
+    let total_fees = pool_fees
+        .swap_fee
+        .share
+        .checked_add(pool_fees.protocol_fee.share)?
+        .checked_add(pool_fees.burn_fee.share)?
+        .checked_add(extra_fees)?;
+
+    ensure!(
+        total_fees <= pool_fee_caps.max_total_fee,
+        ContractError::PoolFeeCapExceeded {
+            fee: "total_fees".to_string(),
+            share: total_fees,
+            cap: pool_fee_caps.max_total_fee,
+        }
+    );
+
+    Ok(())
+}
+
+/// Validates the pool fee caps. The total fee cap must be below 100%, otherwise the swap math
+/// breaks, and no individual cap can be greater than the total cap.
+pub fn validate_pool_fee_caps(pool_fee_caps: &PoolFeeCaps) -> Result<(), ContractError> {
+    ensure!(
+        pool_fee_caps.max_total_fee < Decimal::one()
+            && [
+                pool_fee_caps.max_swap_fee,
+                pool_fee_caps.max_protocol_fee,
+                pool_fee_caps.max_burn_fee,
+                pool_fee_caps.max_extra_fees,
+            ]
+            .iter()
+            .all(|cap| cap <= &pool_fee_caps.max_total_fee),
+        ContractError::InvalidPoolFeeCaps
+    );
+
+    Ok(())
+}
+
 /// Aggregates the fees from a simulation response that go out of the contract, i.e. protocol fee and burn fee.
 /// Doesn't know about the denom, just the amount.
 pub fn aggregate_outgoing_fees(
@@ -532,6 +955,30 @@ pub fn aggregate_outgoing_fees(
     Ok(fees)
 }
 
+/// Applies a computed swap of `offer_amount` of the asset at `offer_index` to the reserves of the
+/// given pool, adding the offer and deducting the return amount and the fees leaving the pool.
+/// Shared by the swaps and the simulation of routes, so every hop of a simulated route sees the
+/// pool as left by the previous ones.
+pub(crate) fn apply_swap_to_pool(
+    pool_info: &mut PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    swap_computation: &SwapComputation,
+) -> Result<(), ContractError> {
+    pool_info.assets[offer_index].amount = pool_info.assets[offer_index]
+        .amount
+        .checked_add(offer_amount)?;
+
+    let outgoing_fees = aggregate_outgoing_fees(&swap_computation.to_simulation_response())?;
+    pool_info.assets[ask_index].amount = pool_info.assets[ask_index]
+        .amount
+        .checked_sub(swap_computation.return_amount)?
+        .checked_sub(outgoing_fees)?;
+
+    Ok(())
+}
+
 /// Validates that the pool creation and token factory fees are paid with the transaction.
 /// Returns the total amount of fees paid.
 pub fn validate_fees_are_paid(
@@ -676,6 +1123,130 @@ pub(crate) fn validate_no_additional_funds_sent_with_pool_creation(
     Ok(())
 }
 
+/// Validates the pool creation fees accepted by the config, i.e. there's at least one of them and
+/// each denom is only accepted once.
+pub(crate) fn validate_pool_creation_fees(
+    pool_creation_fees: &[Coin],
+) -> Result<(), ContractError> {
+    ensure!(
+        !pool_creation_fees.is_empty()
+            && pool_creation_fees.iter().enumerate().all(|(index, fee)| {
+                pool_creation_fees[..index]
+                    .iter()
+                    .all(|other| other.denom != fee.denom)
+            }),
+        ContractError::InvalidPoolCreationFees
+    );
+
+    Ok(())
+}
+
+/// Validates the pool and token factory fees were paid with any of the accepted pool creation
+/// fees, with no additional funds. Returns the pool creation fee paid, together with all the fees
+/// paid. The fees whose denom was sent are tried first, so the error returned when none was paid
+/// is the one of the fee the sender most likely meant to pay.
+pub(crate) fn validate_any_pool_creation_fee_is_paid(
+    pool_creation_fees: &[Coin],
+    denom_creation_fee: Vec<Coin>,
+    info: &MessageInfo,
+) -> Result<(Coin, Vec<Coin>), ContractError> {
+    let (sent, not_sent): (Vec<&Coin>, Vec<&Coin>) = pool_creation_fees
+        .iter()
+        .partition(|fee| info.funds.iter().any(|fund| fund.denom == fee.denom));
+
+    let mut first_error = None;
+    for pool_creation_fee in sent.into_iter().chain(not_sent) {
+        let result = validate_fees_are_paid(pool_creation_fee, denom_creation_fee.clone(), info)
+            .and_then(|total_fees| {
+                validate_no_additional_funds_sent_with_pool_creation(info, total_fees.clone())?;
+                Ok(total_fees)
+            });
+
+        match result {
+            Ok(total_fees) => return Ok((pool_creation_fee.clone(), total_fees)),
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or(ContractError::InvalidPoolCreationFees))
+}
+
+/// Gets the bounds of the unlocking durations accepted when providing liquidity to the given pool
+/// with a lock, i.e. the bounds of the pool or, if it has none, the ones of the config.
+pub(crate) fn get_unlocking_duration_bounds(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+) -> Result<Option<UnlockingDurationBounds>, ContractError> {
+    match UNLOCKING_DURATION_BOUNDS.may_load(storage, pool_identifier)? {
+        Some(bounds) => Ok(Some(bounds)),
+        None => Ok(CONFIG.load(storage)?.unlocking_duration_bounds),
+    }
+}
+
+/// Ensures the given unlocking duration is within the bounds of the given pool, if any, before any
+/// funds move, rather than leaving the farm manager to reject it downstream.
+pub(crate) fn validate_unlocking_duration(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    unlocking_duration: u64,
+) -> Result<(), ContractError> {
+    let Some(bounds) = get_unlocking_duration_bounds(storage, pool_identifier)? else {
+        return Ok(());
+    };
+
+    ensure!(
+        (bounds.min..=bounds.max).contains(&unlocking_duration),
+        ContractError::UnlockingDurationOutOfBounds {
+            pool_identifier: pool_identifier.to_string(),
+            unlocking_duration,
+            min: bounds.min,
+            max: bounds.max,
+        }
+    );
+
+    Ok(())
+}
+
+/// Gets the spot exchange rate along the given route, i.e. the amount of the route's output asset
+/// obtained per unit of the input asset, ignoring fees and price impact. The rate is expressed in
+/// the base units of the assets.
+pub fn get_route_spot_price(
+    deps: &Deps,
+    operations: &[SwapOperation],
+) -> Result<Decimal, ContractError> {
+    operations
+        .iter()
+        .try_fold(Decimal::one(), |price, operation| match operation {
+            SwapOperation::MantraSwap {
+                token_in_denom,
+                token_out_denom,
+                pool_identifier,
+            } => {
+                let pool_info = get_pool_by_identifier(deps, pool_identifier)?;
+                let (offer_asset_in_pool, ask_asset_in_pool, ..) = get_asset_indexes_in_pool(
+                    &pool_info,
+                    token_in_denom.to_string(),
+                    token_out_denom.to_string(),
+                )?;
+
+                let pool_price = Decimal::checked_from_ratio(
+                    ask_asset_in_pool.amount,
+                    offer_asset_in_pool.amount,
+                )
+                .map_err(|_| ContractError::PoolHasNoAssets)?;
+
+                Ok(price.checked_mul(pool_price)?)
+            }
+            SwapOperation::ExternalAdapter { .. } => {
+                Err(ContractError::UnsupportedForSwapAdapter {
+                    operation: "spot_price".to_string(),
+                })
+            }
+        })
+}
+
 /// Gets the offer and ask asset indexes in a pool, together with their decimals.
 pub fn get_asset_indexes_in_pool(
     pool_info: &PoolInfo,
@@ -714,6 +1285,100 @@ pub fn get_asset_indexes_in_pool(
     ))
 }
 
+/// Replaces the reserves of the given pool with the provided ones. Used to simulate swaps against
+/// hypothetical balances without touching the state. Each denom must belong to the pool and can
+/// only be overridden once.
+pub fn override_pool_reserves(
+    pool_info: &mut PoolInfo,
+    reserves: Vec<Coin>,
+) -> Result<(), ContractError> {
+    let mut overridden_denoms: Vec<String> = vec![];
+
+    for reserve in reserves {
+        ensure!(
+            !overridden_denoms.contains(&reserve.denom),
+            ContractError::InvalidReserveOverrides
+        );
+
+        let asset = pool_info
+            .assets
+            .iter_mut()
+            .find(|asset| asset.denom == reserve.denom)
+            .ok_or(ContractError::InvalidReserveOverrides)?;
+
+        asset.amount = reserve.amount;
+        overridden_denoms.push(reserve.denom);
+    }
+
+    Ok(())
+}
+
+/// Computes the assets to refund when withdrawing `amount` LP shares out of the `total_share` of
+/// the given pool. Assets with a zero amount are filtered out.
+pub fn get_withdrawal_assets(
+    pool_info: &PoolInfo,
+    amount: Uint128,
+    total_share: Uint128,
+) -> Result<Vec<Coin>, ContractError> {
+    // Get the ratio of the amount to withdraw to the total share
+    let share_ratio: Decimal256 = Decimal256::from_ratio(amount, total_share);
+
+    // sanity check, the share_ratio cannot possibly be greater than 1
+    ensure!(
+        share_ratio <= Decimal256::one(),
+        ContractError::InvalidLpShareToWithdraw
+    );
+
+    // Use the ratio to calculate the amount of each pool asset to refund
+    Ok(pool_info
+        .assets
+        .iter()
+        .map(|pool_asset| {
+            Ok(Coin {
+                denom: pool_asset.denom.clone(),
+                amount: Uint128::try_from(
+                    Decimal256::from_ratio(pool_asset.amount, Uint256::one())
+                        .checked_mul(share_ratio)?
+                        .to_uint_floor(),
+                )?,
+            })
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?
+        .into_iter()
+        // filter out assets with zero amount
+        .filter(|coin| coin.amount > Uint128::zero())
+        .collect())
+}
+
+/// Gets how far the reserves of the given pool are from the peg, i.e. the largest relative
+/// deviation of any reserve from the average reserve, once normalized by the asset decimals.
+/// An empty pool has no deviation.
+pub fn get_peg_deviation(pool_info: &PoolInfo) -> Result<Decimal, ContractError> {
+    let normalized_reserves = get_scaled_reserves(pool_info)?;
+
+    let total = normalized_reserves
+        .iter()
+        .try_fold(Uint256::zero(), |acc, reserve| acc.checked_add(*reserve))?;
+    if total.is_zero() {
+        return Ok(Decimal::zero());
+    }
+
+    let reserves_len = Uint256::from(normalized_reserves.len() as u128);
+    normalized_reserves
+        .iter()
+        .try_fold(Decimal::zero(), |deviation, reserve| {
+            // |reserve - average| / average == |reserve * n - total| / total
+            let scaled_reserve = reserve.checked_mul(reserves_len)?;
+            let difference = if scaled_reserve > total {
+                scaled_reserve - total
+            } else {
+                total - scaled_reserve
+            };
+
+            Ok(deviation.max(Decimal::checked_from_ratio(difference, total)?))
+        })
+}
+
 #[allow(clippy::unwrap_used)]
 pub fn compute_d(amp_factor: &u64, deposits: &[Coin]) -> Option<Uint512> {
     let n_coins = Uint128::from(deposits.len() as u128);
@@ -726,10 +1391,10 @@ pub fn compute_d(amp_factor: &u64, deposits: &[Coin]) -> Option<Uint512> {
     if sum_x == Uint128::zero() {
         Some(Uint512::zero())
     } else {
-        // do as below but for a generic number of assets
-        let amount_times_coins: Vec<Uint128> = deposits
+        // do as below but for a generic number of assets, converting the amounts only once
+        let amount_times_coins: Vec<Uint512> = deposits
             .iter()
-            .map(|coin| coin.amount.checked_mul(n_coins).unwrap())
+            .map(|coin| Uint512::from(coin.amount.checked_mul(n_coins).unwrap()))
             .collect();
 
         // Newton's method to approximate D
@@ -737,12 +1402,8 @@ pub fn compute_d(amp_factor: &u64, deposits: &[Coin]) -> Option<Uint512> {
         let mut d: Uint512 = sum_x.into();
         for _ in 0..256 {
             let mut d_prod = d;
-            for amount in amount_times_coins.clone().into_iter() {
-                d_prod = d_prod
-                    .checked_mul(d)
-                    .unwrap()
-                    .checked_div(amount.into())
-                    .unwrap();
+            for amount in amount_times_coins.iter() {
+                d_prod = d_prod.checked_mul(d).unwrap().checked_div(*amount).unwrap();
             }
             d_prev = d;
             d = compute_next_d(amp_factor, d, d_prod, sum_x, n_coins).unwrap();
@@ -792,15 +1453,30 @@ fn compute_next_d(
     Some(numerator.checked_div(denominator).unwrap())
 }
 
+/// Represents the lp tokens minted for a stableswap deposit
+#[cw_serde]
+pub struct StableSwapDepositComputation {
+    /// The amount of lp tokens to mint.
+    pub mint_amount: Uint128,
+    /// The amount of lp tokens the deposit didn't get due to the imbalance fee.
+    pub imbalance_fee_amount: Uint128,
+}
+
 /// Computes the amount of lp tokens to mint after a deposit for a stableswap pool.
 /// Assumes the deposits have already been credited to the pool_assets.
+///
+/// As Curve does, the portion of the deposit deviating from the ratio of the pool is charged
+/// `swap_fee * n / (4 * (n - 1))`, i.e. about the fee of swapping it into the other assets, so
+/// imbalanced deposits can't be used as fee-less swaps. The fee stays in the pool, accruing to the
+/// LPs, and balanced deposits don't pay any.
 #[allow(clippy::unwrap_used, clippy::too_many_arguments)]
 pub fn compute_lp_mint_amount_for_stableswap_deposit(
     amp_factor: &u64,
     old_pool_assets: &[Coin],
     new_pool_assets: &[Coin],
     pool_lp_token_total_supply: Uint128,
-) -> Result<Option<Uint128>, ContractError> {
+    swap_fee: Decimal,
+) -> Result<Option<StableSwapDepositComputation>, ContractError> {
     // Initial invariant
     let d_0 = compute_d(amp_factor, old_pool_assets).ok_or(ContractError::StableInvariantError)?;
 
@@ -810,13 +1486,51 @@ pub fn compute_lp_mint_amount_for_stableswap_deposit(
 
     // If the invariant didn't change, return None
     if d_1 <= d_0 {
-        Ok(None)
-    } else {
-        let amount = Uint512::from(pool_lp_token_total_supply)
-            .checked_mul(d_1.checked_sub(d_0)?)?
-            .checked_div(d_0)?;
-        Ok(Some(Uint128::try_from(amount)?))
+        return Ok(None);
+    }
+
+    // charge the imbalance fee on the difference between each new balance and the one the pool
+    // would have had if the deposit had kept its ratio
+    let n_coins = new_pool_assets.len() as u128;
+    let imbalance_fee = match n_coins {
+        0 | 1 => Decimal::zero(),
+        _ => swap_fee.checked_mul(Decimal::from_ratio(n_coins, 4 * (n_coins - 1)))?,
+    };
+
+    let mut charged_pool_assets = new_pool_assets.to_vec();
+    for charged_pool_asset in charged_pool_assets.iter_mut() {
+        let old_balance = old_pool_assets
+            .iter()
+            .find(|old_pool_asset| old_pool_asset.denom == charged_pool_asset.denom)
+            .map_or(Uint128::zero(), |old_pool_asset| old_pool_asset.amount);
+        let ideal_balance =
+            Uint128::try_from(d_1.checked_mul(old_balance.into())?.checked_div(d_0)?)?;
+        let difference = ideal_balance.abs_diff(charged_pool_asset.amount);
+
+        charged_pool_asset.amount = charged_pool_asset
+            .amount
+            .checked_sub(difference.checked_mul_floor(imbalance_fee)?)?;
     }
+
+    let d_2 =
+        compute_d(amp_factor, &charged_pool_assets).ok_or(ContractError::StableInvariantError)?;
+
+    // the deposit was eaten by the fee
+    if d_2 <= d_0 {
+        return Ok(None);
+    }
+
+    let mint_amount = Uint512::from(pool_lp_token_total_supply)
+        .checked_mul(d_2.checked_sub(d_0)?)?
+        .checked_div(d_0)?;
+    let mint_amount_before_fee = Uint512::from(pool_lp_token_total_supply)
+        .checked_mul(d_1.checked_sub(d_0)?)?
+        .checked_div(d_0)?;
+
+    Ok(Some(StableSwapDepositComputation {
+        mint_amount: Uint128::try_from(mint_amount)?,
+        imbalance_fee_amount: Uint128::try_from(mint_amount_before_fee.checked_sub(mint_amount)?)?,
+    }))
 }
 
 /// Compute the swap amount `y` in proportion to `x`.
@@ -1045,13 +1759,66 @@ mod tests {
             &deposits,
             &pool_assets,
             pool_token_supply,
+            Decimal::zero(),
         )
-        .unwrap();
+        .unwrap()
+        .map(|deposit| deposit.mint_amount);
         let expected_mint_amount = Some(MAX_TOKENS_IN);
 
         assert_eq!(actual_mint_amount, expected_mint_amount);
     }
 
+    #[test]
+    fn test_compute_mint_amount_for_imbalanced_deposit() {
+        let pool_assets = vec![coin(1_000_000u128, "denom1"), coin(1_000_000u128, "denom2")];
+        let swap_fee = Decimal::percent(1);
+
+        // balanced deposits don't pay the imbalance fee
+        let balanced_deposit = compute_lp_mint_amount_for_stableswap_deposit(
+            &MIN_AMP,
+            &pool_assets,
+            &[coin(1_100_000u128, "denom1"), coin(1_100_000u128, "denom2")],
+            Uint128::new(2_000_000u128),
+            swap_fee,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(balanced_deposit.mint_amount, Uint128::new(200_000u128));
+        assert_eq!(balanced_deposit.imbalance_fee_amount, Uint128::zero());
+
+        // a one-sided deposit pays the fee on the half of it deviating from the pool ratio
+        let fee_less_deposit = compute_lp_mint_amount_for_stableswap_deposit(
+            &MIN_AMP,
+            &pool_assets,
+            &[coin(1_200_000u128, "denom1"), coin(1_000_000u128, "denom2")],
+            Uint128::new(2_000_000u128),
+            Decimal::zero(),
+        )
+        .unwrap()
+        .unwrap();
+        let imbalanced_deposit = compute_lp_mint_amount_for_stableswap_deposit(
+            &MIN_AMP,
+            &pool_assets,
+            &[coin(1_200_000u128, "denom1"), coin(1_000_000u128, "denom2")],
+            Uint128::new(2_000_000u128),
+            swap_fee,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(fee_less_deposit.imbalance_fee_amount, Uint128::zero());
+        assert_eq!(
+            imbalanced_deposit
+                .mint_amount
+                .checked_add(imbalanced_deposit.imbalance_fee_amount)
+                .unwrap(),
+            fee_less_deposit.mint_amount
+        );
+        // about half the swap fee on the 100_000 deviating from the ratio on each side
+        assert!(imbalanced_deposit.imbalance_fee_amount >= Uint128::new(900u128));
+        assert!(imbalanced_deposit.imbalance_fee_amount <= Uint128::new(1_100u128));
+    }
+
     #[test]
     fn test_curve_math_with_random_inputs() {
         for _ in 0..100 {
@@ -1281,6 +2048,7 @@ mod tests {
                 &deposits,
                 &new_pool_assets,
                 Uint128::new(pool_token_supply),
+                Decimal::zero(),
                 ).unwrap();
 
             prop_assume!(mint_amount.is_some());
@@ -1329,4 +2097,131 @@ mod tests {
             assert!(d0 <= d1);  // Pool token supply not changed on swaps
         }
     }
+
+    #[test]
+    fn test_stableswap_y_keeps_invariant_for_n_assets() {
+        let amp = 100u64;
+        let offer_amount = Decimal256::from_ratio(10_000u128, 1u128);
+
+        for n_coins in 2..=5usize {
+            // an imbalanced pool, holding 1M, 2M, 3M... of each asset
+            let pools: Vec<Decimal256> = (1..=n_coins)
+                .map(|i| Decimal256::from_ratio(i as u128 * 1_000_000u128, 1u128))
+                .collect();
+            let ask_index = n_coins - 1;
+
+            let d0 = calculate_stableswap_d(&pools, &amp, 6).unwrap();
+
+            let new_ask_pool = calculate_stableswap_y(
+                &pools,
+                0,
+                ask_index,
+                offer_amount,
+                &amp,
+                6,
+                StableSwapDirection::Simulate,
+            )
+            .unwrap();
+
+            let mut new_pools = pools.clone();
+            new_pools[0] = new_pools[0].checked_add(offer_amount).unwrap();
+            new_pools[ask_index] = Decimal256::decimal_with_precision(new_ask_pool, 6).unwrap();
+
+            // the swap keeps the invariant of all the pools, not only of the swapped ones
+            let d1 = calculate_stableswap_d(&new_pools, &amp, 6).unwrap();
+            assert!(d0.abs_diff(d1) <= Decimal256::permille(1));
+
+            // the asset bought is the most abundant one, so more than the offer amount is returned
+            let return_amount = pools[ask_index].checked_sub(new_pools[ask_index]).unwrap();
+            assert!(return_amount > offer_amount);
+
+            // reversing the swap recovers the offer pool
+            let new_offer_pool = calculate_stableswap_y(
+                &pools,
+                0,
+                ask_index,
+                return_amount,
+                &amp,
+                6,
+                StableSwapDirection::ReverseSimulate,
+            )
+            .unwrap();
+            let expected_offer_pool = new_pools[0].to_uint256_with_precision(6).unwrap();
+            assert!(
+                Uint256::from(new_offer_pool).abs_diff(expected_offer_pool) <= Uint256::from(10u8)
+            );
+        }
+    }
+
+    #[test]
+    fn test_asset_scaling_factors() {
+        assert_eq!(
+            get_asset_scaling_factors(&[6, 18, 8]).unwrap(),
+            vec![
+                Uint128::new(1_000_000_000_000),
+                Uint128::one(),
+                Uint128::new(10_000_000_000)
+            ]
+        );
+        assert_eq!(
+            get_asset_scaling_factors(&[6, 6]).unwrap(),
+            vec![Uint128::one(), Uint128::one()]
+        );
+        // the factor doesn't fit, as the decimals are too far apart
+        assert_eq!(
+            get_asset_scaling_factors(&[0, 39]).unwrap_err(),
+            ContractError::DecimalOverflow
+        );
+    }
+
+    #[test]
+    fn test_stableswap_trace_records_the_iterations() {
+        let pools = vec![
+            Decimal256::from_ratio(1_000u128, 1u128),
+            Decimal256::from_ratio(1_200u128, 1u128),
+            Decimal256::from_ratio(900u128, 1u128),
+        ];
+        let offer_amount = Decimal256::from_ratio(10u128, 1u128);
+
+        let mut trace = StableSwapTrace::default();
+        let new_pool = calculate_stableswap_y_with_trace(
+            &pools,
+            0,
+            1,
+            offer_amount,
+            &100,
+            6,
+            StableSwapDirection::Simulate,
+            Some(&mut trace),
+        )
+        .unwrap();
+
+        // the trace doesn't change the result, and ends on it
+        assert_eq!(
+            new_pool,
+            calculate_stableswap_y(
+                &pools,
+                0,
+                1,
+                offer_amount,
+                &100,
+                6,
+                StableSwapDirection::Simulate,
+            )
+            .unwrap()
+        );
+        assert!(!trace.d_iterations.is_empty());
+        assert_eq!(trace.y_iterations.last().copied(), Some(new_pool.into()));
+
+        // the inputs hash is deterministic, and changes with the inputs
+        let swap = Some((0, 1, offer_amount, &StableSwapDirection::Simulate));
+        assert_eq!(
+            hash_stableswap_inputs(&pools, &100, 6, swap),
+            hash_stableswap_inputs(&pools, &100, 6, swap)
+        );
+        assert_ne!(
+            hash_stableswap_inputs(&pools, &100, 6, swap),
+            hash_stableswap_inputs(&pools, &101, 6, swap)
+        );
+    }
 }
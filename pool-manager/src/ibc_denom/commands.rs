@@ -0,0 +1,95 @@
+use cosmwasm_std::{ensure, Deps, DepsMut, HexBinary, MessageInfo, Response};
+use sha2::{Digest, Sha256};
+
+use mantra_dex_std::ibc::query_denom_trace;
+use mantra_dex_std::pool_manager::IbcDenomTrace;
+
+use crate::state::IBC_SOURCE_CHANNELS;
+use crate::ContractError;
+
+/// The prefix of the denoms of assets transferred over IBC, followed by the hash of their trace.
+const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// Sets the channels assets transferred over IBC must have come through to be added to a pool.
+/// An empty list allows any channel. Only the owner can do this.
+pub fn update_ibc_source_channels(
+    deps: DepsMut,
+    info: MessageInfo,
+    channels: Vec<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    for channel_id in channels.iter() {
+        ensure!(
+            channel_id.starts_with("channel-"),
+            ContractError::InvalidIbcChannel {
+                channel_id: channel_id.clone()
+            }
+        );
+    }
+
+    if channels.is_empty() {
+        IBC_SOURCE_CHANNELS.remove(deps.storage);
+    } else {
+        IBC_SOURCE_CHANNELS.save(deps.storage, &channels)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_ibc_source_channels".to_string()),
+        ("channels", channels.join(",")),
+    ]))
+}
+
+/// Resolves the traces of the IBC denoms among the given ones with the transfer module. Fails if
+/// a denom has no trace, if its trace doesn't hash to it, or if it came through a channel that
+/// isn't allowed.
+pub(crate) fn get_ibc_denom_traces(
+    deps: Deps,
+    asset_denoms: &[String],
+) -> Result<Vec<IbcDenomTrace>, ContractError> {
+    let allowed_channels = IBC_SOURCE_CHANNELS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    let mut denom_traces = vec![];
+    for denom in asset_denoms {
+        let Some(hash) = denom.strip_prefix(IBC_DENOM_PREFIX) else {
+            continue;
+        };
+
+        let denom_trace = query_denom_trace(&deps.querier, hash).map_err(|_| {
+            ContractError::InvalidIbcDenomTrace {
+                denom: denom.clone(),
+            }
+        })?;
+
+        // the transfer module derives the denom from the hash of the full trace
+        let full_trace = format!("{}/{}", denom_trace.path, denom_trace.base_denom);
+        let trace_hash = HexBinary::from(Sha256::digest(full_trace.as_bytes()).as_slice()).to_hex();
+        ensure!(
+            trace_hash.eq_ignore_ascii_case(hash),
+            ContractError::InvalidIbcDenomTrace {
+                denom: denom.clone(),
+            }
+        );
+
+        // the trace starts with the port and channel the asset came through on this chain
+        let channel_id = denom_trace.path.split('/').nth(1).unwrap_or_default();
+        ensure!(
+            allowed_channels.is_empty() || allowed_channels.iter().any(|c| c == channel_id),
+            ContractError::IbcSourceChannelNotAllowed {
+                denom: denom.clone(),
+                channel_id: channel_id.to_string(),
+            }
+        );
+
+        denom_traces.push(IbcDenomTrace {
+            denom: denom.clone(),
+            path: denom_trace.path,
+            base_denom: denom_trace.base_denom,
+        });
+    }
+
+    Ok(denom_traces)
+}
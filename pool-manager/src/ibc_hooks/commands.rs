@@ -0,0 +1,107 @@
+use cosmwasm_std::{ensure, Decimal, Deps, DepsMut, Env, MessageInfo, Response, Uint128};
+
+use mantra_dex_std::pool_manager::{PacketForward, SwapOperation};
+
+use crate::router::commands::execute_swap_operations_to;
+use crate::swap::commands::{swap_to, SwapDestination};
+use crate::ContractError;
+
+/// The port of the ICS20 transfer module, the only one packets can be forwarded over.
+const TRANSFER_PORT: &str = "transfer";
+
+/// Swaps the assets received over IBC, as called by the ibc-hooks middleware with the `msg` of the
+/// `wasm` memo of an ICS20 transfer. The sender of such calls is an address derived from the
+/// channel and the remote sender, which no one controls, so the output goes to an explicit
+/// `receiver` on this chain. If `forward` is set, the output is instead sent over IBC following
+/// the packet-forward middleware metadata given, and refunded to `receiver` if the transfer fails.
+#[allow(clippy::too_many_arguments)]
+pub fn hook_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset_denom: String,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    pool_identifier: String,
+    receiver: String,
+    forward: Option<PacketForward>,
+) -> Result<Response, ContractError> {
+    let destination = get_hook_destination(deps.as_ref(), receiver, forward)?;
+
+    let sender = info.sender.clone();
+    Ok(swap_to(
+        deps,
+        env,
+        info,
+        sender,
+        ask_asset_denom,
+        belief_price,
+        max_spread,
+        destination,
+        pool_identifier,
+        None,
+        None,
+        None,
+    )?
+    .add_attribute("hook", "ibc_hooks"))
+}
+
+/// Executes the given swap operations with the assets received over IBC, as [hook_swap] does for a
+/// single pool. The output of the route goes to `receiver` on this chain, or over IBC following
+/// `forward`, and is refunded to `receiver` if the transfer fails.
+#[allow(clippy::too_many_arguments)]
+pub fn hook_swap_operations(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    receiver: String,
+    forward: Option<PacketForward>,
+) -> Result<Response, ContractError> {
+    let destination = get_hook_destination(deps.as_ref(), receiver, forward)?;
+
+    Ok(execute_swap_operations_to(
+        deps,
+        env,
+        info,
+        operations,
+        minimum_receive,
+        destination,
+        max_spread,
+        None,
+        None,
+        None,
+    )?
+    .add_attribute("hook", "ibc_hooks"))
+}
+
+/// Gets where the output of a hook call goes: the `receiver` on this chain, or the receiver of
+/// the given packet forward, which must go over the ICS20 transfer port.
+fn get_hook_destination(
+    deps: Deps,
+    receiver: String,
+    forward: Option<PacketForward>,
+) -> Result<SwapDestination, ContractError> {
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    match forward {
+        None => Ok(SwapDestination::Local(Some(receiver.into_string()))),
+        Some(forward) => {
+            ensure!(
+                forward.port == TRANSFER_PORT && forward.channel.starts_with("channel-"),
+                ContractError::InvalidIbcChannel {
+                    channel_id: format!("{}/{}", forward.port, forward.channel)
+                }
+            );
+
+            Ok(SwapDestination::Remote {
+                channel_id: forward.channel,
+                to_address: forward.receiver,
+                refund_address: receiver,
+                forward: forward.next.map(|next| *next),
+            })
+        }
+    }
+}
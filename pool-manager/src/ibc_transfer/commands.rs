@@ -0,0 +1,179 @@
+use cosmwasm_std::{
+    coin, ensure, from_json, to_json_string, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env,
+    IbcBasicResponse, IbcMsg, IbcPacket, IbcSourceCallbackMsg, IbcSrcCallback, IbcTimeout,
+    MessageInfo, Response, Storage, Uint128,
+};
+use serde::{Deserialize, Serialize};
+
+use mantra_dex_std::pool_manager::{IbcWithdrawalChannel, PacketForward};
+
+use crate::state::IBC_WITHDRAWAL_CHANNELS;
+use crate::ContractError;
+
+/// The time after which the IBC transfers of withdrawn assets time out, and are refunded.
+pub const IBC_TRANSFER_TIMEOUT_SECONDS: u64 = 900u64;
+
+/// The memo attached to the IBC transfers of withdrawn assets. It requests a callback to this
+/// contract when the transfer is acknowledged or times out, and holds what to refund if it fails.
+#[derive(Serialize, Deserialize)]
+struct TransferMemo {
+    src_callback: IbcSrcCallback,
+    withdrawal_refund: WithdrawalRefund,
+    /// The packet-forward middleware metadata, for the receiving chain to forward the transfer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    forward: Option<PacketForward>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WithdrawalRefund {
+    address: String,
+    denom: String,
+}
+
+/// The data of an ICS20 packet.
+#[derive(Deserialize)]
+struct Ics20Packet {
+    amount: Uint128,
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+/// The acknowledgement of a failed ICS20 packet.
+#[derive(Deserialize)]
+struct Ics20ErrorAck {
+    error: String,
+}
+
+/// Sets or removes the channels over which the given assets are transferred when withdrawing
+/// liquidity to a remote address. Only the owner can do this.
+pub fn update_ibc_withdrawal_channels(
+    deps: DepsMut,
+    info: MessageInfo,
+    channels: Vec<IbcWithdrawalChannel>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![("action", "update_ibc_withdrawal_channels".to_string())];
+
+    for channel in channels {
+        match channel.channel_id {
+            Some(channel_id) => {
+                ensure!(
+                    channel_id.starts_with("channel-"),
+                    ContractError::InvalidIbcChannel { channel_id }
+                );
+                IBC_WITHDRAWAL_CHANNELS.save(deps.storage, &channel.denom, &channel_id)?;
+                attributes.push(("channel", format!("{}={}", channel.denom, channel_id)));
+            }
+            None => {
+                IBC_WITHDRAWAL_CHANNELS.remove(deps.storage, &channel.denom);
+                attributes.push(("removed_channel", channel.denom));
+            }
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+/// Creates the IBC transfers sending the given assets to `remote_receiver`, each over the channel
+/// configured for it. If a transfer fails, the asset is refunded to `refund_address`.
+pub(crate) fn get_ibc_transfer_msgs(
+    storage: &dyn Storage,
+    env: &Env,
+    assets: &[Coin],
+    remote_receiver: &str,
+    refund_address: &Addr,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    assets
+        .iter()
+        .map(|asset| {
+            let channel_id = IBC_WITHDRAWAL_CHANNELS
+                .may_load(storage, &asset.denom)?
+                .ok_or(ContractError::NoIbcChannelForAsset {
+                    denom: asset.denom.clone(),
+                })?;
+
+            get_ibc_forward_msg(
+                env,
+                channel_id,
+                asset.clone(),
+                remote_receiver,
+                refund_address,
+                None,
+            )
+        })
+        .collect()
+}
+
+/// Creates the IBC transfer sending the given asset to `remote_receiver` over `channel_id`, with
+/// the packet-forward middleware metadata for the receiving chain to forward it further, if any.
+/// If the transfer fails, the asset is refunded to `refund_address`.
+pub(crate) fn get_ibc_forward_msg(
+    env: &Env,
+    channel_id: String,
+    asset: Coin,
+    remote_receiver: &str,
+    refund_address: &Addr,
+    forward: Option<PacketForward>,
+) -> Result<CosmosMsg, ContractError> {
+    let memo = to_json_string(&TransferMemo {
+        src_callback: IbcSrcCallback {
+            address: env.contract.address.clone(),
+            gas_limit: None,
+        },
+        withdrawal_refund: WithdrawalRefund {
+            address: refund_address.to_string(),
+            denom: asset.denom.clone(),
+        },
+        forward,
+    })?;
+
+    Ok(IbcMsg::Transfer {
+        channel_id,
+        to_address: remote_receiver.to_string(),
+        amount: asset,
+        timeout: IbcTimeout::with_timestamp(
+            env.block.time.plus_seconds(IBC_TRANSFER_TIMEOUT_SECONDS),
+        ),
+        memo: Some(memo),
+    }
+    .into())
+}
+
+/// Handles the callbacks of the IBC transfers of withdrawn assets. Transfers that time out or are
+/// acknowledged with an error are refunded to this contract by the transfer module, which are
+/// then sent to the refund address of the withdrawal.
+pub fn ibc_source_callback(msg: IbcSourceCallbackMsg) -> Result<IbcBasicResponse, ContractError> {
+    match msg {
+        IbcSourceCallbackMsg::Acknowledgement(ack) => {
+            match from_json::<Ics20ErrorAck>(&ack.acknowledgement.data) {
+                Ok(Ics20ErrorAck { error }) => {
+                    Ok(refund_transfer(&ack.original_packet)?.add_attribute("error", error))
+                }
+                // the transfer succeeded
+                Err(_) => Ok(IbcBasicResponse::default()
+                    .add_attribute("action", "ibc_withdrawal_acknowledged")),
+            }
+        }
+        IbcSourceCallbackMsg::Timeout(timeout) => refund_transfer(&timeout.packet),
+    }
+}
+
+fn refund_transfer(packet: &IbcPacket) -> Result<IbcBasicResponse, ContractError> {
+    let packet_data: Ics20Packet = from_json(&packet.data)?;
+    let memo: TransferMemo = from_json(packet_data.memo.unwrap_or_default())?;
+
+    let refund = coin(packet_data.amount.u128(), memo.withdrawal_refund.denom);
+
+    Ok(IbcBasicResponse::default()
+        .add_message(BankMsg::Send {
+            to_address: memo.withdrawal_refund.address.clone(),
+            amount: vec![refund.clone()],
+        })
+        .add_attributes(vec![
+            ("action", "ibc_withdrawal_refunded".to_string()),
+            ("receiver", memo.withdrawal_refund.address),
+            ("refund", refund.to_string()),
+        ]))
+}
@@ -1,14 +1,48 @@
+pub mod batch;
+pub mod concentrated;
 pub mod contract;
+pub mod dca;
+pub mod depeg;
+pub mod dust;
 pub mod error;
+mod events;
+mod failure_injection;
+pub mod fee_discount;
+pub mod flash_swap;
+mod gas;
 pub mod state;
 pub use crate::error::ContractError;
 pub mod helpers;
+pub mod ibc_denom;
+pub mod ibc_hooks;
+pub mod ibc_transfer;
 pub mod liquidity;
+pub mod lp_wrapper;
 pub mod manager;
 pub mod math;
+pub mod migration;
+pub mod normalization;
+pub mod pegged;
+pub mod permissioned;
+pub mod price_breaker;
+pub mod price_hooks;
 pub mod queries;
+pub mod rates;
+pub mod rebalance;
+pub mod rfq;
 pub mod router;
+pub mod skim;
+pub mod split;
+pub mod stop_loss;
+pub mod streaming;
 pub mod swap;
+pub mod swap_adapter;
+#[cfg(any(test, feature = "testing"))]
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
+pub mod twap;
+pub mod yield_strategy;
+pub mod zap;
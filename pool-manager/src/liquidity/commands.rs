@@ -1,39 +1,50 @@
 use cosmwasm_std::{
-    coin, coins, ensure, to_json_binary, wasm_execute, BankMsg, Coin, CosmosMsg, Decimal256,
-    DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint256,
+    coin, coins, ensure, to_json_binary, wasm_execute, Addr, BankMsg, Coin, CosmosMsg, DepsMut,
+    Env, MessageInfo, Response, StdResult, SubMsg,
 };
 use cosmwasm_std::{Decimal, Uint128};
 
-use mantra_dex_std::coin::{add_coins, aggregate_coins};
-use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::coin::{add_coins, aggregate_coins, burn_coin_msg};
 use mantra_dex_std::farm_manager::{PositionsBy, PositionsResponse};
 use mantra_dex_std::lp_common::MINIMUM_LIQUIDITY_AMOUNT;
-use mantra_dex_std::pool_manager::{get_total_share, ExecuteMsg, PoolType};
+use mantra_dex_std::pool_manager::{
+    ExecuteMsg, PoolInfo, PoolType, ProvideLiquidityResult, SwapOperation,
+};
 use mantra_dex_std::U256;
 
+use crate::permissioned::commands::assert_pool_access;
 use crate::{
     helpers::{self},
     state::get_pool_by_identifier,
 };
 use crate::{
-    state::{CONFIG, POOLS},
+    state::{get_lp_supply, CONFIG, LP_SUPPLY, POOLS},
     ContractError,
 };
 // After writing create_pool I see this can get quite verbose so attempting to
 // break it down into smaller modules which house some things like swap, liquidity etc
-use crate::contract::SINGLE_SIDE_LIQUIDITY_PROVISION_REPLY_ID;
+use crate::contract::{SINGLE_SIDE_LIQUIDITY_PROVISION_REPLY_ID, UNLOCK_AND_WITHDRAW_REPLY_ID};
+use crate::dust::tracking::{track_deposit_dust, track_withdrawal_dust};
 use crate::helpers::{
-    aggregate_outgoing_fees, compute_d, compute_lp_mint_amount_for_stableswap_deposit,
+    aggregate_outgoing_fees, compute_d, compute_lp_mint_amount_for_stableswap_deposit, scale_assets,
 };
+use crate::ibc_transfer::commands::get_ibc_transfer_msgs;
+use crate::pegged::{get_pegged_rate, get_pegged_value};
 use crate::queries::query_simulation;
+use crate::rates::{apply_rates, get_rates};
 use crate::state::{
-    LiquidityProvisionData, SingleSideLiquidityProvisionBuffer,
-    SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER,
+    LiquidityProvisionData, SingleSideLiquidityProvisionBuffer, UnlockAndWithdrawBuffer,
+    DEPRECATED_POOLS, LIQUIDITY_PROVISION_DEPOSITOR, MIN_LP_LOCKS,
+    SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER, UNLOCK_AND_WITHDRAW_BUFFER,
 };
+use crate::swap::perform_swap::perform_swap;
+use crate::twap::accumulate_prices;
+use crate::yield_strategy::commands::{get_recall_amount, recall_uncovered_reserves};
+use crate::zap::commands::zap_into_pool;
 
 #[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     slippage_tolerance: Option<Decimal>,
@@ -42,6 +53,8 @@ pub fn provide_liquidity(
     pool_identifier: String,
     unlocking_duration: Option<u64>,
     lock_position_identifier: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+    deposit_route: Option<Vec<SwapOperation>>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     // check if the deposit feature is enabled
@@ -52,6 +65,37 @@ pub fn provide_liquidity(
 
     // Get the pool by the pool_identifier
     let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // concentrated liquidity is provided within a range, see provide_concentrated_liquidity
+    ensure!(
+        !helpers::is_concentrated(&pool.pool_type),
+        ContractError::UnsupportedForConcentratedLiquidity {
+            operation: "provide_liquidity".to_string(),
+        }
+    );
+
+    // a deposit of an asset the pool doesn't hold is swapped into one of the pool assets through
+    // the given route first, then provided on behalf of the receiver as with a zap
+    if let Some(deposit_route) = deposit_route {
+        if let Some(receiver) = &receiver {
+            deps.api.addr_validate(receiver)?;
+        }
+
+        return zap_into_pool(
+            deps,
+            env,
+            info,
+            "provide_liquidity",
+            deposit_route,
+            None,
+            max_spread,
+            pool_identifier,
+            slippage_tolerance,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+        );
+    }
 
     let mut pool_assets = pool.assets.clone();
     let deposits = aggregate_coins(info.funds.clone())?;
@@ -66,33 +110,75 @@ pub fn provide_liquidity(
         ContractError::AssetMismatch
     );
 
-    let receiver =
-        validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone()).to_string();
+    // an invalid receiver is rejected rather than defaulting to the sender, as the sender may be
+    // an address no one controls locally, e.g. an interchain account
+    let receiver = match receiver {
+        Some(receiver) => deps.api.addr_validate(&receiver)?.into_string(),
+        None => info.sender.to_string(),
+    };
+
+    // both the depositor and the receiver of the LP tokens need access to permissioned pools
+    assert_pool_access(deps.storage, &env, &pool_identifier, &info.sender)?;
+    assert_pool_access(
+        deps.storage,
+        &env,
+        &pool_identifier,
+        &deps.api.addr_validate(&receiver)?,
+    )?;
+
+    // pools with a minimum lock only take liquidity locked for long enough. The only LP tokens
+    // minted without a lock are the MINIMUM_LIQUIDITY_AMOUNT the first deposit locks in the contract
+    if let Some(min_unlocking_duration) = MIN_LP_LOCKS.may_load(deps.storage, &pool_identifier)? {
+        ensure!(
+            unlocking_duration.is_some_and(|duration| duration >= min_unlocking_duration),
+            ContractError::MinLpLockNotMet {
+                pool_identifier,
+                min_unlocking_duration,
+            }
+        );
+    }
+
+    if let Some(unlocking_duration) = unlocking_duration {
+        helpers::validate_unlocking_duration(deps.storage, &pool_identifier, unlocking_duration)?;
+    }
 
-    //let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
     // check if the user is providing liquidity with a single asset
     let is_single_asset_provision = deposits.len() == 1usize;
 
     if is_single_asset_provision {
+        // dormant pools only hold dust, which the deposit can't be swapped against
         ensure!(
-            !pool_assets.iter().any(|asset| asset.amount.is_zero()),
+            !pool_assets.iter().any(|asset| asset.amount.is_zero())
+                && get_lp_supply(&deps.as_ref(), &pool)? > MINIMUM_LIQUIDITY_AMOUNT,
             ContractError::EmptyPoolForSingleSideLiquidityProvision
         );
+    }
 
-        // can't provide single side liquidity on a pool with more than 2 assets
-        ensure!(
-            pool_assets.len() == 2,
-            ContractError::InvalidPoolAssetsForSingleSideLiquidityProvision
-        );
+    // the contract provides liquidity on behalf of others, e.g. with zaps and liquidity streams.
+    // Whoever the funds came from is refunded if the swap of a single asset deposit fails, or what
+    // a deposit doesn't use. Otherwise the funds came from the receiver, e.g. when moving liquidity
+    // or when the contract provides liquidity for itself
+    let depositor = if info.sender == env.contract.address {
+        match LIQUIDITY_PROVISION_DEPOSITOR.may_load(deps.storage)? {
+            Some(depositor) => {
+                LIQUIDITY_PROVISION_DEPOSITOR.remove(deps.storage);
+                depositor
+            }
+            None => deps.api.addr_validate(&receiver)?,
+        }
+    } else {
+        info.sender.clone()
+    };
 
+    if is_single_asset_provision && pool_assets.len() == 2 {
         let deposit = deposits[0].clone();
 
-        let ask_asset_denom = pool_assets
+        let ask_asset_in_pool = pool_assets
             .iter()
             .find(|pool_asset| pool_asset.denom != deposit.denom)
             .ok_or(ContractError::AssetMismatch)?
-            .denom
             .clone();
+        let ask_asset_denom = ask_asset_in_pool.denom.clone();
 
         // swap half of the deposit asset for the other asset in the pool
         let swap_half = Coin {
@@ -102,9 +188,12 @@ pub fn provide_liquidity(
 
         let swap_simulation_response = query_simulation(
             deps.as_ref(),
+            &env,
             swap_half.clone(),
             ask_asset_denom.clone(),
             pool_identifier.clone(),
+            // the half is swapped by the contract itself, which has no stake to discount fees by
+            None,
         )?;
 
         // let's compute the expected offer asset balance in the contract after the swap and liquidity
@@ -113,18 +202,29 @@ pub fn provide_liquidity(
         // the single side liquidity provision
         let expected_offer_asset_balance_in_contract = deps
             .querier
-            .query_balance(&env.contract.address, deposit.denom)?;
+            .query_balance(&env.contract.address, &deposit.denom)?;
 
         // let's compute the expected ask asset balance in the contract after the swap and liquidity
         // provision takes place. It should be the current balance minus the fees that will be sent
-        // off the contract.
+        // off the contract, plus the deployed reserves the swap recalls, if any.
         let mut expected_ask_asset_balance_in_contract = deps
             .querier
             .query_balance(&env.contract.address, ask_asset_denom.clone())?;
 
+        let outgoing_fees = aggregate_outgoing_fees(&swap_simulation_response)?;
+        let recalled = get_recall_amount(
+            deps.storage,
+            &pool_identifier,
+            &ask_asset_denom,
+            ask_asset_in_pool
+                .amount
+                .saturating_sub(swap_simulation_response.return_amount)
+                .saturating_sub(outgoing_fees),
+        )?;
         expected_ask_asset_balance_in_contract.amount = expected_ask_asset_balance_in_contract
             .amount
-            .saturating_sub(aggregate_outgoing_fees(&swap_simulation_response)?);
+            .saturating_sub(outgoing_fees)
+            .checked_add(recalled)?;
 
         // sanity check. Theoretically, with the given conditions of min LP, pool fees and max spread assertion,
         // the expected ask asset balance in the contract will always be greater than zero after
@@ -138,6 +238,8 @@ pub fn provide_liquidity(
             deps.storage,
             &SingleSideLiquidityProvisionBuffer {
                 receiver,
+                depositor,
+                deposit: deposit.clone(),
                 expected_offer_asset_balance_in_contract,
                 expected_ask_asset_balance_in_contract,
                 offer_asset_half: swap_half.clone(),
@@ -151,12 +253,14 @@ pub fn provide_liquidity(
                     pool_identifier: pool_identifier.clone(),
                     unlocking_duration,
                     lock_position_identifier,
+                    min_lp_to_receive,
                 },
             },
         )?;
 
         Ok(Response::default()
-            .add_submessage(SubMsg::reply_on_success(
+            // the deposit is refunded in full if the swap fails
+            .add_submessage(SubMsg::reply_always(
                 wasm_execute(
                     env.contract.address.into_string(),
                     &ExecuteMsg::Swap {
@@ -165,6 +269,9 @@ pub fn provide_liquidity(
                         max_spread,
                         receiver: None,
                         pool_identifier,
+                        fee_payer: None,
+                        referral: None,
+                        min_receive: None,
                     },
                     vec![swap_half],
                 )?,
@@ -173,18 +280,45 @@ pub fn provide_liquidity(
             .add_attributes(vec![("action", "single_side_liquidity_provision")]))
     } else {
         let mut messages: Vec<CosmosMsg> = vec![];
+        let mut price_hook_msgs: Vec<SubMsg> = vec![];
+
+        // a single asset deposit into a pool of more than two assets is swapped into the other
+        // assets of the pool, except for stableswap pools where it's minted against the invariant
+        let deposits = if is_single_asset_provision && !helpers::is_stableswap(&pool.pool_type) {
+            let deposits = swap_deposit_into_pool_assets(
+                deps.branch(),
+                &env,
+                &info.sender,
+                &pool,
+                &deposits[0],
+                max_spread,
+                &mut messages,
+                &mut price_hook_msgs,
+            )?;
+
+            // the swaps moved the reserves of the pool
+            pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+            pool_assets = pool.assets.clone();
+
+            deposits
+        } else {
+            deposits
+        };
 
         let liquidity_token = pool.lp_denom.clone();
 
         // Compute share and other logic based on the number of assets
-        let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
+        let total_share = get_lp_supply(&deps.as_ref(), &pool)?;
+        // the lp tokens imbalanced deposits into stableswap pools forgo to the imbalance fee
+        let mut imbalance_fee_amount = Uint128::zero();
+        // the part of the deposits taken by the pool, and the remainder refunded to the depositor
+        let mut provided_deposits = deposits.clone();
+        let mut deposit_remainders: Vec<Coin> = vec![];
 
         let share = match &pool.pool_type {
             PoolType::ConstantProduct => {
-                if total_share == Uint128::zero() {
-                    // Make sure at least MINIMUM_LIQUIDITY_AMOUNT is deposited to mitigate the risk of the first
-                    // depositor preventing small liquidity providers from joining the pool
-                    let share = Uint128::new(
+                if is_dormant(total_share) {
+                    let initial_share = Uint128::new(
                         (U256::from(deposits[0].amount.u128())
                             .checked_mul(U256::from(deposits[1].amount.u128()))
                             .ok_or::<ContractError>(
@@ -192,24 +326,15 @@ pub fn provide_liquidity(
                             ))?
                         .integer_sqrt()
                         .as_u128(),
-                    )
-                    .saturating_sub(MINIMUM_LIQUIDITY_AMOUNT);
-
-                    // share should be above zero after subtracting the MINIMUM_LIQUIDITY_AMOUNT
-                    if share.is_zero() {
-                        return Err(ContractError::InvalidInitialLiquidityAmount(
-                            MINIMUM_LIQUIDITY_AMOUNT,
-                        ));
-                    }
-
-                    messages.push(mantra_dex_std::lp_common::mint_lp_token_msg(
-                        liquidity_token.clone(),
-                        &env.contract.address,
-                        &env.contract.address,
-                        MINIMUM_LIQUIDITY_AMOUNT,
-                    )?);
+                    );
 
-                    share
+                    get_seed_share(
+                        initial_share,
+                        total_share,
+                        &liquidity_token,
+                        &env,
+                        &mut messages,
+                    )?
                 } else {
                     let mut asset_shares = vec![];
 
@@ -227,44 +352,99 @@ pub fn provide_liquidity(
                         );
                     }
 
-                    std::cmp::min(asset_shares[0], asset_shares[1])
-                }
-            }
-            PoolType::StableSwap { amp: amp_factor } => {
-                if total_share == Uint128::zero() {
-                    // Make sure at least MINIMUM_LIQUIDITY_AMOUNT is deposited to mitigate the risk of the first
-                    // depositor preventing small liquidity providers from joining the pool
-                    let share = Uint128::try_from(compute_d(amp_factor, &deposits).unwrap())?
-                        .saturating_sub(MINIMUM_LIQUIDITY_AMOUNT);
-
-                    // share should be above zero after subtracting the min_lp_token_amount
-                    if share.is_zero() {
-                        return Err(ContractError::InvalidInitialLiquidityAmount(
-                            MINIMUM_LIQUIDITY_AMOUNT,
-                        ));
+                    let share = asset_shares
+                        .into_iter()
+                        .min()
+                        .ok_or(ContractError::EmptyAssets)?;
+
+                    // only the amounts in proportion to the reserves are taken, the remainder of
+                    // the over-supplied asset is refunded rather than donated to the pool. The
+                    // liquidity the contract provides for itself, e.g. when rebalancing, still
+                    // donates it
+                    if depositor != env.contract.address {
+                        (provided_deposits, deposit_remainders) = split_proportional_deposits(
+                            &deposits,
+                            &pool_assets,
+                            total_share,
+                            share,
+                        )?;
                     }
 
-                    // mint the lp tokens to the contract
-                    messages.push(mantra_dex_std::lp_common::mint_lp_token_msg(
-                        liquidity_token.clone(),
-                        &env.contract.address,
-                        &env.contract.address,
-                        MINIMUM_LIQUIDITY_AMOUNT,
-                    )?);
+                    track_deposit_dust(
+                        deps.storage,
+                        &pool,
+                        &provided_deposits,
+                        &pool_assets,
+                        total_share,
+                        share,
+                    )?;
 
                     share
+                }
+            }
+            PoolType::StableSwap { amp: amp_factor }
+            | PoolType::ComposableStableSwap {
+                amp: amp_factor, ..
+            } => {
+                // the assets of pools with rate providers are valued at their rates
+                let rated_pool = apply_rates(&pool, get_rates(deps.as_ref(), &pool)?.as_deref())?;
+
+                if is_dormant(total_share) {
+                    let initial_share = Uint128::try_from(
+                        compute_d(amp_factor, &scale_assets(&rated_pool, &deposits)?).unwrap(),
+                    )?;
+
+                    get_seed_share(
+                        initial_share,
+                        total_share,
+                        &liquidity_token,
+                        &env,
+                        &mut messages,
+                    )?
                 } else {
-                    compute_lp_mint_amount_for_stableswap_deposit(
+                    let deposit_computation = compute_lp_mint_amount_for_stableswap_deposit(
                         amp_factor,
                         // pool_assets hold the balances before the deposit was made
-                        &pool_assets,
+                        &scale_assets(&rated_pool, &pool_assets)?,
                         // add the deposit to the pool_assets to calculate the new balances
-                        &add_coins(pool_assets.clone(), deposits.clone())?,
+                        &scale_assets(
+                            &rated_pool,
+                            &add_coins(pool_assets.clone(), deposits.clone())?,
+                        )?,
                         total_share,
+                        pool.pool_fees.swap_fee.share,
                     )?
-                    .ok_or(ContractError::StableLpMintError)?
+                    .ok_or(ContractError::StableLpMintError)?;
+
+                    imbalance_fee_amount = deposit_computation.imbalance_fee_amount;
+                    deposit_computation.mint_amount
+                }
+            }
+            PoolType::Pegged { .. } => {
+                // the assets of pegged pools are valued in the second asset at the oracle rate
+                let rate = get_pegged_rate(deps.as_ref(), &env, &pool)?
+                    .ok_or(ContractError::PeggedRateUnavailable)?;
+                let deposits_value = get_pegged_value(&pool, &deposits, rate)?;
+
+                if is_dormant(total_share) {
+                    get_seed_share(
+                        deposits_value,
+                        total_share,
+                        &liquidity_token,
+                        &env,
+                        &mut messages,
+                    )?
+                } else {
+                    // pool_assets hold the balances before the deposit was made
+                    deposits_value
+                        .multiply_ratio(total_share, get_pegged_value(&pool, &pool_assets, rate)?)
                 }
             }
+            PoolType::ConcentratedLiquidity { .. } => {
+                return Err(ContractError::UnsupportedForConcentratedLiquidity {
+                    operation: "provide_liquidity".to_string(),
+                });
+            }
         };
 
         // assert slippage tolerance
@@ -277,11 +457,22 @@ pub fn provide_liquidity(
             total_share,
         )?;
 
+        if let Some(min_lp_to_receive) = min_lp_to_receive {
+            ensure!(
+                share >= min_lp_to_receive,
+                ContractError::MinLpToReceiveAssertion {
+                    min_lp_to_receive,
+                    share,
+                }
+            );
+        }
+
         // if the unlocking duration is set, lock the LP tokens in the farm manager
         if let Some(unlocking_duration) = unlocking_duration {
-            // check if receiver is the same as the sender of the tx
+            // check if receiver is the same as the sender of the tx, unless the contract itself is
+            // locking on behalf of the receiver
             ensure!(
-                receiver == info.sender.to_string(),
+                receiver == info.sender.to_string() || info.sender == env.contract.address,
                 ContractError::Unauthorized
             );
 
@@ -373,8 +564,15 @@ pub fn provide_liquidity(
             )?);
         }
 
-        // Increment the pool asset amount by the amount sent
-        for asset in deposits.iter() {
+        if !deposit_remainders.is_empty() {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: depositor.to_string(),
+                amount: deposit_remainders.clone(),
+            }));
+        }
+
+        // Increment the pool asset amount by the amount provided
+        for asset in provided_deposits.iter() {
             let asset_denom = &asset.denom;
             let pool_asset_index = pool_assets
                 .iter()
@@ -388,86 +586,320 @@ pub fn provide_liquidity(
 
         pool.assets = pool_assets.clone();
 
+        accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
         POOLS.save(deps.storage, &pool_identifier, &pool)?;
 
-        Ok(Response::new().add_messages(messages).add_attributes(vec![
-            ("action", "provide_liquidity"),
-            ("sender", info.sender.as_str()),
-            ("receiver", receiver.as_str()),
-            (
-                "assets",
-                &pool_assets
-                    .iter()
-                    .map(|asset| asset.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", "),
-            ),
-            ("share", &share.to_string()),
-        ]))
+        // the minimum liquidity is minted on top of the share by the first deposit
+        let minted_share = if total_share.is_zero() {
+            share.checked_add(MINIMUM_LIQUIDITY_AMOUNT)?
+        } else {
+            share
+        };
+        LP_SUPPLY.save(
+            deps.storage,
+            &pool_identifier,
+            &total_share.checked_add(minted_share)?,
+        )?;
+
+        // the result is returned as data, for controllers of interchain accounts to read it from
+        // the acknowledgement of the packet
+        let lp_asset = coin(share.u128(), &pool.lp_denom);
+        let result = ProvideLiquidityResult {
+            pool_identifier: pool_identifier.clone(),
+            receiver: receiver.clone(),
+            lp_asset,
+            locked: unlocking_duration.is_some(),
+        };
+
+        Ok(Response::new()
+            .add_messages(messages)
+            .add_submessages(price_hook_msgs)
+            .set_data(to_json_binary(&result)?)
+            .add_attributes(vec![
+                ("action", "provide_liquidity"),
+                ("sender", info.sender.as_str()),
+                ("receiver", receiver.as_str()),
+                (
+                    "assets",
+                    &pool_assets
+                        .iter()
+                        .map(|asset| asset.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                ("share", &share.to_string()),
+                ("imbalance_fee", &imbalance_fee_amount.to_string()),
+            ])
+            .add_attributes((!deposit_remainders.is_empty()).then(|| {
+                (
+                    "refunded_assets",
+                    deposit_remainders
+                        .iter()
+                        .map(|asset| asset.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            })))
+    }
+}
+
+/// Splits a deposit into a constant product pool into the amounts of each asset the given share
+/// is worth at the current reserves, rounded up in favor of the pool, and the remainder of each
+/// asset the share doesn't use.
+fn split_proportional_deposits(
+    deposits: &[Coin],
+    pool_assets: &[Coin],
+    total_share: Uint128,
+    share: Uint128,
+) -> Result<(Vec<Coin>, Vec<Coin>), ContractError> {
+    let mut provided_deposits = vec![];
+    let mut remainders = vec![];
+
+    for deposit in deposits {
+        let pool_asset = pool_assets
+            .iter()
+            .find(|pool_asset| pool_asset.denom == deposit.denom)
+            .ok_or(ContractError::AssetMismatch)?;
+
+        // the share is rounded down, so the amount it's worth never exceeds the deposit
+        let amount = share
+            .checked_mul_ceil((pool_asset.amount, total_share))?
+            .min(deposit.amount);
+        let remainder = deposit.amount.checked_sub(amount)?;
+
+        provided_deposits.push(coin(amount.u128(), &deposit.denom));
+        if !remainder.is_zero() {
+            remainders.push(coin(remainder.u128(), &deposit.denom));
+        }
     }
+
+    Ok((provided_deposits, remainders))
+}
+
+/// Swaps a single asset deposit into a pool of more than two assets into each of the other assets
+/// of the pool, an equal part of the deposit each, so it can be provided in proportion. The swaps
+/// are performed against the pool itself, paying their fees as any other swap. Returns the assets
+/// to provide, i.e. what's left of the deposit and the return of each swap.
+#[allow(clippy::too_many_arguments)]
+fn swap_deposit_into_pool_assets(
+    mut deps: DepsMut,
+    env: &Env,
+    trader: &Addr,
+    pool: &PoolInfo,
+    deposit: &Coin,
+    max_spread: Option<Decimal>,
+    messages: &mut Vec<CosmosMsg>,
+    price_hook_msgs: &mut Vec<SubMsg>,
+) -> Result<Vec<Coin>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    let swap_amount = deposit
+        .amount
+        .checked_div(Uint128::from(pool.assets.len() as u128))?;
+    let mut remaining = deposit.amount;
+    let mut deposits = vec![];
+
+    for ask_asset_denom in pool
+        .asset_denoms
+        .iter()
+        .filter(|denom| **denom != deposit.denom)
+    {
+        let swap_result = perform_swap(
+            deps.branch(),
+            env,
+            trader,
+            coin(swap_amount.u128(), &deposit.denom),
+            ask_asset_denom.clone(),
+            pool.pool_identifier.clone(),
+            None,
+            max_spread,
+            None,
+        )?;
+        remaining = remaining.checked_sub(swap_amount)?;
+
+        messages.extend(swap_result.recall_msgs);
+        price_hook_msgs.extend(swap_result.price_hook_msgs);
+
+        if !swap_result.burn_fee_asset.amount.is_zero() {
+            messages.push(burn_coin_msg(swap_result.burn_fee_asset));
+        }
+        if !swap_result.protocol_fee_asset.amount.is_zero() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: config.fee_collector_addr.to_string(),
+                    amount: vec![swap_result.protocol_fee_asset],
+                }
+                .into(),
+            );
+        }
+
+        deposits.push(swap_result.return_asset);
+    }
+
+    deposits.push(coin(remaining.u128(), &deposit.denom));
+
+    Ok(aggregate_coins(deposits)?)
+}
+
+/// Whether a pool with the given LP supply is empty or dormant, i.e. all its liquidity but the
+/// minimum locked by the first deposit was withdrawn. Either way, the next deposit seeds it.
+fn is_dormant(total_share: Uint128) -> bool {
+    total_share <= MINIMUM_LIQUIDITY_AMOUNT
+}
+
+/// Computes the LP shares minted to the deposit seeding a pool, out of the `initial_share` the
+/// deposit is worth. The first deposit of a pool locks MINIMUM_LIQUIDITY_AMOUNT in the contract to
+/// mitigate the risk of the first depositor preventing small liquidity providers from joining the
+/// pool. Dormant pools already hold the locked shares, which keep the dust left in the reserves,
+/// so the deposit re-seeding them gets the whole `initial_share` instead of shares priced against
+/// the dust.
+fn get_seed_share(
+    initial_share: Uint128,
+    total_share: Uint128,
+    liquidity_token: &str,
+    env: &Env,
+    messages: &mut Vec<CosmosMsg>,
+) -> Result<Uint128, ContractError> {
+    if !total_share.is_zero() {
+        ensure!(
+            !initial_share.is_zero(),
+            ContractError::InvalidInitialLiquidityAmount(MINIMUM_LIQUIDITY_AMOUNT)
+        );
+
+        return Ok(initial_share);
+    }
+
+    let share = initial_share.saturating_sub(MINIMUM_LIQUIDITY_AMOUNT);
+
+    // share should be above zero after subtracting the MINIMUM_LIQUIDITY_AMOUNT
+    ensure!(
+        !share.is_zero(),
+        ContractError::InvalidInitialLiquidityAmount(MINIMUM_LIQUIDITY_AMOUNT)
+    );
+
+    // mint the lp tokens to the contract
+    messages.push(mantra_dex_std::lp_common::mint_lp_token_msg(
+        liquidity_token.to_string(),
+        &env.contract.address,
+        &env.contract.address,
+        MINIMUM_LIQUIDITY_AMOUNT,
+    )?);
+
+    Ok(share)
 }
 
 /// Withdraws the liquidity. The user burns the LP tokens in exchange for the tokens provided, including
-/// the swap fees accrued by its share of the pool.
+/// the swap fees accrued by its share of the pool. The withdrawal fails if any of the assets withdrawn
+/// falls short of its amount in `min_amounts_out`. Deprecated pools can be exited even when
+/// withdrawals are disabled, so their LPs are never locked in.
 pub fn withdraw_liquidity(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     pool_identifier: String,
+    remote_receiver: Option<String>,
+    min_amounts_out: Option<Vec<Coin>>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    // check if the withdraw feature is enabled
-    if !config.feature_toggle.withdrawals_enabled {
+    // check if the withdraw feature is enabled, deprecated pools can always be exited
+    if !config.feature_toggle.withdrawals_enabled
+        && !DEPRECATED_POOLS.has(deps.storage, &pool_identifier)
+    {
         return Err(ContractError::OperationDisabled(
             "withdraw_liquidity".to_string(),
         ));
     }
 
     // Get the pool by the pool_identifier
-    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
-    let liquidity_token = pool.lp_denom.clone();
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // concentrated liquidity is withdrawn by position, see withdraw_concentrated_liquidity
+    ensure!(
+        !helpers::is_concentrated(&pool.pool_type),
+        ContractError::UnsupportedForConcentratedLiquidity {
+            operation: "withdraw_liquidity".to_string(),
+        }
+    );
     // Verify that the LP token was sent
-    let amount = cw_utils::must_pay(&info, &liquidity_token)?;
-
-    // Get the total share of the pool
-    let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
+    let amount = cw_utils::must_pay(&info, &pool.lp_denom)?;
 
-    // Get the ratio of the amount to withdraw to the total share
-    let share_ratio: Decimal256 = Decimal256::from_ratio(amount, total_share);
+    let (refund_assets, withdrawal_msgs) =
+        burn_liquidity(deps.branch(), &env, pool.clone(), amount)?;
 
-    // sanity check, the share_ratio cannot possibly be greater than 1
-    ensure!(
-        share_ratio <= Decimal256::one(),
-        ContractError::InvalidLpShareToWithdraw
-    );
+    // protect the withdrawer against the reserves moving before the withdrawal is executed
+    for min_amount_out in min_amounts_out.unwrap_or_default() {
+        ensure!(
+            pool.asset_denoms.contains(&min_amount_out.denom),
+            ContractError::AssetMismatch
+        );
 
-    // Use the ratio to calculate the amount of each pool asset to refund
-    let refund_assets: Vec<Coin> = pool
-        .assets
-        .iter()
-        .map(|pool_asset| {
-            Ok(Coin {
-                denom: pool_asset.denom.clone(),
-                amount: Uint128::try_from(
-                    Decimal256::from_ratio(pool_asset.amount, Uint256::one())
-                        .checked_mul(share_ratio)?
-                        .to_uint_floor(),
-                )?,
-            })
-        })
-        .collect::<Result<Vec<Coin>, ContractError>>()?
-        .into_iter()
-        // filter out assets with zero amount
-        .filter(|coin| coin.amount > Uint128::zero())
-        .collect();
+        let amount_out = refund_assets
+            .iter()
+            .find(|refund_asset| refund_asset.denom == min_amount_out.denom)
+            .map(|refund_asset| refund_asset.amount)
+            .unwrap_or_default();
+        ensure!(
+            amount_out >= min_amount_out.amount,
+            ContractError::MinAmountOutAssertion {
+                min_amount_out,
+                amount_out,
+            }
+        );
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    // Transfer the refund assets to the sender
-    messages.push(CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: refund_assets.clone(),
-    }));
+    match &remote_receiver {
+        // Transfer the refund assets to the remote receiver over IBC
+        Some(remote_receiver) => messages.extend(get_ibc_transfer_msgs(
+            deps.storage,
+            &env,
+            &refund_assets,
+            remote_receiver,
+            &info.sender,
+        )?),
+        // Transfer the refund assets to the sender
+        None => messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund_assets.clone(),
+        })),
+    }
+
+    Ok(Response::new()
+        .add_messages(withdrawal_msgs)
+        .add_messages(messages)
+        .set_data(to_json_binary(&refund_assets)?)
+        .add_attributes(vec![
+            ("action", "withdraw_liquidity"),
+            ("sender", info.sender.as_str()),
+            ("withdrawn_share", &amount.to_string()),
+        ])
+        .add_attributes(
+            remote_receiver.map(|remote_receiver| ("remote_receiver", remote_receiver)),
+        ))
+}
+
+/// Burns `amount` of the LP tokens of the given pool held by this contract, deducting the assets
+/// they are worth from the reserves of the pool. Returns the withdrawn assets, which are left for
+/// the caller to pay out, and the messages recalling the deployed reserves they take and burning
+/// the LP tokens.
+pub(crate) fn burn_liquidity(
+    deps: DepsMut,
+    env: &Env,
+    mut pool: PoolInfo,
+    amount: Uint128,
+) -> Result<(Vec<Coin>, Vec<CosmosMsg>), ContractError> {
+    let pool_identifier = pool.pool_identifier.clone();
+
+    // Get the total share of the pool
+    let total_share = get_lp_supply(&deps.as_ref(), &pool)?;
+
+    let refund_assets = helpers::get_withdrawal_assets(&pool, amount, total_share)?;
+
+    track_withdrawal_dust(deps.storage, &pool, amount, total_share, &refund_assets)?;
 
     // Deduct balances on pool_info by the amount of each refund asset
     for refund_asset in refund_assets.iter() {
@@ -483,21 +915,176 @@ pub fn withdraw_liquidity(
             .checked_sub(refund_asset.amount)?;
     }
 
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
     POOLS.save(deps.storage, &pool_identifier, &pool)?;
+    LP_SUPPLY.save(
+        deps.storage,
+        &pool_identifier,
+        &total_share.checked_sub(amount)?,
+    )?;
+
+    // recall the deployed reserves the withdrawal pays out before sending them
+    let mut messages = recall_uncovered_reserves(deps.storage, &pool)?;
 
     // Burn the LP tokens
     messages.push(mantra_dex_std::lp_common::burn_lp_asset_msg(
-        liquidity_token,
-        env.contract.address,
+        pool.lp_denom,
+        env.contract.address.clone(),
         amount,
     )?);
-    // update pool info
-    Ok(Response::new()
-        .add_messages(messages)
-        .set_data(to_json_binary(&refund_assets)?)
+
+    Ok((refund_assets, messages))
+}
+
+/// Withdraws a matured farm position of the sender, i.e. one that was closed and whose unlocking
+/// period has passed, and the liquidity it holds in a single transaction. The farm manager sends
+/// the LP tokens of the position back to this contract, which then burns them and sends the
+/// underlying assets to the sender.
+pub fn unlock_and_withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    position_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    // check if the withdraw feature is enabled, deprecated pools can always be exited
+    if !config.feature_toggle.withdrawals_enabled
+        && !DEPRECATED_POOLS.has(deps.storage, &pool_identifier)
+    {
+        return Err(ContractError::OperationDisabled(
+            "withdraw_liquidity".to_string(),
+        ));
+    }
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+
+    let positions_response: PositionsResponse = deps.querier.query_wasm_smart(
+        config.farm_manager_addr.to_string(),
+        &mantra_dex_std::farm_manager::QueryMsg::Positions {
+            filter_by: Some(PositionsBy::Identifier(position_identifier.clone())),
+            open_state: None,
+            start_after: None,
+            limit: None,
+        },
+    )?;
+
+    let position = positions_response
+        .positions
+        .into_iter()
+        .find(|position| {
+            position.identifier == position_identifier && position.receiver == info.sender
+        })
+        .ok_or(ContractError::FarmPositionNotFound {
+            identifier: position_identifier.clone(),
+        })?;
+
+    ensure!(
+        position.lp_asset.denom == pool.lp_denom,
+        ContractError::FarmPositionPoolMismatch {
+            identifier: position_identifier,
+            pool_identifier,
+        }
+    );
+    ensure!(
+        !position.open && position.is_expired(env.block.time.seconds()),
+        ContractError::FarmPositionNotMatured {
+            identifier: position_identifier,
+        }
+    );
+
+    // the LP tokens sent back by the farm manager are withdrawn once the position is withdrawn
+    let lp_balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &pool.lp_denom)?
+        .amount;
+    UNLOCK_AND_WITHDRAW_BUFFER.save(
+        deps.storage,
+        &UnlockAndWithdrawBuffer {
+            receiver: info.sender.clone(),
+            pool_identifier: pool_identifier.clone(),
+            lp_balance_before,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_submessage(SubMsg::reply_on_success(
+            wasm_execute(
+                config.farm_manager_addr,
+                &mantra_dex_std::farm_manager::ExecuteMsg::ManagePosition {
+                    action: mantra_dex_std::farm_manager::PositionAction::Withdraw {
+                        identifier: position_identifier.clone(),
+                        emergency_unlock: None,
+                    },
+                },
+                vec![],
+            )?,
+            UNLOCK_AND_WITHDRAW_REPLY_ID,
+        ))
         .add_attributes(vec![
-            ("action", "withdraw_liquidity"),
-            ("sender", info.sender.as_str()),
-            ("withdrawn_share", &amount.to_string()),
+            ("action", "unlock_and_withdraw_liquidity".to_string()),
+            ("sender", info.sender.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("position_identifier", position_identifier),
+            ("lp_amount", position.lp_asset.amount.to_string()),
         ]))
 }
+
+/// Donates the funds sent to the given pool, crediting them to its reserves without minting LP
+/// tokens, so they're shared among the current liquidity providers. Empty or dormant pools can't
+/// receive donations, as they would end up with the next depositor or locked for good.
+pub fn donate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the deposit feature is enabled
+    ensure!(
+        config.feature_toggle.deposits_enabled,
+        ContractError::OperationDisabled("donate".to_string())
+    );
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let donations = aggregate_coins(info.funds.clone())?;
+
+    ensure!(!donations.is_empty(), ContractError::EmptyAssets);
+
+    ensure!(
+        !is_dormant(get_lp_supply(&deps.as_ref(), &pool)?),
+        ContractError::DonationToEmptyPool
+    );
+
+    // credit the donations to the reserves, which must hold the assets sent
+    for asset in donations.iter() {
+        let pool_asset_index = pool
+            .assets
+            .iter()
+            .position(|pool_asset| pool_asset.denom == asset.denom)
+            .ok_or(ContractError::AssetMismatch)?;
+
+        pool.assets[pool_asset_index].amount = pool.assets[pool_asset_index]
+            .amount
+            .checked_add(asset.amount)?;
+    }
+
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "donate".to_string()),
+        ("donor", info.sender.into_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "assets",
+            donations
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
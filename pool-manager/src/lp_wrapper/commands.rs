@@ -0,0 +1,58 @@
+use cosmwasm_std::{ensure, to_json_binary, DepsMut, Env, MessageInfo, Response, SubMsg, WasmMsg};
+
+use crate::contract::LP_WRAPPER_REPLY_ID;
+use crate::state::{get_pool_by_identifier, LP_WRAPPERS, LP_WRAPPER_BUFFER};
+use crate::ContractError;
+
+/// The decimals of the CW20 tokens minted by the LP wrappers, same as the LP tokens.
+const LP_WRAPPER_DECIMALS: u8 = 6;
+
+/// Deploys the CW20 wrapper of the LP tokens of the given pool, from the given code id. There can
+/// only be one wrapper per pool. Only the owner can do this.
+pub fn create_lp_wrapper(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    code_id: u64,
+    name: String,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        !LP_WRAPPERS.has(deps.storage, &pool_identifier),
+        ContractError::LpWrapperAlreadyExists {
+            pool_identifier: pool_identifier.clone(),
+        }
+    );
+
+    // the address of the wrapper is stored once it is instantiated
+    LP_WRAPPER_BUFFER.save(deps.storage, &pool_identifier)?;
+
+    Ok(Response::default()
+        .add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(info.sender.to_string()),
+                code_id,
+                msg: to_json_binary(&mantra_dex_std::lp_wrapper::InstantiateMsg {
+                    lp_denom: pool.lp_denom.clone(),
+                    name,
+                    symbol,
+                    decimals: LP_WRAPPER_DECIMALS,
+                })?,
+                funds: vec![],
+                label: format!("{} LP Wrapper", pool_identifier),
+            },
+            LP_WRAPPER_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "create_lp_wrapper".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("lp_denom", pool.lp_denom),
+            ("code_id", code_id.to_string()),
+            ("pool_manager", env.contract.address.into_string()),
+        ]))
+}
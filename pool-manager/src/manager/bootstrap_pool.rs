@@ -0,0 +1,109 @@
+use cosmwasm_std::{ensure, wasm_execute, Coin, DepsMut, Env, MessageInfo, Response};
+
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::fee::PoolFee;
+use mantra_dex_std::pool_manager::{ExecuteMsg, PoolType};
+
+use crate::manager::commands::create_pool_and_get_identifier;
+use crate::ContractError;
+
+/// Creates a pool and seeds it with `seed_assets` in a single call, locking the LP tokens minted
+/// in a farm position owned by the receiver, which defaults to the sender. The funds sent must
+/// cover both the pool creation fees and the seed assets. Only the owner can do this.
+#[allow(clippy::too_many_arguments)]
+pub fn bootstrap_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_denoms: Vec<String>,
+    asset_decimals: Vec<u8>,
+    pool_fees: PoolFee,
+    pool_type: PoolType,
+    pool_identifier: Option<String>,
+    seed_assets: Vec<Coin>,
+    unlocking_duration: u64,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let (seed_assets, fee_funds) = split_seed_funds(&info.funds, seed_assets, asset_denoms.len())?;
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    let (response, pool_identifier) = create_pool_and_get_identifier(
+        deps,
+        env.clone(),
+        MessageInfo {
+            sender: info.sender.clone(),
+            funds: fee_funds,
+        },
+        asset_denoms,
+        asset_decimals,
+        pool_fees,
+        pool_type,
+        pool_identifier,
+        false,
+        None,
+    )?;
+
+    Ok(response
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::ProvideLiquidity {
+                slippage_tolerance: None,
+                max_spread: None,
+                receiver: Some(receiver.to_string()),
+                pool_identifier,
+                unlocking_duration: Some(unlocking_duration),
+                lock_position_identifier: None,
+                min_lp_to_receive: None,
+                deposit_route: None,
+            },
+            seed_assets.clone(),
+        )?)
+        .add_attributes(vec![
+            ("action", "bootstrap_pool".to_string()),
+            ("receiver", receiver.into_string()),
+            (
+                "seed_assets",
+                seed_assets
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ("unlocking_duration", unlocking_duration.to_string()),
+        ]))
+}
+
+/// Splits the funds sent to create and seed a pool into the seed assets, which must cover each of
+/// the `asset_count` assets of the pool, and the rest, going to the pool creation fees.
+pub(crate) fn split_seed_funds(
+    funds: &[Coin],
+    seed_assets: Vec<Coin>,
+    asset_count: usize,
+) -> Result<(Vec<Coin>, Vec<Coin>), ContractError> {
+    let seed_assets = aggregate_coins(seed_assets)?;
+    ensure!(
+        seed_assets.len() == asset_count,
+        ContractError::InvalidBootstrapSeed
+    );
+
+    // whatever isn't seeded into the pool goes to the pool creation fees
+    let mut fee_funds = aggregate_coins(funds.to_vec())?;
+    for seed_asset in seed_assets.iter() {
+        let fund = fee_funds
+            .iter_mut()
+            .find(|fund| fund.denom == seed_asset.denom)
+            .ok_or(ContractError::InvalidBootstrapSeed)?;
+        fund.amount = fund
+            .amount
+            .checked_sub(seed_asset.amount)
+            .map_err(|_| ContractError::InvalidBootstrapSeed)?;
+    }
+    fee_funds.retain(|fund| !fund.amount.is_zero());
+
+    Ok((seed_assets, fee_funds))
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use mantra_dex_std::pool_manager::PoolOverrides;
+
+use crate::manager::commands::create_pool;
+use crate::state::get_pool_by_identifier;
+use crate::ContractError;
+
+/// Creates a pool with the same assets, decimals, pool type and fees as `source_pool`, except for
+/// the ones set in `overrides`. The new pool is created as with `CreatePool`, so the pool creation
+/// fees must be paid and the new pool starts empty.
+pub fn clone_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    source_pool: String,
+    identifier: Option<String>,
+    overrides: PoolOverrides,
+) -> Result<Response, ContractError> {
+    let source = get_pool_by_identifier(&deps.as_ref(), &source_pool)?;
+
+    let response = create_pool(
+        deps,
+        env,
+        info,
+        source.asset_denoms,
+        source.asset_decimals,
+        overrides.pool_fees.unwrap_or(source.pool_fees),
+        overrides.pool_type.unwrap_or(source.pool_type),
+        identifier,
+        false,
+        None,
+    )?;
+
+    Ok(response.add_attributes(vec![
+        ("action", "clone_pool".to_string()),
+        ("source_pool", source_pool),
+    ]))
+}
@@ -1,35 +1,73 @@
 use cosmwasm_std::{
-    attr, ensure, Attribute, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128,
+    attr, coin, ensure, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, Event,
+    MessageInfo, Response, Storage, Uint128,
 };
 
 use mantra_dex_std::coin::is_factory_token;
 use mantra_dex_std::constants::LP_SYMBOL;
 use mantra_dex_std::fee::PoolFee;
-use mantra_dex_std::pool_manager::{PoolInfo, PoolType};
+use mantra_dex_std::pool_manager::{
+    PoolCreationParams, PoolFeeCaps, PoolInfo, PoolMetadata, PoolType,
+};
 use mantra_dex_std::tokenfactory::utils::get_factory_denom_creation_fee;
 
 use crate::helpers::{
-    validate_fees_are_paid, validate_no_additional_funds_sent_with_pool_creation,
-    validate_pool_identifier,
+    get_asset_scaling_factors, get_canonical_pool_identifier,
+    validate_any_pool_creation_fee_is_paid, validate_pool_fees, validate_pool_identifier,
+};
+use crate::ibc_denom::commands::get_ibc_denom_traces;
+use crate::manager::validate_pool_metadata;
+use crate::state::{
+    get_pool_by_identifier, LP_SUPPLY, POOL_ALIASES, POOL_COUNTER, POOL_CREATOR_ALLOWLIST,
+    POOL_DENOM_TRACES, POOL_METADATA,
 };
-use crate::state::{get_pool_by_identifier, POOL_COUNTER};
 use crate::{
     state::{Config, CONFIG, POOLS},
     ContractError,
 };
 
-pub const MAX_ASSETS_PER_POOL: usize = 4usize;
+/// The maximum number of assets of constant product pools the config is instantiated with.
+pub const DEFAULT_MAX_ASSETS_PER_POOL: u32 = 4u32;
+/// The hard ceiling the owner can raise the maximum number of assets of constant product pools to.
+pub const MAX_ASSETS_PER_POOL_CEILING: u32 = 8u32;
+/// The maximum number of assets of stableswap pools the config is instantiated with.
+pub const DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL: u32 = 5u32;
+/// The hard ceiling the owner can raise the maximum number of assets of stableswap pools to, past
+/// which the `n^n` terms of the invariant computation risk overflowing.
+pub const MAX_ASSETS_PER_STABLESWAP_POOL_CEILING: u32 = 8u32;
+pub const MAX_ASSETS_PER_PEGGED_POOL: usize = 2usize;
+pub const MAX_ASSETS_PER_CONCENTRATED_POOL: usize = 2usize;
 pub const MIN_ASSETS_PER_POOL: usize = 2usize;
 
+/// The delay, in seconds, pool fee updates are applied after that the config is instantiated with.
+pub const DEFAULT_POOL_FEE_UPDATE_DELAY: u64 = 86_400u64;
+
+/// The pool fee caps backfilled into configs stored before the caps were configurable.
+pub const DEFAULT_POOL_FEE_CAPS: PoolFeeCaps = PoolFeeCaps {
+    max_swap_fee: Decimal::percent(20),
+    max_protocol_fee: Decimal::percent(20),
+    max_burn_fee: Decimal::percent(20),
+    max_extra_fees: Decimal::percent(20),
+    max_total_fee: Decimal::percent(50),
+};
+
 /// The prefix used when creation a pool with an explicitly provided ID
 pub const EXPLICIT_POOL_ID_PREFIX: &str = "o.";
 
 /// The prefix used when creation a pool with an auto-generated ID
 pub const AUTO_POOL_ID_PREFIX: &str = "p.";
 
+/// The prefix used when creation a pool with a canonical ID, derived from its assets, type and fee tier
+pub const CANONICAL_POOL_ID_PREFIX: &str = "c.";
+
 /// Creates a pool with 2, 3, or N assets. The function dynamically handles different numbers of assets,
 /// allowing for the creation of pools with varying configurations. The maximum number of assets per pool is defined by
-/// the constant `MAX_ASSETS_PER_POOL`.
+/// the `max_assets_per_pool` of the config, or its `max_assets_per_stableswap_pool` for stableswap pools.
+///
+/// With `canonical_identifier` set, the pool is identified by the identifier derived from its assets, type and fee tier
+/// with [get_canonical_pool_identifier], and the explicit identifier, if given, is kept as an alias of it.
+///
+/// The pool can be given the metadata front-ends display it with, which its admin can update later on.
 ///
 /// # Example
 ///
@@ -38,7 +76,7 @@ pub const AUTO_POOL_ID_PREFIX: &str = "p.";
 /// # use mantra_dex_std::fee::PoolFee;
 /// # use mantra_dex_std::fee::Fee;
 /// # use pool_manager::error::ContractError;
-/// # use pool_manager::manager::commands::MAX_ASSETS_PER_POOL;
+/// # use pool_manager::manager::commands::DEFAULT_MAX_ASSETS_PER_POOL;
 /// # use pool_manager::manager::commands::create_pool;
 /// # use std::convert::TryInto;
 /// # use mantra_dex_std::pool_manager::PoolType;
@@ -66,11 +104,10 @@ pub const AUTO_POOL_ID_PREFIX: &str = "p.";
 /// let pool_type = PoolType::ConstantProduct;
 /// let token_factory_lp = false;
 ///
-/// let response = create_pool(deps, env, info, asset_infos, asset_decimals, pool_fees, pool_type, None)?;
+/// let response = create_pool(deps, env, info, asset_infos, asset_decimals, pool_fees, pool_type, None, false, None)?;
 /// # Ok(response)
 /// # }
 /// ```
-#[allow(unreachable_code)]
 #[allow(clippy::too_many_arguments)]
 pub fn create_pool(
     deps: DepsMut,
@@ -81,10 +118,108 @@ pub fn create_pool(
     pool_fees: PoolFee,
     pool_type: PoolType,
     pool_identifier: Option<String>,
+    canonical_identifier: bool,
+    metadata: Option<PoolMetadata>,
 ) -> Result<Response, ContractError> {
+    create_pool_and_get_identifier(
+        deps,
+        env,
+        info,
+        asset_denoms,
+        asset_decimals,
+        pool_fees,
+        pool_type,
+        pool_identifier,
+        canonical_identifier,
+        metadata,
+    )
+    .map(|(response, _)| response)
+}
+
+/// Creates the given pools atomically, as a launch batch. The funds sent must be the pool creation
+/// fees of a single pool, as accepted by [create_pool], times the number of pools, which are then
+/// paid pool by pool. Each pool emits its own `create_pool` event.
+pub fn create_pools(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pools: Vec<PoolCreationParams>,
+) -> Result<Response, ContractError> {
+    ensure!(!pools.is_empty(), ContractError::NoPoolsToCreate);
+
+    // split the funds evenly between the pools, each pool validating its share
+    let pool_count = Uint128::from(pools.len() as u128);
+    let funds_per_pool = info
+        .funds
+        .iter()
+        .map(|fund| {
+            ensure!(
+                (fund.amount % pool_count).is_zero(),
+                ContractError::UnevenPoolCreationFees {
+                    pools: pools.len(),
+                    funds: fund.to_string(),
+                }
+            );
+            Ok(coin(
+                fund.amount.checked_div(pool_count)?.u128(),
+                &fund.denom,
+            ))
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?;
+    let pool_info = MessageInfo {
+        sender: info.sender,
+        funds: funds_per_pool,
+    };
+
+    let mut response = Response::default();
+    let mut pool_identifiers = vec![];
+    for pool in pools {
+        let (pool_response, pool_identifier) = create_pool_and_get_identifier(
+            deps.branch(),
+            env.clone(),
+            pool_info.clone(),
+            pool.asset_denoms,
+            pool.asset_decimals,
+            pool.pool_fees,
+            pool.pool_type,
+            pool.pool_identifier,
+            pool.canonical_identifier,
+            pool.metadata,
+        )?;
+
+        response = response
+            .add_submessages(pool_response.messages)
+            .add_event(Event::new("create_pool").add_attributes(pool_response.attributes));
+        pool_identifiers.push(pool_identifier);
+    }
+
+    Ok(response.add_attributes(vec![
+        ("action", "create_pools".to_string()),
+        ("pool_identifiers", pool_identifiers.join(",")),
+    ]))
+}
+
+/// Creates a pool like [create_pool], returning the identifier of the new pool together with the
+/// response, for flows acting on the pool right after creating it.
+#[allow(unreachable_code)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_pool_and_get_identifier(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_denoms: Vec<String>,
+    asset_decimals: Vec<u8>,
+    pool_fees: PoolFee,
+    pool_type: PoolType,
+    pool_identifier: Option<String>,
+    canonical_identifier: bool,
+    metadata: Option<PoolMetadata>,
+) -> Result<(Response, String), ContractError> {
     // Load config for pool creation fee
     let config: Config = CONFIG.load(deps.storage)?;
 
+    assert_pool_creator(deps.storage, &config, &info.sender)?;
+
     // Ensure that the number of assets and decimals match, and that they are not empty
     ensure!(
         !asset_denoms.is_empty()
@@ -94,31 +229,64 @@ pub fn create_pool(
     );
 
     // Ensure that the number of assets is within the allowed range
+    let max_assets = match pool_type {
+        PoolType::ConstantProduct => config.max_assets_per_pool as usize,
+        PoolType::StableSwap { .. } | PoolType::ComposableStableSwap { .. } => {
+            config.max_assets_per_stableswap_pool as usize
+        }
+        PoolType::Pegged { .. } => MAX_ASSETS_PER_PEGGED_POOL,
+        PoolType::ConcentratedLiquidity { .. } => MAX_ASSETS_PER_CONCENTRATED_POOL,
+    };
     ensure!(
-        asset_denoms.len() <= MAX_ASSETS_PER_POOL,
+        asset_denoms.len() <= max_assets,
         ContractError::TooManyAssets {
             assets_provided: asset_denoms.len(),
+            max_assets,
         }
     );
 
-    // check if the pool and token factory fees were paid
-    let total_fees = validate_fees_are_paid(
-        &config.pool_creation_fee,
+    // composable stableswap pools need a non-zero weight per asset
+    if let PoolType::ComposableStableSwap { weights, .. } = &pool_type {
+        ensure!(
+            weights.len() == asset_denoms.len() && weights.iter().all(|weight| !weight.is_zero()),
+            ContractError::InvalidPoolWeights
+        );
+    }
+
+    // pegged pools need an oracle to quote their rate, within a band narrower than the rate itself
+    if let PoolType::Pegged {
+        oracle,
+        max_deviation,
+        ..
+    } = &pool_type
+    {
+        ensure!(
+            deps.api.addr_validate(oracle).is_ok() && *max_deviation < Decimal::one(),
+            ContractError::InvalidPeggedPoolConfig
+        );
+    }
+
+    // concentrated liquidity pools need a tick spacing to place the ranges of the positions on
+    if let PoolType::ConcentratedLiquidity { tick_spacing } = &pool_type {
+        ensure!(*tick_spacing > 0, ContractError::InvalidTickSpacing);
+    }
+
+    // check if the pool and token factory fees were paid, with any of the accepted pool creation
+    // fees, and that the user didn't accidentally send more tokens than needed
+    let (pool_creation_fee, _) = validate_any_pool_creation_fee_is_paid(
+        &config.pool_creation_fees,
         get_factory_denom_creation_fee(deps.as_ref())?,
         &info,
     )?;
 
-    // make sure the user doesn't accidentally send more tokens than needed
-    validate_no_additional_funds_sent_with_pool_creation(&info, total_fees)?;
-
     // Prepare the sending of pool creation fee
     let mut messages: Vec<CosmosMsg> = vec![];
-    if !config.pool_creation_fee.amount.is_zero() {
+    if !pool_creation_fee.amount.is_zero() {
         // send pool creation fee to the fee collector
         messages.push(
             BankMsg::Send {
                 to_address: config.fee_collector_addr.to_string(),
-                amount: vec![config.pool_creation_fee],
+                amount: vec![pool_creation_fee],
             }
             .into(),
         );
@@ -132,11 +300,19 @@ pub fn create_pool(
         return Err(ContractError::SameAsset);
     }
 
-    // Verify pool fees
-    pool_fees.is_valid()?;
+    // Verify pool fees are within the caps defined in the config
+    validate_pool_fees(&pool_fees, &config.pool_fee_caps)?;
+
+    let explicit_identifier = pool_identifier.map(|id| format!("{EXPLICIT_POOL_ID_PREFIX}{id}"));
 
-    let identifier = if let Some(id) = pool_identifier {
-        format!("{EXPLICIT_POOL_ID_PREFIX}{id}")
+    // pools with a canonical identifier keep the explicit one, if any, as an alias
+    let (identifier, alias) = if canonical_identifier {
+        (
+            get_canonical_pool_identifier(&asset_denoms, &pool_type, &pool_fees),
+            explicit_identifier,
+        )
+    } else if let Some(id) = explicit_identifier {
+        (id, None)
     } else {
         // if no identifier is provided, use the pool counter (id) as identifier
         let pool_counter =
@@ -144,22 +320,30 @@ pub fn create_pool(
                 counter += 1;
                 Ok(counter)
             })?;
-        format!("{AUTO_POOL_ID_PREFIX}{pool_counter}")
+        (format!("{AUTO_POOL_ID_PREFIX}{pool_counter}"), None)
     };
 
-    validate_pool_identifier(&identifier)?;
-
-    // check if there is an existing pool with the given identifier
-    let pool = get_pool_by_identifier(&deps.as_ref(), &identifier);
-    if pool.is_ok() {
-        return Err(ContractError::PoolExists {
-            asset_infos: asset_denoms
-                .iter()
-                .map(|i| i.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-            identifier,
-        });
+    for id in std::iter::once(&identifier).chain(alias.iter()) {
+        validate_pool_identifier(id)?;
+
+        // check if there is an existing pool or alias with the given identifier
+        let pool = get_pool_by_identifier(&deps.as_ref(), id);
+        if pool.is_ok() || POOL_ALIASES.has(deps.storage, id) {
+            return Err(ContractError::PoolExists {
+                asset_infos: asset_denoms
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                identifier: id.clone(),
+            });
+        }
+    }
+
+    // IBC denoms must resolve to a trace matching them, through an allowed channel
+    let denom_traces = get_ibc_denom_traces(deps.as_ref(), &asset_denoms)?;
+    if !denom_traces.is_empty() {
+        POOL_DENOM_TRACES.save(deps.storage, &identifier, &denom_traces)?;
     }
 
     let mut attributes = Vec::<Attribute>::new();
@@ -173,6 +357,8 @@ pub fn create_pool(
         })
         .collect::<Vec<_>>();
 
+    let asset_scaling_factors = get_asset_scaling_factors(&asset_decimals)?;
+
     let lp_symbol = format!("{identifier}.{LP_SYMBOL}");
     let lp_asset = format!("{}/{}/{}", "factory", env.contract.address, lp_symbol);
 
@@ -190,17 +376,31 @@ pub fn create_pool(
         &identifier,
         &PoolInfo {
             pool_identifier: identifier.clone(),
+            admin: Some(info.sender.clone()),
             asset_denoms,
             pool_type: pool_type.clone(),
             lp_denom: lp_asset.clone(),
             asset_decimals,
+            asset_scaling_factors,
             pool_fees,
             assets,
         },
     )?;
 
+    LP_SUPPLY.save(deps.storage, &identifier, &Uint128::zero())?;
+
     attributes.push(attr("lp_asset", lp_asset));
 
+    if let Some(alias) = alias {
+        POOL_ALIASES.save(deps.storage, &alias, &identifier)?;
+        attributes.push(attr("alias", alias));
+    }
+
+    if let Some(metadata) = metadata {
+        validate_pool_metadata(&metadata)?;
+        POOL_METADATA.save(deps.storage, &identifier, &metadata)?;
+    }
+
     messages.push(mantra_dex_std::tokenfactory::create_denom::create_denom(
         env.contract.address,
         lp_symbol,
@@ -210,7 +410,61 @@ pub fn create_pool(
     attributes.push(attr("pool_identifier", identifier.as_str()));
     attributes.push(attr("pool_type", pool_type.get_label()));
 
-    Ok(Response::new()
-        .add_attributes(attributes)
-        .add_messages(messages))
+    Ok((
+        Response::new()
+            .add_attributes(attributes)
+            .add_messages(messages),
+        identifier,
+    ))
+}
+
+/// Adds and removes addresses from the allowlist of pool creators, the only addresses other than
+/// the owner allowed to create pools while pool creation isn't permissionless. Only the owner can
+/// do this.
+pub fn update_pool_creator_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    for address in &add {
+        let address = deps.api.addr_validate(address)?;
+        POOL_CREATOR_ALLOWLIST.save(deps.storage, &address, &true)?;
+    }
+
+    for address in &remove {
+        let address = deps.api.addr_validate(address)?;
+        POOL_CREATOR_ALLOWLIST.remove(deps.storage, &address);
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_pool_creator_allowlist".to_string()),
+        ("added", add.join(",")),
+        ("removed", remove.join(",")),
+    ]))
+}
+
+/// Ensures the given address can create pools, i.e. pool creation is permissionless, or the address
+/// is the owner or on the allowlist of pool creators.
+fn assert_pool_creator(
+    storage: &dyn Storage,
+    config: &Config,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if config.permissionless_pool_creation || cw_ownable::is_owner(storage, address)? {
+        return Ok(());
+    }
+
+    ensure!(
+        POOL_CREATOR_ALLOWLIST.has(storage, address),
+        ContractError::PoolCreatorNotAllowlisted {
+            address: address.to_string(),
+        }
+    );
+
+    Ok(())
 }
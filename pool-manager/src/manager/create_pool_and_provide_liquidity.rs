@@ -0,0 +1,78 @@
+use cosmwasm_std::{wasm_execute, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::fee::PoolFee;
+use mantra_dex_std::pool_manager::{ExecuteMsg, PoolMetadata, PoolType};
+
+use crate::manager::bootstrap_pool::split_seed_funds;
+use crate::manager::commands::create_pool_and_get_identifier;
+use crate::ContractError;
+
+/// Creates a pool and provides its initial liquidity in a single transaction, so the creator can't
+/// be front-run on the first deposit. The funds sent must cover both the pool creation fees and
+/// `liquidity`, which must include each of the pool assets. The LP tokens are minted to the
+/// receiver, which defaults to the sender.
+#[allow(clippy::too_many_arguments)]
+pub fn create_pool_and_provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_denoms: Vec<String>,
+    asset_decimals: Vec<u8>,
+    pool_fees: PoolFee,
+    pool_type: PoolType,
+    pool_identifier: Option<String>,
+    canonical_identifier: bool,
+    metadata: Option<PoolMetadata>,
+    liquidity: Vec<Coin>,
+    receiver: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let (liquidity, fee_funds) = split_seed_funds(&info.funds, liquidity, asset_denoms.len())?;
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    let (response, pool_identifier) = create_pool_and_get_identifier(
+        deps,
+        env.clone(),
+        MessageInfo {
+            sender: info.sender,
+            funds: fee_funds,
+        },
+        asset_denoms,
+        asset_decimals,
+        pool_fees,
+        pool_type,
+        pool_identifier,
+        canonical_identifier,
+        metadata,
+    )?;
+
+    // the deposit runs right after the pool is created, failing the whole transaction if it fails
+    Ok(response
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::ProvideLiquidity {
+                slippage_tolerance: None,
+                max_spread: None,
+                receiver: Some(receiver.to_string()),
+                pool_identifier,
+                unlocking_duration: None,
+                lock_position_identifier: None,
+                min_lp_to_receive,
+                deposit_route: None,
+            },
+            liquidity.clone(),
+        )?)
+        .add_attributes(vec![
+            ("action", "create_pool_and_provide_liquidity".to_string()),
+            ("receiver", receiver.into_string()),
+            (
+                "liquidity",
+                liquidity
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
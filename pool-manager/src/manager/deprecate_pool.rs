@@ -0,0 +1,37 @@
+use cosmwasm_std::{ensure, DepsMut, Env, MessageInfo, Response};
+
+use crate::state::{get_pool_by_identifier, DEPRECATED_POOLS};
+use crate::ContractError;
+
+/// Deprecates the given pool to wind it down, disabling new deposits and swaps for good while
+/// keeping withdrawals enabled, so LPs can always exit. Only the owner can do this.
+pub fn deprecate_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        !DEPRECATED_POOLS.has(deps.storage, &pool.pool_identifier),
+        ContractError::PoolDeprecated {
+            pool_identifier: pool.pool_identifier,
+        }
+    );
+
+    DEPRECATED_POOLS.save(
+        deps.storage,
+        &pool.pool_identifier,
+        &env.block.time.seconds(),
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "deprecate_pool".to_string()),
+        ("pool_identifier", pool.pool_identifier),
+        ("deprecated_at", env.block.time.seconds().to_string()),
+    ]))
+}
@@ -1,4 +1,32 @@
 pub mod commands;
 
+mod bootstrap_pool;
+mod clone_pool;
+mod create_pool_and_provide_liquidity;
+mod deprecate_pool;
+mod pool_admin;
+mod sync_pool;
 mod update_config;
+mod update_imbalance_fee;
+mod update_min_lp_lock;
+mod update_pool_fees;
+mod update_pool_metadata;
+mod update_rate_providers;
+mod update_reserve_floors;
+mod update_unlocking_duration_bounds;
+pub use bootstrap_pool::bootstrap_pool;
+pub use clone_pool::clone_pool;
+pub use create_pool_and_provide_liquidity::create_pool_and_provide_liquidity;
+pub use deprecate_pool::deprecate_pool;
+pub(crate) use pool_admin::assert_pool_admin;
+pub use pool_admin::{accept_pool_admin, propose_pool_admin};
+pub use sync_pool::sync_pool;
 pub use update_config::update_config;
+pub use update_imbalance_fee::update_imbalance_fee;
+pub use update_min_lp_lock::update_min_lp_lock;
+pub use update_pool_fees::{apply_pool_fees, update_pool_fees};
+pub use update_pool_metadata::update_pool_metadata;
+pub(crate) use update_pool_metadata::validate_pool_metadata;
+pub use update_rate_providers::update_rate_providers;
+pub use update_reserve_floors::update_reserve_floors;
+pub use update_unlocking_duration_bounds::update_unlocking_duration_bounds;
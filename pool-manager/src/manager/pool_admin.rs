@@ -0,0 +1,89 @@
+use cosmwasm_std::{ensure, Addr, DepsMut, MessageInfo, Response, Storage};
+use mantra_dex_std::pool_manager::PoolInfo;
+
+use crate::state::{get_pool_by_identifier, PENDING_POOL_ADMINS, POOLS};
+use crate::ContractError;
+
+/// Proposes a new admin for the given pool, who has to accept it with [accept_pool_admin] for the
+/// transfer to take effect. Proposing no admin cancels the pending transfer. Only the admin of the
+/// pool or the owner can do this.
+pub fn propose_pool_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    new_admin: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // permission check
+    assert_pool_admin(deps.storage, &pool, &info.sender)?;
+
+    let Some(new_admin) = new_admin else {
+        PENDING_POOL_ADMINS.remove(deps.storage, &pool.pool_identifier);
+
+        return Ok(Response::default().add_attributes(vec![
+            ("action", "propose_pool_admin".to_string()),
+            ("pool_identifier", pool.pool_identifier),
+            ("cancelled", "true".to_string()),
+        ]));
+    };
+
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_POOL_ADMINS.save(deps.storage, &pool.pool_identifier, &new_admin)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "propose_pool_admin".to_string()),
+        ("pool_identifier", pool.pool_identifier),
+        ("pending_admin", new_admin.into_string()),
+    ]))
+}
+
+/// Accepts the admin role of the given pool, completing its transfer. Only the pending admin can
+/// do this.
+pub fn accept_pool_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let pending_admin = PENDING_POOL_ADMINS
+        .may_load(deps.storage, &pool.pool_identifier)?
+        .ok_or(ContractError::NoPendingPoolAdmin {
+            pool_identifier: pool.pool_identifier.clone(),
+        })?;
+
+    // permission check
+    ensure!(info.sender == pending_admin, ContractError::Unauthorized);
+
+    PENDING_POOL_ADMINS.remove(deps.storage, &pool.pool_identifier);
+    let previous_admin = pool.admin.replace(pending_admin.clone());
+    POOLS.save(deps.storage, &pool.pool_identifier, &pool)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "accept_pool_admin".to_string()),
+        ("pool_identifier", pool.pool_identifier),
+        (
+            "previous_admin",
+            previous_admin.map(Addr::into_string).unwrap_or_default(),
+        ),
+        ("admin", pending_admin.into_string()),
+    ]))
+}
+
+/// Ensures the given address can manage the given pool, i.e. it's the admin of the pool or the
+/// owner of the contract.
+pub(crate) fn assert_pool_admin(
+    storage: &dyn Storage,
+    pool: &PoolInfo,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    ensure!(
+        pool.admin.as_ref() == Some(address) || cw_ownable::is_owner(storage, address)?,
+        ContractError::Unauthorized
+    );
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use cosmwasm_std::{coin, ensure, Decimal, DepsMut, Env, MessageInfo, Response};
+
+use crate::skim::commands::get_tracked_balance;
+use crate::state::{get_pool_by_identifier, POOLS, YIELD_DEPLOYMENTS};
+use crate::twap::accumulate_prices;
+use crate::ContractError;
+
+/// Reconciles the reserves of the given pool with the balances attributable to it, i.e. the
+/// balances of the contract minus whatever it accounts for elsewhere, for instance other pools
+/// holding the same assets or funds escrowed by orders, plus the reserves it deployed into yield
+/// strategies. The sync is rejected if any reserve would move by more than `max_deviation`
+/// relative to its tracked amount. Only the owner can do this.
+pub fn sync_pool(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    max_deviation: Decimal,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let previous_assets = pool.assets.clone();
+
+    let mut deviations = vec![];
+    for asset in pool.assets.iter_mut() {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &asset.denom)?
+            .amount;
+        // the reserves the pool deployed into a yield strategy aren't held by the contract
+        let deployed = YIELD_DEPLOYMENTS
+            .may_load(deps.storage, (&pool_identifier, &asset.denom))?
+            .map(|deployment| deployment.deployed)
+            .unwrap_or_default();
+        let tracked_elsewhere = get_tracked_balance(deps.as_ref(), &asset.denom)?
+            .checked_sub(asset.amount.saturating_sub(deployed))?;
+        let attributable = balance
+            .saturating_sub(tracked_elsewhere)
+            .checked_add(deployed)?;
+
+        let deviation = if asset.amount.is_zero() {
+            if attributable.is_zero() {
+                Decimal::zero()
+            } else {
+                Decimal::MAX
+            }
+        } else {
+            Decimal::from_ratio(asset.amount.abs_diff(attributable), asset.amount)
+        };
+
+        ensure!(
+            deviation <= max_deviation,
+            ContractError::SyncDeviationExceeded {
+                tracked: asset.clone(),
+                attributable: coin(attributable.u128(), &asset.denom),
+                max_deviation,
+            }
+        );
+
+        deviations.push(format!("{}:{}", asset.denom, deviation));
+        asset.amount = attributable;
+    }
+
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "sync_pool".to_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "previous_assets",
+            previous_assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        (
+            "synced_assets",
+            pool.assets
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        ("deviations", deviations.join(",")),
+        ("max_deviation", max_deviation.to_string()),
+    ]))
+}
@@ -1,15 +1,30 @@
-use cosmwasm_std::{Coin, DepsMut, MessageInfo, Response};
-use mantra_dex_std::pool_manager::{Config, FeatureToggle};
+use cosmwasm_std::{ensure, Coin, Decimal, DepsMut, MessageInfo, Response};
+use mantra_dex_std::farm_manager::SuccessionResponse;
+use mantra_dex_std::pool_manager::{Config, FeatureToggle, PoolFeeCaps};
 
+use crate::helpers::{validate_pool_creation_fees, validate_pool_fee_caps};
+use crate::manager::commands::{
+    MAX_ASSETS_PER_POOL_CEILING, MAX_ASSETS_PER_STABLESWAP_POOL_CEILING, MIN_ASSETS_PER_POOL,
+};
+use crate::swap::commands::MAX_REFERRAL_FEE_BPS_CEILING;
 use crate::{state::CONFIG, ContractError};
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     fee_collector_addr: Option<String>,
     farm_manager_addr: Option<String>,
-    pool_creation_fee: Option<Coin>,
+    pool_creation_fees: Option<Vec<Coin>>,
     feature_toggle: Option<FeatureToggle>,
+    pool_fee_caps: Option<PoolFeeCaps>,
+    default_max_spread: Option<Decimal>,
+    max_spread_cap: Option<Decimal>,
+    max_assets_per_pool: Option<u32>,
+    max_assets_per_stableswap_pool: Option<u32>,
+    permissionless_pool_creation: Option<bool>,
+    pool_fee_update_delay: Option<u64>,
+    max_referral_fee_bps: Option<u16>,
 ) -> Result<Response, ContractError> {
     // permission check
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
@@ -22,16 +37,105 @@ pub fn update_config(
 
         if let Some(new_farm_manager_addr) = farm_manager_addr {
             let farm_manager_addr = deps.api.addr_validate(&new_farm_manager_addr)?;
+
+            // the farm manager can only be rotated to its successor, so the positions locked in
+            // the current one can be migrated rather than left behind
+            if farm_manager_addr != config.farm_manager_addr {
+                let current_succession: SuccessionResponse = deps.querier.query_wasm_smart(
+                    &config.farm_manager_addr,
+                    &mantra_dex_std::farm_manager::QueryMsg::Succession {},
+                )?;
+                let new_succession: SuccessionResponse = deps.querier.query_wasm_smart(
+                    &farm_manager_addr,
+                    &mantra_dex_std::farm_manager::QueryMsg::Succession {},
+                )?;
+
+                ensure!(
+                    current_succession.successor.as_ref() == Some(&farm_manager_addr)
+                        && new_succession.predecessor.as_ref() == Some(&config.farm_manager_addr),
+                    ContractError::FarmManagerNotSuccessor {
+                        farm_manager_addr: farm_manager_addr.into_string(),
+                    }
+                );
+            }
+
             config.farm_manager_addr = farm_manager_addr;
         }
 
-        if let Some(pool_creation_fee) = pool_creation_fee {
-            config.pool_creation_fee = pool_creation_fee;
+        if let Some(pool_creation_fees) = pool_creation_fees {
+            validate_pool_creation_fees(&pool_creation_fees)?;
+            config.pool_creation_fees = pool_creation_fees;
         }
 
         if let Some(feature_toggle) = feature_toggle {
             config.feature_toggle = feature_toggle;
         }
+
+        if let Some(pool_fee_caps) = pool_fee_caps {
+            validate_pool_fee_caps(&pool_fee_caps)?;
+            config.pool_fee_caps = pool_fee_caps;
+        }
+
+        if let Some(default_max_spread) = default_max_spread {
+            config.default_max_spread = default_max_spread;
+        }
+
+        if let Some(max_spread_cap) = max_spread_cap {
+            config.max_spread_cap = max_spread_cap;
+        }
+
+        if let Some(max_assets_per_pool) = max_assets_per_pool {
+            ensure!(
+                (MIN_ASSETS_PER_POOL as u32..=MAX_ASSETS_PER_POOL_CEILING)
+                    .contains(&max_assets_per_pool),
+                ContractError::InvalidMaxAssetsPerPool {
+                    max_assets_per_pool,
+                    min: MIN_ASSETS_PER_POOL,
+                    ceiling: MAX_ASSETS_PER_POOL_CEILING,
+                }
+            );
+            config.max_assets_per_pool = max_assets_per_pool;
+        }
+
+        if let Some(max_assets_per_stableswap_pool) = max_assets_per_stableswap_pool {
+            ensure!(
+                (MIN_ASSETS_PER_POOL as u32..=MAX_ASSETS_PER_STABLESWAP_POOL_CEILING)
+                    .contains(&max_assets_per_stableswap_pool),
+                ContractError::InvalidMaxAssetsPerPool {
+                    max_assets_per_pool: max_assets_per_stableswap_pool,
+                    min: MIN_ASSETS_PER_POOL,
+                    ceiling: MAX_ASSETS_PER_STABLESWAP_POOL_CEILING,
+                }
+            );
+            config.max_assets_per_stableswap_pool = max_assets_per_stableswap_pool;
+        }
+
+        if let Some(permissionless_pool_creation) = permissionless_pool_creation {
+            config.permissionless_pool_creation = permissionless_pool_creation;
+        }
+
+        if let Some(pool_fee_update_delay) = pool_fee_update_delay {
+            config.pool_fee_update_delay = pool_fee_update_delay;
+        }
+
+        if let Some(max_referral_fee_bps) = max_referral_fee_bps {
+            ensure!(
+                max_referral_fee_bps <= MAX_REFERRAL_FEE_BPS_CEILING,
+                ContractError::InvalidMaxReferralFee {
+                    max_referral_fee_bps,
+                    ceiling: MAX_REFERRAL_FEE_BPS_CEILING,
+                }
+            );
+            config.max_referral_fee_bps = max_referral_fee_bps;
+        }
+
+        // the default spread must be within the cap, which can't exceed 100%
+        ensure!(
+            !config.max_spread_cap.is_zero()
+                && config.max_spread_cap <= Decimal::one()
+                && config.default_max_spread <= config.max_spread_cap,
+            ContractError::InvalidSpreadConfig
+        );
         Ok::<Config, ContractError>(config)
     })?;
 
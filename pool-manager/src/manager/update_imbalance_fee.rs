@@ -0,0 +1,56 @@
+use cosmwasm_std::{ensure, DepsMut, MessageInfo, Response};
+use mantra_dex_std::pool_manager::ImbalanceFee;
+
+use crate::helpers::{is_stableswap, validate_pool_fees};
+use crate::state::{get_pool_by_identifier, CONFIG, IMBALANCE_FEES};
+use crate::ContractError;
+
+/// Sets or removes the imbalance fee of the given stableswap pool. Only the owner can do this.
+pub fn update_imbalance_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    imbalance_fee: Option<ImbalanceFee>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        is_stableswap(&pool.pool_type),
+        ContractError::NotStableSwapPool { pool_identifier }
+    );
+
+    let Some(imbalance_fee) = imbalance_fee else {
+        IMBALANCE_FEES.remove(deps.storage, &pool_identifier);
+
+        return Ok(Response::default().add_attributes(vec![
+            ("action", "update_imbalance_fee".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("removed", "true".to_string()),
+        ]));
+    };
+
+    // the pool fees must stay within the caps even when the full surcharge applies
+    let config = CONFIG.load(deps.storage)?;
+    let mut surcharged_pool_fees = pool.pool_fees;
+    surcharged_pool_fees.swap_fee.share = surcharged_pool_fees
+        .swap_fee
+        .share
+        .checked_add(imbalance_fee.max_surcharge)?;
+    validate_pool_fees(&surcharged_pool_fees, &config.pool_fee_caps)?;
+
+    IMBALANCE_FEES.save(deps.storage, &pool_identifier, &imbalance_fee)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_imbalance_fee".to_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "surcharge_factor",
+            imbalance_fee.surcharge_factor.to_string(),
+        ),
+        ("max_surcharge", imbalance_fee.max_surcharge.to_string()),
+        ("max_discount", imbalance_fee.max_discount.to_string()),
+    ]))
+}
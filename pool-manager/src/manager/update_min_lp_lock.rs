@@ -0,0 +1,39 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+
+use crate::manager::assert_pool_admin;
+use crate::state::{get_pool_by_identifier, MIN_LP_LOCKS};
+use crate::ContractError;
+
+/// Sets the minimum unlocking duration, in seconds, the liquidity provided to the given pool must
+/// be locked for in the farm manager, or removes it so the LP tokens can be minted to the
+/// receiver again. Only the admin of the pool or the owner can do this.
+pub fn update_min_lp_lock(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    min_unlocking_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // permission check
+    assert_pool_admin(deps.storage, &pool, &info.sender)?;
+
+    match min_unlocking_duration {
+        Some(min_unlocking_duration) => {
+            MIN_LP_LOCKS.save(deps.storage, &pool_identifier, &min_unlocking_duration)?
+        }
+        None => MIN_LP_LOCKS.remove(deps.storage, &pool_identifier),
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_min_lp_lock".to_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "min_unlocking_duration",
+            min_unlocking_duration
+                .map(|duration| duration.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
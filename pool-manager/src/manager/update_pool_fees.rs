@@ -0,0 +1,126 @@
+use cosmwasm_std::{ensure, DepsMut, Env, MessageInfo, Response};
+use mantra_dex_std::fee::PoolFee;
+use mantra_dex_std::pool_manager::{Config, PendingPoolFees};
+
+use crate::helpers::validate_pool_fees;
+use crate::state::{get_pool_by_identifier, CONFIG, IMBALANCE_FEES, PENDING_POOL_FEES, POOLS};
+use crate::ContractError;
+
+/// Schedules new fees for the given pool, to be applied with [apply_pool_fees] once the pool fee
+/// update delay of the config has elapsed. Scheduling fees again replaces the pending ones,
+/// restarting the delay. Only the owner can do this.
+pub fn update_pool_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    new_fees: PoolFee,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let config = CONFIG.load(deps.storage)?;
+    validate_new_pool_fees(&deps, &pool.pool_identifier, &new_fees, &config)?;
+
+    let pending_pool_fees = PendingPoolFees {
+        new_fees,
+        effective_at: env
+            .block
+            .time
+            .plus_seconds(config.pool_fee_update_delay)
+            .seconds(),
+    };
+    PENDING_POOL_FEES.save(deps.storage, &pool.pool_identifier, &pending_pool_fees)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("action", "update_pool_fees".to_string()),
+            ("pool_identifier", pool.pool_identifier),
+            ("effective_at", pending_pool_fees.effective_at.to_string()),
+        ])
+        .add_attributes(get_pool_fees_attributes(&pending_pool_fees.new_fees)))
+}
+
+/// Applies the fees scheduled for the given pool with [update_pool_fees] once they are effective.
+/// Anyone can do this.
+pub fn apply_pool_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let pending_pool_fees = PENDING_POOL_FEES
+        .may_load(deps.storage, &pool.pool_identifier)?
+        .ok_or_else(|| ContractError::NoPendingPoolFees {
+            pool_identifier: pool.pool_identifier.clone(),
+        })?;
+
+    ensure!(
+        env.block.time.seconds() >= pending_pool_fees.effective_at,
+        ContractError::PoolFeesNotEffective {
+            effective_at: pending_pool_fees.effective_at,
+        }
+    );
+
+    // the caps may have changed since the fees were scheduled
+    let config = CONFIG.load(deps.storage)?;
+    validate_new_pool_fees(
+        &deps,
+        &pool.pool_identifier,
+        &pending_pool_fees.new_fees,
+        &config,
+    )?;
+
+    pool.pool_fees = pending_pool_fees.new_fees;
+    POOLS.save(deps.storage, &pool.pool_identifier, &pool)?;
+    PENDING_POOL_FEES.remove(deps.storage, &pool.pool_identifier);
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("action", "apply_pool_fees".to_string()),
+            ("pool_identifier", pool.pool_identifier.clone()),
+        ])
+        .add_attributes(get_pool_fees_attributes(&pool.pool_fees)))
+}
+
+/// Validates the given fees against the fee caps of the config, including the full surcharge of
+/// the imbalance fee of the pool, if any.
+fn validate_new_pool_fees(
+    deps: &DepsMut,
+    pool_identifier: &str,
+    new_fees: &PoolFee,
+    config: &Config,
+) -> Result<(), ContractError> {
+    let mut surcharged_pool_fees = new_fees.clone();
+    if let Some(imbalance_fee) = IMBALANCE_FEES.may_load(deps.storage, pool_identifier)? {
+        surcharged_pool_fees.swap_fee.share = surcharged_pool_fees
+            .swap_fee
+            .share
+            .checked_add(imbalance_fee.max_surcharge)?;
+    }
+
+    validate_pool_fees(&surcharged_pool_fees, &config.pool_fee_caps)
+}
+
+/// Gets the shares of the given fees as attributes.
+fn get_pool_fees_attributes(pool_fees: &PoolFee) -> Vec<(&'static str, String)> {
+    vec![
+        ("swap_fee", pool_fees.swap_fee.share.to_string()),
+        ("protocol_fee", pool_fees.protocol_fee.share.to_string()),
+        ("burn_fee", pool_fees.burn_fee.share.to_string()),
+        (
+            "extra_fees",
+            pool_fees
+                .extra_fees
+                .iter()
+                .map(|fee| fee.share.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]
+}
@@ -0,0 +1,109 @@
+use cosmwasm_std::{ensure, DepsMut, MessageInfo, Response};
+use mantra_dex_std::pool_manager::PoolMetadata;
+
+use crate::manager::assert_pool_admin;
+use crate::state::{get_pool_by_identifier, POOL_METADATA};
+use crate::ContractError;
+
+/// The maximum length of the display name of a pool.
+pub const MAX_POOL_DISPLAY_NAME_LENGTH: usize = 64usize;
+/// The maximum length of the description of a pool.
+pub const MAX_POOL_DESCRIPTION_LENGTH: usize = 512usize;
+/// The maximum length of the logo URI of a pool.
+pub const MAX_POOL_LOGO_URI_LENGTH: usize = 256usize;
+/// The maximum number of tags of a pool.
+pub const MAX_POOL_TAGS: usize = 8usize;
+/// The maximum length of each tag of a pool.
+pub const MAX_POOL_TAG_LENGTH: usize = 32usize;
+
+/// Sets the metadata front-ends display the given pool with, or removes it. Only the admin of the
+/// pool or the owner can do this.
+pub fn update_pool_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    metadata: Option<PoolMetadata>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // permission check
+    assert_pool_admin(deps.storage, &pool, &info.sender)?;
+
+    let Some(metadata) = metadata else {
+        POOL_METADATA.remove(deps.storage, &pool.pool_identifier);
+
+        return Ok(Response::default().add_attributes(vec![
+            ("action", "update_pool_metadata".to_string()),
+            ("pool_identifier", pool.pool_identifier),
+            ("removed", "true".to_string()),
+        ]));
+    };
+
+    validate_pool_metadata(&metadata)?;
+    POOL_METADATA.save(deps.storage, &pool.pool_identifier, &metadata)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_pool_metadata".to_string()),
+        ("pool_identifier", pool.pool_identifier),
+        ("display_name", metadata.display_name),
+    ]))
+}
+
+/// Validates the given pool metadata is within the length limits.
+pub(crate) fn validate_pool_metadata(metadata: &PoolMetadata) -> Result<(), ContractError> {
+    for (field, value, max_length) in [
+        (
+            "display_name",
+            Some(&metadata.display_name),
+            MAX_POOL_DISPLAY_NAME_LENGTH,
+        ),
+        (
+            "description",
+            metadata.description.as_ref(),
+            MAX_POOL_DESCRIPTION_LENGTH,
+        ),
+        (
+            "logo_uri",
+            metadata.logo_uri.as_ref(),
+            MAX_POOL_LOGO_URI_LENGTH,
+        ),
+    ] {
+        ensure!(
+            value.map_or(true, |value| value.len() <= max_length),
+            ContractError::InvalidPoolMetadata {
+                field: field.to_string(),
+                max_length,
+            }
+        );
+    }
+
+    ensure!(
+        !metadata.display_name.is_empty(),
+        ContractError::InvalidPoolMetadata {
+            field: "display_name".to_string(),
+            max_length: MAX_POOL_DISPLAY_NAME_LENGTH,
+        }
+    );
+
+    ensure!(
+        metadata.tags.len() <= MAX_POOL_TAGS,
+        ContractError::InvalidPoolMetadata {
+            field: "tags".to_string(),
+            max_length: MAX_POOL_TAGS,
+        }
+    );
+
+    ensure!(
+        metadata
+            .tags
+            .iter()
+            .all(|tag| !tag.is_empty() && tag.len() <= MAX_POOL_TAG_LENGTH),
+        ContractError::InvalidPoolMetadata {
+            field: "tag".to_string(),
+            max_length: MAX_POOL_TAG_LENGTH,
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use cosmwasm_std::{ensure, DepsMut, MessageInfo, Response};
+
+use crate::helpers::is_stableswap;
+use crate::state::{get_pool_by_identifier, RATE_PROVIDERS};
+use crate::ContractError;
+
+/// Sets the rate providers of the given stableswap pool, one per asset in the order of the pool
+/// assets, so its math works on the balances scaled by the rates they report. Assets without a
+/// rate provider have a rate of one, and a list without any rate provider removes them all. Only
+/// the owner can do this.
+pub fn update_rate_providers(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    rate_providers: Vec<Option<String>>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        is_stableswap(&pool.pool_type),
+        ContractError::NotStableSwapPool { pool_identifier }
+    );
+
+    if rate_providers.iter().all(Option::is_none) {
+        RATE_PROVIDERS.remove(deps.storage, &pool_identifier);
+
+        return Ok(Response::default().add_attributes(vec![
+            ("action", "update_rate_providers".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("removed", "true".to_string()),
+        ]));
+    }
+
+    ensure!(
+        rate_providers.len() == pool.asset_denoms.len(),
+        ContractError::AssetMismatch
+    );
+
+    let rate_providers = rate_providers
+        .iter()
+        .map(|rate_provider| {
+            rate_provider
+                .as_ref()
+                .map(|rate_provider| deps.api.addr_validate(rate_provider))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    RATE_PROVIDERS.save(deps.storage, &pool_identifier, &rate_providers)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_rate_providers".to_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "rate_providers",
+            rate_providers
+                .iter()
+                .map(|rate_provider| {
+                    rate_provider
+                        .as_ref()
+                        .map_or_else(|| "none".to_string(), |addr| addr.to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
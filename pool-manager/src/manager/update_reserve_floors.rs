@@ -0,0 +1,53 @@
+use cosmwasm_std::{ensure, Coin, DepsMut, MessageInfo, Response};
+
+use crate::state::{get_pool_by_identifier, RESERVE_FLOORS};
+use crate::ContractError;
+
+/// Sets the minimum reserves of the given pool, below which swaps draining the assets are
+/// rejected. Floors with a zero amount are dropped, and an empty list removes them all. Only the
+/// owner can do this.
+pub fn update_reserve_floors(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    floors: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+
+    let floors: Vec<Coin> = floors
+        .into_iter()
+        .filter(|floor| !floor.amount.is_zero())
+        .collect();
+
+    // one floor per pool asset at most
+    for (i, floor) in floors.iter().enumerate() {
+        ensure!(
+            pool.asset_denoms.contains(&floor.denom)
+                && !floors[..i].iter().any(|other| other.denom == floor.denom),
+            ContractError::AssetMismatch
+        );
+    }
+
+    if floors.is_empty() {
+        RESERVE_FLOORS.remove(deps.storage, &pool_identifier);
+    } else {
+        RESERVE_FLOORS.save(deps.storage, &pool_identifier, &floors)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_reserve_floors".to_string()),
+        ("pool_identifier", pool_identifier),
+        (
+            "floors",
+            floors
+                .iter()
+                .map(|floor| floor.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
@@ -0,0 +1,67 @@
+use cosmwasm_std::{ensure, DepsMut, MessageInfo, Response};
+use mantra_dex_std::pool_manager::UnlockingDurationBounds;
+
+use crate::manager::assert_pool_admin;
+use crate::state::{get_pool_by_identifier, CONFIG, UNLOCKING_DURATION_BOUNDS};
+use crate::ContractError;
+
+/// Sets the bounds of the unlocking durations accepted when providing liquidity with a lock, for
+/// the given pool or, when no pool is given, for the pools without bounds of their own. Removing
+/// them leaves the validation of the unlocking durations to the farm manager. Only the owner can
+/// update the global bounds, while the bounds of a pool can also be updated by its admin.
+pub fn update_unlocking_duration_bounds(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: Option<String>,
+    bounds: Option<UnlockingDurationBounds>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    if let Some(bounds) = &bounds {
+        ensure!(
+            bounds.min <= bounds.max,
+            ContractError::InvalidUnlockingDurationBounds {
+                min: bounds.min,
+                max: bounds.max,
+            }
+        );
+    }
+
+    match &pool_identifier {
+        Some(pool_identifier) => {
+            let pool = get_pool_by_identifier(&deps.as_ref(), pool_identifier)?;
+            // permission check
+            assert_pool_admin(deps.storage, &pool, &info.sender)?;
+
+            match &bounds {
+                Some(bounds) => {
+                    UNLOCKING_DURATION_BOUNDS.save(deps.storage, pool_identifier, bounds)?
+                }
+                None => UNLOCKING_DURATION_BOUNDS.remove(deps.storage, pool_identifier),
+            }
+        }
+        None => {
+            // permission check
+            cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+            CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
+                config.unlocking_duration_bounds = bounds.clone();
+                Ok(config)
+            })?;
+        }
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_unlocking_duration_bounds".to_string()),
+        (
+            "pool_identifier",
+            pool_identifier.unwrap_or_else(|| "global".to_string()),
+        ),
+        (
+            "bounds",
+            bounds
+                .map(|bounds| format!("{}-{}", bounds.min, bounds.max))
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+    ]))
+}
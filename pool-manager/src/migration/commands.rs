@@ -0,0 +1,299 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
+
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::pool_manager::{ExecuteMsg, MigrationCampaign, PoolInfo};
+
+use crate::dust::tracking::track_withdrawal_dust;
+use crate::helpers::get_withdrawal_assets;
+use crate::state::{
+    get_lp_supply, get_pool_by_identifier, MIGRATED_LIQUIDITY, MIGRATION_CAMPAIGNS,
+};
+use crate::ContractError;
+
+/// Creates a campaign escrowing the rewards sent, which are paid pro-rata to the LPs moving their
+/// liquidity from the given pool to its successor with [move_liquidity] before the deadline.
+/// Only the owner can do this.
+pub fn create_migration_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_pool_identifier: String,
+    to_pool_identifier: String,
+    deadline: u64,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    assert_successor_pool(&deps, &from_pool_identifier, &to_pool_identifier)?;
+
+    ensure!(
+        !MIGRATION_CAMPAIGNS.has(deps.storage, &from_pool_identifier),
+        ContractError::MigrationCampaignAlreadyExists {
+            pool_identifier: from_pool_identifier,
+        }
+    );
+    ensure!(
+        deadline > env.block.time.seconds(),
+        ContractError::InvalidMigrationDeadline
+    );
+    ensure!(!info.funds.is_empty(), ContractError::EmptyAssets);
+
+    let rewards = aggregate_coins(info.funds.clone())?;
+
+    MIGRATION_CAMPAIGNS.save(
+        deps.storage,
+        &from_pool_identifier,
+        &MigrationCampaign {
+            from_pool_identifier: from_pool_identifier.clone(),
+            to_pool_identifier: to_pool_identifier.clone(),
+            rewards: rewards.clone(),
+            deadline,
+            total_migrated: Uint128::zero(),
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "create_migration_campaign".to_string()),
+        ("from_pool_identifier", from_pool_identifier),
+        ("to_pool_identifier", to_pool_identifier),
+        ("deadline", deadline.to_string()),
+        (
+            "rewards",
+            rewards
+                .iter()
+                .map(|reward| reward.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
+
+/// Cancels the migration campaign of the given pool, refunding the rewards to the owner. Only
+/// possible while nobody has migrated as part of the campaign. Only the owner can do this.
+pub fn cancel_migration_campaign(
+    deps: DepsMut,
+    info: MessageInfo,
+    from_pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let campaign = load_campaign(&deps, &from_pool_identifier)?;
+    ensure!(
+        campaign.total_migrated.is_zero(),
+        ContractError::MigrationCampaignInProgress
+    );
+
+    MIGRATION_CAMPAIGNS.remove(deps.storage, &from_pool_identifier);
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: campaign.rewards,
+        })
+        .add_attributes(vec![
+            ("action", "cancel_migration_campaign".to_string()),
+            ("from_pool_identifier", from_pool_identifier),
+        ]))
+}
+
+/// Moves the liquidity of the LP tokens sent from one pool to another holding the same assets, by
+/// withdrawing it from the former and providing it to the latter on behalf of the sender. If the
+/// move is part of an active migration campaign, the sender becomes eligible to its rewards.
+pub fn move_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_pool_identifier: String,
+    to_pool_identifier: String,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let from_pool = assert_successor_pool(&deps, &from_pool_identifier, &to_pool_identifier)?;
+    let lp_amount = cw_utils::must_pay(&info, &from_pool.lp_denom)?;
+
+    let mut attributes = vec![
+        ("action", "move_liquidity".to_string()),
+        ("sender", info.sender.to_string()),
+        ("from_pool_identifier", from_pool_identifier.clone()),
+        ("to_pool_identifier", to_pool_identifier.clone()),
+        ("lp_amount", lp_amount.to_string()),
+    ];
+
+    // account for the migration if it is part of an active campaign
+    if let Some(mut campaign) = MIGRATION_CAMPAIGNS.may_load(deps.storage, &from_pool_identifier)? {
+        if campaign.to_pool_identifier == to_pool_identifier
+            && env.block.time.seconds() <= campaign.deadline
+        {
+            campaign.total_migrated = campaign.total_migrated.checked_add(lp_amount)?;
+            MIGRATION_CAMPAIGNS.save(deps.storage, &from_pool_identifier, &campaign)?;
+
+            MIGRATED_LIQUIDITY.update(
+                deps.storage,
+                (&from_pool_identifier, &info.sender),
+                |migrated| -> Result<_, ContractError> {
+                    Ok(migrated.unwrap_or_default().checked_add(lp_amount)?)
+                },
+            )?;
+
+            attributes.push(("migration_campaign", from_pool_identifier.clone()));
+        }
+    }
+
+    let total_share = get_lp_supply(&deps.as_ref(), &from_pool)?;
+    let withdrawn_assets = get_withdrawal_assets(&from_pool, lp_amount, total_share)?;
+    track_withdrawal_dust(
+        deps.storage,
+        &from_pool,
+        lp_amount,
+        total_share,
+        &withdrawn_assets,
+    )?;
+    ensure!(!withdrawn_assets.is_empty(), ContractError::EmptyAssets);
+
+    attributes.push((
+        "assets",
+        withdrawn_assets
+            .iter()
+            .map(|asset| asset.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    ));
+
+    Ok(Response::default()
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::WithdrawLiquidity {
+                pool_identifier: from_pool_identifier,
+                remote_receiver: None,
+                min_amounts_out: None,
+            },
+            vec![coin(lp_amount.u128(), from_pool.lp_denom)],
+        )?)
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::ProvideLiquidity {
+                slippage_tolerance,
+                max_spread: None,
+                receiver: Some(info.sender.to_string()),
+                pool_identifier: to_pool_identifier,
+                unlocking_duration: None,
+                lock_position_identifier: None,
+                min_lp_to_receive: None,
+                deposit_route: None,
+            },
+            withdrawn_assets,
+        )?)
+        .add_attributes(attributes))
+}
+
+/// Claims the share of the rewards of the migration campaign of the given pool corresponding to
+/// the liquidity the sender moved as part of it, once the campaign is over.
+pub fn claim_migration_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let campaign = load_campaign(&deps, &from_pool_identifier)?;
+    ensure!(
+        env.block.time.seconds() > campaign.deadline,
+        ContractError::MigrationCampaignInProgress
+    );
+
+    let migrated = MIGRATED_LIQUIDITY
+        .may_load(deps.storage, (&from_pool_identifier, &info.sender))?
+        .unwrap_or_default();
+    ensure!(!migrated.is_zero(), ContractError::NoMigrationRewards);
+
+    MIGRATED_LIQUIDITY.remove(deps.storage, (&from_pool_identifier, &info.sender));
+
+    let rewards = get_migration_rewards(&campaign, migrated)?;
+
+    let mut response = Response::default();
+    if !rewards.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: rewards.clone(),
+        });
+    }
+
+    Ok(response.add_attributes(vec![
+        ("action", "claim_migration_rewards".to_string()),
+        ("from_pool_identifier", from_pool_identifier),
+        ("receiver", info.sender.into_string()),
+        ("migrated", migrated.to_string()),
+        (
+            "rewards",
+            rewards
+                .iter()
+                .map(|reward| reward.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
+
+/// Computes the share of the campaign rewards corresponding to the `migrated` LP amount.
+pub(crate) fn get_migration_rewards(
+    campaign: &MigrationCampaign,
+    migrated: Uint128,
+) -> Result<Vec<Coin>, ContractError> {
+    if campaign.total_migrated.is_zero() {
+        return Ok(vec![]);
+    }
+
+    Ok(campaign
+        .rewards
+        .iter()
+        .map(|reward| {
+            Ok(coin(
+                reward
+                    .amount
+                    .checked_multiply_ratio(migrated, campaign.total_migrated)?
+                    .u128(),
+                &reward.denom,
+            ))
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?
+        .into_iter()
+        .filter(|reward| !reward.amount.is_zero())
+        .collect())
+}
+
+fn load_campaign(
+    deps: &DepsMut,
+    from_pool_identifier: &str,
+) -> Result<MigrationCampaign, ContractError> {
+    MIGRATION_CAMPAIGNS
+        .may_load(deps.storage, from_pool_identifier)?
+        .ok_or(ContractError::MigrationCampaignNotFound {
+            pool_identifier: from_pool_identifier.to_string(),
+        })
+}
+
+/// Validates that liquidity can be moved between the given pools, i.e. they are different and
+/// hold the same assets. Returns the pool the liquidity is moved from.
+fn assert_successor_pool(
+    deps: &DepsMut,
+    from_pool_identifier: &str,
+    to_pool_identifier: &str,
+) -> Result<PoolInfo, ContractError> {
+    let from_pool = get_pool_by_identifier(&deps.as_ref(), from_pool_identifier)?;
+    let to_pool = get_pool_by_identifier(&deps.as_ref(), to_pool_identifier)?;
+
+    let mut from_denoms = from_pool.asset_denoms.clone();
+    let mut to_denoms = to_pool.asset_denoms;
+    from_denoms.sort();
+    to_denoms.sort();
+
+    ensure!(
+        from_pool_identifier != to_pool_identifier && from_denoms == to_denoms,
+        ContractError::InvalidMigrationPools
+    );
+
+    Ok(from_pool)
+}
@@ -0,0 +1,83 @@
+//! The precision normalization shared by the swap math, its simulations and the router.
+//!
+//! Stableswap pools may pair assets with different decimals, e.g. a 6 decimals stablecoin with an
+//! 18 decimals one. Their math works on amounts normalized to the largest decimals of the pool and
+//! weighed by the asset weights, so no reserve is truncated whichever direction it's traded in.
+//! Amounts are only converted back into units of an asset once the math is done, rounding down
+//! what the pool pays out and rounding up what it's paid, so simulations, reverse simulations and
+//! the swaps they quote agree on every hop of a route regardless of the decimals it crosses.
+
+use cosmwasm_std::{Decimal256, Uint256};
+
+use mantra_dex_std::pool_manager::PoolInfo;
+
+use crate::helpers::{get_asset_weights, get_scaled_reserves};
+use crate::math::Decimal256Helper;
+use crate::ContractError;
+
+/// The direction a normalized amount is rounded in when converted back into units of an asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Rounds towards zero, for the amounts paid out by the pool.
+    Down,
+    /// Rounds away from zero, for the amounts paid into the pool.
+    Up,
+}
+
+/// Gets the precision the amounts of the given pool are normalized to, i.e. the largest decimals
+/// among its assets.
+pub fn get_normalized_precision(pool_info: &PoolInfo) -> u8 {
+    pool_info.asset_decimals.iter().max().copied().unwrap_or(0)
+}
+
+/// Normalizes the reserves of the given pool into weighed decimals at the normalized precision.
+pub fn normalize_reserves(pool_info: &PoolInfo) -> Result<Vec<Decimal256>, ContractError> {
+    let precision = get_normalized_precision(pool_info);
+
+    get_scaled_reserves(pool_info)?
+        .into_iter()
+        .map(|reserve| Decimal256::decimal_with_precision(reserve, precision))
+        .collect()
+}
+
+/// Normalizes `amount` units of the asset at `index` of the given pool into a weighed decimal.
+pub fn normalize_amount(
+    pool_info: &PoolInfo,
+    index: usize,
+    amount: impl Into<Uint256>,
+) -> Result<Decimal256, ContractError> {
+    Ok(
+        Decimal256::decimal_with_precision(amount, pool_info.asset_decimals[index])?
+            .checked_mul(get_asset_weights(pool_info)[index].into())?,
+    )
+}
+
+/// Converts a normalized amount back into units of the asset at `index` of the given pool,
+/// unweighing it and rounding it in the given direction.
+pub fn denormalize_amount(
+    pool_info: &PoolInfo,
+    index: usize,
+    amount: Decimal256,
+    rounding: Rounding,
+) -> Result<Uint256, ContractError> {
+    let precision = pool_info.asset_decimals[index];
+    let amount = amount.checked_div(get_asset_weights(pool_info)[index].into())?;
+    let units = amount.to_uint256_with_precision(u32::from(precision))?;
+
+    match rounding {
+        Rounding::Down => Ok(units),
+        Rounding::Up if Decimal256::decimal_with_precision(units, precision)? < amount => {
+            Ok(units.checked_add(Uint256::one())?)
+        }
+        Rounding::Up => Ok(units),
+    }
+}
+
+/// Converts an amount the stableswap solver returned at the normalized precision of the given
+/// pool into a normalized amount.
+pub(crate) fn from_solver_amount(
+    pool_info: &PoolInfo,
+    amount: impl Into<Uint256>,
+) -> Result<Decimal256, ContractError> {
+    Decimal256::decimal_with_precision(amount, get_normalized_precision(pool_info))
+}
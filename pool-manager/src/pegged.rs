@@ -0,0 +1,106 @@
+use cosmwasm_std::{ensure, Coin, Decimal, Deps, Env, Uint128};
+
+use mantra_dex_std::oracle::{PriceResponse, QueryMsg};
+use mantra_dex_std::pool_manager::{PoolInfo, PoolType};
+
+use crate::ContractError;
+
+/// Gets the rate the given pegged pool trades at, i.e. the amount of its second asset a unit of its
+/// first asset is worth, as reported by the oracle of the pool. Fails if the oracle price is stale
+/// or out of the band allowed by the pool. Returns `None` for pools of any other type.
+pub(crate) fn get_pegged_rate(
+    deps: Deps,
+    env: &Env,
+    pool_info: &PoolInfo,
+) -> Result<Option<Decimal>, ContractError> {
+    let PoolType::Pegged {
+        oracle,
+        max_deviation,
+        max_staleness,
+    } = &pool_info.pool_type
+    else {
+        return Ok(None);
+    };
+
+    let price: PriceResponse = deps.querier.query_wasm_smart(
+        oracle,
+        &QueryMsg::Price {
+            base_denom: pool_info.asset_denoms[0].clone(),
+            quote_denom: pool_info.asset_denoms[1].clone(),
+        },
+    )?;
+
+    ensure!(
+        env.block.time.seconds().saturating_sub(price.last_updated) <= *max_staleness,
+        ContractError::StaleOracleRate {
+            last_updated: price.last_updated,
+        }
+    );
+
+    let deviation = if price.rate > Decimal::one() {
+        price.rate - Decimal::one()
+    } else {
+        Decimal::one() - price.rate
+    };
+    ensure!(
+        deviation <= *max_deviation,
+        ContractError::PeggedRateOutOfBand { rate: price.rate }
+    );
+
+    Ok(Some(price.rate))
+}
+
+/// Converts the given amount of the asset at `offer_index` of a pegged pool into the other asset
+/// at `rate`, rounding down or up as requested. The amounts are scaled to the same decimals first.
+pub(crate) fn convert_at_pegged_rate(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    amount: Uint128,
+    rate: Decimal,
+    round_up: bool,
+) -> Result<Uint128, ContractError> {
+    let scaling_factors = &pool_info.asset_scaling_factors;
+    let scaled_amount = amount.checked_mul(scaling_factors[offer_index])?;
+
+    // the rate is quoted in units of the second asset per unit of the first one
+    let converted = match (offer_index, round_up) {
+        (0, false) => scaled_amount.checked_mul_floor(rate)?,
+        (0, true) => scaled_amount.checked_mul_ceil(rate)?,
+        (_, false) => scaled_amount.checked_div_floor(rate)?,
+        (_, true) => scaled_amount.checked_div_ceil(rate)?,
+    };
+
+    Ok(if round_up {
+        converted.checked_div_ceil((scaling_factors[ask_index], Uint128::one()))?
+    } else {
+        converted.checked_div(scaling_factors[ask_index])?
+    })
+}
+
+/// Values the given assets of a pegged pool in its second asset at `rate`, scaled up to the
+/// largest decimals of the pool assets.
+pub(crate) fn get_pegged_value(
+    pool_info: &PoolInfo,
+    assets: &[Coin],
+    rate: Decimal,
+) -> Result<Uint128, ContractError> {
+    assets.iter().try_fold(Uint128::zero(), |acc, asset| {
+        let index = pool_info
+            .asset_denoms
+            .iter()
+            .position(|denom| denom == &asset.denom)
+            .ok_or(ContractError::AssetMismatch)?;
+        let scaled_amount = asset
+            .amount
+            .checked_mul(pool_info.asset_scaling_factors[index])?;
+
+        let value = if index == 0 {
+            scaled_amount.checked_mul_floor(rate)?
+        } else {
+            scaled_amount
+        };
+
+        Ok(acc.checked_add(value)?)
+    })
+}
@@ -0,0 +1,119 @@
+use cosmwasm_std::{ensure, Addr, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+
+use crate::manager::assert_pool_admin;
+use crate::state::{get_pool_by_identifier, DEPRECATED_POOLS, PERMISSIONED_POOLS, POOL_ALLOWLIST};
+use crate::ContractError;
+
+/// Makes the given pool permissioned under the given admin, or public again when no admin is
+/// given, clearing its allowlist. Only the owner can do this.
+pub fn update_permissioned_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+
+    let mut attributes = vec![
+        ("action", "update_permissioned_pool".to_string()),
+        ("pool_identifier", pool_identifier.clone()),
+    ];
+
+    match admin {
+        Some(admin) => {
+            let admin = deps.api.addr_validate(&admin)?;
+            PERMISSIONED_POOLS.save(deps.storage, &pool_identifier, &admin)?;
+            attributes.push(("admin", admin.into_string()));
+        }
+        None => {
+            PERMISSIONED_POOLS.remove(deps.storage, &pool_identifier);
+            let allowlisted = POOL_ALLOWLIST
+                .prefix(&pool_identifier)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<Addr>>>()?;
+            for address in allowlisted {
+                POOL_ALLOWLIST.remove(deps.storage, (&pool_identifier, &address));
+            }
+            attributes.push(("removed", "true".to_string()));
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+/// Adds and removes addresses from the allowlist of the given permissioned pool. Only the admin
+/// of the allowlist, the admin of the pool or the owner can do this.
+pub fn update_pool_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let admin = PERMISSIONED_POOLS
+        .may_load(deps.storage, &pool_identifier)?
+        .ok_or(ContractError::PoolNotPermissioned {
+            pool_identifier: pool_identifier.clone(),
+        })?;
+
+    // permission check
+    if info.sender != admin {
+        let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+        assert_pool_admin(deps.storage, &pool, &info.sender)?;
+    }
+
+    for address in &add {
+        let address = deps.api.addr_validate(address)?;
+        POOL_ALLOWLIST.save(deps.storage, (&pool_identifier, &address), &true)?;
+    }
+
+    for address in &remove {
+        let address = deps.api.addr_validate(address)?;
+        POOL_ALLOWLIST.remove(deps.storage, (&pool_identifier, &address));
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_pool_allowlist".to_string()),
+        ("pool_identifier", pool_identifier),
+        ("added", add.join(",")),
+        ("removed", remove.join(",")),
+    ]))
+}
+
+/// Ensures the given address can swap in or provide liquidity to the given pool, i.e. the pool
+/// isn't deprecated, and is public or the address is on its allowlist. The contract itself is let
+/// through permissioned pools, as it only acts on behalf of users already checked, e.g. when
+/// executing their orders or schedules.
+pub(crate) fn assert_pool_access(
+    storage: &dyn Storage,
+    env: &Env,
+    pool_identifier: &str,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    ensure!(
+        !DEPRECATED_POOLS.has(storage, pool_identifier),
+        ContractError::PoolDeprecated {
+            pool_identifier: pool_identifier.to_string(),
+        }
+    );
+
+    if address == env.contract.address || !PERMISSIONED_POOLS.has(storage, pool_identifier) {
+        return Ok(());
+    }
+
+    ensure!(
+        POOL_ALLOWLIST.has(storage, (pool_identifier, address)),
+        ContractError::NotAllowlisted {
+            address: address.to_string(),
+            pool_identifier: pool_identifier.to_string(),
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use cosmwasm_std::{ensure, Decimal256, Deps, Env, Uint128};
+
+use mantra_dex_std::oracle::{PriceResponse, QueryMsg};
+use mantra_dex_std::pool_manager::{PoolInfo, PriceReference};
+
+use crate::helpers::SwapComputation;
+use crate::state::{PRICE_BREAKERS, PRICE_BREAKER_TRIPPED_AT};
+use crate::twap::{get_spot_prices, get_twap};
+use crate::ContractError;
+
+/// Asserts a swap of `offer_amount` of the asset at `offer_index` for the asset at `ask_index` of
+/// the given pool can go through its price breaker, if it has one. Swaps fail while the breaker is
+/// tripped, and when their execution price, before fees, deviates from the reference price by more
+/// than the maximum deviation of the breaker.
+pub(crate) fn assert_swap_within_price_breaker(
+    deps: Deps,
+    env: &Env,
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    swap_computation: &SwapComputation,
+) -> Result<(), ContractError> {
+    let Some(config) = PRICE_BREAKERS.may_load(deps.storage, &pool_info.pool_identifier)? else {
+        return Ok(());
+    };
+
+    ensure!(
+        !PRICE_BREAKER_TRIPPED_AT.has(deps.storage, &pool_info.pool_identifier),
+        ContractError::PriceBreakerTripped {
+            pool_identifier: pool_info.pool_identifier.clone(),
+        }
+    );
+
+    let Some(reference_price) = get_reference_price(
+        deps,
+        env,
+        pool_info,
+        &config.reference,
+        offer_index,
+        ask_index,
+    )?
+    else {
+        return Ok(());
+    };
+
+    // the fees aren't a deviation of the price, so they're added back to the return
+    let gross_return_amount = swap_computation
+        .return_amount
+        .checked_add(swap_computation.swap_fee_amount)?
+        .checked_add(swap_computation.protocol_fee_amount)?
+        .checked_add(swap_computation.burn_fee_amount)?
+        .checked_add(swap_computation.extra_fees_amount)?;
+    let execution_price = Decimal256::checked_from_ratio(gross_return_amount, offer_amount)
+        .map_err(|_| ContractError::DecimalOverflow)?;
+
+    let deviation = get_deviation(execution_price, reference_price)?;
+    ensure!(
+        deviation <= Decimal256::from(config.max_deviation),
+        ContractError::PriceDeviationExceeded {
+            deviation,
+            max_deviation: config.max_deviation,
+        }
+    );
+
+    Ok(())
+}
+
+/// Gets the deviation of the spot price of the first asset of the given pool, in terms of its
+/// second asset, from the reference price of its price breaker. Returns `None` if the pool has no
+/// breaker, or there's no reference price yet, i.e. there's not enough TWAP history.
+pub(crate) fn get_spot_price_deviation(
+    deps: Deps,
+    env: &Env,
+    pool_info: &PoolInfo,
+) -> Result<Option<Decimal256>, ContractError> {
+    let Some(config) = PRICE_BREAKERS.may_load(deps.storage, &pool_info.pool_identifier)? else {
+        return Ok(None);
+    };
+    let Some(reference_price) = get_reference_price(deps, env, pool_info, &config.reference, 0, 1)?
+    else {
+        return Ok(None);
+    };
+
+    let spot_price = get_spot_prices(deps.storage, pool_info)?[0][1];
+
+    Ok(Some(get_deviation(spot_price, reference_price)?))
+}
+
+/// Gets the price of the asset at `base_index` of the given pool in terms of the asset at
+/// `quote_index`, in base units, as given by the reference of its price breaker.
+fn get_reference_price(
+    deps: Deps,
+    env: &Env,
+    pool_info: &PoolInfo,
+    reference: &PriceReference,
+    base_index: usize,
+    quote_index: usize,
+) -> Result<Option<Decimal256>, ContractError> {
+    let base_denom = pool_info.asset_denoms[base_index].clone();
+    let quote_denom = pool_info.asset_denoms[quote_index].clone();
+
+    match reference {
+        PriceReference::Twap { window } => match get_twap(
+            deps,
+            env.block.time.seconds(),
+            &pool_info.pool_identifier,
+            base_denom,
+            quote_denom,
+            *window,
+        ) {
+            Ok(twap) => Ok(Some(twap)),
+            // new pools have no reference price until their TWAP window is covered
+            Err(ContractError::InsufficientTwapHistory) => Ok(None),
+            Err(error) => Err(error),
+        },
+        PriceReference::Oracle {
+            oracle,
+            max_staleness,
+        } => {
+            let price: PriceResponse = deps.querier.query_wasm_smart(
+                oracle,
+                &QueryMsg::Price {
+                    base_denom,
+                    quote_denom,
+                },
+            )?;
+
+            ensure!(
+                env.block.time.seconds().saturating_sub(price.last_updated) <= *max_staleness,
+                ContractError::StaleOracleRate {
+                    last_updated: price.last_updated,
+                }
+            );
+
+            // the oracle quotes whole units, so the price is converted into base units
+            let scaling_factors = &pool_info.asset_scaling_factors;
+            Ok(Some(Decimal256::from(price.rate).checked_mul(
+                Decimal256::from_ratio(scaling_factors[base_index], scaling_factors[quote_index]),
+            )?))
+        }
+    }
+}
+
+/// Gets the relative deviation of `price` from `reference_price`.
+fn get_deviation(
+    price: Decimal256,
+    reference_price: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    if reference_price.is_zero() {
+        return Ok(Decimal256::zero());
+    }
+
+    price
+        .abs_diff(reference_price)
+        .checked_div(reference_price)
+        .map_err(|_| ContractError::DecimalOverflow)
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::{ensure, Decimal256, DepsMut, Env, MessageInfo, Response, Storage};
+
+use mantra_dex_std::pool_manager::{PriceBreakerConfig, PriceReference};
+
+use crate::price_breaker::breaker::get_spot_price_deviation;
+use crate::state::{get_pool_by_identifier, PRICE_BREAKERS, PRICE_BREAKER_TRIPPED_AT};
+use crate::twap::MAX_TWAP_WINDOW;
+use crate::ContractError;
+
+/// Sets or removes the price breaker of the given pool. Setting it resets the breaker if it was
+/// tripped. Only the owner can do this.
+pub fn update_price_breaker(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    config: Option<PriceBreakerConfig>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    PRICE_BREAKER_TRIPPED_AT.remove(deps.storage, &pool_identifier);
+
+    let mut attributes = vec![
+        ("action", "update_price_breaker".to_string()),
+        ("pool_identifier", pool_identifier.clone()),
+    ];
+
+    match config {
+        Some(config) => {
+            ensure!(
+                !config.max_deviation.is_zero(),
+                ContractError::InvalidPriceBreakerConfig
+            );
+
+            match &config.reference {
+                PriceReference::Twap { window } => {
+                    ensure!(
+                        *window > 0 && *window <= MAX_TWAP_WINDOW,
+                        ContractError::InvalidTwapWindow {
+                            max: MAX_TWAP_WINDOW
+                        }
+                    );
+                    attributes.push(("twap_window", window.to_string()));
+                }
+                PriceReference::Oracle { oracle, .. } => {
+                    deps.api.addr_validate(oracle)?;
+                    ensure!(
+                        pool.assets.len() == 2,
+                        ContractError::InvalidPriceBreakerConfig
+                    );
+                    attributes.push(("oracle", oracle.clone()));
+                }
+            }
+
+            if let Some(guardian) = &config.guardian {
+                deps.api.addr_validate(guardian)?;
+            }
+
+            attributes.push(("max_deviation", config.max_deviation.to_string()));
+            PRICE_BREAKERS.save(deps.storage, &pool_identifier, &config)?;
+        }
+        None => {
+            attributes.push(("removed", "true".to_string()));
+            PRICE_BREAKERS.remove(deps.storage, &pool_identifier);
+        }
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+/// Trips the price breaker of the given pool, halting its swaps, if the spot price of the pool
+/// deviates from the reference price of the breaker by more than the maximum deviation. Anyone can
+/// do this, e.g. keepers watching the pools.
+pub fn trip_price_breaker(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let config = PRICE_BREAKERS
+        .may_load(deps.storage, &pool_identifier)?
+        .ok_or_else(|| ContractError::NoPriceBreaker {
+            pool_identifier: pool_identifier.clone(),
+        })?;
+    ensure!(
+        !PRICE_BREAKER_TRIPPED_AT.has(deps.storage, &pool_identifier),
+        ContractError::PriceBreakerTripped {
+            pool_identifier: pool_identifier.clone(),
+        }
+    );
+
+    let deviation = get_spot_price_deviation(deps.as_ref(), &env, &pool)?.unwrap_or_default();
+    ensure!(
+        deviation > Decimal256::from(config.max_deviation),
+        ContractError::PriceDeviationWithinBounds {
+            deviation,
+            max_deviation: config.max_deviation,
+        }
+    );
+
+    PRICE_BREAKER_TRIPPED_AT.save(deps.storage, &pool_identifier, &env.block.time.seconds())?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "trip_price_breaker".to_string()),
+        ("pool_identifier", pool_identifier),
+        ("deviation", deviation.to_string()),
+        ("sender", info.sender.into_string()),
+    ]))
+}
+
+/// Resets the tripped price breaker of the given pool, resuming its swaps. Only the owner or the
+/// guardian of the breaker can do this.
+pub fn reset_price_breaker(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    assert_owner_or_guardian(deps.storage, &pool_identifier, &info)?;
+
+    ensure!(
+        PRICE_BREAKER_TRIPPED_AT.has(deps.storage, &pool_identifier),
+        ContractError::PriceBreakerNotTripped {
+            pool_identifier: pool_identifier.clone(),
+        }
+    );
+    PRICE_BREAKER_TRIPPED_AT.remove(deps.storage, &pool_identifier);
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "reset_price_breaker".to_string()),
+        ("pool_identifier", pool_identifier),
+        ("sender", info.sender.into_string()),
+    ]))
+}
+
+/// Asserts the sender is the owner, or the guardian of the price breaker of the given pool.
+fn assert_owner_or_guardian(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    if cw_ownable::assert_owner(storage, &info.sender).is_ok() {
+        return Ok(());
+    }
+
+    let guardian = PRICE_BREAKERS
+        .may_load(storage, pool_identifier)?
+        .and_then(|config| config.guardian);
+    ensure!(
+        guardian.as_deref() == Some(info.sender.as_str()),
+        ContractError::Unauthorized
+    );
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+pub(crate) mod breaker;
+pub mod commands;
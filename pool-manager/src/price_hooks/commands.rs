@@ -0,0 +1,110 @@
+use cosmwasm_std::{ensure, Binary, Decimal, DepsMut, MessageInfo, Order, Response, StdResult};
+
+use mantra_dex_std::pool_manager::PriceSubscription;
+
+use crate::helpers::get_asset_indexes_in_pool;
+use crate::state::{
+    get_pool_by_identifier, get_price_subscription_by_id, PRICE_SUBSCRIPTIONS,
+    PRICE_SUBSCRIPTION_COUNTER,
+};
+use crate::ContractError;
+
+/// The maximum number of price subscriptions a pool can have, as they are checked on every swap.
+pub const MAX_PRICE_SUBSCRIPTIONS_PER_POOL: usize = 10;
+
+/// Subscribes the sender to the price of `base_denom` in terms of `quote_denom` in the given pool.
+/// Whenever a swap moves the price across the `threshold`, the pool manager executes the sender
+/// with the `callback` message. Only contracts can subscribe.
+pub fn subscribe_price_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    threshold: Decimal,
+    callback: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    ensure!(
+        deps.querier
+            .query_wasm_contract_info(info.sender.to_string())
+            .is_ok(),
+        ContractError::PriceSubscriberNotAContract
+    );
+
+    ensure!(!threshold.is_zero(), ContractError::InvalidPriceThreshold);
+
+    let pool_info = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    // make sure both denoms belong to the pool and are different
+    get_asset_indexes_in_pool(&pool_info, base_denom.clone(), quote_denom.clone())?;
+
+    let pool_subscriptions = PRICE_SUBSCRIPTIONS
+        .idx
+        .pool_identifier
+        .prefix(pool_identifier.clone())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<u64>>>()?
+        .len();
+
+    ensure!(
+        pool_subscriptions < MAX_PRICE_SUBSCRIPTIONS_PER_POOL,
+        ContractError::TooManyPriceSubscriptions {
+            max: MAX_PRICE_SUBSCRIPTIONS_PER_POOL
+        }
+    );
+
+    let subscription_id = PRICE_SUBSCRIPTION_COUNTER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1u64;
+    PRICE_SUBSCRIPTION_COUNTER.save(deps.storage, &subscription_id)?;
+
+    PRICE_SUBSCRIPTIONS.save(
+        deps.storage,
+        subscription_id,
+        &PriceSubscription {
+            id: subscription_id,
+            subscriber: info.sender.clone(),
+            pool_identifier: pool_identifier.clone(),
+            base_denom: base_denom.clone(),
+            quote_denom: quote_denom.clone(),
+            threshold,
+            callback,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "subscribe_price_threshold".to_string()),
+        ("subscription_id", subscription_id.to_string()),
+        ("subscriber", info.sender.into_string()),
+        ("pool_identifier", pool_identifier),
+        ("base_denom", base_denom),
+        ("quote_denom", quote_denom),
+        ("threshold", threshold.to_string()),
+    ]))
+}
+
+/// Removes a price subscription. Can be called by the subscriber or the owner of the contract.
+pub fn unsubscribe_price_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    subscription_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let subscription = get_price_subscription_by_id(deps.storage, subscription_id)?;
+
+    ensure!(
+        subscription.subscriber == info.sender || cw_ownable::is_owner(deps.storage, &info.sender)?,
+        ContractError::Unauthorized
+    );
+
+    PRICE_SUBSCRIPTIONS.remove(deps.storage, subscription_id)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "unsubscribe_price_threshold".to_string()),
+        ("subscription_id", subscription_id.to_string()),
+        ("subscriber", subscription.subscriber.into_string()),
+    ]))
+}
@@ -0,0 +1,77 @@
+use cosmwasm_std::{Decimal, Order, StdResult, Storage, SubMsg, WasmMsg};
+
+use mantra_dex_std::pool_manager::{PoolInfo, PriceSubscription};
+
+use crate::contract::PRICE_HOOK_REPLY_ID;
+use crate::state::PRICE_SUBSCRIPTIONS;
+use crate::ContractError;
+
+/// The gas limit given to each price hook, so subscribers can't make swaps arbitrarily expensive.
+pub const PRICE_HOOK_GAS_LIMIT: u64 = 500_000;
+
+/// Builds the callback messages for the subscriptions of a pool whose price was crossed by a swap,
+/// given the state of the pool before and after the swap.
+///
+/// Hooks are dispatched as submessages replying on error, so a failing subscriber can't make the
+/// swap fail.
+pub(crate) fn get_price_hook_msgs(
+    storage: &dyn Storage,
+    pool_before: &PoolInfo,
+    pool_after: &PoolInfo,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let subscriptions = PRICE_SUBSCRIPTIONS
+        .idx
+        .pool_identifier
+        .prefix(pool_after.pool_identifier.clone())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, subscription)| subscription))
+        .collect::<StdResult<Vec<PriceSubscription>>>()?;
+
+    let mut hook_msgs = vec![];
+
+    for subscription in subscriptions {
+        let (Some(price_before), Some(price_after)) = (
+            get_spot_price(pool_before, &subscription),
+            get_spot_price(pool_after, &subscription),
+        ) else {
+            continue;
+        };
+
+        let crossed =
+            (price_before < subscription.threshold) != (price_after < subscription.threshold);
+
+        if crossed {
+            hook_msgs.push(
+                SubMsg::reply_on_error(
+                    WasmMsg::Execute {
+                        contract_addr: subscription.subscriber.to_string(),
+                        msg: subscription.callback,
+                        funds: vec![],
+                    },
+                    PRICE_HOOK_REPLY_ID,
+                )
+                .with_gas_limit(PRICE_HOOK_GAS_LIMIT),
+            );
+        }
+    }
+
+    Ok(hook_msgs)
+}
+
+/// Gets the spot price of the base denom in terms of the quote denom, in base units. Returns
+/// [None] if the pool has no reserves of either asset.
+fn get_spot_price(pool_info: &PoolInfo, subscription: &PriceSubscription) -> Option<Decimal> {
+    let reserve = |denom: &String| {
+        pool_info
+            .assets
+            .iter()
+            .find(|asset| &asset.denom == denom)
+            .map(|asset| asset.amount)
+    };
+
+    Decimal::checked_from_ratio(
+        reserve(&subscription.quote_denom)?,
+        reserve(&subscription.base_denom)?,
+    )
+    .ok()
+}
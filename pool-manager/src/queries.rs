@@ -1,24 +1,70 @@
-use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 use cosmwasm_std::{
-    coin, ensure, Coin, Decimal256, Deps, Fraction, Order, StdResult, Uint128, Uint256,
+    coin, ensure, Binary, Coin, DecCoin, Decimal, Decimal256, Deps, Env, Fraction, Order,
+    StdResult, Storage, Uint128, Uint256,
 };
 use cw_storage_plus::Bound;
 use mantra_dex_std::coin::aggregate_coins;
 use mantra_dex_std::pool_manager::{
-    AssetDecimalsResponse, Config, PoolInfoResponse, PoolType, PoolsResponse,
-    ReverseSimulateSwapOperationsResponse, ReverseSimulationResponse,
-    SimulateSwapOperationsResponse, SimulationResponse, SwapOperation,
+    AssetDecimalsResponse, BatchSimulationResponse, ConcentratedPoolResponse,
+    ConcentratedPoolState, ConcentratedPosition, ConcentratedPositionInfo,
+    ConcentratedPositionsResponse, Config, ContractRegistryResponse, DcaSchedule,
+    DcaSchedulesResponse, DenomTracesResponse, DepegProtectionResponse, DisabledMessagesResponse,
+    FeeDiscountsResponse, FindBestRouteResponse, IbcSourceChannelsResponse, IbcWithdrawalChannel,
+    IbcWithdrawalChannelsResponse, ImbalanceFee, ImbalanceFeeResponse, LiquidityStream,
+    LiquidityStreamsResponse, LpValuationResponse, LpWrapperResponse, MigrationCampaignResponse,
+    MinLpLockResponse, PendingPoolFeesResponse, PoolAdminResponse, PoolAllowlistResponse,
+    PoolCreatorAllowlistResponse, PoolInfo, PoolInfoResponse, PoolType, PoolsResponse,
+    PriceBreakerResponse, PriceSubscription, PriceSubscriptionsResponse, ProtocolLiquidityResponse,
+    RateProvidersResponse, RegisteredContract, ReserveFloorsResponse,
+    ReverseSimulateSwapOperationsResponse, ReverseSimulationResponse, RfqMakerResponse, RfqQuote,
+    RfqQuoteDigestResponse, RoundingDustResponse, RouteTwapResponse,
+    SimulateSwapOperationsResponse, SimulationRequest, SimulationResponse, SplitSimulationResponse,
+    StableSwapAmpResponse, StableSwapDebugResponse, StopLossOrder, StopLossOrdersResponse,
+    SwapAdaptersResponse, SwapOperation, SwapRoute, SwapRouteResponse, SwapRoutesResponse,
+    TwapResponse, UnlockingDurationBoundsResponse, YieldDeployment, YieldDeploymentsResponse,
+    YieldStrategiesResponse, YieldStrategy,
 };
 
-use crate::helpers::get_asset_indexes_in_pool;
+use crate::concentrated::commands::get_position_fees;
+use crate::concentrated::math::{get_amounts_for_liquidity, get_sqrt_price_at_tick};
+use crate::concentrated::swap::{compute_concentrated_swap, load_concentrated_pool};
+use crate::depeg::protection::get_depegging_denom;
+use crate::dust::tracking::{get_rounding_dust, is_dust_tracking_enabled};
+use crate::fee_discount::discount::{apply_fee_discount, get_fee_discount};
+use crate::helpers::{get_asset_indexes_in_pool, get_unlocking_duration_bounds, is_concentrated};
 use crate::math::Decimal256Helper;
-use crate::state::{CONFIG, POOLS};
+use crate::migration::commands::get_migration_rewards;
+use crate::normalization::{
+    denormalize_amount, from_solver_amount, get_normalized_precision, normalize_amount,
+    normalize_reserves, Rounding,
+};
+use crate::pegged::{convert_at_pegged_rate, get_pegged_rate};
+use crate::price_breaker::breaker::get_spot_price_deviation;
+use crate::rates::{apply_rates, get_rates};
+use crate::router::commands::{assert_no_swap_adapters, assert_operations, assert_swap_route};
+use crate::split::commands::compute_split;
+use crate::state::{
+    CONCENTRATED_POOLS, CONCENTRATED_POSITIONS, CONFIG, DCA_SCHEDULES, DEPEG_DEVIATING_SINCE,
+    DEPEG_PROTECTION, DEPRECATED_POOLS, FEE_DISCOUNTS, IBC_SOURCE_CHANNELS,
+    IBC_WITHDRAWAL_CHANNELS, IMBALANCE_FEES, LIQUIDITY_STREAMS, LP_WRAPPERS, MIGRATED_LIQUIDITY,
+    MIGRATION_CAMPAIGNS, MIN_LP_LOCKS, PENDING_POOL_ADMINS, PENDING_POOL_FEES, PERMISSIONED_POOLS,
+    POOLS, POOL_ALIASES, POOL_ALLOWLIST, POOL_CREATOR_ALLOWLIST, POOL_DENOM_TRACES, POOL_METADATA,
+    PRICE_BREAKERS, PRICE_BREAKER_TRIPPED_AT, PRICE_SUBSCRIPTIONS, PROTOCOL_LIQUIDITY,
+    RATE_PROVIDERS, REBALANCE_CONFIG, RESERVE_FLOORS, RFQ_INVENTORY, RFQ_MAKERS, STOP_LOSS_ORDERS,
+    SWAP_ADAPTERS, SWAP_ROUTES, YIELD_DEPLOYMENTS, YIELD_STRATEGIES,
+};
+use crate::swap_adapter::adapter::simulate_adapter_swap;
 use crate::{
-    helpers::{self, calculate_stableswap_y, StableSwapDirection},
-    state::get_pool_by_identifier,
+    helpers::{
+        self, calculate_stableswap_y, calculate_stableswap_y_with_trace, hash_stableswap_inputs,
+        StableSwapDirection, StableSwapTrace,
+    },
+    state::{get_lp_supply, get_pool_by_identifier},
     ContractError,
 };
+use crate::{rfq, twap};
 
 /// Query the config of the contract.
 pub fn query_config(deps: Deps) -> Result<Config, ContractError> {
@@ -46,27 +92,104 @@ pub fn query_asset_decimals(
 }
 
 // Simulate a swap with the provided asset to determine the amount of the other asset that would be received
+// by the given trader, whose stake may discount the fees of the swap
 pub fn query_simulation(
     deps: Deps,
+    env: &Env,
     offer_asset: Coin,
     ask_asset_denom: String,
     pool_identifier: String,
+    trader: Option<String>,
 ) -> Result<SimulationResponse, ContractError> {
     let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps, env, &pool_info)?;
+    let mut pool_info = apply_rates(&pool_info, get_rates(deps, &pool_info)?.as_deref())?;
+
+    // the fees are discounted by the tier the stake of the trader reaches, if given
+    let fee_discount = match trader {
+        Some(trader) => get_fee_discount(deps, &deps.api.addr_validate(&trader)?)?,
+        None => Decimal::zero(),
+    };
+    pool_info.pool_fees = apply_fee_discount(&pool_info.pool_fees, fee_discount)?;
+
+    Ok(SimulationResponse {
+        fee_discount,
+        ..simulate_swap(
+            deps.storage,
+            pool_info,
+            offer_asset,
+            ask_asset_denom,
+            imbalance_fee.as_ref(),
+            pegged_rate,
+        )?
+    })
+}
 
-    let (offer_asset_in_pool, ask_asset_in_pool, _, _, offer_decimal, ask_decimal) =
+/// Simulates a swap as [query_simulation] does, but replacing the reserves of the pool with the
+/// given ones. Reserves not overridden keep their current balance.
+pub fn query_simulation_with_reserves(
+    deps: Deps,
+    env: &Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    reserves: Vec<Coin>,
+) -> Result<SimulationResponse, ContractError> {
+    let mut pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    assert_reserves_can_be_overridden(&pool_info)?;
+    helpers::override_pool_reserves(&mut pool_info, reserves)?;
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps, env, &pool_info)?;
+    let pool_info = apply_rates(&pool_info, get_rates(deps, &pool_info)?.as_deref())?;
+
+    simulate_swap(
+        deps.storage,
+        pool_info,
+        offer_asset,
+        ask_asset_denom,
+        imbalance_fee.as_ref(),
+        pegged_rate,
+    )
+}
+
+/// Simulates a swap against the reserves of the given pool, applying its imbalance fee if any.
+/// Pegged pools trade at the given oracle rate, while concentrated liquidity pools are swapped over
+/// their ticks.
+pub(crate) fn simulate_swap(
+    storage: &dyn Storage,
+    pool_info: PoolInfo,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    imbalance_fee: Option<&ImbalanceFee>,
+    pegged_rate: Option<Decimal>,
+) -> Result<SimulationResponse, ContractError> {
+    let (_, _, offer_index, ask_index, _, _) =
         get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
 
-    let swap_computation = helpers::compute_swap(
-        Uint256::from(pool_info.assets.len() as u128),
-        offer_asset_in_pool.amount,
-        ask_asset_in_pool.amount,
-        offer_asset.amount,
-        pool_info.pool_fees,
-        &pool_info.pool_type,
-        offer_decimal,
-        ask_decimal,
-    )?;
+    let swap_computation = match load_concentrated_pool(storage, &pool_info)? {
+        Some(state) => {
+            compute_concentrated_swap(
+                storage,
+                &pool_info,
+                &state,
+                offer_index,
+                ask_index,
+                offer_asset.amount,
+                pool_info.pool_fees.clone(),
+            )?
+            .swap_computation
+        }
+        None => helpers::compute_swap(
+            &pool_info,
+            offer_index,
+            ask_index,
+            offer_asset.amount,
+            pool_info.pool_fees.clone(),
+            imbalance_fee,
+            pegged_rate,
+        )?,
+    };
 
     Ok(SimulationResponse {
         return_amount: swap_computation.return_amount,
@@ -75,6 +198,82 @@ pub fn query_simulation(
         protocol_fee_amount: swap_computation.protocol_fee_amount,
         burn_fee_amount: swap_computation.burn_fee_amount,
         extra_fees_amount: swap_computation.extra_fees_amount,
+        fee_discount: Decimal::zero(),
+    })
+}
+
+/// Ensures the reserves of the given pool can be overridden in a simulation, which isn't the case
+/// of concentrated liquidity pools as their swaps depend on the liquidity in range rather than on
+/// their reserves.
+fn assert_reserves_can_be_overridden(pool_info: &PoolInfo) -> Result<(), ContractError> {
+    ensure!(
+        !is_concentrated(&pool_info.pool_type),
+        ContractError::UnsupportedForConcentratedLiquidity {
+            operation: "simulations with reserve overrides".to_string(),
+        }
+    );
+
+    Ok(())
+}
+
+/// Simulates a batch of independent swaps, returning the results in the same order as the
+/// requests. Each simulation is performed against the current state of its pool.
+pub fn query_batch_simulation(
+    deps: Deps,
+    env: &Env,
+    simulations: Vec<SimulationRequest>,
+) -> Result<BatchSimulationResponse, ContractError> {
+    ensure!(
+        simulations.len() <= MAX_LIMIT as usize,
+        ContractError::TooManySimulations { max: MAX_LIMIT }
+    );
+
+    let simulations = simulations
+        .into_iter()
+        .map(|simulation| {
+            query_simulation(
+                deps,
+                env,
+                simulation.offer_asset,
+                simulation.ask_asset_denom,
+                simulation.pool_identifier,
+                None,
+            )
+        })
+        .collect::<Result<Vec<SimulationResponse>, ContractError>>()?;
+
+    Ok(BatchSimulationResponse { simulations })
+}
+
+/// Simulates swapping `offer_asset` for `ask_asset_denom` split across the given pools for the
+/// same pair, by the given weights or automatically, returning how much is routed through each
+/// pool and the total return amount.
+pub fn query_split_simulation(
+    deps: Deps,
+    env: &Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifiers: Vec<String>,
+    weights: Option<Vec<u64>>,
+) -> Result<SplitSimulationResponse, ContractError> {
+    let allocations = compute_split(
+        deps,
+        env,
+        &offer_asset,
+        &ask_asset_denom,
+        &pool_identifiers,
+        weights.as_deref(),
+    )?;
+
+    let return_amount = allocations
+        .iter()
+        .try_fold(Uint128::zero(), |acc, allocation| {
+            acc.checked_add(allocation.return_amount)
+        })?;
+
+    Ok(SplitSimulationResponse {
+        allocations,
+        return_amount,
     })
 }
 
@@ -82,16 +281,65 @@ pub fn query_simulation(
 /// the number of target tokens.
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: &Env,
     ask_asset: Coin,
     offer_asset_denom: String,
     pool_identifier: String,
 ) -> Result<ReverseSimulationResponse, ContractError> {
     let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps, env, &pool_info)?;
+    let pool_info = apply_rates(&pool_info, get_rates(deps, &pool_info)?.as_deref())?;
 
-    let (offer_asset_in_pool, ask_asset_in_pool, _, _, offer_decimal, ask_decimal) =
+    reverse_simulate_swap(
+        pool_info,
+        ask_asset,
+        offer_asset_denom,
+        imbalance_fee.as_ref(),
+        pegged_rate,
+    )
+}
+
+/// Reverse simulates a swap as [query_reverse_simulation] does, but replacing the reserves of the
+/// pool with the given ones. Reserves not overridden keep their current balance.
+pub fn query_reverse_simulation_with_reserves(
+    deps: Deps,
+    env: &Env,
+    ask_asset: Coin,
+    offer_asset_denom: String,
+    pool_identifier: String,
+    reserves: Vec<Coin>,
+) -> Result<ReverseSimulationResponse, ContractError> {
+    let mut pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    assert_reserves_can_be_overridden(&pool_info)?;
+    helpers::override_pool_reserves(&mut pool_info, reserves)?;
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps, env, &pool_info)?;
+    let pool_info = apply_rates(&pool_info, get_rates(deps, &pool_info)?.as_deref())?;
+
+    reverse_simulate_swap(
+        pool_info,
+        ask_asset,
+        offer_asset_denom,
+        imbalance_fee.as_ref(),
+        pegged_rate,
+    )
+}
+
+/// Reverse simulates a swap against the reserves of the given pool. As the imbalance fee depends
+/// on the trade it applies to, it's estimated from the same trade at the base pool fees. Pegged
+/// pools trade at the given oracle rate.
+fn reverse_simulate_swap(
+    pool_info: PoolInfo,
+    ask_asset: Coin,
+    offer_asset_denom: String,
+    imbalance_fee: Option<&ImbalanceFee>,
+    pegged_rate: Option<Decimal>,
+) -> Result<ReverseSimulationResponse, ContractError> {
+    let (offer_asset_in_pool, ask_asset_in_pool, offer_index, ask_index, _, ask_decimal) =
         get_asset_indexes_in_pool(&pool_info, offer_asset_denom, ask_asset.denom)?;
 
-    let pool_fees = pool_info.pool_fees;
+    let pool_fees = pool_info.pool_fees.clone();
 
     match pool_info.pool_type {
         PoolType::ConstantProduct => {
@@ -111,11 +359,39 @@ pub fn query_reverse_simulation(
                 extra_fees_amount: offer_amount_computation.extra_fees_amount,
             })
         }
-        PoolType::StableSwap { amp } => {
-            let offer_pool =
-                Decimal256::decimal_with_precision(offer_asset_in_pool.amount, offer_decimal)?;
-            let ask_pool =
-                Decimal256::decimal_with_precision(ask_asset_in_pool.amount, ask_decimal)?;
+        PoolType::StableSwap { amp } | PoolType::ComposableStableSwap { amp, .. } => {
+            let pool_fees = match imbalance_fee {
+                Some(imbalance_fee) => {
+                    let estimate = reverse_simulate_swap(
+                        pool_info.clone(),
+                        ask_asset.clone(),
+                        offer_asset_in_pool.denom.clone(),
+                        None,
+                        None,
+                    )?;
+                    let return_amount = ask_asset
+                        .amount
+                        .checked_add(estimate.swap_fee_amount)?
+                        .checked_add(estimate.protocol_fee_amount)?
+                        .checked_add(estimate.burn_fee_amount)?
+                        .checked_add(estimate.extra_fees_amount)?;
+
+                    helpers::apply_imbalance_fee(
+                        &pool_info,
+                        imbalance_fee,
+                        offer_index,
+                        ask_index,
+                        estimate.offer_amount,
+                        return_amount,
+                        pool_fees,
+                    )?
+                }
+                None => pool_fees,
+            };
+
+            // the stableswap math works on the amounts normalized to the precision of the pool
+            let weights = helpers::get_asset_weights(&pool_info);
+            let pools = normalize_reserves(&pool_info)?;
 
             let mut extra_fees = Decimal256::zero();
             for extra_fee in pool_fees.extra_fees.iter() {
@@ -128,43 +404,42 @@ pub fn query_reverse_simulation(
                 .checked_sub(pool_fees.burn_fee.to_decimal_256())?)
             .checked_sub(extra_fees)?
             .inv()
-            .unwrap_or_else(Decimal256::one)
+            .ok_or(ContractError::InvalidTotalPoolFees)?
             .checked_mul(Decimal256::decimal_with_precision(
                 ask_asset.amount,
                 ask_decimal,
             )?)?;
 
-            let before_fees_offer = before_fees.to_uint256_with_precision(offer_decimal.into())?;
-            let before_fees_ask = before_fees.to_uint256_with_precision(ask_decimal.into())?;
+            let weighted_before_fees = before_fees.checked_mul(weights[ask_index].into())?;
 
-            let max_precision = offer_decimal.max(ask_decimal);
+            // the offer amount equivalent to the ask amount at the pegged rate, 1:1 unless weighted
+            let before_fees_offer = denormalize_amount(
+                &pool_info,
+                offer_index,
+                weighted_before_fees,
+                Rounding::Down,
+            )?;
+            let before_fees_ask = before_fees.to_uint256_with_precision(ask_decimal.into())?;
 
-            let new_offer_pool_amount = calculate_stableswap_y(
-                Uint256::from(pool_info.assets.len() as u128),
-                offer_pool,
-                ask_pool,
-                before_fees,
+            let new_offer_pool = calculate_stableswap_y(
+                &pools,
+                offer_index,
+                ask_index,
+                weighted_before_fees,
                 &amp,
-                max_precision,
+                get_normalized_precision(&pool_info),
                 StableSwapDirection::ReverseSimulate,
             )?;
 
-            let offer_amount = new_offer_pool_amount.checked_sub(Uint128::try_from(
-                offer_pool.to_uint256_with_precision(u32::from(max_precision))?,
+            // convert the offer back into the offer asset, rounding it up so the offer is enough
+            // to get the ask amount once swapped
+            let offer_amount = Uint128::try_from(denormalize_amount(
+                &pool_info,
+                offer_index,
+                from_solver_amount(&pool_info, new_offer_pool)?.checked_sub(pools[offer_index])?,
+                Rounding::Up,
             )?)?;
 
-            // convert into the original offer precision
-            let offer_amount = match max_precision.cmp(&offer_decimal) {
-                Ordering::Equal => offer_amount,
-                // note that Less should never happen (as max_precision = max(offer_decimal, ask_decimal))
-                Ordering::Less => offer_amount.checked_mul(Uint128::new(
-                    10u128.pow((offer_decimal - max_precision).into()),
-                ))?,
-                Ordering::Greater => offer_amount.checked_div(Uint128::new(
-                    10u128.pow((max_precision - offer_decimal).into()),
-                ))?,
-            };
-
             let spread_amount = offer_amount.saturating_sub(Uint128::try_from(before_fees_offer)?);
             let swap_fee_amount = pool_fees.swap_fee.compute(before_fees_ask)?;
             let protocol_fee_amount = pool_fees.protocol_fee.compute(before_fees_ask)?;
@@ -185,6 +460,65 @@ pub fn query_reverse_simulation(
                 extra_fees_amount: extra_fees_amount.try_into()?,
             })
         }
+        PoolType::Pegged { .. } => {
+            let rate = pegged_rate.ok_or(ContractError::PeggedRateUnavailable)?;
+
+            let mut total_fees = pool_fees
+                .protocol_fee
+                .share
+                .checked_add(pool_fees.swap_fee.share)?
+                .checked_add(pool_fees.burn_fee.share)?;
+            for extra_fee in pool_fees.extra_fees.iter() {
+                total_fees = total_fees.checked_add(extra_fee.share)?;
+            }
+
+            // the fees are taken out of the return amount, so gross it up before converting
+            let before_fees_ask = ask_asset.amount.checked_div_ceil(
+                Decimal::one()
+                    .checked_sub(total_fees)
+                    .map_err(|_| ContractError::InvalidTotalPoolFees)?,
+            )?;
+            ensure!(
+                before_fees_ask < ask_asset_in_pool.amount,
+                ContractError::InsufficientPeggedPoolLiquidity {
+                    denom: ask_asset_in_pool.denom,
+                }
+            );
+
+            // pegged pools trade at the oracle rate, so there's no spread
+            let offer_amount = convert_at_pegged_rate(
+                &pool_info,
+                ask_index,
+                offer_index,
+                before_fees_ask,
+                rate,
+                true,
+            )?;
+
+            let before_fees_ask: Uint256 = before_fees_ask.into();
+            let mut extra_fees_amount: Uint256 = Uint256::zero();
+            for extra_fee in pool_fees.extra_fees.iter() {
+                extra_fees_amount =
+                    extra_fees_amount.checked_add(extra_fee.compute(before_fees_ask)?)?;
+            }
+
+            Ok(ReverseSimulationResponse {
+                offer_amount,
+                spread_amount: Uint128::zero(),
+                swap_fee_amount: pool_fees.swap_fee.compute(before_fees_ask)?.try_into()?,
+                protocol_fee_amount: pool_fees
+                    .protocol_fee
+                    .compute(before_fees_ask)?
+                    .try_into()?,
+                burn_fee_amount: pool_fees.burn_fee.compute(before_fees_ask)?.try_into()?,
+                extra_fees_amount: extra_fees_amount.try_into()?,
+            })
+        }
+        PoolType::ConcentratedLiquidity { .. } => {
+            Err(ContractError::UnsupportedForConcentratedLiquidity {
+                operation: "reverse simulations".to_string(),
+            })
+        }
     }
 }
 
@@ -192,52 +526,1096 @@ pub fn query_reverse_simulation(
 pub(crate) const MAX_LIMIT: u32 = 100;
 const DEFAULT_LIMIT: u32 = 10;
 
-/// Gets the pools in the contract. Returns a [PoolsResponse].
+/// Gets the pools in the contract, in ascending order of identifier unless otherwise specified.
+/// When paginating in descending order, `start_after` is the identifier to start before. Returns a
+/// [PoolsResponse] with the total amount of pools and the cursor of the next page, if any.
 pub fn get_pools(
     deps: Deps,
     pool_identifier: Option<String>,
     start_after: Option<String>,
     limit: Option<u32>,
+    order: Option<Order>,
+    deprecated: Option<bool>,
 ) -> Result<PoolsResponse, ContractError> {
-    let pools = if let Some(pool_identifier) = pool_identifier {
-        vec![get_pool(deps, pool_identifier)?]
+    let total_count = POOLS
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .count() as u64;
+
+    let (pools, next_key) = if let Some(pool_identifier) = pool_identifier {
+        let pool_identifier = POOL_ALIASES
+            .may_load(deps.storage, &pool_identifier)?
+            .unwrap_or(pool_identifier);
+        (vec![get_pool(deps, pool_identifier)?], None)
     } else {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = cw_utils::calc_range_start_string(start_after).map(Bound::ExclusiveRaw);
+        let order = order.unwrap_or(Order::Ascending);
+        let (start, end) = match order {
+            Order::Ascending => (
+                cw_utils::calc_range_start_string(start_after).map(Bound::ExclusiveRaw),
+                None,
+            ),
+            Order::Descending => (
+                None,
+                start_after.map(|start_after| Bound::ExclusiveRaw(start_after.into_bytes())),
+            ),
+        };
 
-        POOLS
-            .range(deps.storage, start, None, Order::Ascending)
+        let pools = POOLS
+            .range(deps.storage, start, end, order)
+            .filter(|item| match (item, deprecated) {
+                (Ok((pool_identifier, _)), Some(deprecated)) => {
+                    DEPRECATED_POOLS.has(deps.storage, pool_identifier) == deprecated
+                }
+                _ => true,
+            })
             .take(limit)
             .map(|item| {
                 let (_, pool) = item?;
-                let total_share = deps.querier.query_supply(&pool.lp_denom)?;
+                let total_share = coin(get_lp_supply(&deps, &pool)?.u128(), pool.lp_denom.clone());
 
                 Ok(PoolInfoResponse {
+                    deprecated: DEPRECATED_POOLS.has(deps.storage, &pool.pool_identifier),
+                    metadata: POOL_METADATA.may_load(deps.storage, &pool.pool_identifier)?,
                     pool_info: pool,
                     total_share,
                 })
             })
-            .collect::<StdResult<Vec<PoolInfoResponse>>>()?
+            .collect::<Result<Vec<PoolInfoResponse>, ContractError>>()?;
+
+        // a full page might be followed by more pools
+        let next_key = if pools.len() == limit {
+            pools
+                .last()
+                .map(|pool| pool.pool_info.pool_identifier.clone())
+        } else {
+            None
+        };
+
+        (pools, next_key)
     };
 
-    Ok(PoolsResponse { pools })
+    Ok(PoolsResponse {
+        pools,
+        total_count,
+        next_key,
+    })
 }
 
 /// Gets the pool info for a given pool identifier. Returns a [PoolInfoResponse].
 fn get_pool(deps: Deps, pool_identifier: String) -> Result<PoolInfoResponse, ContractError> {
     let pool_info = POOLS.load(deps.storage, &pool_identifier)?;
-    let total_share = deps.querier.query_supply(&pool_info.lp_denom)?;
+    let total_share = coin(
+        get_lp_supply(&deps, &pool_info)?.u128(),
+        pool_info.lp_denom.clone(),
+    );
 
     Ok(PoolInfoResponse {
+        deprecated: DEPRECATED_POOLS.has(deps.storage, &pool_info.pool_identifier),
+        metadata: POOL_METADATA.may_load(deps.storage, &pool_info.pool_identifier)?,
         pool_info,
         total_share,
     })
 }
 
+/// Gets the stop-loss orders in the contract, optionally filtered by owner. Returns a
+/// [StopLossOrdersResponse].
+pub fn get_stop_loss_orders(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<StopLossOrdersResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let orders = if let Some(owner) = owner {
+        STOP_LOSS_ORDERS
+            .idx
+            .owner
+            .prefix(owner)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, order)| order))
+            .collect::<StdResult<Vec<StopLossOrder>>>()?
+    } else {
+        STOP_LOSS_ORDERS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, order)| order))
+            .collect::<StdResult<Vec<StopLossOrder>>>()?
+    };
+
+    Ok(StopLossOrdersResponse { orders })
+}
+
+/// Queries the time-weighted average exchange rate of a route over the last `window_secs` seconds.
+pub fn query_route_twap(
+    deps: Deps,
+    env: Env,
+    operations: Vec<SwapOperation>,
+    window_secs: u64,
+) -> Result<RouteTwapResponse, ContractError> {
+    assert_operations(operations.clone())?;
+    assert_no_swap_adapters(&operations, "twap")?;
+
+    Ok(RouteTwapResponse {
+        twap: twap::get_route_twap(deps, env.block.time.seconds(), &operations, window_secs)?,
+    })
+}
+
+/// Queries the time-weighted average prices of every pair of assets in the given pool over the
+/// last `window_secs` seconds. Returns a [TwapResponse].
+pub fn query_twap(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    window_secs: u64,
+) -> Result<TwapResponse, ContractError> {
+    let twaps = twap::get_pool_twaps(
+        deps,
+        env.block.time.seconds(),
+        &pool_identifier,
+        window_secs,
+    )?;
+
+    Ok(TwapResponse {
+        pool_identifier,
+        window_secs,
+        twaps,
+    })
+}
+
+/// Values an LP position in `target_denom`. The position is given either by the address holding
+/// the LP tokens or by an LP amount. Each underlying asset is converted through the given swap
+/// route from its denom to `target_denom`, or the route registered for the pair if none is given,
+/// at the spot price or, if `twap_window_secs` is provided, at the TWAP over that window. Returns
+/// a [LpValuationResponse].
+#[allow(clippy::too_many_arguments)]
+pub fn query_lp_valuation(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    address: Option<String>,
+    lp_amount: Option<Uint128>,
+    target_denom: String,
+    twap_window_secs: Option<u64>,
+    swap_routes: Vec<SwapRoute>,
+) -> Result<LpValuationResponse, ContractError> {
+    for swap_route in &swap_routes {
+        assert_swap_route(swap_route)?;
+        if twap_window_secs.is_some() {
+            assert_no_swap_adapters(&swap_route.swap_operations, "twap")?;
+        }
+    }
+
+    let pool = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let lp_amount = match (address, lp_amount) {
+        (Some(address), None) => {
+            let address = deps.api.addr_validate(&address)?;
+            deps.querier
+                .query_balance(address, pool.lp_denom.clone())?
+                .amount
+        }
+        (None, Some(lp_amount)) => lp_amount,
+        _ => return Err(ContractError::InvalidLpValuationRequest),
+    };
+
+    let total_share = get_lp_supply(&deps, &pool)?;
+    let underlying_assets = if lp_amount.is_zero() || total_share.is_zero() {
+        vec![]
+    } else {
+        helpers::get_withdrawal_assets(&pool, lp_amount, total_share)?
+    };
+
+    let mut total_value = Uint128::zero();
+    let mut asset_values = vec![];
+    for asset in &underlying_assets {
+        let value = if asset.denom == target_denom {
+            asset.amount
+        } else {
+            let swap_operations = match swap_routes.iter().find(|swap_route| {
+                swap_route.offer_asset_denom == asset.denom
+                    && swap_route.ask_asset_denom == target_denom
+            }) {
+                Some(swap_route) => swap_route.swap_operations.clone(),
+                None => {
+                    SWAP_ROUTES
+                        .may_load(deps.storage, (&asset.denom, &target_denom))?
+                        .ok_or(ContractError::NoSwapRouteForAssets {
+                            offer_asset: asset.denom.clone(),
+                            ask_asset: target_denom.clone(),
+                        })?
+                        .swap_operations
+                }
+            };
+
+            let price = match twap_window_secs {
+                Some(window_secs) => twap::get_route_twap(
+                    deps,
+                    env.block.time.seconds(),
+                    &swap_operations,
+                    window_secs,
+                )?,
+                None => helpers::get_route_spot_price(&deps, &swap_operations)?,
+            };
+
+            asset.amount.checked_mul_floor(price)?
+        };
+
+        total_value = total_value.checked_add(value)?;
+        asset_values.push(coin(value.u128(), &target_denom));
+    }
+
+    Ok(LpValuationResponse {
+        lp_amount,
+        underlying_assets,
+        asset_values,
+        total_value: coin(total_value.u128(), target_denom),
+    })
+}
+
+/// Gets the swap route registered between the given denoms. Returns a [SwapRouteResponse].
+pub fn query_swap_route(
+    deps: Deps,
+    offer_asset_denom: String,
+    ask_asset_denom: String,
+) -> Result<SwapRouteResponse, ContractError> {
+    let route = SWAP_ROUTES
+        .may_load(deps.storage, (&offer_asset_denom, &ask_asset_denom))?
+        .ok_or(ContractError::NoSwapRouteForAssets {
+            offer_asset: offer_asset_denom.clone(),
+            ask_asset: ask_asset_denom.clone(),
+        })?;
+
+    Ok(SwapRouteResponse {
+        swap_route: SwapRoute {
+            offer_asset_denom,
+            ask_asset_denom,
+            swap_operations: route.swap_operations,
+        },
+        creator: route.creator,
+    })
+}
+
+/// Gets the registered swap routes, ordered by offer and ask denoms. Returns a
+/// [SwapRoutesResponse].
+pub fn query_swap_routes(
+    deps: Deps,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> Result<SwapRoutesResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(offer, ask)| Bound::exclusive((offer.as_str(), ask.as_str())));
+
+    let swap_routes = SWAP_ROUTES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let ((offer_asset_denom, ask_asset_denom), route) = item?;
+
+            Ok(SwapRouteResponse {
+                swap_route: SwapRoute {
+                    offer_asset_denom,
+                    ask_asset_denom,
+                    swap_operations: route.swap_operations,
+                },
+                creator: route.creator,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SwapRoutesResponse { swap_routes })
+}
+
+/// Gets the whitelisted swap adapters. Returns a [SwapAdaptersResponse].
+pub fn query_swap_adapters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<SwapAdaptersResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let adapters = SWAP_ADAPTERS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SwapAdaptersResponse { adapters })
+}
+
+/// Gets the price subscriptions in the contract, optionally filtered by pool. Returns a
+/// [PriceSubscriptionsResponse].
+pub fn get_price_subscriptions(
+    deps: Deps,
+    pool_identifier: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<PriceSubscriptionsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let subscriptions = if let Some(pool_identifier) = pool_identifier {
+        PRICE_SUBSCRIPTIONS
+            .idx
+            .pool_identifier
+            .prefix(pool_identifier)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, subscription)| subscription))
+            .collect::<StdResult<Vec<PriceSubscription>>>()?
+    } else {
+        PRICE_SUBSCRIPTIONS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, subscription)| subscription))
+            .collect::<StdResult<Vec<PriceSubscription>>>()?
+    };
+
+    Ok(PriceSubscriptionsResponse { subscriptions })
+}
+
+/// Gets the DCA schedules in the contract, optionally filtered by owner. Returns a
+/// [DcaSchedulesResponse].
+pub fn get_dca_schedules(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<DcaSchedulesResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let schedules = if let Some(owner) = owner {
+        DCA_SCHEDULES
+            .idx
+            .owner
+            .prefix(owner)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, schedule)| schedule))
+            .collect::<StdResult<Vec<DcaSchedule>>>()?
+    } else {
+        DCA_SCHEDULES
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, schedule)| schedule))
+            .collect::<StdResult<Vec<DcaSchedule>>>()?
+    };
+
+    Ok(DcaSchedulesResponse { schedules })
+}
+
+/// Gets the active liquidity streams, optionally filtered by owner. Returns a
+/// [LiquidityStreamsResponse].
+pub fn get_liquidity_streams(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<LiquidityStreamsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let streams = if let Some(owner) = owner {
+        LIQUIDITY_STREAMS
+            .idx
+            .owner
+            .prefix(owner)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, stream)| stream))
+            .collect::<StdResult<Vec<LiquidityStream>>>()?
+    } else {
+        LIQUIDITY_STREAMS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, stream)| stream))
+            .collect::<StdResult<Vec<LiquidityStream>>>()?
+    };
+
+    Ok(LiquidityStreamsResponse { streams })
+}
+
+/// Gets the protocol-owned liquidity of each pool together with the rebalancing config. Returns a
+/// [ProtocolLiquidityResponse].
+pub fn query_protocol_liquidity(deps: Deps) -> Result<ProtocolLiquidityResponse, ContractError> {
+    let liquidity = PROTOCOL_LIQUIDITY
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+    Ok(ProtocolLiquidityResponse {
+        rebalance_config: REBALANCE_CONFIG.may_load(deps.storage)?.unwrap_or_default(),
+        liquidity,
+    })
+}
+
+/// Gets the rounding dust accumulated by the given pool while dust tracking is enabled, i.e. the
+/// fractions of a unit the pool math rounded away, kept by the pool or given away by it. Returns a
+/// [RoundingDustResponse].
+pub fn query_rounding_dust(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<RoundingDustResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let rounding_dust = get_rounding_dust(deps.storage, &pool_info)?;
+
+    Ok(RoundingDustResponse {
+        enabled: is_dust_tracking_enabled(deps.storage)?,
+        accrued: pool_info
+            .asset_denoms
+            .iter()
+            .zip(rounding_dust.accrued)
+            .map(|(denom, amount)| DecCoin::new(amount, denom))
+            .collect(),
+        leaked: pool_info
+            .asset_denoms
+            .iter()
+            .zip(rounding_dust.leaked)
+            .map(|(denom, amount)| DecCoin::new(amount, denom))
+            .collect(),
+        lp_accrued: DecCoin::new(rounding_dust.lp_accrued, pool_info.lp_denom),
+        pool_identifier,
+    })
+}
+
+/// Gets the IBC channels over which assets are transferred when withdrawing liquidity to a remote
+/// address. Returns an [IbcWithdrawalChannelsResponse].
+pub fn query_ibc_withdrawal_channels(
+    deps: Deps,
+) -> Result<IbcWithdrawalChannelsResponse, ContractError> {
+    let channels = IBC_WITHDRAWAL_CHANNELS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(denom, channel_id)| IbcWithdrawalChannel {
+                denom,
+                channel_id: Some(channel_id),
+            })
+        })
+        .collect::<StdResult<Vec<IbcWithdrawalChannel>>>()?;
+
+    Ok(IbcWithdrawalChannelsResponse { channels })
+}
+
+/// Gets the migration campaign of the given pool and, if an address is provided, the LP amount it
+/// moved as part of it together with its share of the rewards, as of now. Returns a
+/// [MigrationCampaignResponse].
+pub fn query_migration_campaign(
+    deps: Deps,
+    from_pool_identifier: String,
+    address: Option<String>,
+) -> Result<MigrationCampaignResponse, ContractError> {
+    let campaign = MIGRATION_CAMPAIGNS
+        .may_load(deps.storage, &from_pool_identifier)?
+        .ok_or(ContractError::MigrationCampaignNotFound {
+            pool_identifier: from_pool_identifier.clone(),
+        })?;
+
+    let migrated = match address {
+        Some(address) => {
+            let address = deps.api.addr_validate(&address)?;
+            MIGRATED_LIQUIDITY
+                .may_load(deps.storage, (&from_pool_identifier, &address))?
+                .unwrap_or_default()
+        }
+        None => Uint128::zero(),
+    };
+
+    Ok(MigrationCampaignResponse {
+        rewards: get_migration_rewards(&campaign, migrated)?,
+        migrated,
+        campaign,
+    })
+}
+
+/// Gets the amplification factor of the given stableswap pool, both as configured and as currently
+/// used by the swap and liquidity math. Returns a [StableSwapAmpResponse].
+pub fn query_stableswap_amp(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<StableSwapAmpResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    match pool_info.pool_type {
+        PoolType::StableSwap { amp } | PoolType::ComposableStableSwap { amp, .. } => {
+            Ok(StableSwapAmpResponse {
+                pool_identifier,
+                amp,
+                // the amp is fixed when the pool is created, so it's always the one in effect
+                effective_amp: amp,
+            })
+        }
+        PoolType::ConstantProduct
+        | PoolType::Pegged { .. }
+        | PoolType::ConcentratedLiquidity { .. } => {
+            Err(ContractError::NotStableSwapPool { pool_identifier })
+        }
+    }
+}
+
+/// Replays the stableswap math of swapping `offer_asset` for `ask_asset_denom` in the given pool,
+/// returning the values of every Newton-Raphson iteration together with the result or the error
+/// it failed with. Used to reproduce convergence failures. Returns a [StableSwapDebugResponse].
+pub fn query_stableswap_debug(
+    deps: Deps,
+    pool_identifier: String,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+) -> Result<StableSwapDebugResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let amp = match pool_info.pool_type {
+        PoolType::StableSwap { amp } | PoolType::ComposableStableSwap { amp, .. } => amp,
+        PoolType::ConstantProduct
+        | PoolType::Pegged { .. }
+        | PoolType::ConcentratedLiquidity { .. } => {
+            return Err(ContractError::NotStableSwapPool { pool_identifier })
+        }
+    };
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    // same inputs as the swap computation
+    let precision = get_normalized_precision(&pool_info);
+    let pools = normalize_reserves(&pool_info)?;
+    let offer_amount = normalize_amount(&pool_info, offer_index, offer_asset.amount)?;
+
+    let mut trace = StableSwapTrace::default();
+    let result = calculate_stableswap_y_with_trace(
+        &pools,
+        offer_index,
+        ask_index,
+        offer_amount,
+        &amp,
+        precision,
+        StableSwapDirection::Simulate,
+        Some(&mut trace),
+    );
+
+    Ok(StableSwapDebugResponse {
+        pool_identifier,
+        inputs_hash: hash_stableswap_inputs(
+            &pools,
+            &amp,
+            precision,
+            Some((
+                offer_index,
+                ask_index,
+                offer_amount,
+                &StableSwapDirection::Simulate,
+            )),
+        ),
+        d_iterations: trace.d_iterations,
+        y_iterations: trace.y_iterations,
+        new_ask_pool: result.as_ref().ok().copied(),
+        error: result.err().map(|err| err.to_string()),
+    })
+}
+
+/// Gets the price breaker of the given pool, along with the current deviation of its spot price from
+/// the reference price and the time the breaker was tripped at, if it is. Returns a
+/// [PriceBreakerResponse].
+pub fn query_price_breaker(
+    deps: Deps,
+    env: &Env,
+    pool_identifier: String,
+) -> Result<PriceBreakerResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(PriceBreakerResponse {
+        config: PRICE_BREAKERS.may_load(deps.storage, &pool_identifier)?,
+        deviation: get_spot_price_deviation(deps, env, &pool_info)?,
+        tripped_at: PRICE_BREAKER_TRIPPED_AT.may_load(deps.storage, &pool_identifier)?,
+        pool_identifier,
+    })
+}
+
+/// Gets the tiers of the swap fee discounts, along with the discount the given trader is entitled
+/// to, if any. Returns a [FeeDiscountsResponse].
+pub fn query_fee_discounts(
+    deps: Deps,
+    trader: Option<String>,
+) -> Result<FeeDiscountsResponse, ContractError> {
+    let discount = trader
+        .map(|trader| get_fee_discount(deps, &deps.api.addr_validate(&trader)?))
+        .transpose()?;
+
+    Ok(FeeDiscountsResponse {
+        config: FEE_DISCOUNTS.may_load(deps.storage)?,
+        discount,
+    })
+}
+
+/// Gets the depeg protection of the given pool, along with its current peg deviation and, when the
+/// protection is active, the asset whose sales into the pool are paused. Returns a
+/// [DepegProtectionResponse].
+pub fn query_depeg_protection(
+    deps: Deps,
+    env: &Env,
+    pool_identifier: String,
+) -> Result<DepegProtectionResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(DepegProtectionResponse {
+        config: DEPEG_PROTECTION.may_load(deps.storage, &pool_identifier)?,
+        peg_deviation: helpers::get_peg_deviation(&pool_info)?,
+        deviating_since: DEPEG_DEVIATING_SINCE.may_load(deps.storage, &pool_identifier)?,
+        depegging_denom: get_depegging_denom(deps.storage, &pool_info, env.block.time.seconds())?,
+        pool_identifier,
+    })
+}
+
+/// Gets the imbalance fee of the given pool, if any. Returns an [ImbalanceFeeResponse].
+pub fn query_imbalance_fee(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<ImbalanceFeeResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(ImbalanceFeeResponse {
+        imbalance_fee: IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?,
+        pool_identifier,
+    })
+}
+
+/// Gets the minimum reserves of the given pool, if any. Returns a [ReserveFloorsResponse].
+pub fn query_reserve_floors(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<ReserveFloorsResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(ReserveFloorsResponse {
+        floors: RESERVE_FLOORS
+            .may_load(deps.storage, &pool_identifier)?
+            .unwrap_or_default(),
+        pool_identifier,
+    })
+}
+
+/// Gets the rate providers of the given pool and the rates they currently report, if any.
+/// Returns a [RateProvidersResponse].
+pub fn query_rate_providers(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<RateProvidersResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(RateProvidersResponse {
+        rate_providers: RATE_PROVIDERS
+            .may_load(deps.storage, &pool_identifier)?
+            .unwrap_or_default(),
+        rates: get_rates(deps, &pool_info)?.unwrap_or_default(),
+        pool_identifier,
+    })
+}
+
+/// Gets the fees scheduled for the given pool, if any, along with when they can be applied.
+/// Returns a [PendingPoolFeesResponse].
+pub fn query_pending_pool_fees(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<PendingPoolFeesResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(PendingPoolFeesResponse {
+        pending_pool_fees: PENDING_POOL_FEES.may_load(deps.storage, &pool_info.pool_identifier)?,
+        pool_fees: pool_info.pool_fees,
+        pool_identifier: pool_info.pool_identifier,
+    })
+}
+
+/// Gets the minimum unlocking duration the liquidity provided to the given pool must be locked
+/// for, if any. Returns a [MinLpLockResponse].
+pub fn query_min_lp_lock(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<MinLpLockResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(MinLpLockResponse {
+        min_unlocking_duration: MIN_LP_LOCKS.may_load(deps.storage, &pool_identifier)?,
+        pool_identifier,
+    })
+}
+
+/// Gets the whitelisted yield strategies, together with the denom each of them accepts. Returns a
+/// [YieldStrategiesResponse].
+pub fn query_yield_strategies(deps: Deps) -> Result<YieldStrategiesResponse, ContractError> {
+    let strategies = YIELD_STRATEGIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(address, denom)| YieldStrategy { address, denom }))
+        .collect::<StdResult<Vec<YieldStrategy>>>()?;
+
+    Ok(YieldStrategiesResponse { strategies })
+}
+
+/// Gets the yield strategies the reserves of the given pool can be deployed into, and how much of
+/// them is. Returns a [YieldDeploymentsResponse].
+pub fn query_yield_deployments(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<YieldDeploymentsResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let deployments = YIELD_DEPLOYMENTS
+        .prefix(&pool_identifier)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, deployment)| deployment))
+        .collect::<StdResult<Vec<YieldDeployment>>>()?;
+
+    Ok(YieldDeploymentsResponse {
+        pool_identifier,
+        deployments,
+    })
+}
+
+/// Gets the bounds of the unlocking durations accepted when providing liquidity to the given pool
+/// with a lock, be they its own or the global ones. Returns an [UnlockingDurationBoundsResponse].
+pub fn query_unlocking_duration_bounds(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<UnlockingDurationBoundsResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(UnlockingDurationBoundsResponse {
+        bounds: get_unlocking_duration_bounds(deps.storage, &pool_identifier)?,
+        pool_identifier,
+    })
+}
+
+/// Gets the traces of the IBC denoms of the given pool. Returns a [DenomTracesResponse].
+pub fn query_denom_traces(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<DenomTracesResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(DenomTracesResponse {
+        denom_traces: POOL_DENOM_TRACES
+            .may_load(deps.storage, &pool_identifier)?
+            .unwrap_or_default(),
+        pool_identifier,
+    })
+}
+
+/// Gets the admin and the allowlist of the given pool. The admin is `None` for public pools.
+/// Returns a [PoolAllowlistResponse].
+pub fn query_pool_allowlist(
+    deps: Deps,
+    pool_identifier: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<PoolAllowlistResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = POOL_ALLOWLIST
+        .prefix(&pool_identifier)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PoolAllowlistResponse {
+        admin: PERMISSIONED_POOLS.may_load(deps.storage, &pool_identifier)?,
+        addresses,
+        pool_identifier,
+    })
+}
+
+/// Gets whether pool creation is permissionless, along with the allowlist of pool creators.
+/// Returns a [PoolCreatorAllowlistResponse].
+pub fn query_pool_creator_allowlist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<PoolCreatorAllowlistResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let addresses = POOL_CREATOR_ALLOWLIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PoolCreatorAllowlistResponse {
+        permissionless_pool_creation: CONFIG.load(deps.storage)?.permissionless_pool_creation,
+        addresses,
+    })
+}
+
+/// Gets the admin of the given pool, along with the admin it's being transferred to, if any.
+/// Returns a [PoolAdminResponse].
+pub fn query_pool_admin(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<PoolAdminResponse, ContractError> {
+    let pool = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(PoolAdminResponse {
+        pending_admin: PENDING_POOL_ADMINS.may_load(deps.storage, &pool.pool_identifier)?,
+        admin: pool.admin,
+        pool_identifier: pool.pool_identifier,
+    })
+}
+
+/// Gets the channels IBC assets must have come through to be added to a pool. Returns an
+/// [IbcSourceChannelsResponse].
+pub fn query_ibc_source_channels(deps: Deps) -> Result<IbcSourceChannelsResponse, ContractError> {
+    Ok(IbcSourceChannelsResponse {
+        channels: IBC_SOURCE_CHANNELS
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+    })
+}
+
+/// Gets the execute messages disabled by the circuit breaker module of the chain. Returns a
+/// [DisabledMessagesResponse].
+pub fn query_disabled_messages(deps: Deps) -> Result<DisabledMessagesResponse, ContractError> {
+    Ok(DisabledMessagesResponse {
+        messages: circuit_breaker::get_disabled_messages(deps.storage)?,
+    })
+}
+
+/// Gets the current addresses of the contracts of the DEX, along with their contract name and
+/// version as stored by cw2 when they're available. As both are read live, the registry follows
+/// the config updates and migrations of the contracts. Returns a [ContractRegistryResponse].
+pub fn query_contract_registry(
+    deps: Deps,
+    env: Env,
+) -> Result<ContractRegistryResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // the epoch manager is only known to the farm manager
+    let epoch_manager_addr = deps
+        .querier
+        .query_wasm_smart::<mantra_dex_std::farm_manager::Config>(
+            &config.farm_manager_addr,
+            &mantra_dex_std::farm_manager::QueryMsg::Config {},
+        )
+        .ok()
+        .map(|farm_manager_config| farm_manager_config.epoch_manager_addr);
+
+    let pool_manager_version = cw2::get_contract_version(deps.storage)?;
+    let mut contracts = vec![
+        RegisteredContract {
+            name: "pool_manager".to_string(),
+            address: env.contract.address.clone(),
+            contract: Some(pool_manager_version.contract.clone()),
+            version: Some(pool_manager_version.version.clone()),
+        },
+        // swaps are routed by the pool manager itself
+        RegisteredContract {
+            name: "router".to_string(),
+            address: env.contract.address,
+            contract: Some(pool_manager_version.contract),
+            version: Some(pool_manager_version.version),
+        },
+    ];
+
+    for (name, address) in [
+        ("farm_manager", Some(config.farm_manager_addr)),
+        ("fee_collector", Some(config.fee_collector_addr)),
+        ("epoch_manager", epoch_manager_addr),
+    ] {
+        let Some(address) = address else {
+            continue;
+        };
+
+        let contract_version = cw2::query_contract_info(&deps.querier, &address).ok();
+        contracts.push(RegisteredContract {
+            name: name.to_string(),
+            address,
+            contract: contract_version
+                .as_ref()
+                .map(|contract_version| contract_version.contract.clone()),
+            version: contract_version.map(|contract_version| contract_version.version),
+        });
+    }
+
+    Ok(ContractRegistryResponse { contracts })
+}
+
+/// Gets the CW20 wrapper of the LP tokens of the given pool, if any. Returns a
+/// [LpWrapperResponse].
+pub fn query_lp_wrapper(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<LpWrapperResponse, ContractError> {
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(LpWrapperResponse {
+        lp_wrapper: LP_WRAPPERS
+            .may_load(deps.storage, &pool_identifier)?
+            .map(|lp_wrapper| lp_wrapper.into_string()),
+        pool_identifier,
+    })
+}
+
+/// Gets the public key and the inventory of the given RFQ maker. Returns a [RfqMakerResponse].
+pub fn query_rfq_maker(deps: Deps, maker: String) -> Result<RfqMakerResponse, ContractError> {
+    let maker = deps.api.addr_validate(&maker)?;
+
+    let inventory = RFQ_INVENTORY
+        .prefix(&maker)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect::<StdResult<Vec<Coin>>>()?;
+
+    Ok(RfqMakerResponse {
+        pubkey: RFQ_MAKERS.may_load(deps.storage, &maker)?,
+        maker: maker.into_string(),
+        inventory,
+    })
+}
+
+/// Gets the digest of the given RFQ quote, which is what the maker must sign for the quote to be
+/// filled. Returns a [RfqQuoteDigestResponse].
+pub fn query_rfq_quote_digest(
+    env: Env,
+    quote: RfqQuote,
+) -> Result<RfqQuoteDigestResponse, ContractError> {
+    Ok(RfqQuoteDigestResponse {
+        digest: Binary::from(rfq::commands::quote_digest(&env, &quote)?),
+    })
+}
+
+/// The most hops [find_best_route] searches routes of.
+pub const MAX_ROUTE_HOPS: u32 = 3;
+
+/// Finds the route of at most `max_hops` swaps through the pools of the contract returning the most
+/// of `ask_denom` for `amount` of `offer_denom`, as simulated. Deprecated and permissioned pools,
+/// and pools whose price breaker is tripped, are left out of the search.
+///
+/// The search keeps the best route to each denom found at each hop, and extends it through every
+/// pool holding the denom the route hasn't traded yet. Returns a [FindBestRouteResponse].
+pub fn find_best_route(
+    deps: Deps,
+    env: &Env,
+    offer_denom: String,
+    ask_denom: String,
+    amount: Uint128,
+    max_hops: u32,
+) -> Result<FindBestRouteResponse, ContractError> {
+    ensure!(offer_denom != ask_denom, ContractError::SameAsset);
+    ensure!(
+        max_hops > 0 && max_hops <= MAX_ROUTE_HOPS,
+        ContractError::InvalidMaxHops {
+            max: MAX_ROUTE_HOPS
+        }
+    );
+
+    let pools = POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, pool)| pool))
+        .collect::<StdResult<Vec<PoolInfo>>>()?
+        .into_iter()
+        .filter(|pool| {
+            !DEPRECATED_POOLS.has(deps.storage, &pool.pool_identifier)
+                && !PERMISSIONED_POOLS.has(deps.storage, &pool.pool_identifier)
+                && !PRICE_BREAKER_TRIPPED_AT.has(deps.storage, &pool.pool_identifier)
+        })
+        .collect::<Vec<_>>();
+
+    let mut best_route: Option<(Uint128, Vec<SwapOperation>)> = None;
+    // the routes to extend at the next hop, keyed by the denom they end at
+    let mut routes: BTreeMap<String, (Uint128, Vec<SwapOperation>)> =
+        BTreeMap::from([(offer_denom.clone(), (amount, vec![]))]);
+
+    for _ in 0..max_hops {
+        let mut next_routes: BTreeMap<String, (Uint128, Vec<SwapOperation>)> = BTreeMap::new();
+
+        for (denom, (route_amount, operations)) in routes.iter() {
+            for pool in pools.iter().filter(|pool| {
+                pool.asset_denoms.contains(denom)
+                    && !operations.iter().any(|operation| {
+                        matches!(operation, SwapOperation::MantraSwap { pool_identifier, .. }
+                            if *pool_identifier == pool.pool_identifier)
+                    })
+            }) {
+                for token_out_denom in pool
+                    .asset_denoms
+                    .iter()
+                    .filter(|asset_denom| *asset_denom != denom && **asset_denom != offer_denom)
+                {
+                    // pools that can't be simulated, e.g. lacking liquidity, are skipped
+                    let Ok(simulation) = query_simulation(
+                        deps,
+                        env,
+                        coin(route_amount.u128(), denom),
+                        token_out_denom.clone(),
+                        pool.pool_identifier.clone(),
+                        None,
+                    ) else {
+                        continue;
+                    };
+
+                    if simulation.return_amount.is_zero()
+                        || next_routes
+                            .get(token_out_denom)
+                            .is_some_and(|(best_amount, _)| {
+                                simulation.return_amount <= *best_amount
+                            })
+                    {
+                        continue;
+                    }
+
+                    let mut route = operations.clone();
+                    route.push(SwapOperation::MantraSwap {
+                        token_in_denom: denom.clone(),
+                        token_out_denom: token_out_denom.clone(),
+                        pool_identifier: pool.pool_identifier.clone(),
+                    });
+                    next_routes.insert(token_out_denom.clone(), (simulation.return_amount, route));
+                }
+            }
+        }
+
+        // routes reaching the ask denom are done, the others are extended at the next hop
+        if let Some((return_amount, operations)) = next_routes.remove(&ask_denom) {
+            if best_route
+                .as_ref()
+                .map_or(true, |(best_amount, _)| return_amount > *best_amount)
+            {
+                best_route = Some((return_amount, operations));
+            }
+        }
+
+        routes = next_routes;
+    }
+
+    let (return_amount, operations) = best_route.ok_or(ContractError::NoRouteFound {
+        offer_denom,
+        ask_denom,
+    })?;
+
+    Ok(FindBestRouteResponse {
+        operations,
+        return_amount,
+    })
+}
+
 /// This function iterates over the swap operations, simulates each swap
-/// to get the final amount after all the swaps.
+/// to get the final amount after all the swaps. Each swap is applied to the pool it trades, so
+/// routes trading a pool more than once are simulated as they are executed.
 pub fn simulate_swap_operations(
     deps: Deps,
+    env: &Env,
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
@@ -250,6 +1628,10 @@ pub fn simulate_swap_operations(
     let mut protocol_fees: Vec<Coin> = vec![];
     let mut burn_fees: Vec<Coin> = vec![];
     let mut extra_fees: Vec<Coin> = vec![];
+    // the pools traded by the route, as left by the previous swaps
+    let mut pools: Vec<PoolInfo> = vec![];
+    // the state of the concentrated liquidity pools among them, indexed like the pools
+    let mut concentrated_pools: Vec<Option<ConcentratedPoolState>> = vec![];
 
     for operation in operations.into_iter() {
         match operation {
@@ -258,12 +1640,59 @@ pub fn simulate_swap_operations(
                 token_out_denom,
                 pool_identifier,
             } => {
-                let res = query_simulation(
-                    deps,
-                    coin(amount.u128(), token_in_denom),
-                    token_out_denom.clone(),
-                    pool_identifier,
+                let pool_index = match pools
+                    .iter()
+                    .position(|pool| pool.pool_identifier == pool_identifier)
+                {
+                    Some(pool_index) => pool_index,
+                    None => {
+                        let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+                        concentrated_pools.push(load_concentrated_pool(deps.storage, &pool_info)?);
+                        pools.push(pool_info);
+                        pools.len() - 1
+                    }
+                };
+                let pool_info = &mut pools[pool_index];
+
+                let (_, _, offer_index, ask_index, _, _) =
+                    get_asset_indexes_in_pool(pool_info, token_in_denom, token_out_denom.clone())?;
+                let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+                let pegged_rate = get_pegged_rate(deps, env, pool_info)?;
+                let rates = get_rates(deps, pool_info)?;
+                let swap_computation = match &concentrated_pools[pool_index] {
+                    Some(state) => {
+                        // the ticks crossed aren't kept, as only their fee growth changes
+                        let concentrated_swap = compute_concentrated_swap(
+                            deps.storage,
+                            pool_info,
+                            state,
+                            offer_index,
+                            ask_index,
+                            amount,
+                            pool_info.pool_fees.clone(),
+                        )?;
+                        concentrated_pools[pool_index] = Some(concentrated_swap.state);
+                        concentrated_swap.swap_computation
+                    }
+                    None => helpers::compute_swap(
+                        &apply_rates(pool_info, rates.as_deref())?,
+                        offer_index,
+                        ask_index,
+                        amount,
+                        pool_info.pool_fees.clone(),
+                        imbalance_fee.as_ref(),
+                        pegged_rate,
+                    )?,
+                };
+                helpers::apply_swap_to_pool(
+                    pool_info,
+                    offer_index,
+                    ask_index,
+                    amount,
+                    &swap_computation,
                 )?;
+
+                let res = swap_computation.to_simulation_response();
                 amount = res.return_amount;
 
                 if res.spread_amount > Uint128::zero() {
@@ -282,6 +1711,21 @@ pub fn simulate_swap_operations(
                     extra_fees.push(coin(res.extra_fees_amount.u128(), &token_out_denom));
                 }
             }
+            // the fees and spread of external AMMs are accounted for by the adapter
+            SwapOperation::ExternalAdapter {
+                token_in_denom,
+                token_out_denom,
+                adapter_addr,
+                msg,
+            } => {
+                amount = simulate_adapter_swap(
+                    deps,
+                    &adapter_addr,
+                    coin(amount.u128(), token_in_denom),
+                    token_out_denom,
+                    msg,
+                )?;
+            }
         }
     }
 
@@ -305,6 +1749,7 @@ pub fn simulate_swap_operations(
 /// simulates each swap to get the final amount after all the swaps.
 pub fn reverse_simulate_swap_operations(
     deps: Deps,
+    env: &Env,
     ask_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<ReverseSimulateSwapOperationsResponse, ContractError> {
@@ -312,6 +1757,7 @@ pub fn reverse_simulate_swap_operations(
     if operations_len == 0 {
         return Err(ContractError::NoSwapOperationsProvided);
     }
+    assert_no_swap_adapters(&operations, "reverse_simulation")?;
 
     let mut offer_in_needed = ask_amount;
     let mut spreads: Vec<Coin> = vec![];
@@ -329,6 +1775,7 @@ pub fn reverse_simulate_swap_operations(
             } => {
                 let res = query_reverse_simulation(
                     deps,
+                    env,
                     coin(offer_in_needed.u128(), token_out_denom.clone()),
                     token_in_denom,
                     pool_identifier,
@@ -352,6 +1799,11 @@ pub fn reverse_simulate_swap_operations(
 
                 offer_in_needed = res.offer_amount;
             }
+            SwapOperation::ExternalAdapter { .. } => {
+                return Err(ContractError::UnsupportedForSwapAdapter {
+                    operation: "reverse_simulation".to_string(),
+                });
+            }
         }
     }
 
@@ -370,3 +1822,100 @@ pub fn reverse_simulate_swap_operations(
         extra_fees,
     })
 }
+
+/// Gets the state of the given concentrated liquidity pool, unset until liquidity is first provided
+/// to it. Returns a [ConcentratedPoolResponse].
+pub fn query_concentrated_pool(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<ConcentratedPoolResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let PoolType::ConcentratedLiquidity { tick_spacing } = pool_info.pool_type else {
+        return Err(ContractError::NotConcentratedLiquidityPool { pool_identifier });
+    };
+
+    let state = CONCENTRATED_POOLS.may_load(deps.storage, &pool_identifier)?;
+    let price = state
+        .as_ref()
+        .map(|state| state.sqrt_price.checked_mul(state.sqrt_price))
+        .transpose()?;
+
+    Ok(ConcentratedPoolResponse {
+        pool_identifier,
+        tick_spacing,
+        price,
+        state,
+    })
+}
+
+/// Gets the concentrated liquidity positions in the contract, optionally filtered by owner and
+/// pool, together with the assets they're worth at the current price and the fees they earned.
+/// Returns a [ConcentratedPositionsResponse].
+pub fn query_concentrated_positions(
+    deps: Deps,
+    owner: Option<String>,
+    pool_identifier: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<ConcentratedPositionsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let positions = match owner {
+        Some(owner) => CONCENTRATED_POSITIONS.idx.owner.prefix(owner).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        None => CONCENTRATED_POSITIONS.range(deps.storage, start, None, Order::Ascending),
+    }
+    .filter(|item| match (item, &pool_identifier) {
+        (Ok((_, position)), Some(pool_identifier)) => &position.pool_identifier == pool_identifier,
+        _ => true,
+    })
+    .take(limit)
+    .map(|item| item.map(|(_, position)| position))
+    .collect::<StdResult<Vec<ConcentratedPosition>>>()?;
+
+    let positions = positions
+        .into_iter()
+        .map(|position| get_concentrated_position_info(deps, position))
+        .collect::<Result<Vec<ConcentratedPositionInfo>, ContractError>>()?;
+
+    Ok(ConcentratedPositionsResponse { positions })
+}
+
+/// Gets the assets the given concentrated liquidity position is worth at the current price of its
+/// pool, rounded down as when withdrawn, and the fees it earned.
+fn get_concentrated_position_info(
+    deps: Deps,
+    position: ConcentratedPosition,
+) -> Result<ConcentratedPositionInfo, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &position.pool_identifier)?;
+    let state = CONCENTRATED_POOLS.load(deps.storage, &position.pool_identifier)?;
+
+    let amounts = get_amounts_for_liquidity(
+        state.sqrt_price,
+        get_sqrt_price_at_tick(position.lower_tick)?,
+        get_sqrt_price_at_tick(position.upper_tick)?,
+        position.liquidity,
+        false,
+    )?;
+    let fees = get_position_fees(deps.storage, &state, &position)?;
+
+    let to_coins = |amounts: &[Uint128]| -> Vec<Coin> {
+        pool_info
+            .asset_denoms
+            .iter()
+            .zip(amounts.iter())
+            .map(|(denom, amount)| coin(amount.u128(), denom))
+            .collect()
+    };
+
+    Ok(ConcentratedPositionInfo {
+        assets: to_coins(&amounts),
+        uncollected_fees: to_coins(&fees),
+        position,
+    })
+}
@@ -0,0 +1,71 @@
+use cosmwasm_std::{ensure, Decimal, Deps};
+
+use mantra_dex_std::pool_manager::{PoolInfo, PoolType};
+use mantra_dex_std::rate_provider::{QueryMsg, RateResponse};
+
+use crate::helpers::get_asset_weights;
+use crate::state::RATE_PROVIDERS;
+use crate::ContractError;
+
+/// Gets the exchange rates of the assets of the given pool, as reported by its rate providers.
+/// Assets without a rate provider have a rate of one. Returns `None` for pools without rate
+/// providers.
+pub(crate) fn get_rates(
+    deps: Deps,
+    pool_info: &PoolInfo,
+) -> Result<Option<Vec<Decimal>>, ContractError> {
+    let Some(rate_providers) = RATE_PROVIDERS.may_load(deps.storage, &pool_info.pool_identifier)?
+    else {
+        return Ok(None);
+    };
+
+    rate_providers
+        .iter()
+        .map(|rate_provider| {
+            let Some(rate_provider) = rate_provider else {
+                return Ok(Decimal::one());
+            };
+
+            let response: RateResponse = deps
+                .querier
+                .query_wasm_smart(rate_provider, &QueryMsg::Rate {})?;
+            ensure!(
+                !response.rate.is_zero(),
+                ContractError::InvalidRate {
+                    rate_provider: rate_provider.to_string(),
+                }
+            );
+
+            Ok(response.rate)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()
+        .map(Some)
+}
+
+/// Prices the given stableswap pool at the given rates, by weighing each of its assets by its rate
+/// on top of its weight. The stableswap math then works on the rate-scaled balances, as it does
+/// for the weights of composable stableswap pools. Pools of any other type, or without rates, are
+/// returned as they are.
+///
+/// The returned pool is only meant to be priced, it must not be saved.
+pub(crate) fn apply_rates(
+    pool_info: &PoolInfo,
+    rates: Option<&[Decimal]>,
+) -> Result<PoolInfo, ContractError> {
+    let mut pool_info = pool_info.clone();
+
+    let (Some(rates), PoolType::StableSwap { amp } | PoolType::ComposableStableSwap { amp, .. }) =
+        (rates, &pool_info.pool_type)
+    else {
+        return Ok(pool_info);
+    };
+
+    let weights = get_asset_weights(&pool_info)
+        .into_iter()
+        .zip(rates)
+        .map(|(weight, rate)| weight.checked_mul(*rate))
+        .collect::<Result<Vec<_>, _>>()?;
+    pool_info.pool_type = PoolType::ComposableStableSwap { amp: *amp, weights };
+
+    Ok(pool_info)
+}
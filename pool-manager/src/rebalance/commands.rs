@@ -0,0 +1,273 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, BankMsg, Decimal, DepsMut, Env, MessageInfo, Response, Storage,
+    SubMsg, Uint128,
+};
+
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::pool_manager::ExecuteMsg;
+
+use crate::contract::REBALANCE_REPLY_ID;
+use crate::dust::tracking::track_withdrawal_dust;
+use crate::helpers::{get_peg_deviation, get_withdrawal_assets, is_stableswap};
+use crate::state::{
+    get_lp_supply, get_pool_by_identifier, RebalanceBuffer, PROTOCOL_LIQUIDITY, REBALANCE_BUFFER,
+    REBALANCE_CONFIG,
+};
+use crate::ContractError;
+
+/// Updates the keepers allowed to rebalance the protocol-owned liquidity and the bounds they
+/// operate within. Only the owner can do this.
+pub fn update_rebalance_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    keepers: Option<Vec<String>>,
+    max_move_share: Option<Decimal>,
+    min_deviation_gap: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut config = REBALANCE_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+
+    if let Some(keepers) = keepers {
+        config.keepers = keepers
+            .iter()
+            .map(|keeper| deps.api.addr_validate(keeper))
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+
+    if let Some(max_move_share) = max_move_share {
+        ensure!(
+            !max_move_share.is_zero() && max_move_share <= Decimal::one(),
+            ContractError::InvalidRebalanceConfig
+        );
+        config.max_move_share = max_move_share;
+    }
+
+    if let Some(min_deviation_gap) = min_deviation_gap {
+        config.min_deviation_gap = min_deviation_gap;
+    }
+
+    REBALANCE_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_rebalance_config".to_string()),
+        (
+            "keepers",
+            config
+                .keepers
+                .iter()
+                .map(|keeper| keeper.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        ("max_move_share", config.max_move_share.to_string()),
+        ("min_deviation_gap", config.min_deviation_gap.to_string()),
+    ]))
+}
+
+/// Deposits the LP tokens sent as protocol-owned liquidity of the given pool. Only the owner can
+/// do this.
+pub fn deposit_protocol_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let amount = cw_utils::must_pay(&info, &pool.lp_denom)?;
+
+    PROTOCOL_LIQUIDITY.update(
+        deps.storage,
+        &pool_identifier,
+        |balance| -> Result<_, ContractError> {
+            Ok(balance.unwrap_or_default().checked_add(amount)?)
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "deposit_protocol_liquidity".to_string()),
+        ("pool_identifier", pool_identifier),
+        ("lp_amount", amount.to_string()),
+    ]))
+}
+
+/// Withdraws protocol-owned liquidity of the given pool, sending the LP tokens to the receiver.
+/// Only the owner can do this.
+pub fn withdraw_protocol_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    amount: Uint128,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    decrease_protocol_liquidity(deps.storage, &pool_identifier, amount)?;
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender);
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![coin(amount.u128(), pool.lp_denom)],
+        })
+        .add_attributes(vec![
+            ("action", "withdraw_protocol_liquidity".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("receiver", receiver.into_string()),
+            ("lp_amount", amount.to_string()),
+        ]))
+}
+
+/// Moves `lp_amount` of protocol-owned LP shares from one stable pool to another holding the same
+/// assets, by withdrawing the liquidity from the former and providing it to the latter. Only
+/// keepers can do this, when the peg deviation of the destination pool exceeds the one of the
+/// source pool by the configured gap, and up to the configured share of the protocol-owned
+/// liquidity in the source pool.
+pub fn rebalance_stable_pools(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_pool_identifier: String,
+    to_pool_identifier: String,
+    lp_amount: Uint128,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let config = REBALANCE_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    ensure!(
+        config.keepers.contains(&info.sender),
+        ContractError::Unauthorized
+    );
+
+    let from_pool = get_pool_by_identifier(&deps.as_ref(), &from_pool_identifier)?;
+    let to_pool = get_pool_by_identifier(&deps.as_ref(), &to_pool_identifier)?;
+
+    let mut from_denoms = from_pool.asset_denoms.clone();
+    let mut to_denoms = to_pool.asset_denoms.clone();
+    from_denoms.sort();
+    to_denoms.sort();
+    ensure!(
+        from_pool_identifier != to_pool_identifier
+            && is_stableswap(&from_pool.pool_type)
+            && is_stableswap(&to_pool.pool_type)
+            && from_denoms == to_denoms,
+        ContractError::InvalidRebalancePools
+    );
+
+    let from_deviation = get_peg_deviation(&from_pool)?;
+    let to_deviation = get_peg_deviation(&to_pool)?;
+    ensure!(
+        to_deviation >= from_deviation.checked_add(config.min_deviation_gap)?,
+        ContractError::RebalanceNotNeeded {
+            min_deviation_gap: config.min_deviation_gap,
+        }
+    );
+
+    let protocol_liquidity = PROTOCOL_LIQUIDITY
+        .may_load(deps.storage, &from_pool_identifier)?
+        .unwrap_or_default();
+    let max_amount = protocol_liquidity.checked_mul_floor(config.max_move_share)?;
+    ensure!(
+        !lp_amount.is_zero() && lp_amount <= max_amount,
+        ContractError::InvalidRebalanceAmount { max: max_amount }
+    );
+
+    decrease_protocol_liquidity(deps.storage, &from_pool_identifier, lp_amount)?;
+
+    let total_share = get_lp_supply(&deps.as_ref(), &from_pool)?;
+    let withdrawn_assets = get_withdrawal_assets(&from_pool, lp_amount, total_share)?;
+    track_withdrawal_dust(
+        deps.storage,
+        &from_pool,
+        lp_amount,
+        total_share,
+        &withdrawn_assets,
+    )?;
+
+    // the LP shares minted to the contract are accounted for once the provision is done
+    let lp_balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &to_pool.lp_denom)?
+        .amount;
+    REBALANCE_BUFFER.save(
+        deps.storage,
+        &RebalanceBuffer {
+            pool_identifier: to_pool_identifier.clone(),
+            lp_balance_before,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::WithdrawLiquidity {
+                pool_identifier: from_pool_identifier.clone(),
+                remote_receiver: None,
+                min_amounts_out: None,
+            },
+            vec![coin(lp_amount.u128(), from_pool.lp_denom)],
+        )?)
+        .add_submessage(SubMsg::reply_on_success(
+            wasm_execute(
+                env.contract.address.to_string(),
+                &ExecuteMsg::ProvideLiquidity {
+                    slippage_tolerance: None,
+                    max_spread: None,
+                    receiver: Some(env.contract.address.to_string()),
+                    pool_identifier: to_pool_identifier.clone(),
+                    unlocking_duration: None,
+                    lock_position_identifier: None,
+                    min_lp_to_receive: None,
+                    deposit_route: None,
+                },
+                withdrawn_assets.clone(),
+            )?,
+            REBALANCE_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "rebalance_stable_pools".to_string()),
+            ("keeper", info.sender.into_string()),
+            ("from_pool_identifier", from_pool_identifier),
+            ("to_pool_identifier", to_pool_identifier),
+            ("lp_amount", lp_amount.to_string()),
+            (
+                "assets",
+                withdrawn_assets
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ("from_peg_deviation", from_deviation.to_string()),
+            ("to_peg_deviation", to_deviation.to_string()),
+        ]))
+}
+
+fn decrease_protocol_liquidity(
+    storage: &mut dyn Storage,
+    pool_identifier: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let balance = PROTOCOL_LIQUIDITY
+        .may_load(storage, pool_identifier)?
+        .unwrap_or_default();
+
+    let remaining =
+        balance
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientProtocolLiquidity {
+                pool_identifier: pool_identifier.to_string(),
+            })?;
+
+    if remaining.is_zero() {
+        PROTOCOL_LIQUIDITY.remove(storage, pool_identifier);
+    } else {
+        PROTOCOL_LIQUIDITY.save(storage, pool_identifier, &remaining)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,291 @@
+use cosmwasm_std::{
+    ensure, to_json_vec, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, DepsMut, Env,
+    MessageInfo, Response, StdResult, Storage,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use mantra_dex_std::coin::burn_coin_msg;
+use mantra_dex_std::pool_manager::RfqQuote;
+
+use crate::permissioned::commands::assert_pool_access;
+use crate::state::{CONFIG, RFQ_INVENTORY, RFQ_MAKERS, RFQ_USED_NONCES};
+use crate::swap::perform_swap::perform_swap;
+use crate::ContractError;
+
+/// The length of a compressed secp256k1 public key.
+const COMPRESSED_PUBKEY_LENGTH: usize = 33;
+
+/// The payload signed by the makers. It binds the quote to this contract and chain so it can't
+/// be replayed elsewhere.
+#[derive(Serialize)]
+struct RfqSignDoc<'a> {
+    chain_id: &'a str,
+    contract: &'a Addr,
+    quote: &'a RfqQuote,
+}
+
+/// Computes the sha256 digest of the given quote, which makers sign with their secp256k1 key.
+pub(crate) fn quote_digest(env: &Env, quote: &RfqQuote) -> StdResult<Vec<u8>> {
+    let sign_doc = to_json_vec(&RfqSignDoc {
+        chain_id: &env.block.chain_id,
+        contract: &env.contract.address,
+        quote,
+    })?;
+
+    Ok(Sha256::digest(sign_doc).to_vec())
+}
+
+/// Registers the sender as an RFQ maker, or rotates its key, with the public key its quotes
+/// will be verified against.
+pub fn register_rfq_maker(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    ensure!(
+        pubkey.len() == COMPRESSED_PUBKEY_LENGTH,
+        ContractError::InvalidRfqMakerPubkey
+    );
+
+    RFQ_MAKERS.save(deps.storage, &info.sender, &pubkey)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "register_rfq_maker".to_string()),
+        ("maker", info.sender.into_string()),
+        ("pubkey", pubkey.to_base64()),
+    ]))
+}
+
+/// Deposits the funds sent into the inventory of the sender, used to settle its quotes.
+pub fn deposit_rfq_inventory(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure!(
+        RFQ_MAKERS.has(deps.storage, &info.sender),
+        ContractError::RfqMakerNotRegistered {
+            maker: info.sender.to_string(),
+        }
+    );
+    ensure!(!info.funds.is_empty(), ContractError::EmptyAssets);
+
+    for asset in &info.funds {
+        increase_inventory(deps.storage, &info.sender, asset)?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "deposit_rfq_inventory".to_string()),
+        ("maker", info.sender.into_string()),
+        (
+            "assets",
+            info.funds
+                .iter()
+                .map(|asset| asset.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
+
+/// Withdraws the given assets from the inventory of the sender.
+pub fn withdraw_rfq_inventory(
+    deps: DepsMut,
+    info: MessageInfo,
+    assets: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let assets: Vec<Coin> = assets
+        .into_iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .collect();
+    ensure!(!assets.is_empty(), ContractError::EmptyAssets);
+
+    for asset in &assets {
+        decrease_inventory(deps.storage, &info.sender, asset)?;
+    }
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: assets.clone(),
+        })
+        .add_attributes(vec![
+            ("action", "withdraw_rfq_inventory".to_string()),
+            ("maker", info.sender.into_string()),
+            (
+                "assets",
+                assets
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ]))
+}
+
+/// Fills a quote signed by a maker, swapping the offer asset sent by the taker for the ask asset
+/// of the quote out of the maker's inventory. If the quote expired, the trade falls back to the
+/// given pool at the AMM price instead.
+pub fn fill_rfq_quote(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quote: RfqQuote,
+    signature: Binary,
+    fallback_pool_identifier: Option<String>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the swap feature is enabled
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    ensure!(
+        quote.offer_asset.denom != quote.ask_asset.denom,
+        ContractError::SameAsset
+    );
+
+    let paid = cw_utils::must_pay(&info, &quote.offer_asset.denom)?;
+    ensure!(
+        paid == quote.offer_asset.amount,
+        ContractError::InvalidRfqPayment {
+            expected: quote.offer_asset.clone(),
+        }
+    );
+
+    if env.block.time.seconds() > quote.expiry {
+        let pool_identifier = fallback_pool_identifier.ok_or(ContractError::RfqQuoteExpired)?;
+        assert_pool_access(deps.storage, &env, &pool_identifier, &info.sender)?;
+
+        let swap_result = perform_swap(
+            deps.branch(),
+            &env,
+            &info.sender,
+            quote.offer_asset.clone(),
+            quote.ask_asset.denom.clone(),
+            pool_identifier.clone(),
+            None,
+            max_spread,
+            None,
+        )?;
+
+        let mut messages: Vec<CosmosMsg> = swap_result.recall_msgs;
+        if !swap_result.return_asset.amount.is_zero() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: vec![swap_result.return_asset.clone()],
+                }
+                .into(),
+            );
+        }
+        if !swap_result.burn_fee_asset.amount.is_zero() {
+            messages.push(burn_coin_msg(swap_result.burn_fee_asset));
+        }
+        if !swap_result.protocol_fee_asset.amount.is_zero() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: config.fee_collector_addr.to_string(),
+                    amount: vec![swap_result.protocol_fee_asset],
+                }
+                .into(),
+            );
+        }
+
+        return Ok(Response::default()
+            .add_messages(messages)
+            .add_submessages(swap_result.price_hook_msgs)
+            .add_attributes(vec![
+                ("action", "fill_rfq_quote".to_string()),
+                ("taker", info.sender.into_string()),
+                ("maker", quote.maker),
+                ("nonce", quote.nonce.to_string()),
+                ("fallback_pool_identifier", pool_identifier),
+                ("offer_asset", quote.offer_asset.to_string()),
+                ("return_asset", swap_result.return_asset.to_string()),
+            ]));
+    }
+
+    let maker = deps.api.addr_validate(&quote.maker)?;
+    let pubkey =
+        RFQ_MAKERS
+            .may_load(deps.storage, &maker)?
+            .ok_or(ContractError::RfqMakerNotRegistered {
+                maker: maker.to_string(),
+            })?;
+
+    let digest = quote_digest(&env, &quote)?;
+    let verified = deps
+        .api
+        .secp256k1_verify(&digest, &signature, &pubkey)
+        .map_err(|_| ContractError::InvalidRfqSignature)?;
+    ensure!(verified, ContractError::InvalidRfqSignature);
+
+    ensure!(
+        !RFQ_USED_NONCES.has(deps.storage, (&maker, quote.nonce)),
+        ContractError::RfqNonceUsed { nonce: quote.nonce }
+    );
+    RFQ_USED_NONCES.save(deps.storage, (&maker, quote.nonce), &true)?;
+
+    // the maker gives the ask asset out of its inventory and receives the offer asset in it
+    decrease_inventory(deps.storage, &maker, &quote.ask_asset)?;
+    increase_inventory(deps.storage, &maker, &quote.offer_asset)?;
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![quote.ask_asset.clone()],
+        })
+        .add_attributes(vec![
+            ("action", "fill_rfq_quote".to_string()),
+            ("taker", info.sender.into_string()),
+            ("maker", maker.into_string()),
+            ("nonce", quote.nonce.to_string()),
+            ("offer_asset", quote.offer_asset.to_string()),
+            ("return_asset", quote.ask_asset.to_string()),
+        ]))
+}
+
+fn increase_inventory(
+    storage: &mut dyn Storage,
+    maker: &Addr,
+    asset: &Coin,
+) -> Result<(), ContractError> {
+    RFQ_INVENTORY.update(
+        storage,
+        (maker, &asset.denom),
+        |balance| -> Result<_, ContractError> {
+            Ok(balance.unwrap_or_default().checked_add(asset.amount)?)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn decrease_inventory(
+    storage: &mut dyn Storage,
+    maker: &Addr,
+    asset: &Coin,
+) -> Result<(), ContractError> {
+    let balance = RFQ_INVENTORY
+        .may_load(storage, (maker, &asset.denom))?
+        .unwrap_or_default();
+
+    let remaining =
+        balance
+            .checked_sub(asset.amount)
+            .map_err(|_| ContractError::InsufficientRfqInventory {
+                denom: asset.denom.clone(),
+            })?;
+
+    if remaining.is_zero() {
+        RFQ_INVENTORY.remove(storage, (maker, &asset.denom));
+    } else {
+        RFQ_INVENTORY.save(storage, (maker, &asset.denom), &remaining)?;
+    }
+
+    Ok(())
+}
@@ -1,15 +1,25 @@
 use cosmwasm_std::{
-    attr, coin, ensure, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, MessageInfo, Response, Uint128,
+    attr, coin, ensure, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, SubMsg, Uint128,
 };
 
 use mantra_dex_std::coin::burn_coin_msg;
 use mantra_dex_std::common::validate_addr_or_default;
-use mantra_dex_std::pool_manager::SwapOperation;
+use mantra_dex_std::pool_manager::{HopLimit, Referral, SwapOperation, SwapRoute};
 
+use crate::gas;
+use crate::ibc_transfer::commands::get_ibc_forward_msg;
+use crate::permissioned::commands::assert_pool_access;
+use crate::queries::query_reverse_simulation;
+use crate::state::{get_pool_by_identifier, Config, SwapOperations, SWAP_ROUTES};
+use crate::swap::commands::{deduct_referral_fee, SwapDestination};
+use crate::swap_adapter::adapter::{
+    assert_swap_adapter, get_swap_through_adapter_msg, simulate_adapter_swap,
+};
 use crate::{state::CONFIG, swap::perform_swap::perform_swap, ContractError};
 
 /// Checks that the output of each [`SwapOperation`] acts as the input of the next swap.
-fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError> {
+pub(crate) fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError> {
     // check that the output of each swap is the input of the next swap
     let mut previous_output_info = operations
         .first()
@@ -31,54 +41,144 @@ fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError
     Ok(())
 }
 
-pub fn execute_swap_operations(
-    mut deps: DepsMut,
-    info: MessageInfo,
-    operations: Vec<SwapOperation>,
-    minimum_receive: Option<Uint128>,
-    receiver: Option<String>,
-    max_spread: Option<Decimal>,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    // check if the swap feature is enabled
+/// Checks that the given operations don't go through swap adapters, for the modes pricing the route
+/// from the pools of the contract, e.g. exact-out swaps and TWAPs. `operation` names the mode in
+/// the error.
+pub(crate) fn assert_no_swap_adapters(
+    operations: &[SwapOperation],
+    operation: &str,
+) -> Result<(), ContractError> {
     ensure!(
-        config.feature_toggle.swaps_enabled,
-        ContractError::OperationDisabled("swap".to_string())
+        operations
+            .iter()
+            .all(|operation| matches!(operation, SwapOperation::MantraSwap { .. })),
+        ContractError::UnsupportedForSwapAdapter {
+            operation: operation.to_string(),
+        }
     );
 
-    // ensure that there was at least one operation
-    // and retrieve the output token info
-    let target_asset_denom = operations
-        .last()
-        .ok_or(ContractError::NoSwapOperationsProvided)?
-        .get_target_asset_info();
+    Ok(())
+}
 
-    let offer_asset_denom = operations
-        .first()
-        .ok_or(ContractError::NoSwapOperationsProvided)?
-        .get_input_asset_info();
+/// Checks that every pool in the given operations exists and holds both assets of its hop, and
+/// that every swap adapter is whitelisted.
+pub(crate) fn assert_operations_pools(
+    deps: Deps,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
+    for operation in operations {
+        match operation {
+            SwapOperation::MantraSwap {
+                token_in_denom,
+                token_out_denom,
+                pool_identifier,
+            } => {
+                let pool = get_pool_by_identifier(&deps, pool_identifier)?;
+                ensure!(
+                    [token_in_denom, token_out_denom]
+                        .iter()
+                        .all(|denom| pool.assets.iter().any(|asset| &asset.denom == *denom)),
+                    ContractError::AssetMismatch
+                );
+            }
+            SwapOperation::ExternalAdapter { adapter_addr, .. } => {
+                assert_swap_adapter(deps.storage, &deps.api.addr_validate(adapter_addr)?)?;
+            }
+        }
+    }
 
-    let offer_asset = Coin {
-        denom: offer_asset_denom.to_string(),
-        amount: cw_utils::must_pay(&info, offer_asset_denom)?,
-    };
+    Ok(())
+}
 
-    assert_operations(operations.clone())?;
+/// Computes the amount of the input asset needed to get `ask_amount` out of the given route, by
+/// reverse simulating each hop from the last one. Each hop is rounded up by one unit so the
+/// forward execution doesn't fall short due to rounding.
+fn get_exact_out_offer_amount(
+    deps: Deps,
+    env: &Env,
+    ask_amount: Uint128,
+    operations: &[SwapOperation],
+) -> Result<Uint128, ContractError> {
+    let mut offer_in_needed = ask_amount;
 
-    // we return the output to the sender if no alternative recipient was specified.
-    let receiver =
-        validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone()).to_string();
+    for operation in operations.iter().rev() {
+        match operation {
+            SwapOperation::MantraSwap {
+                token_in_denom,
+                token_out_denom,
+                pool_identifier,
+            } => {
+                let res = query_reverse_simulation(
+                    deps,
+                    env,
+                    coin(offer_in_needed.u128(), token_out_denom),
+                    token_in_denom.to_string(),
+                    pool_identifier.to_string(),
+                )?;
+
+                offer_in_needed = res.offer_amount.checked_add(Uint128::one())?;
+            }
+            SwapOperation::ExternalAdapter { .. } => {
+                return Err(ContractError::UnsupportedForSwapAdapter {
+                    operation: "exact_out".to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(offer_in_needed)
+}
 
-    // perform each swap operation
+/// The result of swapping an asset through a route of swap operations.
+pub(crate) struct SwapOperationsResult {
+    /// The output of the last swap of the route.
+    pub return_asset: Coin,
+    /// The messages recalling the deployed reserves the swaps pay out, to send before anything.
+    pub recall_msgs: Vec<CosmosMsg>,
+    /// The messages swapping through external AMMs, to send right after the recalls, before the
+    /// output of the route is spent. Each refunds whatever its AMM returns above the quote to the
+    /// surplus receiver of the route.
+    pub adapter_msgs: Vec<CosmosMsg>,
+    /// The messages burning and sending the fees of the swaps.
+    pub fee_msgs: Vec<CosmosMsg>,
+    /// The callbacks of the price subscriptions triggered by the swaps.
+    pub price_hook_msgs: Vec<SubMsg>,
+    /// The attributes describing each swap of the route.
+    pub swap_attributes: Vec<(&'static str, String)>,
+}
+
+/// Swaps `offer_asset` through the given swap operations on behalf of `trader`, each swap taking
+/// the output of the previous one. The operations are expected to be checked with
+/// [assert_operations] already. The output of the route is held by the contract, for the caller
+/// to send on.
+///
+/// Hops through external AMMs are quoted by their swap adapter, and executed by a message asking
+/// the adapter for at least the quoted amount, which fails the whole transaction if the adapter
+/// falls short. The route continues with the quoted amount, and the contract measures what the
+/// adapter actually returns once the message runs, refunding anything above the quote to
+/// `surplus_receiver` instead of keeping it untracked.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn perform_swap_operations(
+    mut deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    trader: &Addr,
+    surplus_receiver: &Addr,
+    offer_asset: Coin,
+    operations: Vec<SwapOperation>,
+    max_spread: Option<Decimal>,
+    hop_limits: Option<&[HopLimit]>,
+) -> Result<SwapOperationsResult, ContractError> {
     // we start off with the initial funds
-    let mut previous_swap_output = offer_asset.clone();
+    let mut previous_swap_output = offer_asset;
 
-    // stores messages for sending fees after the swaps
-    let mut fee_messages = vec![];
-    // stores swap attributes to add to tx info
+    let mut recall_msgs = vec![];
+    let mut adapter_msgs = vec![];
+    let mut fee_msgs = vec![];
+    let mut price_hook_msgs = vec![];
     let mut swap_attributes = vec![];
 
-    for operation in operations {
+    for (hop, operation) in operations.into_iter().enumerate() {
         match operation {
             SwapOperation::MantraSwap {
                 token_out_denom,
@@ -88,13 +188,22 @@ pub fn execute_swap_operations(
                 // inside assert_operations() we have already checked that
                 // the output of each swap is the input of the next swap.
 
+                assert_pool_access(deps.storage, env, &pool_identifier, trader)?;
+                gas::checkpoint("route_hop", 1);
+
+                let hop_limit = hop_limits.map(|hop_limits| &hop_limits[hop]);
                 let swap_result = perform_swap(
                     deps.branch(),
+                    env,
+                    trader,
                     previous_swap_output.clone(),
                     token_out_denom,
                     pool_identifier,
                     None,
-                    max_spread,
+                    hop_limit
+                        .and_then(|hop_limit| hop_limit.max_spread)
+                        .or(max_spread),
+                    hop_limit.and_then(|hop_limit| hop_limit.minimum_receive),
                 )?;
                 swap_attributes.push((
                     "swap",
@@ -110,13 +219,15 @@ pub fn execute_swap_operations(
 
                 // update the previous swap output
                 previous_swap_output = swap_result.return_asset;
+                price_hook_msgs.extend(swap_result.price_hook_msgs);
+                recall_msgs.extend(swap_result.recall_msgs);
 
                 // add the fee messages
                 if !swap_result.burn_fee_asset.amount.is_zero() {
-                    fee_messages.push(burn_coin_msg(swap_result.burn_fee_asset));
+                    fee_msgs.push(burn_coin_msg(swap_result.burn_fee_asset));
                 }
                 if !swap_result.protocol_fee_asset.amount.is_zero() {
-                    fee_messages.push(
+                    fee_msgs.push(
                         BankMsg::Send {
                             to_address: config.fee_collector_addr.to_string(),
                             amount: vec![swap_result.protocol_fee_asset.clone()],
@@ -125,11 +236,249 @@ pub fn execute_swap_operations(
                     );
                 }
             }
+            SwapOperation::ExternalAdapter {
+                token_out_denom,
+                adapter_addr,
+                msg,
+                ..
+            } => {
+                gas::checkpoint("route_hop", 1);
+
+                let return_amount = simulate_adapter_swap(
+                    deps.as_ref(),
+                    &adapter_addr,
+                    previous_swap_output.clone(),
+                    token_out_denom.clone(),
+                    msg.clone(),
+                )?;
+
+                // the max spread of the route only applies to the pools of the contract, the
+                // slippage of external AMMs is bounded by the minimum to receive of the hop
+                if let Some(minimum_receive) =
+                    hop_limits.and_then(|hop_limits| hop_limits[hop].minimum_receive)
+                {
+                    ensure!(
+                        return_amount >= minimum_receive,
+                        ContractError::MinimumReceiveAssertion {
+                            minimum_receive,
+                            swap_amount: return_amount,
+                        }
+                    );
+                }
+
+                adapter_msgs.push(get_swap_through_adapter_msg(
+                    env,
+                    &adapter_addr,
+                    previous_swap_output.clone(),
+                    token_out_denom.clone(),
+                    return_amount,
+                    msg,
+                    surplus_receiver,
+                )?);
+
+                let return_asset = coin(return_amount.u128(), token_out_denom);
+                swap_attributes.push((
+                    "swap",
+                    format!(
+                        "in={}, out={}, adapter={}",
+                        previous_swap_output, return_asset, adapter_addr
+                    ),
+                ));
+
+                previous_swap_output = return_asset;
+            }
         }
     }
 
-    // Execute minimum amount assertion
-    let receiver_balance = previous_swap_output.amount;
+    Ok(SwapOperationsResult {
+        return_asset: previous_swap_output,
+        recall_msgs,
+        adapter_msgs,
+        fee_msgs,
+        price_hook_msgs,
+        swap_attributes,
+    })
+}
+
+/// Executes the given swap operations. If an `ask_amount` is provided, the swap is performed in
+/// exact-out mode: the funds sent are the maximum offer amount, only the amount needed to get
+/// `ask_amount` out of the route is swapped and the rest is refunded to the sender. The fee of the
+/// given referral is deducted from the output of the route before the minimum amount assertions.
+///
+/// The `hop_limits`, if given, are checked against each operation in order, so a thin pool along
+/// the route can't eat its value before the final minimum amount assertions. The max spread of a
+/// hop overrides the one of the route.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_swap_operations(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    receiver: Option<String>,
+    max_spread: Option<Decimal>,
+    ask_amount: Option<Uint128>,
+    referral: Option<Referral>,
+    hop_limits: Option<Vec<HopLimit>>,
+) -> Result<Response, ContractError> {
+    execute_swap_operations_to(
+        deps,
+        env,
+        info,
+        operations,
+        minimum_receive,
+        SwapDestination::Local(receiver),
+        max_spread,
+        ask_amount,
+        referral,
+        hop_limits,
+    )
+}
+
+/// Executes the given swap operations as [execute_swap_operations] does, sending the output of
+/// the route to the given destination.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn execute_swap_operations_to(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    destination: SwapDestination,
+    max_spread: Option<Decimal>,
+    ask_amount: Option<Uint128>,
+    referral: Option<Referral>,
+    hop_limits: Option<Vec<HopLimit>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the swap feature is enabled
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    // ensure that there was at least one operation
+    // and retrieve the output token info
+    let target_asset_denom = operations
+        .last()
+        .ok_or(ContractError::NoSwapOperationsProvided)?
+        .get_target_asset_info();
+
+    let offer_asset_denom = operations
+        .first()
+        .ok_or(ContractError::NoSwapOperationsProvided)?
+        .get_input_asset_info();
+
+    let offer_asset = Coin {
+        denom: offer_asset_denom.to_string(),
+        amount: cw_utils::must_pay(&info, offer_asset_denom)?,
+    };
+
+    assert_operations(operations.clone())?;
+    // exact-out swaps reverse simulate the route, which swap adapters can't do
+    if ask_amount.is_some() {
+        assert_no_swap_adapters(&operations, "exact_out")?;
+    }
+
+    if let Some(hop_limits) = &hop_limits {
+        ensure!(
+            hop_limits.len() == operations.len(),
+            ContractError::InvalidHopLimits {
+                operations: operations.len(),
+            }
+        );
+    }
+
+    // we return the output to the sender if no alternative recipient was specified.
+    let receiver = match &destination {
+        SwapDestination::Local(receiver) => {
+            validate_addr_or_default(&deps.as_ref(), receiver.clone(), info.sender.clone())
+                .to_string()
+        }
+        SwapDestination::Remote { to_address, .. } => to_address.clone(),
+    };
+
+    // the contract swaps on behalf of the receiver, e.g. when executing orders and schedules
+    let trader = if info.sender == env.contract.address {
+        deps.api.addr_validate(&receiver)?
+    } else {
+        info.sender.clone()
+    };
+
+    // the surplus of the external hops is refunded on this chain, to the refund address of the
+    // transfer if the output leaves it
+    let surplus_receiver = match &destination {
+        SwapDestination::Local(_) => deps.api.addr_validate(&receiver)?,
+        SwapDestination::Remote { refund_address, .. } => refund_address.clone(),
+    };
+
+    // in exact-out mode only the amount needed is swapped, the rest is refunded
+    let mut refund_msg: Vec<CosmosMsg> = vec![];
+    let offer_asset = if let Some(ask_amount) = ask_amount {
+        let offer_amount =
+            get_exact_out_offer_amount(deps.as_ref(), &env, ask_amount, &operations)?;
+
+        ensure!(
+            offer_amount <= offer_asset.amount,
+            ContractError::MaxOfferAmountExceeded {
+                offer_amount,
+                max_offer_amount: offer_asset.amount,
+            }
+        );
+
+        let refund_amount = offer_asset.amount.checked_sub(offer_amount)?;
+        if !refund_amount.is_zero() {
+            refund_msg.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(refund_amount.u128(), offer_asset.denom.clone())],
+            }));
+        }
+
+        coin(offer_amount.u128(), offer_asset.denom)
+    } else {
+        offer_asset
+    };
+
+    let SwapOperationsResult {
+        return_asset: previous_swap_output,
+        recall_msgs: recall_messages,
+        adapter_msgs: adapter_messages,
+        fee_msgs: fee_messages,
+        price_hook_msgs,
+        swap_attributes,
+    } = perform_swap_operations(
+        deps.branch(),
+        &env,
+        &config,
+        &trader,
+        &surplus_receiver,
+        offer_asset.clone(),
+        operations,
+        max_spread,
+        hop_limits.as_deref(),
+    )?;
+
+    // split the referrer's share off the output
+    let mut receiver_asset = previous_swap_output.clone();
+    let (referral_msgs, referral_attributes) = deduct_referral_fee(
+        deps.as_ref(),
+        &config,
+        referral,
+        &previous_swap_output,
+        &mut receiver_asset,
+    )?;
+
+    // Execute minimum amount assertion. In exact-out mode, the ask amount is the minimum to receive
+    let receiver_balance = receiver_asset.amount;
+    if let Some(ask_amount) = ask_amount {
+        ensure!(
+            receiver_balance >= ask_amount,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive: ask_amount,
+                swap_amount: receiver_balance,
+            }
+        );
+    }
     if let Some(minimum_receive) = minimum_receive {
         if receiver_balance < minimum_receive {
             return Err(ContractError::MinimumReceiveAssertion {
@@ -141,16 +490,37 @@ pub fn execute_swap_operations(
 
     let mut bank_msg: Vec<CosmosMsg> = vec![];
     if !receiver_balance.is_zero() {
-        bank_msg.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: receiver.clone(),
-            amount: vec![coin(receiver_balance.u128(), target_asset_denom.clone())],
-        }));
+        let receiver_asset = coin(receiver_balance.u128(), target_asset_denom.clone());
+        match destination {
+            SwapDestination::Local(_) => bank_msg.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: receiver.clone(),
+                amount: vec![receiver_asset],
+            })),
+            SwapDestination::Remote {
+                channel_id,
+                to_address,
+                refund_address,
+                forward,
+            } => bank_msg.push(get_ibc_forward_msg(
+                &env,
+                channel_id,
+                receiver_asset,
+                &to_address,
+                &refund_address,
+                forward,
+            )?),
+        }
     }
 
     // send output to recipient
     Ok(Response::new()
+        .add_messages(recall_messages)
+        .add_messages(adapter_messages)
         .add_messages(bank_msg)
+        .add_messages(referral_msgs)
+        .add_messages(refund_msg)
         .add_messages(fee_messages)
+        .add_submessages(price_hook_msgs)
         .add_attributes(vec![
             attr("action", "execute_swap_operations".to_string()),
             attr("sender", info.sender.to_string()),
@@ -160,5 +530,141 @@ pub fn execute_swap_operations(
             attr("return_denom", target_asset_denom),
             attr("return_amount", receiver_balance.to_string()),
         ])
+        .add_attributes(referral_attributes)
         .add_attributes(swap_attributes))
 }
+
+/// Swaps the funds sent for `ask_asset_denom` through the swap route registered between the two
+/// denoms, as [execute_swap_operations] does with the operations of the route.
+pub fn swap_via_registry(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset_denom: String,
+    minimum_receive: Option<Uint128>,
+    receiver: Option<String>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let offer_asset = cw_utils::one_coin(&info)?;
+
+    let route = SWAP_ROUTES
+        .may_load(deps.storage, (&offer_asset.denom, &ask_asset_denom))?
+        .ok_or(ContractError::NoSwapRouteForAssets {
+            offer_asset: offer_asset.denom,
+            ask_asset: ask_asset_denom,
+        })?;
+
+    execute_swap_operations(
+        deps,
+        env,
+        info,
+        route.swap_operations,
+        minimum_receive,
+        receiver,
+        max_spread,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Checks that the operations of the given [SwapRoute] are chained, and go from its offer asset
+/// to its ask asset.
+pub fn assert_swap_route(swap_route: &SwapRoute) -> Result<(), ContractError> {
+    assert_operations(swap_route.swap_operations.clone())?;
+
+    ensure!(
+        swap_route
+            .swap_operations
+            .first()
+            .map(|op| op.get_input_asset_info())
+            == Some(&swap_route.offer_asset_denom)
+            && swap_route
+                .swap_operations
+                .last()
+                .map(|op| op.get_target_asset_info())
+                == Some(swap_route.ask_asset_denom.clone()),
+        ContractError::InvalidSwapRoute {
+            offer_asset: swap_route.offer_asset_denom.clone(),
+            ask_asset: swap_route.ask_asset_denom.clone(),
+        }
+    );
+
+    Ok(())
+}
+
+/// Registers the given swap routes, used to convert between denoms, e.g. when valuing LP
+/// positions. Only the owner can do this. Existing routes for the same pair are replaced.
+pub fn add_swap_routes(
+    deps: DepsMut,
+    info: MessageInfo,
+    swap_routes: Vec<SwapRoute>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![attr("action", "add_swap_routes")];
+
+    for swap_route in swap_routes {
+        assert_swap_route(&swap_route)?;
+        assert_operations_pools(deps.as_ref(), &swap_route.swap_operations)?;
+
+        SWAP_ROUTES.save(
+            deps.storage,
+            (&swap_route.offer_asset_denom, &swap_route.ask_asset_denom),
+            &SwapOperations {
+                creator: info.sender.to_string(),
+                swap_operations: swap_route.swap_operations.clone(),
+            },
+        )?;
+
+        attributes.push(attr(
+            "swap_route",
+            format!(
+                "{}-{}",
+                swap_route.offer_asset_denom, swap_route.ask_asset_denom
+            ),
+        ));
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
+
+/// Removes the registered swap routes for the given pairs. Only the owner can do this.
+pub fn remove_swap_routes(
+    deps: DepsMut,
+    info: MessageInfo,
+    swap_routes: Vec<SwapRoute>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![attr("action", "remove_swap_routes")];
+
+    for swap_route in swap_routes {
+        let key = (
+            swap_route.offer_asset_denom.as_str(),
+            swap_route.ask_asset_denom.as_str(),
+        );
+
+        ensure!(
+            SWAP_ROUTES.has(deps.storage, key),
+            ContractError::NoSwapRouteForAssets {
+                offer_asset: swap_route.offer_asset_denom.clone(),
+                ask_asset: swap_route.ask_asset_denom.clone(),
+            }
+        );
+
+        SWAP_ROUTES.remove(deps.storage, key);
+
+        attributes.push(attr(
+            "swap_route",
+            format!(
+                "{}-{}",
+                swap_route.offer_asset_denom, swap_route.ask_asset_denom
+            ),
+        ));
+    }
+
+    Ok(Response::default().add_attributes(attributes))
+}
@@ -0,0 +1,129 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, Uint128,
+};
+
+use crate::migration::commands::get_migration_rewards;
+use crate::state::{
+    CONFIG, DCA_SCHEDULES, LIQUIDITY_STREAMS, MIGRATED_LIQUIDITY, MIGRATION_CAMPAIGNS, POOLS,
+    RFQ_INVENTORY, STOP_LOSS_ORDERS,
+};
+use crate::yield_strategy::commands::get_total_deployed;
+use crate::ContractError;
+
+/// Computes the amount of `denom` the contract accounts for: the reserves of the pools but the
+/// ones deployed into yield strategies, the RFQ inventories, the funds escrowed by stop-loss
+/// orders, DCA schedules and liquidity streams, and the migration rewards not claimed yet. The buffers of the multi-step flows, i.e. single side
+/// provisions, rebalances, LP wrapping and unlock and withdraw, only hold funds between a message
+/// and its reply, so they're always settled by the time this is called.
+pub(crate) fn get_tracked_balance(deps: Deps, denom: &str) -> Result<Uint128, ContractError> {
+    let mut tracked = Uint128::zero();
+
+    for pool in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, pool) = pool?;
+        for asset in pool.assets.iter().filter(|asset| asset.denom == denom) {
+            tracked = tracked.checked_add(asset.amount)?;
+        }
+    }
+
+    for inventory in RFQ_INVENTORY.range(deps.storage, None, None, Order::Ascending) {
+        let ((_, inventory_denom), amount) = inventory?;
+        if inventory_denom == denom {
+            tracked = tracked.checked_add(amount)?;
+        }
+    }
+
+    for order in STOP_LOSS_ORDERS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, order) = order?;
+        for asset in [order.offer_asset, order.bounty] {
+            if asset.denom == denom {
+                tracked = tracked.checked_add(asset.amount)?;
+            }
+        }
+    }
+
+    for schedule in DCA_SCHEDULES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, schedule) = schedule?;
+        if schedule.budget.denom == denom {
+            tracked = tracked.checked_add(schedule.budget.amount)?;
+        }
+    }
+
+    for stream in LIQUIDITY_STREAMS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, stream) = stream?;
+        for asset in stream.remaining_assets.iter().filter(|a| a.denom == denom) {
+            tracked = tracked.checked_add(asset.amount)?;
+        }
+    }
+
+    for campaign in MIGRATION_CAMPAIGNS.range(deps.storage, None, None, Order::Ascending) {
+        let (from_pool_identifier, campaign) = campaign?;
+
+        // the rewards are refundable in full until someone migrates, then owed to the migrators
+        let owed_rewards = if campaign.total_migrated.is_zero() {
+            campaign.rewards.clone()
+        } else {
+            let mut owed_rewards = vec![];
+            for migrated in MIGRATED_LIQUIDITY.prefix(&from_pool_identifier).range(
+                deps.storage,
+                None,
+                None,
+                Order::Ascending,
+            ) {
+                let (_, migrated) = migrated?;
+                owed_rewards.extend(get_migration_rewards(&campaign, migrated)?);
+            }
+            owed_rewards
+        };
+
+        for reward in owed_rewards.iter().filter(|reward| reward.denom == denom) {
+            tracked = tracked.checked_add(reward.amount)?;
+        }
+    }
+
+    // the deployed reserves are held by the yield strategies rather than by the contract
+    Ok(tracked.saturating_sub(get_total_deployed(deps.storage, denom)?))
+}
+
+/// Sends the balance of `denom` the contract doesn't account for, e.g. funds sent to it by
+/// mistake, to the fee collector. LP tokens can't be skimmed, as the ones held by the contract
+/// back the locked minimum liquidity, the protocol-owned liquidity and the LP wrappers. Can be
+/// called by anyone.
+pub fn skim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    ensure!(
+        POOLS
+            .idx
+            .lp_asset
+            .item(deps.storage, denom.clone())?
+            .is_none(),
+        ContractError::CannotSkimLpDenom { denom }
+    );
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+    let excess = balance.saturating_sub(get_tracked_balance(deps.as_ref(), &denom)?);
+    ensure!(!excess.is_zero(), ContractError::NothingToSkim { denom });
+
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: config.fee_collector_addr.to_string(),
+            amount: vec![coin(excess.u128(), &denom)],
+        })
+        .add_attributes(vec![
+            ("action", "skim".to_string()),
+            ("sender", info.sender.into_string()),
+            ("denom", denom),
+            ("balance", balance.to_string()),
+            ("excess", excess.to_string()),
+        ]))
+}
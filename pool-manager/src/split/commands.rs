@@ -0,0 +1,338 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
+
+use mantra_dex_std::coin::burn_coin_msg;
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::pool_manager::{PoolInfo, SimulationResponse, SplitSwapAllocation};
+
+use crate::pegged::get_pegged_rate;
+use crate::permissioned::commands::assert_pool_access;
+use crate::queries::simulate_swap;
+use crate::rates::{apply_rates, get_rates};
+use crate::state::{get_pool_by_identifier, CONFIG, IMBALANCE_FEES};
+use crate::swap::perform_swap::perform_swap;
+use crate::ContractError;
+
+/// The maximum number of pools a trade can be split across.
+pub const MAX_SPLIT_POOLS: usize = 5;
+/// The number of chunks the offer amount is divided into when computing the split.
+const SPLIT_CHUNKS: u128 = 20;
+
+/// Computes how to split `offer_asset` across the given pools, which must all hold the offer and
+/// ask denoms. If `weights` are given, the offer is split in proportion to them, one per pool.
+/// Otherwise, the offer is divided into chunks, and each chunk is allocated to the pool that
+/// yields the highest marginal return given the chunks allocated so far, which minimizes the
+/// total price impact of the trade.
+pub(crate) fn compute_split(
+    deps: Deps,
+    env: &Env,
+    offer_asset: &Coin,
+    ask_asset_denom: &str,
+    pool_identifiers: &[String],
+    weights: Option<&[u64]>,
+) -> Result<Vec<SplitSwapAllocation>, ContractError> {
+    ensure!(
+        !pool_identifiers.is_empty() && pool_identifiers.len() <= MAX_SPLIT_POOLS,
+        ContractError::InvalidSplitPools {
+            max: MAX_SPLIT_POOLS
+        }
+    );
+    ensure!(
+        offer_asset.denom != ask_asset_denom,
+        ContractError::SameAsset
+    );
+
+    let mut pools: Vec<PoolInfo> = vec![];
+    for pool_identifier in pool_identifiers {
+        ensure!(
+            !pools
+                .iter()
+                .any(|pool| &pool.pool_identifier == pool_identifier),
+            ContractError::InvalidSplitPools {
+                max: MAX_SPLIT_POOLS
+            }
+        );
+
+        let pool = get_pool_by_identifier(&deps, pool_identifier)?;
+        ensure!(
+            [&offer_asset.denom, ask_asset_denom]
+                .iter()
+                .all(|denom| pool.asset_denoms.iter().any(|d| d == *denom)),
+            ContractError::AssetMismatch
+        );
+
+        pools.push(pool);
+    }
+
+    if let Some(weights) = weights {
+        return split_by_weights(deps, env, &pools, offer_asset, ask_asset_denom, weights);
+    }
+
+    let mut allocations: Vec<SplitSwapAllocation> = pools
+        .iter()
+        .map(|pool| SplitSwapAllocation {
+            pool_identifier: pool.pool_identifier.clone(),
+            offer_amount: Uint128::zero(),
+            return_amount: Uint128::zero(),
+        })
+        .collect();
+
+    for chunk in 0..SPLIT_CHUNKS {
+        // spreads the rounding remainder across the chunks so they add up to the offer amount
+        let chunk_amount = offer_asset
+            .amount
+            .multiply_ratio(chunk + 1, SPLIT_CHUNKS)
+            .checked_sub(offer_asset.amount.multiply_ratio(chunk, SPLIT_CHUNKS))?;
+
+        if chunk_amount.is_zero() {
+            continue;
+        }
+
+        // pick the pool with the best marginal return for this chunk
+        let mut best: Option<(usize, Uint128)> = None;
+        let mut last_error = None;
+        for (i, pool) in pools.iter().enumerate() {
+            let offer_amount = allocations[i].offer_amount.checked_add(chunk_amount)?;
+
+            match simulate_pool_swap(
+                deps,
+                env,
+                pool,
+                coin(offer_amount.u128(), &offer_asset.denom),
+                ask_asset_denom,
+            ) {
+                Ok(simulation) => {
+                    let marginal_return = simulation
+                        .return_amount
+                        .saturating_sub(allocations[i].return_amount);
+
+                    if best.map_or(true, |(_, best_return)| marginal_return > best_return) {
+                        best = Some((i, marginal_return));
+                    }
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        let (i, marginal_return) = match (best, last_error) {
+            (Some(best), _) => best,
+            (None, Some(err)) => return Err(err),
+            (None, None) => unreachable!("there is at least one pool to split the trade across"),
+        };
+
+        allocations[i].offer_amount = allocations[i].offer_amount.checked_add(chunk_amount)?;
+        allocations[i].return_amount = allocations[i].return_amount.checked_add(marginal_return)?;
+    }
+
+    Ok(allocations)
+}
+
+/// Splits `offer_asset` across the given pools in proportion to the given weights, one per pool.
+/// Pools weighing zero are left out of the trade.
+fn split_by_weights(
+    deps: Deps,
+    env: &Env,
+    pools: &[PoolInfo],
+    offer_asset: &Coin,
+    ask_asset_denom: &str,
+    weights: &[u64],
+) -> Result<Vec<SplitSwapAllocation>, ContractError> {
+    let total_weight: u128 = weights.iter().map(|weight| u128::from(*weight)).sum();
+    ensure!(
+        weights.len() == pools.len() && total_weight > 0,
+        ContractError::InvalidSplitWeights
+    );
+
+    let mut cumulative_weight = 0u128;
+    pools
+        .iter()
+        .zip(weights)
+        .map(
+            |(pool, weight)| -> Result<SplitSwapAllocation, ContractError> {
+                // allocates the cumulative share so the rounding remainders add up to the offer amount
+                let allocated_before = offer_asset
+                    .amount
+                    .multiply_ratio(cumulative_weight, total_weight);
+                cumulative_weight += u128::from(*weight);
+                let offer_amount = offer_asset
+                    .amount
+                    .multiply_ratio(cumulative_weight, total_weight)
+                    .checked_sub(allocated_before)?;
+
+                let return_amount = if offer_amount.is_zero() {
+                    Uint128::zero()
+                } else {
+                    simulate_pool_swap(
+                        deps,
+                        env,
+                        pool,
+                        coin(offer_amount.u128(), &offer_asset.denom),
+                        ask_asset_denom,
+                    )?
+                    .return_amount
+                };
+
+                Ok(SplitSwapAllocation {
+                    pool_identifier: pool.pool_identifier.clone(),
+                    offer_amount,
+                    return_amount,
+                })
+            },
+        )
+        .collect()
+}
+
+/// Simulates swapping `offer_asset` for `ask_asset_denom` against the given pool.
+fn simulate_pool_swap(
+    deps: Deps,
+    env: &Env,
+    pool: &PoolInfo,
+    offer_asset: Coin,
+    ask_asset_denom: &str,
+) -> Result<SimulationResponse, ContractError> {
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool.pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps, env, pool)?;
+
+    simulate_swap(
+        deps.storage,
+        apply_rates(pool, get_rates(deps, pool)?.as_deref())?,
+        offer_asset,
+        ask_asset_denom.to_string(),
+        imbalance_fee.as_ref(),
+        pegged_rate,
+    )
+}
+
+/// Swaps the funds sent into `ask_asset_denom`, splitting the trade across the given pools for
+/// the same pair as computed by [compute_split], by the given weights or automatically. The
+/// minimum to receive applies to the combined return of the pools.
+#[allow(clippy::too_many_arguments)]
+pub fn split_swap(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ask_asset_denom: String,
+    pool_identifiers: Vec<String>,
+    weights: Option<Vec<u64>>,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // check if the swap feature is enabled
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    let offer_asset = cw_utils::one_coin(&info)?;
+
+    let allocations = compute_split(
+        deps.as_ref(),
+        &env,
+        &offer_asset,
+        &ask_asset_denom,
+        &pool_identifiers,
+        weights.as_deref(),
+    )?;
+
+    let mut return_amount = Uint128::zero();
+    let mut fee_messages: Vec<CosmosMsg> = vec![];
+    let mut price_hook_msgs = vec![];
+    let mut recall_messages = vec![];
+    let mut swap_attributes = vec![];
+
+    for allocation in allocations {
+        if allocation.offer_amount.is_zero() {
+            continue;
+        }
+
+        assert_pool_access(
+            deps.storage,
+            &env,
+            &allocation.pool_identifier,
+            &info.sender,
+        )?;
+
+        let offer = coin(allocation.offer_amount.u128(), &offer_asset.denom);
+        let swap_result = perform_swap(
+            deps.branch(),
+            &env,
+            &info.sender,
+            offer.clone(),
+            ask_asset_denom.clone(),
+            allocation.pool_identifier.clone(),
+            None,
+            max_spread,
+            None,
+        )?;
+
+        swap_attributes.push((
+            "swap",
+            format!(
+                "pool={}, in={}, out={}, burn_fee={}, protocol_fee={}, swap_fee={}",
+                allocation.pool_identifier,
+                offer,
+                swap_result.return_asset,
+                swap_result.burn_fee_asset,
+                swap_result.protocol_fee_asset,
+                swap_result.swap_fee_asset
+            ),
+        ));
+
+        return_amount = return_amount.checked_add(swap_result.return_asset.amount)?;
+        price_hook_msgs.extend(swap_result.price_hook_msgs);
+        recall_messages.extend(swap_result.recall_msgs);
+
+        if !swap_result.burn_fee_asset.amount.is_zero() {
+            fee_messages.push(burn_coin_msg(swap_result.burn_fee_asset));
+        }
+        if !swap_result.protocol_fee_asset.amount.is_zero() {
+            fee_messages.push(
+                BankMsg::Send {
+                    to_address: config.fee_collector_addr.to_string(),
+                    amount: vec![swap_result.protocol_fee_asset],
+                }
+                .into(),
+            );
+        }
+    }
+
+    if let Some(minimum_receive) = minimum_receive {
+        ensure!(
+            return_amount >= minimum_receive,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive,
+                swap_amount: return_amount,
+            }
+        );
+    }
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    let mut messages = vec![];
+    if !return_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![coin(return_amount.u128(), &ask_asset_denom)],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(recall_messages)
+        .add_messages(messages)
+        .add_messages(fee_messages)
+        .add_submessages(price_hook_msgs)
+        .add_attributes(vec![
+            ("action", "split_swap".to_string()),
+            ("sender", info.sender.into_string()),
+            ("receiver", receiver.into_string()),
+            ("offer_denom", offer_asset.denom),
+            ("ask_denom", ask_asset_denom),
+            ("offer_amount", offer_asset.amount.to_string()),
+            ("return_amount", return_amount.to_string()),
+        ])
+        .add_attributes(swap_attributes))
+}
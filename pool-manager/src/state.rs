@@ -1,8 +1,14 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Decimal, Deps};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, UniqueIndex};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Decimal256, Deps, Int128, Storage, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 pub use mantra_dex_std::pool_manager::Config;
-use mantra_dex_std::pool_manager::{PoolInfo, SwapOperation};
+use mantra_dex_std::pool_manager::{
+    ConcentratedPoolState, ConcentratedPosition, DcaSchedule, DepegProtectionConfig,
+    FailureInjection, FeeDiscountConfig, IbcDenomTrace, ImbalanceFee, LiquidityStream,
+    MigrationCampaign, PendingPoolFees, PoolInfo, PoolMetadata, PriceBreakerConfig,
+    PriceSubscription, RebalanceConfig, StopLossOrder, SwapOperation, UnlockingDurationBounds,
+    YieldDeployment,
+};
 
 use crate::ContractError;
 
@@ -12,6 +18,10 @@ use crate::ContractError;
 pub struct SingleSideLiquidityProvisionBuffer {
     /// The receiver of the LP
     pub receiver: String,
+    /// The address that made the deposit, refunded if the swap fails.
+    pub depositor: Addr,
+    /// The full deposit, refunded if the swap fails.
+    pub deposit: Coin,
     /// The expected offer asset balance in the contract after the single side liquidity provision
     /// is done. Used for validations.
     pub expected_offer_asset_balance_in_contract: Coin,
@@ -47,11 +57,18 @@ pub struct LiquidityProvisionData {
     pub unlocking_duration: Option<u64>,
     /// The identifier of the position to lock the LP tokens in the farm manager, if any.
     pub lock_position_identifier: Option<String>,
+    /// The minimum amount of LP tokens to mint, if any.
+    pub min_lp_to_receive: Option<Uint128>,
 }
 
 pub const SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER: Item<SingleSideLiquidityProvisionBuffer> =
     Item::new("single_side_liquidity_provision_buffer");
 
+/// Holds who the funds of the liquidity the contract provides on someone's behalf, e.g. with zaps
+/// and liquidity streams, came from, so what the deposit doesn't use is refunded to them rather
+/// than to the receiver. Saved along the `ProvideLiquidity` self-call, which consumes it.
+pub const LIQUIDITY_PROVISION_DEPOSITOR: Item<Addr> = Item::new("liquidity_provision_depositor");
+
 pub const POOLS: IndexedMap<&str, PoolInfo, PoolIndexes> = IndexedMap::new(
     "pools",
     PoolIndexes {
@@ -80,6 +97,19 @@ pub fn get_pool_by_identifier(
         .ok_or(ContractError::UnExistingPool)
 }
 
+/// The LP token supply of each pool, cached to save the bank supply query on the hot paths. The
+/// contract is the only minter and burner of LP tokens, so it keeps the cache in sync.
+pub const LP_SUPPLY: Map<&str, Uint128> = Map::new("lp_supply");
+
+/// Gets the LP token supply of the given pool, from the cache or from the bank module for pools
+/// whose supply wasn't cached yet, which the migration backfills.
+pub fn get_lp_supply(deps: &Deps, pool_info: &PoolInfo) -> Result<Uint128, ContractError> {
+    match LP_SUPPLY.may_load(deps.storage, &pool_info.pool_identifier)? {
+        Some(lp_supply) => Ok(lp_supply),
+        None => Ok(deps.querier.query_supply(&pool_info.lp_denom)?.amount),
+    }
+}
+
 /// Swap routes are used to establish defined routes for a given fee
 /// token to a desired fee token and is used for fee collection
 #[cw_serde]
@@ -89,5 +119,428 @@ pub struct SwapOperations {
     /// The operations to be executed for a given swap.
     pub swap_operations: Vec<SwapOperation>,
 }
+
+/// The registered swap routes, keyed by (offer_asset_denom, ask_asset_denom).
+pub const SWAP_ROUTES: Map<(&str, &str), SwapOperations> = Map::new("swap_routes");
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const POOL_COUNTER: Item<u64> = Item::new("pool_count");
+/// The explicit identifiers given to pools created with a canonical identifier, mapped to the
+/// canonical one.
+pub const POOL_ALIASES: Map<&str, String> = Map::new("pool_aliases");
+
+/// A monotonically increasing counter to generate unique stop-loss order identifiers.
+pub const STOP_LOSS_ORDER_COUNTER: Item<u64> = Item::new("stop_loss_order_counter");
+
+/// The stop-loss orders waiting to be executed by keepers. The key is the order identifier.
+pub const STOP_LOSS_ORDERS: IndexedMap<u64, StopLossOrder, StopLossOrderIndexes> = IndexedMap::new(
+    "stop_loss_orders",
+    StopLossOrderIndexes {
+        owner: MultiIndex::new(
+            |_pk, o| o.owner.to_string(),
+            "stop_loss_orders",
+            "stop_loss_orders__owner",
+        ),
+    },
+);
+
+pub struct StopLossOrderIndexes<'a> {
+    pub owner: MultiIndex<'a, String, StopLossOrder, u64>,
+}
+
+impl<'a> IndexList<StopLossOrder> for StopLossOrderIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<StopLossOrder>> + '_> {
+        let v: Vec<&dyn Index<StopLossOrder>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Gets the stop-loss order given its identifier
+pub fn get_stop_loss_order_by_id(
+    storage: &dyn Storage,
+    order_id: u64,
+) -> Result<StopLossOrder, ContractError> {
+    STOP_LOSS_ORDERS
+        .may_load(storage, order_id)?
+        .ok_or(ContractError::UnExistingStopLossOrder)
+}
+
+/// A monotonically increasing counter to generate unique DCA schedule identifiers.
+pub const DCA_SCHEDULE_COUNTER: Item<u64> = Item::new("dca_schedule_counter");
+
+/// The active DCA schedules. The key is the schedule identifier.
+pub const DCA_SCHEDULES: IndexedMap<u64, DcaSchedule, DcaScheduleIndexes> = IndexedMap::new(
+    "dca_schedules",
+    DcaScheduleIndexes {
+        owner: MultiIndex::new(
+            |_pk, s| s.owner.to_string(),
+            "dca_schedules",
+            "dca_schedules__owner",
+        ),
+    },
+);
+
+pub struct DcaScheduleIndexes<'a> {
+    pub owner: MultiIndex<'a, String, DcaSchedule, u64>,
+}
+
+impl<'a> IndexList<DcaSchedule> for DcaScheduleIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DcaSchedule>> + '_> {
+        let v: Vec<&dyn Index<DcaSchedule>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Gets the DCA schedule given its identifier
+pub fn get_dca_schedule_by_id(
+    storage: &dyn Storage,
+    schedule_id: u64,
+) -> Result<DcaSchedule, ContractError> {
+    DCA_SCHEDULES
+        .may_load(storage, schedule_id)?
+        .ok_or(ContractError::UnExistingDcaSchedule)
+}
+
+/// A monotonically increasing counter to generate unique price subscription identifiers.
+pub const PRICE_SUBSCRIPTION_COUNTER: Item<u64> = Item::new("price_subscription_counter");
+
+/// The price threshold subscriptions, checked after every swap on their pool. The key is the
+/// subscription identifier.
+pub const PRICE_SUBSCRIPTIONS: IndexedMap<u64, PriceSubscription, PriceSubscriptionIndexes> =
+    IndexedMap::new(
+        "price_subscriptions",
+        PriceSubscriptionIndexes {
+            pool_identifier: MultiIndex::new(
+                |_pk, s| s.pool_identifier.to_string(),
+                "price_subscriptions",
+                "price_subscriptions__pool_identifier",
+            ),
+        },
+    );
+
+pub struct PriceSubscriptionIndexes<'a> {
+    pub pool_identifier: MultiIndex<'a, String, PriceSubscription, u64>,
+}
+
+impl<'a> IndexList<PriceSubscription> for PriceSubscriptionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PriceSubscription>> + '_> {
+        let v: Vec<&dyn Index<PriceSubscription>> = vec![&self.pool_identifier];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Gets the price subscription given its identifier
+pub fn get_price_subscription_by_id(
+    storage: &dyn Storage,
+    subscription_id: u64,
+) -> Result<PriceSubscription, ContractError> {
+    PRICE_SUBSCRIPTIONS
+        .may_load(storage, subscription_id)?
+        .ok_or(ContractError::UnExistingPriceSubscription)
+}
+
+/// The cumulative prices of a pool at a point in time, used to compute TWAPs.
+#[cw_serde]
+pub struct PriceObservation {
+    /// The cumulative prices of the assets in the pool, where `cumulative_prices[i][j]` is the sum
+    /// over time, in seconds, of the price of the asset `i` in terms of the asset `j`.
+    pub cumulative_prices: Vec<Vec<Decimal256>>,
+}
+
+/// The price observations of each pool, keyed by pool identifier and timestamp in seconds.
+pub const PRICE_OBSERVATIONS: Map<(&str, u64), PriceObservation> = Map::new("price_observations");
+
+/// A monotonically increasing counter to generate unique liquidity stream identifiers.
+pub const LIQUIDITY_STREAM_COUNTER: Item<u64> = Item::new("liquidity_stream_counter");
+
+/// The active liquidity streams. The key is the stream identifier.
+pub const LIQUIDITY_STREAMS: IndexedMap<u64, LiquidityStream, LiquidityStreamIndexes> =
+    IndexedMap::new(
+        "liquidity_streams",
+        LiquidityStreamIndexes {
+            owner: MultiIndex::new(
+                |_pk, s| s.owner.to_string(),
+                "liquidity_streams",
+                "liquidity_streams__owner",
+            ),
+        },
+    );
+
+pub struct LiquidityStreamIndexes<'a> {
+    pub owner: MultiIndex<'a, String, LiquidityStream, u64>,
+}
+
+impl<'a> IndexList<LiquidityStream> for LiquidityStreamIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<LiquidityStream>> + '_> {
+        let v: Vec<&dyn Index<LiquidityStream>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Gets the liquidity stream given its identifier
+pub fn get_liquidity_stream_by_id(
+    storage: &dyn Storage,
+    stream_id: u64,
+) -> Result<LiquidityStream, ContractError> {
+    LIQUIDITY_STREAMS
+        .may_load(storage, stream_id)?
+        .ok_or(ContractError::UnExistingLiquidityStream)
+}
+
+/// The IBC channels over which assets are transferred when withdrawing liquidity to a remote
+/// address, keyed by denom.
+pub const IBC_WITHDRAWAL_CHANNELS: Map<&str, String> = Map::new("ibc_withdrawal_channels");
+
+/// The channels assets transferred over IBC must have come through to be added to a pool. Any
+/// channel is allowed when unset.
+pub const IBC_SOURCE_CHANNELS: Item<Vec<String>> = Item::new("ibc_source_channels");
+
+/// The traces of the IBC denoms of each pool, resolved when the pool was created.
+pub const POOL_DENOM_TRACES: Map<&str, Vec<IbcDenomTrace>> = Map::new("pool_denom_traces");
+
+/// The migration campaigns, keyed by the identifier of the pool the liquidity is moved from.
+pub const MIGRATION_CAMPAIGNS: Map<&str, MigrationCampaign> = Map::new("migration_campaigns");
+
+/// The LP amount each address moved as part of a migration campaign, keyed by the identifier of
+/// the pool the liquidity is moved from and address.
+pub const MIGRATED_LIQUIDITY: Map<(&str, &Addr), Uint128> = Map::new("migrated_liquidity");
+
+/// The CW20 wrappers of the LP tokens of the pools, keyed by pool identifier.
+pub const LP_WRAPPERS: Map<&str, Addr> = Map::new("lp_wrappers");
+
+/// Holds the identifier of the pool whose LP wrapper is being instantiated.
+pub const LP_WRAPPER_BUFFER: Item<String> = Item::new("lp_wrapper_buffer");
+
+/// The secp256k1 public keys of the RFQ makers, used to verify the quotes they sign.
+pub const RFQ_MAKERS: Map<&Addr, Binary> = Map::new("rfq_makers");
+
+/// The inventory deposited by the RFQ makers to settle their quotes, keyed by maker and denom.
+pub const RFQ_INVENTORY: Map<(&Addr, &str), Uint128> = Map::new("rfq_inventory");
+
+/// The quote nonces already used by each RFQ maker, so quotes can't be filled twice.
+pub const RFQ_USED_NONCES: Map<(&Addr, u64), bool> = Map::new("rfq_used_nonces");
+
+/// The LP shares owned by the protocol in each pool, keyed by pool identifier. Keepers can move
+/// them between stable pools.
+pub const PROTOCOL_LIQUIDITY: Map<&str, Uint128> = Map::new("protocol_liquidity");
+
+/// The keepers and bounds of the protocol-owned liquidity rebalancing.
+pub const REBALANCE_CONFIG: Item<RebalanceConfig> = Item::new("rebalance_config");
+
+/// Holds information about an ongoing rebalance until the liquidity provision into the
+/// destination pool is completed, to account for the LP shares minted to the protocol.
+#[cw_serde]
+pub struct RebalanceBuffer {
+    /// The identifier of the pool the liquidity is moved to.
+    pub pool_identifier: String,
+    /// The LP balance of the contract in the destination pool before the liquidity provision.
+    pub lp_balance_before: Uint128,
+}
+
+pub const REBALANCE_BUFFER: Item<RebalanceBuffer> = Item::new("rebalance_buffer");
+
+/// Holds information about an ongoing unlock-and-withdraw until the farm manager has sent the LP
+/// tokens of the position back, to withdraw the liquidity they represent.
+#[cw_serde]
+pub struct UnlockAndWithdrawBuffer {
+    /// The address that owns the position, which receives the withdrawn assets.
+    pub receiver: Addr,
+    /// The identifier of the pool the liquidity is withdrawn from.
+    pub pool_identifier: String,
+    /// The LP balance of the contract before the position is withdrawn.
+    pub lp_balance_before: Uint128,
+}
+
+pub const UNLOCK_AND_WITHDRAW_BUFFER: Item<UnlockAndWithdrawBuffer> =
+    Item::new("unlock_and_withdraw_buffer");
+
+/// Holds information about an ongoing flash swap until the callback of its receiver is done, to
+/// settle the swap with what the receiver repaid.
+#[cw_serde]
+pub struct FlashSwapBuffer {
+    /// The address that started the flash swap.
+    pub sender: Addr,
+    /// The address the ask asset was sent to, whose callback repays the flash swap.
+    pub receiver: Addr,
+    /// The identifier of the pool the ask asset was sent out of.
+    pub pool_identifier: String,
+    /// The asset sent to the receiver.
+    pub ask_asset: Coin,
+    /// The denom the flash swap is repaid in.
+    pub offer_asset_denom: String,
+    /// The minimum amount of the offer asset to repay, as reverse simulated.
+    pub repay_amount: Uint128,
+    /// The balance of the offer asset in the contract before the callback.
+    pub offer_balance_before: Uint128,
+    /// The maximum spread of the swap the flash swap is settled with.
+    pub max_spread: Option<Decimal>,
+}
+
+pub const FLASH_SWAP_BUFFER: Item<FlashSwapBuffer> = Item::new("flash_swap_buffer");
+
+/// Holds information about an ongoing swap through a swap adapter until it's done, to measure what
+/// the adapter actually returned against the quote the route continued with.
+#[cw_serde]
+pub struct AdapterSwapBuffer {
+    /// The address whatever the adapter returns above the quote is refunded to.
+    pub receiver: Addr,
+    /// The output of the swap as quoted by the adapter, which the route continued with.
+    pub quoted_asset: Coin,
+    /// The balance of the contract in the denom of the quoted asset right before the swap.
+    pub balance_before: Uint128,
+}
+
+pub const ADAPTER_SWAP_BUFFER: Item<AdapterSwapBuffer> = Item::new("adapter_swap_buffer");
+
+/// The depeg protection settings of the stableswap pools that opted in, keyed by pool identifier.
+pub const DEPEG_PROTECTION: Map<&str, DepegProtectionConfig> = Map::new("depeg_protection");
+
+/// The time, in seconds, since which the peg deviation of a protected pool has been beyond its
+/// band, keyed by pool identifier. Removed once the pool is back within the band.
+pub const DEPEG_DEVIATING_SINCE: Map<&str, u64> = Map::new("depeg_deviating_since");
+
+/// The price breakers of the pools that opted in, keyed by pool identifier.
+pub const PRICE_BREAKERS: Map<&str, PriceBreakerConfig> = Map::new("price_breakers");
+
+/// The time, in seconds, the price breaker of a pool was tripped at, keyed by pool identifier.
+/// Removed once the breaker is reset.
+pub const PRICE_BREAKER_TRIPPED_AT: Map<&str, u64> = Map::new("price_breaker_tripped_at");
+
+/// The tiers of the swap fee discounts the traders get by staking, with the staking contract their
+/// stake is queried from. Fees aren't discounted if unset.
+pub const FEE_DISCOUNTS: Item<FeeDiscountConfig> = Item::new("fee_discounts");
+
+/// The imbalance fees of the stableswap pools that opted in, keyed by pool identifier.
+pub const IMBALANCE_FEES: Map<&str, ImbalanceFee> = Map::new("imbalance_fees");
+
+/// The minimum reserves swaps can't drain the assets of a pool below, keyed by pool identifier.
+pub const RESERVE_FLOORS: Map<&str, Vec<Coin>> = Map::new("reserve_floors");
+
+/// The fees scheduled for each pool, keyed by pool identifier, until they are applied.
+pub const PENDING_POOL_FEES: Map<&str, PendingPoolFees> = Map::new("pending_pool_fees");
+
+/// The rate providers of the stableswap pools that registered them, keyed by pool identifier. One
+/// per asset in the order of the pool assets, `None` for the assets with a rate of one.
+pub const RATE_PROVIDERS: Map<&str, Vec<Option<Addr>>> = Map::new("rate_providers");
+
+/// The admins of the permissioned pools, keyed by pool identifier. Only allowlisted addresses can
+/// swap in or provide liquidity to these pools.
+pub const PERMISSIONED_POOLS: Map<&str, Addr> = Map::new("permissioned_pools");
+
+/// The metadata front-ends display each pool with, keyed by pool identifier.
+pub const POOL_METADATA: Map<&str, PoolMetadata> = Map::new("pool_metadata");
+
+/// The time, in seconds, each deprecated pool was deprecated at, keyed by pool identifier. Deprecated
+/// pools only accept withdrawals.
+pub const DEPRECATED_POOLS: Map<&str, u64> = Map::new("deprecated_pools");
+
+/// The addresses allowed to trade in each permissioned pool.
+pub const POOL_ALLOWLIST: Map<(&str, &Addr), bool> = Map::new("pool_allowlist");
+
+/// The addresses allowed to create pools while pool creation isn't permissionless.
+pub const POOL_CREATOR_ALLOWLIST: Map<&Addr, bool> = Map::new("pool_creator_allowlist");
+
+/// The swap adapters whitelisted by the owner, through which routes can trade external AMMs.
+pub const SWAP_ADAPTERS: Map<&Addr, bool> = Map::new("swap_adapters");
+
+/// The admins proposed for each pool, keyed by pool identifier, until they accept the role.
+pub const PENDING_POOL_ADMINS: Map<&str, Addr> = Map::new("pending_pool_admins");
+
+/// The minimum unlocking duration, in seconds, the liquidity provided to a pool must be locked for
+/// in the farm manager, keyed by pool identifier.
+pub const MIN_LP_LOCKS: Map<&str, u64> = Map::new("min_lp_locks");
+
+/// The bounds of the unlocking durations accepted when providing liquidity to a pool with a lock,
+/// keyed by pool identifier. Pools without bounds of their own use the ones of the config, if any.
+pub const UNLOCKING_DURATION_BOUNDS: Map<&str, UnlockingDurationBounds> =
+    Map::new("unlocking_duration_bounds");
+
+/// The yield strategies the idle reserves of the pools can be deployed into, whitelisted by the
+/// owner, together with the denom each of them accepts.
+pub const YIELD_STRATEGIES: Map<&Addr, String> = Map::new("yield_strategies");
+
+/// The yield strategy the reserves of an asset of a pool are deployed into, and how much of them
+/// is, keyed by pool identifier and denom. Deployed reserves stay accounted for in the pool.
+pub const YIELD_DEPLOYMENTS: Map<(&str, &str), YieldDeployment> = Map::new("yield_deployments");
+
+/// Whether the rounding remainders of the pool math are accumulated into [ROUNDING_DUST].
+pub const DUST_TRACKING: Item<bool> = Item::new("dust_tracking");
+
+/// The rounding remainders accumulated by a pool while dust tracking is enabled, i.e. the
+/// fractions of a unit rounded away by the pool math.
+#[cw_serde]
+pub struct RoundingDust {
+    /// The remainders kept by the pool, accruing to its LPs, indexed like the pool assets.
+    pub accrued: Vec<Decimal256>,
+    /// The remainders given away by the pool, leaking from its LPs, indexed like the pool assets.
+    pub leaked: Vec<Decimal256>,
+    /// The LP shares deposits were rounded down by, accruing to the existing LPs.
+    pub lp_accrued: Decimal256,
+}
+
+/// The rounding dust of each pool, keyed by pool identifier.
+pub const ROUNDING_DUST: Map<&str, RoundingDust> = Map::new("rounding_dust");
+
+/// The failures injected into the contract for chaos testing. Only read by builds with the
+/// `failure-injection` feature.
+pub const FAILURE_INJECTION: Item<FailureInjection> = Item::new("failure_injection");
+
+/// The state of each concentrated liquidity pool, keyed by pool identifier. Set by the first
+/// provision to the pool, which sets its initial price.
+pub const CONCENTRATED_POOLS: Map<&str, ConcentratedPoolState> = Map::new("concentrated_pools");
+
+/// A tick of a concentrated liquidity pool at least one position starts or ends at.
+#[cw_serde]
+pub struct TickInfo {
+    /// The total liquidity of the positions starting or ending at the tick.
+    pub liquidity_gross: Uint128,
+    /// The liquidity added to the pool when the price crosses the tick upwards, and removed from
+    /// it when crossing it downwards.
+    pub liquidity_net: Int128,
+    /// The swap fees accrued per unit of liquidity on the other side of the tick from the current
+    /// price, for each asset of the pool, indexed like the pool assets.
+    pub fee_growth_outside: Vec<Decimal256>,
+}
+
+/// The initialized ticks of the concentrated liquidity pools, keyed by pool identifier and tick.
+pub const TICKS: Map<(&str, i64), TickInfo> = Map::new("ticks");
+
+/// A monotonically increasing counter to generate unique concentrated liquidity position
+/// identifiers.
+pub const CONCENTRATED_POSITION_COUNTER: Item<u64> = Item::new("concentrated_position_counter");
+
+/// The concentrated liquidity positions. The key is the position identifier.
+pub const CONCENTRATED_POSITIONS: IndexedMap<
+    u64,
+    ConcentratedPosition,
+    ConcentratedPositionIndexes,
+> = IndexedMap::new(
+    "concentrated_positions",
+    ConcentratedPositionIndexes {
+        owner: MultiIndex::new(
+            |_pk, p| p.owner.to_string(),
+            "concentrated_positions",
+            "concentrated_positions__owner",
+        ),
+    },
+);
+
+pub struct ConcentratedPositionIndexes<'a> {
+    pub owner: MultiIndex<'a, String, ConcentratedPosition, u64>,
+}
+
+impl<'a> IndexList<ConcentratedPosition> for ConcentratedPositionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ConcentratedPosition>> + '_> {
+        let v: Vec<&dyn Index<ConcentratedPosition>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Gets the concentrated liquidity position given its identifier
+pub fn get_concentrated_position_by_id(
+    storage: &dyn Storage,
+    position_id: u64,
+) -> Result<ConcentratedPosition, ContractError> {
+    CONCENTRATED_POSITIONS
+        .may_load(storage, position_id)?
+        .ok_or(ContractError::UnExistingConcentratedPosition)
+}
@@ -0,0 +1,250 @@
+use cosmwasm_std::{
+    ensure, wasm_execute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
+
+use mantra_dex_std::pool_manager::{ExecuteMsg, StopLossOrder, SwapOperation};
+
+use crate::router::commands::{
+    assert_no_swap_adapters, assert_operations, assert_operations_pools,
+};
+use crate::state::{get_stop_loss_order_by_id, STOP_LOSS_ORDERS, STOP_LOSS_ORDER_COUNTER};
+use crate::twap::{get_route_twap, MAX_TWAP_WINDOW};
+use crate::ContractError;
+
+/// Places a stop-loss order. The funds sent with the message must be the asset to be sold, i.e.
+/// the input of the first swap operation, plus the bounty that will be paid to the keeper that
+/// executes the order.
+///
+/// The order triggers on the TWAP of the route over the last `twap_window` seconds, so the price
+/// can't be pushed through the trigger within the block the order is executed in.
+#[allow(clippy::too_many_arguments)]
+pub fn place_stop_loss_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    trigger_price: Decimal,
+    minimum_receive: Uint128,
+    max_spread: Option<Decimal>,
+    bounty: Coin,
+    twap_window: u64,
+) -> Result<Response, ContractError> {
+    assert_operations(operations.clone())?;
+    // the order triggers on the TWAP of the route, which swap adapters don't have
+    assert_no_swap_adapters(&operations, "stop_loss")?;
+
+    assert_operations_pools(deps.as_ref(), &operations)?;
+
+    ensure!(!trigger_price.is_zero(), ContractError::InvalidTriggerPrice);
+    ensure!(
+        twap_window > 0 && twap_window <= MAX_TWAP_WINDOW,
+        ContractError::InvalidTwapWindow {
+            max: MAX_TWAP_WINDOW
+        }
+    );
+
+    let offer_asset_denom = operations
+        .first()
+        .ok_or(ContractError::NoSwapOperationsProvided)?
+        .get_input_asset_info()
+        .clone();
+
+    let offer_asset = get_offer_asset_from_deposit(&info, &offer_asset_denom, &bounty)?;
+
+    let order_id = STOP_LOSS_ORDER_COUNTER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1u64;
+    STOP_LOSS_ORDER_COUNTER.save(deps.storage, &order_id)?;
+
+    STOP_LOSS_ORDERS.save(
+        deps.storage,
+        order_id,
+        &StopLossOrder {
+            id: order_id,
+            owner: info.sender.clone(),
+            offer_asset: offer_asset.clone(),
+            operations,
+            trigger_price,
+            minimum_receive,
+            max_spread,
+            bounty: bounty.clone(),
+            twap_window,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "place_stop_loss_order".to_string()),
+        ("order_id", order_id.to_string()),
+        ("owner", info.sender.into_string()),
+        ("offer_asset", offer_asset.to_string()),
+        ("trigger_price", trigger_price.to_string()),
+        ("minimum_receive", minimum_receive.to_string()),
+        ("bounty", bounty.to_string()),
+        ("twap_window", twap_window.to_string()),
+    ]))
+}
+
+/// Cancels a stop-loss order, refunding the offer asset and the bounty to the owner.
+pub fn cancel_stop_loss_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let order = get_stop_loss_order_by_id(deps.storage, order_id)?;
+
+    ensure!(order.owner == info.sender, ContractError::Unauthorized);
+
+    STOP_LOSS_ORDERS.remove(deps.storage, order_id)?;
+
+    let refund: Vec<Coin> = mantra_dex_std::coin::aggregate_coins(vec![
+        order.offer_asset.clone(),
+        order.bounty.clone(),
+    ])?
+    .into_iter()
+    .filter(|coin| !coin.amount.is_zero())
+    .collect();
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !refund.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: order.owner.to_string(),
+                amount: refund,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "cancel_stop_loss_order".to_string()),
+            ("order_id", order_id.to_string()),
+            ("owner", order.owner.into_string()),
+        ]))
+}
+
+/// Executes a stop-loss order once the TWAP of its route over the order's window has crossed the
+/// trigger price. Can be called by anyone, i.e. keepers, who get the order's bounty in exchange.
+/// The price is in base units of the assets, as the one of the `RouteTwap` query.
+///
+/// The swap is performed by the contract itself via [ExecuteMsg::ExecuteSwapOperations], sending
+/// the proceeds to the owner of the order. If the `minimum_receive` of the order is not met, the
+/// whole execution fails and the order remains untouched.
+pub fn execute_stop_loss_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let order = get_stop_loss_order_by_id(deps.storage, order_id)?;
+
+    // the spot price can be moved within the block, the TWAP only by holding the price over time
+    let current_price = get_route_twap(
+        deps.as_ref(),
+        env.block.time.seconds(),
+        &order.operations,
+        order.twap_window,
+    )?;
+
+    ensure!(
+        current_price <= order.trigger_price,
+        ContractError::StopLossNotTriggered {
+            trigger_price: order.trigger_price,
+            current_price,
+        }
+    );
+
+    STOP_LOSS_ORDERS.remove(deps.storage, order_id)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![wasm_execute(
+        env.contract.address.into_string(),
+        &ExecuteMsg::ExecuteSwapOperations {
+            operations: order.operations,
+            minimum_receive: Some(order.minimum_receive),
+            receiver: Some(order.owner.to_string()),
+            max_spread: order.max_spread,
+            ask_amount: None,
+            referral: None,
+            hop_limits: None,
+        },
+        vec![order.offer_asset.clone()],
+    )?
+    .into()];
+
+    if !order.bounty.amount.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![order.bounty.clone()],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "execute_stop_loss_order".to_string()),
+            ("order_id", order_id.to_string()),
+            ("owner", order.owner.into_string()),
+            ("keeper", info.sender.into_string()),
+            ("offer_asset", order.offer_asset.to_string()),
+            ("trigger_price", order.trigger_price.to_string()),
+            ("current_price", current_price.to_string()),
+            ("bounty", order.bounty.to_string()),
+        ]))
+}
+
+/// Extracts the offer asset from the funds sent with the order, validating that only the offer
+/// asset and the bounty were sent.
+fn get_offer_asset_from_deposit(
+    info: &MessageInfo,
+    offer_asset_denom: &String,
+    bounty: &Coin,
+) -> Result<Coin, ContractError> {
+    let deposits = mantra_dex_std::coin::aggregate_coins(info.funds.clone())?;
+
+    // only the offer asset and the bounty can be sent
+    ensure!(
+        deposits
+            .iter()
+            .all(|coin| &coin.denom == offer_asset_denom || coin.denom == bounty.denom),
+        ContractError::InvalidStopLossOrderFunds
+    );
+
+    let paid_amount = |denom: &String| {
+        deposits
+            .iter()
+            .find(|coin| &coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default()
+    };
+
+    let offer_amount = if &bounty.denom == offer_asset_denom {
+        paid_amount(offer_asset_denom)
+            .checked_sub(bounty.amount)
+            .map_err(|_| ContractError::InvalidStopLossOrderFunds)?
+    } else {
+        ensure!(
+            paid_amount(&bounty.denom) == bounty.amount,
+            ContractError::InvalidStopLossOrderFunds
+        );
+        paid_amount(offer_asset_denom)
+    };
+
+    ensure!(
+        !offer_amount.is_zero(),
+        ContractError::InvalidStopLossOrderFunds
+    );
+
+    Ok(Coin {
+        denom: offer_asset_denom.to_string(),
+        amount: offer_amount,
+    })
+}
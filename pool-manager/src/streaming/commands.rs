@@ -0,0 +1,228 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, BankMsg, Coin, Decimal, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
+
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::pool_manager::{ExecuteMsg, LiquidityStream};
+
+use crate::state::{
+    get_liquidity_stream_by_id, get_pool_by_identifier, LIQUIDITY_PROVISION_DEPOSITOR,
+    LIQUIDITY_STREAMS, LIQUIDITY_STREAM_COUNTER,
+};
+use crate::ContractError;
+
+/// Creates a liquidity stream. The funds sent with the message are provided to the given pool in
+/// `tranches` equal parts, one every `interval` seconds, on behalf of the sender. The first
+/// tranche can be provided right away.
+///
+/// Each tranche must mint at least `min_lp_per_tranche`, as keepers choose when tranches are
+/// provided and could sandwich them otherwise.
+///
+/// Tranches are driven by keepers rather than by the epoch hook, as the pool manager isn't
+/// registered with the epoch manager. `interval` can be set to the epoch duration to provide one
+/// tranche per epoch.
+#[allow(clippy::too_many_arguments)]
+pub fn create_liquidity_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    tranches: u64,
+    min_lp_per_tranche: Uint128,
+    interval: u64,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        tranches > 0 && !min_lp_per_tranche.is_zero() && interval > 0,
+        ContractError::InvalidLiquidityStream
+    );
+    ensure!(!info.funds.is_empty(), ContractError::EmptyAssets);
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        info.funds
+            .iter()
+            .all(|asset| pool.asset_denoms.contains(&asset.denom)),
+        ContractError::AssetMismatch
+    );
+
+    let assets = aggregate_coins(info.funds.clone())?;
+
+    let stream_id = LIQUIDITY_STREAM_COUNTER
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1u64;
+    LIQUIDITY_STREAM_COUNTER.save(deps.storage, &stream_id)?;
+
+    LIQUIDITY_STREAMS.save(
+        deps.storage,
+        stream_id,
+        &LiquidityStream {
+            id: stream_id,
+            owner: info.sender.clone(),
+            pool_identifier: pool_identifier.clone(),
+            remaining_assets: assets.clone(),
+            remaining_tranches: tranches,
+            min_lp_per_tranche,
+            interval,
+            slippage_tolerance,
+            next_execution: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "create_liquidity_stream".to_string()),
+        ("stream_id", stream_id.to_string()),
+        ("owner", info.sender.into_string()),
+        ("pool_identifier", pool_identifier),
+        ("assets", coins_to_string(&assets)),
+        ("tranches", tranches.to_string()),
+        ("min_lp_per_tranche", min_lp_per_tranche.to_string()),
+        ("interval", interval.to_string()),
+    ]))
+}
+
+/// Provides the next tranche of a liquidity stream. Can be called by anyone, i.e. keepers, once
+/// the interval since the last tranche has elapsed.
+///
+/// The liquidity is provided by the contract itself via [ExecuteMsg::ProvideLiquidity], minting
+/// the LP tokens to the owner of the stream. The last tranche provides whatever is left, after
+/// which the stream is removed.
+pub fn execute_liquidity_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut stream = get_liquidity_stream_by_id(deps.storage, stream_id)?;
+
+    ensure!(
+        env.block.time.seconds() >= stream.next_execution,
+        ContractError::LiquidityStreamNotDue {
+            next_execution: stream.next_execution,
+        }
+    );
+
+    let mut tranche: Vec<Coin> = stream
+        .remaining_assets
+        .iter()
+        .map(|asset| {
+            coin(
+                asset
+                    .amount
+                    .multiply_ratio(1u128, stream.remaining_tranches)
+                    .u128(),
+                &asset.denom,
+            )
+        })
+        .filter(|asset| !asset.amount.is_zero())
+        .collect();
+
+    // the dust left once no asset splits into the remaining tranches isn't worth sandwiching, and
+    // would hardly mint the minimum
+    let min_lp_to_receive = (!tranche.is_empty()).then_some(stream.min_lp_per_tranche);
+
+    stream.remaining_tranches = stream.remaining_tranches.saturating_sub(1);
+    // provide everything that's left if this is the last tranche, or if the rest is just dust
+    if stream.remaining_tranches == 0 || tranche.is_empty() {
+        tranche = stream
+            .remaining_assets
+            .iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .cloned()
+            .collect();
+        stream.remaining_tranches = 0;
+    }
+
+    for asset in stream.remaining_assets.iter_mut() {
+        if let Some(provided) = tranche
+            .iter()
+            .find(|provided| provided.denom == asset.denom)
+        {
+            asset.amount = asset.amount.checked_sub(provided.amount)?;
+        }
+    }
+    stream.next_execution = env.block.time.seconds().saturating_add(stream.interval);
+
+    if stream.remaining_tranches == 0 {
+        LIQUIDITY_STREAMS.remove(deps.storage, stream_id)?;
+    } else {
+        LIQUIDITY_STREAMS.save(deps.storage, stream_id, &stream)?;
+    }
+
+    // what the tranche doesn't use is refunded to the owner, who funded the stream
+    LIQUIDITY_PROVISION_DEPOSITOR.save(deps.storage, &stream.owner)?;
+    let provide_msg = wasm_execute(
+        env.contract.address.into_string(),
+        &ExecuteMsg::ProvideLiquidity {
+            slippage_tolerance: stream.slippage_tolerance,
+            max_spread: None,
+            receiver: Some(stream.owner.to_string()),
+            pool_identifier: stream.pool_identifier.clone(),
+            unlocking_duration: None,
+            lock_position_identifier: None,
+            min_lp_to_receive,
+            deposit_route: None,
+        },
+        tranche.clone(),
+    )?;
+
+    Ok(Response::default()
+        .add_message(provide_msg)
+        .add_attributes(vec![
+            ("action", "execute_liquidity_stream".to_string()),
+            ("stream_id", stream_id.to_string()),
+            ("owner", stream.owner.into_string()),
+            ("keeper", info.sender.into_string()),
+            ("pool_identifier", stream.pool_identifier),
+            ("tranche", coins_to_string(&tranche)),
+            ("remaining_tranches", stream.remaining_tranches.to_string()),
+        ]))
+}
+
+/// Cancels a liquidity stream, refunding the assets that haven't been provided yet to its owner.
+pub fn cancel_liquidity_stream(
+    deps: DepsMut,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let stream = get_liquidity_stream_by_id(deps.storage, stream_id)?;
+
+    ensure!(stream.owner == info.sender, ContractError::Unauthorized);
+
+    LIQUIDITY_STREAMS.remove(deps.storage, stream_id)?;
+
+    let refund: Vec<Coin> = stream
+        .remaining_assets
+        .into_iter()
+        .filter(|asset| !asset.amount.is_zero())
+        .collect();
+
+    let mut response = Response::default();
+    if !refund.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: stream.owner.to_string(),
+            amount: refund.clone(),
+        });
+    }
+
+    Ok(response.add_attributes(vec![
+        ("action", "cancel_liquidity_stream".to_string()),
+        ("stream_id", stream_id.to_string()),
+        ("owner", stream.owner.into_string()),
+        ("refund", coins_to_string(&refund)),
+    ]))
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -1,17 +1,44 @@
 use cosmwasm_std::Decimal;
-use cosmwasm_std::{ensure, Addr, BankMsg, CosmosMsg, DepsMut, MessageInfo, Response};
+use cosmwasm_std::{
+    coin, ensure, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    Uint128,
+};
 
 use mantra_dex_std::coin::burn_coin_msg;
 use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::pool_manager::{Config, FeePayer, PacketForward, Referral};
 
+use crate::ibc_transfer::commands::get_ibc_forward_msg;
+use crate::permissioned::commands::assert_pool_access;
+use crate::queries::query_reverse_simulation;
 use crate::state::get_pool_by_identifier;
 use crate::{state::CONFIG, ContractError};
 
 use super::perform_swap::perform_swap;
 
+/// The default maximum referral fee, in basis points, i.e. 1%.
+pub const DEFAULT_MAX_REFERRAL_FEE_BPS: u16 = 100;
+/// The highest the maximum referral fee can be set to, in basis points, i.e. 10%.
+pub const MAX_REFERRAL_FEE_BPS_CEILING: u16 = 1_000;
+
+/// Where the output of a swap is sent.
+pub(crate) enum SwapDestination {
+    /// An address on this chain, the sender by default.
+    Local(Option<String>),
+    /// An address on another chain, reached over `channel_id`. The output is refunded to
+    /// `refund_address` if the transfer fails.
+    Remote {
+        channel_id: String,
+        to_address: String,
+        refund_address: Addr,
+        forward: Option<PacketForward>,
+    },
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
-    mut deps: DepsMut,
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     ask_asset_denom: String,
@@ -19,6 +46,115 @@ pub fn swap(
     max_spread: Option<Decimal>,
     receiver: Option<String>,
     pool_identifier: String,
+    fee_payer: Option<FeePayer>,
+    referral: Option<Referral>,
+    min_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    swap_to(
+        deps,
+        env,
+        info,
+        sender,
+        ask_asset_denom,
+        belief_price,
+        max_spread,
+        SwapDestination::Local(receiver),
+        pool_identifier,
+        fee_payer,
+        referral,
+        min_receive,
+    )
+}
+
+/// Swaps the offer asset sent for exactly `ask_asset`. The funds sent are the maximum offer
+/// amount: only the amount needed to get `ask_asset` out of the pool, as reverse simulated, is
+/// swapped and the rest is refunded to the sender.
+pub fn swap_exact_out(
+    deps: DepsMut,
+    env: Env,
+    mut info: MessageInfo,
+    ask_asset: Coin,
+    max_spread: Option<Decimal>,
+    receiver: Option<String>,
+    pool_identifier: String,
+) -> Result<Response, ContractError> {
+    let max_offer_asset = cw_utils::one_coin(&info)?;
+    ensure!(
+        max_offer_asset.denom != ask_asset.denom,
+        ContractError::SameAsset
+    );
+
+    // the offer amount is rounded up by one unit so the swap doesn't fall short due to rounding
+    let offer_amount = query_reverse_simulation(
+        deps.as_ref(),
+        &env,
+        ask_asset.clone(),
+        max_offer_asset.denom.clone(),
+        pool_identifier.clone(),
+    )?
+    .offer_amount
+    .checked_add(Uint128::one())?;
+
+    ensure!(
+        offer_amount <= max_offer_asset.amount,
+        ContractError::MaxOfferAmountExceeded {
+            offer_amount,
+            max_offer_amount: max_offer_asset.amount,
+        }
+    );
+
+    let refund_amount = max_offer_asset.amount.checked_sub(offer_amount)?;
+    let mut refund_msg: Vec<CosmosMsg> = vec![];
+    if !refund_amount.is_zero() {
+        refund_msg.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(refund_amount.u128(), &max_offer_asset.denom)],
+        }));
+    }
+
+    // only the offer amount needed is swapped
+    info.funds = vec![coin(offer_amount.u128(), max_offer_asset.denom)];
+    let sender = info.sender.clone();
+
+    Ok(swap_to(
+        deps,
+        env,
+        info,
+        sender,
+        ask_asset.denom,
+        None,
+        max_spread,
+        SwapDestination::Local(receiver),
+        pool_identifier,
+        None,
+        None,
+        Some(ask_asset.amount),
+    )?
+    .add_messages(refund_msg)
+    .add_attributes(vec![
+        ("ask_amount", ask_asset.amount.to_string()),
+        ("max_offer_amount", max_offer_asset.amount.to_string()),
+        ("refund_amount", refund_amount.to_string()),
+    ]))
+}
+
+/// Performs a swap like [swap], sending the output to the given destination. If a `min_receive`
+/// is given, the swap fails if the receiver gets less than that, i.e. once the shares of the fee
+/// payer and the referrer are split off the output.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn swap_to(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    ask_asset_denom: String,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    destination: SwapDestination,
+    pool_identifier: String,
+    fee_payer: Option<FeePayer>,
+    referral: Option<Referral>,
+    min_receive: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     // check if the swap feature is enabled
@@ -46,27 +182,98 @@ pub fn swap(
                 .any(|pool_asset| pool_asset.denom == *asset)),
         ContractError::AssetMismatch
     );
+    assert_pool_access(deps.storage, &env, &pool_identifier, &sender)?;
 
     // perform the swap
     let swap_result = perform_swap(
         deps.branch(),
+        &env,
+        &sender,
         offer_asset.clone(),
         ask_asset_denom,
         pool_identifier,
         belief_price,
         max_spread,
+        None,
     )?;
 
-    // add messages
-    let mut messages: Vec<CosmosMsg> = vec![];
+    // add messages, recalling the deployed reserves the swap pays out first
+    let mut messages: Vec<CosmosMsg> = swap_result.recall_msgs;
 
-    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender);
+    let receiver = match &destination {
+        SwapDestination::Local(receiver) => {
+            validate_addr_or_default(&deps.as_ref(), receiver.clone(), info.sender).into_string()
+        }
+        SwapDestination::Remote { to_address, .. } => to_address.clone(),
+    };
 
-    if !swap_result.return_asset.amount.is_zero() {
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: receiver.clone().into_string(),
-            amount: vec![swap_result.return_asset.clone()],
-        }));
+    // split the fee payer's share off the output, i.e. to reimburse a relayer
+    let mut receiver_asset = swap_result.return_asset.clone();
+    let mut fee_payer_attributes = vec![];
+    if let Some(fee_payer) = fee_payer {
+        ensure!(
+            fee_payer.amount < receiver_asset.amount,
+            ContractError::InvalidFeePayerAmount {
+                amount: fee_payer.amount,
+                return_amount: receiver_asset.amount,
+            }
+        );
+
+        let fee_payer_addr = deps.api.addr_validate(&fee_payer.address)?;
+        receiver_asset.amount = receiver_asset.amount.checked_sub(fee_payer.amount)?;
+
+        if !fee_payer.amount.is_zero() {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: fee_payer_addr.to_string(),
+                amount: vec![coin(fee_payer.amount.u128(), receiver_asset.denom.clone())],
+            }));
+        }
+
+        fee_payer_attributes.push(("fee_payer", fee_payer_addr.into_string()));
+        fee_payer_attributes.push(("fee_payer_amount", fee_payer.amount.to_string()));
+    }
+
+    // split the referrer's share off the output
+    let (referral_msgs, referral_attributes) = deduct_referral_fee(
+        deps.as_ref(),
+        &config,
+        referral,
+        &swap_result.return_asset,
+        &mut receiver_asset,
+    )?;
+    messages.extend(referral_msgs);
+
+    // the minimum is what the receiver gets, after the shares of the fee payer and the referrer
+    if let Some(min_receive) = min_receive {
+        ensure!(
+            receiver_asset.amount >= min_receive,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive: min_receive,
+                swap_amount: receiver_asset.amount,
+            }
+        );
+    }
+
+    if !receiver_asset.amount.is_zero() {
+        match destination {
+            SwapDestination::Local(_) => messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: receiver.clone(),
+                amount: vec![receiver_asset],
+            })),
+            SwapDestination::Remote {
+                channel_id,
+                to_address,
+                refund_address,
+                forward,
+            } => messages.push(get_ibc_forward_msg(
+                &env,
+                channel_id,
+                receiver_asset,
+                &to_address,
+                &refund_address,
+                forward,
+            )?),
+        }
     }
 
     if !swap_result.burn_fee_asset.amount.is_zero() {
@@ -83,34 +290,84 @@ pub fn swap(
         );
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        ("action", "swap".to_string()),
-        ("sender", sender.into_string()),
-        ("receiver", receiver.into_string()),
-        ("offer_denom", offer_asset.denom),
-        ("ask_denom", swap_result.return_asset.denom),
-        ("offer_amount", offer_asset.amount.to_string()),
-        ("return_amount", swap_result.return_asset.amount.to_string()),
-        ("spread_amount", swap_result.spread_amount.to_string()),
-        (
-            "swap_fee_amount",
-            swap_result.swap_fee_asset.amount.to_string(),
-        ),
-        (
-            "protocol_fee_amount",
-            swap_result.protocol_fee_asset.amount.to_string(),
-        ),
-        (
-            "burn_fee_amount",
-            swap_result.burn_fee_asset.amount.to_string(),
-        ),
-        (
-            "extra_fees_amount",
-            swap_result.extra_fees_asset.amount.to_string(),
-        ),
-        (
-            "swap_type",
-            swap_result.pool_info.pool_type.get_label().to_string(),
-        ),
-    ]))
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(swap_result.price_hook_msgs)
+        .add_attributes(vec![
+            ("action", "swap".to_string()),
+            ("sender", sender.into_string()),
+            ("receiver", receiver),
+            ("offer_denom", offer_asset.denom),
+            ("ask_denom", swap_result.return_asset.denom),
+            ("offer_amount", offer_asset.amount.to_string()),
+            ("return_amount", swap_result.return_asset.amount.to_string()),
+            ("spread_amount", swap_result.spread_amount.to_string()),
+            (
+                "swap_fee_amount",
+                swap_result.swap_fee_asset.amount.to_string(),
+            ),
+            (
+                "protocol_fee_amount",
+                swap_result.protocol_fee_asset.amount.to_string(),
+            ),
+            (
+                "burn_fee_amount",
+                swap_result.burn_fee_asset.amount.to_string(),
+            ),
+            (
+                "extra_fees_amount",
+                swap_result.extra_fees_asset.amount.to_string(),
+            ),
+            (
+                "swap_type",
+                swap_result.pool_info.pool_type.get_label().to_string(),
+            ),
+        ])
+        .add_attributes(fee_payer_attributes)
+        .add_attributes(referral_attributes))
+}
+
+/// Deducts the fee of the given referral, a share of `return_asset` in basis points, from
+/// `receiver_asset`. Returns the message sending the fee to the referrer, if any, together with
+/// the attributes reporting it. The fee can't be above the maximum referral fee of the config.
+pub(crate) fn deduct_referral_fee(
+    deps: Deps,
+    config: &Config,
+    referral: Option<Referral>,
+    return_asset: &Coin,
+    receiver_asset: &mut Coin,
+) -> Result<(Vec<CosmosMsg>, Vec<(&'static str, String)>), ContractError> {
+    let Some(referral) = referral else {
+        return Ok((vec![], vec![]));
+    };
+
+    ensure!(
+        referral.fee_bps <= config.max_referral_fee_bps,
+        ContractError::ReferralFeeTooHigh {
+            fee_bps: referral.fee_bps,
+            max_fee_bps: config.max_referral_fee_bps,
+        }
+    );
+
+    let referrer = deps.api.addr_validate(&referral.address)?;
+    let referral_fee_amount = return_asset
+        .amount
+        .multiply_ratio(referral.fee_bps, 10_000u128);
+    receiver_asset.amount = receiver_asset.amount.checked_sub(referral_fee_amount)?;
+
+    let mut messages = vec![];
+    if !referral_fee_amount.is_zero() {
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: referrer.to_string(),
+            amount: vec![coin(referral_fee_amount.u128(), &return_asset.denom)],
+        }));
+    }
+
+    Ok((
+        messages,
+        vec![
+            ("referrer", referrer.into_string()),
+            ("referral_fee_amount", referral_fee_amount.to_string()),
+        ],
+    ))
 }
@@ -1,15 +1,27 @@
-use std::str::FromStr;
-
 use cosmwasm_std::{
-    Coin, Decimal, Decimal256, DepsMut, Fraction, StdError, StdResult, Uint128, Uint256,
+    ensure, Addr, Coin, CosmosMsg, Decimal, Decimal256, DepsMut, Env, Fraction, StdError,
+    StdResult, SubMsg, Uint128,
 };
 
-use mantra_dex_std::pool_manager::PoolInfo;
+use mantra_dex_std::pool_manager::{Config, PoolInfo};
 
-use crate::helpers::{aggregate_outgoing_fees, get_asset_indexes_in_pool};
+use crate::concentrated::swap::{
+    apply_concentrated_swap, compute_concentrated_swap, load_concentrated_pool,
+};
+use crate::depeg::protection::{get_depegging_denom, track_peg_deviation};
+use crate::dust::tracking::track_swap_dust;
+use crate::fee_discount::discount::{apply_fee_discount, get_fee_discount};
+use crate::gas;
+use crate::helpers::{apply_swap_to_pool, get_asset_indexes_in_pool};
+use crate::pegged::get_pegged_rate;
+use crate::price_breaker::breaker::assert_swap_within_price_breaker;
+use crate::price_hooks::hooks::get_price_hook_msgs;
+use crate::rates::{apply_rates, get_rates};
+use crate::twap::accumulate_prices;
+use crate::yield_strategy::commands::recall_uncovered_reserves;
 use crate::{
     helpers,
-    state::{get_pool_by_identifier, POOLS},
+    state::{get_pool_by_identifier, CONFIG, IMBALANCE_FEES, POOLS, RESERVE_FLOORS},
     ContractError,
 };
 
@@ -29,55 +41,113 @@ pub struct SwapResult {
     pub pool_info: PoolInfo,
     /// The amount of spread that occurred during the swap from the original exchange rate.
     pub spread_amount: Uint128,
+    /// The callbacks of the price subscriptions whose threshold was crossed by the swap.
+    pub price_hook_msgs: Vec<SubMsg>,
+    /// The recalls of the deployed reserves the swap pays out, which must go before the messages
+    /// sending the funds of the swap.
+    pub recall_msgs: Vec<CosmosMsg>,
 }
 
 /// Attempts to perform a swap from `offer_asset` to the relevant opposing
 /// asset in the pool identified by `pool_identifier`.
 ///
+/// The fees of the pool are discounted by the tier the stake of `trader` reaches, if any. If a
+/// `min_receive` is given, the swap fails if it returns less than that, net of the fees of the
+/// pool. The shares a fee payer or a referrer take off the return are split by the caller.
+///
 /// Assumes that `offer_asset` is a **native token**.
 ///
 /// The resulting [`SwapResult`] has actions that should be taken, as the swap has been performed.
 /// In other words, the caller of the `perform_swap` function _should_ make use
 /// of each field in [`SwapResult`] (besides fields like `spread_amount`).
+#[allow(clippy::too_many_arguments)]
 pub fn perform_swap(
     deps: DepsMut,
+    env: &Env,
+    trader: &Addr,
     offer_asset: Coin,
     ask_asset_denom: String,
     pool_identifier: String,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
+    min_receive: Option<Uint128>,
 ) -> Result<SwapResult, ContractError> {
     let mut pool_info = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    gas::checkpoint("swap", 1);
 
-    let (
-        offer_asset_in_pool,
-        ask_asset_in_pool,
-        offer_index,
-        ask_index,
-        offer_decimal,
-        ask_decimal,
-    ) = get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
-
-    // compute the swap
-    let swap_computation = helpers::compute_swap(
-        Uint256::from(pool_info.assets.len() as u128),
-        offer_asset_in_pool.amount,
-        ask_asset_in_pool.amount,
-        offer_asset.amount,
-        pool_info.pool_fees.clone(),
-        &pool_info.pool_type,
-        offer_decimal,
-        ask_decimal,
+    // selling the depegging asset into the pool is paused while its depeg protection is active
+    if let Some(depegging_denom) =
+        get_depegging_denom(deps.storage, &pool_info, env.block.time.seconds())?
+    {
+        ensure!(
+            offer_asset.denom != depegging_denom,
+            ContractError::DepegProtectionActive {
+                denom: depegging_denom,
+            }
+        );
+    }
+
+    let (_, ask_asset_in_pool, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    // stakers of the protocol token are charged discounted fees
+    let pool_fees = apply_fee_discount(
+        &pool_info.pool_fees,
+        get_fee_discount(deps.as_ref(), trader)?,
     )?;
 
+    // compute the swap, over the ticks of the pool for concentrated liquidity pools
+    let imbalance_fee = IMBALANCE_FEES.may_load(deps.storage, &pool_identifier)?;
+    let pegged_rate = get_pegged_rate(deps.as_ref(), env, &pool_info)?;
+    // stableswap pools with rate providers are priced on their rate-scaled balances
+    let rates = get_rates(deps.as_ref(), &pool_info)?;
+    let concentrated_swap = load_concentrated_pool(deps.storage, &pool_info)?
+        .map(|state| {
+            compute_concentrated_swap(
+                deps.storage,
+                &pool_info,
+                &state,
+                offer_index,
+                ask_index,
+                offer_asset.amount,
+                pool_fees.clone(),
+            )
+        })
+        .transpose()?;
+    let swap_computation = match &concentrated_swap {
+        Some(concentrated_swap) => concentrated_swap.swap_computation.clone(),
+        None => helpers::compute_swap(
+            &apply_rates(&pool_info, rates.as_deref())?,
+            offer_index,
+            ask_index,
+            offer_asset.amount,
+            pool_fees,
+            imbalance_fee.as_ref(),
+            pegged_rate,
+        )?,
+    };
+
     let return_asset = Coin {
         denom: ask_asset_in_pool.denom.clone(),
         amount: swap_computation.return_amount,
     };
 
+    // the swap can't go through a tripped price breaker, nor deviate beyond it
+    assert_swap_within_price_breaker(
+        deps.as_ref(),
+        env,
+        &pool_info,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        &swap_computation,
+    )?;
+
     // Assert spread and other operations
     // check max spread limit if exist
+    let config = CONFIG.load(deps.storage)?;
     assert_max_spread(
+        &config,
         belief_price,
         max_spread,
         offer_asset.amount,
@@ -85,24 +155,73 @@ pub fn perform_swap(
         swap_computation.spread_amount,
     )?;
 
+    if let Some(min_receive) = min_receive {
+        ensure!(
+            return_asset.amount >= min_receive,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive: min_receive,
+                swap_amount: return_asset.amount,
+            }
+        );
+    }
+
+    let pool_before_swap = pool_info.clone();
+
+    // accumulate the prices that held until this swap
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+
+    // account for the rounding of the swap, if tracked
+    track_swap_dust(
+        deps.storage,
+        &apply_rates(&pool_info, rates.as_deref())?,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        imbalance_fee.as_ref(),
+        &swap_computation,
+    )?;
+
     // State changes to the pools balances
     {
-        // add the offer amount to the pool
-        pool_info.assets[offer_index].amount = pool_info.assets[offer_index]
-            .amount
-            .checked_add(offer_asset.amount)?;
-
-        // Deduct the return amount and fees from the pool
-        let outgoing_fees = aggregate_outgoing_fees(&swap_computation.to_simulation_response())?;
+        // add the offer amount to the pool, and deduct the return amount and fees from it
+        apply_swap_to_pool(
+            &mut pool_info,
+            offer_index,
+            ask_index,
+            offer_asset.amount,
+            &swap_computation,
+        )?;
 
-        pool_info.assets[ask_index].amount = pool_info.assets[ask_index]
-            .amount
-            .checked_sub(return_asset.amount)?
-            .checked_sub(outgoing_fees)?;
+        // the swap can't drain the ask asset below its reserve floor
+        if let Some(floor) = RESERVE_FLOORS
+            .may_load(deps.storage, &pool_identifier)?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|floor| floor.denom == ask_asset_in_pool.denom)
+        {
+            ensure!(
+                pool_info.assets[ask_index].amount >= floor.amount,
+                ContractError::ReserveFloorBreached { floor }
+            );
+        }
 
         POOLS.save(deps.storage, &pool_identifier, &pool_info)?;
+
+        if let Some(concentrated_swap) = &concentrated_swap {
+            apply_concentrated_swap(deps.storage, &pool_identifier, concentrated_swap)?;
+        }
     }
 
+    let recall_msgs = recall_uncovered_reserves(deps.storage, &pool_info)?;
+
+    track_peg_deviation(
+        deps.storage,
+        &apply_rates(&pool_info, rates.as_deref())?,
+        env.block.time.seconds(),
+    )?;
+
+    let price_hook_msgs = get_price_hook_msgs(deps.storage, &pool_before_swap, &pool_info)?;
+
     let burn_fee_asset = Coin {
         denom: ask_asset_in_pool.denom.clone(),
         amount: swap_computation.burn_fee_amount,
@@ -130,19 +249,23 @@ pub fn perform_swap(
         pool_info,
         extra_fees_asset,
         spread_amount: swap_computation.spread_amount,
+        price_hook_msgs,
+        recall_msgs,
     })
 }
 
-/// Default swap slippage in case max_spread is not specified
+/// Default swap slippage in case max_spread is not specified, set in the config on instantiation.
 pub const DEFAULT_SLIPPAGE: &str = "0.01";
-/// Cap on the maximum swap slippage that is allowed. If max_spread goes over this limit, it will
-/// be capped to this value.
+/// Cap on the maximum swap slippage that is allowed, set in the config on instantiation. If
+/// max_spread goes over this limit, it will be capped to this value.
 pub const MAX_ALLOWED_SLIPPAGE: &str = "0.5";
 
 /// If `belief_price` and `max_spread` both are given,
 /// we compute new spread else we just use pool network
-/// spread to check `max_spread`
+/// spread to check `max_spread`. The `default_max_spread` of the config applies when
+/// `max_spread` is not given, and the spread is always capped to its `max_spread_cap`.
 pub fn assert_max_spread(
+    config: &Config,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     offer_amount: Uint128,
@@ -150,8 +273,8 @@ pub fn assert_max_spread(
     spread_amount: Uint128,
 ) -> StdResult<()> {
     let max_spread: Decimal256 = max_spread
-        .unwrap_or(Decimal::from_str(DEFAULT_SLIPPAGE)?)
-        .min(Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?)
+        .unwrap_or(config.default_max_spread)
+        .min(config.max_spread_cap)
         .into();
 
     if let Some(belief_price) = belief_price {
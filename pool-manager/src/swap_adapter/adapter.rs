@@ -0,0 +1,97 @@
+use cosmwasm_std::{
+    ensure, wasm_execute, Addr, Binary, Coin, CosmosMsg, Deps, Env, Storage, Uint128,
+};
+
+use mantra_dex_std::swap_adapter::{ExecuteMsg, QueryMsg, SimulationResponse};
+
+use crate::state::SWAP_ADAPTERS;
+use crate::ContractError;
+
+/// Ensures the given address is a whitelisted swap adapter.
+pub(crate) fn assert_swap_adapter(
+    storage: &dyn Storage,
+    adapter_addr: &Addr,
+) -> Result<(), ContractError> {
+    ensure!(
+        SWAP_ADAPTERS.has(storage, adapter_addr),
+        ContractError::SwapAdapterNotWhitelisted {
+            adapter: adapter_addr.to_string(),
+        }
+    );
+
+    Ok(())
+}
+
+/// Gets the amount of `ask_denom` the given swap adapter returns for `offer_asset`, as it would
+/// execute the swap with the given adapter-specific `msg`. Fails for adapters that aren't
+/// whitelisted.
+pub(crate) fn simulate_adapter_swap(
+    deps: Deps,
+    adapter_addr: &str,
+    offer_asset: Coin,
+    ask_denom: String,
+    msg: Binary,
+) -> Result<Uint128, ContractError> {
+    let adapter_addr = deps.api.addr_validate(adapter_addr)?;
+    assert_swap_adapter(deps.storage, &adapter_addr)?;
+
+    let response: SimulationResponse = deps.querier.query_wasm_smart(
+        adapter_addr,
+        &QueryMsg::Simulation {
+            offer_asset,
+            ask_denom,
+            msg,
+        },
+    )?;
+
+    Ok(response.return_amount)
+}
+
+/// Gets the message swapping `offer_asset` for at least `min_return` of `ask_denom` through the
+/// given swap adapter, which sends the output back to the contract or fails.
+pub(crate) fn get_adapter_swap_msg(
+    adapter_addr: &str,
+    offer_asset: Coin,
+    ask_denom: String,
+    min_return: Uint128,
+    msg: Binary,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(wasm_execute(
+        adapter_addr,
+        &ExecuteMsg::Swap {
+            ask_denom,
+            min_return,
+            msg,
+        },
+        vec![offer_asset],
+    )?
+    .into())
+}
+
+/// Gets the message the contract sends itself to swap `offer_asset` for at least `min_return` of
+/// `ask_denom` through the given swap adapter, as a hop of a route. Going through the contract
+/// lets it measure what the adapter actually returns, right before and after the swap, and refund
+/// whatever it returns above `min_return` to `receiver`.
+pub(crate) fn get_swap_through_adapter_msg(
+    env: &Env,
+    adapter_addr: &str,
+    offer_asset: Coin,
+    ask_denom: String,
+    min_return: Uint128,
+    msg: Binary,
+    receiver: &Addr,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(wasm_execute(
+        env.contract.address.to_string(),
+        &mantra_dex_std::pool_manager::ExecuteMsg::SwapThroughAdapter {
+            adapter_addr: adapter_addr.to_string(),
+            offer_asset,
+            ask_denom,
+            min_return,
+            msg,
+            receiver: receiver.to_string(),
+        },
+        vec![],
+    )?
+    .into())
+}
@@ -0,0 +1,147 @@
+use cosmwasm_std::{
+    coin, ensure, BankMsg, Binary, Coin, DepsMut, Env, MessageInfo, Response, SubMsg, Uint128,
+};
+
+use crate::contract::ADAPTER_SWAP_REPLY_ID;
+use crate::state::{AdapterSwapBuffer, ADAPTER_SWAP_BUFFER, SWAP_ADAPTERS};
+use crate::swap_adapter::adapter::{assert_swap_adapter, get_adapter_swap_msg};
+use crate::ContractError;
+
+/// Adds the given contracts to the whitelist of swap adapters, which external AMMs can be traded
+/// through as part of a route, and removes the given ones. Only the owner can do this.
+pub fn update_swap_adapters(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    for adapter in &add {
+        let adapter = deps.api.addr_validate(adapter)?;
+        SWAP_ADAPTERS.save(deps.storage, &adapter, &true)?;
+    }
+
+    for adapter in &remove {
+        let adapter = deps.api.addr_validate(adapter)?;
+        SWAP_ADAPTERS.remove(deps.storage, &adapter);
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "update_swap_adapters".to_string()),
+        ("added", add.join(",")),
+        ("removed", remove.join(",")),
+    ]))
+}
+
+/// Swaps `offer_asset`, held by the contract, for at least `min_return` of `ask_denom` through the
+/// given swap adapter, as a hop of a route. The route continued with `min_return`, the amount the
+/// adapter quoted, so the balance of the contract is measured right before the swap and checked
+/// once it's done, refunding whatever the adapter returned above the quote to `receiver`. Only the
+/// contract itself can do this, as it executes the external hops of its routes.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_through_adapter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    adapter_addr: String,
+    offer_asset: Coin,
+    ask_denom: String,
+    min_return: Uint128,
+    msg: Binary,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    ensure!(
+        info.sender == env.contract.address,
+        ContractError::Unauthorized
+    );
+
+    let adapter_addr = deps.api.addr_validate(&adapter_addr)?;
+    assert_swap_adapter(deps.storage, &adapter_addr)?;
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    let balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &ask_denom)?
+        .amount;
+
+    ADAPTER_SWAP_BUFFER.save(
+        deps.storage,
+        &AdapterSwapBuffer {
+            receiver: receiver.clone(),
+            quoted_asset: coin(min_return.u128(), &ask_denom),
+            balance_before,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_submessage(SubMsg::reply_on_success(
+            get_adapter_swap_msg(
+                adapter_addr.as_str(),
+                offer_asset.clone(),
+                ask_denom.clone(),
+                min_return,
+                msg,
+            )?,
+            ADAPTER_SWAP_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            ("action", "swap_through_adapter".to_string()),
+            ("adapter", adapter_addr.into_string()),
+            ("receiver", receiver.into_string()),
+            ("offer_asset", offer_asset.to_string()),
+            (
+                "quoted_asset",
+                coin(min_return.u128(), ask_denom).to_string(),
+            ),
+        ]))
+}
+
+/// Settles a swap through a swap adapter once it's done. The output the adapter actually sent is
+/// measured from the balance of the contract, which must cover the quote the route continued with,
+/// and whatever is above it is refunded to the receiver.
+pub(crate) fn settle_adapter_swap(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let AdapterSwapBuffer {
+        receiver,
+        quoted_asset,
+        balance_before,
+    } = ADAPTER_SWAP_BUFFER.load(deps.storage)?;
+    ADAPTER_SWAP_BUFFER.remove(deps.storage);
+
+    let returned_amount = deps
+        .querier
+        .query_balance(&env.contract.address, &quoted_asset.denom)?
+        .amount
+        .saturating_sub(balance_before);
+    ensure!(
+        returned_amount >= quoted_asset.amount,
+        ContractError::MinimumReceiveAssertion {
+            minimum_receive: quoted_asset.amount,
+            swap_amount: returned_amount,
+        }
+    );
+
+    let surplus = returned_amount.checked_sub(quoted_asset.amount)?;
+    let mut response = Response::default();
+    if !surplus.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![coin(surplus.u128(), &quoted_asset.denom)],
+        });
+    }
+
+    Ok(response.add_attributes(vec![
+        ("action", "settle_adapter_swap".to_string()),
+        ("receiver", receiver.into_string()),
+        (
+            "returned_asset",
+            coin(returned_amount.u128(), &quoted_asset.denom).to_string(),
+        ),
+        (
+            "refunded_surplus",
+            coin(surplus.u128(), quoted_asset.denom).to_string(),
+        ),
+    ]))
+}
@@ -0,0 +1,222 @@
+//! A mock querier answering the queries the pool manager makes to the farm manager and the bank,
+//! so integrating contracts can unit test their calls to the pool manager, e.g. providing
+//! liquidity into a locked position, without a full multi-test setup.
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coin, from_json, to_json_binary, BankQuery, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SupplyResponse, SystemError, SystemResult, Uint128, WasmQuery,
+};
+
+use mantra_dex_std::farm_manager::{Position, PositionsBy, PositionsResponse};
+
+/// Mocks the farm manager `Positions` query, the supply of token factory denoms and bank
+/// balances. Any other query is answered by the default [MockQuerier].
+pub struct PoolManagerMockQuerier {
+    base: MockQuerier,
+    farm_manager_addr: Option<String>,
+    positions: Vec<Position>,
+    supplies: BTreeMap<String, Uint128>,
+}
+
+/// Creates the dependencies for unit tests, with the given balances and a
+/// [PoolManagerMockQuerier] to configure the rest of the queries.
+pub fn mock_dependencies(
+    balances: &[(&str, &[Coin])],
+) -> OwnedDeps<MockStorage, MockApi, PoolManagerMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: PoolManagerMockQuerier::new(balances),
+        custom_query_type: PhantomData,
+    }
+}
+
+impl PoolManagerMockQuerier {
+    pub fn new(balances: &[(&str, &[Coin])]) -> Self {
+        PoolManagerMockQuerier {
+            base: MockQuerier::new(balances),
+            farm_manager_addr: None,
+            positions: vec![],
+            supplies: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the address of the farm manager the `Positions` query is answered for.
+    pub fn with_farm_manager(&mut self, farm_manager_addr: impl Into<String>) -> &mut Self {
+        self.farm_manager_addr = Some(farm_manager_addr.into());
+        self
+    }
+
+    /// Sets the positions returned by the farm manager, replacing the previous ones.
+    pub fn with_positions(&mut self, positions: Vec<Position>) -> &mut Self {
+        self.positions = positions;
+        self
+    }
+
+    /// Sets the supply of the given denom, e.g. the LP denom of a pool, regardless of the
+    /// balances holding it.
+    pub fn with_supply(&mut self, denom: impl Into<String>, amount: Uint128) -> &mut Self {
+        self.supplies.insert(denom.into(), amount);
+        self
+    }
+
+    /// Sets the balances of the given address, replacing the previous ones.
+    pub fn with_balance(&mut self, address: impl Into<String>, balance: Vec<Coin>) -> &mut Self {
+        self.base.bank.update_balance(address, balance);
+        self
+    }
+
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if Some(contract_addr) == self.farm_manager_addr.as_ref() =>
+            {
+                match from_json(msg) {
+                    Ok(mantra_dex_std::farm_manager::QueryMsg::Positions {
+                        filter_by,
+                        open_state,
+                        ..
+                    }) => self.query_positions(filter_by, open_state),
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "farm manager query other than Positions".to_string(),
+                    }),
+                }
+            }
+            QueryRequest::Bank(BankQuery::Supply { denom })
+                if self.supplies.contains_key(denom) =>
+            {
+                let amount = self.supplies[denom];
+
+                SystemResult::Ok(
+                    to_json_binary(&SupplyResponse::new(coin(amount.u128(), denom))).into(),
+                )
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+
+    /// Answers the `Positions` query as the farm manager does, failing when filtering by an
+    /// identifier no position has.
+    fn query_positions(
+        &self,
+        filter_by: Option<PositionsBy>,
+        open_state: Option<bool>,
+    ) -> QuerierResult {
+        let positions = match filter_by {
+            Some(PositionsBy::Identifier(identifier)) => {
+                match self
+                    .positions
+                    .iter()
+                    .find(|position| position.identifier == identifier)
+                {
+                    Some(position) => vec![position.clone()],
+                    None => {
+                        return SystemResult::Ok(ContractResult::Err(format!(
+                            "No position found with the given identifier: {identifier}"
+                        )))
+                    }
+                }
+            }
+            Some(PositionsBy::Receiver(receiver)) => self
+                .positions
+                .iter()
+                .filter(|position| position.receiver.as_str() == receiver)
+                .filter(|position| match open_state {
+                    Some(open) => position.open == open,
+                    None => true,
+                })
+                .cloned()
+                .collect(),
+            None => self.positions.clone(),
+        };
+
+        SystemResult::Ok(to_json_binary(&PositionsResponse { positions }).into())
+    }
+}
+
+impl Querier for PoolManagerMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
+            Ok(request) => request,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {e}"),
+                    request: bin_request.into(),
+                })
+            }
+        };
+
+        self.handle_query(&request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{coin, Addr, Deps, StdResult, Uint128};
+
+    use mantra_dex_std::farm_manager::{Position, PositionsBy, PositionsResponse};
+
+    use super::mock_dependencies;
+
+    fn query_positions(deps: Deps, filter_by: PositionsBy) -> StdResult<PositionsResponse> {
+        deps.querier.query_wasm_smart(
+            "farm_manager",
+            &mantra_dex_std::farm_manager::QueryMsg::Positions {
+                filter_by: Some(filter_by),
+                open_state: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    #[test]
+    fn stubs_the_queries_of_the_pool_manager() {
+        let mut deps = mock_dependencies(&[("pool_manager", &[coin(1_000, "uom")])]);
+        let position = Position {
+            identifier: "u-lock".to_string(),
+            lp_asset: coin(1_000, "factory/pool_manager/o.om_usd.LP"),
+            unlocking_duration: 86_400,
+            open: true,
+            expiring_at: None,
+            receiver: Addr::unchecked("user"),
+        };
+        deps.querier
+            .with_farm_manager("farm_manager")
+            .with_positions(vec![position.clone()])
+            .with_supply("factory/pool_manager/o.om_usd.LP", Uint128::new(5_000));
+
+        let positions =
+            query_positions(deps.as_ref(), PositionsBy::Identifier("u-lock".to_string())).unwrap();
+        assert_eq!(positions.positions, vec![position.clone()]);
+
+        // unknown positions fail like they do in the farm manager
+        assert!(query_positions(
+            deps.as_ref(),
+            PositionsBy::Identifier("u-other".to_string())
+        )
+        .is_err());
+
+        let positions =
+            query_positions(deps.as_ref(), PositionsBy::Receiver("user".to_string())).unwrap();
+        assert_eq!(positions.positions, vec![position]);
+
+        let supply = deps
+            .as_ref()
+            .querier
+            .query_supply("factory/pool_manager/o.om_usd.LP")
+            .unwrap();
+        assert_eq!(supply.amount, Uint128::new(5_000));
+
+        let balance = deps
+            .as_ref()
+            .querier
+            .query_balance("pool_manager", "uom")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(1_000));
+    }
+}
@@ -0,0 +1,253 @@
+//! Sanity properties of the pool math, which hold whatever the solvers do internally. They're
+//! public behind the `testing` feature, so auditors and integrators can check them against the
+//! pool math without reading its internals.
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+
+use mantra_dex_std::fee::{Fee, PoolFee};
+use mantra_dex_std::pool_manager::{PoolInfo, PoolType};
+
+use crate::helpers::{compute_swap, get_asset_scaling_factors};
+
+/// The lowest amplification the properties are checked with.
+pub const MIN_AMP: u64 = 1;
+/// The highest amplification the properties are checked with.
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// Builds a two asset pool of the given type with the given reserves and decimals, charging no
+/// fees.
+pub fn build_pool(pool_type: PoolType, reserves: [Uint128; 2], decimals: [u8; 2]) -> PoolInfo {
+    PoolInfo {
+        pool_identifier: "o.properties".to_string(),
+        admin: None,
+        asset_denoms: vec!["uaaa".to_string(), "ubbb".to_string()],
+        pool_type,
+        lp_denom: "factory/pool_manager/o.properties.LP".to_string(),
+        asset_decimals: decimals.to_vec(),
+        asset_scaling_factors: get_asset_scaling_factors(&decimals).unwrap(),
+        pool_fees: no_fees(),
+        assets: vec![
+            coin(reserves[0].u128(), "uaaa"),
+            coin(reserves[1].u128(), "ubbb"),
+        ],
+    }
+}
+
+/// Quotes a swap of `offer_amount` of the first asset of the pool for the second one.
+pub fn quote(pool_info: &PoolInfo, offer_amount: Uint128) -> Uint128 {
+    compute_swap(pool_info, 0, 1, offer_amount, no_fees(), None, None)
+        .unwrap()
+        .return_amount
+}
+
+/// Quotes a swap at a constant sum, i.e. one to one once the decimals are accounted for.
+pub fn constant_sum_quote(pool_info: &PoolInfo, offer_amount: Uint128) -> Uint128 {
+    offer_amount
+        .multiply_ratio(
+            pool_info.asset_scaling_factors[0],
+            pool_info.asset_scaling_factors[1],
+        )
+        .min(pool_info.assets[1].amount)
+}
+
+/// Asserts that with a very large amplification, stableswap quotes converge to a constant sum for
+/// balanced pools, i.e. are within `tolerance` of it, as long as the swap doesn't drain the pool.
+/// Quotes may be off by a unit because of rounding.
+#[track_caller]
+pub fn assert_stableswap_converges_to_constant_sum(
+    reserve: Uint128,
+    decimals: u8,
+    offer_amount: Uint128,
+    tolerance: Decimal,
+) {
+    let pool_info = build_pool(
+        PoolType::StableSwap { amp: MAX_AMP },
+        [reserve, reserve],
+        [decimals, decimals],
+    );
+
+    let stableswap_quote = quote(&pool_info, offer_amount);
+    let constant_sum_quote = constant_sum_quote(&pool_info, offer_amount);
+
+    assert!(
+        stableswap_quote <= constant_sum_quote + Uint128::one(),
+        "stableswap quote {stableswap_quote} exceeds the constant sum quote {constant_sum_quote}"
+    );
+    assert!(
+        constant_sum_quote.saturating_sub(stableswap_quote) <= constant_sum_quote.mul_ceil(tolerance),
+        "stableswap quote {stableswap_quote} with amp {MAX_AMP} is not within {tolerance} of the constant sum quote {constant_sum_quote}"
+    );
+}
+
+/// Asserts that stableswap quotes for balanced pools lie between the constant product quote and
+/// the constant sum one, never decrease as the amplification grows, and are closer to the
+/// constant product quote than to the constant sum one at the lowest amplification. Quotes may be
+/// off by a unit because of rounding.
+#[track_caller]
+pub fn assert_stableswap_bounded_by_constant_product(
+    reserve: Uint128,
+    decimals: u8,
+    offer_amount: Uint128,
+    amps: &[u64],
+) {
+    let constant_product_pool = build_pool(
+        PoolType::ConstantProduct,
+        [reserve, reserve],
+        [decimals, decimals],
+    );
+    let constant_product_quote = quote(&constant_product_pool, offer_amount);
+    let constant_sum_quote = constant_sum_quote(&constant_product_pool, offer_amount);
+
+    let mut amps = amps.to_vec();
+    amps.push(MIN_AMP);
+    amps.sort_unstable();
+    amps.dedup();
+
+    let mut previous_quote = Uint128::zero();
+    for amp in amps {
+        let pool_info = build_pool(
+            PoolType::StableSwap { amp },
+            [reserve, reserve],
+            [decimals, decimals],
+        );
+        let stableswap_quote = quote(&pool_info, offer_amount);
+
+        assert!(
+            stableswap_quote + Uint128::one() >= constant_product_quote
+                && stableswap_quote <= constant_sum_quote + Uint128::one(),
+            "stableswap quote {stableswap_quote} with amp {amp} is not between the constant product quote {constant_product_quote} and the constant sum quote {constant_sum_quote}"
+        );
+        assert!(
+            stableswap_quote + Uint128::one() >= previous_quote,
+            "stableswap quote {stableswap_quote} with amp {amp} is lower than {previous_quote} with a lower amp"
+        );
+
+        if amp == MIN_AMP {
+            assert!(
+                stableswap_quote.abs_diff(constant_product_quote)
+                    <= constant_sum_quote.abs_diff(stableswap_quote) + Uint128::one(),
+                "stableswap quote {stableswap_quote} with amp {MIN_AMP} is closer to the constant sum quote {constant_sum_quote} than to the constant product quote {constant_product_quote}"
+            );
+        }
+
+        previous_quote = stableswap_quote;
+    }
+}
+
+/// Asserts that quotes never decrease as the offer amount grows, while the price the offer asset
+/// is sold at never improves, for the given amounts. Quotes may be off by a unit because of
+/// rounding.
+#[track_caller]
+pub fn assert_quotes_monotonic(pool_info: &PoolInfo, offer_amounts: &[Uint128]) {
+    let mut offer_amounts = offer_amounts.to_vec();
+    offer_amounts.sort_unstable();
+    offer_amounts.dedup();
+
+    let mut previous: Option<(Uint128, Uint128)> = None;
+    for offer_amount in offer_amounts {
+        let Ok(swap) = compute_swap(pool_info, 0, 1, offer_amount, no_fees(), None, None) else {
+            // quotes stop once the pool can't pay out
+            break;
+        };
+        let return_amount = swap.return_amount;
+
+        if let Some((previous_offer_amount, previous_return_amount)) = previous {
+            assert!(
+                return_amount >= previous_return_amount,
+                "quote {return_amount} for {offer_amount} is lower than {previous_return_amount} for {previous_offer_amount}"
+            );
+            // return_amount / offer_amount <= previous_return_amount / previous_offer_amount
+            assert!(
+                return_amount.full_mul(previous_offer_amount)
+                    <= (previous_return_amount + Uint128::one()).full_mul(offer_amount),
+                "price {return_amount}/{offer_amount} is better than {previous_return_amount}/{previous_offer_amount} for a smaller offer"
+            );
+        }
+
+        previous = Some((offer_amount, return_amount));
+    }
+}
+
+fn no_fees() -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::zero(),
+        },
+        swap_fee: Fee {
+            share: Decimal::zero(),
+        },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        extra_fees: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{Decimal, Uint128};
+    use proptest::prelude::*;
+
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn stableswap_converges_to_constant_sum_with_large_amp(
+            reserve in 1_000_000_000u128..1_000_000_000_000_000,
+            decimals in 6u8..=18,
+            offer_share in 1u128..=100,
+        ) {
+            // swaps of up to a tenth of the reserves
+            let offer_amount = Uint128::new(reserve * offer_share / 1_000);
+
+            assert_stableswap_converges_to_constant_sum(
+                Uint128::new(reserve),
+                decimals,
+                offer_amount,
+                Decimal::permille(1),
+            );
+        }
+
+        #[test]
+        fn stableswap_is_bounded_by_constant_product(
+            reserve in 1_000_000_000u128..1_000_000_000_000_000,
+            offer_share in 1u128..=500,
+            amps in prop::collection::vec(MIN_AMP..=MAX_AMP, 1..8),
+        ) {
+            let offer_amount = Uint128::new(reserve * offer_share / 1_000);
+
+            assert_stableswap_bounded_by_constant_product(
+                Uint128::new(reserve),
+                6,
+                offer_amount,
+                &amps,
+            );
+        }
+
+        #[test]
+        fn quotes_are_monotonic_in_input_size(
+            reserves in (1_000_000u128..1_000_000_000_000, 1_000_000u128..1_000_000_000_000),
+            stable in any::<bool>(),
+            amp in MIN_AMP..=MAX_AMP,
+            offer_amounts in prop::collection::vec(1u128..1_000_000_000_000, 2..16),
+        ) {
+            let pool_type = if stable {
+                PoolType::StableSwap { amp }
+            } else {
+                PoolType::ConstantProduct
+            };
+            let pool_info = build_pool(
+                pool_type,
+                [Uint128::new(reserves.0), Uint128::new(reserves.1)],
+                [6, 6],
+            );
+
+            assert_quotes_monotonic(
+                &pool_info,
+                &offer_amounts.into_iter().map(Uint128::new).collect::<Vec<_>>(),
+            );
+        }
+    }
+}
@@ -220,6 +220,93 @@ mod pool_creation_failures {
         );
     }
 
+    #[test]
+    fn pool_fees_above_caps() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_001u128, "uwhale".to_string()),
+                coin(1_000_000_001u128, "uusd".to_string()),
+                coin(1_000_000_001u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let asset_infos = vec!["uwhale".to_string(), "uusd".to_string()];
+
+        // a single component above its cap of 20%
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::zero(),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(25),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite.instantiate_default().create_pool(
+            &creator,
+            asset_infos.clone(),
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            None,
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PoolFeeCapExceeded { fee, .. } => {
+                        assert_eq!(fee, "swap_fee");
+                    }
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::PoolFeeCapExceeded")
+                    }
+                }
+            },
+        );
+
+        // all components within their caps, but the total above the cap of 50%
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(20),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(20),
+            },
+            burn_fee: Fee {
+                share: Decimal::percent(5),
+            },
+            extra_fees: vec![Fee {
+                share: Decimal::percent(10),
+            }],
+        };
+
+        suite.create_pool(
+            &creator,
+            asset_infos.clone(),
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            None,
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PoolFeeCapExceeded { fee, .. } => {
+                        assert_eq!(fee, "total_fees");
+                    }
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::PoolFeeCapExceeded")
+                    }
+                }
+            },
+        );
+    }
+
     // Only 1 asset provided, or none
     #[test]
     fn invalid_assets_on_pool_creation() {
@@ -1748,6 +1835,167 @@ mod router {
             },
         );
     }
+
+    #[test]
+    fn exact_out_swap_operations() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::bps(50),
+            },
+            swap_fee: Fee {
+                share: Decimal::bps(50),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uluna".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.uluna.uusd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uluna"), coin(1_000_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let swap_operations = vec![
+            mantra_dex_std::pool_manager::SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: "o.whale.uluna".to_string(),
+            },
+            mantra_dex_std::pool_manager::SwapOperation::MantraSwap {
+                token_in_denom: "uluna".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.uluna.uusd".to_string(),
+            },
+        ];
+
+        let offer_needed = std::cell::RefCell::new(Uint128::zero());
+        let alice_uwhale = std::cell::RefCell::new(Uint128::zero());
+        let alice_uusd = std::cell::RefCell::new(Uint128::zero());
+
+        suite
+            .query_reverse_simulate_swap_operations(
+                Uint128::new(10_000),
+                swap_operations.clone(),
+                |result| {
+                    *offer_needed.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            // not enough funds to get the ask amount out of the route
+            .execute_swap_operations_exact_out(
+                &alice,
+                swap_operations.clone(),
+                None,
+                None,
+                Some(Decimal::percent(5)),
+                Some(Uint128::new(10_000)),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MaxOfferAmountExceeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MaxOfferAmountExceeded"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                *alice_uwhale.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_uusd.borrow_mut() = result.unwrap().amount;
+            })
+            .execute_swap_operations_exact_out(
+                &alice,
+                swap_operations,
+                None,
+                None,
+                Some(Decimal::percent(5)),
+                Some(Uint128::new(10_000)),
+                vec![coin(50_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // only what was needed was taken, the rest was refunded
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                let spent = alice_uwhale
+                    .borrow()
+                    .checked_sub(result.unwrap().amount)
+                    .unwrap();
+                assert_approx_eq!(spent, *offer_needed.borrow(), "0.001");
+                assert!(spent < Uint128::new(50_000));
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                let received = result
+                    .unwrap()
+                    .amount
+                    .checked_sub(*alice_uusd.borrow())
+                    .unwrap();
+                assert!(received >= Uint128::new(10_000));
+                assert_approx_eq!(received, Uint128::new(10_000), "0.001");
+            });
+    }
 }
 
 mod swapping {
@@ -1755,7 +2003,7 @@ mod swapping {
 
     use cosmwasm_std::assert_approx_eq;
 
-    use mantra_dex_std::pool_manager::PoolType;
+    use mantra_dex_std::pool_manager::{FeePayer, PoolType};
 
     use super::*;
 
@@ -2905,59 +3153,188 @@ mod swapping {
                 );
             });
     }
-}
-
-mod ownership {
-    use mantra_dex_std::pool_manager::FeatureToggle;
-
-    use super::*;
 
     #[test]
-    fn verify_ownership() {
+    fn swap_with_fee_payer() {
         let mut suite = TestingSuite::default_with_balances(
-            vec![],
+            vec![
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+            ],
             StargateMock::new("uom".to_string(), "8888".to_string()),
         );
         let creator = suite.creator();
-        let other = suite.senders[1].clone();
-        let unauthorized = suite.senders[2].clone();
+        let alice = suite.senders[1].clone();
+        let relayer = suite.senders[2].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::zero(),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
 
         suite
             .instantiate_default()
-            .query_ownership(|result| {
-                let ownership = result.unwrap();
-                assert_eq!(Addr::unchecked(ownership.owner.unwrap()), creator);
-            })
-            .update_ownership(
-                &unauthorized,
-                cw_ownable::Action::TransferOwnership {
-                    new_owner: other.to_string(),
-                    expiry: None,
-                },
-                |result| {
-                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
-
-                    match err {
-                        ContractError::OwnershipError { .. } => {}
-                        _ => {
-                            panic!("Wrong error type, should return ContractError::OwnershipError")
-                        }
-                    }
-                },
-            )
-            .update_ownership(
+            .add_one_epoch()
+            .create_pool(
                 &creator,
-                cw_ownable::Action::TransferOwnership {
-                    new_owner: other.to_string(),
-                    expiry: None,
-                },
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
                 |result| {
                     result.unwrap();
                 },
             )
-            .update_ownership(&other, cw_ownable::Action::AcceptOwnership, |result| {
-                result.unwrap();
-            })
+            .provide_liquidity(
+                &creator,
+                "p.1".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(10_000_000u128, "uom".to_string()),
+                    coin(10_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let return_amount = RefCell::new(Uint128::zero());
+        let alice_balance = RefCell::new(Uint128::zero());
+        let relayer_balance = RefCell::new(Uint128::zero());
+
+        suite
+            .query_simulation(
+                "p.1".to_string(),
+                coin(1_000u128, "uom"),
+                "uusd".to_string(),
+                |result| {
+                    *return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&relayer.to_string(), "uusd", |result| {
+                *relayer_balance.borrow_mut() = result.unwrap().amount;
+            })
+            // the fee payer can't take the whole output
+            .swap_with_fee_payer(
+                &alice,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "p.1".to_string(),
+                Some(FeePayer {
+                    address: relayer.to_string(),
+                    amount: Uint128::new(1_000),
+                }),
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidFeePayerAmount { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidFeePayerAmount"
+                        ),
+                    }
+                },
+            )
+            .swap_with_fee_payer(
+                &alice,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "p.1".to_string(),
+                Some(FeePayer {
+                    address: relayer.to_string(),
+                    amount: Uint128::new(50),
+                }),
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *alice_balance.borrow() + *return_amount.borrow() - Uint128::new(50)
+                );
+            })
+            .query_balance(&relayer.to_string(), "uusd", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *relayer_balance.borrow() + Uint128::new(50)
+                );
+            });
+    }
+}
+
+mod ownership {
+    use mantra_dex_std::pool_manager::{FeatureToggle, PoolFeeCaps};
+
+    use super::*;
+
+    #[test]
+    fn verify_ownership() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+        let unauthorized = suite.senders[2].clone();
+
+        suite
+            .instantiate_default()
+            .query_ownership(|result| {
+                let ownership = result.unwrap();
+                assert_eq!(Addr::unchecked(ownership.owner.unwrap()), creator);
+            })
+            .update_ownership(
+                &unauthorized,
+                cw_ownable::Action::TransferOwnership {
+                    new_owner: other.to_string(),
+                    expiry: None,
+                },
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_ownership(
+                &creator,
+                cw_ownable::Action::TransferOwnership {
+                    new_owner: other.to_string(),
+                    expiry: None,
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_ownership(&other, cw_ownable::Action::AcceptOwnership, |result| {
+                result.unwrap();
+            })
             .query_ownership(|result| {
                 let ownership = result.unwrap();
                 assert_eq!(Addr::unchecked(ownership.owner.unwrap()), other);
@@ -2985,6 +3362,7 @@ mod ownership {
             None,
             None,
             None,
+            None,
             |result| {
                 let err = result.unwrap_err().downcast::<ContractError>().unwrap();
 
@@ -3006,29 +3384,35 @@ mod ownership {
         );
         let creator = suite.creator();
         let other = suite.senders[1].clone();
-        let another = suite.senders[2].clone();
 
         suite.instantiate_default();
-        let current_pool_creation_fee = suite.query_config().pool_creation_fee;
+        let current_pool_creation_fee = suite.query_config().pool_creation_fees[0].clone();
         let initial_config = suite.query_config();
 
         suite.update_config(
             &creator,
             Some(other),
-            Some(another),
-            Some(coin(
+            None,
+            Some(vec![coin(
                 current_pool_creation_fee
                     .amount
                     .checked_add(Uint128::from(1u32))
                     .unwrap()
                     .u128(),
                 current_pool_creation_fee.denom,
-            )),
+            )]),
             Some(FeatureToggle {
                 deposits_enabled: false,
                 swaps_enabled: false,
                 withdrawals_enabled: false,
             }),
+            Some(PoolFeeCaps {
+                max_swap_fee: Decimal::percent(5),
+                max_protocol_fee: Decimal::percent(5),
+                max_burn_fee: Decimal::percent(5),
+                max_extra_fees: Decimal::percent(5),
+                max_total_fee: Decimal::percent(10),
+            }),
             |res| {
                 res.unwrap();
             },
@@ -3036,9 +3420,69 @@ mod ownership {
 
         let config = suite.query_config();
         assert_ne!(config.fee_collector_addr, initial_config.fee_collector_addr);
-        assert_ne!(config.pool_creation_fee, initial_config.pool_creation_fee);
+        assert_ne!(config.pool_creation_fees, initial_config.pool_creation_fees);
         assert_ne!(config.feature_toggle, initial_config.feature_toggle);
-        assert_ne!(config.farm_manager_addr, initial_config.farm_manager_addr);
+        assert_ne!(config.pool_fee_caps, initial_config.pool_fee_caps);
+    }
+
+    #[test]
+    fn rejects_invalid_pool_fee_caps() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        suite
+            .instantiate_default()
+            // total fee cap can't be 100%
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                Some(PoolFeeCaps {
+                    max_swap_fee: Decimal::percent(5),
+                    max_protocol_fee: Decimal::percent(5),
+                    max_burn_fee: Decimal::percent(5),
+                    max_extra_fees: Decimal::percent(5),
+                    max_total_fee: Decimal::percent(100),
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolFeeCaps => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolFeeCaps"
+                        ),
+                    }
+                },
+            )
+            // a component cap can't be above the total cap
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                Some(PoolFeeCaps {
+                    max_swap_fee: Decimal::percent(15),
+                    max_protocol_fee: Decimal::percent(5),
+                    max_burn_fee: Decimal::percent(5),
+                    max_extra_fees: Decimal::percent(5),
+                    max_total_fee: Decimal::percent(10),
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolFeeCaps => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolFeeCaps"
+                        ),
+                    }
+                },
+            );
     }
 }
 
@@ -5696,7 +6140,7 @@ mod provide_liquidity {
 }
 
 mod multiple_pools {
-    use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+    use cosmwasm_std::{coin, Coin, Decimal, Order, Uint128};
 
     use mantra_common_testing::multi_test::stargate_mock::StargateMock;
     use mantra_dex_std::fee::{Fee, PoolFee};
@@ -5783,6 +6227,98 @@ mod multiple_pools {
             });
     }
 
+    #[test]
+    fn paginates_pools_in_both_orders() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(10),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(7),
+            },
+            burn_fee: Fee {
+                share: Decimal::percent(3),
+            },
+            extra_fees: vec![],
+        };
+
+        suite.instantiate_default().add_one_epoch();
+
+        for identifier in ["pool.1", "pool.2", "pool.3"] {
+            suite.create_pool(
+                &creator,
+                vec!["uom".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some(identifier.to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_pools(None, None, Some(2), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.total_count, 3);
+                assert_eq!(response.pools.len(), 2);
+                assert_eq!(response.pools[0].pool_info.pool_identifier, "o.pool.1");
+                assert_eq!(response.pools[1].pool_info.pool_identifier, "o.pool.2");
+                assert_eq!(response.next_key, Some("o.pool.2".to_string()));
+            })
+            .query_pools_with_order(
+                None,
+                Some("o.pool.2".to_string()),
+                Some(2),
+                Some(Order::Ascending),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.total_count, 3);
+                    assert_eq!(response.pools.len(), 1);
+                    assert_eq!(response.pools[0].pool_info.pool_identifier, "o.pool.3");
+                    assert_eq!(response.next_key, None);
+                },
+            )
+            .query_pools_with_order(None, None, Some(2), Some(Order::Descending), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.total_count, 3);
+                assert_eq!(response.pools.len(), 2);
+                assert_eq!(response.pools[0].pool_info.pool_identifier, "o.pool.3");
+                assert_eq!(response.pools[1].pool_info.pool_identifier, "o.pool.2");
+                assert_eq!(response.next_key, Some("o.pool.2".to_string()));
+            })
+            .query_pools_with_order(
+                None,
+                Some("o.pool.2".to_string()),
+                Some(2),
+                Some(Order::Descending),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.pools.len(), 1);
+                    assert_eq!(response.pools[0].pool_info.pool_identifier, "o.pool.1");
+                    assert_eq!(response.next_key, None);
+                },
+            )
+            .query_pools(Some("o.pool.2".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.total_count, 3);
+                assert_eq!(response.pools.len(), 1);
+                assert_eq!(response.next_key, None);
+            });
+    }
+
     #[test]
     fn provide_liquidity_to_multiple_pools_check_fees() {
         let mut suite = TestingSuite::default_with_balances(
@@ -6032,9 +6568,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.whale.uluna.pool.1".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.1.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(1001000, "uwhale"), coin(999070, "uluna")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_1.clone(),
@@ -6076,9 +6614,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.whale.uluna.pool.1".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.1.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(999_140, "uwhale"), coin(1_001_070, "uluna")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_1.clone(),
@@ -6122,9 +6662,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.whale.uluna.pool.2".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.2.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(1001000, "uwhale"), coin(999_150, "uluna")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_2.clone(),
@@ -6159,9 +6701,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.whale.uluna.pool.2".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.2.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(999_300, "uwhale"), coin(1_001_150, "uluna")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_2.clone(),
@@ -6206,9 +6750,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.uluna.uusd.pool.1".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uluna".to_string(), "uusd".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.uluna.uusd.pool.1.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(1003000, "uluna"), coin(997_218, "uusd")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_1.clone(),
@@ -6248,9 +6794,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.uluna.uusd.pool.1".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uluna".to_string(), "uusd".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.uluna.uusd.pool.1.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(1_001_599, "uluna"), coin(998_718, "uusd")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_1.clone(),
@@ -6346,9 +6894,11 @@ mod multiple_pools {
             // this should have not changed since last time, since we didn't touch this pool
             assert_eq!(pool_info, PoolInfo {
                 pool_identifier: "o.whale.uluna.pool.1".to_string(),
+                admin: Some(creator.clone()),
                 asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                 lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.1.LP".to_string(),
                 asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                 assets: vec![coin(999_140, "uwhale"), coin(1_001_070, "uluna")],
                 pool_type: PoolType::ConstantProduct,
                 pool_fees: pool_fees_1.clone(),
@@ -6365,9 +6915,11 @@ mod multiple_pools {
 
                 assert_eq!(pool_info, PoolInfo {
                     pool_identifier: "o.whale.uluna.pool.2".to_string(),
+                    admin: Some(creator.clone()),
                     asset_denoms: vec!["uwhale".to_string(), "uluna".to_string()],
                     lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.whale.uluna.pool.2.LP".to_string(),
                     asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                     assets: vec![coin(1_004_300, "uwhale"), coin(996_913, "uluna")],
                     pool_type: PoolType::ConstantProduct,
                     pool_fees: pool_fees_2.clone(),
@@ -6383,9 +6935,11 @@ mod multiple_pools {
 
             assert_eq!(pool_info, PoolInfo {
                 pool_identifier: "o.uluna.uusd.pool.1".to_string(),
+                admin: Some(creator.clone()),
                 asset_denoms: vec!["uluna".to_string(), "uusd".to_string()],
                 lp_denom: "factory/mantra1zwv6feuzhy6a9wekh96cd57lsarmqlwxdypdsplw6zhfncqw6ftqlydlr9/o.uluna.uusd.pool.1.LP".to_string(),
                 asset_decimals: vec![6u8, 6u8],
+                    asset_scaling_factors: vec![Uint128::one(), Uint128::one()],
                 assets: vec![coin(1_005_587, "uluna"), coin(995_035, "uusd")],
                 pool_type: PoolType::ConstantProduct,
                 pool_fees: pool_fees_1.clone(),
@@ -6593,8 +7147,9 @@ mod multiple_pools {
                     "uusdt".to_string(),
                     "uusd".to_string(),
                     "uom".to_string(),
+                    "uwhale".to_string(),
                 ],
-                vec![6u8, 6u8, 6u8, 6u8, 6u8],
+                vec![6u8, 6u8, 6u8, 6u8, 6u8, 6u8],
                 pool_fees.clone(),
                 PoolType::StableSwap { amp: 80 },
                 Some("stableswap".to_string()),
@@ -6603,7 +7158,9 @@ mod multiple_pools {
                     let err = result.unwrap_err().downcast::<ContractError>().unwrap();
 
                     match err {
-                        ContractError::TooManyAssets { .. } => {}
+                        ContractError::TooManyAssets { max_assets, .. } => {
+                            assert_eq!(max_assets, 5)
+                        }
                         _ => {
                             panic!("Wrong error type, should return ContractError::TooManyAssets")
                         }
@@ -6731,7 +7288,7 @@ mod query_simulations {
     use cosmwasm_std::{assert_approx_eq, coin, Coin, Decimal, Uint128};
     use mantra_common_testing::multi_test::stargate_mock::StargateMock;
     use mantra_dex_std::fee::{Fee, PoolFee};
-    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+    use mantra_dex_std::pool_manager::{PoolType, SimulationRequest, SwapOperation};
     use std::cell::RefCell;
 
     #[test]
@@ -7624,4 +8181,14459 @@ mod query_simulations {
             },
         );
     }
+
+    #[test]
+    fn simulation_queries_with_reserve_overrides() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_001u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_001u128, "uusd".to_string()),
+                coin(1_000_000_001u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(2u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let current_simulation = RefCell::new(Uint128::zero());
+        let current_reverse_simulation = RefCell::new(Uint128::zero());
+
+        suite
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *current_simulation.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .query_reverse_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    *current_reverse_simulation.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            // overriding with the current reserves yields the same result
+            .query_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, *current_simulation.borrow());
+                },
+            )
+            .query_reverse_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uluna"),
+                "uwhale".to_string(),
+                vec![coin(1_000_000u128, "uwhale")],
+                |result| {
+                    assert_eq!(
+                        result.unwrap().offer_amount,
+                        *current_reverse_simulation.borrow()
+                    );
+                },
+            )
+            // with twice as much uwhale in the pool, uwhale is worth half as much
+            .query_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![coin(2_000_000u128, "uwhale")],
+                |result| {
+                    let return_amount = result.unwrap().return_amount;
+                    assert_approx_eq!(
+                        return_amount.u128() * 2,
+                        current_simulation.borrow().u128(),
+                        "0.01"
+                    );
+                },
+            )
+            .query_reverse_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uluna"),
+                "uwhale".to_string(),
+                vec![coin(2_000_000u128, "uwhale")],
+                |result| {
+                    let offer_amount = result.unwrap().offer_amount;
+                    assert_approx_eq!(
+                        offer_amount.u128(),
+                        current_reverse_simulation.borrow().u128() * 2,
+                        "0.01"
+                    );
+                },
+            )
+            // the pool state is untouched
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, *current_simulation.borrow());
+                },
+            )
+            // overrides must belong to the pool and can't be duplicated
+            .query_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![coin(2_000_000u128, "uusd")],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("Invalid reserve overrides"));
+                },
+            )
+            .query_simulation_with_reserves(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![coin(2_000_000u128, "uwhale"), coin(1_000_000u128, "uwhale")],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("Invalid reserve overrides"));
+                },
+            );
+    }
+
+    #[test]
+    fn batch_simulation_query() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_001u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_001u128, "uusd".to_string()),
+                coin(1_000_000_001u128, "uusdc".to_string()),
+                coin(1_000_000_001u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1u64),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(2u64),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::StableSwap { amp: 85 },
+                Some("uusd.uusdc".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.uusd.uusdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uusd".to_string()),
+                    coin(1_000_000u128, "uusdc".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let expected_results = RefCell::new(vec![]);
+
+        suite
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    expected_results.borrow_mut().push(result.unwrap());
+                },
+            )
+            .query_simulation(
+                "o.uusd.uusdc".to_string(),
+                coin(50_000u128, "uusdc"),
+                "uusd".to_string(),
+                |result| {
+                    expected_results.borrow_mut().push(result.unwrap());
+                },
+            )
+            .query_batch_simulation(
+                vec![
+                    SimulationRequest {
+                        offer_asset: coin(10_000u128, "uwhale"),
+                        ask_asset_denom: "uluna".to_string(),
+                        pool_identifier: "o.whale.uluna".to_string(),
+                    },
+                    SimulationRequest {
+                        offer_asset: coin(50_000u128, "uusdc"),
+                        ask_asset_denom: "uusd".to_string(),
+                        pool_identifier: "o.uusd.uusdc".to_string(),
+                    },
+                ],
+                |result| {
+                    assert_eq!(result.unwrap().simulations, *expected_results.borrow());
+                },
+            )
+            // a single invalid simulation fails the whole batch
+            .query_batch_simulation(
+                vec![
+                    SimulationRequest {
+                        offer_asset: coin(10_000u128, "uwhale"),
+                        ask_asset_denom: "uluna".to_string(),
+                        pool_identifier: "o.whale.uluna".to_string(),
+                    },
+                    SimulationRequest {
+                        offer_asset: coin(50_000u128, "uusdc"),
+                        ask_asset_denom: "uusd".to_string(),
+                        pool_identifier: "o.nonexistent".to_string(),
+                    },
+                ],
+                |result| {
+                    assert!(result.is_err());
+                },
+            )
+            .query_batch_simulation(
+                vec![
+                    SimulationRequest {
+                        offer_asset: coin(10_000u128, "uwhale"),
+                        ask_asset_denom: "uluna".to_string(),
+                        pool_identifier: "o.whale.uluna".to_string(),
+                    };
+                    101
+                ],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("Too many simulations requested"));
+                },
+            );
+    }
+}
+
+mod stop_loss {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Binary, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    const TWAP_WINDOW: u64 = 3_600u64;
+
+    fn setup_pool() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "uom.uusd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        suite
+    }
+
+    fn uom_to_uusd() -> Vec<SwapOperation> {
+        vec![SwapOperation::MantraSwap {
+            token_in_denom: "uom".to_string(),
+            token_out_denom: "uusd".to_string(),
+            pool_identifier: "o.uom.uusd".to_string(),
+        }]
+    }
+
+    #[test]
+    fn stop_loss_order_executes_once_triggered() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+        let keeper = suite.senders[3].clone();
+
+        suite
+            .place_stop_loss_order(
+                &alice,
+                uom_to_uusd(),
+                Decimal::percent(90),
+                Uint128::new(500),
+                Some(Decimal::percent(50)),
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_stop_loss_orders(Some(alice.to_string()), None, None, |result| {
+                let orders = result.unwrap().orders;
+                assert_eq!(orders.len(), 1);
+                assert_eq!(orders[0].id, 1);
+                assert_eq!(orders[0].offer_asset, coin(1_000u128, "uom"));
+                assert_eq!(orders[0].bounty, coin(100u128, "uusd"));
+            })
+            // the pool has no price history over the window yet
+            .execute_stop_loss_order(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InsufficientTwapHistory => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InsufficientTwapHistory"
+                    ),
+                }
+            })
+            .add_one_day()
+            // the price is still 1:1, the order can't be executed yet
+            .execute_stop_loss_order(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::StopLossNotTriggered { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::StopLossNotTriggered"
+                    ),
+                }
+            })
+            // bob dumps uom in the pool, pushing the price below the trigger
+            .swap(
+                &bob,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.uom.uusd".to_string(),
+                vec![coin(200_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the spot price is below the trigger, but the TWAP isn't within the same block
+            .execute_stop_loss_order(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::StopLossNotTriggered { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::StopLossNotTriggered"
+                    ),
+                }
+            })
+            // once the price held over the whole window, the TWAP is below the trigger too
+            .add_one_day();
+
+        let alice_uusd_before = RefCell::new(Uint128::zero());
+        let keeper_uusd_before = RefCell::new(Uint128::zero());
+
+        suite
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_uusd_before.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&keeper.to_string(), "uusd", |result| {
+                *keeper_uusd_before.borrow_mut() = result.unwrap().amount;
+            })
+            .execute_stop_loss_order(&keeper, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                let received = result
+                    .unwrap()
+                    .amount
+                    .checked_sub(*alice_uusd_before.borrow())
+                    .unwrap();
+                assert!(received >= Uint128::new(500));
+            })
+            .query_balance(&keeper.to_string(), "uusd", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *keeper_uusd_before.borrow() + Uint128::new(100)
+                );
+            })
+            .query_stop_loss_orders(None, None, None, |result| {
+                assert!(result.unwrap().orders.is_empty());
+            })
+            // the order is gone, it can't be executed twice
+            .execute_stop_loss_order(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::UnExistingStopLossOrder => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::UnExistingStopLossOrder"
+                    ),
+                }
+            });
+    }
+
+    #[test]
+    fn stop_loss_order_cancellation() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        suite
+            // the bounty can be paid in the offer denom
+            .place_stop_loss_order(
+                &alice,
+                uom_to_uusd(),
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uom"),
+                TWAP_WINDOW,
+                vec![coin(1_100u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_stop_loss_orders(None, None, None, |result| {
+                let orders = result.unwrap().orders;
+                assert_eq!(orders.len(), 1);
+                assert_eq!(orders[0].offer_asset, coin(1_000u128, "uom"));
+            })
+            .cancel_stop_loss_order(&bob, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            });
+
+        let alice_uom_before = RefCell::new(Uint128::zero());
+
+        suite
+            .query_balance(&alice.to_string(), "uom", |result| {
+                *alice_uom_before.borrow_mut() = result.unwrap().amount;
+            })
+            .cancel_stop_loss_order(&alice, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), "uom", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *alice_uom_before.borrow() + Uint128::new(1_100)
+                );
+            })
+            .query_stop_loss_orders(Some(alice.to_string()), None, None, |result| {
+                assert!(result.unwrap().orders.is_empty());
+            });
+    }
+
+    #[test]
+    fn stop_loss_order_placement_failures() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .place_stop_loss_order(
+                &alice,
+                uom_to_uusd(),
+                Decimal::zero(),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidTriggerPrice => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidTriggerPrice"
+                        ),
+                    }
+                },
+            )
+            .place_stop_loss_order(
+                &alice,
+                uom_to_uusd(),
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                0,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidTwapWindow { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidTwapWindow"
+                        ),
+                    }
+                },
+            )
+            // bounty not sent
+            .place_stop_loss_order(
+                &alice,
+                uom_to_uusd(),
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidStopLossOrderFunds => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidStopLossOrderFunds"
+                        ),
+                    }
+                },
+            )
+            // the pool doesn't exist
+            .place_stop_loss_order(
+                &alice,
+                vec![SwapOperation::MantraSwap {
+                    token_in_denom: "uom".to_string(),
+                    token_out_denom: "uusd".to_string(),
+                    pool_identifier: "o.nonexistent".to_string(),
+                }],
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnExistingPool => {}
+                        _ => panic!("Wrong error type, should return ContractError::UnExistingPool"),
+                    }
+                },
+            )
+            // the pool doesn't hold the assets of the hop
+            .place_stop_loss_order(
+                &alice,
+                vec![SwapOperation::MantraSwap {
+                    token_in_denom: "uom".to_string(),
+                    token_out_denom: "uwhale".to_string(),
+                    pool_identifier: "o.uom.uusd".to_string(),
+                }],
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            // swap adapters have no TWAP to trigger on
+            .place_stop_loss_order(
+                &alice,
+                vec![SwapOperation::ExternalAdapter {
+                    token_in_denom: "uom".to_string(),
+                    token_out_denom: "uusd".to_string(),
+                    adapter_addr: alice.to_string(),
+                    msg: Binary::default(),
+                }],
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnsupportedForSwapAdapter { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnsupportedForSwapAdapter"
+                        ),
+                    }
+                },
+            )
+            // the hops aren't chained
+            .place_stop_loss_order(
+                &alice,
+                vec![
+                    uom_to_uusd()[0].clone(),
+                    SwapOperation::MantraSwap {
+                        token_in_denom: "uom".to_string(),
+                        token_out_denom: "uusd".to_string(),
+                        pool_identifier: "o.uom.uusd".to_string(),
+                    },
+                ],
+                Decimal::percent(90),
+                Uint128::new(500),
+                None,
+                coin(100u128, "uusd"),
+                TWAP_WINDOW,
+                vec![coin(1_000u128, "uom"), coin(100u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NonConsecutiveSwapOperations { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NonConsecutiveSwapOperations"
+                        ),
+                    }
+                },
+            )
+            .query_stop_loss_orders(Some(alice.to_string()), None, None, |result| {
+                assert!(result.unwrap().orders.is_empty());
+            });
+    }
+}
+
+mod dca {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    const ONE_DAY: u64 = 86_400;
+
+    fn setup_pool() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::permille(1));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "uom.uusd",
+                vec![
+                    coin(10_000_000u128, "uom".to_string()),
+                    coin(10_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        suite
+    }
+
+    fn uusd_to_uom() -> Vec<SwapOperation> {
+        vec![SwapOperation::MantraSwap {
+            token_in_denom: "uusd".to_string(),
+            token_out_denom: "uom".to_string(),
+            pool_identifier: "o.uom.uusd".to_string(),
+        }]
+    }
+
+    #[test]
+    fn dca_schedule_runs_until_budget_is_exhausted() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let keeper = suite.senders[3].clone();
+
+        let alice_uom_balance = RefCell::new(Uint128::zero());
+
+        suite
+            .query_balance(&alice.to_string(), "uom", |result| {
+                *alice_uom_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::new(900),
+                ONE_DAY,
+                Some(Decimal::percent(5)),
+                vec![coin(2_500u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_dca_schedules(Some(alice.to_string()), None, None, |result| {
+                let schedules = result.unwrap().schedules;
+                assert_eq!(schedules.len(), 1);
+                assert_eq!(schedules[0].id, 1);
+                assert_eq!(schedules[0].budget, coin(2_500u128, "uusd"));
+            })
+            // the first swap can be executed right away
+            .execute_dca_schedule(&keeper, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), "uom", |result| {
+                let balance = result.unwrap().amount;
+                assert!(balance > *alice_uom_balance.borrow());
+                *alice_uom_balance.borrow_mut() = balance;
+            })
+            .query_dca_schedules(None, None, None, |result| {
+                let schedules = result.unwrap().schedules;
+                assert_eq!(schedules[0].budget, coin(1_500u128, "uusd"));
+            })
+            // the interval hasn't elapsed yet
+            .execute_dca_schedule(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::DcaScheduleNotDue { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::DcaScheduleNotDue"),
+                }
+            })
+            .add_one_day()
+            .execute_dca_schedule(&keeper, 1, |result| {
+                result.unwrap();
+            })
+            .add_one_day()
+            // the last swap uses what's left of the budget
+            .execute_dca_schedule(&keeper, 1, |result| {
+                let response = result.unwrap();
+                let swap_amount = response
+                    .events
+                    .iter()
+                    .flat_map(|event| event.attributes.iter())
+                    .find(|attribute| attribute.key == "swap_amount")
+                    .unwrap()
+                    .value
+                    .clone();
+                assert_eq!(swap_amount, "500uusd");
+            })
+            .query_balance(&alice.to_string(), "uom", |result| {
+                assert!(result.unwrap().amount > *alice_uom_balance.borrow());
+            })
+            .query_dca_schedules(None, None, None, |result| {
+                assert!(result.unwrap().schedules.is_empty());
+            })
+            .add_one_day()
+            .execute_dca_schedule(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::UnExistingDcaSchedule => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::UnExistingDcaSchedule"
+                    ),
+                }
+            });
+    }
+
+    #[test]
+    fn dca_swaps_enforce_the_minimum_receive() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+        let keeper = suite.senders[3].clone();
+
+        suite
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::new(900),
+                ONE_DAY,
+                None,
+                vec![coin(2_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // bob front-runs the keeper, making uom twice as expensive
+            .swap(
+                &bob,
+                "uom".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.uom.uusd".to_string(),
+                vec![coin(4_200_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the swap would return less than the minimum, so it fails
+            .execute_dca_schedule(&keeper, 1, |result| {
+                assert!(result.is_err());
+            })
+            .query_dca_schedules(None, None, None, |result| {
+                let schedules = result.unwrap().schedules;
+                assert_eq!(schedules[0].budget, coin(2_000u128, "uusd"));
+            });
+    }
+
+    #[test]
+    fn dca_schedule_withdrawals() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let alice_uusd_balance = RefCell::new(Uint128::zero());
+
+        suite
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::new(900),
+                ONE_DAY,
+                None,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .withdraw_dca_budget(&bob, 1, None, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .withdraw_dca_budget(&alice, 1, Some(Uint128::new(5_001)), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidDcaWithdrawalAmount => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidDcaWithdrawalAmount"
+                    ),
+                }
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_uusd_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .withdraw_dca_budget(&alice, 1, Some(Uint128::new(2_000)), |result| {
+                result.unwrap();
+            })
+            .query_dca_schedules(None, None, None, |result| {
+                let schedules = result.unwrap().schedules;
+                assert_eq!(schedules[0].budget, coin(3_000u128, "uusd"));
+            })
+            // cancel the schedule by withdrawing the rest of the budget
+            .withdraw_dca_budget(&alice, 1, None, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *alice_uusd_balance.borrow() + Uint128::new(5_000)
+                );
+            })
+            .query_dca_schedules(Some(alice.to_string()), None, None, |result| {
+                assert!(result.unwrap().schedules.is_empty());
+            });
+    }
+
+    #[test]
+    fn dca_schedule_creation_failures() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::zero(),
+                Uint128::new(900),
+                ONE_DAY,
+                None,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidDcaSchedule => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidDcaSchedule"
+                        ),
+                    }
+                },
+            )
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::new(900),
+                0,
+                None,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidDcaSchedule => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidDcaSchedule"
+                        ),
+                    }
+                },
+            )
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::zero(),
+                ONE_DAY,
+                None,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidDcaSchedule => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidDcaSchedule"
+                        ),
+                    }
+                },
+            )
+            // the budget must be the input of the route
+            .create_dca_schedule(
+                &alice,
+                uusd_to_uom(),
+                Uint128::new(1_000),
+                Uint128::new(900),
+                ONE_DAY,
+                None,
+                vec![coin(5_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PaymentError(_) => {}
+                        _ => panic!("Wrong error type, should return ContractError::PaymentError"),
+                    }
+                },
+            );
+    }
+}
+
+mod price_hooks {
+    use cosmwasm_std::{coin, to_json_binary, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, HookReceiverExecuteMsg, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn price_hooks_fire_when_threshold_is_crossed() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "uom.uusd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        let hook_receiver = suite.instantiate_hook_receiver();
+
+        suite
+            // only contracts can subscribe
+            .subscribe_price_threshold(
+                &alice,
+                "o.uom.uusd".to_string(),
+                "uom".to_string(),
+                "uusd".to_string(),
+                Decimal::percent(90),
+                to_json_binary(&HookReceiverExecuteMsg::Record {}).unwrap(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PriceSubscriberNotAContract => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PriceSubscriberNotAContract"
+                        ),
+                    }
+                },
+            )
+            .subscribe_price_threshold(
+                &hook_receiver,
+                "o.uom.uusd".to_string(),
+                "uom".to_string(),
+                "uom".to_string(),
+                Decimal::percent(90),
+                to_json_binary(&HookReceiverExecuteMsg::Record {}).unwrap(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .subscribe_price_threshold(
+                &hook_receiver,
+                "o.uom.uusd".to_string(),
+                "uom".to_string(),
+                "uusd".to_string(),
+                Decimal::percent(90),
+                to_json_binary(&HookReceiverExecuteMsg::Record {}).unwrap(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // a failing subscriber can't block swaps
+            .subscribe_price_threshold(
+                &hook_receiver,
+                "o.uom.uusd".to_string(),
+                "uom".to_string(),
+                "uusd".to_string(),
+                Decimal::percent(80),
+                to_json_binary(&HookReceiverExecuteMsg::Fail {}).unwrap(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_price_subscriptions(Some("o.uom.uusd".to_string()), None, None, |result| {
+                let subscriptions = result.unwrap().subscriptions;
+                assert_eq!(subscriptions.len(), 2);
+                assert_eq!(subscriptions[0].subscriber, hook_receiver);
+            })
+            // the price of uom drops from 1 to ~0.69, crossing both thresholds
+            .swap(
+                &bob,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.uom.uusd".to_string(),
+                vec![coin(200_000u128, "uom")],
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.value == "price_hook_failed")));
+                },
+            )
+            .query_hook_receiver_count(&hook_receiver, |result| {
+                assert_eq!(result.unwrap(), 1);
+            })
+            // no threshold is crossed
+            .swap(
+                &bob,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.uom.uusd".to_string(),
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_hook_receiver_count(&hook_receiver, |result| {
+                assert_eq!(result.unwrap(), 1);
+            })
+            // the price of uom goes back above the thresholds
+            .swap(
+                &bob,
+                "uom".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.uom.uusd".to_string(),
+                vec![coin(300_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_hook_receiver_count(&hook_receiver, |result| {
+                assert_eq!(result.unwrap(), 2);
+            })
+            .unsubscribe_price_threshold(&alice, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .unsubscribe_price_threshold(&hook_receiver, 1, |result| {
+                result.unwrap();
+            })
+            // the contract owner can remove any subscription
+            .unsubscribe_price_threshold(&creator, 2, |result| {
+                result.unwrap();
+            })
+            .query_price_subscriptions(None, None, None, |result| {
+                assert!(result.unwrap().subscriptions.is_empty());
+            });
+    }
+}
+
+mod twap {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Decimal256};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+
+    #[test]
+    fn route_twap_composes_pool_twaps() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uluna".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.uluna.uusd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uluna"), coin(2_000_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let route = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: "o.whale.uluna".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uluna".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.uluna.uusd".to_string(),
+            },
+        ];
+
+        suite
+            .add_one_day()
+            // 1 uwhale = 1 uluna = 2 uusd
+            .query_route_twap(route.clone(), 3_600, |result| {
+                assert_eq!(result.unwrap().twap, Decimal::percent(200));
+            })
+            // there's no price history older than a day
+            .query_route_twap(route.clone(), 2 * 86_400, |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("There is not enough price history"));
+            })
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(250_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // the price of uwhale in uluna after the swap
+        let whale_price = RefCell::new(Decimal::zero());
+        suite.query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+            let assets = result.unwrap().pools[0].pool_info.assets.clone();
+            *whale_price.borrow_mut() = Decimal::from_ratio(assets[1].amount, assets[0].amount);
+        });
+
+        suite
+            .add_one_day()
+            // half the window at the old price, half at the new one
+            .query_route_twap(route.clone(), 2 * 86_400, |result| {
+                let expected = (Decimal::one() + *whale_price.borrow())
+                    .checked_div(Decimal::percent(200))
+                    .unwrap()
+                    .checked_mul(Decimal::percent(200))
+                    .unwrap();
+                assert_eq!(result.unwrap().twap, expected);
+            })
+            // the last day only saw the new price
+            .query_route_twap(route, 86_400, |result| {
+                assert_eq!(
+                    result.unwrap().twap,
+                    *whale_price.borrow() * Decimal::percent(200)
+                );
+            })
+            .query_route_twap(vec![], 86_400, |result| {
+                assert!(result.is_err());
+            });
+    }
+
+    #[test]
+    fn pool_twap_covers_every_pair() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(2_000_000u128, "uluna")],
+            )
+            .add_one_day()
+            .query_twap("o.whale.uluna".to_string(), 3_600, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pool_identifier, "o.whale.uluna");
+                assert_eq!(response.window_secs, 3_600);
+                assert_eq!(response.twaps.len(), 2);
+
+                // 1 uwhale = 2 uluna
+                for twap in response.twaps {
+                    match (twap.base_denom.as_str(), twap.quote_denom.as_str()) {
+                        ("uwhale", "uluna") => assert_eq!(twap.twap, Decimal256::percent(200)),
+                        ("uluna", "uwhale") => assert_eq!(twap.twap, Decimal256::percent(50)),
+                        _ => panic!("unexpected pair"),
+                    }
+                }
+            })
+            .query_twap("o.whale.uluna".to_string(), 0, |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("Invalid TWAP window"));
+            })
+            .query_twap("o.whale.uluna".to_string(), 2 * 86_400, |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("There is not enough price history"));
+            });
+    }
+}
+
+mod split_swap {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.1".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.1".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(3_000_000u128, "uwhale"), coin(3_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        suite
+    }
+
+    #[test]
+    fn split_swap_across_parallel_pools() {
+        let mut suite = setup();
+        let other = suite.senders[1].clone();
+
+        let pools = vec!["o.whale.uluna.1".to_string(), "o.whale.uluna.2".to_string()];
+
+        // the return of swapping everything through the deepest pool
+        let single_pool_return = RefCell::new(Uint128::zero());
+        suite.query_simulation(
+            "o.whale.uluna.2".to_string(),
+            coin(100_000u128, "uwhale"),
+            "uluna".to_string(),
+            |result| {
+                *single_pool_return.borrow_mut() = result.unwrap().return_amount;
+            },
+        );
+
+        let split_return = RefCell::new(Uint128::zero());
+        suite.query_split_simulation(
+            coin(100_000u128, "uwhale"),
+            "uluna".to_string(),
+            pools.clone(),
+            |result| {
+                let response = result.unwrap();
+
+                // the trade is split in proportion to the depth of each pool, give or take a
+                // chunk of the offer amount
+                assert_eq!(response.allocations.len(), 2);
+                assert_eq!(
+                    response.allocations[0].offer_amount + response.allocations[1].offer_amount,
+                    Uint128::new(100_000)
+                );
+                assert!(response.allocations[0].offer_amount >= Uint128::new(20_000));
+                assert!(response.allocations[0].offer_amount <= Uint128::new(30_000));
+                assert!(response.return_amount > *single_pool_return.borrow());
+
+                *split_return.borrow_mut() = response.return_amount;
+            },
+        );
+
+        suite
+            .split_swap(
+                &other,
+                "uluna".to_string(),
+                vec!["o.whale.uluna.1".to_string(), "o.whale.uluna.1".to_string()],
+                None,
+                None,
+                None,
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidSplitPools { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidSplitPools"
+                        ),
+                    }
+                },
+            )
+            .split_swap(
+                &other,
+                "uluna".to_string(),
+                pools.clone(),
+                Some(split_return.borrow().checked_add(Uint128::one()).unwrap()),
+                None,
+                None,
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .split_swap(
+                &other,
+                "uluna".to_string(),
+                pools,
+                Some(*split_return.borrow()),
+                Some(Decimal::percent(50)),
+                None,
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&other.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    Uint128::new(1_000_000_000u128) + *split_return.borrow()
+                );
+            });
+    }
+
+    #[test]
+    fn weighted_split_swap_across_parallel_pools() {
+        let mut suite = setup();
+        let other = suite.senders[1].clone();
+
+        let pools = vec!["o.whale.uluna.1".to_string(), "o.whale.uluna.2".to_string()];
+
+        let expected_returns = RefCell::new(vec![]);
+        for (pool, offer_amount) in [
+            ("o.whale.uluna.1", 33_333u128),
+            ("o.whale.uluna.2", 66_667u128),
+        ] {
+            suite.query_simulation(
+                pool.to_string(),
+                coin(offer_amount, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    expected_returns
+                        .borrow_mut()
+                        .push(result.unwrap().return_amount);
+                },
+            );
+        }
+        let expected_returns = expected_returns.into_inner();
+        let expected_return = expected_returns[0] + expected_returns[1];
+
+        // the trade is split by the weights given, the remainder going to the last pool
+        suite.query_weighted_split_simulation(
+            coin(100_000u128, "uwhale"),
+            "uluna".to_string(),
+            pools.clone(),
+            Some(vec![1, 2]),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.allocations[0].offer_amount, Uint128::new(33_333));
+                assert_eq!(response.allocations[1].offer_amount, Uint128::new(66_667));
+                assert_eq!(response.allocations[0].return_amount, expected_returns[0]);
+                assert_eq!(response.allocations[1].return_amount, expected_returns[1]);
+                assert_eq!(response.return_amount, expected_return);
+            },
+        );
+
+        for invalid_weights in [vec![1], vec![0, 0]] {
+            suite.weighted_split_swap(
+                &other,
+                "uluna".to_string(),
+                pools.clone(),
+                Some(invalid_weights),
+                None,
+                None,
+                None,
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidSplitWeights => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidSplitWeights"
+                        ),
+                    }
+                },
+            );
+        }
+
+        suite
+            .weighted_split_swap(
+                &other,
+                "uluna".to_string(),
+                pools,
+                Some(vec![1, 2]),
+                Some(expected_return),
+                Some(Decimal::percent(50)),
+                None,
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&other.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    Uint128::new(1_000_000_000u128) + expected_return
+                );
+            });
+    }
+}
+
+mod rfq {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Binary, Decimal, Uint128};
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, RfqQuote};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn sign(signing_key: &SigningKey, digest: &Binary) -> Binary {
+        let signature: Signature = signing_key.sign_prehash(digest.as_slice()).unwrap();
+        Binary::from(signature.to_bytes().to_vec())
+    }
+
+    #[test]
+    fn fill_rfq_quotes() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let maker = suite.senders[1].clone();
+        let taker = suite.senders[2].clone();
+
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let pubkey = Binary::from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            )
+            .deposit_rfq_inventory(&maker, vec![coin(10_000u128, "uluna")], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::RfqMakerNotRegistered { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::RfqMakerNotRegistered"
+                    ),
+                }
+            })
+            .register_rfq_maker(&maker, Binary::from(vec![1u8; 20]), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidRfqMakerPubkey => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidRfqMakerPubkey"
+                    ),
+                }
+            })
+            .register_rfq_maker(&maker, pubkey.clone(), |result| {
+                result.unwrap();
+            })
+            .deposit_rfq_inventory(&maker, vec![coin(10_000u128, "uluna")], |result| {
+                result.unwrap();
+            });
+
+        let now = RefCell::new(0u64);
+        suite.get_time(|time| *now.borrow_mut() = time.seconds());
+
+        let quote = RfqQuote {
+            maker: maker.to_string(),
+            offer_asset: coin(5_100u128, "uwhale"),
+            ask_asset: coin(5_000u128, "uluna"),
+            expiry: *now.borrow() + 60,
+            nonce: 1,
+        };
+        let other_quote = RfqQuote {
+            nonce: 2,
+            ..quote.clone()
+        };
+
+        let signature = RefCell::new(Binary::default());
+        let other_signature = RefCell::new(Binary::default());
+        suite
+            .query_rfq_quote_digest(quote.clone(), |result| {
+                *signature.borrow_mut() = sign(&signing_key, &result.unwrap().digest);
+            })
+            .query_rfq_quote_digest(other_quote.clone(), |result| {
+                *other_signature.borrow_mut() = sign(&signing_key, &result.unwrap().digest);
+            });
+
+        suite
+            .fill_rfq_quote(
+                &taker,
+                quote.clone(),
+                signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRfqPayment { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidRfqPayment"
+                        ),
+                    }
+                },
+            )
+            // the signature belongs to a different quote
+            .fill_rfq_quote(
+                &taker,
+                quote.clone(),
+                other_signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRfqSignature => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidRfqSignature"
+                        ),
+                    }
+                },
+            )
+            .fill_rfq_quote(
+                &taker,
+                quote.clone(),
+                signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&taker.to_string(), "uluna", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_005_000u128));
+            })
+            .query_rfq_maker(&maker, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pubkey, Some(pubkey.clone()));
+                assert_eq!(
+                    response.inventory,
+                    vec![coin(5_000u128, "uluna"), coin(5_100u128, "uwhale")]
+                );
+            })
+            .fill_rfq_quote(
+                &taker,
+                quote,
+                signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::RfqNonceUsed { nonce } => assert_eq!(nonce, 1),
+                        _ => panic!("Wrong error type, should return ContractError::RfqNonceUsed"),
+                    }
+                },
+            )
+            // tampering with the quote invalidates the signature
+            .fill_rfq_quote(
+                &taker,
+                RfqQuote {
+                    ask_asset: coin(50_000u128, "uluna"),
+                    nonce: 3,
+                    ..other_quote.clone()
+                },
+                other_signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRfqSignature => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidRfqSignature"
+                        ),
+                    }
+                },
+            )
+            .add_one_day()
+            // once expired, the quote can only be filled by falling back to the pool
+            .fill_rfq_quote(
+                &taker,
+                other_quote.clone(),
+                other_signature.borrow().clone(),
+                None,
+                None,
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::RfqQuoteExpired => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::RfqQuoteExpired")
+                        }
+                    }
+                },
+            )
+            .fill_rfq_quote(
+                &taker,
+                other_quote,
+                other_signature.borrow().clone(),
+                Some("o.whale.uluna".to_string()),
+                Some(Decimal::percent(5)),
+                vec![coin(5_100u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the AMM fill doesn't touch the inventory of the maker
+            .query_rfq_maker(&maker, |result| {
+                assert_eq!(
+                    result.unwrap().inventory,
+                    vec![coin(5_000u128, "uluna"), coin(5_100u128, "uwhale")]
+                );
+            })
+            .withdraw_rfq_inventory(&maker, vec![coin(5_001u128, "uluna")], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InsufficientRfqInventory { denom } => assert_eq!(denom, "uluna"),
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InsufficientRfqInventory"
+                    ),
+                }
+            })
+            .withdraw_rfq_inventory(
+                &maker,
+                vec![coin(5_000u128, "uluna"), coin(5_100u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_rfq_maker(&maker, |result| {
+                assert!(result.unwrap().inventory.is_empty());
+            })
+            .query_balance(&maker.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_005_100u128));
+            });
+    }
+}
+
+mod rebalance {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn keepers_rebalance_protocol_liquidity_between_stable_pools() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let keeper = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::StableSwap { amp: 100 },
+                Some("stable.a".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                Some("stable.b".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.stable.a".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the second pool is off-peg
+            .provide_liquidity(
+                &creator,
+                "o.stable.b".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(500_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let lp_denom_a = suite.get_lp_denom("o.stable.a".to_string());
+        let lp_denom_b = suite.get_lp_denom("o.stable.b".to_string());
+
+        let protocol_liquidity = RefCell::new(Uint128::zero());
+        suite.query_balance(&creator.to_string(), lp_denom_a.clone(), |result| {
+            *protocol_liquidity.borrow_mut() = result.unwrap().amount;
+        });
+        let protocol_liquidity = *protocol_liquidity.borrow();
+        let lp_amount = protocol_liquidity.multiply_ratio(1u128, 4u128);
+
+        suite
+            .deposit_protocol_liquidity(
+                &keeper,
+                "o.stable.a".to_string(),
+                vec![coin(1_000u128, lp_denom_a.clone())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .deposit_protocol_liquidity(
+                &creator,
+                "o.stable.a".to_string(),
+                vec![coin(protocol_liquidity.u128(), lp_denom_a.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .rebalance_stable_pools(
+                &keeper,
+                "o.stable.a".to_string(),
+                "o.stable.b".to_string(),
+                lp_amount,
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            .update_rebalance_config(
+                &creator,
+                Some(vec![keeper.to_string()]),
+                Some(Decimal::percent(150)),
+                None,
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRebalanceConfig => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidRebalanceConfig"
+                        ),
+                    }
+                },
+            )
+            .update_rebalance_config(
+                &creator,
+                Some(vec![keeper.to_string()]),
+                Some(Decimal::percent(50)),
+                Some(Decimal::percent(10)),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // can't move more than half of the protocol-owned liquidity at once
+            .rebalance_stable_pools(
+                &keeper,
+                "o.stable.a".to_string(),
+                "o.stable.b".to_string(),
+                protocol_liquidity.multiply_ratio(3u128, 4u128),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRebalanceAmount { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidRebalanceAmount"
+                        ),
+                    }
+                },
+            )
+            // the pool on peg doesn't need more liquidity
+            .rebalance_stable_pools(
+                &keeper,
+                "o.stable.b".to_string(),
+                "o.stable.a".to_string(),
+                Uint128::new(1_000u128),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::RebalanceNotNeeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::RebalanceNotNeeded"
+                        ),
+                    }
+                },
+            )
+            .rebalance_stable_pools(
+                &keeper,
+                "o.stable.a".to_string(),
+                "o.stable.b".to_string(),
+                lp_amount,
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.value == "rebalance_stable_pools")));
+                },
+            )
+            .query_protocol_liquidity(|result| {
+                let response = result.unwrap();
+                assert_eq!(response.rebalance_config.keepers, vec![keeper.clone()]);
+                assert_eq!(response.liquidity.len(), 2);
+                assert_eq!(
+                    response.liquidity[0],
+                    ("o.stable.a".to_string(), protocol_liquidity - lp_amount)
+                );
+                assert_eq!(response.liquidity[1].0, "o.stable.b".to_string());
+                assert!(!response.liquidity[1].1.is_zero());
+            })
+            // the assets moved from one pool to the other
+            .query_pools(Some("o.stable.a".to_string()), None, None, |result| {
+                let assets = result.unwrap().pools[0].pool_info.assets.clone();
+                assert!(assets[0].amount < Uint128::new(1_000_000u128));
+                assert!(assets[1].amount < Uint128::new(1_000_000u128));
+            })
+            .query_pools(Some("o.stable.b".to_string()), None, None, |result| {
+                let assets = result.unwrap().pools[0].pool_info.assets.clone();
+                assert!(assets[0].amount > Uint128::new(1_000_000u128));
+                assert!(assets[1].amount > Uint128::new(500_000u128));
+            });
+
+        // the treasury can take the liquidity out of the destination pool
+        let protocol_liquidity_b = RefCell::new(Uint128::zero());
+        suite.query_protocol_liquidity(|result| {
+            *protocol_liquidity_b.borrow_mut() = result.unwrap().liquidity[1].1;
+        });
+
+        suite
+            .withdraw_protocol_liquidity(
+                &creator,
+                "o.stable.b".to_string(),
+                *protocol_liquidity_b.borrow(),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&creator.to_string(), lp_denom_b, |result| {
+                assert!(result.unwrap().amount >= *protocol_liquidity_b.borrow());
+            })
+            .query_protocol_liquidity(|result| {
+                assert_eq!(result.unwrap().liquidity.len(), 1);
+            });
+    }
+}
+
+mod lp_valuation {
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation, SwapRoute};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+
+    #[test]
+    fn value_lp_positions_through_swap_routes() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uluna".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.uluna.uusd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uluna"), coin(2_000_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let luna_to_usd = SwapOperation::MantraSwap {
+            token_in_denom: "uluna".to_string(),
+            token_out_denom: "uusd".to_string(),
+            pool_identifier: "o.uluna.uusd".to_string(),
+        };
+        let swap_routes = vec![
+            SwapRoute {
+                offer_asset_denom: "uwhale".to_string(),
+                ask_asset_denom: "uusd".to_string(),
+                swap_operations: vec![
+                    SwapOperation::MantraSwap {
+                        token_in_denom: "uwhale".to_string(),
+                        token_out_denom: "uluna".to_string(),
+                        pool_identifier: "o.whale.uluna".to_string(),
+                    },
+                    luna_to_usd.clone(),
+                ],
+            },
+            SwapRoute {
+                offer_asset_denom: "uluna".to_string(),
+                ask_asset_denom: "uusd".to_string(),
+                swap_operations: vec![luna_to_usd.clone()],
+            },
+        ];
+
+        suite
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                Some(creator.to_string()),
+                None,
+                "uusd".to_string(),
+                None,
+                vec![SwapRoute {
+                    offer_asset_denom: "uwhale".to_string(),
+                    ask_asset_denom: "uusd".to_string(),
+                    swap_operations: vec![luna_to_usd],
+                }],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("Invalid swap route from uwhale to uusd"));
+                },
+            )
+            // 1 uwhale = 1 uluna = 2 uusd, the creator holds all the LP tokens but the minimum
+            // liquidity locked in the contract
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                Some(creator.to_string()),
+                None,
+                "uusd".to_string(),
+                None,
+                swap_routes.clone(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.lp_amount, Uint128::new(999_000u128));
+                    assert_eq!(
+                        response.underlying_assets,
+                        vec![coin(999_000u128, "uwhale"), coin(999_000u128, "uluna")]
+                    );
+                    assert_eq!(
+                        response.asset_values,
+                        vec![coin(1_998_000u128, "uusd"), coin(1_998_000u128, "uusd")]
+                    );
+                    assert_eq!(response.total_value, coin(3_996_000u128, "uusd"));
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uluna".to_string(),
+                None,
+                swap_routes.clone(),
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("No swap route from uwhale to uluna"));
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                Some(creator.to_string()),
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                swap_routes.clone(),
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("Either an address or an LP amount must be provided"));
+                },
+            )
+            .add_one_day()
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                Some(3_600),
+                swap_routes.clone(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(
+                        response.underlying_assets,
+                        vec![coin(500_000u128, "uwhale"), coin(500_000u128, "uluna")]
+                    );
+                    assert_eq!(response.total_value, coin(2_000_000u128, "uusd"));
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                vec![swap_routes[0].clone()],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("No swap route from uluna to uusd"));
+                },
+            );
+    }
+}
+
+mod lp_wrapper {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn create_lp_wrapper_and_wrap_lp_tokens() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &other,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let code_id = suite.store_lp_wrapper_code();
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .query_lp_wrapper("o.whale.uluna".to_string(), |result| {
+                assert_eq!(result.unwrap().lp_wrapper, None);
+            })
+            .create_lp_wrapper(&other, "o.whale.uluna".to_string(), code_id, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .create_lp_wrapper(&creator, "o.non.existent".to_string(), code_id, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::UnExistingPool => {}
+                    _ => panic!("Wrong error type, should return ContractError::UnExistingPool"),
+                }
+            })
+            .create_lp_wrapper(&creator, "o.whale.uluna".to_string(), code_id, |result| {
+                result.unwrap();
+            })
+            .create_lp_wrapper(&creator, "o.whale.uluna".to_string(), code_id, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::LpWrapperAlreadyExists { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::LpWrapperAlreadyExists"
+                    ),
+                }
+            });
+
+        let lp_wrapper = RefCell::new(Addr::unchecked(""));
+        suite.query_lp_wrapper("o.whale.uluna".to_string(), |result| {
+            let response = result.unwrap();
+            assert_eq!(response.pool_identifier, "o.whale.uluna");
+            *lp_wrapper.borrow_mut() = Addr::unchecked(response.lp_wrapper.unwrap());
+        });
+        let lp_wrapper = lp_wrapper.into_inner();
+
+        suite
+            .wrap_lp_tokens(
+                &other,
+                &lp_wrapper,
+                vec![coin(400_000u128, lp_denom.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_cw20_balance(&lp_wrapper, &other, |result| {
+                assert_eq!(result.unwrap().balance, Uint128::new(400_000));
+            })
+            .query_balance(&lp_wrapper.to_string(), lp_denom.clone(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(400_000));
+            })
+            .unwrap_lp_tokens(&other, &lp_wrapper, Uint128::new(150_000), |result| {
+                result.unwrap();
+            })
+            .query_cw20_balance(&lp_wrapper, &other, |result| {
+                assert_eq!(result.unwrap().balance, Uint128::new(250_000));
+            })
+            .query_balance(&lp_wrapper.to_string(), lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(250_000));
+            });
+    }
+}
+
+mod migration {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn move_liquidity_and_claim_migration_rewards() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("old".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("new".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("other".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.old".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &bob,
+                "o.old".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(3_000_000u128, "uwhale"), coin(3_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let old_lp_denom = suite.get_lp_denom("o.old".to_string());
+        let new_lp_denom = suite.get_lp_denom("o.new".to_string());
+
+        let deadline = RefCell::new(0u64);
+        suite.get_time(|time| {
+            *deadline.borrow_mut() = time.seconds() + 86_400;
+        });
+        let deadline = deadline.into_inner();
+
+        suite
+            .create_migration_campaign(
+                &alice,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                deadline,
+                vec![coin(10_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                    }
+                },
+            )
+            .create_migration_campaign(
+                &creator,
+                "o.old".to_string(),
+                "o.other".to_string(),
+                deadline,
+                vec![coin(10_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidMigrationPools => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidMigrationPools"
+                        ),
+                    }
+                },
+            )
+            .create_migration_campaign(
+                &creator,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                deadline,
+                vec![coin(10_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_migration_campaign(
+                &creator,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                deadline,
+                vec![coin(10_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MigrationCampaignAlreadyExists { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::MigrationCampaignAlreadyExists"),
+                    }
+                },
+            );
+
+        let alice_lp = RefCell::new(Uint128::zero());
+        let bob_lp = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), old_lp_denom.clone(), |result| {
+                *alice_lp.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), old_lp_denom.clone(), |result| {
+                *bob_lp.borrow_mut() = result.unwrap().amount;
+            });
+        let alice_lp = alice_lp.into_inner();
+        let bob_lp = bob_lp.into_inner();
+
+        suite
+            .move_liquidity(
+                &alice,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                vec![coin(alice_lp.u128(), old_lp_denom.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), old_lp_denom.clone(), |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            .query_balance(&alice.to_string(), new_lp_denom.clone(), |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            })
+            // bob only moves half of his liquidity before the deadline
+            .move_liquidity(
+                &bob,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                vec![coin(bob_lp.u128() / 2, old_lp_denom.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .claim_migration_rewards(&alice, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::MigrationCampaignInProgress => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::MigrationCampaignInProgress"
+                    ),
+                }
+            })
+            .cancel_migration_campaign(&creator, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::MigrationCampaignInProgress => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::MigrationCampaignInProgress"
+                    ),
+                }
+            })
+            .add_one_day()
+            .add_one_day()
+            // moves after the deadline don't count towards the campaign
+            .move_liquidity(
+                &bob,
+                "o.old".to_string(),
+                "o.new".to_string(),
+                vec![coin(bob_lp.u128() / 2, old_lp_denom.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let total_migrated = alice_lp + bob_lp / Uint128::new(2);
+        let alice_rewards = Uint128::new(10_000).multiply_ratio(alice_lp, total_migrated);
+        let bob_rewards =
+            Uint128::new(10_000).multiply_ratio(bob_lp / Uint128::new(2), total_migrated);
+
+        suite
+            .query_migration_campaign("o.old".to_string(), Some(&alice), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.campaign.total_migrated, total_migrated);
+                assert_eq!(response.migrated, alice_lp);
+                assert_eq!(response.rewards, vec![coin(alice_rewards.u128(), "uusd")]);
+            })
+            .claim_migration_rewards(&creator, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NoMigrationRewards => {}
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::NoMigrationRewards")
+                    }
+                }
+            });
+
+        let alice_usd = RefCell::new(Uint128::zero());
+        let bob_usd = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_usd.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), "uusd", |result| {
+                *bob_usd.borrow_mut() = result.unwrap().amount;
+            })
+            .claim_migration_rewards(&alice, "o.old".to_string(), |result| {
+                result.unwrap();
+            })
+            .claim_migration_rewards(&bob, "o.old".to_string(), |result| {
+                result.unwrap();
+            })
+            .claim_migration_rewards(&alice, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NoMigrationRewards => {}
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::NoMigrationRewards")
+                    }
+                }
+            })
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                assert_eq!(result.unwrap().amount, *alice_usd.borrow() + alice_rewards);
+            })
+            .query_balance(&bob.to_string(), "uusd", |result| {
+                assert_eq!(result.unwrap().amount, *bob_usd.borrow() + bob_rewards);
+            });
+    }
+}
+
+mod streaming {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn stream_liquidity_into_a_pool() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+        let keeper = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            );
+
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .create_liquidity_stream(
+                &alice,
+                "o.whale.uluna".to_string(),
+                0,
+                Uint128::new(99_000),
+                3_600,
+                None,
+                vec![coin(300_000u128, "uwhale"), coin(300_000u128, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidLiquidityStream => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidLiquidityStream"
+                        ),
+                    }
+                },
+            )
+            .create_liquidity_stream(
+                &alice,
+                "o.whale.uluna".to_string(),
+                3,
+                Uint128::zero(),
+                3_600,
+                None,
+                vec![coin(300_000u128, "uwhale"), coin(300_000u128, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidLiquidityStream => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidLiquidityStream"
+                        ),
+                    }
+                },
+            )
+            .create_liquidity_stream(
+                &alice,
+                "o.whale.uluna".to_string(),
+                3,
+                Uint128::new(99_000),
+                3_600,
+                None,
+                vec![coin(300_000u128, "uwhale"), coin(300_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .create_liquidity_stream(
+                &alice,
+                "o.whale.uluna".to_string(),
+                3,
+                Uint128::new(99_000),
+                3_600,
+                None,
+                vec![coin(300_000u128, "uwhale"), coin(300_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_liquidity_streams(Some(alice.to_string()), None, None, |result| {
+                let streams = result.unwrap().streams;
+                assert_eq!(streams.len(), 1);
+                assert_eq!(streams[0].id, 1);
+                assert_eq!(streams[0].remaining_tranches, 3);
+
+                let mut remaining_assets = streams[0].remaining_assets.clone();
+                remaining_assets.sort_by(|a, b| a.denom.cmp(&b.denom));
+                assert_eq!(
+                    remaining_assets,
+                    vec![coin(300_000u128, "uluna"), coin(300_000u128, "uwhale")]
+                );
+            })
+            .execute_liquidity_stream(&keeper, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), lp_denom.clone(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(100_000));
+            })
+            .execute_liquidity_stream(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::LiquidityStreamNotDue { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::LiquidityStreamNotDue"
+                    ),
+                }
+            })
+            .add_one_day()
+            .execute_liquidity_stream(&keeper, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), lp_denom.clone(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(200_000));
+            })
+            .query_liquidity_streams(None, None, None, |result| {
+                let streams = result.unwrap().streams;
+                assert_eq!(streams[0].remaining_tranches, 1);
+
+                let mut remaining_assets = streams[0].remaining_assets.clone();
+                remaining_assets.sort_by(|a, b| a.denom.cmp(&b.denom));
+                assert_eq!(
+                    remaining_assets,
+                    vec![coin(100_000u128, "uluna"), coin(100_000u128, "uwhale")]
+                );
+            })
+            .cancel_liquidity_stream(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            });
+
+        let whale_balance = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                *whale_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .cancel_liquidity_stream(&alice, 1, |result| {
+                result.unwrap();
+            })
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *whale_balance.borrow() + Uint128::new(100_000)
+                );
+            })
+            .query_liquidity_streams(None, None, None, |result| {
+                assert!(result.unwrap().streams.is_empty());
+            })
+            .execute_liquidity_stream(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::UnExistingLiquidityStream => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::UnExistingLiquidityStream"
+                    ),
+                }
+            });
+    }
+
+    #[test]
+    fn stream_tranches_enforce_the_minimum_lp() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+        let keeper = suite.senders[3].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            )
+            .create_liquidity_stream(
+                &alice,
+                "o.whale.uluna".to_string(),
+                3,
+                Uint128::new(99_000),
+                3_600,
+                None,
+                vec![coin(300_000u128, "uwhale"), coin(300_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // bob front-runs the keeper, so a balanced tranche mints less LP
+            .swap(
+                &bob,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(100_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the tranche would mint less than the minimum, so it fails
+            .execute_liquidity_stream(&keeper, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::MinLpToReceiveAssertion { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::MinLpToReceiveAssertion"
+                    ),
+                }
+            })
+            .query_liquidity_streams(None, None, None, |result| {
+                let streams = result.unwrap().streams;
+                assert_eq!(streams[0].remaining_tranches, 3);
+            });
+    }
+}
+
+mod ibc_withdrawal {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{
+        coin, to_json_string, Addr, BankMsg, Binary, CosmosMsg, IbcEndpoint, IbcPacket,
+        IbcSourceCallbackMsg, IbcTimeout, IbcTimeoutCallbackMsg,
+    };
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{IbcWithdrawalChannel, PoolType};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn configure_channels_and_withdraw_to_remote_address() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .update_ibc_withdrawal_channels(
+                &alice,
+                vec![IbcWithdrawalChannel {
+                    denom: "uwhale".to_string(),
+                    channel_id: Some("channel-1".to_string()),
+                }],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_ibc_withdrawal_channels(
+                &creator,
+                vec![IbcWithdrawalChannel {
+                    denom: "uwhale".to_string(),
+                    channel_id: Some("transfer".to_string()),
+                }],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidIbcChannel { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidIbcChannel"
+                        ),
+                    }
+                },
+            )
+            .update_ibc_withdrawal_channels(
+                &creator,
+                vec![
+                    IbcWithdrawalChannel {
+                        denom: "uwhale".to_string(),
+                        channel_id: Some("channel-1".to_string()),
+                    },
+                    IbcWithdrawalChannel {
+                        denom: "uluna".to_string(),
+                        channel_id: Some("channel-2".to_string()),
+                    },
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_ibc_withdrawal_channels(
+                &creator,
+                vec![IbcWithdrawalChannel {
+                    denom: "uluna".to_string(),
+                    channel_id: None,
+                }],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_ibc_withdrawal_channels(|result| {
+                assert_eq!(
+                    result.unwrap().channels,
+                    vec![IbcWithdrawalChannel {
+                        denom: "uwhale".to_string(),
+                        channel_id: Some("channel-1".to_string()),
+                    }]
+                );
+            })
+            // uluna can't be sent to the remote address
+            .withdraw_liquidity_to_remote(
+                &alice,
+                "o.whale.uluna".to_string(),
+                Some("cosmos1remote".to_string()),
+                vec![coin(1_000u128, lp_denom.clone())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NoIbcChannelForAsset { denom } => {
+                            assert_eq!(denom, "uluna")
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NoIbcChannelForAsset"
+                        ),
+                    }
+                },
+            )
+            .withdraw_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                vec![coin(1_000u128, lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+
+    #[test]
+    fn refund_timed_out_withdrawals() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let memo = format!(
+            r#"{{"src_callback":{{"address":"{}"}},"withdrawal_refund":{{"address":"alice","denom":"uwhale"}}}}"#,
+            env.contract.address
+        );
+
+        let packet = IbcPacket::new(
+            Binary::from(
+                format!(
+                    r#"{{"amount":"1000","denom":"uwhale","receiver":"cosmos1remote","sender":"{}","memo":{}}}"#,
+                    env.contract.address,
+                    to_json_string(&memo).unwrap()
+                )
+                .into_bytes(),
+            ),
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-1".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-0".to_string(),
+            },
+            1,
+            IbcTimeout::with_timestamp(env.block.time),
+        );
+
+        let response = crate::contract::ibc_source_callback(
+            deps.as_mut(),
+            env,
+            IbcSourceCallbackMsg::Timeout(IbcTimeoutCallbackMsg::new(
+                packet,
+                Addr::unchecked("relayer"),
+            )),
+        )
+        .unwrap();
+
+        assert_eq!(
+            response.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: vec![coin(1_000u128, "uwhale")],
+            })
+        );
+    }
+}
+
+mod unlock_and_withdraw {
+    use cosmwasm_std::{coin, Coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::farm_manager::{PositionAction, PositionsBy};
+    use mantra_dex_std::lp_common::MINIMUM_LIQUIDITY_AMOUNT;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn unlock_and_withdraw_matured_position() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                Some(86_400u64),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+
+        suite
+            .unlock_and_withdraw_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                "p-1".to_string(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::FarmPositionNotMatured { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::FarmPositionNotMatured"
+                        ),
+                    }
+                },
+            )
+            .manage_farm_position(
+                &alice,
+                PositionAction::Close {
+                    identifier: "p-1".to_string(),
+                    lp_asset: None,
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // still unlocking
+            .unlock_and_withdraw_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                "p-1".to_string(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::FarmPositionNotMatured { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::FarmPositionNotMatured"
+                        ),
+                    }
+                },
+            )
+            .add_one_day()
+            .add_one_day()
+            // bob can't withdraw alice's position
+            .unlock_and_withdraw_liquidity(
+                &bob,
+                "o.whale.uluna".to_string(),
+                "p-1".to_string(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::FarmPositionNotFound { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::FarmPositionNotFound"
+                        ),
+                    }
+                },
+            )
+            .unlock_and_withdraw_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                "p-1".to_string(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_farm_positions(
+                Some(PositionsBy::Receiver(alice.to_string())),
+                None,
+                None,
+                None,
+                |result| {
+                    assert!(result.unwrap().positions.is_empty());
+                },
+            )
+            // the liquidity of the position was withdrawn to alice, only the minimum liquidity is
+            // left in the pool
+            .query_balance(&alice.to_string(), "uwhale".to_string(), |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    Uint128::new(1_000_000_000u128) - MINIMUM_LIQUIDITY_AMOUNT
+                );
+            })
+            .query_balance(&alice.to_string(), "uluna".to_string(), |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    Uint128::new(1_000_000_000u128) - MINIMUM_LIQUIDITY_AMOUNT
+                );
+            })
+            .query_balance(&alice.to_string(), lp_denom.clone(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::zero());
+            })
+            .query_balance(&pool_manager_addr.to_string(), lp_denom.clone(), |result| {
+                assert_eq!(
+                    result.unwrap(),
+                    Coin {
+                        denom: lp_denom.clone(),
+                        amount: MINIMUM_LIQUIDITY_AMOUNT,
+                    }
+                );
+            });
+    }
+}
+
+mod stableswap_n_assets {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{assert_approx_eq, coin, Coin, Uint128};
+    use test_case::test_case;
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    const STABLE_DENOMS: [&str; 5] = ["uusd", "uusdc", "uusdt", "uusdy", "uusde"];
+
+    fn get_return_amount(result: Result<cw_multi_test::AppResponse, anyhow::Error>) -> Uint128 {
+        result
+            .unwrap()
+            .events
+            .into_iter()
+            .filter(|event| event.ty == "wasm")
+            .flat_map(|event| event.attributes)
+            .find(|attribute| attribute.key == "return_amount")
+            .map(|attribute| attribute.value.parse::<Uint128>().unwrap())
+            .unwrap()
+    }
+
+    #[test_case(3 ; "three assets")]
+    #[test_case(4 ; "four assets")]
+    #[test_case(5 ; "five assets")]
+    fn swap_on_imbalanced_stableswap_pool(n_assets: usize) {
+        let mut balances: Vec<Coin> = STABLE_DENOMS
+            .iter()
+            .map(|denom| coin(1_000_000_000_000u128, denom.to_string()))
+            .collect();
+        balances.push(coin(1_000_000_000_000u128, "uom".to_string()));
+
+        let mut suite = TestingSuite::default_with_balances(
+            balances,
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let asset_denoms: Vec<String> = STABLE_DENOMS[..n_assets]
+            .iter()
+            .map(|denom| denom.to_string())
+            .collect();
+        let last_denom = asset_denoms[n_assets - 1].clone();
+
+        let pool_fees = zero_fees();
+
+        // the last asset of the pool is twice as abundant as the rest
+        let deposits: Vec<Coin> = asset_denoms
+            .iter()
+            .map(|denom| {
+                if *denom == last_denom {
+                    coin(2_000_000_000u128, denom)
+                } else {
+                    coin(1_000_000_000u128, denom)
+                }
+            })
+            .collect();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                asset_denoms.clone(),
+                vec![6u8; n_assets],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                "stable",
+                deposits,
+            );
+
+        // buying the abundant asset returns more than offered, and the swap matches the simulation
+        let simulated_return_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(1_000_000u128, "uusd"),
+                last_denom.clone(),
+                |result| {
+                    *simulated_return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .swap(
+                &creator,
+                last_denom.clone(),
+                None,
+                None,
+                None,
+                "o.stable".to_string(),
+                vec![coin(1_000_000u128, "uusd")],
+                |result| {
+                    let return_amount = get_return_amount(result);
+                    assert_eq!(return_amount, *simulated_return_amount.borrow());
+                    assert!(return_amount > Uint128::new(1_000_000u128));
+                },
+            )
+            // selling it returns less than offered
+            .swap(
+                &creator,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.stable".to_string(),
+                vec![coin(1_000_000u128, last_denom.clone())],
+                |result| {
+                    assert!(get_return_amount(result) < Uint128::new(1_000_000u128));
+                },
+            );
+
+        // the reverse simulation gives what to offer to get the desired amount back
+        let simulated_offer_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_reverse_simulation(
+                "o.stable".to_string(),
+                coin(1_000_000u128, "uusdc"),
+                "uusd".to_string(),
+                |result| {
+                    *simulated_offer_amount.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            .swap(
+                &creator,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.stable".to_string(),
+                vec![coin(simulated_offer_amount.borrow().u128(), "uusd")],
+                |result| {
+                    assert_approx_eq!(get_return_amount(result).u128(), 1_000_000u128, "0.0001");
+                },
+            );
+    }
+
+    #[test]
+    fn max_assets_depend_on_the_pool_type() {
+        let mut balances: Vec<Coin> = STABLE_DENOMS
+            .iter()
+            .map(|denom| coin(1_000_000_000_000u128, denom.to_string()))
+            .collect();
+        balances.push(coin(1_000_000_000_000u128, "uom".to_string()));
+
+        let mut suite = TestingSuite::default_with_balances(
+            balances,
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        let asset_denoms: Vec<String> = STABLE_DENOMS.iter().map(|d| d.to_string()).collect();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                asset_denoms.clone(),
+                vec![6u8; 5],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::TooManyAssets { max_assets, .. } => {
+                            assert_eq!(max_assets, 4)
+                        }
+                        _ => panic!("Wrong error type, should return ContractError::TooManyAssets"),
+                    }
+                },
+            )
+            .create_pool(
+                &creator,
+                asset_denoms,
+                vec![6u8; 5],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+
+    #[test]
+    fn query_stableswap_amp() {
+        let mut balances: Vec<Coin> = STABLE_DENOMS
+            .iter()
+            .map(|denom| coin(1_000_000_000_000u128, denom.to_string()))
+            .collect();
+        balances.push(coin(1_000_000_000_000u128, "uom".to_string()));
+
+        let mut suite = TestingSuite::default_with_balances(
+            balances,
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::StableSwap { amp: 85 },
+                Some("stable".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("xyk".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_stableswap_amp("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pool_identifier, "o.stable");
+                assert_eq!(response.amp, 85);
+                assert_eq!(response.effective_amp, 85);
+            })
+            .query_stableswap_amp("o.xyk".to_string(), |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("The pool o.xyk is not a stableswap pool"));
+            });
+    }
+
+    #[test]
+    fn query_stableswap_debug() {
+        let mut balances: Vec<Coin> = STABLE_DENOMS
+            .iter()
+            .map(|denom| coin(1_000_000_000_000u128, denom.to_string()))
+            .collect();
+        balances.push(coin(1_000_000_000_000u128, "uom".to_string()));
+
+        let mut suite = TestingSuite::default_with_balances(
+            balances,
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::StableSwap { amp: 100 },
+                Some("stable".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("xyk".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.stable".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000_000u128, "uusd".to_string()),
+                    coin(1_000_000_000u128, "uusdc".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let return_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(1_000_000u128, "uusd"),
+                "uusdc".to_string(),
+                |result| {
+                    *return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            // the replay lands on the same ask pool the swap computation does
+            .query_stableswap_debug(
+                "o.stable".to_string(),
+                coin(1_000_000u128, "uusd"),
+                "uusdc".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    let expected_ask_pool =
+                        Uint128::new(1_000_000_000u128) - *return_amount.borrow();
+
+                    assert_eq!(response.error, None);
+                    assert_eq!(response.new_ask_pool, Some(expected_ask_pool));
+                    assert_eq!(response.inputs_hash.len(), 64);
+                    assert!(!response.d_iterations.is_empty());
+                    assert_eq!(
+                        response.y_iterations.last().copied(),
+                        Some(expected_ask_pool.into())
+                    );
+                },
+            )
+            .query_stableswap_debug(
+                "o.xyk".to_string(),
+                coin(1_000_000u128, "uusd"),
+                "uusdc".to_string(),
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("The pool o.xyk is not a stableswap pool"));
+                },
+            );
+    }
+}
+
+mod depeg_protection {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{DepegProtectionConfig, PoolType};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    const DAY_IN_SECONDS: u64 = 86_400;
+
+    #[test]
+    fn only_owner_can_protect_stableswap_pools() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uusdc".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let config = DepegProtectionConfig {
+            band: Decimal::percent(10),
+            grace_period: DAY_IN_SECONDS,
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::StableSwap { amp: 100 },
+                Some("stable".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("xyk".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_depeg_protection(
+                &other,
+                "o.stable".to_string(),
+                Some(config.clone()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError(_) => {}
+                        _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                    }
+                },
+            )
+            .update_depeg_protection(
+                &creator,
+                "o.xyk".to_string(),
+                Some(config.clone()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NotStableSwapPool { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NotStableSwapPool"
+                        ),
+                    }
+                },
+            )
+            .update_depeg_protection(
+                &creator,
+                "o.stable".to_string(),
+                Some(DepegProtectionConfig {
+                    band: Decimal::zero(),
+                    grace_period: DAY_IN_SECONDS,
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidDepegProtectionConfig => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidDepegProtectionConfig"
+                        ),
+                    }
+                },
+            )
+            .update_depeg_protection(
+                &creator,
+                "o.stable".to_string(),
+                Some(config.clone()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.config, Some(config.clone()));
+                assert_eq!(response.deviating_since, None);
+                assert_eq!(response.depegging_denom, None);
+            })
+            .update_depeg_protection(&creator, "o.stable".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                assert_eq!(result.unwrap().config, None);
+            });
+    }
+
+    #[test]
+    fn pauses_sales_of_the_depegging_asset_after_the_grace_period() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uusdc".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let trader = suite.senders[1].clone();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::StableSwap { amp: 100 },
+                "stable",
+                vec![
+                    coin(1_000_000_000u128, "uusd".to_string()),
+                    coin(1_000_000_000u128, "uusdc".to_string()),
+                ],
+            )
+            .update_depeg_protection(
+                &creator,
+                "o.stable".to_string(),
+                Some(DepegProtectionConfig {
+                    band: Decimal::percent(10),
+                    grace_period: DAY_IN_SECONDS,
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // a small swap keeps the pool within the band
+        suite
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.stable".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.peg_deviation < Decimal::percent(10));
+                assert_eq!(response.deviating_since, None);
+            });
+
+        // a large sale of uusd pushes the pool beyond the band, starting the grace period
+        let deviating_since = RefCell::new(0u64);
+        suite
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.stable".to_string(),
+                vec![coin(300_000_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .get_time(|time| {
+                *deviating_since.borrow_mut() = time.seconds();
+            })
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.peg_deviation > Decimal::percent(10));
+                assert_eq!(response.deviating_since, Some(*deviating_since.borrow()));
+                assert_eq!(response.depegging_denom, None);
+            })
+            // uusd can still be sold during the grace period
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.stable".to_string(),
+                vec![coin(10_000_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // once the grace period is over, the protection kicks in
+        suite
+            .add_one_day()
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.deviating_since, Some(*deviating_since.borrow()));
+                assert_eq!(response.depegging_denom, Some("uusd".to_string()));
+            })
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.stable".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::DepegProtectionActive { denom } => {
+                            assert_eq!(denom, "uusd");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::DepegProtectionActive"
+                        ),
+                    }
+                },
+            )
+            // buying the depegging asset is still possible, and restores the peg
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.stable".to_string(),
+                vec![coin(310_000_000u128, "uusdc".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_depeg_protection("o.stable".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.peg_deviation <= Decimal::percent(10));
+                assert_eq!(response.deviating_since, None);
+                assert_eq!(response.depegging_denom, None);
+            })
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.stable".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod imbalance_fee {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{assert_approx_eq, coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{ImbalanceFee, PoolType, SimulationResponse};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uusdc".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::percent(1));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                "stable",
+                vec![
+                    coin(1_000_000_000u128, "uusd".to_string()),
+                    coin(1_000_000_000u128, "uusdc".to_string()),
+                ],
+            );
+
+        suite
+    }
+
+    fn imbalance_fee() -> ImbalanceFee {
+        ImbalanceFee {
+            surcharge_factor: Decimal::one(),
+            max_surcharge: Decimal::percent(5),
+            max_discount: Decimal::percent(1),
+        }
+    }
+
+    #[test]
+    fn only_owner_can_set_imbalance_fees_within_the_caps() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        suite
+            .update_imbalance_fee(
+                &other,
+                "o.stable".to_string(),
+                Some(imbalance_fee()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError(_) => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            // the swap fee plus the max surcharge exceeds the swap fee cap
+            .update_imbalance_fee(
+                &creator,
+                "o.stable".to_string(),
+                Some(ImbalanceFee {
+                    max_surcharge: Decimal::percent(25),
+                    ..imbalance_fee()
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolFeeCapExceeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PoolFeeCapExceeded"
+                        ),
+                    }
+                },
+            )
+            .update_imbalance_fee(
+                &creator,
+                "o.stable".to_string(),
+                Some(imbalance_fee()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_imbalance_fee("o.stable".to_string(), |result| {
+                assert_eq!(result.unwrap().imbalance_fee, Some(imbalance_fee()));
+            })
+            .update_imbalance_fee(&creator, "o.stable".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_imbalance_fee("o.stable".to_string(), |result| {
+                assert_eq!(result.unwrap().imbalance_fee, None);
+            });
+    }
+
+    #[test]
+    fn surcharges_imbalancing_trades_and_discounts_restoring_ones() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let trader = suite.senders[1].clone();
+
+        let base_simulation = RefCell::new(None::<SimulationResponse>);
+        let simulation = RefCell::new(None::<SimulationResponse>);
+
+        suite
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(100_000_000u128, "uusd".to_string()),
+                "uusdc".to_string(),
+                |result| {
+                    *base_simulation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            .update_imbalance_fee(
+                &creator,
+                "o.stable".to_string(),
+                Some(imbalance_fee()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(100_000_000u128, "uusd".to_string()),
+                "uusdc".to_string(),
+                |result| {
+                    *simulation.borrow_mut() = Some(result.unwrap());
+                },
+            );
+
+        // the trade leaves the pool ~10% imbalanced, so the surcharge is capped at 5%
+        let base_simulation = base_simulation.into_inner().unwrap();
+        let surcharged_simulation = simulation.borrow().clone().unwrap();
+        let gross_return_amount = base_simulation
+            .return_amount
+            .checked_add(base_simulation.swap_fee_amount)
+            .unwrap();
+        assert_eq!(
+            base_simulation.swap_fee_amount,
+            gross_return_amount.mul_floor(Decimal::percent(1))
+        );
+        assert_eq!(
+            surcharged_simulation.swap_fee_amount,
+            gross_return_amount.mul_floor(Decimal::percent(6))
+        );
+        assert!(surcharged_simulation.return_amount < base_simulation.return_amount);
+
+        // the swap matches the simulation
+        suite.swap(
+            &trader,
+            "uusdc".to_string(),
+            None,
+            Some(Decimal::percent(10)),
+            None,
+            "o.stable".to_string(),
+            vec![coin(100_000_000u128, "uusd".to_string())],
+            |result| {
+                let return_amount = result
+                    .unwrap()
+                    .events
+                    .into_iter()
+                    .filter(|event| event.ty == "wasm")
+                    .flat_map(|event| event.attributes)
+                    .find(|attribute| attribute.key == "return_amount")
+                    .map(|attribute| attribute.value.parse::<Uint128>().unwrap())
+                    .unwrap();
+                assert_eq!(
+                    return_amount,
+                    simulation.borrow().as_ref().unwrap().return_amount
+                );
+            },
+        );
+
+        // restoring the balance waives the swap fee, as the discount covers it
+        suite.query_simulation(
+            "o.stable".to_string(),
+            coin(50_000_000u128, "uusdc".to_string()),
+            "uusd".to_string(),
+            |result| {
+                let simulation = result.unwrap();
+                assert_eq!(simulation.swap_fee_amount, Uint128::zero());
+            },
+        );
+
+        // the reverse simulation estimates the adjusted fee too
+        let offer_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_reverse_simulation(
+                "o.stable".to_string(),
+                coin(50_000_000u128, "uusdc".to_string()),
+                "uusd".to_string(),
+                |result| {
+                    *offer_amount.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(offer_amount.borrow().u128(), "uusd".to_string()),
+                "uusdc".to_string(),
+                |result| {
+                    assert_approx_eq!(
+                        result.unwrap().return_amount,
+                        Uint128::new(50_000_000u128),
+                        "0.002"
+                    );
+                },
+            );
+
+        // without the imbalance fee, the base swap fee applies again
+        suite
+            .update_imbalance_fee(&creator, "o.stable".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_simulation(
+                "o.stable".to_string(),
+                coin(50_000_000u128, "uusdc".to_string()),
+                "uusd".to_string(),
+                |result| {
+                    assert!(!result.unwrap().swap_fee_amount.is_zero());
+                },
+            );
+    }
+}
+
+mod composable_stableswap {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{assert_approx_eq, coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uwusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        )
+    }
+
+    #[test]
+    fn cant_create_composable_pools_with_invalid_weights() {
+        let mut suite = setup();
+        let creator = suite.creator();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ComposableStableSwap {
+                    amp: 100,
+                    weights: vec![Decimal::one()],
+                },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolWeights => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolWeights"
+                        ),
+                    }
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ComposableStableSwap {
+                    amp: 100,
+                    weights: vec![Decimal::one(), Decimal::zero()],
+                },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolWeights => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolWeights"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn swaps_at_the_weighted_peg() {
+        let mut suite = setup();
+        let trader = suite.senders[1].clone();
+
+        // 1 uwusd is pegged to 2 uusd
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ComposableStableSwap {
+                    amp: 100,
+                    weights: vec![Decimal::one(), Decimal::from_ratio(2u128, 1u128)],
+                },
+                "composable",
+                vec![
+                    coin(1_000_000_000u128, "uusd".to_string()),
+                    coin(500_000_000u128, "uwusd".to_string()),
+                ],
+            )
+            .query_stableswap_amp("o.composable".to_string(), |result| {
+                assert_eq!(result.unwrap().amp, 100);
+            })
+            // the pool is balanced once the reserves are weighted
+            .query_depeg_protection("o.composable".to_string(), |result| {
+                assert_eq!(result.unwrap().peg_deviation, Decimal::zero());
+            });
+
+        let simulated_return_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                "o.composable".to_string(),
+                coin(1_000_000u128, "uusd".to_string()),
+                "uwusd".to_string(),
+                |result| {
+                    let return_amount = result.unwrap().return_amount;
+                    assert_approx_eq!(return_amount, Uint128::new(500_000u128), "0.001");
+                    *simulated_return_amount.borrow_mut() = return_amount;
+                },
+            )
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.composable".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let return_amount = result
+                        .unwrap()
+                        .events
+                        .into_iter()
+                        .filter(|event| event.ty == "wasm")
+                        .flat_map(|event| event.attributes)
+                        .find(|attribute| attribute.key == "return_amount")
+                        .map(|attribute| attribute.value.parse::<Uint128>().unwrap())
+                        .unwrap();
+                    assert_eq!(return_amount, *simulated_return_amount.borrow());
+                },
+            )
+            .query_simulation(
+                "o.composable".to_string(),
+                coin(1_000_000u128, "uwusd".to_string()),
+                "uusd".to_string(),
+                |result| {
+                    assert_approx_eq!(
+                        result.unwrap().return_amount,
+                        Uint128::new(2_000_000u128),
+                        "0.001"
+                    );
+                },
+            )
+            .query_reverse_simulation(
+                "o.composable".to_string(),
+                coin(500_000u128, "uwusd".to_string()),
+                "uusd".to_string(),
+                |result| {
+                    assert_approx_eq!(
+                        result.unwrap().offer_amount,
+                        Uint128::new(1_000_000u128),
+                        "0.001"
+                    );
+                },
+            );
+    }
+}
+
+mod pegged_pools {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uwusd".to_string()),
+                coin(1_000_000_000_000u128, "uusdt".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        )
+    }
+
+    fn create_pegged_pool(suite: &mut TestingSuite) -> Addr {
+        let oracle = suite.instantiate_mock_oracle();
+        let now = RefCell::new(0u64);
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .get_time(|time| *now.borrow_mut() = time.seconds())
+            .set_oracle_price(&oracle, Decimal::permille(999), *now.borrow(), |result| {
+                result.unwrap();
+            })
+            .create_seeded_pool(
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::Pegged {
+                    oracle: oracle.to_string(),
+                    max_deviation: Decimal::percent(1),
+                    max_staleness: 3_600,
+                },
+                "pegged",
+                vec![
+                    coin(1_000_000_000u128, "uusd".to_string()),
+                    coin(1_000_000_000u128, "uwusd".to_string()),
+                ],
+            );
+
+        oracle
+    }
+
+    #[test]
+    fn cant_create_pegged_pools_with_invalid_config() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let oracle = suite.instantiate_mock_oracle();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::Pegged {
+                    oracle: oracle.to_string(),
+                    max_deviation: Decimal::one(),
+                    max_staleness: 3_600,
+                },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPeggedPoolConfig => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPeggedPoolConfig"
+                        ),
+                    }
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uwusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::Pegged {
+                    oracle: "invalid_oracle".to_string(),
+                    max_deviation: Decimal::percent(1),
+                    max_staleness: 3_600,
+                },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPeggedPoolConfig => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPeggedPoolConfig"
+                        ),
+                    }
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uwusd".to_string(), "uusdt".to_string()],
+                vec![6u8, 6u8, 6u8],
+                zero_fees(),
+                PoolType::Pegged {
+                    oracle: oracle.to_string(),
+                    max_deviation: Decimal::percent(1),
+                    max_staleness: 3_600,
+                },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::TooManyAssets { max_assets, .. } => {
+                            assert_eq!(max_assets, 2);
+                        }
+                        _ => panic!("Wrong error type, should return ContractError::TooManyAssets"),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn swaps_at_the_oracle_rate() {
+        let mut suite = setup();
+        let trader = suite.senders[1].clone();
+        create_pegged_pool(&mut suite);
+
+        // 1 uusd is worth 0.999 uwusd, with no spread whatsoever
+        suite
+            .query_simulation(
+                "o.pegged".to_string(),
+                coin(1_000_000u128, "uusd".to_string()),
+                "uwusd".to_string(),
+                |result| {
+                    let simulation = result.unwrap();
+                    assert_eq!(simulation.return_amount, Uint128::new(999_000u128));
+                    assert_eq!(simulation.spread_amount, Uint128::zero());
+                },
+            )
+            .query_reverse_simulation(
+                "o.pegged".to_string(),
+                coin(999_000u128, "uwusd".to_string()),
+                "uusd".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().offer_amount, Uint128::new(1_000_000u128));
+                },
+            )
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.pegged".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let return_amount = result
+                        .unwrap()
+                        .events
+                        .into_iter()
+                        .filter(|event| event.ty == "wasm")
+                        .flat_map(|event| event.attributes)
+                        .find(|attribute| attribute.key == "return_amount")
+                        .map(|attribute| attribute.value.parse::<Uint128>().unwrap())
+                        .unwrap();
+                    assert_eq!(return_amount, Uint128::new(999_000u128));
+                },
+            )
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.pegged".to_string(),
+                vec![coin(2_000_000_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InsufficientPeggedPoolLiquidity { denom } => {
+                            assert_eq!(denom, "uwusd");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InsufficientPeggedPoolLiquidity"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn rejects_trades_when_the_oracle_rate_is_stale_or_out_of_band() {
+        let mut suite = setup();
+        let trader = suite.senders[1].clone();
+        let oracle = create_pegged_pool(&mut suite);
+
+        suite
+            .add_one_day()
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.pegged".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::StaleOracleRate { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::StaleOracleRate")
+                        }
+                    }
+                },
+            )
+            .query_simulation(
+                "o.pegged".to_string(),
+                coin(1_000_000u128, "uusd".to_string()),
+                "uwusd".to_string(),
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains("stale"));
+                },
+            );
+
+        let now = RefCell::new(0u64);
+        suite
+            .get_time(|time| *now.borrow_mut() = time.seconds())
+            .set_oracle_price(&oracle, Decimal::percent(98), *now.borrow(), |result| {
+                result.unwrap();
+            })
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.pegged".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PeggedRateOutOfBand { rate } => {
+                            assert_eq!(rate, Decimal::percent(98));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PeggedRateOutOfBand"
+                        ),
+                    }
+                },
+            )
+            .set_oracle_price(&oracle, Decimal::one(), *now.borrow(), |result| {
+                result.unwrap();
+            })
+            .swap(
+                &trader,
+                "uwusd".to_string(),
+                None,
+                None,
+                None,
+                "o.pegged".to_string(),
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod reserve_floors {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        suite
+    }
+
+    #[test]
+    fn only_owner_can_set_reserve_floors_of_the_pool_assets() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        suite
+            .update_reserve_floors(
+                &other,
+                "o.om_usd".to_string(),
+                vec![coin(900_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError(_) => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_reserve_floors(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(900_000u128, "uatom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .update_reserve_floors(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(900_000u128, "uusd"), coin(800_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .update_reserve_floors(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(900_000u128, "uusd"), coin(0u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_reserve_floors("o.om_usd".to_string(), |result| {
+                assert_eq!(result.unwrap().floors, vec![coin(900_000u128, "uusd")]);
+            })
+            .update_reserve_floors(&creator, "o.om_usd".to_string(), vec![], |result| {
+                result.unwrap();
+            })
+            .query_reserve_floors("o.om_usd".to_string(), |result| {
+                assert!(result.unwrap().floors.is_empty());
+            });
+    }
+
+    #[test]
+    fn rejects_swaps_draining_an_asset_below_its_floor() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let trader = suite.senders[1].clone();
+
+        suite
+            .update_reserve_floors(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(900_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // leaves ~990_100 uusd in the pool
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(5)),
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // would leave ~825_000 uusd in the pool
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(200_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::ReserveFloorBreached { floor } => {
+                            assert_eq!(floor, coin(900_000u128, "uusd"));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::ReserveFloorBreached"
+                        ),
+                    }
+                },
+            )
+            // draining the other asset is fine
+            .swap(
+                &trader,
+                "uom".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(200_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod dormant_pools {
+    use cosmwasm_std::{assert_approx_eq, coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::lp_common::MINIMUM_LIQUIDITY_AMOUNT;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    /// Creates a pool of the given type and withdraws all its liquidity but the locked minimum.
+    fn setup(pool_type: PoolType) -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uusdc".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                pool_type,
+                "dormant",
+                vec![
+                    coin(1_000_000u128, "uusd".to_string()),
+                    coin(1_000_000u128, "uusdc".to_string()),
+                ],
+            );
+
+        let lp_denom = suite.get_lp_denom("o.dormant".to_string());
+        suite.withdraw_liquidity(
+            &creator,
+            "o.dormant".to_string(),
+            vec![coin(
+                1_000_000u128 - MINIMUM_LIQUIDITY_AMOUNT.u128(),
+                lp_denom,
+            )],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        suite
+    }
+
+    #[test]
+    fn dormant_constant_product_pools_are_reseeded_with_first_deposit_semantics() {
+        let mut suite = setup(PoolType::ConstantProduct);
+        let other = suite.senders[1].clone();
+
+        suite.query_pools(Some("o.dormant".to_string()), None, None, |result| {
+            let response = result.unwrap();
+            assert_eq!(
+                response.pools[0].total_share.amount,
+                MINIMUM_LIQUIDITY_AMOUNT
+            );
+        });
+
+        suite
+            // single side deposits can't be swapped against the dust
+            .provide_liquidity(
+                &other,
+                "o.dormant".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::EmptyPoolForSingleSideLiquidityProvision => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::EmptyPoolForSingleSideLiquidityProvision"
+                        ),
+                    }
+                },
+            )
+            // the deposit sets a new price, and it's not charged the locked minimum again
+            .provide_liquidity(
+                &other,
+                "o.dormant".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(4_000_000u128, "uusd".to_string()),
+                    coin(1_000_000u128, "uusdc".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let lp_denom = suite.get_lp_denom("o.dormant".to_string());
+        suite
+            .query_balance(&other.to_string(), lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(2_000_000u128));
+            })
+            .query_pools(Some("o.dormant".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].total_share.amount,
+                    Uint128::new(2_000_000u128) + MINIMUM_LIQUIDITY_AMOUNT
+                );
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(4_001_000u128, "uusd"), coin(1_001_000u128, "uusdc")]
+                );
+            });
+    }
+
+    #[test]
+    fn dormant_stableswap_pools_are_reseeded_with_first_deposit_semantics() {
+        let mut suite = setup(PoolType::StableSwap { amp: 100 });
+        let other = suite.senders[1].clone();
+
+        suite.provide_liquidity(
+            &other,
+            "o.dormant".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![
+                coin(1_000_000u128, "uusd".to_string()),
+                coin(1_000_000u128, "uusdc".to_string()),
+            ],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        // a balanced deposit is worth the sum of its assets
+        let lp_denom = suite.get_lp_denom("o.dormant".to_string());
+        suite
+            .query_balance(&other.to_string(), lp_denom, |result| {
+                assert_approx_eq!(
+                    result.unwrap().amount,
+                    Uint128::new(2_000_000u128),
+                    "0.0001"
+                );
+            })
+            .swap(
+                &other,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.dormant".to_string(),
+                vec![coin(1_000u128, "uusd".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod donations {
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uatom".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        suite.instantiate_default().add_one_epoch().create_pool(
+            &creator,
+            vec!["uom".to_string(), "uusd".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            Some("om_usd".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        suite
+    }
+
+    #[test]
+    fn cant_donate_to_empty_pools_or_assets_out_of_the_pool() {
+        let mut suite = setup();
+        let creator = suite.creator();
+
+        suite
+            .donate(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::DonationToEmptyPool => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::DonationToEmptyPool"
+                        ),
+                    }
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .donate(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uusd"), coin(1_000u128, "uatom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .donate(&creator, "o.om_usd".to_string(), vec![], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::EmptyAssets => {}
+                    _ => panic!("Wrong error type, should return ContractError::EmptyAssets"),
+                }
+            });
+    }
+
+    #[test]
+    fn donations_are_credited_to_the_reserves_without_minting_lp() {
+        let mut suite = setup();
+        let creator = suite.creator();
+        let donor = suite.senders[1].clone();
+
+        suite.provide_liquidity(
+            &creator,
+            "o.om_usd".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![
+                coin(1_000_000u128, "uom".to_string()),
+                coin(1_000_000u128, "uusd".to_string()),
+            ],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+        suite
+            .donate(
+                &donor,
+                "o.om_usd".to_string(),
+                vec![coin(100_000u128, "uusd"), coin(100_000u128, "uom")],
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| {
+                        event.attributes.iter().any(|attribute| {
+                            attribute.key == "action" && attribute.value == "donate"
+                        })
+                    }));
+                },
+            )
+            .query_balance(&donor.to_string(), lp_denom.clone(), |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].total_share.amount,
+                    Uint128::new(1_000_000u128)
+                );
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(1_100_000u128, "uom"), coin(1_100_000u128, "uusd")]
+                );
+            });
+
+        // the liquidity providers get their share of the donation when withdrawing
+        suite
+            .withdraw_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(999_000u128, lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(1_100u128, "uom"), coin(1_100u128, "uusd")]
+                );
+            });
+    }
+}
+
+mod skim {
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn skims_untracked_balances_to_the_fee_collector() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let other = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            .skim(&other, "uusd".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToSkim { denom } => assert_eq!(denom, "uusd"),
+                    _ => panic!("Wrong error type, should return ContractError::NothingToSkim"),
+                }
+            });
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+        let fee_collector_addr = suite.fee_collector_addr.clone();
+
+        let fee_collector_balance = std::cell::RefCell::new(Uint128::zero());
+        suite
+            .skim(&other, lp_denom, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::CannotSkimLpDenom { .. } => {}
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::CannotSkimLpDenom")
+                    }
+                }
+            })
+            // tokens sent to the contract by mistake
+            .send_tokens(
+                &other,
+                &pool_manager_addr,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&fee_collector_addr.to_string(), "uusd", |result| {
+                *fee_collector_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .skim(&other, "uusd".to_string(), |result| {
+                result.unwrap();
+            })
+            .query_balance(&fee_collector_addr.to_string(), "uusd", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *fee_collector_balance.borrow() + Uint128::new(5_000u128)
+                );
+            })
+            .query_balance(&pool_manager_addr.to_string(), "uusd", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_000u128));
+            });
+    }
+}
+
+mod sync_pool {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn syncs_pool_reserves_within_the_max_deviation() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+
+        suite
+            .send_tokens(
+                &other,
+                &pool_manager_addr,
+                vec![coin(5_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .sync_pool(
+                &other,
+                "o.om_usd".to_string(),
+                Decimal::percent(1),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            // the 5_000 uusd are a 0.5% deviation
+            .sync_pool(
+                &creator,
+                "o.om_usd".to_string(),
+                Decimal::permille(1),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::SyncDeviationExceeded {
+                            tracked,
+                            attributable,
+                            ..
+                        } => {
+                            assert_eq!(tracked, coin(1_000_000u128, "uusd"));
+                            assert_eq!(attributable, coin(1_005_000u128, "uusd"));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::SyncDeviationExceeded"
+                        ),
+                    }
+                },
+            )
+            .sync_pool(
+                &creator,
+                "o.om_usd".to_string(),
+                Decimal::percent(1),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(1_000_000u128, "uom"), coin(1_005_000u128, "uusd")]
+                );
+            })
+            // nothing left to skim, the balance is now part of the pool
+            .skim(&other, "uusd".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToSkim { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::NothingToSkim"),
+                }
+            });
+    }
+}
+
+mod bootstrap_pool {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::farm_manager::PositionsBy;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn bootstraps_a_pool_into_a_locked_position() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        let seed_assets = vec![
+            coin(1_000_000u128, "uom".to_string()),
+            coin(1_000_000u128, "uusd".to_string()),
+        ];
+        let funds = vec![
+            coin(1_008_888u128, "uom".to_string()),
+            coin(1_001_000u128, "uusd".to_string()),
+        ];
+
+        suite.instantiate_default().add_one_epoch().bootstrap_pool(
+            &other,
+            vec!["uom".to_string(), "uusd".to_string()],
+            vec![6u8, 6u8],
+            pool_fees.clone(),
+            PoolType::ConstantProduct,
+            Some("om_usd".to_string()),
+            seed_assets.clone(),
+            86_400u64,
+            None,
+            funds.clone(),
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            },
+        );
+
+        // the funds don't cover the creation fees on top of the seed assets
+        suite.bootstrap_pool(
+            &creator,
+            vec!["uom".to_string(), "uusd".to_string()],
+            vec![6u8, 6u8],
+            pool_fees.clone(),
+            PoolType::ConstantProduct,
+            Some("om_usd".to_string()),
+            seed_assets.clone(),
+            86_400u64,
+            None,
+            vec![
+                coin(1_008_888u128, "uom".to_string()),
+                coin(999_000u128, "uusd".to_string()),
+            ],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidBootstrapSeed => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidBootstrapSeed"
+                    ),
+                }
+            },
+        );
+
+        suite
+            .bootstrap_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                seed_assets,
+                86_400u64,
+                None,
+                funds,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(1_000_000u128, "uom"), coin(1_000_000u128, "uusd")]
+                );
+            });
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+
+        suite
+            .query_balance(&creator.to_string(), &lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::zero());
+            })
+            .query_farm_positions(
+                Some(PositionsBy::Receiver(creator.to_string())),
+                None,
+                None,
+                None,
+                |result| {
+                    let positions = result.unwrap().positions;
+                    assert_eq!(positions.len(), 1);
+                    assert_eq!(positions[0].lp_asset, coin(999_000u128, &lp_denom));
+                    assert_eq!(positions[0].unlocking_duration, 86_400u64);
+                    assert_eq!(positions[0].receiver, creator);
+                    assert!(positions[0].open);
+                },
+            );
+    }
+}
+
+mod clone_pool {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{PoolOverrides, PoolType};
+
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    #[test]
+    fn clones_a_pool_with_overrides() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+        let new_fee_tier = PoolFee {
+            swap_fee: Fee {
+                share: Decimal::percent(1),
+            },
+            ..pool_fees.clone()
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 8u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .clone_pool(
+                &other,
+                "o.unknown".to_string(),
+                Some("om_usd_1".to_string()),
+                PoolOverrides::default(),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnExistingPool => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::UnExistingPool")
+                        }
+                    }
+                },
+            )
+            .clone_pool(
+                &other,
+                "o.om_usd".to_string(),
+                Some("om_usd_1".to_string()),
+                PoolOverrides {
+                    pool_fees: Some(new_fee_tier.clone()),
+                    pool_type: None,
+                },
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.om_usd_1".to_string()), None, None, |result| {
+                let pool_info = result.unwrap().pools[0].pool_info.clone();
+                assert_eq!(pool_info.asset_denoms, vec!["uom", "uusd"]);
+                assert_eq!(pool_info.asset_decimals, vec![6u8, 8u8]);
+                assert_eq!(pool_info.pool_type, PoolType::ConstantProduct);
+                assert_eq!(pool_info.pool_fees, new_fee_tier);
+            })
+            // the source pool is left untouched
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                assert_eq!(result.unwrap().pools[0].pool_info.pool_fees, pool_fees);
+            });
+    }
+}
+
+mod canonical_pool_identifiers {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::helpers::get_canonical_pool_identifier;
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn creates_pools_with_canonical_identifiers() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        // the identifier doesn't depend on the order of the assets
+        let canonical_identifier = get_canonical_pool_identifier(
+            &["uusd".to_string(), "uom".to_string()],
+            &PoolType::ConstantProduct,
+            &pool_fees,
+        );
+        assert_eq!(
+            canonical_identifier,
+            get_canonical_pool_identifier(
+                &["uom".to_string(), "uusd".to_string()],
+                &PoolType::ConstantProduct,
+                &pool_fees,
+            )
+        );
+        assert!(canonical_identifier.starts_with("c."));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool_with_canonical_identifier(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                true,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some(canonical_identifier.clone()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.pool_identifier,
+                    canonical_identifier
+                );
+            })
+            // the explicit identifier is kept as an alias
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.pool_identifier,
+                    canonical_identifier
+                );
+            })
+            .provide_liquidity(
+                &creator,
+                canonical_identifier.clone(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the alias can't be reused by another pool
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolExists { identifier, .. } => {
+                            assert_eq!(identifier, "o.om_usd")
+                        }
+                        _ => panic!("Wrong error type, should return ContractError::PoolExists"),
+                    }
+                },
+            )
+            // nor can the canonical identifier
+            .create_pool_with_canonical_identifier(
+                &creator,
+                vec!["uusd".to_string(), "uom".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                None,
+                true,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolExists { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::PoolExists"),
+                    }
+                },
+            );
+    }
+}
+
+mod spread_config {
+    use cosmwasm_std::{coin, Decimal, StdError};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn applies_the_configured_spread_default_and_cap() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        let config = suite.query_config();
+        assert_eq!(config.default_max_spread, Decimal::percent(1));
+        assert_eq!(config.max_spread_cap, Decimal::percent(50));
+
+        // a ~5% spread is over the 1% default
+        suite
+            .swap(
+                &other,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(50_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(
+                        err,
+                        ContractError::Std(StdError::generic_err("Spread limit exceeded"))
+                    );
+                },
+            )
+            .update_spread_config(&other, Some(Decimal::percent(10)), None, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            // the default can't exceed the cap
+            .update_spread_config(
+                &creator,
+                Some(Decimal::percent(10)),
+                Some(Decimal::percent(5)),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidSpreadConfig => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidSpreadConfig"
+                        ),
+                    }
+                },
+            )
+            .update_spread_config(&creator, Some(Decimal::percent(10)), None, |result| {
+                result.unwrap();
+            })
+            .swap(
+                &other,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(50_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the cap applies even if a larger max spread is given
+            .update_spread_config(
+                &creator,
+                Some(Decimal::percent(1)),
+                Some(Decimal::percent(2)),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &other,
+                "uusd".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(50_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(
+                        err,
+                        ContractError::Std(StdError::generic_err("Spread limit exceeded"))
+                    );
+                },
+            );
+
+        let config = suite.query_config();
+        assert_eq!(config.default_max_spread, Decimal::percent(1));
+        assert_eq!(config.max_spread_cap, Decimal::percent(2));
+    }
+}
+
+mod lp_supply_cache {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+
+    #[test]
+    fn cached_lp_supply_tracks_the_bank_supply() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        let cached_supply = RefCell::new(Uint128::zero());
+        let assert_supply_in_sync = |suite: &mut TestingSuite| {
+            suite
+                .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                    *cached_supply.borrow_mut() = result.unwrap().pools[0].total_share.amount;
+                })
+                .query_lp_supply("o.om_usd".to_string(), |result| {
+                    assert_eq!(result.unwrap().amount, *cached_supply.borrow());
+                });
+        };
+
+        suite.instantiate_default().add_one_epoch().create_pool(
+            &creator,
+            vec!["uom".to_string(), "uusd".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            Some("om_usd".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+        assert_supply_in_sync(&mut suite);
+
+        suite.provide_liquidity(
+            &creator,
+            "o.om_usd".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![
+                coin(1_000_000u128, "uom".to_string()),
+                coin(1_000_000u128, "uusd".to_string()),
+            ],
+            |result| {
+                result.unwrap();
+            },
+        );
+        assert_supply_in_sync(&mut suite);
+        assert_eq!(*cached_supply.borrow(), Uint128::new(1_000_000u128));
+
+        suite.provide_liquidity(
+            &other,
+            "o.om_usd".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![coin(10_000u128, "uom".to_string())],
+            |result| {
+                result.unwrap();
+            },
+        );
+        assert_supply_in_sync(&mut suite);
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+        suite.withdraw_liquidity(
+            &creator,
+            "o.om_usd".to_string(),
+            vec![coin(500_000u128, lp_denom)],
+            |result| {
+                result.unwrap();
+            },
+        );
+        assert_supply_in_sync(&mut suite);
+    }
+
+    #[test]
+    fn migration_backfills_the_lp_supply_cache() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            // as for a pool created before the supply was cached
+            .clear_cached_lp_supply("o.om_usd")
+            .query_cached_lp_supply("o.om_usd", |result| {
+                assert_eq!(result, None);
+            })
+            .migrate_pool_manager(|result| {
+                result.unwrap();
+            })
+            .query_cached_lp_supply("o.om_usd", |result| {
+                assert_eq!(result, Some(Uint128::new(1_000_000u128)));
+            });
+    }
+}
+
+mod ibc_denom_traces {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    const FAKE_IBC_DENOM: &str =
+        "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    #[test]
+    fn rejects_ibc_denoms_without_a_matching_trace() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, FAKE_IBC_DENOM.to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec![FAKE_IBC_DENOM.to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("atom_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidIbcDenomTrace { denom } => {
+                            assert_eq!(denom, FAKE_IBC_DENOM)
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidIbcDenomTrace"
+                        ),
+                    }
+                },
+            )
+            // pools of native denoms don't need a trace
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_denom_traces("o.om_usd".to_string(), |result| {
+                assert!(result.unwrap().denom_traces.is_empty());
+            });
+    }
+
+    #[test]
+    fn updates_ibc_source_channels() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![coin(1_000_000_000u128, "uom".to_string())],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+
+        suite
+            .instantiate_default()
+            .update_ibc_source_channels(&other, vec!["channel-0".to_string()], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_ibc_source_channels(&creator, vec!["transfer".to_string()], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidIbcChannel { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::InvalidIbcChannel"),
+                }
+            })
+            .update_ibc_source_channels(
+                &creator,
+                vec!["channel-0".to_string(), "channel-5".to_string()],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_ibc_source_channels(|result| {
+                assert_eq!(result.unwrap().channels, vec!["channel-0", "channel-5"]);
+            })
+            .update_ibc_source_channels(&creator, vec![], |result| {
+                result.unwrap();
+            })
+            .query_ibc_source_channels(|result| {
+                assert!(result.unwrap().channels.is_empty());
+            });
+    }
+}
+
+mod ibc_hooks {
+    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::{coin, Addr, CosmosMsg, IbcMsg, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PacketForward, PoolType, SwapOperation};
+
+    use crate::ibc_transfer::commands::get_ibc_forward_msg;
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn hook_swaps_send_the_output_to_the_receiver() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        // stands for the address ibc-hooks derives for the remote sender
+        let hook_sender = suite.senders[1].clone();
+        let receiver = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            .hook_swap(
+                &hook_sender,
+                "uusd".to_string(),
+                None,
+                "o.om_usd".to_string(),
+                receiver.to_string(),
+                Some(PacketForward {
+                    receiver: "cosmos1remote".to_string(),
+                    port: "wasm.contract".to_string(),
+                    channel: "channel-1".to_string(),
+                    next: None,
+                }),
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidIbcChannel { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidIbcChannel"
+                        ),
+                    }
+                },
+            );
+
+        let receiver_balance = std::cell::RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&receiver.to_string(), "uusd", |result| {
+                *receiver_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .hook_swap(
+                &hook_sender,
+                "uusd".to_string(),
+                None,
+                "o.om_usd".to_string(),
+                receiver.to_string(),
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&receiver.to_string(), "uusd", |result| {
+                // 1_000 * 1_000_000 / 1_001_000
+                assert_eq!(
+                    result.unwrap().amount,
+                    *receiver_balance.borrow() + Uint128::new(999u128)
+                );
+            });
+    }
+
+    #[test]
+    fn forwarded_outputs_carry_the_packet_forward_metadata() {
+        let env = mock_env();
+
+        let msg = get_ibc_forward_msg(
+            &env,
+            "channel-1".to_string(),
+            coin(1_000u128, "uusd"),
+            "osmo1hop",
+            &Addr::unchecked("alice"),
+            Some(PacketForward {
+                receiver: "cosmos1remote".to_string(),
+                port: "transfer".to_string(),
+                channel: "channel-7".to_string(),
+                next: None,
+            }),
+        )
+        .unwrap();
+
+        match msg {
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id,
+                to_address,
+                amount,
+                memo,
+                ..
+            }) => {
+                assert_eq!(channel_id, "channel-1");
+                assert_eq!(to_address, "osmo1hop");
+                assert_eq!(amount, coin(1_000u128, "uusd"));
+
+                let memo = memo.unwrap();
+                assert!(memo.contains(
+                    r#""forward":{"receiver":"cosmos1remote","port":"transfer","channel":"channel-7""#
+                ));
+                assert!(memo.contains(r#""withdrawal_refund":{"address":"alice","denom":"uusd"}"#));
+            }
+            _ => panic!("Should be an IBC transfer"),
+        }
+    }
+
+    #[test]
+    fn hook_swap_operations_send_the_output_of_the_route_to_the_receiver() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000_000u128, "uusdc".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        // stands for the address ibc-hooks derives for the remote sender
+        let hook_sender = suite.senders[1].clone();
+        let receiver = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("usd_usdc".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uusd".to_string()),
+                    coin(1_000_000u128, "uusdc".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let operations = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uom".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.om_usd".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusd".to_string(),
+                token_out_denom: "uusdc".to_string(),
+                pool_identifier: "o.usd_usdc".to_string(),
+            },
+        ];
+
+        suite.hook_swap_operations(
+            &hook_sender,
+            operations.clone(),
+            None,
+            None,
+            receiver.to_string(),
+            Some(PacketForward {
+                receiver: "cosmos1remote".to_string(),
+                port: "wasm.contract".to_string(),
+                channel: "channel-1".to_string(),
+                next: None,
+            }),
+            vec![coin(1_000u128, "uom")],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidIbcChannel { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::InvalidIbcChannel"),
+                }
+            },
+        );
+
+        let receiver_balance = std::cell::RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&receiver.to_string(), "uusdc", |result| {
+                *receiver_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .hook_swap_operations(
+                &hook_sender,
+                operations.clone(),
+                Some(Uint128::new(999u128)),
+                None,
+                receiver.to_string(),
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .hook_swap_operations(
+                &hook_sender,
+                operations,
+                Some(Uint128::new(998u128)),
+                None,
+                receiver.to_string(),
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&receiver.to_string(), "uusdc", |result| {
+                // 1_000 * 1_000_000 / 1_001_000 = 999, then 999 * 1_000_000 / 1_000_999 = 998
+                assert_eq!(
+                    result.unwrap().amount,
+                    *receiver_balance.borrow() + Uint128::new(998u128)
+                );
+            });
+    }
+}
+
+mod ica_liquidity {
+    use cosmwasm_std::{coin, from_json, Coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, ProvideLiquidityResult};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn remote_liquidity_provision_returns_its_result() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        // stands for the interchain account of a DAO on another chain
+        let interchain_account = suite.senders[1].clone();
+        let dao_treasury = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            // an invalid receiver is rejected instead of minting the LP tokens to the sender
+            .provide_liquidity(
+                &interchain_account,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                Some("not_an_address".to_string()),
+                vec![
+                    coin(500_000u128, "uom".to_string()),
+                    coin(500_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Std(_) => {}
+                        _ => panic!("Wrong error type, should return ContractError::Std"),
+                    }
+                },
+            );
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+        suite.provide_liquidity(
+            &interchain_account,
+            "o.om_usd".to_string(),
+            None,
+            None,
+            None,
+            Some(dao_treasury.to_string()),
+            vec![
+                coin(500_000u128, "uom".to_string()),
+                coin(500_000u128, "uusd".to_string()),
+            ],
+            |result| {
+                let data = result.unwrap().data.unwrap();
+                let result: ProvideLiquidityResult = from_json(data).unwrap();
+
+                assert_eq!(
+                    result,
+                    ProvideLiquidityResult {
+                        pool_identifier: "o.om_usd".to_string(),
+                        receiver: dao_treasury.to_string(),
+                        lp_asset: Coin {
+                            denom: lp_denom.clone(),
+                            amount: Uint128::new(500_000u128),
+                        },
+                        locked: false,
+                    }
+                );
+            },
+        );
+    }
+}
+
+mod contract_registry {
+    use cosmwasm_std::coin;
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+
+    use crate::tests::suite::TestingSuite;
+
+    #[test]
+    fn registry_returns_the_current_contracts() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![coin(1_000_000_000u128, "uom".to_string())],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        suite.instantiate_default();
+
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+        let farm_manager_addr = suite.farm_manager_addr.clone();
+        let fee_collector_addr = suite.fee_collector_addr.clone();
+        let epoch_manager_addr = suite.epoch_manager_addr.clone();
+
+        suite.query_contract_registry(|result| {
+            let contracts = result.unwrap().contracts;
+
+            let registered = contracts
+                .iter()
+                .map(|contract| {
+                    (
+                        contract.name.as_str(),
+                        contract.address.clone(),
+                        contract.contract.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                registered,
+                vec![
+                    (
+                        "pool_manager",
+                        pool_manager_addr.clone(),
+                        Some("mantra:pool-manager".to_string())
+                    ),
+                    (
+                        "router",
+                        pool_manager_addr.clone(),
+                        Some("mantra:pool-manager".to_string())
+                    ),
+                    (
+                        "farm_manager",
+                        farm_manager_addr.clone(),
+                        Some("mantra:farm-manager".to_string())
+                    ),
+                    (
+                        "fee_collector",
+                        fee_collector_addr.clone(),
+                        Some("mantra:fee-collector".to_string())
+                    ),
+                    (
+                        "epoch_manager",
+                        epoch_manager_addr.clone(),
+                        Some("mantra:epoch-manager".to_string())
+                    ),
+                ]
+            );
+            assert_eq!(
+                contracts[0].version,
+                Some(env!("CARGO_PKG_VERSION").to_string())
+            );
+        });
+    }
+}
+
+mod event_schema {
+    use cosmwasm_std::coin;
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::common::EVENT_SCHEMA_VERSION;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+
+    #[test]
+    fn events_carry_the_schema_version() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = zero_fees();
+
+        let assert_schema_version = |events: Vec<cosmwasm_std::Event>, action: &str| {
+            let event = events
+                .into_iter()
+                .find(|event| {
+                    event.ty == "wasm"
+                        && event
+                            .attributes
+                            .iter()
+                            .any(|attr| attr.key == "action" && attr.value == action)
+                })
+                .unwrap();
+
+            // the version is appended once, after the attributes of the action
+            let versions = event
+                .attributes
+                .iter()
+                .filter(|attr| attr.key == "schema_version")
+                .collect::<Vec<_>>();
+            assert_eq!(versions.len(), 1);
+            assert_eq!(versions[0].value, EVENT_SCHEMA_VERSION);
+            assert_eq!(event.attributes.last().unwrap().key, "schema_version");
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    assert_schema_version(result.unwrap().events, "create_pool");
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    assert_schema_version(result.unwrap().events, "provide_liquidity");
+                },
+            )
+            .swap(
+                &creator,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    assert_schema_version(result.unwrap().events, "swap");
+                },
+            );
+    }
+}
+
+mod permissioned_pools {
+    use cosmwasm_std::coin;
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn only_allowlisted_addresses_trade_in_permissioned_pools() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let admin = suite.senders[1].clone();
+        let trader = suite.senders[2].clone();
+        let outsider = suite.senders[3].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            .update_permissioned_pool(
+                &admin,
+                "o.om_usd".to_string(),
+                Some(admin.to_string()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_permissioned_pool(
+                &creator,
+                "o.om_usd".to_string(),
+                Some(admin.to_string()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NotAllowlisted { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::NotAllowlisted")
+                        }
+                    }
+                },
+            )
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000u128, "uom".to_string()),
+                    coin(1_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NotAllowlisted { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::NotAllowlisted")
+                        }
+                    }
+                },
+            )
+            // only the admin of the pool or the owner manage the allowlist
+            .update_pool_allowlist(
+                &outsider,
+                "o.om_usd".to_string(),
+                vec![outsider.to_string()],
+                vec![],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            .update_pool_allowlist(
+                &admin,
+                "o.om_usd".to_string(),
+                vec![trader.to_string()],
+                vec![],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000u128, "uom".to_string()),
+                    coin(1_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // LP tokens can't be minted to addresses outside the allowlist either
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                Some(outsider.to_string()),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NotAllowlisted { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::NotAllowlisted")
+                        }
+                    }
+                },
+            )
+            .query_pool_allowlist("o.om_usd".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.admin, Some(admin.clone()));
+                assert_eq!(response.addresses, vec![trader.clone()]);
+            })
+            // making the pool public again clears its allowlist
+            .update_permissioned_pool(&creator, "o.om_usd".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_pool_allowlist("o.om_usd".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.admin, None);
+                assert!(response.addresses.is_empty());
+            })
+            .swap(
+                &outsider,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod circuit_breaker_sudo {
+    use circuit_breaker::CircuitBreakerError;
+    use cosmwasm_std::coin;
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SudoMsg};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn chain_disables_and_enables_messages() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let trader = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(vec!["uom".to_string(), "uusd".to_string()], vec![6u8, 6u8], pool_fees, PoolType::ConstantProduct, "om_usd", vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ])
+            .sudo(
+                SudoMsg::DisableMessages {
+                    messages: vec!["".to_string()],
+                },
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::CircuitBreakerError(
+                            CircuitBreakerError::InvalidMessageName { message },
+                        ) => {
+                            assert_eq!(message, "");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return CircuitBreakerError::InvalidMessageName"
+                        ),
+                    }
+                },
+            )
+            // a typo doesn't go through, nor does the rest of the batch
+            .sudo(
+                SudoMsg::DisableMessages {
+                    messages: vec!["swap".to_string(), "withdraw_liqudity".to_string()],
+                },
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::CircuitBreakerError(
+                            CircuitBreakerError::InvalidMessageName { message },
+                        ) => {
+                            assert_eq!(message, "withdraw_liqudity");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return CircuitBreakerError::InvalidMessageName"
+                        ),
+                    }
+                },
+            )
+            .query_disabled_messages(|result| {
+                assert!(result.unwrap().messages.is_empty());
+            })
+            .sudo(
+                SudoMsg::DisableMessages {
+                    messages: vec!["swap".to_string(), "withdraw_liquidity".to_string()],
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_disabled_messages(|result| {
+                assert_eq!(
+                    result.unwrap().messages,
+                    vec!["swap".to_string(), "withdraw_liquidity".to_string()]
+                );
+            })
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::CircuitBreakerError(
+                            CircuitBreakerError::MessageDisabled { message },
+                        ) => {
+                            assert_eq!(message, "swap");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return CircuitBreakerError::MessageDisabled"
+                        ),
+                    }
+                },
+            )
+            // messages that weren't disabled go through
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000u128, "uom".to_string()),
+                    coin(1_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .sudo(
+                SudoMsg::EnableMessages {
+                    messages: vec!["swap".to_string()],
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_disabled_messages(|result| {
+                assert_eq!(
+                    result.unwrap().messages,
+                    vec!["withdraw_liquidity".to_string()]
+                );
+            })
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .sudo(
+                SudoMsg::EnableMessages {
+                    messages: vec!["withdraw_liquidity".to_string()],
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_disabled_messages(|result| {
+                assert!(result.unwrap().messages.is_empty());
+            });
+    }
+}
+
+mod rounding_dust {
+    use cosmwasm_std::{coin, Decimal256, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn tracks_and_sweeps_rounding_dust() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let trader = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            .update_dust_tracking(&trader, true, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_dust_tracking(&creator, true, |result| {
+                result.unwrap();
+            })
+            // 1001 uom return 999.000999... uusd, rounded down to 999
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_001u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_rounding_dust("o.om_usd".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.enabled);
+                assert_eq!(response.accrued[0].denom, "uom");
+                assert!(response.accrued[0].amount.is_zero());
+                assert_eq!(response.accrued[1].denom, "uusd");
+                assert!(
+                    response.accrued[1].amount > Decimal256::percent(99)
+                        && response.accrued[1].amount < Decimal256::one()
+                );
+                // no fee was rounded
+                assert!(response.leaked.iter().all(|dust| dust.amount.is_zero()));
+            })
+            .sweep_dust(&creator, "o.om_usd".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToSweep { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::NothingToSweep"),
+                }
+            })
+            // the second swap returns 998.002... uusd, pushing the dust above a unit
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_001u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .sweep_dust(&trader, "o.om_usd".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .sweep_dust(&creator, "o.om_usd".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.events.iter().any(|event| event
+                    .attributes
+                    .iter()
+                    .any(|attribute| attribute.key == "swept" && attribute.value == "1uusd")));
+            })
+            .query_rounding_dust("o.om_usd".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.accrued[1].amount < Decimal256::percent(1));
+            })
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let pool_info = result.unwrap().pools[0].pool_info.clone();
+                // the swept unit left the reserves on top of the swap returns
+                assert_eq!(
+                    pool_info.assets[1].amount,
+                    Uint128::new(1_000_000 - 999 - 998 - 1)
+                );
+            });
+    }
+}
+
+mod single_side_liquidity_provision_rollback {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::TestingSuite;
+
+    #[test]
+    fn failed_swap_refunds_the_deposit_and_clears_the_buffer() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let depositor = suite.senders[1].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(2),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            )
+            // the floor makes the internal swap of any single side provision of uom fail
+            .update_reserve_floors(
+                &creator,
+                "o.om_usd".to_string(),
+                vec![coin(999_999u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &depositor,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    let response = result.unwrap();
+                    let attributes = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .map(|attribute| (attribute.key.as_str(), attribute.value.as_str()))
+                        .collect::<Vec<_>>();
+
+                    assert!(
+                        attributes.contains(&("action", "single_side_liquidity_provision_failed"))
+                    );
+                    assert!(attributes.contains(&("refund", "10000uom")));
+                    assert!(attributes.iter().any(|(key, _)| *key == "error"));
+                },
+            )
+            .query_single_side_liquidity_provision_buffer(|buffer| {
+                assert!(buffer.is_none());
+            })
+            // the depositor got the whole deposit back and no LP tokens
+            .query_balance(&depositor.to_string(), "uom", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_000_000));
+            });
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+
+        suite
+            .query_balance(&depositor.to_string(), lp_denom.clone(), |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            // once the floor is lifted, the same provision goes through
+            .update_reserve_floors(&creator, "o.om_usd".to_string(), vec![], |result| {
+                result.unwrap();
+            })
+            .provide_liquidity(
+                &depositor,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_single_side_liquidity_provision_buffer(|buffer| {
+                assert!(buffer.is_none());
+            })
+            .query_balance(&depositor.to_string(), lp_denom, |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            });
+    }
+}
+
+mod failure_injection {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{FailureInjection, PoolType, SudoMsg};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uusdc".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::permille(2));
+
+        suite.instantiate_default().add_one_epoch();
+
+        for (asset_denoms, pool_type, identifier) in [
+            (
+                vec!["uom".to_string(), "uusd".to_string()],
+                PoolType::ConstantProduct,
+                "om_usd",
+            ),
+            (
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                PoolType::StableSwap { amp: 100 },
+                "usd_usdc",
+            ),
+        ] {
+            suite.create_seeded_pool(
+                asset_denoms.clone(),
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                pool_type,
+                identifier,
+                asset_denoms
+                    .iter()
+                    .map(|denom| coin(1_000_000u128, denom))
+                    .collect(),
+            );
+        }
+
+        suite
+    }
+
+    #[cfg(not(feature = "failure-injection"))]
+    #[test]
+    fn failure_injection_is_unavailable_without_the_feature() {
+        let mut suite = setup();
+
+        suite.sudo(
+            SudoMsg::UpdateFailureInjection {
+                failures: FailureInjection {
+                    stableswap_convergence: true,
+                    bank_sends: false,
+                    replies: vec![],
+                },
+            },
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::FailureInjectionUnavailable => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::FailureInjectionUnavailable"
+                    ),
+                }
+            },
+        );
+    }
+
+    #[cfg(feature = "failure-injection")]
+    #[test]
+    fn injected_failures_exercise_error_paths() {
+        let mut suite = setup();
+        let trader = suite.senders[1].clone();
+
+        suite
+            .sudo(
+                SudoMsg::UpdateFailureInjection {
+                    failures: FailureInjection {
+                        stableswap_convergence: true,
+                        bank_sends: false,
+                        replies: vec![],
+                    },
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &trader,
+                "uusdc".to_string(),
+                None,
+                None,
+                None,
+                "o.usd_usdc".to_string(),
+                vec![coin(1_000u128, "uusd".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::ConvergeError { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::ConvergeError"),
+                    }
+                },
+            )
+            // constant product pools don't go through the solvers
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .sudo(
+                SudoMsg::UpdateFailureInjection {
+                    failures: FailureInjection {
+                        stableswap_convergence: false,
+                        bank_sends: true,
+                        replies: vec![],
+                    },
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &trader,
+                "uusd".to_string(),
+                None,
+                None,
+                None,
+                "o.om_usd".to_string(),
+                vec![coin(1_000u128, "uom".to_string())],
+                |result| {
+                    assert!(result.is_err());
+                },
+            )
+            // the internal swap of a single side provision fails, so the deposit is refunded
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response
+                        .events
+                        .iter()
+                        .any(|event| event
+                            .attributes
+                            .iter()
+                            .any(|attribute| attribute.value
+                                == "single_side_liquidity_provision_failed")));
+                },
+            )
+            .sudo(
+                SudoMsg::UpdateFailureInjection {
+                    failures: FailureInjection {
+                        stableswap_convergence: false,
+                        bank_sends: false,
+                        replies: vec![crate::contract::SINGLE_SIDE_LIQUIDITY_PROVISION_REPLY_ID],
+                    },
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InjectedFailure { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::InjectedFailure")
+                        }
+                    }
+                },
+            )
+            // clearing the injected failures restores the contract
+            .sudo(
+                SudoMsg::UpdateFailureInjection {
+                    failures: FailureInjection {
+                        stableswap_convergence: false,
+                        bank_sends: false,
+                        replies: vec![],
+                    },
+                },
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &trader,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "uom".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod create_pools {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{PoolCreationParams, PoolType};
+
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    fn pool_creation_params(pool_identifier: &str) -> PoolCreationParams {
+        PoolCreationParams {
+            asset_denoms: vec!["uom".to_string(), "uusd".to_string()],
+            asset_decimals: vec![6u8, 6u8],
+            pool_fees: PoolFee {
+                protocol_fee: Fee {
+                    share: Decimal::permille(1),
+                },
+                swap_fee: Fee {
+                    share: Decimal::permille(2),
+                },
+                burn_fee: Fee {
+                    share: Decimal::zero(),
+                },
+                extra_fees: vec![],
+            },
+            pool_type: PoolType::ConstantProduct,
+            pool_identifier: Some(pool_identifier.to_string()),
+            canonical_identifier: false,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn creates_pools_in_bulk() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pools(&creator, vec![], vec![], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NoPoolsToCreate => {}
+                    _ => panic!("Wrong error type, should return ContractError::NoPoolsToCreate"),
+                }
+            })
+            .create_pools(
+                &creator,
+                vec![
+                    pool_creation_params("pool.1"),
+                    pool_creation_params("pool.2"),
+                ],
+                vec![coin(1_000, "uusd"), coin(17_776, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolCreationFee { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolCreationFee"
+                        ),
+                    }
+                },
+            )
+            .create_pools(
+                &creator,
+                vec![
+                    pool_creation_params("pool.1"),
+                    pool_creation_params("pool.2"),
+                ],
+                vec![coin(2_001, "uusd"), coin(17_776, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnevenPoolCreationFees { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnevenPoolCreationFees"
+                        ),
+                    }
+                },
+            )
+            // pools are created atomically, so a single invalid pool fails the whole batch
+            .create_pools(
+                &creator,
+                vec![
+                    pool_creation_params("pool.1"),
+                    pool_creation_params("pool.1"),
+                ],
+                vec![coin(2_000, "uusd"), coin(17_776, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolExists { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::PoolExists"),
+                    }
+                },
+            )
+            .query_pools(None, None, None, |result| {
+                assert_eq!(result.unwrap().total_count, 0);
+            });
+
+        suite
+            .create_pools(
+                &creator,
+                vec![
+                    pool_creation_params("pool.1"),
+                    pool_creation_params("pool.2"),
+                    pool_creation_params("pool.3"),
+                ],
+                vec![coin(3_000, "uusd"), coin(26_664, "uom")],
+                |result| {
+                    let response = result.unwrap();
+                    let pool_identifiers = response
+                        .events
+                        .iter()
+                        .filter(|event| event.ty == "wasm-create_pool")
+                        .filter_map(|event| {
+                            event
+                                .attributes
+                                .iter()
+                                .find(|attribute| attribute.key == "pool_identifier")
+                                .map(|attribute| attribute.value.clone())
+                        })
+                        .collect::<Vec<_>>();
+                    assert_eq!(pool_identifiers, vec!["o.pool.1", "o.pool.2", "o.pool.3"]);
+                },
+            )
+            .query_pools(None, None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.total_count, 3);
+                assert_eq!(response.pools[0].pool_info.pool_identifier, "o.pool.1");
+                assert_eq!(response.pools[2].pool_info.pool_identifier, "o.pool.3");
+            });
+    }
+}
+
+mod pool_admin {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn transfers_the_pool_admin_in_two_steps() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let partner = suite.senders[1].clone();
+        let new_partner = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &partner,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("partner".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the creator of the pool is its admin
+            .query_pool_admin("o.partner".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.admin, Some(partner.clone()));
+                assert_eq!(response.pending_admin, None);
+            })
+            .propose_pool_admin(
+                &new_partner,
+                "o.partner".to_string(),
+                Some(new_partner.to_string()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            .accept_pool_admin(&new_partner, "o.partner".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NoPendingPoolAdmin { .. } => {}
+                    _ => {
+                        panic!("Wrong error type, should return ContractError::NoPendingPoolAdmin")
+                    }
+                }
+            })
+            .propose_pool_admin(
+                &partner,
+                "o.partner".to_string(),
+                Some(new_partner.to_string()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the admin doesn't change until the transfer is accepted
+            .query_pool_admin("o.partner".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.admin, Some(partner.clone()));
+                assert_eq!(response.pending_admin, Some(new_partner.clone()));
+            })
+            .accept_pool_admin(&owner, "o.partner".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .accept_pool_admin(&new_partner, "o.partner".to_string(), |result| {
+                result.unwrap();
+            })
+            .query_pool_admin("o.partner".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.admin, Some(new_partner.clone()));
+                assert_eq!(response.pending_admin, None);
+            })
+            // the previous admin can't manage the pool anymore
+            .propose_pool_admin(
+                &partner,
+                "o.partner".to_string(),
+                Some(partner.to_string()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            // while the owner can, e.g. to cancel a pending transfer
+            .propose_pool_admin(
+                &owner,
+                "o.partner".to_string(),
+                Some(partner.to_string()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .propose_pool_admin(&owner, "o.partner".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_pool_admin("o.partner".to_string(), |result| {
+                assert_eq!(result.unwrap().pending_admin, None);
+            });
+    }
+
+    #[test]
+    fn pool_admin_manages_the_allowlist_of_its_pool() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let partner = suite.senders[1].clone();
+        let trader = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &partner,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("partner".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_permissioned_pool(
+                &owner,
+                "o.partner".to_string(),
+                Some(owner.to_string()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_pool_allowlist(
+                &trader,
+                "o.partner".to_string(),
+                vec![trader.to_string()],
+                vec![],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            .update_pool_allowlist(
+                &partner,
+                "o.partner".to_string(),
+                vec![trader.to_string()],
+                vec![],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pool_allowlist("o.partner".to_string(), |result| {
+                assert_eq!(result.unwrap().addresses, vec![trader.clone()]);
+            });
+    }
+}
+
+mod min_lp_lock {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::lp_common::MINIMUM_LIQUIDITY_AMOUNT;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn liquidity_must_be_locked_for_the_min_lp_lock() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let partner = suite.senders[1].clone();
+        let alice = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default().add_one_epoch().create_pool(
+            &partner,
+            vec!["uom".to_string(), "uusd".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            Some("launch".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+        let lp_denom = suite.get_lp_denom("o.launch".to_string());
+
+        suite
+            .update_min_lp_lock(&alice, "o.launch".to_string(), Some(172_800), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .update_min_lp_lock(&partner, "o.launch".to_string(), Some(172_800), |result| {
+                result.unwrap();
+            })
+            .query_min_lp_lock("o.launch".to_string(), |result| {
+                assert_eq!(result.unwrap().min_unlocking_duration, Some(172_800));
+            })
+            .provide_liquidity(
+                &alice,
+                "o.launch".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLpLockNotMet {
+                            min_unlocking_duration,
+                            ..
+                        } => assert_eq!(min_unlocking_duration, 172_800),
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::MinLpLockNotMet")
+                        }
+                    }
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.launch".to_string(),
+                Some(86_400),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLpLockNotMet { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::MinLpLockNotMet")
+                        }
+                    }
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.launch".to_string(),
+                Some(172_800),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the minimum liquidity of the first deposit is the only LP left unlocked
+            .query_balance(&pool_manager_addr.to_string(), lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, MINIMUM_LIQUIDITY_AMOUNT);
+            })
+            // single side deposits are held to the minimum lock too
+            .provide_liquidity(
+                &alice,
+                "o.launch".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLpLockNotMet { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::MinLpLockNotMet")
+                        }
+                    }
+                },
+            )
+            .update_min_lp_lock(&owner, "o.launch".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .provide_liquidity(
+                &alice,
+                "o.launch".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod unlocking_duration_bounds {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, UnlockingDurationBounds};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn rejects_unlocking_durations_out_of_bounds() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let partner = suite.senders[1].clone();
+        let alice = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        let global_bounds = UnlockingDurationBounds {
+            min: 86_400,
+            max: 604_800,
+        };
+        let pool_bounds = UnlockingDurationBounds {
+            min: 86_400,
+            max: 172_800,
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &partner,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("partner".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // only the owner can set the global bounds
+            .update_unlocking_duration_bounds(
+                &partner,
+                None,
+                Some(global_bounds.clone()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                    }
+                },
+            )
+            .update_unlocking_duration_bounds(
+                &owner,
+                None,
+                Some(UnlockingDurationBounds {
+                    min: 604_800,
+                    max: 86_400,
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidUnlockingDurationBounds { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidUnlockingDurationBounds"
+                        ),
+                    }
+                },
+            )
+            .update_unlocking_duration_bounds(
+                &owner,
+                None,
+                Some(global_bounds.clone()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_unlocking_duration_bounds("o.partner".to_string(), |result| {
+                assert_eq!(result.unwrap().bounds, Some(global_bounds.clone()));
+            })
+            .provide_liquidity(
+                &alice,
+                "o.partner".to_string(),
+                Some(3_600),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnlockingDurationOutOfBounds {
+                            unlocking_duration,
+                            min,
+                            max,
+                            ..
+                        } => {
+                            assert_eq!(unlocking_duration, 3_600);
+                            assert_eq!(min, 86_400);
+                            assert_eq!(max, 604_800);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnlockingDurationOutOfBounds"
+                        ),
+                    }
+                },
+            )
+            // the bounds of the pool take precedence over the global ones
+            .update_unlocking_duration_bounds(
+                &partner,
+                Some("o.partner".to_string()),
+                Some(pool_bounds.clone()),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_unlocking_duration_bounds("o.partner".to_string(), |result| {
+                assert_eq!(result.unwrap().bounds, Some(pool_bounds.clone()));
+            })
+            .provide_liquidity(
+                &alice,
+                "o.partner".to_string(),
+                Some(604_800),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnlockingDurationOutOfBounds { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnlockingDurationOutOfBounds"
+                        ),
+                    }
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.partner".to_string(),
+                Some(172_800),
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // deposits without a lock aren't affected
+            .provide_liquidity(
+                &alice,
+                "o.partner".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_unlocking_duration_bounds(
+                &partner,
+                Some("o.partner".to_string()),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_unlocking_duration_bounds("o.partner".to_string(), |result| {
+                assert_eq!(result.unwrap().bounds, Some(global_bounds.clone()));
+            });
+    }
+}
+
+mod farm_manager_rotation {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::farm_manager::PositionsBy;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn rotates_farm_manager_keeping_positions() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &owner,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.whale".to_string(),
+                Some(86_400),
+                Some("alice".to_string()),
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let locked_lp = RefCell::new(Uint128::zero());
+        suite.query_farm_positions(
+            Some(PositionsBy::Receiver(alice.to_string())),
+            None,
+            None,
+            None,
+            |result| {
+                *locked_lp.borrow_mut() = result.unwrap().positions[0].lp_asset.amount;
+            },
+        );
+
+        let old_farm_manager = suite.farm_manager_addr.clone();
+        let new_farm_manager = suite.instantiate_farm_manager();
+        let pool_manager = suite.pool_manager_addr.clone();
+
+        // the farm manager can't be rotated to one that isn't its successor
+        suite.update_config(
+            &owner,
+            None,
+            Some(new_farm_manager.clone()),
+            None,
+            None,
+            None,
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::FarmManagerNotSuccessor { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::FarmManagerNotSuccessor"
+                    ),
+                }
+            },
+        );
+
+        suite.farm_manager_addr = new_farm_manager.clone();
+        suite
+            .update_farm_manager_config(&owner, pool_manager, |result| {
+                result.unwrap();
+            })
+            .update_farm_manager_successor(
+                &owner,
+                &old_farm_manager,
+                Some(&new_farm_manager),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_farm_manager_predecessor(
+                &owner,
+                &new_farm_manager,
+                Some(&old_farm_manager),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_config(
+                &owner,
+                None,
+                Some(new_farm_manager.clone()),
+                None,
+                None,
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        assert_eq!(suite.query_config().farm_manager_addr, new_farm_manager);
+
+        // anyone can migrate alice's position to the new farm manager
+        suite
+            .migrate_farm_positions(&owner, &old_farm_manager, &alice, |result| {
+                result.unwrap();
+            })
+            .query_farm_positions(
+                Some(PositionsBy::Receiver(alice.to_string())),
+                None,
+                None,
+                None,
+                |result| {
+                    let positions = result.unwrap().positions;
+                    assert_eq!(positions.len(), 1);
+                    assert_eq!(positions[0].identifier, "u-alice");
+                    assert_eq!(positions[0].lp_asset.amount, *locked_lp.borrow());
+                    assert_eq!(positions[0].unlocking_duration, 86_400);
+                    assert!(positions[0].open);
+                },
+            )
+            .query_all_balances(&old_farm_manager.to_string(), |result| {
+                assert!(result.unwrap().is_empty());
+            })
+            // the migrated position keeps being expanded through the pool manager
+            .provide_liquidity(
+                &alice,
+                "o.whale".to_string(),
+                Some(86_400),
+                Some("u-alice".to_string()),
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_farm_positions(
+                Some(PositionsBy::Receiver(alice.to_string())),
+                None,
+                None,
+                None,
+                |result| {
+                    let positions = result.unwrap().positions;
+                    assert_eq!(positions.len(), 1);
+                    assert!(positions[0].lp_asset.amount > *locked_lp.borrow());
+                },
+            );
+    }
+}
+
+mod max_assets_per_pool {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::manager::commands::{
+        DEFAULT_MAX_ASSETS_PER_POOL, DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL,
+        MAX_ASSETS_PER_POOL_CEILING, MAX_ASSETS_PER_STABLESWAP_POOL_CEILING,
+    };
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn owner_updates_max_assets_per_pool() {
+        let denoms = ["uom", "uusd", "uusdc", "uusdt", "uwhale"];
+        let mut suite = TestingSuite::default_with_balances(
+            denoms
+                .iter()
+                .map(|denom| coin(1_000_000_000u128, *denom))
+                .collect(),
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+        let asset_denoms: Vec<String> = denoms.iter().map(|denom| denom.to_string()).collect();
+
+        suite.instantiate_default();
+        assert_eq!(
+            suite.query_config().max_assets_per_pool,
+            DEFAULT_MAX_ASSETS_PER_POOL
+        );
+
+        suite
+            .create_pool(
+                &owner,
+                asset_denoms.clone(),
+                vec![6u8; 5],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("five".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::TooManyAssets {
+                            assets_provided,
+                            max_assets,
+                        } => {
+                            assert_eq!(assets_provided, 5);
+                            assert_eq!(max_assets, 4);
+                        }
+                        _ => panic!("Wrong error type, should return ContractError::TooManyAssets"),
+                    }
+                },
+            )
+            .update_max_assets_per_pool(&alice, 5, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_max_assets_per_pool(&owner, MAX_ASSETS_PER_POOL_CEILING + 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidMaxAssetsPerPool { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidMaxAssetsPerPool"
+                    ),
+                }
+            })
+            .update_max_assets_per_pool(&owner, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidMaxAssetsPerPool { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidMaxAssetsPerPool"
+                    ),
+                }
+            })
+            .update_max_assets_per_pool(&owner, 5, |result| {
+                result.unwrap();
+            })
+            .create_pool(
+                &owner,
+                asset_denoms,
+                vec![6u8; 5],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("five".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        assert_eq!(suite.query_config().max_assets_per_pool, 5);
+    }
+
+    #[test]
+    fn owner_updates_max_assets_per_stableswap_pool() {
+        let denoms = ["uom", "uusd", "uusdc", "uusdt", "uwhale", "uluna"];
+        let mut suite = TestingSuite::default_with_balances(
+            denoms
+                .iter()
+                .map(|denom| coin(1_000_000_000u128, *denom))
+                .collect(),
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+        let asset_denoms: Vec<String> = denoms.iter().map(|denom| denom.to_string()).collect();
+
+        suite.instantiate_default();
+        assert_eq!(
+            suite.query_config().max_assets_per_stableswap_pool,
+            DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL
+        );
+
+        suite
+            .create_pool(
+                &owner,
+                asset_denoms.clone(),
+                vec![6u8; 6],
+                pool_fees.clone(),
+                PoolType::StableSwap { amp: 100 },
+                Some("six".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::TooManyAssets {
+                            assets_provided,
+                            max_assets,
+                        } => {
+                            assert_eq!(assets_provided, 6);
+                            assert_eq!(max_assets, 5);
+                        }
+                        _ => panic!("Wrong error type, should return ContractError::TooManyAssets"),
+                    }
+                },
+            )
+            .update_max_assets_per_stableswap_pool(&alice, 6, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_max_assets_per_stableswap_pool(
+                &owner,
+                MAX_ASSETS_PER_STABLESWAP_POOL_CEILING + 1,
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidMaxAssetsPerPool { ceiling, .. } => {
+                            assert_eq!(ceiling, MAX_ASSETS_PER_STABLESWAP_POOL_CEILING);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidMaxAssetsPerPool"
+                        ),
+                    }
+                },
+            )
+            .update_max_assets_per_stableswap_pool(&owner, 6, |result| {
+                result.unwrap();
+            })
+            .create_pool(
+                &owner,
+                asset_denoms,
+                vec![6u8; 6],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                Some("six".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // the maximum of the constant product pools is unaffected
+        let config = suite.query_config();
+        assert_eq!(config.max_assets_per_stableswap_pool, 6);
+        assert_eq!(config.max_assets_per_pool, DEFAULT_MAX_ASSETS_PER_POOL);
+    }
+}
+
+mod precision_normalization {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::TestingSuite;
+
+    const ONE_USDC: u128 = 1_000_000;
+    const ONE_USDY: u128 = 1_000_000_000_000_000_000;
+
+    /// Creates a constant product pool of uusdc (6 decimals) and ausdy (18 decimals), and a
+    /// stableswap pool of ausdy and uusdt (6 decimals), each seeded with a million tokens per asset.
+    fn setup_mixed_decimal_pools() -> (TestingSuite, Addr) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(u128::MAX / 4, "uusdc"),
+                coin(u128::MAX / 4, "ausdy"),
+                coin(u128::MAX / 4, "uusdt"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(2),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .create_pool(
+                &creator,
+                vec!["uusdc".to_string(), "ausdy".to_string()],
+                vec![6u8, 18u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("usdc.usdy".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["ausdy".to_string(), "uusdt".to_string()],
+                vec![18u8, 6u8],
+                pool_fees,
+                PoolType::StableSwap { amp: 100 },
+                Some("usdy.usdt".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.usdc.usdy".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000 * ONE_USDC, "uusdc"),
+                    coin(1_000_000 * ONE_USDY, "ausdy"),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.usdy.usdt".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000 * ONE_USDY, "ausdy"),
+                    coin(1_000_000 * ONE_USDC, "uusdt"),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        (suite, creator)
+    }
+
+    /// Simulates the route, then executes it with the simulated return as its minimum receive and
+    /// asserts exactly the simulated return was received.
+    #[track_caller]
+    fn assert_route_executes_as_simulated(
+        suite: &mut TestingSuite,
+        sender: &Addr,
+        offer: Uint128,
+        operations: Vec<SwapOperation>,
+        ask_denom: &str,
+    ) {
+        let offer_denom = operations[0].get_input_asset_info().clone();
+        let simulated_return_amount = RefCell::new(Uint128::zero());
+        let balance_before = RefCell::new(Uint128::zero());
+
+        suite
+            .query_simulate_swap_operations(offer, operations.clone(), |result| {
+                *simulated_return_amount.borrow_mut() = result.unwrap().return_amount;
+            })
+            .query_balance(&sender.to_string(), ask_denom, |result| {
+                *balance_before.borrow_mut() = result.unwrap().amount;
+            });
+
+        let simulated_return_amount = *simulated_return_amount.borrow();
+        assert!(!simulated_return_amount.is_zero());
+
+        suite
+            .execute_swap_operations(
+                sender,
+                operations,
+                Some(simulated_return_amount),
+                None,
+                Some(Decimal::percent(5)),
+                vec![coin(offer.u128(), offer_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&sender.to_string(), ask_denom, |result| {
+                let received = result
+                    .unwrap()
+                    .amount
+                    .checked_sub(*balance_before.borrow())
+                    .unwrap();
+                assert_eq!(received, simulated_return_amount);
+            });
+    }
+
+    #[test]
+    fn mixed_decimals_route_executes_as_simulated() {
+        let (mut suite, creator) = setup_mixed_decimal_pools();
+
+        // 6 -> 18 -> 6 decimals, through a constant product and a stableswap pool
+        let operations = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusdc".to_string(),
+                token_out_denom: "ausdy".to_string(),
+                pool_identifier: "o.usdc.usdy".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "ausdy".to_string(),
+                token_out_denom: "uusdt".to_string(),
+                pool_identifier: "o.usdy.usdt".to_string(),
+            },
+        ];
+
+        for offer in [999u128, 1_234 * ONE_USDC + 567, 25_000 * ONE_USDC] {
+            assert_route_executes_as_simulated(
+                &mut suite,
+                &creator,
+                Uint128::new(offer),
+                operations.clone(),
+                "uusdt",
+            );
+        }
+    }
+
+    #[test]
+    fn route_trading_a_pool_twice_executes_as_simulated() {
+        let (mut suite, creator) = setup_mixed_decimal_pools();
+
+        // the stableswap pool is traded back and forth, so the second trade must see the first
+        let operations = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusdc".to_string(),
+                token_out_denom: "ausdy".to_string(),
+                pool_identifier: "o.usdc.usdy".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "ausdy".to_string(),
+                token_out_denom: "uusdt".to_string(),
+                pool_identifier: "o.usdy.usdt".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusdt".to_string(),
+                token_out_denom: "ausdy".to_string(),
+                pool_identifier: "o.usdy.usdt".to_string(),
+            },
+        ];
+
+        assert_route_executes_as_simulated(
+            &mut suite,
+            &creator,
+            Uint128::new(20_000 * ONE_USDC),
+            operations,
+            "ausdy",
+        );
+    }
+}
+
+mod yield_strategies {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    const POOL_IDENTIFIER: &str = "o.whale.uluna";
+
+    /// Creates a constant product pool of uwhale and uluna seeded with 1_000_000 of each, and a
+    /// mock yield strategy whitelisted for uwhale.
+    fn setup_pool_with_strategy() -> (TestingSuite, Addr) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default();
+        let strategy = suite.instantiate_mock_yield_strategy();
+
+        suite
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000, "uwhale"), coin(1_000_000, "uluna")],
+            )
+            .add_yield_strategy(&creator, &strategy, "uwhale", |result| {
+                result.unwrap();
+            });
+
+        (suite, strategy)
+    }
+
+    fn assert_reserve(suite: &TestingSuite, denom: &str, expected: u128) {
+        suite.query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+            let assets = result.unwrap().pools[0].pool_info.assets.clone();
+            let reserve = assets.iter().find(|asset| asset.denom == denom).unwrap();
+            assert_eq!(reserve.amount, Uint128::new(expected));
+        });
+    }
+
+    fn assert_deployed(suite: &mut TestingSuite, expected: u128) {
+        suite.query_yield_deployments(POOL_IDENTIFIER.to_string(), |result| {
+            let deployments = result.unwrap().deployments;
+            assert_eq!(deployments.len(), 1);
+            assert_eq!(deployments[0].deployed, Uint128::new(expected));
+        });
+    }
+
+    #[test]
+    fn only_owner_manages_yield_strategies() {
+        let (mut suite, strategy) = setup_pool_with_strategy();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .add_yield_strategy(&alice, &strategy, "uluna", |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .query_yield_strategies(|result| {
+                let strategies = result.unwrap().strategies;
+                assert_eq!(strategies.len(), 1);
+                assert_eq!(strategies[0].address, strategy);
+                assert_eq!(strategies[0].denom, "uwhale");
+            })
+            .update_pool_yield_strategy(
+                &alice,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::percent(50),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uluna",
+                Some(&strategy),
+                Decimal::percent(50),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidYieldStrategy { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidYieldStrategy"
+                        ),
+                    }
+                },
+            )
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::one(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidMaxDeployedShare { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidMaxDeployedShare"
+                        ),
+                    }
+                },
+            )
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::percent(50),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .deploy_idle_reserves(
+                &alice,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(100_000),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                    }
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uluna",
+                Uint128::new(100_000),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NoYieldStrategy { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::NoYieldStrategy")
+                        }
+                    }
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(500_001),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MaxDeployedReservesExceeded { max_deployed } => {
+                            assert_eq!(max_deployed, coin(500_000, "uwhale"));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MaxDeployedReservesExceeded"
+                        ),
+                    }
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(500_000),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .remove_yield_strategy(&creator, &strategy, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::YieldStrategyInUse { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::YieldStrategyInUse"),
+                }
+            });
+
+        // the deployed reserves stay accounted for in the pool
+        assert_reserve(&suite, "uwhale", 1_000_000);
+        assert_deployed(&mut suite, 500_000);
+
+        let pool_manager = suite.pool_manager_addr.to_string();
+        suite
+            .query_balance(&pool_manager, "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(500_000));
+            })
+            .query_balance(&strategy.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(500_000));
+            })
+            // unassigning the strategy recalls everything deployed into it
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                None,
+                Decimal::zero(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&pool_manager, "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_000));
+            })
+            .query_yield_deployments(POOL_IDENTIFIER.to_string(), |result| {
+                assert!(result.unwrap().deployments.is_empty());
+            })
+            .remove_yield_strategy(&creator, &strategy, |result| {
+                result.unwrap();
+            })
+            .query_yield_strategies(|result| {
+                assert!(result.unwrap().strategies.is_empty());
+            });
+    }
+
+    #[test]
+    fn withdrawals_recall_uncovered_reserves() {
+        let (mut suite, strategy) = setup_pool_with_strategy();
+        let creator = suite.creator();
+        let lp_denom = suite.get_lp_denom(POOL_IDENTIFIER.to_string());
+
+        suite
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::percent(50),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(500_000),
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let whale_balance = RefCell::new(Uint128::zero());
+        suite.query_balance(&creator.to_string(), "uwhale", |result| {
+            *whale_balance.borrow_mut() = result.unwrap().amount;
+        });
+
+        // withdrawing 80% of the pool pays out more uwhale than the contract holds
+        suite.withdraw_liquidity(
+            &creator,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(800_000, lp_denom)],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        assert_reserve(&suite, "uwhale", 200_000);
+        assert_deployed(&mut suite, 200_000);
+
+        let pool_manager = suite.pool_manager_addr.to_string();
+        suite
+            .query_balance(&creator.to_string(), "uwhale", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *whale_balance.borrow() + Uint128::new(800_000)
+                );
+            })
+            .query_balance(&pool_manager, "uwhale", |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            .query_balance(&strategy.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(200_000));
+            })
+            .recall_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(Uint128::new(200_001)),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InsufficientDeployedReserves { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InsufficientDeployedReserves"
+                        ),
+                    }
+                },
+            )
+            .recall_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&pool_manager, "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(200_000));
+            });
+
+        assert_deployed(&mut suite, 0);
+    }
+
+    #[test]
+    fn swaps_recall_uncovered_reserves() {
+        let (mut suite, strategy) = setup_pool_with_strategy();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::percent(90),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(900_000),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &alice,
+                "uwhale".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(200_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let reserve = RefCell::new(Uint128::zero());
+        suite.query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+            let assets = result.unwrap().pools[0].pool_info.assets.clone();
+            *reserve.borrow_mut() = assets
+                .iter()
+                .find(|asset| asset.denom == "uwhale")
+                .unwrap()
+                .amount;
+        });
+
+        // the reserve dropped under the deployed amount, the shortfall was recalled to pay out
+        let reserve = reserve.into_inner();
+        assert!(reserve < Uint128::new(900_000));
+        assert_deployed(&mut suite, reserve.u128());
+
+        let pool_manager = suite.pool_manager_addr.to_string();
+        suite.query_balance(&pool_manager, "uwhale", |result| {
+            assert!(result.unwrap().amount.is_zero());
+        });
+    }
+
+    #[test]
+    fn harvest_settles_gains_and_losses() {
+        let (mut suite, strategy) = setup_pool_with_strategy();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .update_pool_yield_strategy(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Some(&strategy),
+                Decimal::percent(50),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .deploy_idle_reserves(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                "uwhale",
+                Uint128::new(500_000),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .harvest_yield(&alice, POOL_IDENTIFIER.to_string(), "uwhale", |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToHarvest { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::NothingToHarvest"),
+                }
+            })
+            .accrue_strategy_yield(
+                &strategy,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(10_000, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // anyone can harvest
+            .harvest_yield(&alice, POOL_IDENTIFIER.to_string(), "uwhale", |result| {
+                result.unwrap();
+            });
+
+        // the gain was recalled and credited to the LPs
+        assert_reserve(&suite, "uwhale", 1_010_000);
+        assert_deployed(&mut suite, 500_000);
+
+        let pool_manager = suite.pool_manager_addr.to_string();
+        suite
+            .query_balance(&pool_manager, "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(510_000));
+            })
+            .slash_strategy(
+                &strategy,
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(50_000),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .harvest_yield(&alice, POOL_IDENTIFIER.to_string(), "uwhale", |result| {
+                result.unwrap();
+            });
+
+        // the loss was written off the reserve
+        assert_reserve(&suite, "uwhale", 960_000);
+        assert_deployed(&mut suite, 450_000);
+    }
+}
+
+mod swap_exact_out {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    const POOL_IDENTIFIER: &str = "o.whale.uluna";
+
+    fn setup_pool(pool_type: PoolType) -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite.instantiate_default().create_seeded_pool(
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            pool_type,
+            "whale.uluna",
+            vec![coin(10_000_000, "uwhale"), coin(10_000_000, "uluna")],
+        );
+
+        suite
+    }
+
+    fn assert_swaps_exact_out(pool_type: PoolType) {
+        let mut suite = setup_pool(pool_type);
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let offer_amount = RefCell::new(Uint128::zero());
+        suite.query_reverse_simulation(
+            POOL_IDENTIFIER.to_string(),
+            coin(100_000, "uluna"),
+            "uwhale".to_string(),
+            |result| {
+                *offer_amount.borrow_mut() = result.unwrap().offer_amount;
+            },
+        );
+        let offer_amount = offer_amount.into_inner() + Uint128::one();
+
+        let alice_whale = RefCell::new(Uint128::zero());
+        let bob_luna = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                *alice_whale.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), "uluna", |result| {
+                *bob_luna.borrow_mut() = result.unwrap().amount;
+            })
+            .swap_exact_out(
+                &alice,
+                coin(100_000, "uluna"),
+                None,
+                Some(bob.to_string()),
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(200_000, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // only the offer amount needed was taken, the rest was refunded
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, *alice_whale.borrow() - offer_amount);
+            })
+            .query_balance(&bob.to_string(), "uluna", |result| {
+                let received = result.unwrap().amount - *bob_luna.borrow();
+                assert!(received >= Uint128::new(100_000));
+                // rounding the offer amount up by a unit can't return more than a couple units extra
+                assert!(received <= Uint128::new(100_002));
+            });
+    }
+
+    #[test]
+    fn swaps_exact_out_on_constant_product_pools() {
+        assert_swaps_exact_out(PoolType::ConstantProduct);
+    }
+
+    #[test]
+    fn swaps_exact_out_on_stableswap_pools() {
+        assert_swaps_exact_out(PoolType::StableSwap { amp: 100 });
+    }
+
+    #[test]
+    fn swaps_exact_out_through_routes() {
+        let mut suite = setup_pool(PoolType::ConstantProduct);
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let operations = vec![SwapOperation::MantraSwap {
+            token_in_denom: "uwhale".to_string(),
+            token_out_denom: "uluna".to_string(),
+            pool_identifier: POOL_IDENTIFIER.to_string(),
+        }];
+
+        let offer_amount = RefCell::new(Uint128::zero());
+        suite.query_reverse_simulate_swap_operations(
+            Uint128::new(100_000),
+            operations.clone(),
+            |result| {
+                *offer_amount.borrow_mut() = result.unwrap().offer_amount;
+            },
+        );
+        // each hop is rounded up by a unit
+        let offer_amount = offer_amount.into_inner() + Uint128::one();
+
+        let alice_whale = RefCell::new(Uint128::zero());
+        let bob_luna = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                *alice_whale.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), "uluna", |result| {
+                *bob_luna.borrow_mut() = result.unwrap().amount;
+            })
+            .execute_swap_operations_exact_out(
+                &alice,
+                operations.clone(),
+                None,
+                Some(bob.to_string()),
+                None,
+                Some(Uint128::new(100_000)),
+                vec![coin(100_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MaxOfferAmountExceeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MaxOfferAmountExceeded"
+                        ),
+                    }
+                },
+            )
+            .execute_swap_operations_exact_out(
+                &alice,
+                operations,
+                None,
+                Some(bob.to_string()),
+                None,
+                Some(Uint128::new(100_000)),
+                vec![coin(200_000, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // only the offer amount needed was taken, the rest was refunded
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, *alice_whale.borrow() - offer_amount);
+            })
+            .query_balance(&bob.to_string(), "uluna", |result| {
+                let received = result.unwrap().amount - *bob_luna.borrow();
+                assert!(received >= Uint128::new(100_000));
+                assert!(received <= Uint128::new(100_002));
+            });
+    }
+
+    #[test]
+    fn fails_when_max_offer_amount_is_exceeded() {
+        let mut suite = setup_pool(PoolType::ConstantProduct);
+        let alice = suite.senders[1].clone();
+
+        suite
+            .swap_exact_out(
+                &alice,
+                coin(100_000, "uluna"),
+                None,
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MaxOfferAmountExceeded {
+                            offer_amount,
+                            max_offer_amount,
+                        } => {
+                            assert!(offer_amount > max_offer_amount);
+                            assert_eq!(max_offer_amount, Uint128::new(100_000));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MaxOfferAmountExceeded"
+                        ),
+                    }
+                },
+            )
+            .swap_exact_out(
+                &alice,
+                coin(100_000, "uwhale"),
+                None,
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(200_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::SameAsset => {}
+                        _ => panic!("Wrong error type, should return ContractError::SameAsset"),
+                    }
+                },
+            );
+    }
+}
+
+mod batch_swap {
+    use cosmwasm_std::{coin, Coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{PoolType, SwapSpec};
+
+    use crate::batch::commands::MAX_BATCH_SWAPS;
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    /// Creates the o.whale.uluna and o.uluna.uosmo constant product pools, seeded with 1_000_000
+    /// of each asset.
+    fn setup_pools() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uosmo"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(2),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite.instantiate_default();
+        for (denoms, identifier) in [
+            (["uwhale", "uluna"], "whale.uluna"),
+            (["uluna", "uosmo"], "uluna.uosmo"),
+        ] {
+            suite.create_seeded_pool(
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                identifier,
+                denoms.iter().map(|denom| coin(1_000_000, *denom)).collect(),
+            );
+        }
+
+        suite
+    }
+
+    fn swap_spec(pool_identifier: &str, offer_asset: Coin, ask_asset_denom: &str) -> SwapSpec {
+        SwapSpec {
+            pool_identifier: pool_identifier.to_string(),
+            offer_asset,
+            ask_asset_denom: ask_asset_denom.to_string(),
+            max_spread: None,
+            minimum_receive: None,
+        }
+    }
+
+    #[test]
+    fn batch_swaps_atomically() {
+        let mut suite = setup_pools();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        suite
+            .batch_swap(
+                &alice,
+                vec![
+                    swap_spec("o.whale.uluna", coin(1_000, "uwhale"), "uluna"),
+                    swap_spec("o.uluna.uosmo", coin(2_000, "uluna"), "uosmo"),
+                    swap_spec("o.whale.uluna", coin(1_000, "uwhale"), "uluna"),
+                ],
+                None,
+                Some(bob.to_string()),
+                vec![coin(2_500, "uwhale"), coin(2_000, "uluna")],
+                |result| {
+                    let response = result.unwrap();
+
+                    let swaps = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .filter(|attribute| attribute.key == "swap")
+                        .count();
+                    assert_eq!(swaps, 3);
+
+                    let refunded = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .find(|attribute| attribute.key == "refunded")
+                        .unwrap();
+                    assert_eq!(refunded.value, "500uwhale");
+                },
+            )
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(999_998_000));
+            })
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(999_998_000));
+            })
+            .query_balance(&bob.to_string(), "uluna", |result| {
+                // both whale swaps paid out into the same transfer
+                assert!(result.unwrap().amount > Uint128::new(1_000_001_900));
+            })
+            .query_balance(&bob.to_string(), "uosmo", |result| {
+                assert!(result.unwrap().amount > Uint128::new(1_000_001_900));
+            });
+    }
+
+    #[test]
+    fn batch_swap_fails_atomically() {
+        let mut suite = setup_pools();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .batch_swap(&alice, vec![], None, None, vec![], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidBatchSwaps { max } => {
+                        assert_eq!(max, MAX_BATCH_SWAPS);
+                    }
+                    _ => panic!("Wrong error type, should return ContractError::InvalidBatchSwaps"),
+                }
+            })
+            .batch_swap(
+                &alice,
+                vec![
+                    swap_spec("o.whale.uluna", coin(1_000, "uwhale"), "uluna"),
+                    swap_spec("o.whale.uluna", coin(1_000, "uwhale"), "uluna"),
+                ],
+                None,
+                None,
+                vec![coin(1_500, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MissingNativeSwapFunds { denom } => {
+                            assert_eq!(denom, "uwhale");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MissingNativeSwapFunds"
+                        ),
+                    }
+                },
+            )
+            .batch_swap(
+                &alice,
+                vec![
+                    swap_spec("o.whale.uluna", coin(1_000, "uwhale"), "uluna"),
+                    SwapSpec {
+                        minimum_receive: Some(Uint128::new(2_000)),
+                        ..swap_spec("o.uluna.uosmo", coin(1_000, "uluna"), "uosmo")
+                    },
+                ],
+                None,
+                None,
+                vec![coin(1_000, "uwhale"), coin(1_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            // nothing was swapped
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_000_000));
+            });
+    }
+}
+
+mod withdraw_min_amounts_out {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn withdrawal_fails_below_min_amounts_out() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default().create_seeded_pool(
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            "whale.uluna",
+            vec![coin(1_000_000, "uwhale"), coin(1_000_000, "uluna")],
+        );
+
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .withdraw_liquidity_with_min_amounts_out(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                Some(vec![coin(100_000, "uwhale"), coin(100_001, "uluna")]),
+                vec![coin(100_000, &lp_denom)],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinAmountOutAssertion {
+                            min_amount_out,
+                            amount_out,
+                        } => {
+                            assert_eq!(min_amount_out, coin(100_001, "uluna"));
+                            assert_eq!(amount_out, Uint128::new(100_000));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinAmountOutAssertion"
+                        ),
+                    }
+                },
+            )
+            .withdraw_liquidity_with_min_amounts_out(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                Some(vec![coin(1, "uosmo")]),
+                vec![coin(100_000, &lp_denom)],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .withdraw_liquidity_with_min_amounts_out(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                Some(vec![coin(100_000, "uwhale"), coin(100_000, "uluna")]),
+                vec![coin(100_000, &lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod min_lp_to_receive {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup_pool() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default().create_seeded_pool(
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConstantProduct,
+            "whale.uluna",
+            vec![coin(1_000_000, "uwhale"), coin(1_000_000, "uluna")],
+        );
+
+        suite
+    }
+
+    #[test]
+    fn deposit_fails_below_min_lp_to_receive() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .provide_liquidity_with_min_lp(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(Uint128::new(100_001)),
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLpToReceiveAssertion {
+                            min_lp_to_receive,
+                            share,
+                        } => {
+                            assert_eq!(min_lp_to_receive, Uint128::new(100_001));
+                            assert_eq!(share, Uint128::new(100_000));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinLpToReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .provide_liquidity_with_min_lp(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some(Uint128::new(100_000)),
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(100_000));
+            });
+    }
+
+    #[test]
+    fn single_side_deposit_fails_below_min_lp_to_receive() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let lp_denom = suite.get_lp_denom("o.whale.uluna".to_string());
+
+        suite
+            .provide_liquidity_with_min_lp(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                Some(Uint128::new(10_000)),
+                vec![coin(10_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLpToReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinLpToReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&alice.to_string(), "uwhale", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(1_000_000_000));
+            })
+            .provide_liquidity_with_min_lp(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                Some(Uint128::new(4_900)),
+                vec![coin(10_000, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                assert!(result.unwrap().amount >= Uint128::new(4_900));
+            });
+    }
+}
+
+mod concentrated_liquidity {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Decimal256, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{FeatureToggle, PoolType};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    /// Creates the o.whale.uluna concentrated liquidity pool, with a tick spacing of 10 and a 1%
+    /// swap fee, without liquidity.
+    fn setup_pool() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::percent(1));
+
+        suite.instantiate_default().create_pool(
+            &creator,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::ConcentratedLiquidity { tick_spacing: 10 },
+            Some("whale.uluna".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        suite
+    }
+
+    #[test]
+    fn provides_within_a_range_and_refunds_the_excess() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                None,
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidInitialPrice { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidInitialPrice"
+                        ),
+                    }
+                },
+            )
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_005,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidTickRange { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidTickRange"
+                        ),
+                    }
+                },
+            )
+            // at a price of 1 the range takes as much of both assets, so the excess is refunded
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(150_000, "uluna")],
+                |result| {
+                    let response = result.unwrap();
+                    let refunded = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .find(|attribute| attribute.key == "refunded")
+                        .unwrap();
+                    assert!(refunded.value.ends_with("uluna"));
+                },
+            )
+            .query_concentrated_pool("o.whale.uluna".to_string(), |result| {
+                let response = result.unwrap();
+                let state = response.state.unwrap();
+                assert_eq!(response.tick_spacing, 10);
+                assert_eq!(state.tick, 0);
+                assert!(!state.liquidity.is_zero());
+            })
+            .query_concentrated_positions(Some(alice.to_string()), None, None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.positions.len(), 1);
+
+                let position = &response.positions[0];
+                assert_eq!(position.position.id, 1);
+                assert_eq!(position.position.lower_tick, -1_000);
+                assert_eq!(position.position.upper_tick, 1_000);
+                for asset in position.assets.iter() {
+                    assert!(asset.amount <= Uint128::new(100_000));
+                    assert!(asset.amount >= Uint128::new(99_990));
+                }
+            })
+            // the initial price can only be set by the first provision
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::percent(200)),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidInitialPrice { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidInitialPrice"
+                        ),
+                    }
+                },
+            )
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                None,
+                Some(Uint128::new(u128::MAX)),
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinLiquidityAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinLiquidityAssertion"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn swaps_across_ranges_and_accrues_fees_in_range() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+        let carol = suite.senders[3].clone();
+
+        suite
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // below the price, the range is only made of uluna
+            .provide_concentrated_liquidity(
+                &bob,
+                "o.whale.uluna".to_string(),
+                -2_000,
+                -1_000,
+                None,
+                None,
+                vec![coin(1_000_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // above the price, the range is only made of uwhale
+            .provide_concentrated_liquidity(
+                &carol,
+                "o.whale.uluna".to_string(),
+                1_000,
+                2_000,
+                None,
+                None,
+                vec![coin(1_000_000, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // the swap takes more than the range of alice holds, so it crosses into the one of bob
+        let simulated_return_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(200_000, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *simulated_return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(200_000, "uwhale")],
+                |result| {
+                    let response = result.unwrap();
+                    let return_amount = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .find(|attribute| attribute.key == "return_amount")
+                        .unwrap();
+                    assert_eq!(
+                        return_amount.value,
+                        simulated_return_amount.borrow().to_string()
+                    );
+                },
+            )
+            .query_concentrated_pool("o.whale.uluna".to_string(), |result| {
+                let state = result.unwrap().state.unwrap();
+                assert!(state.tick < -1_000 && state.tick >= -2_000);
+            })
+            .query_concentrated_positions(None, None, None, None, |result| {
+                let positions = result.unwrap().positions;
+                assert_eq!(positions.len(), 3);
+
+                // alice and bob earned uluna fees, carol was never in range
+                for position in positions.iter() {
+                    let earned = position
+                        .uncollected_fees
+                        .iter()
+                        .find(|fee| fee.denom == "uluna")
+                        .unwrap()
+                        .amount;
+                    if position.position.id == 3 {
+                        assert!(earned.is_zero());
+                    } else {
+                        assert!(!earned.is_zero());
+                    }
+                }
+
+                // the range of alice was swapped out of uluna
+                let alice_position = positions
+                    .iter()
+                    .find(|position| position.position.id == 1)
+                    .unwrap();
+                assert!(alice_position
+                    .assets
+                    .iter()
+                    .find(|asset| asset.denom == "uluna")
+                    .unwrap()
+                    .amount
+                    .is_zero());
+            })
+            .collect_concentrated_fees(&bob, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .collect_concentrated_fees(&alice, 1, |result| {
+                let response = result.unwrap();
+                let collected = response
+                    .events
+                    .iter()
+                    .flat_map(|event| event.attributes.iter())
+                    .find(|attribute| attribute.key == "collected")
+                    .unwrap();
+                assert!(collected.value.ends_with("uluna"));
+            })
+            .collect_concentrated_fees(&alice, 1, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToCollect { position_id } => {
+                        assert_eq!(position_id, 1);
+                    }
+                    _ => panic!("Wrong error type, should return ContractError::NothingToCollect"),
+                }
+            })
+            .collect_concentrated_fees(&carol, 3, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NothingToCollect { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::NothingToCollect"),
+                }
+            });
+    }
+
+    #[test]
+    fn swap_fails_without_liquidity_in_the_way() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(1_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InsufficientConcentratedLiquidity { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InsufficientConcentratedLiquidity"
+                        ),
+                    }
+                },
+            )
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // there is no range below the one of alice
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(500_000, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InsufficientConcentratedLiquidity { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InsufficientConcentratedLiquidity"
+                        ),
+                    }
+                },
+            )
+            // fungible liquidity isn't supported
+            .provide_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnsupportedForConcentratedLiquidity { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnsupportedForConcentratedLiquidity"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn withdraws_liquidity_and_closes_empty_positions() {
+        let mut suite = setup_pool();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let liquidity = RefCell::new(Uint128::zero());
+        suite
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_concentrated_positions(Some(alice.to_string()), None, None, None, |result| {
+                *liquidity.borrow_mut() = result.unwrap().positions[0].position.liquidity;
+            });
+
+        let half = liquidity.borrow().checked_div(Uint128::new(2)).unwrap();
+        suite
+            .withdraw_concentrated_liquidity(&bob, 1, None, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .withdraw_concentrated_liquidity(
+                &alice,
+                1,
+                Some(liquidity.borrow().checked_add(Uint128::one()).unwrap()),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidConcentratedLiquidity { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidConcentratedLiquidity"
+                        ),
+                    }
+                },
+            )
+            .withdraw_concentrated_liquidity(&alice, 1, Some(half), |result| {
+                result.unwrap();
+            })
+            .query_concentrated_positions(Some(alice.to_string()), None, None, None, |result| {
+                let positions = result.unwrap().positions;
+                assert_eq!(positions.len(), 1);
+                assert_eq!(
+                    positions[0].position.liquidity,
+                    liquidity.borrow().checked_sub(half).unwrap()
+                );
+            })
+            .withdraw_concentrated_liquidity(&alice, 1, None, |result| {
+                result.unwrap();
+            })
+            .query_concentrated_positions(Some(alice.to_string()), None, None, None, |result| {
+                assert!(result.unwrap().positions.is_empty());
+            })
+            .query_concentrated_pool("o.whale.uluna".to_string(), |result| {
+                assert!(result.unwrap().state.unwrap().liquidity.is_zero());
+            })
+            // the deposits were rounded up and the withdrawals down, so the pool keeps the dust
+            .query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                for asset in response.pools[0].pool_info.assets.iter() {
+                    assert!(asset.amount <= Uint128::new(2));
+                }
+            });
+    }
+
+    #[test]
+    fn deprecated_pools_can_be_exited_while_withdrawals_are_disabled() {
+        let mut suite = setup_pool();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .provide_concentrated_liquidity(
+                &alice,
+                "o.whale.uluna".to_string(),
+                -1_000,
+                1_000,
+                Some(Decimal256::one()),
+                None,
+                vec![coin(100_000, "uwhale"), coin(100_000, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                Some(FeatureToggle {
+                    deposits_enabled: true,
+                    swaps_enabled: true,
+                    withdrawals_enabled: false,
+                }),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .withdraw_concentrated_liquidity(&alice, 1, None, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OperationDisabled(operation) => {
+                        assert_eq!(operation, "withdraw_concentrated_liquidity");
+                    }
+                    _ => panic!("Wrong error type, should return ContractError::OperationDisabled"),
+                }
+            })
+            .deprecate_pool(&creator, "o.whale.uluna".to_string(), |result| {
+                result.unwrap();
+            })
+            .withdraw_concentrated_liquidity(&alice, 1, None, |result| {
+                let response = result.unwrap();
+                // the payout is sorted by denom, as the bank module expects
+                let payout = response
+                    .events
+                    .iter()
+                    .filter(|event| event.ty == "transfer")
+                    .flat_map(|event| event.attributes.iter())
+                    .find(|attribute| attribute.key == "amount")
+                    .unwrap();
+                let denoms: Vec<&str> = payout
+                    .value
+                    .split(',')
+                    .map(|coin| coin.trim_start_matches(|c: char| c.is_ascii_digit()))
+                    .collect();
+                assert_eq!(denoms, vec!["uluna", "uwhale"]);
+            })
+            .query_concentrated_positions(Some(alice.to_string()), None, None, None, |result| {
+                assert!(result.unwrap().positions.is_empty());
+            });
+    }
+}
+
+mod rate_providers {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    /// Creates the o.stom.om stableswap pool with 1_000_000 of each asset, and a rate provider
+    /// reporting a rate of 1.2 for stOM.
+    fn setup() -> (TestingSuite, Addr) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "ustom"),
+                coin(1_000_000_000u128, "uom"),
+                coin(1_000_000_000u128, "uusd"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite.instantiate_default().create_seeded_pool(
+            vec!["ustom".to_string(), "uom".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::StableSwap { amp: 100 },
+            "stom.om",
+            vec![coin(1_000_000u128, "ustom"), coin(1_000_000u128, "uom")],
+        );
+
+        let rate_provider = suite.instantiate_mock_rate_provider();
+        suite.set_rate(&rate_provider, Decimal::percent(120), |result| {
+            result.unwrap();
+        });
+
+        (suite, rate_provider)
+    }
+
+    #[test]
+    fn swaps_at_the_rate_of_the_rate_provider() {
+        let (mut suite, rate_provider) = setup();
+        let creator = suite.creator();
+
+        // without rate providers the pool trades stOM around 1:1
+        suite.query_simulation(
+            "o.stom.om".to_string(),
+            coin(1_000, "ustom"),
+            "uom".to_string(),
+            |result| {
+                let return_amount = result.unwrap().return_amount;
+                assert!(return_amount <= Uint128::new(1_000));
+                assert!(return_amount >= Uint128::new(990));
+            },
+        );
+
+        let simulated_return_amount = RefCell::new(Uint128::zero());
+        suite
+            .update_rate_providers(
+                &creator,
+                "o.stom.om".to_string(),
+                vec![Some(rate_provider.to_string()), None],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_rate_providers("o.stom.om".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.rate_providers,
+                    vec![Some(rate_provider.clone()), None]
+                );
+                assert_eq!(response.rates, vec![Decimal::percent(120), Decimal::one()]);
+            })
+            // stOM is now worth its rate in OM, slightly less as it's the abundant asset
+            .query_simulation(
+                "o.stom.om".to_string(),
+                coin(1_000, "ustom"),
+                "uom".to_string(),
+                |result| {
+                    let return_amount = result.unwrap().return_amount;
+                    assert!(return_amount <= Uint128::new(1_200));
+                    assert!(return_amount >= Uint128::new(1_150));
+                    *simulated_return_amount.borrow_mut() = return_amount;
+                },
+            )
+            .query_reverse_simulation(
+                "o.stom.om".to_string(),
+                coin(1_200, "uom"),
+                "ustom".to_string(),
+                |result| {
+                    let offer_amount = result.unwrap().offer_amount;
+                    assert!(offer_amount >= Uint128::new(1_000));
+                    assert!(offer_amount <= Uint128::new(1_050));
+                },
+            )
+            .swap(
+                &creator,
+                "uom".to_string(),
+                None,
+                None,
+                None,
+                "o.stom.om".to_string(),
+                vec![coin(1_000, "ustom")],
+                |result| {
+                    let response = result.unwrap();
+                    let return_amount = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .find(|attribute| attribute.key == "return_amount")
+                        .unwrap();
+                    assert_eq!(
+                        return_amount.value,
+                        simulated_return_amount.borrow().to_string()
+                    );
+                },
+            )
+            // the price follows the rate as it accrues
+            .set_rate(&rate_provider, Decimal::percent(150), |result| {
+                result.unwrap();
+            })
+            .query_simulation(
+                "o.stom.om".to_string(),
+                coin(1_000, "ustom"),
+                "uom".to_string(),
+                |result| {
+                    let return_amount = result.unwrap().return_amount;
+                    assert!(return_amount <= Uint128::new(1_500));
+                    assert!(return_amount >= Uint128::new(1_400));
+                },
+            )
+            // removing the rate providers trades around 1:1 again
+            .update_rate_providers(&creator, "o.stom.om".to_string(), vec![], |result| {
+                result.unwrap();
+            })
+            .query_rate_providers("o.stom.om".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.rate_providers.is_empty());
+                assert!(response.rates.is_empty());
+            })
+            .query_simulation(
+                "o.stom.om".to_string(),
+                coin(1_000, "ustom"),
+                "uom".to_string(),
+                |result| {
+                    assert!(result.unwrap().return_amount <= Uint128::new(1_000));
+                },
+            );
+    }
+
+    #[test]
+    fn values_deposits_at_the_rates() {
+        let (mut suite, rate_provider) = setup();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let lp_denom = suite.get_lp_denom("o.stom.om".to_string());
+
+        let lp_without_rate = RefCell::new(Uint128::zero());
+        let lp_with_rate = RefCell::new(Uint128::zero());
+        suite
+            .provide_liquidity(
+                &alice,
+                "o.stom.om".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "ustom"), coin(10_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                *lp_without_rate.borrow_mut() = result.unwrap().amount;
+            })
+            .update_rate_providers(
+                &creator,
+                "o.stom.om".to_string(),
+                vec![Some(rate_provider.to_string()), None],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "o.stom.om".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000u128, "ustom"), coin(10_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                *lp_with_rate.borrow_mut() = result
+                    .unwrap()
+                    .amount
+                    .checked_sub(*lp_without_rate.borrow())
+                    .unwrap();
+            });
+
+        // the pool is valued at the same rate as the deposit, so the share barely moves
+        let lp_without_rate = *lp_without_rate.borrow();
+        let lp_with_rate = *lp_with_rate.borrow();
+        assert!(lp_with_rate.abs_diff(lp_without_rate) <= lp_without_rate / Uint128::new(20));
+    }
+
+    #[test]
+    fn only_the_owner_can_update_the_rate_providers_of_stableswap_pools() {
+        let (mut suite, rate_provider) = setup();
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .update_rate_providers(
+                &alice,
+                "o.stom.om".to_string(),
+                vec![Some(rate_provider.to_string()), None],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            .update_rate_providers(
+                &creator,
+                "o.stom.om".to_string(),
+                vec![Some(rate_provider.to_string())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["ustom".to_string(), "uom".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("stom.om.cp".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_rate_providers(
+                &creator,
+                "o.stom.om.cp".to_string(),
+                vec![Some(rate_provider.to_string()), None],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NotStableSwapPool { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NotStableSwapPool"
+                        ),
+                    }
+                },
+            )
+            // a rate of zero would price the asset out of the pool
+            .update_rate_providers(
+                &creator,
+                "o.stom.om".to_string(),
+                vec![Some(rate_provider.to_string()), None],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_rate(&rate_provider, Decimal::zero(), |result| {
+                result.unwrap();
+            })
+            .swap(
+                &creator,
+                "uom".to_string(),
+                None,
+                None,
+                None,
+                "o.stom.om".to_string(),
+                vec![coin(1_000, "ustom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidRate { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::InvalidRate"),
+                    }
+                },
+            );
+    }
+}
+
+mod imbalanced_stableswap_deposits {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+
+    /// Creates the o.usd.usdc stableswap pool with a 1% swap fee and 1_000_000 of each asset.
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uusdc"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::percent(1));
+
+        suite.instantiate_default().create_seeded_pool(
+            vec!["uusd".to_string(), "uusdc".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            PoolType::StableSwap { amp: 100 },
+            "usd.usdc",
+            vec![coin(1_000_000u128, "uusd"), coin(1_000_000u128, "uusdc")],
+        );
+
+        suite
+    }
+
+    fn get_attribute(response: &cw_multi_test::AppResponse, key: &str) -> String {
+        response
+            .events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == key)
+            .unwrap()
+            .value
+            .clone()
+    }
+
+    #[test]
+    fn balanced_deposits_pay_no_imbalance_fee() {
+        let mut suite = setup();
+        let alice = suite.senders[1].clone();
+
+        suite.provide_liquidity(
+            &alice,
+            "o.usd.usdc".to_string(),
+            None,
+            None,
+            None,
+            None,
+            vec![coin(100_000u128, "uusd"), coin(100_000u128, "uusdc")],
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(get_attribute(&response, "imbalance_fee"), "0");
+            },
+        );
+    }
+
+    #[test]
+    fn imbalanced_deposits_pay_the_imbalance_fee_to_the_pool() {
+        let mut suite = setup();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+        let lp_denom = suite.get_lp_denom("o.usd.usdc".to_string());
+
+        let alice_share = RefCell::new(Uint128::zero());
+        let bob_share = RefCell::new(Uint128::zero());
+        let bob_fee = RefCell::new(Uint128::zero());
+        suite
+            .provide_liquidity(
+                &alice,
+                "o.usd.usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(100_000u128, "uusd"), coin(100_000u128, "uusdc")],
+                |result| {
+                    let response = result.unwrap();
+                    *alice_share.borrow_mut() = get_attribute(&response, "share").parse().unwrap();
+                },
+            )
+            // the same value deposited mostly in one asset
+            .provide_liquidity(
+                &bob,
+                "o.usd.usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(190_000u128, "uusd"), coin(10_000u128, "uusdc")],
+                |result| {
+                    let response = result.unwrap();
+                    *bob_share.borrow_mut() = get_attribute(&response, "share").parse().unwrap();
+                    *bob_fee.borrow_mut() =
+                        get_attribute(&response, "imbalance_fee").parse().unwrap();
+                },
+            )
+            // the whole deposit is credited to the pool, the fee included
+            .query_pools(Some("o.usd.usdc".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                let assets = &response.pools[0].pool_info.assets;
+                assert_eq!(
+                    assets
+                        .iter()
+                        .find(|asset| asset.denom == "uusd")
+                        .unwrap()
+                        .amount,
+                    Uint128::new(1_290_000u128)
+                );
+                assert_eq!(
+                    assets
+                        .iter()
+                        .find(|asset| asset.denom == "uusdc")
+                        .unwrap()
+                        .amount,
+                    Uint128::new(1_110_000u128)
+                );
+            });
+
+        let alice_share = *alice_share.borrow();
+        let bob_share = *bob_share.borrow();
+        let bob_fee = *bob_fee.borrow();
+
+        // the fee is about half the swap fee on the 90_000 deviating from the ratio on each side,
+        // on top of the loss of depositing along the curve
+        assert!(!bob_fee.is_zero());
+        assert!(bob_share < alice_share);
+        assert!(bob_share.checked_add(bob_fee).unwrap() <= alice_share);
+        assert!(bob_fee >= Uint128::new(700u128) && bob_fee <= Uint128::new(1_000u128));
+
+        // withdrawing right away returns less than was deposited, so the deposit can't be used as
+        // a fee-less swap
+        let bob_balance = RefCell::new(Uint128::zero());
+        suite
+            .withdraw_liquidity(
+                &bob,
+                "o.usd.usdc".to_string(),
+                vec![coin(bob_share.u128(), &lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&bob.to_string(), "uusd", |result| {
+                *bob_balance.borrow_mut() += result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), "uusdc", |result| {
+                *bob_balance.borrow_mut() += result.unwrap().amount;
+            });
+
+        assert!(*bob_balance.borrow() < Uint128::new(2_000_000_000u128));
+    }
+}
+
+mod single_side_multi_asset_provision {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::TestingSuite;
+
+    /// Creates a pool of the given type out of uwhale, uluna and uusd, with 1_000_000 of each.
+    fn setup(pool_type: PoolType) -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(2),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite.instantiate_default().create_seeded_pool(
+            vec![
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                "uusd".to_string(),
+            ],
+            vec![6u8, 6u8, 6u8],
+            pool_fees,
+            pool_type,
+            "tripool",
+            vec![
+                coin(1_000_000u128, "uwhale"),
+                coin(1_000_000u128, "uluna"),
+                coin(1_000_000u128, "uusd"),
+            ],
+        );
+
+        suite
+    }
+
+    #[test]
+    fn swaps_the_deposit_into_the_other_assets_of_constant_product_pools() {
+        let mut suite = setup(PoolType::ConstantProduct);
+        let alice = suite.senders[1].clone();
+        let lp_denom = suite.get_lp_denom("o.tripool".to_string());
+
+        suite
+            .provide_liquidity(
+                &alice,
+                "o.tripool".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(30_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            })
+            .query_pools(Some("o.tripool".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                let assets = &response.pools[0].pool_info.assets;
+
+                // a third of the deposit is swapped into each of the other assets, so the whole
+                // deposit ends up in the pool while the other reserves only lose the protocol fees
+                let uwhale = assets
+                    .iter()
+                    .find(|asset| asset.denom == "uwhale")
+                    .unwrap()
+                    .amount;
+                assert_eq!(uwhale, Uint128::new(1_030_000u128));
+                for denom in ["uluna", "uusd"] {
+                    let amount = assets
+                        .iter()
+                        .find(|asset| asset.denom == denom)
+                        .unwrap()
+                        .amount;
+                    assert!(amount < Uint128::new(1_000_000u128));
+                    assert!(amount > Uint128::new(999_900u128));
+                }
+            });
+    }
+
+    #[test]
+    fn mints_the_deposit_against_the_invariant_of_stableswap_pools() {
+        let mut suite = setup(PoolType::StableSwap { amp: 100 });
+        let alice = suite.senders[1].clone();
+        let lp_denom = suite.get_lp_denom("o.tripool".to_string());
+
+        suite
+            .provide_liquidity(
+                &alice,
+                "o.tripool".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(30_000u128, "uwhale")],
+                |result| {
+                    let response = result.unwrap();
+                    let imbalance_fee: Uint128 = response
+                        .events
+                        .iter()
+                        .flat_map(|event| event.attributes.iter())
+                        .find(|attribute| attribute.key == "imbalance_fee")
+                        .unwrap()
+                        .value
+                        .parse()
+                        .unwrap();
+                    assert!(!imbalance_fee.is_zero());
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                let share = result.unwrap().amount;
+                assert!(share > Uint128::new(29_000u128) && share < Uint128::new(30_000u128));
+            })
+            // the deposit is credited to the pool as is, nothing is swapped
+            .query_pools(Some("o.tripool".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                for asset in response.pools[0].pool_info.assets.iter() {
+                    let expected = if asset.denom == "uwhale" {
+                        1_030_000u128
+                    } else {
+                        1_000_000u128
+                    };
+                    assert_eq!(asset.amount, Uint128::new(expected));
+                }
+            });
+    }
+}
+
+mod permissionless_pool_creation {
+    use cosmwasm_std::{coin, Addr, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[track_caller]
+    fn create_pool(
+        suite: &mut TestingSuite,
+        sender: &Addr,
+        pool_identifier: &str,
+        result: impl Fn(Result<cw_multi_test::AppResponse, anyhow::Error>),
+    ) {
+        suite.create_pool(
+            sender,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            swap_fee_only(Decimal::permille(3)),
+            PoolType::ConstantProduct,
+            Some(pool_identifier.to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            result,
+        );
+    }
+
+    #[test]
+    fn only_the_owner_and_allowlisted_creators_create_pools_until_opened() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        suite.instantiate_default();
+
+        // pool creation is permissionless by default
+        assert!(suite.query_config().permissionless_pool_creation);
+        create_pool(&mut suite, &bob, "bob.pool", |result| {
+            result.unwrap();
+        });
+
+        suite
+            .update_permissionless_pool_creation(&alice, false, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_permissionless_pool_creation(&owner, false, |result| {
+                result.unwrap();
+            })
+            .update_pool_creator_allowlist(&alice, vec![alice.to_string()], vec![], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_pool_creator_allowlist(&owner, vec![alice.to_string()], vec![], |result| {
+                result.unwrap();
+            })
+            .query_pool_creator_allowlist(|result| {
+                let response = result.unwrap();
+                assert!(!response.permissionless_pool_creation);
+                assert_eq!(response.addresses, vec![alice.clone()]);
+            });
+
+        create_pool(&mut suite, &bob, "bob.pool.2", |result| {
+            let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+            match err {
+                ContractError::PoolCreatorNotAllowlisted { address } => {
+                    assert_eq!(address, bob.to_string());
+                }
+                _ => panic!(
+                    "Wrong error type, should return ContractError::PoolCreatorNotAllowlisted"
+                ),
+            }
+        });
+        create_pool(&mut suite, &alice, "alice.pool", |result| {
+            result.unwrap();
+        });
+        create_pool(&mut suite, &owner, "owner.pool", |result| {
+            result.unwrap();
+        });
+
+        // removed creators can't create pools anymore
+        suite.update_pool_creator_allowlist(&owner, vec![], vec![alice.to_string()], |result| {
+            result.unwrap();
+        });
+        create_pool(&mut suite, &alice, "alice.pool.2", |result| {
+            let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+            match err {
+                ContractError::PoolCreatorNotAllowlisted { .. } => {}
+                _ => panic!(
+                    "Wrong error type, should return ContractError::PoolCreatorNotAllowlisted"
+                ),
+            }
+        });
+
+        // opening pool creation lets anyone create pools again
+        suite.update_permissionless_pool_creation(&owner, true, |result| {
+            result.unwrap();
+        });
+        create_pool(&mut suite, &bob, "bob.pool.2", |result| {
+            result.unwrap();
+        });
+    }
+}
+
+mod pool_fee_updates {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::manager::commands::DEFAULT_POOL_FEE_UPDATE_DELAY;
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    fn pool_fees(swap_fee: Decimal) -> PoolFee {
+        PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee { share: swap_fee },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        }
+    }
+
+    #[test]
+    fn applies_scheduled_fees_after_the_delay() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite.instantiate_default().create_pool(
+            &owner,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees(Decimal::permille(3)),
+            PoolType::ConstantProduct,
+            Some("whale.uluna".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+        assert_eq!(
+            suite.query_config().pool_fee_update_delay,
+            DEFAULT_POOL_FEE_UPDATE_DELAY
+        );
+
+        let new_fees = pool_fees(Decimal::percent(1));
+        suite
+            .update_pool_fees(
+                &alice,
+                "o.whale.uluna".to_string(),
+                new_fees.clone(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OwnershipError { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::OwnershipError")
+                        }
+                    }
+                },
+            )
+            // fees beyond the caps can't be scheduled
+            .update_pool_fees(
+                &owner,
+                "o.whale.uluna".to_string(),
+                pool_fees(Decimal::percent(21)),
+                |result| {
+                    result.unwrap_err();
+                },
+            )
+            .apply_pool_fees(&alice, "o.whale.uluna".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::NoPendingPoolFees { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::NoPendingPoolFees"),
+                }
+            })
+            .update_pool_fees(
+                &owner,
+                "o.whale.uluna".to_string(),
+                new_fees.clone(),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| {
+                        event
+                            .attributes
+                            .iter()
+                            .any(|attr| attr.key == "action" && attr.value == "update_pool_fees")
+                    }));
+                },
+            )
+            .query_pending_pool_fees("o.whale.uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pool_fees.swap_fee.share, Decimal::permille(3));
+                assert_eq!(
+                    response.pending_pool_fees.unwrap().new_fees.swap_fee.share,
+                    Decimal::percent(1)
+                );
+            })
+            .apply_pool_fees(&alice, "o.whale.uluna".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PoolFeesNotEffective { .. } => {}
+                    _ => {
+                        panic!(
+                            "Wrong error type, should return ContractError::PoolFeesNotEffective"
+                        )
+                    }
+                }
+            })
+            .add_one_day()
+            // anyone can apply the fees once they are effective
+            .apply_pool_fees(&alice, "o.whale.uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.events.iter().any(|event| {
+                    event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.key == "action" && attr.value == "apply_pool_fees")
+                }));
+            })
+            .query_pending_pool_fees("o.whale.uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pool_fees, new_fees);
+                assert!(response.pending_pool_fees.is_none());
+            })
+            .query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools[0].pool_info.pool_fees, new_fees);
+            });
+    }
+}
+
+mod pool_deprecation {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{FeatureToggle, PoolType};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn deprecated_pools_only_accept_withdrawals() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default();
+        for pool_identifier in ["old", "new"] {
+            suite
+                .create_pool(
+                    &owner,
+                    vec!["uwhale".to_string(), "uluna".to_string()],
+                    vec![6u8, 6u8],
+                    pool_fees.clone(),
+                    PoolType::ConstantProduct,
+                    Some(pool_identifier.to_string()),
+                    vec![coin(1000, "uusd"), coin(8888, "uom")],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .provide_liquidity(
+                    &alice,
+                    format!("o.{pool_identifier}"),
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                    |result| {
+                        result.unwrap();
+                    },
+                );
+        }
+
+        let lp_denom = suite.get_lp_denom("o.old".to_string());
+
+        suite
+            .deprecate_pool(&alice, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .deprecate_pool(&owner, "o.old".to_string(), |result| {
+                result.unwrap();
+            })
+            .deprecate_pool(&owner, "o.old".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PoolDeprecated { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::PoolDeprecated"),
+                }
+            })
+            .provide_liquidity(
+                &alice,
+                "o.old".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000u128, "uwhale"), coin(1_000u128, "uluna")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolDeprecated { pool_identifier } => {
+                            assert_eq!(pool_identifier, "o.old");
+                        }
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::PoolDeprecated")
+                        }
+                    }
+                },
+            )
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.old".to_string(),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PoolDeprecated { .. } => {}
+                        _ => {
+                            panic!("Wrong error type, should return ContractError::PoolDeprecated")
+                        }
+                    }
+                },
+            )
+            // the other pools are unaffected
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.new".to_string(),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools_by_deprecation(true, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools.len(), 1);
+                assert_eq!(response.pools[0].pool_info.pool_identifier, "o.old");
+                assert!(response.pools[0].deprecated);
+            })
+            .query_pools_by_deprecation(false, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools.len(), 1);
+                assert_eq!(response.pools[0].pool_info.pool_identifier, "o.new");
+                assert!(!response.pools[0].deprecated);
+            });
+
+        // LPs can still exit the deprecated pool, even with withdrawals disabled
+        let new_lp_denom = suite.get_lp_denom("o.new".to_string());
+        let lp_balance = RefCell::new(Uint128::zero());
+        suite.query_balance(&alice.to_string(), &lp_denom, |result| {
+            *lp_balance.borrow_mut() = result.unwrap().amount;
+        });
+        let lp_balance = lp_balance.into_inner();
+        suite
+            .update_config(
+                &owner,
+                None,
+                None,
+                None,
+                Some(FeatureToggle {
+                    deposits_enabled: true,
+                    swaps_enabled: true,
+                    withdrawals_enabled: false,
+                }),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .withdraw_liquidity(
+                &alice,
+                "o.new".to_string(),
+                vec![coin(1_000u128, &new_lp_denom)],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::OperationDisabled(operation) => {
+                            assert_eq!(operation, "withdraw_liquidity");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::OperationDisabled"
+                        ),
+                    }
+                },
+            )
+            .withdraw_liquidity(
+                &alice,
+                "o.old".to_string(),
+                vec![coin(lp_balance.u128(), &lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), &lp_denom, |result| {
+                assert!(result.unwrap().amount.is_zero());
+            });
+    }
+}
+
+mod pool_metadata {
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolMetadata, PoolType};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn metadata(display_name: &str) -> PoolMetadata {
+        PoolMetadata {
+            display_name: display_name.to_string(),
+            description: Some("The WHALE/LUNA pool".to_string()),
+            logo_uri: Some("https://example.com/whale-luna.png".to_string()),
+            tags: vec!["defi".to_string(), "blue-chip".to_string()],
+        }
+    }
+
+    #[test]
+    fn pool_admins_manage_the_metadata_of_their_pools() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .create_pool_with_metadata(
+                &alice,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                Some(metadata(&"x".repeat(65))),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolMetadata { field, max_length } => {
+                            assert_eq!(field, "display_name");
+                            assert_eq!(max_length, 64);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolMetadata"
+                        ),
+                    }
+                },
+            )
+            .create_pool_with_metadata(
+                &alice,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                Some(metadata("WHALE/LUNA")),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_pool_metadata(
+                &bob,
+                "o.whale.uluna".to_string(),
+                Some(metadata("Bob's pool")),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            )
+            .update_pool_metadata(
+                &alice,
+                "o.whale.uluna".to_string(),
+                Some(PoolMetadata {
+                    tags: vec!["tag".to_string(); 9],
+                    ..metadata("WHALE/LUNA")
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolMetadata { field, .. } => {
+                            assert_eq!(field, "tags");
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolMetadata"
+                        ),
+                    }
+                },
+            )
+            .update_pool_metadata(
+                &alice,
+                "o.whale.uluna".to_string(),
+                Some(metadata("Whale Luna")),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools[0].metadata, Some(metadata("Whale Luna")));
+            });
+
+        // the owner can manage the metadata of any pool
+        suite
+            .update_pool_metadata(&owner, "o.whale.uluna".to_string(), None, |result| {
+                result.unwrap();
+            })
+            .query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools[0].metadata, None);
+            });
+    }
+}
+
+mod create_pool_and_provide_liquidity {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn creates_and_seeds_a_pool_atomically() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        let liquidity = vec![
+            coin(1_000_000u128, "uom".to_string()),
+            coin(1_000_000u128, "uusd".to_string()),
+        ];
+        let funds = vec![
+            coin(1_008_888u128, "uom".to_string()),
+            coin(1_001_000u128, "uusd".to_string()),
+        ];
+
+        // the funds don't cover the creation fees on top of the liquidity
+        suite
+            .instantiate_default()
+            .create_pool_and_provide_liquidity(
+                &alice,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                liquidity.clone(),
+                None,
+                vec![
+                    coin(1_008_888u128, "uom".to_string()),
+                    coin(999_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidBootstrapSeed => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidBootstrapSeed"
+                        ),
+                    }
+                },
+            )
+            // a failing deposit reverts the creation of the pool
+            .create_pool_and_provide_liquidity(
+                &alice,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                liquidity.clone(),
+                Some(Uint128::new(1_000_000u128)),
+                funds.clone(),
+                |result| {
+                    result.unwrap_err();
+                },
+            )
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                result.unwrap_err();
+            });
+
+        suite
+            .create_pool_and_provide_liquidity(
+                &alice,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                liquidity,
+                Some(Uint128::new(999_000u128)),
+                funds,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let response = result.unwrap();
+                assert_eq!(
+                    response.pools[0].pool_info.assets,
+                    vec![coin(1_000_000u128, "uom"), coin(1_000_000u128, "uusd")]
+                );
+            });
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+        suite.query_balance(&alice.to_string(), &lp_denom, |result| {
+            assert_eq!(result.unwrap().amount, Uint128::new(999_000u128));
+        });
+    }
+}
+
+mod multi_denom_pool_creation_fees {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::PoolType;
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn pools_are_created_paying_any_accepted_fee() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite.instantiate_default().update_config(
+            &owner,
+            None,
+            None,
+            Some(vec![coin(1_000, "uusd"), coin(500, "uom")]),
+            None,
+            None,
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let fee_collector = suite.fee_collector_addr.to_string();
+        let uom_balance = RefCell::new(coin(0, "uom"));
+        suite
+            .query_balance(&fee_collector, "uom", |result| {
+                *uom_balance.borrow_mut() = result.unwrap();
+            })
+            .create_pool(
+                &alice,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                swap_fee_only(Decimal::permille(3)),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.usd".to_string()),
+                vec![coin(1_000, "uusd"), coin(8_888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &alice,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                swap_fee_only(Decimal::permille(3)),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.om".to_string()),
+                vec![coin(9_388, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&fee_collector, "uom", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    uom_balance.borrow().amount + Uint128::new(500)
+                );
+            })
+            // uluna isn't accepted
+            .create_pool(
+                &alice,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                swap_fee_only(Decimal::permille(3)),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.luna".to_string()),
+                vec![coin(1_000, "uluna"), coin(8_888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolCreationFee { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolCreationFee"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn accepted_fees_are_validated() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![coin(1_000_000_000u128, "uom")],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let owner = suite.creator();
+
+        suite.instantiate_default();
+
+        for pool_creation_fees in [vec![], vec![coin(1_000, "uusd"), coin(500, "uusd")]] {
+            suite.update_config(
+                &owner,
+                None,
+                None,
+                Some(pool_creation_fees),
+                None,
+                None,
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidPoolCreationFees => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidPoolCreationFees"
+                        ),
+                    }
+                },
+            );
+        }
+    }
+}
+
+mod flash_swaps {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, to_json_binary, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{ExecuteMsg, PoolType};
+
+    use crate::tests::suite::{swap_fee_only, MockFlashBorrowerExecuteMsg, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> (TestingSuite, Addr) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            );
+
+        let borrower = suite.instantiate_mock_flash_borrower();
+        suite.send_tokens(
+            &creator,
+            &borrower,
+            vec![coin(100_000u128, "uluna")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        (suite, borrower)
+    }
+
+    #[test]
+    fn repaid_flash_swaps_are_settled() {
+        let (mut suite, borrower) = setup();
+        let creator = suite.creator();
+        let pool_manager = suite.pool_manager_addr.to_string();
+
+        let repay_amount = RefCell::new(Uint128::zero());
+        suite.query_reverse_simulation(
+            "o.whale.uluna".to_string(),
+            coin(10_000u128, "uwhale"),
+            "uluna".to_string(),
+            |result| {
+                *repay_amount.borrow_mut() = result.unwrap().offer_amount + Uint128::one();
+            },
+        );
+        let repay_amount = *repay_amount.borrow();
+
+        suite
+            .flash_swap(
+                &creator,
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                Some(borrower.to_string()),
+                to_json_binary(&MockFlashBorrowerExecuteMsg::Repay {
+                    to: pool_manager,
+                    asset: coin(repay_amount.u128(), "uluna"),
+                })
+                .unwrap(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&borrower.to_string(), "uwhale", |result| {
+                assert!(result.unwrap().amount >= Uint128::new(10_000));
+            })
+            .query_balance(&borrower.to_string(), "uluna", |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(100_000) - repay_amount);
+            })
+            .query_pools(Some("o.whale.uluna".to_string()), None, None, |result| {
+                let pool = result.unwrap().pools[0].pool_info.clone();
+                assert_eq!(
+                    pool.assets[1].amount,
+                    Uint128::new(1_000_000) + repay_amount
+                );
+                assert!(pool.assets[0].amount <= Uint128::new(990_000));
+            });
+    }
+
+    #[test]
+    fn unpaid_flash_swaps_are_reverted() {
+        let (mut suite, borrower) = setup();
+        let creator = suite.creator();
+        let pool_manager = suite.pool_manager_addr.to_string();
+
+        suite
+            .flash_swap(
+                &creator,
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                Some(borrower.to_string()),
+                to_json_binary(&MockFlashBorrowerExecuteMsg::Repay {
+                    to: pool_manager.clone(),
+                    asset: coin(10_000u128, "uluna"),
+                })
+                .unwrap(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::FlashSwapNotRepaid { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::FlashSwapNotRepaid"
+                        ),
+                    }
+                },
+            )
+            // the callback can't reenter the pool manager, i.e. to pass a swap off as repayment
+            .flash_swap(
+                &creator,
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                Some(borrower.to_string()),
+                to_json_binary(&MockFlashBorrowerExecuteMsg::Reenter {
+                    contract: pool_manager,
+                    msg: to_json_binary(&ExecuteMsg::Swap {
+                        ask_asset_denom: "uwhale".to_string(),
+                        belief_price: None,
+                        max_spread: Some(Decimal::percent(5)),
+                        receiver: None,
+                        pool_identifier: "o.whale.uluna".to_string(),
+                        fee_payer: None,
+                        referral: None,
+                        min_receive: None,
+                    })
+                    .unwrap(),
+                    funds: vec![coin(20_000u128, "uluna")],
+                })
+                .unwrap(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::FlashSwapInProgress => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::FlashSwapInProgress"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&borrower.to_string(), "uwhale", |result| {
+                assert!(result.unwrap().amount.is_zero());
+            });
+    }
+}
+
+mod referral_fees {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, Referral, SwapOperation};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![
+                    coin(100_000_000u128, "uwhale"),
+                    coin(100_000_000u128, "uluna"),
+                ],
+            );
+
+        suite
+    }
+
+    #[test]
+    fn referral_fees_are_sent_to_the_referrer() {
+        let mut suite = setup();
+        let alice = suite.senders[1].clone();
+        let referrer = suite.senders[2].clone();
+
+        let referrer_balance = RefCell::new(Uint128::zero());
+        suite.query_balance(&referrer.to_string(), "uluna", |result| {
+            *referrer_balance.borrow_mut() = result.unwrap().amount;
+        });
+
+        let return_amount = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .swap_with_referral(
+                &alice,
+                "uluna".to_string(),
+                None,
+                "o.whale.uluna".to_string(),
+                Some(Referral {
+                    address: referrer.to_string(),
+                    fee_bps: 50,
+                }),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&referrer.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *referrer_balance.borrow()
+                        + return_amount.borrow().multiply_ratio(50u128, 10_000u128)
+                );
+            });
+
+        // routes pay the referral fee out of the output of the last hop
+        let referrer_balance = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&referrer.to_string(), "uwhale", |result| {
+                *referrer_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .execute_swap_operations_with_referral(
+                &alice,
+                vec![SwapOperation::MantraSwap {
+                    token_in_denom: "uluna".to_string(),
+                    token_out_denom: "uwhale".to_string(),
+                    pool_identifier: "o.whale.uluna".to_string(),
+                }],
+                None,
+                None,
+                Some(Referral {
+                    address: referrer.to_string(),
+                    fee_bps: 100,
+                }),
+                vec![coin(10_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&referrer.to_string(), "uwhale", |result| {
+                assert!(result.unwrap().amount > *referrer_balance.borrow());
+            });
+    }
+
+    #[test]
+    fn referral_fees_are_capped_by_the_config() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+        let referrer = suite.senders[2].clone();
+
+        suite
+            .swap_with_referral(
+                &alice,
+                "uluna".to_string(),
+                None,
+                "o.whale.uluna".to_string(),
+                Some(Referral {
+                    address: referrer.to_string(),
+                    fee_bps: 101,
+                }),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::ReferralFeeTooHigh { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::ReferralFeeTooHigh"
+                        ),
+                    }
+                },
+            )
+            .update_max_referral_fee_bps(&alice, 200, |result| {
+                result.unwrap_err();
+            })
+            .update_max_referral_fee_bps(&owner, 1_001, |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::InvalidMaxReferralFee { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::InvalidMaxReferralFee"
+                    ),
+                }
+            })
+            .update_max_referral_fee_bps(&owner, 200, |result| {
+                result.unwrap();
+            })
+            .swap_with_referral(
+                &alice,
+                "uluna".to_string(),
+                None,
+                "o.whale.uluna".to_string(),
+                Some(Referral {
+                    address: referrer.to_string(),
+                    fee_bps: 101,
+                }),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod price_breaker {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, PriceBreakerConfig, PriceReference};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            );
+
+        suite
+    }
+
+    #[test]
+    fn oracle_price_breaker_halts_swaps_until_reset() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+        let guardian = suite.senders[2].clone();
+
+        let oracle = suite.instantiate_mock_oracle();
+        let now = RefCell::new(0u64);
+        suite.get_time(|time| *now.borrow_mut() = time.seconds());
+        let now = *now.borrow();
+
+        suite
+            .set_oracle_price(&oracle, Decimal::one(), now, |result| {
+                result.unwrap();
+            })
+            .update_price_breaker(
+                &alice,
+                "o.whale.uluna".to_string(),
+                Some(PriceBreakerConfig {
+                    max_deviation: Decimal::percent(5),
+                    reference: PriceReference::Oracle {
+                        oracle: oracle.to_string(),
+                        max_staleness: 3_600,
+                    },
+                    guardian: Some(guardian.to_string()),
+                }),
+                |result| {
+                    result.unwrap_err();
+                },
+            )
+            .update_price_breaker(
+                &owner,
+                "o.whale.uluna".to_string(),
+                Some(PriceBreakerConfig {
+                    max_deviation: Decimal::percent(5),
+                    reference: PriceReference::Oracle {
+                        oracle: oracle.to_string(),
+                        max_staleness: 3_600,
+                    },
+                    guardian: Some(guardian.to_string()),
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // a fat-fingered swap deviates too much from the oracle price
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(200_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PriceDeviationExceeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PriceDeviationExceeded"
+                        ),
+                    }
+                },
+            )
+            .trip_price_breaker(&alice, "o.whale.uluna".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PriceDeviationWithinBounds { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::PriceDeviationWithinBounds"
+                    ),
+                }
+            })
+            // the oracle moves away from the pool price, so anyone can trip the breaker
+            .set_oracle_price(&oracle, Decimal::percent(120), now, |result| {
+                result.unwrap();
+            })
+            .trip_price_breaker(&alice, "o.whale.uluna".to_string(), |result| {
+                result.unwrap();
+            })
+            .query_price_breaker("o.whale.uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.tripped_at.is_some());
+                assert!(response.deviation.unwrap() > Decimal::percent(5).into());
+            })
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PriceBreakerTripped { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PriceBreakerTripped"
+                        ),
+                    }
+                },
+            )
+            .reset_price_breaker(&alice, "o.whale.uluna".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::Unauthorized => {}
+                    _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                }
+            })
+            .reset_price_breaker(&guardian, "o.whale.uluna".to_string(), |result| {
+                result.unwrap();
+            })
+            .reset_price_breaker(&owner, "o.whale.uluna".to_string(), |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::PriceBreakerNotTripped { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::PriceBreakerNotTripped"
+                    ),
+                }
+            })
+            .set_oracle_price(&oracle, Decimal::one(), now, |result| {
+                result.unwrap();
+            })
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+
+    #[test]
+    fn twap_price_breaker_rejects_swaps_deviating_from_the_twap() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .update_price_breaker(
+                &owner,
+                "o.whale.uluna".to_string(),
+                Some(PriceBreakerConfig {
+                    max_deviation: Decimal::percent(10),
+                    reference: PriceReference::Twap { window: 3_600 },
+                    guardian: None,
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .add_one_day()
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(300_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PriceDeviationExceeded { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::PriceDeviationExceeded"
+                        ),
+                    }
+                },
+            );
+    }
+}
+
+mod fee_discounts {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{FeeDiscountConfig, FeeDiscountTier, PoolType};
+
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::percent(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::percent(1),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            );
+
+        suite
+    }
+
+    fn tiers() -> Vec<FeeDiscountTier> {
+        vec![
+            FeeDiscountTier {
+                min_stake: Uint128::new(1_000),
+                discount: Decimal::percent(10),
+            },
+            FeeDiscountTier {
+                min_stake: Uint128::new(10_000),
+                discount: Decimal::percent(25),
+            },
+            FeeDiscountTier {
+                min_stake: Uint128::new(100_000),
+                discount: Decimal::percent(50),
+            },
+        ]
+    }
+
+    #[test]
+    fn stakers_get_the_discount_of_their_tier() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let staking = suite.instantiate_mock_staking();
+
+        suite
+            .update_fee_discounts(
+                &owner,
+                Some(FeeDiscountConfig {
+                    staking_contract: staking.to_string(),
+                    tiers: tiers(),
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_fee_discounts(Some(alice.to_string()), |result| {
+                assert_eq!(result.unwrap().discount, Some(Decimal::zero()));
+            });
+
+        for (stake, discount) in [
+            (999u128, Decimal::zero()),
+            (1_000u128, Decimal::percent(10)),
+            (50_000u128, Decimal::percent(25)),
+            (100_000u128, Decimal::percent(50)),
+        ] {
+            suite
+                .set_stake(&staking, &alice, Uint128::new(stake), |result| {
+                    result.unwrap();
+                })
+                .query_fee_discounts(Some(alice.to_string()), |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.discount, Some(discount));
+                    assert_eq!(response.config.unwrap().tiers, tiers());
+                });
+        }
+    }
+
+    #[test]
+    fn discounted_swaps_are_charged_less_fees() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let staking = suite.instantiate_mock_staking();
+        let undiscounted = RefCell::new(None);
+        let discounted = RefCell::new(None);
+        let balance_before = RefCell::new(Uint128::zero());
+
+        suite
+            .update_fee_discounts(
+                &owner,
+                Some(FeeDiscountConfig {
+                    staking_contract: staking.to_string(),
+                    tiers: tiers(),
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_stake(&staking, &alice, Uint128::new(100_000), |result| {
+                result.unwrap();
+            })
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *undiscounted.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            .query_simulation_for_trader(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                Some(alice.to_string()),
+                |result| {
+                    *discounted.borrow_mut() = Some(result.unwrap());
+                },
+            );
+
+        let undiscounted = undiscounted.into_inner().unwrap();
+        let discounted = discounted.into_inner().unwrap();
+        assert_eq!(undiscounted.fee_discount, Decimal::zero());
+        assert_eq!(undiscounted.swap_fee_amount, Uint128::new(99));
+        assert_eq!(undiscounted.protocol_fee_amount, Uint128::new(99));
+        assert_eq!(discounted.fee_discount, Decimal::percent(50));
+        assert!(discounted.swap_fee_amount < undiscounted.swap_fee_amount);
+        assert!(discounted.protocol_fee_amount < undiscounted.protocol_fee_amount);
+        assert!(discounted.return_amount > undiscounted.return_amount);
+
+        // the swap returns what was simulated for the trader
+        suite
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                *balance_before.borrow_mut() = result.unwrap().amount;
+            })
+            .swap(
+                &alice,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                "o.whale.uluna".to_string(),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *balance_before.borrow() + discounted.return_amount
+                );
+            });
+    }
+
+    #[test]
+    fn fee_discounts_can_only_be_updated_by_the_owner_with_valid_tiers() {
+        let mut suite = setup();
+        let owner = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let staking = suite.instantiate_mock_staking();
+        let mut unsorted_tiers = tiers();
+        unsorted_tiers.swap(0, 1);
+        let mut excessive_tiers = tiers();
+        excessive_tiers[2].discount = Decimal::percent(101);
+
+        suite.update_fee_discounts(
+            &alice,
+            Some(FeeDiscountConfig {
+                staking_contract: staking.to_string(),
+                tiers: tiers(),
+            }),
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            },
+        );
+
+        for invalid_tiers in [vec![], unsorted_tiers, excessive_tiers] {
+            suite.update_fee_discounts(
+                &owner,
+                Some(FeeDiscountConfig {
+                    staking_contract: staking.to_string(),
+                    tiers: invalid_tiers,
+                }),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidFeeDiscountTiers { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidFeeDiscountTiers"
+                        ),
+                    }
+                },
+            );
+        }
+
+        suite
+            .update_fee_discounts(
+                &owner,
+                Some(FeeDiscountConfig {
+                    staking_contract: staking.to_string(),
+                    tiers: tiers(),
+                }),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_stake(&staking, &alice, Uint128::new(100_000), |result| {
+                result.unwrap();
+            })
+            .update_fee_discounts(&owner, None, |result| {
+                result.unwrap();
+            })
+            .query_fee_discounts(Some(alice.to_string()), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.config, None);
+                assert_eq!(response.discount, Some(Decimal::zero()));
+            });
+    }
+}
+
+mod min_receive {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::fee::{Fee, PoolFee};
+    use mantra_dex_std::pool_manager::{FeePayer, PoolType, Referral};
+
+    use crate::tests::suite::TestingSuite;
+    use crate::ContractError;
+
+    #[test]
+    fn swaps_returning_less_than_min_receive_fail() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        let simulated_return = RefCell::new(Uint128::zero());
+        let balance_before = RefCell::new(Uint128::zero());
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            )
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(1_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *simulated_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            );
+
+        let simulated_return = *simulated_return.borrow();
+
+        // the return net of the fees of the pool is checked against the minimum
+        suite
+            .swap_with_min_receive(
+                &alice,
+                "uluna".to_string(),
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                Some(simulated_return + Uint128::one()),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion {
+                            minimum_receive,
+                            swap_amount,
+                        } => {
+                            assert_eq!(minimum_receive, simulated_return + Uint128::one());
+                            assert_eq!(swap_amount, simulated_return);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                *balance_before.borrow_mut() = result.unwrap().amount;
+            })
+            .swap_with_min_receive(
+                &alice,
+                "uluna".to_string(),
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                Some(simulated_return),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *balance_before.borrow() + simulated_return
+                );
+            });
+    }
+
+    #[test]
+    fn min_receive_is_checked_after_the_fee_payer_and_referral_shares() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+        let relayer = suite.senders[2].clone();
+        let referrer = suite.senders[3].clone();
+
+        let pool_fees = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![],
+        };
+
+        let simulated_return = RefCell::new(Uint128::zero());
+        let balance_before = RefCell::new(Uint128::zero());
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "whale.uluna",
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+            )
+            .query_simulation(
+                "o.whale.uluna".to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *simulated_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            );
+
+        // the relayer takes 100 and the referrer 0.5% off the return
+        let simulated_return = *simulated_return.borrow();
+        let net_return = simulated_return
+            - Uint128::new(100)
+            - simulated_return.multiply_ratio(50u128, 10_000u128);
+        let fee_payer = FeePayer {
+            address: relayer.to_string(),
+            amount: Uint128::new(100),
+        };
+        let referral = Referral {
+            address: referrer.to_string(),
+            fee_bps: 50,
+        };
+
+        // a minimum the return covers, but not what's left of it for alice, fails
+        suite
+            .swap_with_min_receive(
+                &alice,
+                "uluna".to_string(),
+                "o.whale.uluna".to_string(),
+                Some(fee_payer.clone()),
+                Some(referral.clone()),
+                Some(net_return + Uint128::one()),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion {
+                            minimum_receive,
+                            swap_amount,
+                        } => {
+                            assert_eq!(minimum_receive, net_return + Uint128::one());
+                            assert_eq!(swap_amount, net_return);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                *balance_before.borrow_mut() = result.unwrap().amount;
+            })
+            .swap_with_min_receive(
+                &alice,
+                "uluna".to_string(),
+                "o.whale.uluna".to_string(),
+                Some(fee_payer),
+                Some(referral),
+                Some(net_return),
+                vec![coin(10_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uluna", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *balance_before.borrow() + net_return
+                );
+            });
+    }
+}
+
+mod best_route {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite.instantiate_default().add_one_epoch();
+
+        // a shallow direct pool, and a deep route through uusd
+        for (assets, identifier, liquidity) in [
+            (["uwhale", "uluna"], "whale.uluna", 100_000u128),
+            (["uwhale", "uusd"], "whale.uusd", 10_000_000u128),
+            (["uusd", "uluna"], "uusd.uluna", 10_000_000u128),
+        ] {
+            suite.create_seeded_pool(
+                assets.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                identifier,
+                vec![coin(liquidity, assets[0]), coin(liquidity, assets[1])],
+            );
+        }
+
+        suite
+    }
+
+    #[test]
+    fn finds_the_route_with_the_best_simulated_output() {
+        let mut suite = setup();
+
+        let multi_hop_route = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.whale.uusd".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusd".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: "o.uusd.uluna".to_string(),
+            },
+        ];
+
+        suite
+            .query_find_best_route(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(10_000),
+                1,
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(
+                        response.operations,
+                        vec![SwapOperation::MantraSwap {
+                            token_in_denom: "uwhale".to_string(),
+                            token_out_denom: "uluna".to_string(),
+                            pool_identifier: "o.whale.uluna".to_string(),
+                        }]
+                    );
+                },
+            )
+            .query_find_best_route(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(10_000),
+                2,
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.operations, multi_hop_route);
+                },
+            );
+
+        let route_return = RefCell::new(Uint128::zero());
+        suite
+            .query_simulate_swap_operations(Uint128::new(10_000), multi_hop_route, |result| {
+                *route_return.borrow_mut() = result.unwrap().return_amount;
+            })
+            .query_find_best_route(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(10_000),
+                3,
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, *route_return.borrow());
+                },
+            );
+    }
+
+    #[test]
+    fn fails_without_a_route() {
+        let mut suite = setup();
+
+        suite
+            .query_find_best_route(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(10_000),
+                0,
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains(&ContractError::InvalidMaxHops { max: 3 }.to_string()));
+                },
+            )
+            .query_find_best_route(
+                "uwhale".to_string(),
+                "uatom".to_string(),
+                Uint128::new(10_000),
+                3,
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::NoRouteFound {
+                            offer_denom: "uwhale".to_string(),
+                            ask_denom: "uatom".to_string(),
+                        }
+                        .to_string()
+                    ));
+                },
+            );
+    }
+}
+
+mod hop_limits {
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{HopLimit, PoolType, SwapOperation};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> TestingSuite {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale"),
+                coin(1_000_000_000u128, "uluna"),
+                coin(1_000_000_000u128, "uusd"),
+                coin(1_000_000_000u128, "uom"),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+
+        let pool_fees = zero_fees();
+
+        suite.instantiate_default().add_one_epoch();
+
+        // a deep first hop, and a thin second one
+        for (assets, identifier, liquidity) in [
+            (["uwhale", "uluna"], "whale.uluna", 1_000_000u128),
+            (["uluna", "uusd"], "uluna.uusd", 10_000u128),
+        ] {
+            suite.create_seeded_pool(
+                assets.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                identifier,
+                vec![coin(liquidity, assets[0]), coin(liquidity, assets[1])],
+            );
+        }
+
+        suite
+    }
+
+    fn operations() -> Vec<SwapOperation> {
+        vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: "o.whale.uluna".to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uluna".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.uluna.uusd".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn hop_limits_are_checked_against_each_operation() {
+        let mut suite = setup();
+        let alice = suite.senders[1].clone();
+
+        suite
+            .execute_swap_operations_with_hop_limits(
+                &alice,
+                operations(),
+                None,
+                Some(Decimal::percent(50)),
+                Some(vec![HopLimit {
+                    max_spread: None,
+                    minimum_receive: None,
+                }]),
+                vec![coin(5_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidHopLimits { operations } => {
+                            assert_eq!(operations, 2);
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidHopLimits"
+                        ),
+                    }
+                },
+            )
+            // the max spread of the thin hop is tighter than the one of the route
+            .execute_swap_operations_with_hop_limits(
+                &alice,
+                operations(),
+                None,
+                Some(Decimal::percent(50)),
+                Some(vec![
+                    HopLimit {
+                        max_spread: None,
+                        minimum_receive: None,
+                    },
+                    HopLimit {
+                        max_spread: Some(Decimal::percent(5)),
+                        minimum_receive: None,
+                    },
+                ]),
+                vec![coin(5_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MaxSpreadAssertion => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MaxSpreadAssertion"
+                        ),
+                    }
+                },
+            )
+            .execute_swap_operations_with_hop_limits(
+                &alice,
+                operations(),
+                None,
+                Some(Decimal::percent(50)),
+                Some(vec![
+                    HopLimit {
+                        max_spread: None,
+                        minimum_receive: Some(Uint128::new(4_900)),
+                    },
+                    HopLimit {
+                        max_spread: None,
+                        minimum_receive: Some(Uint128::new(4_000)),
+                    },
+                ]),
+                vec![coin(5_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion {
+                            minimum_receive, ..
+                        } => {
+                            assert_eq!(minimum_receive, Uint128::new(4_000));
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .execute_swap_operations_with_hop_limits(
+                &alice,
+                operations(),
+                None,
+                Some(Decimal::percent(50)),
+                Some(vec![
+                    HopLimit {
+                        max_spread: Some(Decimal::percent(1)),
+                        minimum_receive: Some(Uint128::new(4_900)),
+                    },
+                    HopLimit {
+                        max_spread: None,
+                        minimum_receive: Some(Uint128::new(3_000)),
+                    },
+                ]),
+                vec![coin(5_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+    }
+}
+
+mod swap_and_lock {
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::farm_manager::PositionsBy;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> (TestingSuite, Addr, Vec<SwapOperation>) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusdc".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("usd_usdc".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uusd"), coin(1_000_000, "uusdc")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let operations = vec![SwapOperation::MantraSwap {
+            token_in_denom: "uom".to_string(),
+            token_out_denom: "uusd".to_string(),
+            pool_identifier: "o.om_usd".to_string(),
+        }];
+
+        (suite, alice, operations)
+    }
+
+    #[test]
+    fn swaps_provides_and_locks_in_one_message() {
+        let (mut suite, alice, operations) = setup();
+
+        suite
+            .swap_and_lock(
+                &alice,
+                operations.clone(),
+                None,
+                "o.usd_usdc".to_string(),
+                86_400,
+                Some("zap".to_string()),
+                vec![coin(10_000, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_farm_positions(
+                Some(PositionsBy::Receiver(alice.to_string())),
+                None,
+                None,
+                None,
+                |result| {
+                    let positions = result.unwrap().positions;
+                    assert_eq!(positions.len(), 1);
+                    assert_eq!(positions[0].identifier, "u-zap");
+                    assert_eq!(positions[0].unlocking_duration, 86_400);
+                    assert!(!positions[0].lp_asset.amount.is_zero());
+                    assert!(positions[0].open);
+                },
+            );
+    }
+
+    #[test]
+    fn swap_and_lock_asserts_the_minimum_receive() {
+        let (mut suite, alice, operations) = setup();
+
+        suite.swap_and_lock(
+            &alice,
+            operations,
+            Some(Uint128::new(10_000)),
+            "o.usd_usdc".to_string(),
+            86_400,
+            None,
+            vec![coin(10_000, "uom")],
+            |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::MinimumReceiveAssertion { .. } => {}
+                    _ => panic!(
+                        "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                    ),
+                }
+            },
+        );
+    }
+}
+
+mod zap {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{swap_fee_only, TestingSuite};
+    use crate::ContractError;
+
+    fn setup() -> (TestingSuite, Addr, Addr, Vec<SwapOperation>) {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusdc".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("om_usd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uusd".to_string(), "uusdc".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("usd_usdc".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000, "uusd"), coin(1_000_000, "uusdc")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let route = vec![SwapOperation::MantraSwap {
+            token_in_denom: "uom".to_string(),
+            token_out_denom: "uusd".to_string(),
+            pool_identifier: "o.om_usd".to_string(),
+        }];
+
+        (suite, alice, bob, route)
+    }
+
+    #[test]
+    fn zaps_a_single_asset_into_the_pool() {
+        let (mut suite, alice, bob, route) = setup();
+        let lp_denom = suite.get_lp_denom("o.usd_usdc".to_string());
+
+        suite
+            .zap(
+                &alice,
+                route,
+                "o.usd_usdc".to_string(),
+                Some(bob.to_string()),
+                None,
+                None,
+                vec![coin(10_000, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&bob.to_string(), lp_denom.clone(), |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            })
+            .query_balance(&alice.to_string(), lp_denom, |result| {
+                assert!(result.unwrap().amount.is_zero());
+            });
+    }
+
+    #[test]
+    fn zaps_the_output_along_the_other_funds_sent() {
+        let (mut suite, alice, _, route) = setup();
+        let lp_denom = suite.get_lp_denom("o.usd_usdc".to_string());
+
+        let reserves = RefCell::new(vec![]);
+        suite
+            .query_pools(Some("o.usd_usdc".to_string()), None, None, |result| {
+                *reserves.borrow_mut() = result.unwrap().pools[0].pool_info.assets.clone();
+            })
+            .zap(
+                &alice,
+                route,
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                vec![coin(10_000, "uom"), coin(9_000, "uusdc")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), lp_denom, |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            })
+            .query_pools(Some("o.usd_usdc".to_string()), None, None, |result| {
+                let assets = result.unwrap().pools[0].pool_info.assets.clone();
+                let reserves = reserves.borrow();
+
+                // both sides are deposited as they are, without any swap against the pool
+                assert!(assets[0].amount > reserves[0].amount);
+                assert_eq!(
+                    assets[1].amount,
+                    reserves[1].amount + Uint128::new(9_000u128)
+                );
+            });
+    }
+
+    #[test]
+    fn zap_rejects_invalid_funds_and_locks() {
+        let (mut suite, alice, bob, route) = setup();
+
+        suite
+            .zap(
+                &alice,
+                route.clone(),
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                vec![coin(10_000, "uusdc")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::PaymentError(_) => {}
+                        _ => panic!("Wrong error type, should return ContractError::PaymentError"),
+                    }
+                },
+            )
+            .zap(
+                &alice,
+                route,
+                "o.usd_usdc".to_string(),
+                Some(bob.to_string()),
+                Some(86_400),
+                None,
+                vec![coin(10_000, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::Unauthorized => {}
+                        _ => panic!("Wrong error type, should return ContractError::Unauthorized"),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn provides_liquidity_from_an_asset_outside_the_pool() {
+        let (mut suite, alice, bob, route) = setup();
+        let lp_denom = suite.get_lp_denom("o.usd_usdc".to_string());
+
+        suite
+            // uom isn't an asset of the pool, so it can only be provided through a route
+            .provide_liquidity(
+                &alice,
+                "o.usd_usdc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::AssetMismatch => {}
+                        _ => panic!("Wrong error type, should return ContractError::AssetMismatch"),
+                    }
+                },
+            )
+            .provide_liquidity_via_route(
+                &alice,
+                "o.usd_usdc".to_string(),
+                route.clone(),
+                None,
+                Some(bob.to_string()),
+                vec![coin(10_000, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&bob.to_string(), lp_denom.clone(), |result| {
+                assert!(!result.unwrap().amount.is_zero());
+            })
+            .query_balance(&alice.to_string(), lp_denom, |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            .provide_liquidity_via_route(
+                &alice,
+                "o.usd_usdc".to_string(),
+                route,
+                None,
+                Some("invalid".to_string()),
+                vec![coin(10_000, "uom")],
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+
+    #[test]
+    fn withdraws_liquidity_and_swaps_it_into_a_single_asset() {
+        let (mut suite, alice, _, route) = setup();
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+
+        let route = vec![
+            route[0].clone(),
+            SwapOperation::MantraSwap {
+                token_in_denom: "uusd".to_string(),
+                token_out_denom: "uusdc".to_string(),
+                pool_identifier: "o.usd_usdc".to_string(),
+            },
+        ];
+
+        let lp_shares = RefCell::new(Uint128::zero());
+        let usdc_balance = RefCell::new(Uint128::zero());
+        suite
+            .provide_liquidity(
+                &alice,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(10_000, "uom"), coin(10_000, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), lp_denom.clone(), |result| {
+                *lp_shares.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&alice.to_string(), "uusdc".to_string(), |result| {
+                *usdc_balance.borrow_mut() = result.unwrap().amount;
+            });
+
+        let lp_shares = lp_shares.into_inner();
+        suite
+            // the route must end in the target denom
+            .withdraw_liquidity_and_swap(
+                &alice,
+                "o.om_usd".to_string(),
+                "uusdc".to_string(),
+                route[..1].to_vec(),
+                None,
+                vec![coin(lp_shares.u128(), lp_denom.clone())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NoSwapRouteForAssets { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NoSwapRouteForAssets"
+                        ),
+                    }
+                },
+            )
+            .withdraw_liquidity_and_swap(
+                &alice,
+                "o.om_usd".to_string(),
+                "uusdc".to_string(),
+                route.clone(),
+                Some(Uint128::new(1_000_000u128)),
+                vec![coin(lp_shares.u128(), lp_denom.clone())],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .withdraw_liquidity_and_swap(
+                &alice,
+                "o.om_usd".to_string(),
+                "uusdc".to_string(),
+                route,
+                Some(Uint128::new(15_000u128)),
+                vec![coin(lp_shares.u128(), lp_denom.clone())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), lp_denom, |result| {
+                assert!(result.unwrap().amount.is_zero());
+            })
+            .query_balance(&alice.to_string(), "uom".to_string(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(999_990_000u128));
+            })
+            .query_balance(&alice.to_string(), "uusd".to_string(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(999_990_000u128));
+            })
+            .query_balance(&alice.to_string(), "uusdc".to_string(), |result| {
+                // both sides of the withdrawal end up in uusdc
+                assert!(result.unwrap().amount > *usdc_balance.borrow() + Uint128::new(15_000u128));
+            });
+    }
+}
+
+mod swap_route_registry {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation, SwapRoute};
+
+    use crate::tests::suite::{zero_fees, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn swaps_through_registered_routes() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uluna".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                Some("uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.uluna.uusd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![coin(1_000_000u128, "uluna"), coin(2_000_000u128, "uusd")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let luna_to_usd = SwapOperation::MantraSwap {
+            token_in_denom: "uluna".to_string(),
+            token_out_denom: "uusd".to_string(),
+            pool_identifier: "o.uluna.uusd".to_string(),
+        };
+        let whale_to_usd = SwapRoute {
+            offer_asset_denom: "uwhale".to_string(),
+            ask_asset_denom: "uusd".to_string(),
+            swap_operations: vec![
+                SwapOperation::MantraSwap {
+                    token_in_denom: "uwhale".to_string(),
+                    token_out_denom: "uluna".to_string(),
+                    pool_identifier: "o.whale.uluna".to_string(),
+                },
+                luna_to_usd.clone(),
+            ],
+        };
+        let luna_to_usd = SwapRoute {
+            offer_asset_denom: "uluna".to_string(),
+            ask_asset_denom: "uusd".to_string(),
+            swap_operations: vec![luna_to_usd],
+        };
+
+        suite
+            .add_swap_routes(
+                &creator,
+                vec![whale_to_usd.clone(), luna_to_usd.clone()],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_swap_route("uwhale".to_string(), "uusd".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.swap_route, whale_to_usd);
+                assert_eq!(response.creator, creator.to_string());
+            })
+            .query_swap_route("uusd".to_string(), "uwhale".to_string(), |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("No swap route from uusd to uwhale"));
+            })
+            // routes are ordered by offer and ask denoms
+            .query_swap_routes(None, Some(1), |result| {
+                let swap_routes = result.unwrap().swap_routes;
+                assert_eq!(swap_routes.len(), 1);
+                assert_eq!(swap_routes[0].swap_route, luna_to_usd);
+            })
+            .query_swap_routes(
+                Some(("uluna".to_string(), "uusd".to_string())),
+                None,
+                |result| {
+                    let swap_routes = result.unwrap().swap_routes;
+                    assert_eq!(swap_routes.len(), 1);
+                    assert_eq!(swap_routes[0].swap_route, whale_to_usd);
+                },
+            );
+
+        let alice_balance = RefCell::new(Uint128::zero());
+        suite
+            .swap_via_registry(
+                &alice,
+                "uwhale".to_string(),
+                None,
+                vec![coin(1_000u128, "uusd")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::NoSwapRouteForAssets { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::NoSwapRouteForAssets"
+                        ),
+                    }
+                },
+            )
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                *alice_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .swap_via_registry(
+                &alice,
+                "uusd".to_string(),
+                Some(Uint128::new(1_997u128)),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::MinimumReceiveAssertion { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::MinimumReceiveAssertion"
+                        ),
+                    }
+                },
+            )
+            .swap_via_registry(
+                &alice,
+                "uusd".to_string(),
+                Some(Uint128::new(1_996u128)),
+                vec![coin(1_000u128, "uwhale")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uusd", |result| {
+                // 1_000 uwhale -> 999 uluna -> 1_996 uusd
+                assert_eq!(
+                    result.unwrap().amount,
+                    *alice_balance.borrow() + Uint128::new(1_996u128)
+                );
+            });
+
+        // the LP valuation falls back to the registered routes for the assets it isn't given a
+        // route for
+        let lp_valuation = RefCell::new(None);
+        suite
+            .add_swap_routes(&alice, vec![luna_to_usd.clone()], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .add_swap_routes(
+                &creator,
+                vec![SwapRoute {
+                    offer_asset_denom: "uwhale".to_string(),
+                    ask_asset_denom: "uusd".to_string(),
+                    swap_operations: luna_to_usd.swap_operations.clone(),
+                }],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidSwapRoute { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::InvalidSwapRoute"
+                        ),
+                    }
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                vec![whale_to_usd.clone(), luna_to_usd.clone()],
+                |result| {
+                    *lp_valuation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                vec![],
+                |result| {
+                    assert_eq!(Some(result.unwrap()), *lp_valuation.borrow());
+                },
+            )
+            .remove_swap_routes(&creator, vec![luna_to_usd.clone()], |result| {
+                result.unwrap();
+            })
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                vec![],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("No swap route from uluna to uusd"));
+                },
+            )
+            .query_lp_valuation(
+                "o.whale.uluna".to_string(),
+                None,
+                Some(Uint128::new(500_000u128)),
+                "uusd".to_string(),
+                None,
+                vec![luna_to_usd],
+                |result| {
+                    assert_eq!(Some(result.unwrap()), *lp_valuation.borrow());
+                },
+            );
+    }
+}
+
+mod swap_adapters {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, to_json_binary, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{zero_fees, MockSwapAdapterMsg, TestingSuite};
+    use crate::ContractError;
+
+    #[test]
+    fn routes_trade_through_whitelisted_swap_adapters() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusdc".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = zero_fees();
+
+        let adapter = suite.instantiate_mock_swap_adapter();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+            )
+            // the adapter swaps out of its own balance
+            .send_tokens(
+                &creator,
+                &adapter,
+                vec![coin(1_000_000, "uusdc")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        // the adapter swaps 1 uusd for 2 uusdc
+        let operations = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uom".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.om_usd".to_string(),
+            },
+            SwapOperation::ExternalAdapter {
+                token_in_denom: "uusd".to_string(),
+                token_out_denom: "uusdc".to_string(),
+                adapter_addr: adapter.to_string(),
+                msg: to_json_binary(&MockSwapAdapterMsg {
+                    rate: Decimal::from_ratio(2u128, 1u128),
+                    surplus: Uint128::zero(),
+                })
+                .unwrap(),
+            },
+        ];
+
+        suite
+            .execute_swap_operations(
+                &alice,
+                operations.clone(),
+                None,
+                None,
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::SwapAdapterNotWhitelisted { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::SwapAdapterNotWhitelisted"
+                        ),
+                    }
+                },
+            )
+            .update_swap_adapters(&alice, vec![adapter.to_string()], vec![], |result| {
+                let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                match err {
+                    ContractError::OwnershipError { .. } => {}
+                    _ => panic!("Wrong error type, should return ContractError::OwnershipError"),
+                }
+            })
+            .update_swap_adapters(&creator, vec![adapter.to_string()], vec![], |result| {
+                result.unwrap();
+            })
+            .query_swap_adapters(|result| {
+                assert_eq!(result.unwrap().adapters, vec![adapter.clone()]);
+            })
+            // 1_000 uom -> 999 uusd -> 1_998 uusdc
+            .query_simulate_swap_operations(
+                Uint128::new(1_000u128),
+                operations.clone(),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, Uint128::new(1_998u128));
+                },
+            )
+            .query_reverse_simulate_swap_operations(
+                Uint128::new(1_998u128),
+                operations.clone(),
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains("isn't supported for routes through swap adapters"));
+                },
+            )
+            .query_route_twap(operations.clone(), 3_600u64, |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("twap isn't supported for routes through swap adapters"));
+            })
+            // exact-out routes are rejected before anything is simulated
+            .execute_swap_operations_exact_out(
+                &alice,
+                operations.clone(),
+                None,
+                None,
+                None,
+                Some(Uint128::new(1_998u128)),
+                vec![coin(2_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::UnsupportedForSwapAdapter { operation } => {
+                            assert_eq!(operation, "exact_out")
+                        }
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::UnsupportedForSwapAdapter"
+                        ),
+                    }
+                },
+            );
+
+        let alice_balance = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uusdc", |result| {
+                *alice_balance.borrow_mut() = result.unwrap().amount;
+            })
+            .execute_swap_operations(
+                &alice,
+                operations.clone(),
+                Some(Uint128::new(1_998u128)),
+                None,
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&alice.to_string(), "uusdc", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *alice_balance.borrow() + Uint128::new(1_998u128)
+                );
+            })
+            .query_balance(&adapter.to_string(), "uusd", |result| {
+                // the adapter got the output of the first hop
+                assert_eq!(result.unwrap().amount, Uint128::new(999u128));
+            })
+            .update_swap_adapters(&creator, vec![], vec![adapter.to_string()], |result| {
+                result.unwrap();
+            })
+            .query_swap_adapters(|result| {
+                assert!(result.unwrap().adapters.is_empty());
+            })
+            .execute_swap_operations(
+                &alice,
+                operations,
+                None,
+                None,
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::SwapAdapterNotWhitelisted { .. } => {}
+                        _ => panic!(
+                            "Wrong error type, should return ContractError::SwapAdapterNotWhitelisted"
+                        ),
+                    }
+                },
+            );
+    }
+
+    #[test]
+    fn the_surplus_of_swap_adapters_is_refunded_to_the_receiver() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uusdc".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let creator = suite.creator();
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        let adapter = suite.instantiate_mock_swap_adapter();
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![coin(1_000_000, "uom"), coin(1_000_000, "uusd")],
+            )
+            .send_tokens(
+                &creator,
+                &adapter,
+                vec![coin(1_000_000, "uusdc")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .update_swap_adapters(&creator, vec![adapter.to_string()], vec![], |result| {
+                result.unwrap();
+            });
+
+        // the adapter quotes 2 uusdc per uusd, but sends 10 uusdc more than that
+        let operations = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uom".to_string(),
+                token_out_denom: "uusd".to_string(),
+                pool_identifier: "o.om_usd".to_string(),
+            },
+            SwapOperation::ExternalAdapter {
+                token_in_denom: "uusd".to_string(),
+                token_out_denom: "uusdc".to_string(),
+                adapter_addr: adapter.to_string(),
+                msg: to_json_binary(&MockSwapAdapterMsg {
+                    rate: Decimal::from_ratio(2u128, 1u128),
+                    surplus: Uint128::new(10u128),
+                })
+                .unwrap(),
+            },
+        ];
+
+        let pool_manager_addr = suite.pool_manager_addr.clone();
+        let bob_balance = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&bob.to_string(), "uusdc", |result| {
+                *bob_balance.borrow_mut() = result.unwrap().amount;
+            })
+            // 1_000 uom -> 999 uusd -> 1_998 uusdc quoted, 2_008 uusdc returned
+            .execute_swap_operations(
+                &alice,
+                operations,
+                Some(Uint128::new(1_998u128)),
+                Some(bob.to_string()),
+                None,
+                vec![coin(1_000u128, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_balance(&bob.to_string(), "uusdc", |result| {
+                assert_eq!(
+                    result.unwrap().amount,
+                    *bob_balance.borrow() + Uint128::new(2_008u128)
+                );
+            })
+            // nothing is left untracked in the pool manager
+            .query_balance(&pool_manager_addr.to_string(), "uusdc", |result| {
+                assert!(result.unwrap().amount.is_zero());
+            });
+    }
+}
+
+mod proportional_deposits {
+    use std::cell::RefCell;
+
+    use cosmwasm_std::{coin, Decimal, Uint128};
+
+    use mantra_common_testing::multi_test::stargate_mock::StargateMock;
+    use mantra_dex_std::pool_manager::{PoolType, SwapOperation};
+
+    use crate::tests::suite::{swap_fee_only, zero_fees, TestingSuite};
+
+    #[test]
+    fn refunds_the_remainder_of_non_proportional_deposits() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+
+        let pool_fees = swap_fee_only(Decimal::permille(3));
+
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_seeded_pool(
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::ConstantProduct,
+                "om_usd",
+                vec![
+                    coin(1_000_000u128, "uom".to_string()),
+                    coin(1_000_000u128, "uusd".to_string()),
+                ],
+            );
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+
+        suite
+            // twice as much uusd as the reserves take for the uom deposited
+            .provide_liquidity(
+                &alice,
+                "o.om_usd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(10_000u128, "uom".to_string()),
+                    coin(20_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| event
+                        .attributes
+                        .iter()
+                        .any(|attribute| attribute.key == "refunded_assets"
+                            && attribute.value == "10000uusd")));
+                },
+            )
+            .query_balance(&alice.to_string(), lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(10_000u128));
+            })
+            .query_balance(&alice.to_string(), "uusd".to_string(), |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(999_990_000u128));
+            })
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let pool_info = result.unwrap().pools[0].pool_info.clone();
+                // only the proportional amounts were added to the reserves
+                assert_eq!(pool_info.assets[0].amount, Uint128::new(1_010_000u128));
+                assert_eq!(pool_info.assets[1].amount, Uint128::new(1_010_000u128));
+            });
+    }
+
+    #[test]
+    fn refunds_the_remainder_of_zaps_to_the_sender() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000u128, "uusd".to_string()),
+                coin(1_000_000_000u128, "uom".to_string()),
+                coin(1_000_000_000u128, "uwhale".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.senders[1].clone();
+        let bob = suite.senders[2].clone();
+
+        let pool_fees = zero_fees();
+
+        suite.instantiate_default().add_one_epoch();
+
+        for (asset_denoms, pool_identifier) in [
+            (vec!["uom".to_string(), "uusd".to_string()], "om_usd"),
+            (vec!["uwhale".to_string(), "uusd".to_string()], "whale_usd"),
+        ] {
+            suite.create_seeded_pool(
+                asset_denoms.clone(),
+                vec![6u8, 6u8],
+                pool_fees.clone(),
+                PoolType::ConstantProduct,
+                pool_identifier,
+                asset_denoms
+                    .iter()
+                    .map(|denom| coin(1_000_000u128, denom))
+                    .collect(),
+            );
+        }
+
+        let lp_denom = suite.get_lp_denom("o.om_usd".to_string());
+
+        let alice_uusd = RefCell::new(Uint128::zero());
+        let bob_uusd = RefCell::new(Uint128::zero());
+        suite
+            .query_balance(&alice.to_string(), "uusd".to_string(), |result| {
+                *alice_uusd.borrow_mut() = result.unwrap().amount;
+            })
+            .query_balance(&bob.to_string(), "uusd".to_string(), |result| {
+                *bob_uusd.borrow_mut() = result.unwrap().amount;
+            })
+            // the uwhale is swapped for about three times as much uusd as the reserves take for
+            // the uom deposited. The contract provides the deposit on behalf of bob
+            .zap(
+                &alice,
+                vec![SwapOperation::MantraSwap {
+                    token_in_denom: "uwhale".to_string(),
+                    token_out_denom: "uusd".to_string(),
+                    pool_identifier: "o.whale_usd".to_string(),
+                }],
+                "o.om_usd".to_string(),
+                Some(bob.to_string()),
+                None,
+                None,
+                vec![
+                    coin(10_000u128, "uom".to_string()),
+                    coin(30_000u128, "uwhale".to_string()),
+                ],
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.events.iter().any(|event| event
+                        .attributes
+                        .iter()
+                        .any(|attribute| attribute.key == "refunded_assets")));
+                },
+            )
+            .query_balance(&bob.to_string(), lp_denom, |result| {
+                assert_eq!(result.unwrap().amount, Uint128::new(10_000u128));
+            })
+            // the remainder went back to alice, who sent the funds, rather than to bob
+            .query_balance(&bob.to_string(), "uusd".to_string(), |result| {
+                assert_eq!(result.unwrap().amount, *bob_uusd.borrow());
+            })
+            .query_balance(&alice.to_string(), "uusd".to_string(), |result| {
+                assert!(result.unwrap().amount > *alice_uusd.borrow() + Uint128::new(19_000u128));
+            })
+            .query_pools(Some("o.om_usd".to_string()), None, None, |result| {
+                let pool_info = result.unwrap().pools[0].pool_info.clone();
+                assert_eq!(pool_info.assets[0].amount, Uint128::new(1_010_000u128));
+                assert_eq!(pool_info.assets[1].amount, Uint128::new(1_010_000u128));
+            });
+    }
+}
+
+mod config_migration {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{coin, Decimal};
+    use cw2::set_contract_version;
+
+    use mantra_dex_std::pool_manager::MigrateMsg;
+
+    use crate::contract::migrate;
+    use crate::manager::commands::{
+        DEFAULT_MAX_ASSETS_PER_POOL, DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL, DEFAULT_POOL_FEE_CAPS,
+        DEFAULT_POOL_FEE_UPDATE_DELAY,
+    };
+    use crate::state::CONFIG;
+    use crate::swap::commands::DEFAULT_MAX_REFERRAL_FEE_BPS;
+
+    /// The config as stored by the first release of the contract.
+    const FIRST_RELEASE_CONFIG: &str = r#"{"fee_collector_addr":"fee_collector","farm_manager_addr":"farm_manager","pool_creation_fee":{"denom":"uusd","amount":"1000"},"feature_toggle":{"withdrawals_enabled":true,"deposits_enabled":false,"swaps_enabled":true}}"#;
+
+    #[test]
+    fn migrates_config_stored_by_the_first_release() {
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, "mantra:pool-manager", "1.0.0").unwrap();
+        deps.storage
+            .set(CONFIG.as_slice(), FIRST_RELEASE_CONFIG.as_bytes());
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.fee_collector_addr.as_str(), "fee_collector");
+        assert_eq!(config.farm_manager_addr.as_str(), "farm_manager");
+        assert_eq!(config.pool_creation_fees, vec![coin(1_000, "uusd")]);
+        assert!(!config.feature_toggle.deposits_enabled);
+        assert_eq!(config.default_max_spread, Decimal::percent(1));
+        assert_eq!(config.max_spread_cap, Decimal::percent(50));
+        assert_eq!(config.max_assets_per_pool, DEFAULT_MAX_ASSETS_PER_POOL);
+        assert_eq!(
+            config.max_assets_per_stableswap_pool,
+            DEFAULT_MAX_ASSETS_PER_STABLESWAP_POOL
+        );
+        assert!(config.permissionless_pool_creation);
+        assert_eq!(config.pool_fee_update_delay, DEFAULT_POOL_FEE_UPDATE_DELAY);
+        assert_eq!(config.max_referral_fee_bps, DEFAULT_MAX_REFERRAL_FEE_BPS);
+        assert_eq!(config.pool_fee_caps, DEFAULT_POOL_FEE_CAPS);
+        assert_eq!(config.unlocking_duration_bounds, None);
+    }
+
+    #[test]
+    fn migration_keeps_permissioned_pool_creation() {
+        let mut deps = mock_dependencies();
+        set_contract_version(deps.as_mut().storage, "mantra:pool-manager", "1.0.0").unwrap();
+        let config =
+            FIRST_RELEASE_CONFIG.replacen('{', r#"{"permissionless_pool_creation":false,"#, 1);
+        deps.storage.set(CONFIG.as_slice(), config.as_bytes());
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert!(!config.permissionless_pool_creation);
+    }
 }
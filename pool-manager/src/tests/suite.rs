@@ -1,23 +1,48 @@
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::MockStorage;
 use mantra_dex_std::pool_manager::{
-    Config, FeatureToggle, PoolsResponse, ReverseSimulateSwapOperationsResponse,
-    ReverseSimulationResponse, SimulateSwapOperationsResponse, SimulationResponse, SwapOperation,
+    BatchSimulationResponse, ConcentratedPoolResponse, ConcentratedPositionsResponse, Config,
+    ContractRegistryResponse, DcaSchedulesResponse, DenomTracesResponse, DepegProtectionConfig,
+    DepegProtectionResponse, DisabledMessagesResponse, FeatureToggle, FeeDiscountConfig,
+    FeeDiscountsResponse, FeePayer, FindBestRouteResponse, HopLimit, IbcSourceChannelsResponse,
+    IbcWithdrawalChannel, IbcWithdrawalChannelsResponse, ImbalanceFee, ImbalanceFeeResponse,
+    LiquidityStreamsResponse, LpValuationResponse, LpWrapperResponse, MigrationCampaignResponse,
+    MinLpLockResponse, PacketForward, PendingPoolFeesResponse, PoolAdminResponse,
+    PoolAllowlistResponse, PoolCreationParams, PoolCreatorAllowlistResponse, PoolFeeCaps,
+    PoolMetadata, PoolOverrides, PoolsResponse, PriceBreakerConfig, PriceBreakerResponse,
+    PriceSubscriptionsResponse, ProtocolLiquidityResponse, RateProvidersResponse, Referral,
+    ReserveFloorsResponse, ReverseSimulateSwapOperationsResponse, ReverseSimulationResponse,
+    RfqMakerResponse, RfqQuote, RfqQuoteDigestResponse, RoundingDustResponse, RouteTwapResponse,
+    SimulateSwapOperationsResponse, SimulationRequest, SimulationResponse, SplitSimulationResponse,
+    StableSwapAmpResponse, StableSwapDebugResponse, StopLossOrdersResponse, SudoMsg,
+    SwapAdaptersResponse, SwapOperation, SwapRoute, SwapRouteResponse, SwapRoutesResponse,
+    SwapSpec, TwapResponse, UnlockingDurationBounds, UnlockingDurationBoundsResponse,
+    YieldDeploymentsResponse, YieldStrategiesResponse,
 };
 use mantra_dex_std::pool_manager::{InstantiateMsg, PoolType};
 use std::cell::RefCell;
 
-use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, StdResult, Timestamp, Uint128, Uint64};
+use cosmwasm_std::{
+    coin, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Decimal, Decimal256, Deps,
+    DepsMut, Empty, Env, MessageInfo, Order, Response, StdError, StdResult, Timestamp, Uint128,
+    Uint64, WasmMsg,
+};
 use cw_multi_test::{
     App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, DistributionKeeper,
     Executor, FailingModule, GovFailingModule, IbcFailingModule, MockApiBech32, StakeKeeper,
     WasmKeeper,
 };
+use cw_storage_plus::{Item, Map};
 
 use mantra_common_testing::multi_test::stargate_mock::StargateMock;
 use mantra_dex_std::constants::{LP_SYMBOL, MONTH_IN_SECONDS};
 use mantra_dex_std::epoch_manager::EpochConfig;
 use mantra_dex_std::farm_manager::PositionsResponse;
-use mantra_dex_std::fee::PoolFee;
+use mantra_dex_std::fee::{Fee, PoolFee};
+
+use crate::state::{
+    SingleSideLiquidityProvisionBuffer, LP_SUPPLY, SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER,
+};
 
 /// Creates the pool manager contract
 fn contract_pool_manager() -> Box<dyn Contract<Empty>> {
@@ -26,7 +51,9 @@ fn contract_pool_manager() -> Box<dyn Contract<Empty>> {
         crate::contract::instantiate,
         crate::contract::query,
     )
-    .with_reply(crate::contract::reply);
+    .with_reply(crate::contract::reply)
+    .with_sudo(crate::contract::sudo)
+    .with_migrate(crate::contract::migrate);
 
     Box::new(contract)
 }
@@ -67,6 +94,453 @@ pub fn farm_manager_contract() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+/// Creates the LP wrapper contract
+pub fn lp_wrapper_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        lp_wrapper::contract::execute,
+        lp_wrapper::contract::instantiate,
+        lp_wrapper::contract::query,
+    )
+    .with_migrate(lp_wrapper::contract::migrate);
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum HookReceiverExecuteMsg {
+    /// Records the callback
+    Record {},
+    /// Fails the callback
+    Fail {},
+}
+
+#[cw_serde]
+pub enum HookReceiverQueryMsg {
+    /// Returns the amount of callbacks recorded
+    Count {},
+}
+
+const HOOK_RECEIVER_COUNT: Item<u64> = Item::new("count");
+
+fn hook_receiver_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: HookReceiverExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        HookReceiverExecuteMsg::Record {} => {
+            let count = HOOK_RECEIVER_COUNT
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            HOOK_RECEIVER_COUNT.save(deps.storage, &(count + 1))?;
+            Ok(Response::default())
+        }
+        HookReceiverExecuteMsg::Fail {} => Err(StdError::generic_err("hook receiver failure")),
+    }
+}
+
+fn hook_receiver_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn hook_receiver_query(deps: Deps, _env: Env, msg: HookReceiverQueryMsg) -> StdResult<Binary> {
+    match msg {
+        HookReceiverQueryMsg::Count {} => to_json_binary(
+            &HOOK_RECEIVER_COUNT
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+    }
+}
+
+/// Creates a mock contract that records the price hooks it receives
+pub fn hook_receiver_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        hook_receiver_execute,
+        hook_receiver_instantiate,
+        hook_receiver_query,
+    );
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockOracleExecuteMsg {
+    /// Sets the price the oracle reports
+    SetPrice { rate: Decimal, last_updated: u64 },
+}
+
+const MOCK_ORACLE_PRICE: Item<mantra_dex_std::oracle::PriceResponse> = Item::new("price");
+
+fn mock_oracle_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockOracleExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockOracleExecuteMsg::SetPrice { rate, last_updated } => {
+            MOCK_ORACLE_PRICE.save(
+                deps.storage,
+                &mantra_dex_std::oracle::PriceResponse { rate, last_updated },
+            )?;
+            Ok(Response::default())
+        }
+    }
+}
+
+fn mock_oracle_query(
+    deps: Deps,
+    _env: Env,
+    msg: mantra_dex_std::oracle::QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        mantra_dex_std::oracle::QueryMsg::Price { .. } => {
+            to_json_binary(&MOCK_ORACLE_PRICE.load(deps.storage)?)
+        }
+    }
+}
+
+/// Creates a mock oracle contract reporting the price set on it
+pub fn mock_oracle_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_oracle_execute,
+        hook_receiver_instantiate,
+        mock_oracle_query,
+    );
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockRateProviderExecuteMsg {
+    /// Sets the rate the rate provider reports
+    SetRate { rate: Decimal },
+}
+
+const MOCK_RATE: Item<Decimal> = Item::new("rate");
+
+fn mock_rate_provider_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockRateProviderExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockRateProviderExecuteMsg::SetRate { rate } => {
+            MOCK_RATE.save(deps.storage, &rate)?;
+            Ok(Response::default())
+        }
+    }
+}
+
+fn mock_rate_provider_query(
+    deps: Deps,
+    _env: Env,
+    msg: mantra_dex_std::rate_provider::QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        mantra_dex_std::rate_provider::QueryMsg::Rate {} => {
+            to_json_binary(&mantra_dex_std::rate_provider::RateResponse {
+                rate: MOCK_RATE.load(deps.storage)?,
+            })
+        }
+    }
+}
+
+/// Creates a mock rate provider contract reporting the rate set on it
+pub fn mock_rate_provider_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_rate_provider_execute,
+        hook_receiver_instantiate,
+        mock_rate_provider_query,
+    );
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockStakingExecuteMsg {
+    /// Sets the stake the staking contract reports for the given address
+    SetStake { address: String, amount: Uint128 },
+}
+
+const MOCK_STAKES: Map<&str, Uint128> = Map::new("stakes");
+
+fn mock_staking_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockStakingExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockStakingExecuteMsg::SetStake { address, amount } => {
+            MOCK_STAKES.save(deps.storage, &address, &amount)?;
+            Ok(Response::default())
+        }
+    }
+}
+
+fn mock_staking_query(
+    deps: Deps,
+    _env: Env,
+    msg: mantra_dex_std::staking::QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        mantra_dex_std::staking::QueryMsg::Stake { address } => {
+            to_json_binary(&mantra_dex_std::staking::StakeResponse {
+                amount: MOCK_STAKES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            })
+        }
+    }
+}
+
+/// Creates a mock staking contract reporting the stakes set on it
+pub fn mock_staking_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_staking_execute,
+        hook_receiver_instantiate,
+        mock_staking_query,
+    );
+
+    Box::new(contract)
+}
+
+/// The adapter-specific message of the mock swap adapter.
+#[cw_serde]
+pub struct MockSwapAdapterMsg {
+    /// The rate the adapter quotes and swaps at.
+    pub rate: Decimal,
+    /// The amount the adapter sends on top of its quote when swapping, if any.
+    pub surplus: Uint128,
+}
+
+fn mock_swap_adapter_execute(
+    _deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: mantra_dex_std::swap_adapter::ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        mantra_dex_std::swap_adapter::ExecuteMsg::Swap {
+            ask_denom,
+            min_return,
+            msg,
+        } => {
+            let MockSwapAdapterMsg { rate, surplus } = from_json(msg)?;
+            let return_amount = info.funds[0].amount.mul_floor(rate) + surplus;
+            if return_amount < min_return {
+                return Err(StdError::generic_err("min return not met"));
+            }
+
+            Ok(Response::default().add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(return_amount.u128(), ask_denom)],
+            }))
+        }
+    }
+}
+
+fn mock_swap_adapter_query(
+    _deps: Deps,
+    _env: Env,
+    msg: mantra_dex_std::swap_adapter::QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        mantra_dex_std::swap_adapter::QueryMsg::Simulation {
+            offer_asset, msg, ..
+        } => {
+            let MockSwapAdapterMsg { rate, .. } = from_json(msg)?;
+            to_json_binary(&mantra_dex_std::swap_adapter::SimulationResponse {
+                return_amount: offer_asset.amount.mul_floor(rate),
+            })
+        }
+    }
+}
+
+/// Creates a mock swap adapter contract, swapping out of its own balance at the rate given in the
+/// message of each swap, plus the surplus it gives, which it leaves out of its quotes
+pub fn mock_swap_adapter_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_swap_adapter_execute,
+        hook_receiver_instantiate,
+        mock_swap_adapter_query,
+    );
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockYieldStrategyExecuteMsg {
+    /// Deposits the funds sent into the given position
+    Deposit { position: String },
+    /// Withdraws the given asset out of the position, sending it to the sender
+    Withdraw { position: String, asset: Coin },
+    /// Credits the funds sent to the position as yield
+    AccrueYield { position: String },
+    /// Writes the given amount off the position of the given owner as a loss
+    Slash {
+        owner: String,
+        position: String,
+        amount: Uint128,
+    },
+}
+
+const MOCK_YIELD_POSITIONS: Map<(&Addr, &str), Uint128> = Map::new("positions");
+
+fn mock_yield_strategy_execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: MockYieldStrategyExecuteMsg,
+) -> StdResult<Response> {
+    let mut response = Response::default();
+    let (owner, position, change): (Addr, String, i128) = match msg {
+        MockYieldStrategyExecuteMsg::Deposit { position }
+        | MockYieldStrategyExecuteMsg::AccrueYield { position } => {
+            let amount = info
+                .funds
+                .iter()
+                .map(|fund| fund.amount.u128())
+                .sum::<u128>();
+            (info.sender, position, amount as i128)
+        }
+        MockYieldStrategyExecuteMsg::Withdraw { position, asset } => {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![asset.clone()],
+            });
+            (info.sender, position, -(asset.amount.u128() as i128))
+        }
+        MockYieldStrategyExecuteMsg::Slash {
+            owner,
+            position,
+            amount,
+        } => (
+            deps.api.addr_validate(&owner)?,
+            position,
+            -(amount.u128() as i128),
+        ),
+    };
+
+    MOCK_YIELD_POSITIONS.update(
+        deps.storage,
+        (&owner, &position),
+        |amount| -> StdResult<_> {
+            let amount = amount.unwrap_or_default().u128() as i128 + change;
+            if amount < 0 {
+                return Err(StdError::generic_err("position overdrawn"));
+            }
+            Ok(Uint128::new(amount as u128))
+        },
+    )?;
+
+    Ok(response)
+}
+
+fn mock_yield_strategy_query(
+    deps: Deps,
+    _env: Env,
+    msg: mantra_dex_std::yield_strategy::QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        mantra_dex_std::yield_strategy::QueryMsg::Position { owner, position } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            to_json_binary(&mantra_dex_std::yield_strategy::PositionResponse {
+                amount: MOCK_YIELD_POSITIONS
+                    .may_load(deps.storage, (&owner, &position))?
+                    .unwrap_or_default(),
+            })
+        }
+    }
+}
+
+/// Creates a mock yield strategy contract, holding the funds deposited into it in positions
+pub fn mock_yield_strategy_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_yield_strategy_execute,
+        hook_receiver_instantiate,
+        mock_yield_strategy_query,
+    );
+
+    Box::new(contract)
+}
+
+#[cw_serde]
+pub enum MockFlashBorrowerExecuteMsg {
+    /// Sends the given asset to the given address, i.e. to repay a flash swap
+    Repay { to: String, asset: Coin },
+    /// Executes the given message on the given contract, i.e. to reenter the pool manager
+    Reenter {
+        contract: String,
+        msg: Binary,
+        funds: Vec<Coin>,
+    },
+}
+
+fn mock_flash_borrower_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockFlashBorrowerExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockFlashBorrowerExecuteMsg::Repay { to, asset } => {
+            Ok(Response::default().add_message(BankMsg::Send {
+                to_address: to,
+                amount: vec![asset],
+            }))
+        }
+        MockFlashBorrowerExecuteMsg::Reenter {
+            contract,
+            msg,
+            funds,
+        } => Ok(Response::default().add_message(WasmMsg::Execute {
+            contract_addr: contract,
+            msg,
+            funds,
+        })),
+    }
+}
+
+/// Creates a mock contract that borrows from flash swaps, executing the callback it's given
+pub fn mock_flash_borrower_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new_with_empty(
+        mock_flash_borrower_execute,
+        hook_receiver_instantiate,
+        hook_receiver_query,
+    );
+
+    Box::new(contract)
+}
+
+/// Pool fees charging nothing
+pub(crate) fn zero_fees() -> PoolFee {
+    swap_fee_only(Decimal::zero())
+}
+
+/// Pool fees charging only the given swap fee
+pub(crate) fn swap_fee_only(share: Decimal) -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: Decimal::zero(),
+        },
+        swap_fee: Fee { share },
+        burn_fee: Fee {
+            share: Decimal::zero(),
+        },
+        extra_fees: vec![],
+    }
+}
+
 type OsmosisTokenFactoryApp = App<
     BankKeeper,
     MockApiBech32,
@@ -102,6 +576,12 @@ impl TestingSuite {
 
         self
     }
+
+    pub(crate) fn get_time(&mut self, result: impl Fn(Timestamp)) -> &mut Self {
+        result(self.app.block_info().time);
+
+        self
+    }
     pub(crate) fn add_one_day(&mut self) -> &mut Self {
         let mut block_info = self.app.block_info();
         block_info.time = block_info.time.plus_days(1);
@@ -173,7 +653,14 @@ impl TestingSuite {
         let msg = InstantiateMsg {
             fee_collector_addr,
             farm_manager_addr,
-            pool_creation_fee: coin(1_000, "uusd"),
+            pool_creation_fees: vec![coin(1_000, "uusd")],
+            pool_fee_caps: PoolFeeCaps {
+                max_swap_fee: Decimal::percent(20),
+                max_protocol_fee: Decimal::percent(20),
+                max_burn_fee: Decimal::percent(20),
+                max_extra_fees: Decimal::percent(20),
+                max_total_fee: Decimal::percent(50),
+            },
         };
 
         let pool_manager_id = self.app.store_code(contract_pool_manager());
@@ -268,6 +755,12 @@ impl TestingSuite {
     }
 
     fn create_farm_manager(&mut self) {
+        self.farm_manager_addr = self.instantiate_farm_manager();
+    }
+
+    /// Instantiates a farm manager with the default config, e.g. to replace the one in use.
+    #[track_caller]
+    pub(crate) fn instantiate_farm_manager(&mut self) -> Addr {
         let farm_manager_id = self.app.store_code(farm_manager_contract());
 
         let creator = self.creator().clone();
@@ -291,8 +784,7 @@ impl TestingSuite {
             emergency_unlock_penalty: Decimal::percent(10),
         };
 
-        self.farm_manager_addr = self
-            .app
+        self.app
             .instantiate_contract(
                 farm_manager_id,
                 creator.clone(),
@@ -301,315 +793,3968 @@ impl TestingSuite {
                 "Farm Manager".to_string(),
                 Some(creator.to_string()),
             )
-            .unwrap();
+            .unwrap()
     }
 }
 
 /// execute messages
 impl TestingSuite {
     #[track_caller]
-    pub(crate) fn update_ownership(
+    pub(crate) fn instantiate_hook_receiver(&mut self) -> Addr {
+        let hook_receiver_id = self.app.store_code(hook_receiver_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                hook_receiver_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Hook Receiver".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_oracle(&mut self) -> Addr {
+        let mock_oracle_id = self.app.store_code(mock_oracle_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_oracle_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Oracle".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_rate_provider(&mut self) -> Addr {
+        let mock_rate_provider_id = self.app.store_code(mock_rate_provider_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_rate_provider_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Rate Provider".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_staking(&mut self) -> Addr {
+        let mock_staking_id = self.app.store_code(mock_staking_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_staking_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Staking".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_swap_adapter(&mut self) -> Addr {
+        let mock_swap_adapter_id = self.app.store_code(mock_swap_adapter_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_swap_adapter_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Swap Adapter".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_yield_strategy(&mut self) -> Addr {
+        let mock_yield_strategy_id = self.app.store_code(mock_yield_strategy_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_yield_strategy_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Yield Strategy".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn instantiate_mock_flash_borrower(&mut self) -> Addr {
+        let mock_flash_borrower_id = self.app.store_code(mock_flash_borrower_contract());
+        let creator = self.creator();
+
+        self.app
+            .instantiate_contract(
+                mock_flash_borrower_id,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "Mock Flash Borrower".to_string(),
+                Some(creator.to_string()),
+            )
+            .unwrap()
+    }
+
+    #[track_caller]
+    pub(crate) fn accrue_strategy_yield(
         &mut self,
-        sender: &Addr,
-        action: cw_ownable::Action,
+        strategy: &Addr,
+        pool_identifier: String,
+        funds: Vec<Coin>,
         result: impl Fn(Result<AppResponse, anyhow::Error>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateOwnership(action);
+        let sender = self.creator();
 
         result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &[],
+            sender,
+            strategy.clone(),
+            &MockYieldStrategyExecuteMsg::AccrueYield {
+                position: pool_identifier,
+            },
+            &funds,
         ));
 
         self
     }
 
     #[track_caller]
-    pub(crate) fn provide_liquidity(
+    pub(crate) fn slash_strategy(
         &mut self,
-        sender: &Addr,
+        strategy: &Addr,
         pool_identifier: String,
-        unlocking_duration: Option<u64>,
-        lock_position_identifier: Option<String>,
-        max_spread: Option<Decimal>,
-        receiver: Option<String>,
-        funds: Vec<Coin>,
+        amount: Uint128,
         result: impl Fn(Result<AppResponse, anyhow::Error>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ProvideLiquidity {
-            pool_identifier,
-            slippage_tolerance: None,
-            max_spread,
-            receiver,
-            unlocking_duration,
-            lock_position_identifier,
-        };
+        let sender = self.creator();
 
         result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &funds,
+            sender,
+            strategy.clone(),
+            &MockYieldStrategyExecuteMsg::Slash {
+                owner: self.pool_manager_addr.to_string(),
+                position: pool_identifier,
+                amount,
+            },
+            &[],
         ));
 
         self
     }
 
     #[track_caller]
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn swap(
+    pub(crate) fn set_oracle_price(
         &mut self,
-        sender: &Addr,
-        ask_asset_denom: String,
-        belief_price: Option<Decimal>,
-        max_spread: Option<Decimal>,
-        receiver: Option<String>,
-        pool_identifier: String,
-        funds: Vec<Coin>,
+        oracle: &Addr,
+        rate: Decimal,
+        last_updated: u64,
         result: impl Fn(Result<AppResponse, anyhow::Error>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Swap {
-            ask_asset_denom,
-            belief_price,
+        let creator = self.creator();
+
+        result(self.app.execute_contract(
+            creator,
+            oracle.clone(),
+            &MockOracleExecuteMsg::SetPrice { rate, last_updated },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn set_rate(
+        &mut self,
+        rate_provider: &Addr,
+        rate: Decimal,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let creator = self.creator();
+
+        result(self.app.execute_contract(
+            creator,
+            rate_provider.clone(),
+            &MockRateProviderExecuteMsg::SetRate { rate },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn set_stake(
+        &mut self,
+        staking: &Addr,
+        address: &Addr,
+        amount: Uint128,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let creator = self.creator();
+
+        result(self.app.execute_contract(
+            creator,
+            staking.clone(),
+            &MockStakingExecuteMsg::SetStake {
+                address: address.to_string(),
+                amount,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn subscribe_price_threshold(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        threshold: Decimal,
+        callback: Binary,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SubscribePriceThreshold {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            threshold,
+            callback,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn unsubscribe_price_threshold(
+        &mut self,
+        sender: &Addr,
+        subscription_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg =
+            mantra_dex_std::pool_manager::ExecuteMsg::UnsubscribePriceThreshold { subscription_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_ownership(
+        &mut self,
+        sender: &Addr,
+        action: cw_ownable::Action,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateOwnership(action);
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn provide_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        unlocking_duration: Option<u64>,
+        lock_position_identifier: Option<String>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.provide_liquidity_with_min_lp(
+            sender,
+            pool_identifier,
+            unlocking_duration,
+            lock_position_identifier,
+            max_spread,
+            receiver,
+            None,
+            funds,
+            result,
+        )
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn provide_liquidity_with_min_lp(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        unlocking_duration: Option<u64>,
+        lock_position_identifier: Option<String>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        min_lp_to_receive: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ProvideLiquidity {
+            pool_identifier,
+            slippage_tolerance: None,
+            max_spread,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive,
+            deposit_route: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn provide_liquidity_via_route(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        deposit_route: Vec<SwapOperation>,
+        unlocking_duration: Option<u64>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ProvideLiquidity {
+            pool_identifier,
+            slippage_tolerance: None,
+            max_spread: None,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier: None,
+            min_lp_to_receive: None,
+            deposit_route: Some(deposit_route),
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.swap_with_fee_payer(
+            sender,
+            ask_asset_denom,
+            belief_price,
+            max_spread,
+            receiver,
+            pool_identifier,
+            None,
+            funds,
+            result,
+        )
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap_with_fee_payer(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        pool_identifier: String,
+        fee_payer: Option<FeePayer>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Swap {
+            ask_asset_denom,
+            belief_price,
             max_spread,
             receiver,
             pool_identifier,
+            fee_payer,
+            referral: None,
+            min_receive: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap_with_min_receive(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        pool_identifier: String,
+        fee_payer: Option<FeePayer>,
+        referral: Option<Referral>,
+        min_receive: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Swap {
+            ask_asset_denom,
+            belief_price: None,
+            max_spread: None,
+            receiver: None,
+            pool_identifier,
+            fee_payer,
+            referral,
+            min_receive,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap_with_referral(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        max_spread: Option<Decimal>,
+        pool_identifier: String,
+        referral: Option<Referral>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Swap {
+            ask_asset_denom,
+            belief_price: None,
+            max_spread,
+            receiver: None,
+            pool_identifier,
+            fee_payer: None,
+            referral,
+            min_receive: None,
         };
 
-        result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &funds,
-        ));
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn batch_swap(
+        &mut self,
+        sender: &Addr,
+        swaps: Vec<SwapSpec>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::BatchSwap {
+            swaps,
+            max_spread,
+            receiver,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap_exact_out(
+        &mut self,
+        sender: &Addr,
+        ask_asset: Coin,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SwapExactOut {
+            ask_asset,
+            max_spread,
+            receiver,
+            pool_identifier,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn flash_swap(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        ask_asset: Coin,
+        offer_asset_denom: String,
+        receiver: Option<String>,
+        callback: Binary,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::FlashSwap {
+            pool_identifier,
+            ask_asset,
+            offer_asset_denom,
+            max_spread: Some(Decimal::percent(5)),
+            receiver,
+            callback,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_swap_operations(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        receiver: Option<String>,
+        max_spread: Option<Decimal>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.execute_swap_operations_exact_out(
+            sender,
+            operations,
+            minimum_receive,
+            receiver,
+            max_spread,
+            None,
+            funds,
+            result,
+        )
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_swap_operations_exact_out(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        receiver: Option<String>,
+        max_spread: Option<Decimal>,
+        ask_amount: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            receiver,
+            max_spread,
+            ask_amount,
+            referral: None,
+            hop_limits: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_swap_operations_with_hop_limits(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        hop_limits: Option<Vec<HopLimit>>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            receiver: None,
+            max_spread,
+            ask_amount: None,
+            referral: None,
+            hop_limits,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn swap_and_lock(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        pool_identifier: String,
+        unlocking_duration: u64,
+        lock_position_identifier: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SwapAndLock {
+            operations,
+            minimum_receive,
+            max_spread: None,
+            pool_identifier,
+            slippage_tolerance: None,
+            unlocking_duration,
+            lock_position_identifier,
+            min_lp_to_receive: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn zap(
+        &mut self,
+        sender: &Addr,
+        route: Vec<SwapOperation>,
+        pool_identifier: String,
+        receiver: Option<String>,
+        unlocking_duration: Option<u64>,
+        min_lp_to_receive: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Zap {
+            route,
+            pool_identifier,
+            max_spread: None,
+            slippage_tolerance: None,
+            receiver,
+            unlocking_duration,
+            lock_position_identifier: None,
+            min_lp_to_receive,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn withdraw_liquidity_and_swap(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        target_denom: String,
+        route: Vec<SwapOperation>,
+        min_out: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawLiquidityAndSwap {
+            pool_identifier,
+            target_denom,
+            route,
+            min_out,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn execute_swap_operations_with_referral(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        referral: Option<Referral>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            receiver: None,
+            max_spread,
+            ask_amount: None,
+            referral,
+            hop_limits: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_pool(
+        &mut self,
+        sender: &Addr,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: Option<String>,
+        pool_creation_fee_funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.create_pool_with_canonical_identifier(
+            sender,
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            false,
+            pool_creation_fee_funds,
+            result,
+        )
+    }
+
+    /// Creates a pool as the creator, paying the pool creation fee, and provides its initial
+    /// liquidity. The pool identifier is the one given, prefixed as the contract does.
+    #[track_caller]
+    pub(crate) fn create_seeded_pool(
+        &mut self,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: &str,
+        liquidity: Vec<Coin>,
+    ) -> &mut Self {
+        let creator = self.creator();
+
+        self.create_pool(
+            &creator,
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            Some(pool_identifier.to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .provide_liquidity(
+            &creator,
+            format!("o.{pool_identifier}"),
+            None,
+            None,
+            None,
+            None,
+            liquidity,
+            |result| {
+                result.unwrap();
+            },
+        )
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_pool_with_canonical_identifier(
+        &mut self,
+        sender: &Addr,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: Option<String>,
+        canonical_identifier: bool,
+        pool_creation_fee_funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreatePool {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            canonical_identifier,
+            metadata: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &pool_creation_fee_funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_pool_with_metadata(
+        &mut self,
+        sender: &Addr,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: Option<String>,
+        metadata: Option<PoolMetadata>,
+        pool_creation_fee_funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreatePool {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            canonical_identifier: false,
+            metadata,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &pool_creation_fee_funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_pool_metadata(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        metadata: Option<PoolMetadata>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdatePoolMetadata {
+            pool_identifier,
+            metadata,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn create_pools(
+        &mut self,
+        sender: &Addr,
+        pools: Vec<PoolCreationParams>,
+        pool_creation_fee_funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreatePools { pools };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &pool_creation_fee_funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn clone_pool(
+        &mut self,
+        sender: &Addr,
+        source_pool: String,
+        identifier: Option<String>,
+        overrides: PoolOverrides,
+        pool_creation_fee_funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ClonePool {
+            source_pool,
+            identifier,
+            overrides,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &pool_creation_fee_funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn bootstrap_pool(
+        &mut self,
+        sender: &Addr,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: Option<String>,
+        seed_assets: Vec<Coin>,
+        unlocking_duration: u64,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::BootstrapPool {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            seed_assets,
+            unlocking_duration,
+            receiver,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_pool_and_provide_liquidity(
+        &mut self,
+        sender: &Addr,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_fees: PoolFee,
+        pool_type: PoolType,
+        pool_identifier: Option<String>,
+        liquidity: Vec<Coin>,
+        min_lp_to_receive: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreatePoolAndProvideLiquidity {
+            asset_denoms,
+            asset_decimals,
+            pool_fees,
+            pool_type,
+            pool_identifier,
+            canonical_identifier: false,
+            metadata: None,
+            liquidity,
+            receiver: None,
+            min_lp_to_receive,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.withdraw_liquidity_to_remote(sender, pool_identifier, None, funds, result)
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_liquidity_to_remote(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        remote_receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.withdraw_liquidity_with_min_amounts_out(
+            sender,
+            pool_identifier,
+            remote_receiver,
+            None,
+            funds,
+            result,
+        )
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_liquidity_with_min_amounts_out(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        remote_receiver: Option<String>,
+        min_amounts_out: Option<Vec<Coin>>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawLiquidity {
+            pool_identifier,
+            remote_receiver,
+            min_amounts_out,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn send_tokens(
+        &mut self,
+        sender: &Addr,
+        recipient: &Addr,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(
+            self.app
+                .send_tokens(sender.clone(), recipient.clone(), &funds),
+        );
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn skim(
+        &mut self,
+        sender: &Addr,
+        denom: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Skim { denom };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn donate(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::Donate { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn place_stop_loss_order(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        trigger_price: Decimal,
+        minimum_receive: Uint128,
+        max_spread: Option<Decimal>,
+        bounty: Coin,
+        twap_window: u64,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::PlaceStopLossOrder {
+            operations,
+            trigger_price,
+            minimum_receive,
+            max_spread,
+            bounty,
+            twap_window,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn cancel_stop_loss_order(
+        &mut self,
+        sender: &Addr,
+        order_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CancelStopLossOrder { order_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn execute_stop_loss_order(
+        &mut self,
+        sender: &Addr,
+        order_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteStopLossOrder { order_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_dca_schedule(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        amount_per_swap: Uint128,
+        min_receive_per_swap: Uint128,
+        interval: u64,
+        max_spread: Option<Decimal>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreateDcaSchedule {
+            operations,
+            amount_per_swap,
+            min_receive_per_swap,
+            interval,
+            max_spread,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn execute_dca_schedule(
+        &mut self,
+        sender: &Addr,
+        schedule_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteDcaSchedule { schedule_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_liquidity_stream(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        tranches: u64,
+        min_lp_per_tranche: Uint128,
+        interval: u64,
+        slippage_tolerance: Option<Decimal>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreateLiquidityStream {
+            pool_identifier,
+            tranches,
+            min_lp_per_tranche,
+            interval,
+            slippage_tolerance,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn execute_liquidity_stream(
+        &mut self,
+        sender: &Addr,
+        stream_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteLiquidityStream { stream_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn cancel_liquidity_stream(
+        &mut self,
+        sender: &Addr,
+        stream_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CancelLiquidityStream { stream_id };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_dca_budget(
+        &mut self,
+        sender: &Addr,
+        schedule_id: u64,
+        amount: Option<Uint128>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawDcaBudget {
+            schedule_id,
+            amount,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn register_rfq_maker(
+        &mut self,
+        sender: &Addr,
+        pubkey: Binary,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::RegisterRfqMaker { pubkey };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn deposit_rfq_inventory(
+        &mut self,
+        sender: &Addr,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::DepositRfqInventory {};
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_rfq_inventory(
+        &mut self,
+        sender: &Addr,
+        assets: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawRfqInventory { assets };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn fill_rfq_quote(
+        &mut self,
+        sender: &Addr,
+        quote: RfqQuote,
+        signature: Binary,
+        fallback_pool_identifier: Option<String>,
+        max_spread: Option<Decimal>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::FillRfqQuote {
+            quote,
+            signature,
+            fallback_pool_identifier,
+            max_spread,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn store_lp_wrapper_code(&mut self) -> u64 {
+        self.app.store_code(lp_wrapper_contract())
+    }
+
+    #[track_caller]
+    pub(crate) fn create_lp_wrapper(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        code_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreateLpWrapper {
+            pool_identifier,
+            code_id,
+            name: "Wrapped LP".to_string(),
+            symbol: "wLP".to_string(),
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn wrap_lp_tokens(
+        &mut self,
+        sender: &Addr,
+        lp_wrapper: &Addr,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::lp_wrapper::ExecuteMsg::Wrap { receiver: None };
+
+        result(
+            self.app
+                .execute_contract(sender.clone(), lp_wrapper.clone(), &msg, &funds),
+        );
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn unwrap_lp_tokens(
+        &mut self,
+        sender: &Addr,
+        lp_wrapper: &Addr,
+        amount: Uint128,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::lp_wrapper::ExecuteMsg::Unwrap {
+            amount,
+            receiver: None,
+        };
+
+        result(
+            self.app
+                .execute_contract(sender.clone(), lp_wrapper.clone(), &msg, &[]),
+        );
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_migration_campaign(
+        &mut self,
+        sender: &Addr,
+        from_pool_identifier: String,
+        to_pool_identifier: String,
+        deadline: u64,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreateMigrationCampaign {
+            from_pool_identifier,
+            to_pool_identifier,
+            deadline,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn cancel_migration_campaign(
+        &mut self,
+        sender: &Addr,
+        from_pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CancelMigrationCampaign {
+            from_pool_identifier,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn move_liquidity(
+        &mut self,
+        sender: &Addr,
+        from_pool_identifier: String,
+        to_pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::MoveLiquidity {
+            from_pool_identifier,
+            to_pool_identifier,
+            slippage_tolerance: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn claim_migration_rewards(
+        &mut self,
+        sender: &Addr,
+        from_pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ClaimMigrationRewards {
+            from_pool_identifier,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn unlock_and_withdraw_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        position_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UnlockAndWithdrawLiquidity {
+            pool_identifier,
+            position_identifier,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_ibc_withdrawal_channels(
+        &mut self,
+        sender: &Addr,
+        channels: Vec<IbcWithdrawalChannel>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg =
+            mantra_dex_std::pool_manager::ExecuteMsg::UpdateIbcWithdrawalChannels { channels };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn add_swap_routes(
+        &mut self,
+        sender: &Addr,
+        swap_routes: Vec<SwapRoute>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::AddSwapRoutes { swap_routes };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_swap_adapters(
+        &mut self,
+        sender: &Addr,
+        add: Vec<String>,
+        remove: Vec<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateSwapAdapters { add, remove };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn swap_via_registry(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        minimum_receive: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SwapViaRegistry {
+            ask_asset_denom,
+            minimum_receive,
+            receiver: None,
+            max_spread: None,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn remove_swap_routes(
+        &mut self,
+        sender: &Addr,
+        swap_routes: Vec<SwapRoute>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::RemoveSwapRoutes { swap_routes };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_rebalance_config(
+        &mut self,
+        sender: &Addr,
+        keepers: Option<Vec<String>>,
+        max_move_share: Option<Decimal>,
+        min_deviation_gap: Option<Decimal>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateRebalanceConfig {
+            keepers,
+            max_move_share,
+            min_deviation_gap,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_depeg_protection(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        config: Option<DepegProtectionConfig>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateDepegProtection {
+            pool_identifier,
+            config,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_price_breaker(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        config: Option<PriceBreakerConfig>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdatePriceBreaker {
+            pool_identifier,
+            config,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn trip_price_breaker(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::TripPriceBreaker { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn reset_price_breaker(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ResetPriceBreaker { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_fee_discounts(
+        &mut self,
+        sender: &Addr,
+        config: Option<FeeDiscountConfig>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateFeeDiscounts { config };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_imbalance_fee(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        imbalance_fee: Option<ImbalanceFee>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateImbalanceFee {
+            pool_identifier,
+            imbalance_fee,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn hook_swap(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        max_spread: Option<Decimal>,
+        pool_identifier: String,
+        receiver: String,
+        forward: Option<PacketForward>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::HookSwap {
+            ask_asset_denom,
+            belief_price: None,
+            max_spread,
+            pool_identifier,
+            receiver,
+            forward,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn hook_swap_operations(
+        &mut self,
+        sender: &Addr,
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        receiver: String,
+        forward: Option<PacketForward>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::HookSwapOperations {
+            operations,
+            minimum_receive,
+            max_spread,
+            receiver,
+            forward,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_ibc_source_channels(
+        &mut self,
+        sender: &Addr,
+        channels: Vec<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateIbcSourceChannels { channels };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_min_lp_lock(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        min_unlocking_duration: Option<u64>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateMinLpLock {
+            pool_identifier,
+            min_unlocking_duration,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_unlocking_duration_bounds(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: Option<String>,
+        bounds: Option<UnlockingDurationBounds>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateUnlockingDurationBounds {
+            pool_identifier,
+            bounds,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_reserve_floors(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        floors: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateReserveFloors {
+            pool_identifier,
+            floors,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn deprecate_pool(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::DeprecatePool { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_pool_fees(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        new_fees: PoolFee,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdatePoolFees {
+            pool_identifier,
+            new_fees,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn apply_pool_fees(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ApplyPoolFees { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_rate_providers(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        rate_providers: Vec<Option<String>>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateRateProviders {
+            pool_identifier,
+            rate_providers,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_dust_tracking(
+        &mut self,
+        sender: &Addr,
+        enabled: bool,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdateDustTracking { enabled };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn sweep_dust(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SweepDust { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_permissioned_pool(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        admin: Option<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdatePermissionedPool {
+            pool_identifier,
+            admin,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_pool_allowlist(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        add: Vec<String>,
+        remove: Vec<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::UpdatePoolAllowlist {
+            pool_identifier,
+            add,
+            remove,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_pool_creator_allowlist(
+        &mut self,
+        sender: &Addr,
+        add: Vec<String>,
+        remove: Vec<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg =
+            mantra_dex_std::pool_manager::ExecuteMsg::UpdatePoolCreatorAllowlist { add, remove };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn propose_pool_admin(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        new_admin: Option<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ProposePoolAdmin {
+            pool_identifier,
+            new_admin,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn accept_pool_admin(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::AcceptPoolAdmin { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn sudo(
+        &mut self,
+        msg: SudoMsg,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.wasm_sudo(self.pool_manager_addr.clone(), &msg));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn sync_pool(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        max_deviation: Decimal,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SyncPool {
+            pool_identifier,
+            max_deviation,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn deposit_protocol_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg =
+            mantra_dex_std::pool_manager::ExecuteMsg::DepositProtocolLiquidity { pool_identifier };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_protocol_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        amount: Uint128,
+        receiver: Option<String>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawProtocolLiquidity {
+            pool_identifier,
+            amount,
+            receiver,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn rebalance_stable_pools(
+        &mut self,
+        sender: &Addr,
+        from_pool_identifier: String,
+        to_pool_identifier: String,
+        lp_amount: Uint128,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::RebalanceStablePools {
+            from_pool_identifier,
+            to_pool_identifier,
+            lp_amount,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn split_swap(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        pool_identifiers: Vec<String>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        self.weighted_split_swap(
+            sender,
+            ask_asset_denom,
+            pool_identifiers,
+            None,
+            minimum_receive,
+            max_spread,
+            receiver,
+            funds,
+            result,
+        )
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn weighted_split_swap(
+        &mut self,
+        sender: &Addr,
+        ask_asset_denom: String,
+        pool_identifiers: Vec<String>,
+        weights: Option<Vec<u64>>,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        let msg = mantra_dex_std::pool_manager::ExecuteMsg::SplitSwap {
+            ask_asset_denom,
+            pool_identifiers,
+            weights,
+            minimum_receive,
+            max_spread,
+            receiver,
+        };
+
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &msg,
+            &funds,
+        ));
+
+        self
+    }
+
+    /// Updates the configuration of the contract.
+    ///
+    /// Any parameters which are set to `None` when passed will not update
+    /// the current configuration.
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn update_config(
+        &mut self,
+        sender: &Addr,
+        new_fee_collector_addr: Option<Addr>,
+        new_farm_manager_addr: Option<Addr>,
+        new_pool_creation_fees: Option<Vec<Coin>>,
+        new_feature_toggle: Option<FeatureToggle>,
+        new_pool_fee_caps: Option<PoolFeeCaps>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: new_fee_collector_addr.map(|addr| addr.to_string()),
+                farm_manager_addr: new_farm_manager_addr.map(|addr| addr.to_string()),
+                pool_creation_fees: new_pool_creation_fees,
+                feature_toggle: new_feature_toggle,
+                pool_fee_caps: new_pool_fee_caps,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_spread_config(
+        &mut self,
+        sender: &Addr,
+        default_max_spread: Option<Decimal>,
+        max_spread_cap: Option<Decimal>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: None,
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread,
+                max_spread_cap,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_max_assets_per_pool(
+        &mut self,
+        sender: &Addr,
+        max_assets_per_pool: u32,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: None,
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: Some(max_assets_per_pool),
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_max_assets_per_stableswap_pool(
+        &mut self,
+        sender: &Addr,
+        max_assets_per_stableswap_pool: u32,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: None,
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: Some(max_assets_per_stableswap_pool),
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_permissionless_pool_creation(
+        &mut self,
+        sender: &Addr,
+        permissionless_pool_creation: bool,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: None,
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: Some(permissionless_pool_creation),
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_max_referral_fee_bps(
+        &mut self,
+        sender: &Addr,
+        max_referral_fee_bps: u16,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                farm_manager_addr: None,
+                pool_creation_fees: None,
+                feature_toggle: None,
+                pool_fee_caps: None,
+                default_max_spread: None,
+                max_spread_cap: None,
+                max_assets_per_pool: None,
+                max_assets_per_stableswap_pool: None,
+                permissionless_pool_creation: None,
+                pool_fee_update_delay: None,
+                max_referral_fee_bps: Some(max_referral_fee_bps),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn add_yield_strategy(
+        &mut self,
+        sender: &Addr,
+        strategy: &Addr,
+        denom: &str,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::AddYieldStrategy {
+                strategy: strategy.to_string(),
+                denom: denom.to_string(),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn remove_yield_strategy(
+        &mut self,
+        sender: &Addr,
+        strategy: &Addr,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::RemoveYieldStrategy {
+                strategy: strategy.to_string(),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_pool_yield_strategy(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        denom: &str,
+        strategy: Option<&Addr>,
+        max_deployed_share: Decimal,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::UpdatePoolYieldStrategy {
+                pool_identifier,
+                denom: denom.to_string(),
+                strategy: strategy.map(|strategy| strategy.to_string()),
+                max_deployed_share,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn deploy_idle_reserves(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        denom: &str,
+        amount: Uint128,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::DeployIdleReserves {
+                pool_identifier,
+                denom: denom.to_string(),
+                amount,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn recall_reserves(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        denom: &str,
+        amount: Option<Uint128>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::RecallReserves {
+                pool_identifier,
+                denom: denom.to_string(),
+                amount,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn harvest_yield(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        denom: &str,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::HarvestYield {
+                pool_identifier,
+                denom: denom.to_string(),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn provide_concentrated_liquidity(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        lower_tick: i64,
+        upper_tick: i64,
+        initial_price: Option<Decimal256>,
+        min_liquidity: Option<Uint128>,
+        funds: Vec<Coin>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::ProvideConcentratedLiquidity {
+                pool_identifier,
+                lower_tick,
+                upper_tick,
+                initial_price,
+                min_liquidity,
+            },
+            &funds,
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn withdraw_concentrated_liquidity(
+        &mut self,
+        sender: &Addr,
+        position_id: u64,
+        liquidity: Option<Uint128>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::WithdrawConcentratedLiquidity {
+                position_id,
+                liquidity,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn collect_concentrated_fees(
+        &mut self,
+        sender: &Addr,
+        position_id: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &mantra_dex_std::pool_manager::ExecuteMsg::CollectConcentratedFees { position_id },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn manage_farm_position(
+        &mut self,
+        sender: &Addr,
+        action: mantra_dex_std::farm_manager::PositionAction,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::ManagePosition { action },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_farm_manager_successor(
+        &mut self,
+        sender: &Addr,
+        farm_manager_addr: &Addr,
+        successor: Option<&Addr>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::UpdateSuccessor {
+                successor: successor.map(|addr| addr.to_string()),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn update_farm_manager_predecessor(
+        &mut self,
+        sender: &Addr,
+        farm_manager_addr: &Addr,
+        predecessor: Option<&Addr>,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::UpdatePredecessor {
+                predecessor: predecessor.map(|addr| addr.to_string()),
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn migrate_farm_positions(
+        &mut self,
+        sender: &Addr,
+        farm_manager_addr: &Addr,
+        receiver: &Addr,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::MigratePositions {
+                receiver: receiver.to_string(),
+                limit: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    /// Updates the configuration of the farm manager contract.
+    ///
+    /// Any parameters which are set to `None` when passed will not update
+    /// the current configuration.
+    #[track_caller]
+    pub(crate) fn update_farm_manager_config(
+        &mut self,
+        sender: &Addr,
+        new_pool_manager_addr: Addr,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.farm_manager_addr.clone(),
+            &mantra_dex_std::farm_manager::ExecuteMsg::UpdateConfig {
+                fee_collector_addr: None,
+                epoch_manager_addr: None,
+                pool_manager_addr: Some(new_pool_manager_addr.to_string()),
+                create_farm_fee: None,
+                max_concurrent_farms: None,
+                max_farm_epoch_buffer: None,
+                min_unlocking_duration: None,
+                max_unlocking_duration: None,
+                farm_expiration_time: None,
+                emergency_unlock_penalty: None,
+            },
+            &[],
+        ));
+
+        self
+    }
+}
+
+/// queries
+impl TestingSuite {
+    pub(crate) fn query_ownership(
+        &mut self,
+        result: impl Fn(StdResult<cw_ownable::Ownership<String>>),
+    ) -> &mut Self {
+        let ownership_response: StdResult<cw_ownable::Ownership<String>> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::Ownership {},
+            );
+
+        result(ownership_response);
+
+        self
+    }
+
+    pub(crate) fn query_balance(
+        &mut self,
+        addr: &String,
+        denom: impl Into<String>,
+        result: impl Fn(StdResult<Coin>),
+    ) -> &mut Self {
+        let balance_resp: StdResult<Coin> = self.app.wrap().query_balance(addr, denom);
+
+        result(balance_resp);
+
+        self
+    }
+
+    pub(crate) fn query_all_balances(
+        &mut self,
+        addr: &String,
+        result: impl Fn(StdResult<Vec<Coin>>),
+    ) -> &mut Self {
+        let balance_resp: StdResult<Vec<Coin>> = self.app.wrap().query_all_balances(addr);
+
+        result(balance_resp);
+
+        self
+    }
+
+    pub(crate) fn query_pools(
+        &self,
+        pool_identifier: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &Self {
+        self.query_pools_with_order(pool_identifier, start_after, limit, None, result)
+    }
+
+    pub(crate) fn query_pools_with_order(
+        &self,
+        pool_identifier: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &Self {
+        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Pools {
+                pool_identifier,
+                start_after,
+                limit,
+                order,
+                deprecated: None,
+            },
+        );
+
+        result(pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_pools_by_deprecation(
+        &self,
+        deprecated: bool,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &Self {
+        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Pools {
+                pool_identifier: None,
+                start_after: None,
+                limit: None,
+                order: None,
+                deprecated: Some(deprecated),
+            },
+        );
+
+        result(pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_stop_loss_orders(
+        &mut self,
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<StopLossOrdersResponse>),
+    ) -> &mut Self {
+        let orders_response: StdResult<StopLossOrdersResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::StopLossOrders {
+                owner,
+                start_after,
+                limit,
+            },
+        );
+
+        result(orders_response);
+
+        self
+    }
+
+    pub(crate) fn query_twap(
+        &mut self,
+        pool_identifier: String,
+        window_secs: u64,
+        result: impl Fn(StdResult<TwapResponse>),
+    ) -> &mut Self {
+        let twap_response: StdResult<TwapResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Twap {
+                pool_identifier,
+                window_secs,
+            },
+        );
+
+        result(twap_response);
+
+        self
+    }
+
+    pub(crate) fn query_route_twap(
+        &mut self,
+        operations: Vec<SwapOperation>,
+        window_secs: u64,
+        result: impl Fn(StdResult<RouteTwapResponse>),
+    ) -> &mut Self {
+        let twap_response: StdResult<RouteTwapResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::RouteTwap {
+                operations,
+                window_secs,
+            },
+        );
+
+        result(twap_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_subscriptions(
+        &mut self,
+        pool_identifier: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<PriceSubscriptionsResponse>),
+    ) -> &mut Self {
+        let subscriptions_response: StdResult<PriceSubscriptionsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::PriceSubscriptions {
+                    pool_identifier,
+                    start_after,
+                    limit,
+                },
+            );
+
+        result(subscriptions_response);
+
+        self
+    }
+
+    pub(crate) fn query_hook_receiver_count(
+        &mut self,
+        hook_receiver: &Addr,
+        result: impl Fn(StdResult<u64>),
+    ) -> &mut Self {
+        result(
+            self.app
+                .wrap()
+                .query_wasm_smart(hook_receiver, &HookReceiverQueryMsg::Count {}),
+        );
+
+        self
+    }
+
+    pub(crate) fn query_liquidity_streams(
+        &mut self,
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<LiquidityStreamsResponse>),
+    ) -> &mut Self {
+        let streams_response: StdResult<LiquidityStreamsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::LiquidityStreams {
+                    owner,
+                    start_after,
+                    limit,
+                },
+            );
+
+        result(streams_response);
+
+        self
+    }
+
+    pub(crate) fn query_dca_schedules(
+        &mut self,
+        owner: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<DcaSchedulesResponse>),
+    ) -> &mut Self {
+        let schedules_response: StdResult<DcaSchedulesResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::DcaSchedules {
+                owner,
+                start_after,
+                limit,
+            },
+        );
+
+        result(schedules_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        self.query_simulation_for_trader(
+            pool_identifier,
+            offer_asset,
+            ask_asset_denom,
+            None,
+            result,
+        )
+    }
+
+    pub(crate) fn query_simulation_for_trader(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        trader: Option<String>,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let pool_info_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::Simulation {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                trader,
+            },
+        );
+
+        result(pool_info_response);
+
+        self
+    }
+
+    pub(crate) fn query_batch_simulation(
+        &mut self,
+        simulations: Vec<SimulationRequest>,
+        result: impl Fn(StdResult<BatchSimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<BatchSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::BatchSimulation { simulations },
+            );
+
+        result(simulation_response);
+
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn query_lp_valuation(
+        &mut self,
+        pool_identifier: String,
+        address: Option<String>,
+        lp_amount: Option<Uint128>,
+        target_denom: String,
+        twap_window_secs: Option<u64>,
+        swap_routes: Vec<SwapRoute>,
+        result: impl Fn(StdResult<LpValuationResponse>),
+    ) -> &mut Self {
+        let lp_valuation_response: StdResult<LpValuationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::LpValuation {
+                    pool_identifier,
+                    address,
+                    lp_amount,
+                    target_denom,
+                    twap_window_secs,
+                    swap_routes,
+                },
+            );
+
+        result(lp_valuation_response);
+
+        self
+    }
+
+    pub(crate) fn query_protocol_liquidity(
+        &mut self,
+        result: impl Fn(StdResult<ProtocolLiquidityResponse>),
+    ) -> &mut Self {
+        let protocol_liquidity_response: StdResult<ProtocolLiquidityResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ProtocolLiquidity {},
+            );
+
+        result(protocol_liquidity_response);
+
+        self
+    }
+
+    pub(crate) fn query_migration_campaign(
+        &mut self,
+        from_pool_identifier: String,
+        address: Option<&Addr>,
+        result: impl Fn(StdResult<MigrationCampaignResponse>),
+    ) -> &mut Self {
+        let migration_campaign_response: StdResult<MigrationCampaignResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::MigrationCampaign {
+                    from_pool_identifier,
+                    address: address.map(|address| address.to_string()),
+                },
+            );
+
+        result(migration_campaign_response);
+
+        self
+    }
+
+    pub(crate) fn query_ibc_withdrawal_channels(
+        &mut self,
+        result: impl Fn(StdResult<IbcWithdrawalChannelsResponse>),
+    ) -> &mut Self {
+        let channels_response: StdResult<IbcWithdrawalChannelsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::IbcWithdrawalChannels {},
+            );
+
+        result(channels_response);
+
+        self
+    }
+
+    pub(crate) fn query_stableswap_amp(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<StableSwapAmpResponse>),
+    ) -> &mut Self {
+        let amp_response: StdResult<StableSwapAmpResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::StableSwapAmp { pool_identifier },
+        );
+
+        result(amp_response);
+
+        self
+    }
+
+    pub(crate) fn query_depeg_protection(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<DepegProtectionResponse>),
+    ) -> &mut Self {
+        let depeg_protection_response: StdResult<DepegProtectionResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::DepegProtection { pool_identifier },
+            );
+
+        result(depeg_protection_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_breaker(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<PriceBreakerResponse>),
+    ) -> &mut Self {
+        let price_breaker_response: StdResult<PriceBreakerResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::PriceBreaker { pool_identifier },
+            );
+
+        result(price_breaker_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_discounts(
+        &mut self,
+        trader: Option<String>,
+        result: impl Fn(StdResult<FeeDiscountsResponse>),
+    ) -> &mut Self {
+        let fee_discounts_response: StdResult<FeeDiscountsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::FeeDiscounts { trader },
+            );
+
+        result(fee_discounts_response);
+
+        self
+    }
+
+    pub(crate) fn query_stableswap_debug(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<StableSwapDebugResponse>),
+    ) -> &mut Self {
+        let debug_response: StdResult<StableSwapDebugResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::StableSwapDebug {
+                pool_identifier,
+                offer_asset,
+                ask_asset_denom,
+            },
+        );
+
+        result(debug_response);
+
+        self
+    }
+
+    pub(crate) fn query_imbalance_fee(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<ImbalanceFeeResponse>),
+    ) -> &mut Self {
+        let imbalance_fee_response: StdResult<ImbalanceFeeResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ImbalanceFee { pool_identifier },
+            );
+
+        result(imbalance_fee_response);
+
+        self
+    }
+
+    pub(crate) fn query_min_lp_lock(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<MinLpLockResponse>),
+    ) -> &mut Self {
+        let min_lp_lock_response: StdResult<MinLpLockResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::MinLpLock { pool_identifier },
+        );
+
+        result(min_lp_lock_response);
+
+        self
+    }
+
+    pub(crate) fn query_yield_strategies(
+        &mut self,
+        result: impl Fn(StdResult<YieldStrategiesResponse>),
+    ) -> &mut Self {
+        let yield_strategies_response: StdResult<YieldStrategiesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::YieldStrategies {},
+            );
+
+        result(yield_strategies_response);
+
+        self
+    }
+
+    pub(crate) fn query_yield_deployments(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<YieldDeploymentsResponse>),
+    ) -> &mut Self {
+        let yield_deployments_response: StdResult<YieldDeploymentsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::YieldDeployments { pool_identifier },
+            );
+
+        result(yield_deployments_response);
+
+        self
+    }
+
+    pub(crate) fn query_concentrated_pool(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<ConcentratedPoolResponse>),
+    ) -> &mut Self {
+        let concentrated_pool_response: StdResult<ConcentratedPoolResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ConcentratedPool { pool_identifier },
+            );
+
+        result(concentrated_pool_response);
+
+        self
+    }
+
+    pub(crate) fn query_concentrated_positions(
+        &mut self,
+        owner: Option<String>,
+        pool_identifier: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<ConcentratedPositionsResponse>),
+    ) -> &mut Self {
+        let positions_response: StdResult<ConcentratedPositionsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ConcentratedPositions {
+                    owner,
+                    pool_identifier,
+                    start_after,
+                    limit,
+                },
+            );
+
+        result(positions_response);
+
+        self
+    }
+
+    pub(crate) fn query_unlocking_duration_bounds(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<UnlockingDurationBoundsResponse>),
+    ) -> &mut Self {
+        let unlocking_duration_bounds_response: StdResult<UnlockingDurationBoundsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::UnlockingDurationBounds {
+                    pool_identifier,
+                },
+            );
+
+        result(unlocking_duration_bounds_response);
+
+        self
+    }
+
+    pub(crate) fn query_reserve_floors(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<ReserveFloorsResponse>),
+    ) -> &mut Self {
+        let reserve_floors_response: StdResult<ReserveFloorsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ReserveFloors { pool_identifier },
+            );
+
+        result(reserve_floors_response);
+
+        self
+    }
+
+    pub(crate) fn query_pending_pool_fees(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<PendingPoolFeesResponse>),
+    ) -> &mut Self {
+        let pending_pool_fees_response: StdResult<PendingPoolFeesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::PendingPoolFees { pool_identifier },
+            );
+
+        result(pending_pool_fees_response);
+
+        self
+    }
+
+    pub(crate) fn query_rate_providers(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<RateProvidersResponse>),
+    ) -> &mut Self {
+        let rate_providers_response: StdResult<RateProvidersResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::RateProviders { pool_identifier },
+            );
+
+        result(rate_providers_response);
+
+        self
+    }
+
+    pub(crate) fn query_pool_allowlist(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<PoolAllowlistResponse>),
+    ) -> &mut Self {
+        let pool_allowlist_response: StdResult<PoolAllowlistResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::PoolAllowlist {
+                    pool_identifier,
+                    start_after: None,
+                    limit: None,
+                },
+            );
+
+        result(pool_allowlist_response);
+
+        self
+    }
+
+    pub(crate) fn query_pool_creator_allowlist(
+        &mut self,
+        result: impl Fn(StdResult<PoolCreatorAllowlistResponse>),
+    ) -> &mut Self {
+        let pool_creator_allowlist_response: StdResult<PoolCreatorAllowlistResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::PoolCreatorAllowlist {
+                    start_after: None,
+                    limit: None,
+                },
+            );
+
+        result(pool_creator_allowlist_response);
+
+        self
+    }
+
+    pub(crate) fn query_pool_admin(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<PoolAdminResponse>),
+    ) -> &mut Self {
+        let pool_admin_response: StdResult<PoolAdminResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::PoolAdmin { pool_identifier },
+        );
+
+        result(pool_admin_response);
+
+        self
+    }
+
+    pub(crate) fn query_denom_traces(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<DenomTracesResponse>),
+    ) -> &mut Self {
+        let denom_traces_response: StdResult<DenomTracesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::DenomTraces { pool_identifier },
+            );
+
+        result(denom_traces_response);
+
+        self
+    }
+
+    pub(crate) fn query_ibc_source_channels(
+        &mut self,
+        result: impl Fn(StdResult<IbcSourceChannelsResponse>),
+    ) -> &mut Self {
+        let ibc_source_channels_response: StdResult<IbcSourceChannelsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::IbcSourceChannels {},
+            );
+
+        result(ibc_source_channels_response);
+
+        self
+    }
+
+    pub(crate) fn query_contract_registry(
+        &mut self,
+        result: impl Fn(StdResult<ContractRegistryResponse>),
+    ) -> &mut Self {
+        let contract_registry_response: StdResult<ContractRegistryResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ContractRegistry {},
+            );
+
+        result(contract_registry_response);
+
+        self
+    }
+
+    pub(crate) fn query_disabled_messages(
+        &mut self,
+        result: impl Fn(StdResult<DisabledMessagesResponse>),
+    ) -> &mut Self {
+        let disabled_messages_response: StdResult<DisabledMessagesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::DisabledMessages {},
+            );
+
+        result(disabled_messages_response);
 
         self
     }
 
-    #[track_caller]
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn execute_swap_operations(
+    pub(crate) fn query_rounding_dust(
         &mut self,
-        sender: &Addr,
-        operations: Vec<SwapOperation>,
-        minimum_receive: Option<Uint128>,
-        receiver: Option<String>,
-        max_spread: Option<Decimal>,
-        funds: Vec<Coin>,
-        result: impl Fn(Result<AppResponse, anyhow::Error>),
+        pool_identifier: String,
+        result: impl Fn(StdResult<RoundingDustResponse>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::ExecuteSwapOperations {
-            operations,
-            minimum_receive,
-            receiver,
-            max_spread,
-        };
+        let rounding_dust_response: StdResult<RoundingDustResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::RoundingDust { pool_identifier },
+            );
 
-        result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &funds,
-        ));
+        result(rounding_dust_response);
 
         self
     }
 
-    #[track_caller]
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn create_pool(
+    /// Reads the single side liquidity provision buffer straight from the storage of the pool
+    /// manager, as no query exposes it.
+    pub(crate) fn query_single_side_liquidity_provision_buffer(
         &mut self,
-        sender: &Addr,
-        asset_denoms: Vec<String>,
-        asset_decimals: Vec<u8>,
-        pool_fees: PoolFee,
-        pool_type: PoolType,
-        pool_identifier: Option<String>,
-        pool_creation_fee_funds: Vec<Coin>,
-        result: impl Fn(Result<AppResponse, anyhow::Error>),
+        result: impl Fn(Option<SingleSideLiquidityProvisionBuffer>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::CreatePool {
-            asset_denoms,
-            asset_decimals,
-            pool_fees,
-            pool_type,
-            pool_identifier,
-        };
+        let storage = self.app.contract_storage(&self.pool_manager_addr);
 
-        result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &pool_creation_fee_funds,
-        ));
+        result(
+            SINGLE_SIDE_LIQUIDITY_PROVISION_BUFFER
+                .may_load(&*storage)
+                .unwrap(),
+        );
 
         self
     }
 
-    #[track_caller]
-    pub(crate) fn withdraw_liquidity(
+    /// Reads the cached LP supply of the given pool straight from the storage of the pool manager,
+    /// as queries fall back to the bank supply when it isn't cached.
+    pub(crate) fn query_cached_lp_supply(
         &mut self,
-        sender: &Addr,
-        pool_identifier: String,
-        funds: Vec<Coin>,
-        result: impl Fn(Result<AppResponse, anyhow::Error>),
+        pool_identifier: &str,
+        result: impl Fn(Option<Uint128>),
     ) -> &mut Self {
-        let msg = mantra_dex_std::pool_manager::ExecuteMsg::WithdrawLiquidity { pool_identifier };
+        let storage = self.app.contract_storage(&self.pool_manager_addr);
 
-        result(self.app.execute_contract(
-            sender.clone(),
-            self.pool_manager_addr.clone(),
-            &msg,
-            &funds,
-        ));
+        result(LP_SUPPLY.may_load(&*storage, pool_identifier).unwrap());
 
         self
     }
 
-    /// Updates the configuration of the contract.
-    ///
-    /// Any parameters which are set to `None` when passed will not update
-    /// the current configuration.
+    /// Removes the cached LP supply of the given pool, as for pools created before it was cached.
+    pub(crate) fn clear_cached_lp_supply(&mut self, pool_identifier: &str) -> &mut Self {
+        let mut storage = self.app.contract_storage_mut(&self.pool_manager_addr);
+
+        LP_SUPPLY.remove(&mut *storage, pool_identifier);
+
+        self
+    }
+
+    /// Migrates the pool manager to a new upload of its code, rolling its stored contract version
+    /// back first so the migration is accepted.
     #[track_caller]
-    pub(crate) fn update_config(
+    pub(crate) fn migrate_pool_manager(
         &mut self,
-        sender: &Addr,
-        new_fee_collector_addr: Option<Addr>,
-        new_farm_manager_addr: Option<Addr>,
-        new_pool_creation_fee: Option<Coin>,
-        new_feature_toggle: Option<FeatureToggle>,
         result: impl Fn(Result<AppResponse, anyhow::Error>),
     ) -> &mut Self {
-        result(self.app.execute_contract(
-            sender.clone(),
+        {
+            let mut storage = self.app.contract_storage_mut(&self.pool_manager_addr);
+            cw2::set_contract_version(&mut *storage, "mantra:pool-manager", "1.0.0").unwrap();
+        }
+
+        let pool_manager_id = self.app.store_code(contract_pool_manager());
+        let creator = self.creator();
+
+        result(self.app.migrate_contract(
+            creator,
             self.pool_manager_addr.clone(),
-            &mantra_dex_std::pool_manager::ExecuteMsg::UpdateConfig {
-                fee_collector_addr: new_fee_collector_addr.map(|addr| addr.to_string()),
-                farm_manager_addr: new_farm_manager_addr.map(|addr| addr.to_string()),
-                pool_creation_fee: new_pool_creation_fee,
-                feature_toggle: new_feature_toggle,
-            },
-            &[],
+            &mantra_dex_std::pool_manager::MigrateMsg {},
+            pool_manager_id,
         ));
 
         self
     }
 
-    /// Updates the configuration of the farm manager contract.
-    ///
-    /// Any parameters which are set to `None` when passed will not update
-    /// the current configuration.
-    #[track_caller]
-    pub(crate) fn update_farm_manager_config(
+    pub(crate) fn query_lp_wrapper(
         &mut self,
-        sender: &Addr,
-        new_pool_manager_addr: Addr,
-        result: impl Fn(Result<AppResponse, anyhow::Error>),
+        pool_identifier: String,
+        result: impl Fn(StdResult<LpWrapperResponse>),
     ) -> &mut Self {
-        result(self.app.execute_contract(
-            sender.clone(),
-            self.farm_manager_addr.clone(),
-            &mantra_dex_std::farm_manager::ExecuteMsg::UpdateConfig {
-                fee_collector_addr: None,
-                epoch_manager_addr: None,
-                pool_manager_addr: Some(new_pool_manager_addr.to_string()),
-                create_farm_fee: None,
-                max_concurrent_farms: None,
-                max_farm_epoch_buffer: None,
-                min_unlocking_duration: None,
-                max_unlocking_duration: None,
-                farm_expiration_time: None,
-                emergency_unlock_penalty: None,
-            },
-            &[],
-        ));
+        let lp_wrapper_response: StdResult<LpWrapperResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::LpWrapper { pool_identifier },
+        );
+
+        result(lp_wrapper_response);
 
         self
     }
-}
 
-/// queries
-impl TestingSuite {
-    pub(crate) fn query_ownership(
+    pub(crate) fn query_cw20_balance(
         &mut self,
-        result: impl Fn(StdResult<cw_ownable::Ownership<String>>),
+        cw20: &Addr,
+        address: &Addr,
+        result: impl Fn(StdResult<cw20::BalanceResponse>),
     ) -> &mut Self {
-        let ownership_response: StdResult<cw_ownable::Ownership<String>> =
-            self.app.wrap().query_wasm_smart(
-                &self.pool_manager_addr,
-                &mantra_dex_std::pool_manager::QueryMsg::Ownership {},
-            );
+        let balance_response: StdResult<cw20::BalanceResponse> = self.app.wrap().query_wasm_smart(
+            cw20,
+            &mantra_dex_std::lp_wrapper::QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        );
 
-        result(ownership_response);
+        result(balance_response);
 
         self
     }
 
-    pub(crate) fn query_balance(
+    pub(crate) fn query_rfq_maker(
         &mut self,
-        addr: &String,
-        denom: impl Into<String>,
-        result: impl Fn(StdResult<Coin>),
+        maker: &Addr,
+        result: impl Fn(StdResult<RfqMakerResponse>),
     ) -> &mut Self {
-        let balance_resp: StdResult<Coin> = self.app.wrap().query_balance(addr, denom);
+        let rfq_maker_response: StdResult<RfqMakerResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::RfqMaker {
+                maker: maker.to_string(),
+            },
+        );
 
-        result(balance_resp);
+        result(rfq_maker_response);
 
         self
     }
 
-    pub(crate) fn query_all_balances(
+    pub(crate) fn query_rfq_quote_digest(
         &mut self,
-        addr: &String,
-        result: impl Fn(StdResult<Vec<Coin>>),
+        quote: RfqQuote,
+        result: impl Fn(StdResult<RfqQuoteDigestResponse>),
     ) -> &mut Self {
-        let balance_resp: StdResult<Vec<Coin>> = self.app.wrap().query_all_balances(addr);
+        let digest_response: StdResult<RfqQuoteDigestResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::RfqQuoteDigest { quote },
+        );
 
-        result(balance_resp);
+        result(digest_response);
 
         self
     }
 
-    pub(crate) fn query_pools(
-        &self,
-        pool_identifier: Option<String>,
-        start_after: Option<String>,
-        limit: Option<u32>,
-        result: impl Fn(StdResult<PoolsResponse>),
-    ) -> &Self {
-        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
-            &self.pool_manager_addr,
-            &mantra_dex_std::pool_manager::QueryMsg::Pools {
-                pool_identifier,
-                start_after,
-                limit,
-            },
-        );
+    pub(crate) fn query_split_simulation(
+        &mut self,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        pool_identifiers: Vec<String>,
+        result: impl Fn(StdResult<SplitSimulationResponse>),
+    ) -> &mut Self {
+        self.query_weighted_split_simulation(
+            offer_asset,
+            ask_asset_denom,
+            pool_identifiers,
+            None,
+            result,
+        )
+    }
 
-        result(pools_response);
+    pub(crate) fn query_weighted_split_simulation(
+        &mut self,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        pool_identifiers: Vec<String>,
+        weights: Option<Vec<u64>>,
+        result: impl Fn(StdResult<SplitSimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SplitSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::SplitSimulation {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifiers,
+                    weights,
+                },
+            );
+
+        result(simulation_response);
 
         self
     }
 
-    pub(crate) fn query_simulation(
+    pub(crate) fn query_simulation_with_reserves(
         &mut self,
         pool_identifier: String,
         offer_asset: Coin,
         ask_asset_denom: String,
+        reserves: Vec<Coin>,
         result: impl Fn(StdResult<SimulationResponse>),
     ) -> &mut Self {
-        let pool_info_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
             &self.pool_manager_addr,
-            &mantra_dex_std::pool_manager::QueryMsg::Simulation {
+            &mantra_dex_std::pool_manager::QueryMsg::SimulationWithReserves {
                 offer_asset,
                 ask_asset_denom,
                 pool_identifier,
+                reserves,
             },
         );
 
-        result(pool_info_response);
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_reverse_simulation_with_reserves(
+        &mut self,
+        pool_identifier: String,
+        ask_asset: Coin,
+        offer_asset_denom: String,
+        reserves: Vec<Coin>,
+        result: impl Fn(StdResult<ReverseSimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<ReverseSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::ReverseSimulationWithReserves {
+                    ask_asset,
+                    offer_asset_denom,
+                    pool_identifier,
+                    reserves,
+                },
+            );
+
+        result(simulation_response);
 
         self
     }
@@ -656,6 +4801,83 @@ impl TestingSuite {
         self
     }
 
+    pub(crate) fn query_swap_route(
+        &mut self,
+        offer_asset_denom: String,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SwapRouteResponse>),
+    ) -> &mut Self {
+        let swap_route_response: StdResult<SwapRouteResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::SwapRoute {
+                offer_asset_denom,
+                ask_asset_denom,
+            },
+        );
+
+        result(swap_route_response);
+
+        self
+    }
+
+    pub(crate) fn query_swap_adapters(
+        &mut self,
+        result: impl Fn(StdResult<SwapAdaptersResponse>),
+    ) -> &mut Self {
+        let swap_adapters_response: StdResult<SwapAdaptersResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::SwapAdapters {
+                    start_after: None,
+                    limit: None,
+                },
+            );
+
+        result(swap_adapters_response);
+
+        self
+    }
+
+    pub(crate) fn query_swap_routes(
+        &mut self,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<SwapRoutesResponse>),
+    ) -> &mut Self {
+        let swap_routes_response: StdResult<SwapRoutesResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &mantra_dex_std::pool_manager::QueryMsg::SwapRoutes { start_after, limit },
+        );
+
+        result(swap_routes_response);
+
+        self
+    }
+
+    pub(crate) fn query_find_best_route(
+        &mut self,
+        offer_denom: String,
+        ask_denom: String,
+        amount: Uint128,
+        max_hops: u32,
+        result: impl Fn(StdResult<FindBestRouteResponse>),
+    ) -> &mut Self {
+        let best_route_response: StdResult<FindBestRouteResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &mantra_dex_std::pool_manager::QueryMsg::FindBestRoute {
+                    offer_denom,
+                    ask_denom,
+                    amount,
+                    max_hops,
+                },
+            );
+
+        result(best_route_response);
+
+        self
+    }
+
     pub(crate) fn query_reverse_simulate_swap_operations(
         &mut self,
         ask_amount: Uint128,
@@ -692,6 +4914,8 @@ impl TestingSuite {
                     pool_identifier: Some(identifier),
                     start_after: None,
                     limit: None,
+                    order: None,
+                    deprecated: None,
                 },
             )
             .unwrap();
@@ -0,0 +1,295 @@
+use cosmwasm_std::{ensure, Decimal, Decimal256, Deps, Order, StdResult, Storage, Uint256};
+use cw_storage_plus::Bound;
+
+use mantra_dex_std::pool_manager::{AssetPairTwap, PoolInfo, SwapOperation};
+
+use crate::helpers::get_asset_indexes_in_pool;
+use crate::state::{
+    get_pool_by_identifier, PriceObservation, CONCENTRATED_POOLS, POOLS, PRICE_OBSERVATIONS,
+};
+use crate::ContractError;
+
+/// The maximum window a TWAP can be computed for. Older price observations are pruned.
+pub const MAX_TWAP_WINDOW: u64 = 604_800u64;
+
+/// Accumulates the prices of the given pool up to `block_time`. Must be called right before the
+/// reserves of the pool change, so the price that held since the last observation is accumulated.
+pub(crate) fn accumulate_prices(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    pool_identifier: &str,
+) -> Result<(), ContractError> {
+    let pool_info = POOLS.load(storage, pool_identifier)?;
+
+    let cumulative_prices = match get_latest_observation(storage, pool_identifier)? {
+        // the prices were already accumulated up to this block
+        Some((timestamp, _)) if timestamp >= block_time => return Ok(()),
+        Some((timestamp, observation)) => accumulate(
+            &observation.cumulative_prices,
+            &get_spot_prices(storage, &pool_info)?,
+            block_time - timestamp,
+        )?,
+        None => {
+            let assets = pool_info.assets.len();
+            vec![vec![Decimal256::zero(); assets]; assets]
+        }
+    };
+
+    PRICE_OBSERVATIONS.save(
+        storage,
+        (pool_identifier, block_time),
+        &PriceObservation { cumulative_prices },
+    )?;
+
+    prune_observations(storage, block_time, pool_identifier)
+}
+
+/// Computes the time-weighted average price of `base_denom` in terms of `quote_denom` in the given
+/// pool over the last `window` seconds.
+pub(crate) fn get_twap(
+    deps: Deps,
+    block_time: u64,
+    pool_identifier: &str,
+    base_denom: String,
+    quote_denom: String,
+    window: u64,
+) -> Result<Decimal256, ContractError> {
+    ensure!(
+        window > 0 && window <= MAX_TWAP_WINDOW,
+        ContractError::InvalidTwapWindow {
+            max: MAX_TWAP_WINDOW
+        }
+    );
+
+    let pool_info = get_pool_by_identifier(&deps, pool_identifier)?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let start = block_time
+        .checked_sub(window)
+        .ok_or(ContractError::InsufficientTwapHistory)?;
+
+    let spot_price = get_spot_prices(deps.storage, &pool_info)?[base_index][quote_index];
+    let cumulative_price_at = |timestamp: u64| -> Result<Decimal256, ContractError> {
+        // the last observation at or before the timestamp
+        let (before_timestamp, before) = PRICE_OBSERVATIONS
+            .prefix(pool_identifier)
+            .range(
+                deps.storage,
+                None,
+                Some(Bound::inclusive(timestamp)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()?
+            .ok_or(ContractError::InsufficientTwapHistory)?;
+        let before = before.cumulative_prices[base_index][quote_index];
+
+        // the price between two observations is constant, so it can be derived from them. After
+        // the latest observation, the price is the current spot price.
+        let price = match PRICE_OBSERVATIONS
+            .prefix(pool_identifier)
+            .range(
+                deps.storage,
+                Some(Bound::exclusive(before_timestamp)),
+                None,
+                Order::Ascending,
+            )
+            .next()
+            .transpose()?
+        {
+            Some((after_timestamp, after)) => after.cumulative_prices[base_index][quote_index]
+                .checked_sub(before)?
+                .checked_div(to_decimal_256(after_timestamp - before_timestamp))
+                .map_err(|_| ContractError::DecimalOverflow)?,
+            None => spot_price,
+        };
+
+        Ok(before.checked_add(price.checked_mul(to_decimal_256(timestamp - before_timestamp))?)?)
+    };
+
+    cumulative_price_at(block_time)?
+        .checked_sub(cumulative_price_at(start)?)?
+        .checked_div(to_decimal_256(window))
+        .map_err(|_| ContractError::DecimalOverflow)
+}
+
+/// Computes the time-weighted average prices of every pair of assets in the given pool over the
+/// last `window` seconds, both ways.
+pub(crate) fn get_pool_twaps(
+    deps: Deps,
+    block_time: u64,
+    pool_identifier: &str,
+    window: u64,
+) -> Result<Vec<AssetPairTwap>, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, pool_identifier)?;
+
+    let mut twaps = vec![];
+    for base_denom in pool_info.asset_denoms.iter() {
+        for quote_denom in pool_info.asset_denoms.iter() {
+            if base_denom == quote_denom {
+                continue;
+            }
+
+            twaps.push(AssetPairTwap {
+                base_denom: base_denom.clone(),
+                quote_denom: quote_denom.clone(),
+                twap: get_twap(
+                    deps,
+                    block_time,
+                    pool_identifier,
+                    base_denom.clone(),
+                    quote_denom.clone(),
+                    window,
+                )?,
+            });
+        }
+    }
+
+    Ok(twaps)
+}
+
+/// Computes the time-weighted average exchange rate of a route, i.e. the amount of the output
+/// asset per unit of the input asset, by composing the TWAPs of each pool in the route.
+pub(crate) fn get_route_twap(
+    deps: Deps,
+    block_time: u64,
+    operations: &[SwapOperation],
+    window: u64,
+) -> Result<Decimal, ContractError> {
+    ensure!(
+        !operations.is_empty(),
+        ContractError::NoSwapOperationsProvided
+    );
+
+    let mut route_twap = Decimal256::one();
+
+    for operation in operations {
+        match operation {
+            SwapOperation::MantraSwap {
+                token_in_denom,
+                token_out_denom,
+                pool_identifier,
+            } => {
+                let twap = get_twap(
+                    deps,
+                    block_time,
+                    pool_identifier,
+                    token_in_denom.to_string(),
+                    token_out_denom.to_string(),
+                    window,
+                )?;
+
+                route_twap = route_twap.checked_mul(twap)?;
+            }
+            SwapOperation::ExternalAdapter { .. } => {
+                return Err(ContractError::UnsupportedForSwapAdapter {
+                    operation: "twap".to_string(),
+                });
+            }
+        }
+    }
+
+    Decimal::try_from(route_twap).map_err(|_| ContractError::DecimalOverflow)
+}
+
+/// Gets the spot prices of the pool, where `prices[i][j]` is the price of the asset `i` in terms of
+/// the asset `j`, derived from the reserves in base units. Prices involving an empty reserve are
+/// zero. Concentrated liquidity pools trade at their own price rather than at the ratio of their
+/// reserves, so their spot prices are derived from it.
+pub(crate) fn get_spot_prices(
+    storage: &dyn Storage,
+    pool_info: &PoolInfo,
+) -> Result<Vec<Vec<Decimal256>>, ContractError> {
+    if let Some(state) = CONCENTRATED_POOLS.may_load(storage, &pool_info.pool_identifier)? {
+        let price = state.sqrt_price.checked_mul(state.sqrt_price)?;
+
+        return Ok(vec![
+            vec![Decimal256::one(), price],
+            vec![
+                Decimal256::one().checked_div(price).unwrap_or_default(),
+                Decimal256::one(),
+            ],
+        ]);
+    }
+
+    Ok(pool_info
+        .assets
+        .iter()
+        .map(|base| {
+            pool_info
+                .assets
+                .iter()
+                .map(|quote| {
+                    Decimal256::checked_from_ratio(quote.amount, base.amount).unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Adds the prices held during `elapsed` seconds to the cumulative prices.
+fn accumulate(
+    cumulative_prices: &[Vec<Decimal256>],
+    prices: &[Vec<Decimal256>],
+    elapsed: u64,
+) -> Result<Vec<Vec<Decimal256>>, ContractError> {
+    let elapsed = to_decimal_256(elapsed);
+
+    cumulative_prices
+        .iter()
+        .zip(prices.iter())
+        .map(|(cumulative_row, price_row)| {
+            cumulative_row
+                .iter()
+                .zip(price_row.iter())
+                .map(|(cumulative, price)| -> Result<Decimal256, ContractError> {
+                    Ok(cumulative.checked_add(price.checked_mul(elapsed)?)?)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Gets the latest price observation of the given pool, if any.
+fn get_latest_observation(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+) -> StdResult<Option<(u64, PriceObservation)>> {
+    PRICE_OBSERVATIONS
+        .prefix(pool_identifier)
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
+
+/// Removes the observations that are no longer needed to compute a TWAP over [MAX_TWAP_WINDOW],
+/// keeping the last one before the window starts.
+fn prune_observations(
+    storage: &mut dyn Storage,
+    block_time: u64,
+    pool_identifier: &str,
+) -> Result<(), ContractError> {
+    let cutoff = block_time.saturating_sub(MAX_TWAP_WINDOW);
+
+    let stale_timestamps = PRICE_OBSERVATIONS
+        .prefix(pool_identifier)
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(cutoff)),
+            Order::Descending,
+        )
+        .skip(1)
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    for timestamp in stale_timestamps {
+        PRICE_OBSERVATIONS.remove(storage, (pool_identifier, timestamp));
+    }
+
+    Ok(())
+}
+
+fn to_decimal_256(seconds: u64) -> Decimal256 {
+    Decimal256::from_ratio(Uint256::from(seconds), Uint256::one())
+}
@@ -0,0 +1,424 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, Addr, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Storage, Uint128,
+};
+
+use mantra_dex_std::pool_manager::{PoolInfo, YieldDeployment};
+use mantra_dex_std::yield_strategy;
+
+use crate::state::{get_pool_by_identifier, POOLS, YIELD_DEPLOYMENTS, YIELD_STRATEGIES};
+use crate::twap::accumulate_prices;
+use crate::ContractError;
+
+/// Whitelists a yield strategy the idle reserves of `denom` can be deployed into. Only the owner
+/// can do this.
+pub fn add_yield_strategy(
+    deps: DepsMut,
+    info: MessageInfo,
+    strategy: String,
+    denom: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let strategy = deps.api.addr_validate(&strategy)?;
+    YIELD_STRATEGIES.save(deps.storage, &strategy, &denom)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "add_yield_strategy".to_string()),
+        ("strategy", strategy.into_string()),
+        ("denom", denom),
+    ]))
+}
+
+/// Removes a yield strategy from the whitelist. Strategies still assigned to a pool can't be
+/// removed, the pool must stop using them first. Only the owner can do this.
+pub fn remove_yield_strategy(
+    deps: DepsMut,
+    info: MessageInfo,
+    strategy: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let strategy = deps.api.addr_validate(&strategy)?;
+    ensure!(
+        YIELD_STRATEGIES.has(deps.storage, &strategy),
+        ContractError::UnknownYieldStrategy {
+            strategy: strategy.into_string(),
+        }
+    );
+
+    let in_use = YIELD_DEPLOYMENTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .any(|(_, deployment)| deployment.strategy == strategy);
+    ensure!(
+        !in_use,
+        ContractError::YieldStrategyInUse {
+            strategy: strategy.into_string(),
+        }
+    );
+
+    YIELD_STRATEGIES.remove(deps.storage, &strategy);
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "remove_yield_strategy".to_string()),
+        ("strategy", strategy.into_string()),
+    ]))
+}
+
+/// Sets the whitelisted yield strategy the idle reserves of `denom` in the given pool can be
+/// deployed into, and the share of the reserve that can be deployed at most. Switching to another
+/// strategy, or to none, recalls the reserves deployed into the previous one. Only the owner can
+/// do this.
+pub fn update_pool_yield_strategy(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    denom: String,
+    strategy: Option<String>,
+    max_deployed_share: Decimal,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        pool.asset_denoms.contains(&denom),
+        ContractError::AssetMismatch
+    );
+    // part of the reserve always stays idle, to serve the swaps and withdrawals without a recall
+    ensure!(
+        max_deployed_share < Decimal::one(),
+        ContractError::InvalidMaxDeployedShare { max_deployed_share }
+    );
+
+    let strategy = strategy
+        .map(|strategy| deps.api.addr_validate(&strategy))
+        .transpose()?;
+    if let Some(strategy) = &strategy {
+        let strategy_denom = YIELD_STRATEGIES.may_load(deps.storage, strategy)?.ok_or(
+            ContractError::UnknownYieldStrategy {
+                strategy: strategy.to_string(),
+            },
+        )?;
+        ensure!(
+            strategy_denom == denom,
+            ContractError::InvalidYieldStrategy {
+                strategy: strategy.to_string(),
+                denom,
+            }
+        );
+    }
+
+    let mut messages = vec![];
+    let mut deployed = Uint128::zero();
+    if let Some(previous) = YIELD_DEPLOYMENTS.may_load(deps.storage, (&pool_identifier, &denom))? {
+        if strategy.as_ref() == Some(&previous.strategy) {
+            deployed = previous.deployed;
+        } else if !previous.deployed.is_zero() {
+            messages.push(get_recall_msg(
+                &previous.strategy,
+                &pool_identifier,
+                coin(previous.deployed.u128(), &denom),
+            )?);
+        }
+    }
+
+    match &strategy {
+        Some(strategy) => YIELD_DEPLOYMENTS.save(
+            deps.storage,
+            (&pool_identifier, &denom),
+            &YieldDeployment {
+                denom: denom.clone(),
+                strategy: strategy.clone(),
+                max_deployed_share,
+                deployed,
+            },
+        )?,
+        None => YIELD_DEPLOYMENTS.remove(deps.storage, (&pool_identifier, &denom)),
+    }
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "update_pool_yield_strategy".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("denom", denom),
+            (
+                "strategy",
+                strategy
+                    .map(|strategy| strategy.into_string())
+                    .unwrap_or_default(),
+            ),
+            ("max_deployed_share", max_deployed_share.to_string()),
+        ]))
+}
+
+/// Deploys `amount` of the idle reserves of `denom` in the given pool into its yield strategy,
+/// up to its max deployed share of the reserve. The deployed reserves stay accounted for in the
+/// pool, so they keep backing its LP shares and its swaps. Only the owner can do this.
+pub fn deploy_idle_reserves(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut deployment = load_yield_deployment(deps.storage, &pool_identifier, &denom)?;
+
+    let max_deployed =
+        get_reserve(&pool, &denom)?.checked_mul_floor(deployment.max_deployed_share)?;
+    let deployed = deployment.deployed.checked_add(amount)?;
+    ensure!(
+        !amount.is_zero() && deployed <= max_deployed,
+        ContractError::MaxDeployedReservesExceeded {
+            max_deployed: coin(max_deployed.u128(), &denom),
+        }
+    );
+
+    deployment.deployed = deployed;
+    YIELD_DEPLOYMENTS.save(deps.storage, (&pool_identifier, &denom), &deployment)?;
+
+    Ok(Response::default()
+        .add_message(wasm_execute(
+            deployment.strategy.to_string(),
+            &yield_strategy::ExecuteMsg::Deposit {
+                position: pool_identifier.clone(),
+            },
+            vec![coin(amount.u128(), &denom)],
+        )?)
+        .add_attributes(vec![
+            ("action", "deploy_idle_reserves".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("strategy", deployment.strategy.into_string()),
+            ("amount", coin(amount.u128(), denom).to_string()),
+            ("deployed", deployed.to_string()),
+        ]))
+}
+
+/// Recalls `amount` of the reserves of `denom` the given pool deployed into its yield strategy,
+/// or all of them if no amount is given. Only the owner can do this.
+pub fn recall_reserves(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_identifier: String,
+    denom: String,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut deployment = load_yield_deployment(deps.storage, &pool_identifier, &denom)?;
+
+    let amount = amount.unwrap_or(deployment.deployed);
+    ensure!(
+        !amount.is_zero() && amount <= deployment.deployed,
+        ContractError::InsufficientDeployedReserves {
+            deployed: coin(deployment.deployed.u128(), &denom),
+        }
+    );
+
+    deployment.deployed = deployment.deployed.checked_sub(amount)?;
+    YIELD_DEPLOYMENTS.save(deps.storage, (&pool_identifier, &denom), &deployment)?;
+
+    Ok(Response::default()
+        .add_message(get_recall_msg(
+            &deployment.strategy,
+            &pool_identifier,
+            coin(amount.u128(), &denom),
+        )?)
+        .add_attributes(vec![
+            ("action", "recall_reserves".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("strategy", deployment.strategy.into_string()),
+            ("amount", coin(amount.u128(), denom).to_string()),
+            ("deployed", deployment.deployed.to_string()),
+        ]))
+}
+
+/// Settles the yield the reserves of `denom` the given pool deployed earned in its strategy, i.e.
+/// the difference between the value of its position and the deployed reserves. Gains are
+/// recalled and credited to the reserve, so they accrue to the LPs, while losses are written off
+/// the reserve, so the LP shares are never valued with funds the strategy lost. Can be called by
+/// anyone.
+pub fn harvest_yield(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    denom: String,
+) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+
+    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut deployment = load_yield_deployment(deps.storage, &pool_identifier, &denom)?;
+
+    let position: yield_strategy::PositionResponse = deps.querier.query_wasm_smart(
+        &deployment.strategy,
+        &yield_strategy::QueryMsg::Position {
+            owner: env.contract.address.to_string(),
+            position: pool_identifier.clone(),
+        },
+    )?;
+    ensure!(
+        position.amount != deployment.deployed,
+        ContractError::NothingToHarvest {
+            pool_identifier,
+            denom,
+        }
+    );
+
+    let index = pool
+        .asset_denoms
+        .iter()
+        .position(|asset_denom| asset_denom == &denom)
+        .ok_or(ContractError::AssetMismatch)?;
+
+    // the prices that held until the harvest are accumulated before the reserve changes
+    accumulate_prices(deps.storage, env.block.time.seconds(), &pool_identifier)?;
+
+    let mut messages = vec![];
+    let (gain, loss) = if position.amount > deployment.deployed {
+        let gain = position.amount.checked_sub(deployment.deployed)?;
+        pool.assets[index].amount = pool.assets[index].amount.checked_add(gain)?;
+        messages.push(get_recall_msg(
+            &deployment.strategy,
+            &pool_identifier,
+            coin(gain.u128(), &denom),
+        )?);
+
+        (gain, Uint128::zero())
+    } else {
+        let loss = deployment.deployed.checked_sub(position.amount)?;
+        pool.assets[index].amount = pool.assets[index].amount.checked_sub(loss)?;
+        deployment.deployed = position.amount;
+        YIELD_DEPLOYMENTS.save(deps.storage, (&pool_identifier, &denom), &deployment)?;
+
+        (Uint128::zero(), loss)
+    };
+
+    POOLS.save(deps.storage, &pool_identifier, &pool)?;
+
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "harvest_yield".to_string()),
+            ("pool_identifier", pool_identifier),
+            ("denom", denom),
+            ("gain", gain.to_string()),
+            ("loss", loss.to_string()),
+            ("reserve", pool.assets[index].amount.to_string()),
+        ]))
+}
+
+/// Gets the amount of `denom` to recall from the yield strategy of the given pool for its reserve,
+/// once dropped to `reserve`, to keep covering the reserves it deployed.
+pub(crate) fn get_recall_amount(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    denom: &str,
+    reserve: Uint128,
+) -> Result<Uint128, ContractError> {
+    Ok(YIELD_DEPLOYMENTS
+        .may_load(storage, (pool_identifier, denom))?
+        .map(|deployment| deployment.deployed.saturating_sub(reserve))
+        .unwrap_or_default())
+}
+
+/// Recalls the deployed reserves the reserves of the given pool no longer cover, after they
+/// dropped with a swap or a withdrawal, so the contract holds the funds the pool pays out. The
+/// messages returned must go before the ones sending those funds.
+pub(crate) fn recall_uncovered_reserves(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut messages = vec![];
+
+    for asset in pool_info.assets.iter() {
+        let Some(mut deployment) =
+            YIELD_DEPLOYMENTS.may_load(storage, (&pool_info.pool_identifier, &asset.denom))?
+        else {
+            continue;
+        };
+
+        let shortfall = deployment.deployed.saturating_sub(asset.amount);
+        if shortfall.is_zero() {
+            continue;
+        }
+
+        deployment.deployed = asset.amount;
+        YIELD_DEPLOYMENTS.save(
+            storage,
+            (&pool_info.pool_identifier, &asset.denom),
+            &deployment,
+        )?;
+        messages.push(get_recall_msg(
+            &deployment.strategy,
+            &pool_info.pool_identifier,
+            coin(shortfall.u128(), &asset.denom),
+        )?);
+    }
+
+    Ok(messages)
+}
+
+/// Gets the amount of `denom` the contract deployed into yield strategies across all the pools.
+pub(crate) fn get_total_deployed(
+    storage: &dyn Storage,
+    denom: &str,
+) -> Result<Uint128, ContractError> {
+    let mut deployed = Uint128::zero();
+
+    for deployment in YIELD_DEPLOYMENTS.range(storage, None, None, Order::Ascending) {
+        let (_, deployment) = deployment?;
+        if deployment.denom == denom {
+            deployed = deployed.checked_add(deployment.deployed)?;
+        }
+    }
+
+    Ok(deployed)
+}
+
+fn load_yield_deployment(
+    storage: &dyn Storage,
+    pool_identifier: &str,
+    denom: &str,
+) -> Result<YieldDeployment, ContractError> {
+    YIELD_DEPLOYMENTS
+        .may_load(storage, (pool_identifier, denom))?
+        .ok_or(ContractError::NoYieldStrategy {
+            pool_identifier: pool_identifier.to_string(),
+            denom: denom.to_string(),
+        })
+}
+
+fn get_reserve(pool: &PoolInfo, denom: &str) -> Result<Uint128, ContractError> {
+    pool.assets
+        .iter()
+        .find(|asset| asset.denom == denom)
+        .map(|asset| asset.amount)
+        .ok_or(ContractError::AssetMismatch)
+}
+
+fn get_recall_msg(
+    strategy: &Addr,
+    pool_identifier: &str,
+    asset: Coin,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(wasm_execute(
+        strategy.to_string(),
+        &yield_strategy::ExecuteMsg::Withdraw {
+            position: pool_identifier.to_string(),
+            asset,
+        },
+        vec![],
+    )?
+    .into())
+}
@@ -0,0 +1,347 @@
+use cosmwasm_std::{
+    coin, ensure, wasm_execute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    Response, Uint128,
+};
+use cw_utils::PaymentError;
+
+use mantra_dex_std::coin::aggregate_coins;
+use mantra_dex_std::common::validate_addr_or_default;
+use mantra_dex_std::pool_manager::{ExecuteMsg, SwapOperation};
+
+use crate::helpers;
+use crate::liquidity::commands::burn_liquidity;
+use crate::router::commands::{assert_operations, perform_swap_operations, SwapOperationsResult};
+use crate::state::{get_pool_by_identifier, CONFIG, LIQUIDITY_PROVISION_DEPOSITOR};
+use crate::ContractError;
+
+/// Swaps the funds sent through the given swap operations, provides the output as liquidity to the
+/// given pool and locks the LP tokens in the farm manager for `unlocking_duration`, in a single
+/// transaction. The output of the route must be one of the assets of the pool, and is provided
+/// along the rest of the funds sent as [zap] does. The LP position is created for the sender, or
+/// expands the one with the given `lock_position_identifier`.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_and_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    pool_identifier: String,
+    slippage_tolerance: Option<Decimal>,
+    unlocking_duration: u64,
+    lock_position_identifier: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    zap_into_pool(
+        deps,
+        env,
+        info,
+        "swap_and_lock",
+        operations,
+        minimum_receive,
+        max_spread,
+        pool_identifier,
+        slippage_tolerance,
+        None,
+        Some(unlocking_duration),
+        lock_position_identifier,
+        min_lp_to_receive,
+    )
+}
+
+/// Swaps the input asset of the given route into one of the assets of the given pool, and provides
+/// the output as liquidity in the same transaction. The other funds sent, if any, must be assets of
+/// the pool and are provided along the output, i.e. as a double-sided deposit. Otherwise the output
+/// is provided as a single asset deposit. `max_spread` applies to every swap, including the one of
+/// a single asset deposit, and the deposit is checked against `slippage_tolerance` and
+/// `min_lp_to_receive`. The LP tokens go to the receiver, which defaults to the sender, and are
+/// locked in the farm manager if an `unlocking_duration` is given.
+#[allow(clippy::too_many_arguments)]
+pub fn zap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    route: Vec<SwapOperation>,
+    pool_identifier: String,
+    max_spread: Option<Decimal>,
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+    unlocking_duration: Option<u64>,
+    lock_position_identifier: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    zap_into_pool(
+        deps,
+        env,
+        info,
+        "zap",
+        route,
+        None,
+        max_spread,
+        pool_identifier,
+        slippage_tolerance,
+        receiver,
+        unlocking_duration,
+        lock_position_identifier,
+        min_lp_to_receive,
+    )
+}
+
+/// Swaps the funds through the given route and provides the output, together with the rest of the
+/// funds sent, as liquidity to the given pool.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn zap_into_pool(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: &str,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    pool_identifier: String,
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+    unlocking_duration: Option<u64>,
+    lock_position_identifier: Option<String>,
+    min_lp_to_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    let offer_asset_denom = operations
+        .first()
+        .ok_or(ContractError::NoSwapOperationsProvided)?
+        .get_input_asset_info()
+        .clone();
+
+    // the input of the route is swapped, the rest of the funds are provided as they are
+    let (offer_assets, mut deposits): (Vec<Coin>, Vec<Coin>) = aggregate_coins(info.funds.clone())?
+        .into_iter()
+        .partition(|asset| asset.denom == offer_asset_denom);
+    let offer_asset = offer_assets
+        .into_iter()
+        .next()
+        .ok_or(PaymentError::MissingDenom(offer_asset_denom))?;
+
+    assert_operations(operations.clone())?;
+
+    let receiver = validate_addr_or_default(&deps.as_ref(), receiver, info.sender.clone());
+
+    // as with plain deposits, LP tokens can only be locked for the sender
+    ensure!(
+        unlocking_duration.is_none() || receiver == info.sender,
+        ContractError::Unauthorized
+    );
+
+    let SwapOperationsResult {
+        return_asset,
+        recall_msgs,
+        adapter_msgs,
+        fee_msgs,
+        price_hook_msgs,
+        swap_attributes,
+    } = perform_swap_operations(
+        deps.branch(),
+        &env,
+        &config,
+        &info.sender,
+        &receiver,
+        offer_asset.clone(),
+        operations,
+        max_spread,
+        None,
+    )?;
+
+    if let Some(minimum_receive) = minimum_receive {
+        ensure!(
+            return_asset.amount >= minimum_receive,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive,
+                swap_amount: return_asset.amount,
+            }
+        );
+    }
+
+    deposits.push(return_asset.clone());
+    let deposits = aggregate_coins(deposits)?;
+
+    // the deposit is provided by the contract on behalf of the receiver, so it can lock the LP
+    // tokens for them. The provision runs after the fees of the route are sent, failing the whole
+    // transaction if it fails. What it doesn't use is refunded to the sender
+    LIQUIDITY_PROVISION_DEPOSITOR.save(deps.storage, &info.sender)?;
+    Ok(Response::default()
+        .add_messages(recall_msgs)
+        .add_messages(adapter_msgs)
+        .add_messages(fee_msgs)
+        .add_message(wasm_execute(
+            env.contract.address.to_string(),
+            &ExecuteMsg::ProvideLiquidity {
+                slippage_tolerance,
+                max_spread,
+                receiver: Some(receiver.to_string()),
+                pool_identifier: pool_identifier.clone(),
+                unlocking_duration,
+                lock_position_identifier,
+                min_lp_to_receive,
+                deposit_route: None,
+            },
+            deposits.clone(),
+        )?)
+        .add_submessages(price_hook_msgs)
+        .add_attributes(vec![
+            ("action", action.to_string()),
+            ("sender", info.sender.into_string()),
+            ("receiver", receiver.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("offer_asset", offer_asset.to_string()),
+            ("return_asset", return_asset.to_string()),
+            (
+                "deposits",
+                deposits
+                    .iter()
+                    .map(|asset| asset.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ])
+        .add_attributes(
+            unlocking_duration
+                .map(|unlocking_duration| ("unlocking_duration", unlocking_duration.to_string())),
+        )
+        .add_attributes(swap_attributes))
+}
+
+/// Withdraws the liquidity of the LP tokens sent and swaps all the withdrawn assets into
+/// `target_denom`, sending the sender a single coin. Each withdrawn asset other than the target is
+/// swapped along the given route from the first operation taking it as input, so a single route
+/// can collect all the assets of the pool, e.g. `[OM -> USDC, USDC -> ATOM]` for an OM/USDC pool
+/// and ATOM as the target. The total received is checked against `min_out`.
+pub fn withdraw_liquidity_and_swap(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    target_denom: String,
+    route: Vec<SwapOperation>,
+    min_out: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.feature_toggle.withdrawals_enabled,
+        ContractError::OperationDisabled("withdraw_liquidity".to_string())
+    );
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    ensure!(
+        !helpers::is_concentrated(&pool.pool_type),
+        ContractError::UnsupportedForConcentratedLiquidity {
+            operation: "withdraw_liquidity_and_swap".to_string(),
+        }
+    );
+    let amount = cw_utils::must_pay(&info, &pool.lp_denom)?;
+
+    // the route must end in the target, whichever asset it starts from
+    if let Some(last_operation) = route.last() {
+        assert_operations(route.clone())?;
+        ensure!(
+            last_operation.get_target_asset_info() == target_denom,
+            ContractError::NoSwapRouteForAssets {
+                offer_asset: route[0].get_input_asset_info().clone(),
+                ask_asset: target_denom,
+            }
+        );
+    }
+
+    let (refund_assets, withdrawal_msgs) = burn_liquidity(deps.branch(), &env, pool, amount)?;
+
+    let mut return_amount = Uint128::zero();
+    let mut recall_msgs = vec![];
+    let mut adapter_msgs = vec![];
+    let mut fee_msgs = vec![];
+    let mut price_hook_msgs = vec![];
+    let mut swap_attributes = vec![];
+
+    for refund_asset in refund_assets {
+        if refund_asset.denom == target_denom {
+            return_amount = return_amount.checked_add(refund_asset.amount)?;
+            continue;
+        }
+
+        // rounding can leave nothing of an asset to swap
+        if refund_asset.amount.is_zero() {
+            continue;
+        }
+
+        let start = route
+            .iter()
+            .position(|operation| operation.get_input_asset_info() == &refund_asset.denom)
+            .ok_or_else(|| ContractError::NoSwapRouteForAssets {
+                offer_asset: refund_asset.denom.clone(),
+                ask_asset: target_denom.clone(),
+            })?;
+
+        let swap_operations_result = perform_swap_operations(
+            deps.branch(),
+            &env,
+            &config,
+            &info.sender,
+            &info.sender,
+            refund_asset,
+            route[start..].to_vec(),
+            None,
+            None,
+        )?;
+
+        return_amount = return_amount.checked_add(swap_operations_result.return_asset.amount)?;
+        recall_msgs.extend(swap_operations_result.recall_msgs);
+        adapter_msgs.extend(swap_operations_result.adapter_msgs);
+        fee_msgs.extend(swap_operations_result.fee_msgs);
+        price_hook_msgs.extend(swap_operations_result.price_hook_msgs);
+        swap_attributes.extend(swap_operations_result.swap_attributes);
+    }
+
+    if let Some(min_out) = min_out {
+        ensure!(
+            return_amount >= min_out,
+            ContractError::MinimumReceiveAssertion {
+                minimum_receive: min_out,
+                swap_amount: return_amount,
+            }
+        );
+    }
+
+    let mut payout_msgs: Vec<CosmosMsg> = vec![];
+    if !return_amount.is_zero() {
+        payout_msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(return_amount.u128(), target_denom.clone())],
+        }));
+    }
+
+    Ok(Response::default()
+        .add_messages(withdrawal_msgs)
+        .add_messages(recall_msgs)
+        .add_messages(adapter_msgs)
+        .add_messages(payout_msgs)
+        .add_messages(fee_msgs)
+        .add_submessages(price_hook_msgs)
+        .add_attributes(vec![
+            ("action", "withdraw_liquidity_and_swap".to_string()),
+            ("sender", info.sender.into_string()),
+            ("pool_identifier", pool_identifier),
+            ("withdrawn_share", amount.to_string()),
+            (
+                "return_asset",
+                coin(return_amount.u128(), target_denom).to_string(),
+            ),
+        ])
+        .add_attributes(swap_attributes))
+}